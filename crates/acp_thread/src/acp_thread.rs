@@ -13,7 +13,7 @@ use project::lsp_store::{FormatTrigger, LspFormatTarget};
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 use settings::Settings as _;
-use task::{Shell, ShellBuilder};
+use task::Shell;
 pub use terminal::*;
 
 use action_log::{ActionLog, ActionLogTelemetry};
@@ -24,6 +24,7 @@ use futures::{FutureExt, channel::oneshot, future::BoxFuture};
 use gpui::{AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity};
 use itertools::Itertools;
 use language::{Anchor, Buffer, BufferSnapshot, LanguageRegistry, Point, ToPoint, text_diff};
+use language_model::RateLimitStatus;
 use markdown::Markdown;
 use project::{AgentLocation, Project, git_store::GitStoreCheckpoint};
 use std::collections::HashMap;
@@ -879,6 +880,14 @@ pub struct RetryStatus {
     pub duration: Duration,
 }
 
+/// Describes a switch to a fallback model after the previous model's provider failed.
+#[derive(Debug, Clone)]
+pub struct ModelFallback {
+    pub from: SharedString,
+    pub to: SharedString,
+    pub reason: SharedString,
+}
+
 pub struct AcpThread {
     title: SharedString,
     entries: Vec<AgentThreadEntry>,
@@ -915,6 +924,8 @@ pub enum AcpThreadEvent {
     EntriesRemoved(Range<usize>),
     ToolAuthorizationRequired,
     Retry(RetryStatus),
+    ModelFallback(ModelFallback),
+    RateLimited(RateLimitStatus),
     Stopped,
     Error,
     LoadError(LoadError),
@@ -1380,6 +1391,14 @@ impl AcpThread {
         cx.emit(AcpThreadEvent::Retry(status));
     }
 
+    pub fn report_model_fallback(&mut self, fallback: ModelFallback, cx: &mut Context<Self>) {
+        cx.emit(AcpThreadEvent::ModelFallback(fallback));
+    }
+
+    pub fn update_rate_limit_status(&mut self, status: RateLimitStatus, cx: &mut Context<Self>) {
+        cx.emit(AcpThreadEvent::RateLimited(status));
+    }
+
     pub fn update_tool_call(
         &mut self,
         update: impl Into<ToolCallUpdate>,
@@ -2318,17 +2337,20 @@ impl AcpThread {
             let terminal_id = terminal_id.clone();
             async move |_this, cx| {
                 let env = env.await;
-                let shell = project
-                    .update(cx, |project, cx| {
-                        project
-                            .remote_client()
-                            .and_then(|r| r.read(cx).default_system_shell())
-                    })?
-                    .unwrap_or_else(|| get_default_system_shell_preferring_bash());
-                let (task_command, task_args) =
-                    ShellBuilder::new(&Shell::Program(shell), is_windows)
-                        .redirect_stdin_to_dev_null()
-                        .build(Some(command.clone()), &args);
+                let (shell, shell_integration) = project.update(cx, |project, cx| {
+                    let shell = project
+                        .remote_client()
+                        .and_then(|r| r.read(cx).default_system_shell());
+                    (shell, project.terminal_settings(&cwd, cx).shell_integration)
+                })?;
+                let shell = shell.unwrap_or_else(|| get_default_system_shell_preferring_bash());
+                let (task_command, task_args) = build_shell_command(
+                    &Shell::Program(shell),
+                    is_windows,
+                    shell_integration,
+                    &command,
+                    &args,
+                );
                 let terminal = project
                     .update(cx, |project, cx| {
                         project.create_terminal_task(
@@ -48,6 +48,9 @@ pub enum MentionUri {
     Fetch {
         url: Url,
     },
+    SemanticSearch {
+        query: String,
+    },
 }
 
 impl MentionUri {
@@ -163,6 +166,10 @@ impl MentionUri {
                     Ok(Self::Directory {
                         abs_path: path.into(),
                     })
+                } else if path.starts_with("/agent/search") {
+                    let query =
+                        single_query_param(&url, "query")?.context("Missing search query")?;
+                    Ok(Self::SemanticSearch { query })
                 } else if path.starts_with("/agent/selection") {
                     let fragment = url.fragment().context("Missing fragment for selection")?;
                     let line_range = parse_line_range(fragment)?;
@@ -199,6 +206,7 @@ impl MentionUri {
                 ..
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
+            MentionUri::SemanticSearch { query } => query.clone(),
         }
     }
 
@@ -216,6 +224,7 @@ impl MentionUri {
             MentionUri::Rule { .. } => IconName::Reader.path().into(),
             MentionUri::Selection { .. } => IconName::Reader.path().into(),
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
+            MentionUri::SemanticSearch { .. } => IconName::ToolSearch.path().into(),
         }
     }
 
@@ -293,6 +302,11 @@ impl MentionUri {
                 url
             }
             MentionUri::Fetch { url } => url.clone(),
+            MentionUri::SemanticSearch { query } => {
+                let mut url = Url::parse("zed:///agent/search").unwrap();
+                url.query_pairs_mut().append_pair("query", query);
+                url
+            }
         }
     }
 }
@@ -497,6 +511,20 @@ mod tests {
         assert_eq!(parsed.to_uri().to_string(), https_uri);
     }
 
+    #[test]
+    fn test_parse_semantic_search_uri() {
+        let search_uri = "zed:///agent/search?query=where+are+sessions+invalidated%3F";
+        let parsed = MentionUri::parse(search_uri, PathStyle::local()).unwrap();
+        match &parsed {
+            MentionUri::SemanticSearch { query } => {
+                assert_eq!(query, "where are sessions invalidated?");
+            }
+            _ => panic!("Expected SemanticSearch variant"),
+        }
+        assert_eq!(parsed.name(), "where are sessions invalidated?");
+        assert_eq!(parsed.to_uri().to_string(), search_uri);
+    }
+
     #[test]
     fn test_invalid_scheme() {
         assert!(MentionUri::parse("ftp://example.com", PathStyle::local()).is_err());
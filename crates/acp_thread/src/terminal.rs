@@ -6,9 +6,11 @@ use language::LanguageRegistry;
 use markdown::Markdown;
 use project::Project;
 use std::{path::PathBuf, process::ExitStatus, sync::Arc, time::Instant};
-use task::Shell;
+use task::{Shell, ShellBuilder, ShellKind};
 use util::get_default_system_shell_preferring_bash;
 
+pub use terminal::CommandResult;
+
 pub struct Terminal {
     id: acp::TerminalId,
     command: Entity<Markdown>,
@@ -119,7 +121,13 @@ impl Terminal {
 
     fn truncated_output(&self, cx: &App) -> (String, usize) {
         let terminal = self.terminal.read(cx);
-        let mut content = terminal.get_content();
+        // Commands report where their output starts and ends, so that what the shell printed
+        // around them is left out.
+        let mut content = terminal
+            .command_blocks()
+            .last()
+            .and_then(|block| terminal.command_output(block.id))
+            .unwrap_or_else(|| terminal.get_content());
 
         let original_content_len = content.len();
 
@@ -138,6 +146,13 @@ impl Terminal {
         (content, original_content_len)
     }
 
+    /// What the last command run in the terminal did, if the shell integration reported it.
+    pub fn command_result(&self, cx: &App) -> Option<CommandResult> {
+        let terminal = self.terminal.read(cx);
+        let block = terminal.command_blocks().pop()?;
+        terminal.command_result(block.id)
+    }
+
     pub fn command(&self) -> &Entity<Markdown> {
         &self.command
     }
@@ -205,9 +220,11 @@ pub async fn create_terminal_entity(
     let is_windows = project
         .read_with(cx, |project, cx| project.path_style(cx).is_windows())
         .unwrap_or(cfg!(windows));
-    let (task_command, task_args) = task::ShellBuilder::new(&shell, is_windows)
-        .redirect_stdin_to_dev_null()
-        .build(Some(command.clone()), &args);
+    let shell_integration = project.read_with(cx, |project, cx| {
+        project.terminal_settings(&cwd, cx).shell_integration
+    })?;
+    let (task_command, task_args) =
+        build_shell_command(&shell, is_windows, shell_integration, &command, args);
 
     project
         .update(cx, |project, cx| {
@@ -224,3 +241,28 @@ pub async fn create_terminal_entity(
         })?
         .await
 }
+
+/// Builds the program and arguments that run `command` in `shell`. With POSIX shells and the shell
+/// integration enabled, the command reports where its output starts and its exit status, which
+/// scopes the output of the terminal.
+pub(crate) fn build_shell_command(
+    shell: &Shell,
+    is_windows: bool,
+    shell_integration: bool,
+    command: &str,
+    args: &[String],
+) -> (String, Vec<String>) {
+    let builder = ShellBuilder::new(shell, is_windows).redirect_stdin_to_dev_null();
+    if shell_integration && args.is_empty() && shell.shell_kind(is_windows) == ShellKind::Posix {
+        let (program, mut args) = builder.build(
+            Some(terminal::shell_integration::POSIX_COMMAND_WRAPPER.to_string()),
+            &[],
+        );
+        args.extend(terminal::shell_integration::posix_command_wrapper_args(
+            command,
+        ));
+        (program, args)
+    } else {
+        builder.build(Some(command.to_string()), args)
+    }
+}
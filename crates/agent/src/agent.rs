@@ -196,8 +196,8 @@ impl LanguageModels {
                             // that we know are safe to ignore here, like what we do
                             // with `CredentialsNotFound` above.
                             match provider_id.0.as_ref() {
-                                "lmstudio" | "ollama" => {
-                                    // LM Studio and Ollama both make fetch requests to the local APIs to determine if they are "authenticated".
+                                "llama_cpp" | "lmstudio" | "ollama" => {
+                                    // llama.cpp, LM Studio and Ollama all make fetch requests to the local APIs to determine if they are "authenticated".
                                     //
                                     // These fail noisily, so we don't log them.
                                 }
@@ -1025,6 +1025,16 @@ impl NativeAgentConnection {
                                     thread.update_retry_status(status, cx)
                                 })?;
                             }
+                            ThreadEvent::ModelFallback(fallback) => {
+                                acp_thread.update(cx, |thread, cx| {
+                                    thread.report_model_fallback(fallback, cx)
+                                })?;
+                            }
+                            ThreadEvent::RateLimited(status) => {
+                                acp_thread.update(cx, |thread, cx| {
+                                    thread.update_rate_limit_status(status, cx)
+                                })?;
+                            }
                             ThreadEvent::Stop(stop_reason) => {
                                 log::debug!("Assistant message complete: {:?}", stop_reason);
                                 return Ok(acp::PromptResponse::new(stop_reason));
@@ -1484,6 +1494,11 @@ impl TerminalHandle for AcpTerminalHandle {
             .read_with(cx, |term, cx| term.current_output(cx))
     }
 
+    fn command_result(&self, cx: &AsyncApp) -> Result<Option<acp_thread::CommandResult>> {
+        self.terminal
+            .read_with(cx, |term, cx| term.command_result(cx))
+    }
+
     fn kill(&self, cx: &AsyncApp) -> Result<()> {
         cx.update(|cx| {
             self.terminal.update(cx, |terminal, cx| {
@@ -23,9 +23,11 @@ use gpui::{
 use indoc::indoc;
 use language_model::{
     LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId,
-    LanguageModelProviderName, LanguageModelRegistry, LanguageModelRequest,
-    LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolSchemaFormat,
-    LanguageModelToolUse, MessageContent, Role, StopReason, fake_provider::FakeLanguageModel,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelRegistry,
+    LanguageModelRequest, LanguageModelRequestMessage, LanguageModelToolResult,
+    LanguageModelToolSchemaFormat, LanguageModelToolUse, MessageContent, Role, SelectedModel,
+    StopReason,
+    fake_provider::{FakeLanguageModel, FakeLanguageModelProvider},
 };
 use pretty_assertions::assert_eq;
 use project::{
@@ -106,6 +108,10 @@ impl crate::TerminalHandle for FakeTerminalHandle {
         Ok(self.output.clone())
     }
 
+    fn command_result(&self, _cx: &AsyncApp) -> Result<Option<acp_thread::CommandResult>> {
+        Ok(None)
+    }
+
     fn wait_for_exit(&self, _cx: &AsyncApp) -> Result<Shared<Task<acp::TerminalExitStatus>>> {
         Ok(self.wait_for_exit.clone())
     }
@@ -2421,6 +2427,107 @@ async fn test_send_retry_on_error(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_fallback_model_after_retries_are_exhausted(cx: &mut TestAppContext) {
+    let ThreadTest { thread, model, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let fallback_model = cx.update(|cx| {
+        LanguageModelRegistry::test(cx);
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.fallback_models = vec![settings::LanguageModelSelection {
+            provider: "fallback".into(),
+            model: "fake".into(),
+        }];
+        agent_settings::AgentSettings::override_global(settings, cx);
+
+        let provider = Arc::new(FakeLanguageModelProvider::new(
+            LanguageModelProviderId::new("fallback"),
+            LanguageModelProviderName::new("Fallback"),
+        ));
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.register_provider(provider, cx);
+            registry
+                .select_fallback_models(["fallback/fake".parse::<SelectedModel>().unwrap()], cx);
+            registry.fallback_models()[0].clone()
+        })
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hello!"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    fake_model.send_last_completion_stream_text_chunk("Hey,");
+    for _ in 0..crate::thread::MAX_RETRY_ATTEMPTS + 1 {
+        fake_model.send_last_completion_stream_error(
+            LanguageModelCompletionError::ServerOverloaded {
+                provider: LanguageModelProviderName::new("Fake"),
+                retry_after: Some(Duration::from_secs(3)),
+            },
+        );
+        fake_model.end_last_completion_stream();
+        cx.executor().advance_clock(Duration::from_secs(3));
+        cx.run_until_parked();
+    }
+
+    // The turn continues on the fallback model once the model's own retries are used up.
+    let fallback_fake_model = fallback_model.as_fake();
+    fallback_fake_model.send_last_completion_stream_text_chunk("there!");
+    fallback_fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let mut fallback_events = Vec::new();
+    let mut retry_events = Vec::new();
+    while let Some(Ok(event)) = events.next().await {
+        match event {
+            ThreadEvent::ModelFallback(fallback) => fallback_events.push(fallback),
+            ThreadEvent::Retry(retry_status) => retry_events.push(retry_status),
+            ThreadEvent::Stop(..) => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        retry_events.len(),
+        crate::thread::MAX_RETRY_ATTEMPTS as usize
+    );
+    assert_eq!(fallback_events.len(), 1);
+    assert_eq!(
+        fallback_events[0].reason.as_ref(),
+        "Fake's API servers are overloaded right now"
+    );
+    thread.read_with(cx, |thread, _cx| {
+        // The fallback only applied to that turn.
+        assert_eq!(
+            thread.model().unwrap().provider_id(),
+            LanguageModelProviderId::new("fake")
+        );
+        assert_eq!(
+            thread.to_markdown(),
+            indoc! {"
+                ## User
+
+                Hello!
+
+                ## Assistant
+
+                Hey,
+
+                [resume]
+
+                [switched model from fake/fake to fallback/fake: Fake's API servers are overloaded right now]
+
+                ## Assistant
+
+                there!
+            "}
+        )
+    });
+}
+
 #[gpui::test]
 async fn test_send_retry_finishes_tool_calls_on_error(cx: &mut TestAppContext) {
     let ThreadTest { thread, model, .. } = setup(cx, TestModel::Fake).await;
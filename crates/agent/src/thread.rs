@@ -2,8 +2,8 @@ use crate::{
     ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
     DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
     ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
-    RestoreFileFromDiskTool, SaveFileTool, SystemPromptTemplate, Template, Templates, TerminalTool,
-    ThinkingTool, WebSearchTool,
+    RestoreFileFromDiskTool, SaveFileTool, SemanticSearchTool, SystemPromptTemplate, Template,
+    Templates, TerminalTool, ThinkingTool, WebSearchTool,
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
@@ -34,8 +34,8 @@ use language_model::{
     LanguageModelId, LanguageModelImage, LanguageModelProviderId, LanguageModelRegistry,
     LanguageModelRequest, LanguageModelRequestMessage, LanguageModelRequestTool,
     LanguageModelToolResult, LanguageModelToolResultContent, LanguageModelToolSchemaFormat,
-    LanguageModelToolUse, LanguageModelToolUseId, Role, SelectedModel, StopReason, TokenUsage,
-    ZED_CLOUD_PROVIDER_ID,
+    LanguageModelToolUse, LanguageModelToolUseId, RateLimitBudget, RateLimitStatus, Role,
+    SelectedModel, StopReason, TokenUsage, ZED_CLOUD_PROVIDER_ID,
 };
 use project::Project;
 use prompt_store::ProjectContext;
@@ -96,6 +96,7 @@ pub enum Message {
     User(UserMessage),
     Agent(AgentMessage),
     Resume,
+    ModelFallback(ModelFallbackMessage),
 }
 
 impl Message {
@@ -122,6 +123,7 @@ impl Message {
                 cache: false,
                 reasoning_details: None,
             }],
+            Message::ModelFallback(_) => vec![],
         }
     }
 
@@ -130,6 +132,7 @@ impl Message {
             Message::User(message) => message.to_markdown(),
             Message::Agent(message) => message.to_markdown(),
             Message::Resume => "[resume]\n".into(),
+            Message::ModelFallback(fallback) => fallback.to_markdown(),
         }
     }
 
@@ -137,10 +140,30 @@ impl Message {
         match self {
             Message::User(_) | Message::Resume => Role::User,
             Message::Agent(_) => Role::Assistant,
+            Message::ModelFallback(_) => Role::System,
         }
     }
 }
 
+/// Records that the thread switched to a fallback model after its model's provider failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelFallbackMessage {
+    /// The `provider/model` id of the model that failed.
+    pub from: String,
+    /// The `provider/model` id of the model the thread continued on.
+    pub to: String,
+    pub reason: String,
+}
+
+impl ModelFallbackMessage {
+    pub fn to_markdown(&self) -> String {
+        format!(
+            "[switched model from {} to {}: {}]\n",
+            self.from, self.to, self.reason
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserMessage {
     pub id: UserMessageId,
@@ -198,6 +221,7 @@ impl UserMessage {
         const OPEN_SELECTIONS_TAG: &str = "<selections>";
         const OPEN_THREADS_TAG: &str = "<threads>";
         const OPEN_FETCH_TAG: &str = "<fetched_urls>";
+        const OPEN_SEARCH_TAG: &str = "<semantic_searches>";
         const OPEN_RULES_TAG: &str =
             "<rules>\nThe user has specified the following rules that should be applied:\n";
 
@@ -207,6 +231,7 @@ impl UserMessage {
         let mut selection_context = OPEN_SELECTIONS_TAG.to_string();
         let mut thread_context = OPEN_THREADS_TAG.to_string();
         let mut fetch_context = OPEN_FETCH_TAG.to_string();
+        let mut search_context = OPEN_SEARCH_TAG.to_string();
         let mut rules_context = OPEN_RULES_TAG.to_string();
 
         for chunk in &self.content {
@@ -289,6 +314,9 @@ impl UserMessage {
                         MentionUri::Fetch { url } => {
                             write!(&mut fetch_context, "\nFetch: {}\n\n{}", url, content).ok();
                         }
+                        MentionUri::SemanticSearch { query } => {
+                            write!(&mut search_context, "\nSearch: {}\n\n{}", query, content).ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -342,6 +370,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(fetch_context));
         }
 
+        if search_context.len() > OPEN_SEARCH_TAG.len() {
+            search_context.push_str("</semantic_searches>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(search_context));
+        }
+
         if rules_context.len() > OPEN_RULES_TAG.len() {
             rules_context.push_str("</user_rules>\n");
             message
@@ -536,6 +571,8 @@ pub enum AgentMessageContent {
 pub trait TerminalHandle {
     fn id(&self, cx: &AsyncApp) -> Result<acp::TerminalId>;
     fn current_output(&self, cx: &AsyncApp) -> Result<acp::TerminalOutputResponse>;
+    /// What the command did, if the shell integration reported it.
+    fn command_result(&self, cx: &AsyncApp) -> Result<Option<acp_thread::CommandResult>>;
     fn wait_for_exit(&self, cx: &AsyncApp) -> Result<Shared<Task<acp::TerminalExitStatus>>>;
     fn kill(&self, cx: &AsyncApp) -> Result<()>;
 }
@@ -559,6 +596,8 @@ pub enum ThreadEvent {
     ToolCallUpdate(acp_thread::ToolCallUpdate),
     ToolCallAuthorization(ToolCallAuthorization),
     Retry(acp_thread::RetryStatus),
+    ModelFallback(acp_thread::ModelFallback),
+    RateLimited(RateLimitStatus),
     Stop(acp::StopReason),
 }
 
@@ -721,7 +760,7 @@ impl Thread {
                         }
                     }
                 }
-                Message::Resume => {}
+                Message::Resume | Message::ModelFallback(_) => {}
             }
         }
         rx
@@ -1021,6 +1060,7 @@ impl Thread {
         ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
+        self.add_tool(SemanticSearchTool::new(self.project.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment));
         self.add_tool(ThinkingTool);
         self.add_tool(WebSearchTool);
@@ -1082,7 +1122,7 @@ impl Thread {
                 Message::User(message) => {
                     self.request_token_usage.remove(&message.id);
                 }
-                Message::Agent(_) | Message::Resume => {}
+                Message::Agent(_) | Message::Resume | Message::ModelFallback(_) => {}
             }
         }
         self.clear_summary();
@@ -1266,6 +1306,7 @@ impl Thread {
         self.running_turn = Some(RunningTurn {
             event_stream: event_stream.clone(),
             tools: self.enabled_tools(profile, &model, cx),
+            fallback_model: None,
             _task: cx.spawn(async move |this, cx| {
                 log::debug!("Starting agent turn execution");
 
@@ -1302,12 +1343,26 @@ impl Thread {
 
     async fn run_turn_internal(
         this: &WeakEntity<Self>,
-        model: Arc<dyn LanguageModel>,
+        mut model: Arc<dyn LanguageModel>,
         event_stream: &ThreadEventStream,
         cx: &mut AsyncApp,
     ) -> Result<()> {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+
+        // Surface requests that are queued behind the provider's rate limit budget
+        // for as long as this turn is running.
+        let mut rate_limit_status =
+            cx.update(|cx| RateLimitBudget::for_provider(model.provider_id(), cx).status())?;
+        let _rate_limit_updates = cx.background_spawn({
+            let event_stream = event_stream.clone();
+            async move {
+                while let Ok(status) = rate_limit_status.recv().await {
+                    event_stream.send_rate_limited(status);
+                }
+            }
+        });
+
         loop {
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
@@ -1376,18 +1431,40 @@ impl Thread {
                 let retry = this.update(cx, |this, cx| {
                     let user_store = this.user_store.read(cx);
                     this.handle_completion_error(error, attempt, user_store.plan())
-                })??;
-                let timer = cx.background_executor().timer(retry.duration);
-                event_stream.send_retry(retry);
-                timer.await;
-                this.update(cx, |this, _cx| {
-                    if let Some(Message::Agent(message)) = this.messages.last() {
-                        if message.tool_results.is_empty() {
+                })?;
+                match retry {
+                    Ok(retry) => {
+                        let timer = cx.background_executor().timer(retry.duration);
+                        event_stream.send_retry(retry);
+                        timer.await;
+                        if this.update(cx, |this, _cx| this.resume_interrupted_message())? {
                             intent = CompletionIntent::UserPrompt;
-                            this.messages.push(Message::Resume);
                         }
                     }
-                })?;
+                    Err(error) => {
+                        // Only fall back once the current model has used up its retries.
+                        let fallback_model = this
+                            .update(cx, |this, cx| this.fallback_model_for(&error, intent, cx))?;
+                        let Some(fallback_model) = fallback_model else {
+                            return Err(anyhow!(error));
+                        };
+                        let resumed = this.update(cx, |this, cx| {
+                            let resumed = this.resume_interrupted_message();
+                            this.fall_back_to_model(
+                                fallback_model.clone(),
+                                &error,
+                                event_stream,
+                                cx,
+                            );
+                            resumed
+                        })?;
+                        if resumed {
+                            intent = CompletionIntent::UserPrompt;
+                        }
+                        model = fallback_model;
+                        attempt = 0;
+                    }
+                }
             } else if this.read_with(cx, |this, _| this.tool_use_limit_reached)? {
                 return Err(language_model::ToolUseLimitReachedError.into());
             } else if end_turn {
@@ -1399,14 +1476,97 @@ impl Thread {
         }
     }
 
+    /// Pushes a [`Message::Resume`] if the last request failed partway through an
+    /// agent message, so that the model continues it. Returns whether it did.
+    fn resume_interrupted_message(&mut self) -> bool {
+        if let Some(Message::Agent(message)) = self.messages.last()
+            && message.tool_results.is_empty()
+        {
+            self.messages.push(Message::Resume);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the next model in the configured fallback chain that can take over
+    /// the current request, if the error is one that another model might not hit.
+    fn fallback_model_for(
+        &self,
+        error: &LanguageModelCompletionError,
+        intent: CompletionIntent,
+        cx: &App,
+    ) -> Option<Arc<dyn LanguageModel>> {
+        if !error.is_retryable() || AgentSettings::get_global(cx).fallback_models.is_empty() {
+            return None;
+        }
+        let model = self.turn_model()?;
+        let request = self.build_completion_request(intent, cx).log_err()?;
+        LanguageModelRegistry::read_global(cx).next_fallback_model(model.as_ref(), &request)
+    }
+
+    fn fall_back_to_model(
+        &mut self,
+        model: Arc<dyn LanguageModel>,
+        error: &LanguageModelCompletionError,
+        event_stream: &ThreadEventStream,
+        cx: &mut Context<Self>,
+    ) {
+        fn model_id(model: &Arc<dyn LanguageModel>) -> String {
+            format!("{}/{}", model.provider_id().0, model.id().0)
+        }
+
+        if let Some(previous_model) = self.turn_model() {
+            log::info!(
+                "Falling back from {} to {} after error: {error}",
+                model_id(previous_model),
+                model_id(&model)
+            );
+            self.messages
+                .push(Message::ModelFallback(ModelFallbackMessage {
+                    from: model_id(previous_model),
+                    to: model_id(&model),
+                    reason: error.to_string(),
+                }));
+            event_stream.send_model_fallback(acp_thread::ModelFallback {
+                from: previous_model.name().0,
+                to: model.name().0,
+                reason: error.to_string().into(),
+            });
+        }
+
+        // Tools are filtered by provider, so recompute them for the rest of the turn.
+        let tools = AgentSettings::get_global(cx)
+            .profiles
+            .get(&self.profile_id)
+            .map(|profile| self.enabled_tools(profile, &model, cx));
+        // The fallback only lasts for this turn, the thread keeps the user's model.
+        if let Some(turn) = self.running_turn.as_mut() {
+            if let Some(tools) = tools {
+                turn.tools = tools;
+            }
+            turn.fallback_model = Some(model);
+        }
+        cx.notify();
+    }
+
+    /// The model that the running turn sends its requests to, which is a fallback model if the
+    /// thread's model failed during this turn.
+    fn turn_model(&self) -> Option<&Arc<dyn LanguageModel>> {
+        self.running_turn
+            .as_ref()
+            .and_then(|turn| turn.fallback_model.as_ref())
+            .or(self.model.as_ref())
+    }
+
     fn handle_completion_error(
         &mut self,
         error: LanguageModelCompletionError,
         attempt: u8,
         plan: Option<Plan>,
-    ) -> Result<acp_thread::RetryStatus> {
-        let Some(model) = self.model.as_ref() else {
-            return Err(anyhow!(error));
+    ) -> Result<acp_thread::RetryStatus, LanguageModelCompletionError> {
+        let Some(model) = self.turn_model() else {
+            return Err(error);
         };
 
         let auto_retry = if model.provider_id() == ZED_CLOUD_PROVIDER_ID {
@@ -1420,11 +1580,11 @@ impl Thread {
         };
 
         if !auto_retry {
-            return Err(anyhow!(error));
+            return Err(error);
         }
 
         let Some(strategy) = Self::retry_strategy_for(&error) else {
-            return Err(anyhow!(error));
+            return Err(error);
         };
 
         let max_attempts = match &strategy {
@@ -1433,7 +1593,7 @@ impl Thread {
         };
 
         if attempt > max_attempts {
-            return Err(anyhow!(error));
+            return Err(error);
         }
 
         let delay = match &strategy {
@@ -1661,7 +1821,9 @@ impl Thread {
         tool_event_stream.update_fields(
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
-        let supports_images = self.model().is_some_and(|model| model.supports_images());
+        let supports_images = self
+            .turn_model()
+            .is_some_and(|model| model.supports_images());
         let tool_result = tool.run(tool_use.input, tool_event_stream, cx);
         log::debug!("Running tool {}", tool_use.name);
         Some(cx.foreground_executor().spawn(async move {
@@ -1889,6 +2051,7 @@ impl Thread {
                 Message::User(user_message) => Some(user_message),
                 Message::Agent(_) => None,
                 Message::Resume => None,
+                Message::ModelFallback(_) => None,
             })
     }
 
@@ -1935,7 +2098,7 @@ impl Thread {
         completion_intent: CompletionIntent,
         cx: &App,
     ) -> Result<LanguageModelRequest> {
-        let model = self.model().context("No language model configured")?;
+        let model = self.turn_model().context("No language model configured")?;
         let tools = if let Some(turn) = self.running_turn.as_ref() {
             turn.tools
                 .iter()
@@ -2239,6 +2402,8 @@ struct RunningTurn {
     event_stream: ThreadEventStream,
     /// The tools that were enabled for this turn.
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
+    /// The model that took over this turn after the thread's model failed.
+    fallback_model: Option<Arc<dyn LanguageModel>>,
 }
 
 impl RunningTurn {
@@ -2486,6 +2651,18 @@ impl ThreadEventStream {
         self.0.unbounded_send(Ok(ThreadEvent::Retry(status))).ok();
     }
 
+    fn send_model_fallback(&self, fallback: acp_thread::ModelFallback) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::ModelFallback(fallback)))
+            .ok();
+    }
+
+    fn send_rate_limited(&self, status: RateLimitStatus) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::RateLimited(status)))
+            .ok();
+    }
+
     fn send_stop(&self, reason: acp::StopReason) {
         self.0.unbounded_send(Ok(ThreadEvent::Stop(reason))).ok();
     }
@@ -14,6 +14,7 @@ mod open_tool;
 mod read_file_tool;
 mod restore_file_from_disk_tool;
 mod save_file_tool;
+mod semantic_search_tool;
 
 mod terminal_tool;
 mod thinking_tool;
@@ -38,6 +39,7 @@ pub use open_tool::*;
 pub use read_file_tool::*;
 pub use restore_file_from_disk_tool::*;
 pub use save_file_tool::*;
+pub use semantic_search_tool::*;
 
 pub use terminal_tool::*;
 pub use thinking_tool::*;
@@ -96,6 +98,7 @@ tools! {
     ReadFileTool,
     RestoreFileFromDiskTool,
     SaveFileTool,
+    SemanticSearchTool,
     TerminalTool,
     ThinkingTool,
     WebSearchTool,
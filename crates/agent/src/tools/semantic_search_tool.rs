@@ -0,0 +1,126 @@
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use gpui::{App, Entity, Task};
+use project::Project;
+use schemars::JsonSchema;
+use semantic_index::{SemanticDb, Status};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use ui::SharedString;
+use util::markdown::{MarkdownEscaped, MarkdownInlineCode};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+/// Searches the project's code by meaning rather than by exact text, using an index of embeddings.
+///
+/// Use this tool to find code when you know what it does but not what it's called, e.g. "where are
+/// user sessions invalidated?". Prefer `grep` when you know an exact identifier or string.
+///
+/// Results are excerpts of files, ordered from the most to the least relevant. Relevant code can
+/// still be missing from the results, so read the surrounding code before drawing conclusions.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticSearchToolInput {
+    /// A natural language description of the code to find.
+    pub query: String,
+    /// The maximum number of excerpts to return. Defaults to 10.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+pub struct SemanticSearchTool {
+    project: Entity<Project>,
+}
+
+impl SemanticSearchTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SemanticSearchTool {
+    type Input = SemanticSearchToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "semantic_search"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search project for {}", MarkdownEscaped(&input.query)).into(),
+            Err(_) => "Search project".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        semantic_search(self.project.clone(), input.query, limit, cx)
+    }
+}
+
+/// Searches the project's semantic index, and formats the matching excerpts for the model.
+pub fn semantic_search(
+    project: Entity<Project>,
+    query: String,
+    limit: usize,
+    cx: &mut App,
+) -> Task<Result<String>> {
+    let Some(project_index) = SemanticDb::project_index(project, cx) else {
+        return Task::ready(Err(anyhow!(
+            "Semantic search is unavailable. It requires a local project and \
+            `semantic_index.enabled` to be set in the settings."
+        )));
+    };
+    let status = project_index.read(cx).status();
+    if let Status::Error(error) = &status {
+        return Task::ready(Err(anyhow!("The semantic index failed: {error}")));
+    }
+    let search = project_index.read(cx).search(query, limit, cx);
+    cx.spawn(async move |cx| {
+        let results = search.await?;
+        let results = project_index
+            .read_with(cx, |project_index, cx| {
+                project_index.load_results(results, cx)
+            })?
+            .await?;
+
+        let mut output = String::new();
+        if let Status::Indexing { remaining_files } = status {
+            writeln!(
+                output,
+                "The project is still being indexed ({remaining_files} files remaining), \
+                so these results may be incomplete.\n"
+            )?;
+        }
+        if results.is_empty() {
+            output.push_str("No results found.");
+            return Ok(output);
+        }
+        for result in results {
+            writeln!(
+                output,
+                "## Match in {}\n\n### L{}-{}\n```\n{}\n```\n",
+                MarkdownInlineCode(result.full_path.as_unix_str()),
+                result.row_range.start() + 1,
+                result.row_range.end() + 1,
+                result.excerpt
+            )?;
+        }
+        Ok(output)
+    })
+}
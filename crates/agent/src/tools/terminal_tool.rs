@@ -1,3 +1,4 @@
+use acp_thread::CommandResult;
 use agent_client_protocol as acp;
 use anyhow::Result;
 use futures::FutureExt as _;
@@ -143,8 +144,14 @@ impl AgentTool for TerminalTool {
             };
 
             let output = terminal.current_output(cx)?;
-
-            Ok(process_content(output, &input.command, exit_status))
+            let command_result = terminal.command_result(cx)?;
+
+            Ok(process_content(
+                output,
+                &input.command,
+                exit_status,
+                command_result,
+            ))
         })
     }
 }
@@ -153,7 +160,14 @@ fn process_content(
     output: acp::TerminalOutputResponse,
     command: &str,
     exit_status: acp::TerminalExitStatus,
+    command_result: Option<CommandResult>,
 ) -> String {
+    // Prefer the exit code that the shell integration reported for the command to the one that the
+    // terminal exited with.
+    let exit_code = command_result
+        .as_ref()
+        .and_then(|result| result.exit_code)
+        .or(exit_status.exit_code);
     let content = output.output.trim();
     let is_empty = content.is_empty();
 
@@ -167,7 +181,7 @@ fn process_content(
         content
     };
 
-    let content = match exit_status.exit_code {
+    let content = match exit_code {
         Some(0) => {
             if is_empty {
                 "Command executed successfully.".to_string()
@@ -29,6 +29,7 @@ use language::{Buffer, Language, language_settings::InlayHintKind};
 use project::{CompletionIntent, InlayHint, InlayHintLabel, InlayId, Project, Worktree};
 use prompt_store::PromptStore;
 use rope::Point;
+use semantic_index::SemanticIndexSettings;
 use settings::Settings;
 use std::{cell::RefCell, fmt::Write, rc::Rc, sync::Arc};
 use theme::ThemeSettings;
@@ -74,6 +75,9 @@ impl PromptCompletionProviderDelegate for Entity<MessageEditor> {
                 PromptContextType::Fetch,
                 PromptContextType::Rules,
             ]);
+            if SemanticIndexSettings::get_global(cx).enabled {
+                supported.push(PromptContextType::Search);
+            }
         }
         supported
     }
@@ -1,7 +1,7 @@
 use acp_thread::{
     AcpThread, AcpThreadEvent, AgentThreadEntry, AssistantMessage, AssistantMessageChunk,
-    AuthRequired, LoadError, MentionUri, RetryStatus, ThreadStatus, ToolCall, ToolCallContent,
-    ToolCallStatus, UserMessageId,
+    AuthRequired, LoadError, MentionUri, ModelFallback, RetryStatus, ThreadStatus, ToolCall,
+    ToolCallContent, ToolCallStatus, UserMessageId,
 };
 use acp_thread::{AgentConnection, Plan};
 use action_log::{ActionLog, ActionLogTelemetry};
@@ -35,7 +35,7 @@ use gpui::{
 };
 use language::Buffer;
 
-use language_model::LanguageModelRegistry;
+use language_model::{LanguageModelRegistry, RateLimitStatus};
 use markdown::{HeadingLevelStyles, Markdown, MarkdownElement, MarkdownStyle};
 use project::{AgentServerStore, ExternalAgentServerName, Project, ProjectEntryId};
 use prompt_store::{PromptId, PromptStore};
@@ -282,6 +282,8 @@ pub struct AcpThreadView {
     notifications: Vec<WindowHandle<AgentNotification>>,
     notification_subscriptions: HashMap<WindowHandle<AgentNotification>, Vec<Subscription>>,
     thread_retry_status: Option<RetryStatus>,
+    thread_rate_limit_status: Option<RateLimitStatus>,
+    thread_model_fallback: Option<ModelFallback>,
     thread_error: Option<ThreadError>,
     thread_error_markdown: Option<Entity<Markdown>>,
     token_limit_callout_dismissed: bool,
@@ -452,6 +454,8 @@ impl AcpThreadView {
             notification_subscriptions: HashMap::default(),
             list_state: list_state,
             thread_retry_status: None,
+            thread_rate_limit_status: None,
+            thread_model_fallback: None,
             thread_error: None,
             thread_error_markdown: None,
             token_limit_callout_dismissed: false,
@@ -930,6 +934,7 @@ impl AcpThreadView {
     pub fn cancel_generation(&mut self, cx: &mut Context<Self>) {
         self.thread_error.take();
         self.thread_retry_status.take();
+        self.thread_rate_limit_status.take();
 
         if let Some(thread) = self.thread() {
             self._cancel_task = Some(thread.update(cx, |thread, cx| thread.cancel(cx)));
@@ -1317,6 +1322,7 @@ impl AcpThreadView {
         });
 
         self.thread_error.take();
+        self.thread_model_fallback.take();
         self.editing_message.take();
         self.thread_feedback.clear();
 
@@ -1776,8 +1782,16 @@ impl AcpThreadView {
             AcpThreadEvent::Retry(retry) => {
                 self.thread_retry_status = Some(retry.clone());
             }
+            AcpThreadEvent::ModelFallback(fallback) => {
+                self.thread_retry_status.take();
+                self.thread_model_fallback = Some(fallback.clone());
+            }
+            AcpThreadEvent::RateLimited(status) => {
+                self.thread_rate_limit_status = status.is_limited().then(|| status.clone());
+            }
             AcpThreadEvent::Stopped => {
                 self.thread_retry_status.take();
+                self.thread_rate_limit_status.take();
                 let used_tools = thread.read(cx).used_tools_since_last_user_message();
                 self.notify_with_sound(
                     if used_tools {
@@ -1802,6 +1816,7 @@ impl AcpThreadView {
             }
             AcpThreadEvent::Refusal => {
                 self.thread_retry_status.take();
+                self.thread_rate_limit_status.take();
                 self.thread_error = Some(ThreadError::Refusal);
                 let model_or_agent_name = self.current_model_name(cx);
                 let notification_message =
@@ -1810,6 +1825,7 @@ impl AcpThreadView {
             }
             AcpThreadEvent::Error => {
                 self.thread_retry_status.take();
+                self.thread_rate_limit_status.take();
                 self.notify_with_sound(
                     "Agent stopped due to an error",
                     IconName::Warning,
@@ -1819,6 +1835,7 @@ impl AcpThreadView {
             }
             AcpThreadEvent::LoadError(error) => {
                 self.thread_retry_status.take();
+                self.thread_rate_limit_status.take();
                 self.thread_state = ThreadState::LoadError(error.clone());
                 if self.message_editor.focus_handle(cx).is_focused(window) {
                     self.focus_handle.focus(window, cx)
@@ -5580,6 +5597,7 @@ impl AcpThreadView {
                 MentionUri::Fetch { url } => {
                     cx.open_url(url.as_str());
                 }
+                MentionUri::SemanticSearch { .. } => {}
             })
         } else {
             cx.open_url(&url);
@@ -6305,6 +6323,54 @@ impl AcpThreadView {
         )
     }
 
+    fn render_thread_model_fallback_callout(&self, cx: &mut Context<Self>) -> Option<Callout> {
+        let fallback = self.thread_model_fallback.as_ref()?;
+
+        Some(
+            Callout::new()
+                .severity(Severity::Info)
+                .title(format!("Switched to {}", fallback.to))
+                .description(format!("{} failed: {}", fallback.from, fallback.reason))
+                .dismiss_action(
+                    IconButton::new("dismiss-model-fallback", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.thread_model_fallback.take();
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
+    fn render_thread_rate_limit_status_callout(&self) -> Option<Callout> {
+        let status = self.thread_rate_limit_status.as_ref()?;
+
+        let queued_message = if status.queued_requests == 1 {
+            "1 request is waiting to be sent".to_string()
+        } else {
+            format!("{} requests are waiting to be sent", status.queued_requests)
+        };
+        let resumes_in = status
+            .resumes_at
+            .map(|resumes_at| resumes_at.saturating_duration_since(Instant::now()))
+            .filter(|resumes_in| !resumes_in.is_zero());
+        let description = match resumes_in.map(|resumes_in| resumes_in.as_secs() + 1) {
+            Some(1) => format!("{queued_message}. Resuming in 1 second."),
+            Some(resumes_in_secs) => {
+                format!("{queued_message}. Resuming in {resumes_in_secs} seconds.")
+            }
+            None => format!("{queued_message}."),
+        };
+
+        Some(
+            Callout::new()
+                .severity(Severity::Warning)
+                .title("Waiting for the provider's rate limit")
+                .description(description),
+        )
+    }
+
     fn render_codex_windows_warning(&self, cx: &mut Context<Self>) -> Callout {
         Callout::new()
             .icon(IconName::Warning)
@@ -6902,6 +6968,8 @@ impl Render for AcpThreadView {
                 _ => this,
             })
             .children(self.render_thread_retry_status_callout(window, cx))
+            .children(self.render_thread_rate_limit_status_callout())
+            .children(self.render_thread_model_fallback_callout(cx))
             .when(self.show_codex_windows_warning, |this| {
                 this.child(self.render_codex_windows_warning(cx))
             })
@@ -1355,6 +1355,8 @@ impl AgentDiff {
             | AcpThreadEvent::PromptCapabilitiesUpdated
             | AcpThreadEvent::AvailableCommandsUpdated(_)
             | AcpThreadEvent::Retry(_)
+            | AcpThreadEvent::ModelFallback(_)
+            | AcpThreadEvent::RateLimited(_)
             | AcpThreadEvent::ModeUpdated(_)
             | AcpThreadEvent::ConfigOptionsUpdated(_) => {}
         }
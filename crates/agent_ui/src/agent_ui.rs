@@ -392,6 +392,11 @@ fn update_active_language_model_from_settings(cx: &mut App) {
         .iter()
         .map(to_selected_model)
         .collect::<Vec<_>>();
+    let fallback_models = settings
+        .fallback_models
+        .iter()
+        .map(to_selected_model)
+        .collect::<Vec<_>>();
 
     LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
         registry.select_default_model(default.as_ref(), cx);
@@ -399,6 +404,7 @@ fn update_active_language_model_from_settings(cx: &mut App) {
         registry.select_commit_message_model(commit_message.as_ref(), cx);
         registry.select_thread_summary_model(thread_summary.as_ref(), cx);
         registry.select_inline_alternative_models(inline_alternatives, cx);
+        registry.select_fallback_models(fallback_models, cx);
     });
 }
 
@@ -467,6 +473,7 @@ mod tests {
             commit_message_model: None,
             thread_summary_model: None,
             inline_alternatives: vec![],
+            fallback_models: vec![],
             favorite_models: vec![],
             default_profile: AgentProfileId::default(),
             default_view: DefaultAgentView::Thread,
@@ -10,7 +10,7 @@ use feature_flags::{FeatureFlagAppExt as _, InlineAssistantUseToolFeatureFlag};
 use futures::{
     SinkExt, Stream, StreamExt, TryStreamExt as _,
     channel::mpsc,
-    future::{LocalBoxFuture, Shared},
+    future::{BoxFuture, LocalBoxFuture, Shared},
     join,
     stream::BoxStream,
 };
@@ -427,7 +427,12 @@ impl CodegenAlternative {
             let request = self.build_request(&model, user_prompt, context_task, cx)?;
             let completion_events = cx.spawn({
                 let model = model.clone();
-                async move |_, cx| model.stream_completion(request.await, cx).await
+                async move |_, cx| {
+                    stream_with_fallback_models(model, request.await, cx, |model, request, cx| {
+                        model.stream_completion(request, cx)
+                    })
+                    .await
+                }
             });
             self.generation = self.handle_completion(model, completion_events, cx);
         } else {
@@ -439,7 +444,13 @@ impl CodegenAlternative {
                     cx.spawn({
                         let model = model.clone();
                         async move |_, cx| {
-                            Ok(model.stream_completion_text(request.await, cx).await?)
+                            Ok(stream_with_fallback_models(
+                                model,
+                                request.await,
+                                cx,
+                                |model, request, cx| model.stream_completion_text(request, cx),
+                            )
+                            .await?)
                         }
                     })
                     .boxed_local()
@@ -1469,6 +1480,46 @@ where
     }
 }
 
+/// Sends `request` to `model`, moving on to the next configured fallback model
+/// whenever a model fails with an error that another model might not hit.
+async fn stream_with_fallback_models<T>(
+    mut model: Arc<dyn LanguageModel>,
+    request: LanguageModelRequest,
+    cx: &AsyncApp,
+    stream: impl Fn(
+        &Arc<dyn LanguageModel>,
+        LanguageModelRequest,
+        &AsyncApp,
+    ) -> BoxFuture<'static, Result<T, LanguageModelCompletionError>>,
+) -> Result<T, LanguageModelCompletionError> {
+    loop {
+        let error = match stream(&model, request.clone(), cx).await {
+            Ok(response) => return Ok(response),
+            Err(error) => error,
+        };
+        let fallback_model = error
+            .is_retryable()
+            .then(|| {
+                cx.update(|cx| {
+                    LanguageModelRegistry::read_global(cx)
+                        .next_fallback_model(model.as_ref(), &request)
+                })
+                .ok()
+                .flatten()
+            })
+            .flatten();
+        let Some(fallback_model) = fallback_model else {
+            return Err(error);
+        };
+        log::info!(
+            "Inline assist falling back from {} to {} after error: {error}",
+            model.id().0,
+            fallback_model.id().0
+        );
+        model = fallback_model;
+    }
+}
+
 fn prefixes(text: &str) -> impl Iterator<Item = &str> {
     (0..text.len() - 1).map(|ix| &text[..ix + 1])
 }
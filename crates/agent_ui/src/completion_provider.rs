@@ -55,6 +55,7 @@ pub(crate) enum PromptContextType {
     Fetch,
     Thread,
     Rules,
+    Search,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,6 +93,7 @@ impl TryFrom<&str> for PromptContextType {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "search" => Ok(Self::Search),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -105,6 +107,7 @@ impl PromptContextType {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::Search => "search",
         }
     }
 
@@ -115,6 +118,7 @@ impl PromptContextType {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::Search => "Semantic Search",
         }
     }
 
@@ -125,6 +129,7 @@ impl PromptContextType {
             Self::Fetch => IconName::ToolWeb,
             Self::Thread => IconName::Thread,
             Self::Rules => IconName::Reader,
+            Self::Search => IconName::ToolSearch,
         }
     }
 }
@@ -135,6 +140,7 @@ pub(crate) enum Match {
     Thread(HistoryEntry),
     RecentThread(HistoryEntry),
     Fetch(SharedString),
+    Search(SharedString),
     Rules(RulesContextEntry),
     Entry(EntryMatch),
 }
@@ -149,6 +155,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
+            Match::Search(_) => 1.,
         }
     }
 }
@@ -499,6 +506,43 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_search(
+        source_range: Range<Anchor>,
+        query: SharedString,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Option<Completion> {
+        let new_text = format!("@search {} ", query);
+        let mention_uri = MentionUri::SemanticSearch {
+            query: query.to_string(),
+        };
+        let icon_path = mention_uri.icon_path(cx);
+        Some(Completion {
+            replace_range: source_range.clone(),
+            new_text: new_text.clone(),
+            label: CodeLabel::plain(format!("Search for \"{query}\""), None),
+            documentation: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(icon_path),
+            match_start: None,
+            snippet_deduplication_key: None,
+            insert_text_mode: None,
+            confirm: Some(confirm_completion_callback(
+                query,
+                source_range.start,
+                new_text.len() - 1,
+                mention_uri,
+                source,
+                editor,
+                mention_set,
+                workspace,
+            )),
+        })
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -655,6 +699,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
                 }
             }
 
+            Some(PromptContextType::Search) => {
+                if !query.is_empty() {
+                    Task::ready(vec![Match::Search(query.into())])
+                } else {
+                    Task::ready(Vec::new())
+                }
+            }
+
             Some(PromptContextType::Rules) => {
                 if let Some(prompt_store) = self.prompt_store.as_ref() {
                     let search_rules_task =
@@ -852,6 +904,10 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        if self.source.supports_context(PromptContextType::Search, cx) {
+            entries.push(PromptContextEntry::Mode(PromptContextType::Search));
+        }
+
         entries
     }
 }
@@ -1080,6 +1136,16 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                     cx,
                                 ),
 
+                                Match::Search(query) => Self::completion_for_search(
+                                    source_range.clone(),
+                                    query,
+                                    source.clone(),
+                                    editor.clone(),
+                                    mention_set.clone(),
+                                    workspace.clone(),
+                                    cx,
+                                ),
+
                                 Match::Entry(EntryMatch { entry, .. }) => {
                                     Self::completion_for_entry(
                                         entry,
@@ -1326,6 +1392,12 @@ impl MentionCompletion {
                 argument = Some(mode_text.to_string());
             }
             match rest_of_line[mode_text.len()..].find(|c: char| !c.is_whitespace()) {
+                // Search queries are natural language, so they extend to the end of the line.
+                Some(whitespace_count) if mode == Some(PromptContextType::Search) => {
+                    let query = rest_of_line[mode_text.len() + whitespace_count..].trim_end();
+                    argument = Some(query.to_string());
+                    end += whitespace_count + query.len();
+                }
                 Some(whitespace_count) => {
                     if let Some(argument_text) = parts.next() {
                         // If mode wasn't recognized but we have an argument, don't suggest completions
@@ -1918,5 +1990,19 @@ mod tests {
             None,
             "Should not parse with a space after @ at the start of the line"
         );
+
+        assert_eq!(
+            MentionCompletion::try_parse(
+                "Lorem @search where are sessions invalidated ",
+                0,
+                &[PromptContextType::Search]
+            ),
+            Some(MentionCompletion {
+                source_range: 6..44,
+                mode: Some(PromptContextType::Search),
+                argument: Some("where are sessions invalidated".to_string()),
+            }),
+            "Search queries should extend to the end of the line"
+        );
     }
 }
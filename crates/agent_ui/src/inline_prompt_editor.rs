@@ -19,6 +19,7 @@ use markdown::{HeadingLevelStyles, Markdown, MarkdownElement, MarkdownStyle};
 use parking_lot::Mutex;
 use project::Project;
 use prompt_store::PromptStore;
+use semantic_index::SemanticIndexSettings;
 use settings::Settings;
 use std::cmp;
 use std::ops::Range;
@@ -1187,14 +1188,18 @@ fn inline_assistant_model_supports_images(cx: &App) -> bool {
 }
 
 impl PromptCompletionProviderDelegate for PromptEditorCompletionProviderDelegate {
-    fn supported_modes(&self, _cx: &App) -> Vec<PromptContextType> {
-        vec![
+    fn supported_modes(&self, cx: &App) -> Vec<PromptContextType> {
+        let mut supported = vec![
             PromptContextType::File,
             PromptContextType::Symbol,
             PromptContextType::Thread,
             PromptContextType::Fetch,
             PromptContextType::Rules,
-        ]
+        ];
+        if SemanticIndexSettings::get_global(cx).enabled {
+            supported.push(PromptContextType::Search);
+        }
+        supported
     }
 
     fn supports_images(&self, cx: &App) -> bool {
@@ -1,5 +1,5 @@
 use acp_thread::{MentionUri, selection_name};
-use agent::{HistoryStore, outline};
+use agent::{HistoryStore, outline, semantic_search};
 use agent_client_protocol as acp;
 use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
@@ -42,6 +42,9 @@ use crate::ui::MentionCrease;
 
 pub type MentionTask = Shared<Task<Result<Mention, String>>>;
 
+/// How many excerpts a `@search` mention includes.
+const SEMANTIC_SEARCH_MENTION_LIMIT: usize = 8;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Mention {
     Text {
@@ -218,6 +221,9 @@ impl MentionSet {
             MentionUri::Fetch { url } => {
                 self.confirm_mention_for_fetch(url, workspace.read(cx).client().http_client(), cx)
             }
+            MentionUri::SemanticSearch { query } => {
+                self.confirm_mention_for_semantic_search(query, cx)
+            }
             MentionUri::Directory { .. } => Task::ready(Ok(Mention::Link)),
             MentionUri::Thread { id, .. } => self.confirm_mention_for_thread(id, cx),
             MentionUri::TextThread { path, .. } => self.confirm_mention_for_text_thread(path, cx),
@@ -339,6 +345,23 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_semantic_search(
+        &self,
+        query: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("project not found")));
+        };
+        let search = semantic_search(project, query, SEMANTIC_SEARCH_MENTION_LIMIT, cx);
+        cx.background_executor().spawn(async move {
+            Ok(Mention::Text {
+                content: search.await?,
+                tracked_buffers: Vec::new(),
+            })
+        })
+    }
+
     fn confirm_mention_for_symbol(
         &self,
         abs_path: PathBuf,
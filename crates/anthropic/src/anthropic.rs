@@ -655,6 +655,21 @@ impl RateLimitInfo {
             output_tokens: RateLimit::from_headers("output-tokens", headers).ok(),
         }
     }
+
+    /// Returns when the last of the limits that were used up resets, if any were.
+    pub fn exhausted_until(&self) -> Option<DateTime<Utc>> {
+        [
+            &self.requests,
+            &self.tokens,
+            &self.input_tokens,
+            &self.output_tokens,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|limit| limit.remaining == 0)
+        .map(|limit| limit.reset)
+        .max()
+    }
 }
 
 /// Parses the Retry-After header value as an integer number of seconds (anthropic always uses
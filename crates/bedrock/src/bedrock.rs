@@ -1,6 +1,6 @@
 mod models;
 
-use anyhow::{Context, Error, Result, anyhow};
+use anyhow::{Error, Result, anyhow};
 use aws_sdk_bedrockruntime as bedrock;
 pub use aws_sdk_bedrockruntime as bedrock_client;
 use aws_sdk_bedrockruntime::types::InferenceConfiguration;
@@ -74,13 +74,19 @@ pub async fn stream_completion(
         }
     }
 
-    let output = response
-        .send()
-        .await
-        .context("Failed to send API request to Bedrock");
+    let output = response.send().await.map_err(|err| {
+        if err
+            .as_service_error()
+            .is_some_and(|err| err.is_throttling_exception())
+        {
+            anyhow!(BedrockError::Throttled)
+        } else {
+            Error::new(err).context("Failed to send API request to Bedrock")
+        }
+    })?;
 
     let stream = Box::pin(stream::unfold(
-        output?.stream,
+        output.stream,
         move |mut stream| async move {
             match stream.recv().await {
                 Ok(Some(output)) => Some((Ok(output), stream)),
@@ -174,6 +180,8 @@ pub enum BedrockError {
     ClientError(anyhow::Error),
     #[error("extension error: {0}")]
     ExtensionError(anyhow::Error),
+    #[error("request was throttled by Bedrock")]
+    Throttled,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -331,6 +331,13 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::FindSearchCandidates>)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentHighlights>)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentSymbols>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSupertypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSubtypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSelectionRanges>)
             .add_request_handler(forward_read_only_project_request::<proto::GetProjectSymbols>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferForSymbol>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
@@ -385,6 +392,7 @@ impl Server {
             .add_request_handler(lsp_query)
             .add_message_handler(broadcast_project_message_from_host::<proto::LspQueryResponse>)
             .add_request_handler(forward_mutating_project_request::<proto::RestartLanguageServers>)
+            .add_request_handler(forward_read_only_project_request::<proto::CheckWholeProject>)
             .add_request_handler(forward_mutating_project_request::<proto::StopLanguageServers>)
             .add_request_handler(forward_mutating_project_request::<proto::LinkedEditingRange>)
             .add_message_handler(create_buffer_for_peer)
@@ -450,6 +458,20 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::GitShow>)
             .add_request_handler(forward_read_only_project_request::<proto::LoadCommitDiff>)
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitRebaseTodo>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitInteractiveRebase>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebaseCommand>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLog>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCommitOperation>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCreateTag>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetConfig>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitBisect>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitCheckStagedChanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetTags>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDeleteTag>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitReleaseCommits>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLoadConflictTexts>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetCommitSignature>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
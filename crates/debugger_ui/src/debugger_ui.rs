@@ -52,6 +52,10 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps over the current machine instruction.
+        StepOverInstruction,
+        /// Steps into the current machine instruction.
+        StepIntoInstruction,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -194,6 +198,22 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_out(cx)).ok();
                         }
                     })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepOverInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_over_instruction(cx))
+                                .ok();
+                        }
+                    })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepIntoInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_in_instruction(cx))
+                                .ok();
+                        }
+                    })
                     .when(supports_step_back, |div| {
                         let active_item = active_item.clone();
                         div.on_action(move |_: &StepBack, _, cx| {
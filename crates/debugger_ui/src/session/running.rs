@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -18,7 +19,7 @@ use crate::{
     attach_modal::{AttachModal, ModalIntent},
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    session::running::{disassembly_view::DisassemblyView, memory_view::MemoryView},
 };
 
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -26,7 +27,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -89,6 +90,7 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
 }
 
 impl RunningState {
@@ -758,6 +760,14 @@ impl RunningState {
                 cx,
             )
         });
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                project.clone(),
+                stack_frame_list.clone(),
+                cx,
+            )
+        });
         let variable_list = cx.new(|cx| {
             VariableList::new(
                 session.clone(),
@@ -842,6 +852,9 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -880,6 +893,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -909,6 +923,7 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             focus_handle,
@@ -1275,6 +1290,7 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -1355,6 +1371,12 @@ impl RunningState {
                 item_kind,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                cx,
+            )),
         }
     }
 
@@ -1539,6 +1561,11 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1669,6 +1696,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub(crate) fn step_back(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
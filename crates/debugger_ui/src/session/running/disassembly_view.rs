@@ -0,0 +1,387 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::{HashMap, HashSet};
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ListHorizontalSizingBehavior, ScrollStrategy,
+    Subscription, Task, UniformListScrollHandle, uniform_list,
+};
+use project::{
+    Project,
+    debugger::session::{Session, SessionEvent},
+};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt as _;
+
+use crate::session::running::stack_frame_list::{StackFrameList, StackFrameListEvent};
+
+/// How many instructions are disassembled before the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 50;
+/// How many instructions are disassembled from the instruction pointer on.
+const INSTRUCTIONS_AFTER: u64 = 150;
+
+enum DisassemblyEntry {
+    /// A line of source code, shown before the instructions it was compiled to.
+    Source {
+        path: Arc<Path>,
+        line: u64,
+    },
+    Instruction(dap::DisassembledInstruction),
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    project: Entity<Project>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    instruction_pointer: Option<String>,
+    entries: Vec<DisassemblyEntry>,
+    source_lines: HashMap<Arc<Path>, Option<Arc<[SharedString]>>>,
+    _rebuild_task: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        project: Entity<Project>,
+        stack_frame_list: Entity<StackFrameList>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = [
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_)
+                | SessionEvent::HistoricSnapshotSelected
+                | SessionEvent::Disassembly => {
+                    if this._rebuild_task.is_some() {
+                        this.schedule_rebuild(cx);
+                    }
+                }
+                _ => {}
+            }),
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(_)
+                | StackFrameListEvent::BuiltEntries => {
+                    if this._rebuild_task.is_some() {
+                        this.schedule_rebuild(cx);
+                    }
+                }
+            }),
+        ];
+
+        Self {
+            session,
+            project,
+            stack_frame_list,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            instruction_pointer: None,
+            entries: Vec::new(),
+            source_lines: HashMap::default(),
+            _rebuild_task: None,
+            _subscriptions,
+        }
+    }
+
+    fn schedule_rebuild(&mut self, cx: &mut Context<Self>) {
+        self._rebuild_task = Some(cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.rebuild(cx)).ok();
+        }));
+    }
+
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference.clone());
+        let instructions = instruction_pointer.clone().and_then(|instruction_pointer| {
+            self.session.update(cx, |session, cx| {
+                session.disassemble(
+                    instruction_pointer,
+                    -(INSTRUCTIONS_BEFORE as i64),
+                    INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                    cx,
+                )
+            })
+        });
+
+        let mut entries = Vec::new();
+        let mut current_path = None::<Arc<Path>>;
+        let mut current_line = None;
+        for instruction in instructions
+            .iter()
+            .flat_map(|instructions| instructions.iter())
+        {
+            // Adapters may omit the location when it's unchanged from the previous instruction.
+            if let Some(path) = instruction
+                .location
+                .as_ref()
+                .and_then(|source| source.path.as_deref())
+                && current_path.as_deref() != Some(Path::new(path))
+            {
+                current_path = Some(Arc::from(Path::new(path)));
+                current_line = None;
+            }
+            if let Some((path, line)) = current_path.clone().zip(instruction.line)
+                && current_line != Some(line)
+            {
+                current_line = Some(line);
+                entries.push(DisassemblyEntry::Source { path, line });
+            }
+            entries.push(DisassemblyEntry::Instruction(instruction.clone()));
+        }
+
+        let paths = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                DisassemblyEntry::Source { path, .. } => Some(path.clone()),
+                DisassemblyEntry::Instruction(_) => None,
+            })
+            .collect::<HashSet<_>>();
+        for path in paths {
+            self.load_source(path, cx);
+        }
+
+        let instruction_pointer_changed = self.instruction_pointer != instruction_pointer;
+        self.instruction_pointer = instruction_pointer;
+        self.entries = entries;
+        if instruction_pointer_changed && let Some(ix) = self.instruction_pointer_ix() {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = self.instruction_pointer.as_deref()?;
+        self.entries.iter().position(|entry| match entry {
+            DisassemblyEntry::Instruction(instruction) => {
+                instruction.address == instruction_pointer
+            }
+            DisassemblyEntry::Source { .. } => false,
+        })
+    }
+
+    fn load_source(&mut self, path: Arc<Path>, cx: &mut Context<Self>) {
+        if self.source_lines.contains_key(&path) {
+            return;
+        }
+        self.source_lines.insert(path.clone(), None);
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return;
+        }
+        let fs = project.fs().clone();
+        cx.spawn(async move |this, cx| {
+            let Some(text) = fs.load(&path).await.log_err() else {
+                return;
+            };
+            let lines = text
+                .lines()
+                .map(|line| SharedString::from(line.to_owned()))
+                .collect::<Arc<[_]>>();
+            this.update(cx, |this, cx| {
+                this.source_lines.insert(path, Some(lines));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_instruction_breakpoint(&mut self, address: &str, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(address, cx);
+        });
+        cx.notify();
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        breakpoints: &HashSet<String>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        match &self.entries[ix] {
+            DisassemblyEntry::Source { path, line } => {
+                let text = self.source_lines.get(path).and_then(|lines| {
+                    let ix = line.checked_sub(1)? as usize;
+                    lines.as_ref()?.get(ix).cloned()
+                });
+                let file_name = path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                h_flex()
+                    .id(("disassembly-source", ix))
+                    .w_full()
+                    .gap_2()
+                    .pl_6()
+                    .child(
+                        Label::new(format!("{file_name}:{line}"))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    )
+                    .when_some(text, |this, text| {
+                        this.child(
+                            Label::new(text)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line(),
+                        )
+                    })
+                    .into_any()
+            }
+            DisassemblyEntry::Instruction(instruction) => {
+                let is_instruction_pointer =
+                    self.instruction_pointer.as_deref() == Some(instruction.address.as_str());
+                let has_breakpoint = breakpoints.contains(&instruction.address);
+                let address = instruction.address.clone();
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .when(is_instruction_pointer, |this| {
+                        this.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .child(
+                        div()
+                            .id(("disassembly-gutter", ix))
+                            .w_4()
+                            .flex_none()
+                            .cursor_pointer()
+                            .when(has_breakpoint, |this| {
+                                this.child(
+                                    Icon::new(IconName::DebugBreakpoint)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Debugger),
+                                )
+                            })
+                            .tooltip(Tooltip::text("Toggle Instruction Breakpoint"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_instruction_breakpoint(&address, cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(instruction.address.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div().w_40().flex_none().child(
+                                Label::new(bytes)
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            ),
+                        )
+                    })
+                    .child(
+                        Label::new(instruction.instruction.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .into_any()
+            }
+        }
+    }
+
+    /// The entries as they are rendered, with the instruction at the instruction pointer marked with `>`.
+    #[cfg(test)]
+    pub(crate) fn rendered_entries(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                DisassemblyEntry::Source { path, line } => {
+                    let text = self
+                        .source_lines
+                        .get(path)
+                        .and_then(|lines| lines.as_ref()?.get(*line as usize - 1).cloned())
+                        .unwrap_or_default();
+                    format!("{}:{line} {text}", path.display())
+                }
+                DisassemblyEntry::Instruction(instruction) => {
+                    let marker = if self.instruction_pointer.as_deref()
+                        == Some(instruction.address.as_str())
+                    {
+                        ">"
+                    } else {
+                        " "
+                    };
+                    format!(
+                        "{marker} {} {}",
+                        instruction.address, instruction.instruction
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.entries.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                let breakpoints = this
+                    .session
+                    .read(cx)
+                    .instruction_breakpoints()
+                    .filter(|breakpoint| breakpoint.is_enabled)
+                    .map(|breakpoint| breakpoint.dap.instruction_reference.clone())
+                    .collect::<HashSet<_>>();
+                range
+                    .map(|ix| this.render_entry(ix, &breakpoints, cx))
+                    .collect()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+        .size_full()
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self._rebuild_task.is_none() {
+            self.schedule_rebuild(cx);
+        }
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .map(|this| {
+                if self.entries.is_empty() {
+                    this.child(
+                        Label::new("No disassembly available for the selected stack frame")
+                            .color(Color::Muted),
+                    )
+                } else {
+                    this.child(self.render_list(cx)).vertical_scrollbar_for(
+                        &self.scroll_handle,
+                        window,
+                        cx,
+                    )
+                }
+            })
+    }
+}
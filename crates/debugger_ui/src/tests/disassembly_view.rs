@@ -0,0 +1,204 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, SteppingGranularity, StoppedEvent,
+    requests::{
+        Disassemble, Initialize, Next, Scopes, SetInstructionBreakpoints, StackTrace, Threads,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use parking_lot::Mutex;
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::Arc;
+use util::path;
+
+fn instruction(
+    address: &str,
+    instruction: &str,
+    location: Option<&str>,
+    line: u64,
+) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: instruction.into(),
+        symbol: None,
+        location: location.map(|path| dap::Source {
+            name: None,
+            path: Some(path.into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line: Some(line),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    return 0;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 2,
+                column: 5,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Disassemble, _>(move |_, args| {
+        assert_eq!(args.memory_reference, "0x1004");
+        assert_eq!(args.instruction_offset, Some(-50));
+        assert_eq!(args.instruction_count, 200);
+        Ok(dap::DisassembleResponse {
+            instructions: vec![
+                instruction("0x1000", "push rbp", Some(path!("/project/main.c")), 1),
+                instruction("0x1004", "xor eax, eax", None, 2),
+                instruction("0x1008", "pop rbp", None, 2),
+            ],
+        })
+    });
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock() = args.breakpoints;
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+    let step_granularities = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Next, _>({
+        let step_granularities = step_granularities.clone();
+        move |_, args| {
+            step_granularities.lock().push(args.granularity);
+            Ok(())
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+    running_state.update_in(cx, |this, window, cx| {
+        this.session().update(cx, |session, cx| session.threads(cx));
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+    cx.run_until_parked();
+
+    running_state.update(cx, |this, cx| {
+        assert_eq!(
+            this.disassembly_view().read(cx).rendered_entries(),
+            [
+                format!("{}:1 int main() {{", path!("/project/main.c")),
+                "  0x1000 push rbp".to_string(),
+                format!("{}:2     return 0;", path!("/project/main.c")),
+                "> 0x1004 xor eax, eax".to_string(),
+                "  0x1008 pop rbp".to_string(),
+            ]
+        );
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008", cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        instruction_breakpoints
+            .lock()
+            .iter()
+            .map(|breakpoint| breakpoint.instruction_reference.clone())
+            .collect::<Vec<_>>(),
+        ["0x1008"]
+    );
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008", cx);
+    });
+    cx.run_until_parked();
+    assert!(instruction_breakpoints.lock().is_empty());
+
+    running_state.update(cx, |this, cx| this.step_over_instruction(cx));
+    cx.run_until_parked();
+    assert_eq!(
+        *step_granularities.lock(),
+        [Some(SteppingGranularity::Instruction)]
+    );
+}
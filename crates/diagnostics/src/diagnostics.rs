@@ -47,6 +47,7 @@ use util::ResultExt;
 use workspace::{
     ItemNavHistory, Workspace,
     item::{Item, ItemEvent, ItemHandle, SaveOptions, TabContentParams},
+    notifications::NotifyTaskExt as _,
     searchable::SearchableItemHandle,
 };
 
@@ -58,7 +59,10 @@ actions!(
         /// Toggles the display of warning-level diagnostics.
         ToggleWarnings,
         /// Toggles automatic refresh of diagnostics.
-        ToggleDiagnosticsRefresh
+        ToggleDiagnosticsRefresh,
+        /// Pulls the diagnostics of all the files of the project, including the ones not open,
+        /// from the language servers supporting workspace diagnostics.
+        CheckWholeProject
     ]
 );
 
@@ -163,6 +167,7 @@ impl ProjectDiagnosticsEditor {
         _: &mut Context<Workspace>,
     ) {
         workspace.register_action(Self::deploy);
+        workspace.register_action(Self::check_whole_project);
     }
 
     fn new(
@@ -414,6 +419,19 @@ impl ProjectDiagnosticsEditor {
         }
     }
 
+    fn check_whole_project(
+        workspace: &mut Workspace,
+        _: &CheckWholeProject,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        Self::deploy(workspace, &Deploy, window, cx);
+        workspace
+            .project()
+            .update(cx, |project, cx| project.check_whole_project(cx))
+            .detach_and_notify_err(window, cx);
+    }
+
     fn toggle_warnings(&mut self, _: &ToggleWarnings, _: &mut Window, cx: &mut Context<Self>) {
         cx.set_global(IncludeWarnings(!self.include_warnings));
     }
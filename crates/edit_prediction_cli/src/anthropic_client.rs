@@ -7,6 +7,7 @@ use anyhow::Result;
 use futures::StreamExt as _;
 use http_client::HttpClient;
 use indoc::indoc;
+use language_model::{ResponseCache, ResponseCacheMode};
 use reqwest_client::ReqwestClient;
 use sqlez::bindable::Bind;
 use sqlez::bindable::StaticColumnCount;
@@ -14,24 +15,59 @@ use sqlez_macros::sql;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+
+static RESPONSE_CACHE: OnceLock<ResponseCache> = OnceLock::new();
+
+/// Makes every [`PlainLlmClient`] replay and record its responses with the given cache.
+pub fn set_response_cache(cache: ResponseCache) {
+    RESPONSE_CACHE.set(cache).ok();
+}
 
 pub struct PlainLlmClient {
     pub http_client: Arc<dyn HttpClient>,
     pub api_key: String,
+    response_cache: Option<ResponseCache>,
 }
 
 impl PlainLlmClient {
     pub fn new() -> Result<Self> {
         let http_client: Arc<dyn http_client::HttpClient> = Arc::new(ReqwestClient::new());
-        let api_key = std::env::var("ANTHROPIC_API_KEY")
-            .map_err(|_| anyhow::anyhow!("ANTHROPIC_API_KEY environment variable not set"))?;
+        let response_cache = RESPONSE_CACHE.get().cloned();
+        // Replaying cached responses doesn't need an API key.
+        let api_key = match std::env::var("ANTHROPIC_API_KEY") {
+            Ok(api_key) => api_key,
+            Err(_)
+                if response_cache
+                    .as_ref()
+                    .is_some_and(|cache| cache.mode() == ResponseCacheMode::ReplayOnly) =>
+            {
+                String::new()
+            }
+            Err(_) => anyhow::bail!("ANTHROPIC_API_KEY environment variable not set"),
+        };
         Ok(Self {
             http_client,
             api_key,
+            response_cache,
         })
     }
 
+    fn cached_response(&self, request: &serde_json::Value) -> Result<Option<AnthropicResponse>> {
+        match &self.response_cache {
+            Some(cache) => cache.lookup(request),
+            None => Ok(None),
+        }
+    }
+
+    fn record_response(&self, request: &serde_json::Value, response: &AnthropicResponse) {
+        if let Some(cache) = &self.response_cache
+            && let Err(error) = cache.record(request, response)
+        {
+            log::error!("{error:#}");
+        }
+    }
+
     pub async fn generate(
         &self,
         model: &str,
@@ -53,6 +89,11 @@ impl PlainLlmClient {
             top_p: None,
         };
 
+        let cache_key = serde_json::to_value(&request)?;
+        if let Some(response) = self.cached_response(&cache_key)? {
+            return Ok(response);
+        }
+
         let response = non_streaming_completion(
             self.http_client.as_ref(),
             ANTHROPIC_API_URL,
@@ -63,6 +104,7 @@ impl PlainLlmClient {
         .await
         .map_err(|e| anyhow::anyhow!("{:?}", e))?;
 
+        self.record_response(&cache_key, &response);
         Ok(response)
     }
 
@@ -91,6 +133,20 @@ impl PlainLlmClient {
             top_p: None,
         };
 
+        let cache_key = serde_json::to_value(&request)?;
+        if let Some(response) = self.cached_response(&cache_key)? {
+            let text = response
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    ResponseContent::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<String>();
+            on_progress(text.len(), &text);
+            return Ok(response);
+        }
+
         let mut stream = stream_completion(
             self.http_client.as_ref(),
             ANTHROPIC_API_URL,
@@ -129,6 +185,7 @@ impl PlainLlmClient {
                 .push(ResponseContent::Text { text: text_content });
         }
 
+        self.record_response(&cache_key, &response);
         Ok(response)
     }
 }
@@ -18,6 +18,7 @@ mod synthesize;
 use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use edit_prediction::EditPredictionStore;
 use gpui::Application;
+use language_model::{ResponseCache, ResponseCacheMode};
 use reqwest_client::ReqwestClient;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -60,6 +61,18 @@ struct EpArgs {
     in_place: bool,
     #[arg(long, short, global = true)]
     failfast: bool,
+    /// Directory in which to cache Anthropic responses, keyed on the request.
+    /// When unset, no responses are cached.
+    #[arg(long, value_name = "DIR", global = true)]
+    model_cache: Option<PathBuf>,
+    /// How to use the model response cache: record, replay-only or passthrough
+    #[arg(
+        long,
+        default_value = "record",
+        requires = "model_cache",
+        global = true
+    )]
+    model_cache_mode: ResponseCacheMode,
 }
 
 const INPUTS_HELP: &str = r#"
@@ -307,6 +320,13 @@ fn main() {
         return;
     }
 
+    if let Some(cache_dir) = &args.model_cache {
+        anthropic_client::set_response_cache(ResponseCache::new(
+            cache_dir.clone(),
+            args.model_cache_mode,
+        ));
+    }
+
     let output = args.output_path();
     let command = match &args.command {
         Some(cmd) => cmd.clone(),
@@ -19,6 +19,7 @@ pub mod code_context_menus;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -35,7 +36,9 @@ pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
 mod split;
 pub mod tasks;
 
@@ -146,10 +149,10 @@ use multi_buffer::{
 use parking_lot::Mutex;
 use persistence::DB;
 use project::{
-    BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, InlayId,
-    InvalidationStrategy, Location, LocationLink, LspAction, PrepareRenameResponse, Project,
-    ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
+    BreakpointWithPosition, BufferSemanticTokens, CodeAction, Completion, CompletionDisplayOptions,
+    CompletionIntent, CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight,
+    InlayHint, InlayId, InvalidationStrategy, Location, LocationLink, LspAction,
+    PrepareRenameResponse, Project, ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -1048,6 +1051,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_syntax_node_history: SelectSyntaxNodeHistory,
+    selection_ranges_task: Task<()>,
     ime_transaction: Option<TransactionId>,
     pub diagnostics_max_severity: DiagnosticSeverity,
     active_diagnostics: ActiveDiagnostic,
@@ -1212,6 +1216,10 @@ pub struct Editor {
     colors: Option<LspColorData>,
     post_scroll_update: Task<()>,
     refresh_colors_task: Task<()>,
+    semantic_tokens: HashMap<BufferId, BufferSemanticTokens>,
+    refresh_semantic_tokens_task: Task<()>,
+    lsp_folding_creases: HashMap<BufferId, folding_ranges::LspFoldingCreases>,
+    refresh_folding_ranges_task: Task<()>,
     inlay_hints: Option<LspInlayHintData>,
     folding_newlines: Task<()>,
     select_next_is_case_sensitive: Option<bool>,
@@ -2216,6 +2224,7 @@ impl Editor {
             autoclose_regions: Vec::new(),
             snippet_stack: InvalidationStack::default(),
             select_syntax_node_history: SelectSyntaxNodeHistory::default(),
+            selection_ranges_task: Task::ready(()),
             ime_transaction: None,
             active_diagnostics: ActiveDiagnostic::None,
             show_inline_diagnostics: ProjectSettings::get_global(cx).diagnostics.inline.enabled,
@@ -2377,6 +2386,10 @@ impl Editor {
             pull_diagnostics_background_task: Task::ready(()),
             colors: None,
             refresh_colors_task: Task::ready(()),
+            semantic_tokens: HashMap::default(),
+            refresh_semantic_tokens_task: Task::ready(()),
+            lsp_folding_creases: HashMap::default(),
+            refresh_folding_ranges_task: Task::ready(()),
             inlay_hints: None,
             next_color_inlay_id: 0,
             post_scroll_update: Task::ready(()),
@@ -2455,6 +2468,8 @@ impl Editor {
                                 .update_in(cx, |editor, window, cx| {
                                     editor.register_visible_buffers(cx);
                                     editor.refresh_colors_for_visible_range(None, window, cx);
+                                    editor.refresh_semantic_tokens(None, cx);
+                                    editor.refresh_folding_ranges(None, cx);
                                     editor.refresh_inlay_hints(
                                         InlayHintRefreshReason::NewLinesShown,
                                         cx,
@@ -15974,9 +15989,9 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(visible_row_count) = self.visible_row_count() else {
+        if self.visible_row_count().is_none() {
             return;
-        };
+        }
         let old_selections: Box<[_]> = self
             .selections
             .all::<MultiBufferOffset>(&self.display_snapshot(cx))
@@ -15987,6 +16002,17 @@ impl Editor {
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
 
+        if !self.select_larger_selection_range(&old_selections, window, cx) {
+            self.select_larger_tree_sitter_node(old_selections, window, cx);
+        }
+    }
+
+    fn select_larger_tree_sitter_node(
+        &mut self,
+        old_selections: Box<[Selection<MultiBufferOffset>]>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
@@ -16046,6 +16072,22 @@ impl Editor {
             return; // don't put this call in the history
         }
 
+        self.push_larger_syntax_node_selections(old_selections, new_selections, window, cx);
+    }
+
+    /// Selects the expanded selections, and records the previous ones for [`Self::select_smaller_syntax_node`].
+    fn push_larger_syntax_node_selections(
+        &mut self,
+        old_selections: Box<[Selection<MultiBufferOffset>]>,
+        mut new_selections: Vec<Selection<MultiBufferOffset>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(visible_row_count) = self.visible_row_count() else {
+            return;
+        };
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
         // scroll based on transformation done to the last selection created by the user
         let (last_old, last_new) = old_selections
             .last()
@@ -16060,13 +16102,11 @@ impl Editor {
             should_newest_selection_be_reversed
         };
 
-        if selected_larger_node {
-            self.select_syntax_node_history.disable_clearing = true;
-            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                s.select(new_selections.clone());
-            });
-            self.select_syntax_node_history.disable_clearing = false;
-        }
+        self.select_syntax_node_history.disable_clearing = true;
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select(new_selections.clone());
+        });
+        self.select_syntax_node_history.disable_clearing = false;
 
         let start_row = last_new.start.to_display_point(&display_map).row().0;
         let end_row = last_new.end.to_display_point(&display_map).row().0;
@@ -22368,6 +22408,9 @@ impl Editor {
 
             if language_settings_changed || accents_changed {
                 self.colorize_brackets(true, cx);
+                self.highlight_semantic_tokens(cx);
+                self.refresh_semantic_tokens(None, cx);
+                self.refresh_folding_ranges(None, cx);
             }
 
             if let Some(inlay_splice) = self.colors.as_mut().and_then(|colors| {
@@ -23246,6 +23289,8 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors_for_visible_range(for_buffer, window, cx);
+        self.refresh_semantic_tokens(for_buffer, cx);
+        self.refresh_folding_ranges(for_buffer, cx);
     }
 
     fn register_visible_buffers(&mut self, cx: &mut Context<Self>) {
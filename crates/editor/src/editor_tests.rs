@@ -28,6 +28,7 @@ use language::{
     LanguageConfigOverride, LanguageMatcher, LanguageName, Override, Point,
     language_settings::{
         CompletionSettingsContent, FormatterList, LanguageSettingsContent, LspInsertMode,
+        SemanticTokens,
     },
     tree_sitter_python,
 };
@@ -18352,6 +18353,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                 })),
                 enable_lsp_tasks: false,
                 fetch: None,
+                restart_policy: None,
             },
         );
     });
@@ -18373,6 +18375,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                 })),
                 enable_lsp_tasks: false,
                 fetch: None,
+                restart_policy: None,
             },
         );
     });
@@ -18394,6 +18397,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                 })),
                 enable_lsp_tasks: false,
                 fetch: None,
+                restart_policy: None,
             },
         );
     });
@@ -18413,6 +18417,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut TestAppCon
                 initialization_options: None,
                 enable_lsp_tasks: false,
                 fetch: None,
+                restart_policy: None,
             },
         );
     });
@@ -28449,6 +28454,140 @@ async fn test_race_in_multibuffer_save(cx: &mut TestAppContext) {
 }
 
 #[track_caller]
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.semantic_tokens = Some(SemanticTokens::Combined);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": "fn main() { old(); }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::FUNCTION],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DEPRECATED],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..lsp::SemanticTokensOptions::default()
+                        },
+                    ),
+                ),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_abs_path(
+                PathBuf::from(path!("/a/main.rs")),
+                OpenOptions::default(),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let token = |delta_line, delta_start, length, token_modifiers_bitset| lsp::SemanticToken {
+        delta_line,
+        delta_start,
+        length,
+        token_type: 0,
+        token_modifiers_bitset,
+    };
+    let mut full_request_handle = fake_server
+        .set_request_handler::<lsp::request::SemanticTokensFullRequest, _, _>(
+            move |_, _| async move {
+                Ok(Some(lsp::SemanticTokensResult::Tokens(
+                    lsp::SemanticTokens {
+                        result_id: Some("1".to_string()),
+                        data: vec![token(0, 3, 4, 0), token(0, 9, 3, 1)],
+                    },
+                )))
+            },
+        );
+    let mut delta_request_handle = fake_server
+        .set_request_handler::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+            move |params, _| async move {
+                assert_eq!(params.previous_result_id, "1");
+                Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                    lsp::SemanticTokensDelta {
+                        result_id: Some("2".to_string()),
+                        edits: vec![lsp::SemanticTokensEdit {
+                            start: 0,
+                            delete_count: 5,
+                            data: Some(vec![token(1, 3, 4, 0)]),
+                        }],
+                    },
+                )))
+            },
+        );
+
+    cx.executor().advance_clock(FETCH_COLORS_DEBOUNCE_TIMEOUT);
+    full_request_handle.next().await.unwrap();
+    cx.run_until_parked();
+    editor.update_in(cx, |editor, window, cx| {
+        assert_eq!(
+            deprecated_token_highlights(editor, window, cx),
+            vec![DisplayPoint::new(DisplayRow(0), 12)..DisplayPoint::new(DisplayRow(0), 15)],
+            "Should strike through the deprecated function"
+        );
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_to_beginning(&MoveToBeginning, window, cx);
+        editor.handle_input("\n", window, cx);
+    });
+    cx.executor().advance_clock(FETCH_COLORS_DEBOUNCE_TIMEOUT);
+    delta_request_handle.next().await.unwrap();
+    cx.run_until_parked();
+    editor.update_in(cx, |editor, window, cx| {
+        assert_eq!(
+            deprecated_token_highlights(editor, window, cx),
+            vec![DisplayPoint::new(DisplayRow(1), 12)..DisplayPoint::new(DisplayRow(1), 15)],
+            "Should apply the delta to the previous tokens"
+        );
+    });
+}
+
+fn deprecated_token_highlights(
+    editor: &mut Editor,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) -> Vec<Range<DisplayPoint>> {
+    editor
+        .all_text_highlights(window, cx)
+        .into_iter()
+        .filter(|(style, _)| style.strikethrough.is_some())
+        .flat_map(|(_, ranges)| ranges)
+        .collect()
+}
+
 fn extract_color_inlays(editor: &Editor, cx: &App) -> Vec<Rgba> {
     editor
         .all_inlays(cx)
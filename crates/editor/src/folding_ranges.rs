@@ -0,0 +1,173 @@
+//! Folds from the LSP folding ranges of buffers whose `folding_ranges` language setting is `language_server`.
+//! The ranges are inserted as creases, which take precedence over the indentation-based folds starting on the same rows.
+
+use futures::future::join_all;
+use gpui::{App, Context, Entity, Task};
+use itertools::Itertools as _;
+use language::{
+    Buffer,
+    language_settings::{FoldingRanges, language_settings},
+};
+use project::BufferFoldingRanges;
+use text::{BufferId, ToPoint as _};
+
+use crate::{
+    Editor, FETCH_COLORS_DEBOUNCE_TIMEOUT,
+    display_map::{Crease, CreaseId},
+};
+
+/// The creases inserted for the folding ranges of a buffer.
+#[derive(Debug, Default)]
+pub(super) struct LspFoldingCreases {
+    cache_version: Option<usize>,
+    crease_ids: Vec<CreaseId>,
+}
+
+impl Editor {
+    pub(super) fn refresh_folding_ranges(
+        &mut self,
+        buffer_id: Option<BufferId>,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_full() || self.ignore_lsp_data() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        self.remove_stale_folding_creases(cx);
+
+        let visible_buffers = self
+            .visible_excerpts(true, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                let editor_buffer_id = editor_buffer.read(cx).remote_id();
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer_id)
+                    && self.registered_buffers.contains_key(&editor_buffer_id)
+                    && folding_ranges_mode(editor_buffer, cx) == FoldingRanges::LanguageServer
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let all_ranges_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let known_cache_version = self
+                        .lsp_folding_creases
+                        .get(&buffer.read(cx).remote_id())
+                        .and_then(|creases| creases.cache_version);
+                    let ranges_task =
+                        lsp_store.folding_ranges(known_cache_version, buffer.clone(), cx)?;
+                    Some(async move { (buffer, ranges_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if all_ranges_task.is_empty() {
+            self.refresh_folding_ranges_task = Task::ready(());
+            return;
+        }
+
+        self.refresh_folding_ranges_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_COLORS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_ranges = join_all(all_ranges_task).await;
+            editor
+                .update(cx, |editor, cx| {
+                    for (buffer, ranges) in all_ranges {
+                        match ranges {
+                            // The buffer changed while the ranges were fetched, keep the current creases until the next refresh.
+                            Ok(ranges) if ranges.cache_version.is_none() => {}
+                            Ok(ranges) => editor.replace_folding_creases(&buffer, ranges, cx),
+                            Err(e) => log::error!("Failed to retrieve folding ranges: {e:#}"),
+                        }
+                    }
+                })
+                .ok();
+        });
+    }
+
+    fn replace_folding_creases(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        ranges: BufferFoldingRanges,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut buffer_ranges = ranges
+            .ranges
+            .values()
+            .flat_map(|server_ranges| server_ranges.iter())
+            .map(|folding_range| folding_range.range.clone())
+            .collect::<Vec<_>>();
+        buffer_ranges.sort_by(|a, b| {
+            a.start
+                .cmp(&b.start, &buffer_snapshot)
+                .then_with(|| b.end.cmp(&a.end, &buffer_snapshot))
+        });
+        // Only one fold can be toggled per row, so keep the outermost of the ranges starting on the same row.
+        buffer_ranges.dedup_by_key(|range| range.start.to_point(&buffer_snapshot).row);
+
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let placeholder = self.display_map.read(cx).fold_placeholder.clone();
+        let mut creases = Vec::new();
+        for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(buffer_id, cx) {
+            for range in &buffer_ranges {
+                if let Some(range) =
+                    multi_buffer_snapshot.anchor_range_in_excerpt(excerpt_id, range.clone())
+                {
+                    creases.push(Crease::simple(range, placeholder.clone()));
+                }
+            }
+        }
+
+        if let Some(old_creases) = self.lsp_folding_creases.remove(&buffer_id) {
+            self.remove_creases(old_creases.crease_ids, cx);
+        }
+        let crease_ids = self.insert_creases(creases, cx);
+        self.lsp_folding_creases.insert(
+            buffer_id,
+            LspFoldingCreases {
+                cache_version: ranges.cache_version,
+                crease_ids,
+            },
+        );
+        cx.notify();
+    }
+
+    /// Removes the creases of the buffers that left the editor, or no longer fold by language server ranges.
+    fn remove_stale_folding_creases(&mut self, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let stale_buffers = self
+            .lsp_folding_creases
+            .keys()
+            .copied()
+            .filter(|buffer_id| {
+                multi_buffer.buffer(*buffer_id).is_none_or(|buffer| {
+                    folding_ranges_mode(&buffer, cx) != FoldingRanges::LanguageServer
+                })
+            })
+            .collect::<Vec<_>>();
+        for buffer_id in stale_buffers {
+            if let Some(creases) = self.lsp_folding_creases.remove(&buffer_id) {
+                self.remove_creases(creases.crease_ids, cx);
+            }
+        }
+    }
+}
+
+fn folding_ranges_mode(buffer: &Entity<Buffer>, cx: &App) -> FoldingRanges {
+    let buffer = buffer.read(cx);
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .folding_ranges
+}
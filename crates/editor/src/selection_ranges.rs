@@ -0,0 +1,139 @@
+//! Expanding selections to the LSP selection ranges of buffers whose `selection_ranges` language setting is `language_server`.
+
+use gpui::{App, Context, Entity, Window};
+use language::{
+    Buffer, OffsetRangeExt as _, Selection, SelectionGoal,
+    language_settings::{SelectionRanges, language_settings},
+};
+use multi_buffer::{BufferOffset, MultiBufferOffset};
+use util::ResultExt as _;
+
+use crate::Editor;
+
+impl Editor {
+    /// Starts expanding the selections to the enclosing ranges reported by a language server, falling back
+    /// to the tree-sitter syntax nodes if no server provides them.
+    /// Returns `false` when the selections should be expanded with tree-sitter right away.
+    pub(super) fn select_larger_selection_range(
+        &mut self,
+        old_selections: &[Selection<MultiBufferOffset>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+        let (Some(first_selection), Some(last_selection)) =
+            (old_selections.first(), old_selections.last())
+        else {
+            return false;
+        };
+        let selections_range = first_selection.start..last_selection.end;
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        // A language server only knows about a single buffer, so selections spanning several excerpts use tree-sitter.
+        let Some(mut excerpt) = multi_buffer_snapshot.excerpt_containing(selections_range.clone())
+        else {
+            return false;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(excerpt.buffer_id()) else {
+            return false;
+        };
+        if selection_ranges_mode(&buffer, cx) != SelectionRanges::LanguageServer {
+            return false;
+        }
+
+        let buffer_snapshot = excerpt.buffer();
+        let positions = old_selections
+            .iter()
+            .map(|selection| {
+                buffer_snapshot.anchor_before(excerpt.map_offset_to_buffer(selection.start).0)
+            })
+            .collect();
+        let selection_ranges = project.update(cx, |project, cx| {
+            project.selection_ranges(&buffer, positions, cx)
+        });
+        let old_selections: Box<[_]> = old_selections.into();
+        self.selection_ranges_task = cx.spawn_in(window, async move |editor, cx| {
+            let selection_ranges = selection_ranges.await.log_err().unwrap_or_default();
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    let display_snapshot = editor.display_snapshot(cx);
+                    let current_selections = editor
+                        .selections
+                        .all::<MultiBufferOffset>(&display_snapshot);
+                    if current_selections.len() != old_selections.len()
+                        || current_selections
+                            .iter()
+                            .zip(old_selections.iter())
+                            .any(|(current, old)| current.range() != old.range())
+                    {
+                        // The selections changed while the server was responding.
+                        return;
+                    }
+                    if selection_ranges.len() != old_selections.len() {
+                        editor.select_larger_tree_sitter_node(old_selections, window, cx);
+                        return;
+                    }
+
+                    let Some(mut excerpt) = display_snapshot
+                        .buffer_snapshot()
+                        .excerpt_containing(selections_range)
+                    else {
+                        return;
+                    };
+                    let buffer_snapshot = excerpt.buffer();
+                    let mut selected_larger_range = false;
+                    let mut new_selections = Vec::with_capacity(old_selections.len());
+                    for (selection, ranges) in old_selections.iter().zip(selection_ranges) {
+                        let old_range = selection.range();
+                        let mut new_range = old_range.clone();
+                        // The ranges go from the innermost to the outermost, pick the first one enclosing the selection.
+                        for range in ranges {
+                            let range = range.to_offset(buffer_snapshot);
+                            let range = BufferOffset(range.start)..BufferOffset(range.end);
+                            if !excerpt.contains_buffer_range(range.clone()) {
+                                break;
+                            }
+                            let range = excerpt.map_range_from_buffer(range);
+                            if range.start <= old_range.start
+                                && old_range.end <= range.end
+                                && range != old_range
+                            {
+                                new_range = range;
+                                break;
+                            }
+                        }
+                        selected_larger_range |= new_range != old_range;
+                        new_selections.push(Selection {
+                            id: selection.id,
+                            start: new_range.start,
+                            end: new_range.end,
+                            goal: SelectionGoal::None,
+                            reversed: selection.reversed,
+                        });
+                    }
+
+                    if selected_larger_range {
+                        editor.push_larger_syntax_node_selections(
+                            old_selections,
+                            new_selections,
+                            window,
+                            cx,
+                        );
+                    }
+                })
+                .ok();
+        });
+        true
+    }
+}
+
+fn selection_ranges_mode(buffer: &Entity<Buffer>, cx: &App) -> SelectionRanges {
+    let buffer = buffer.read(cx);
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .selection_ranges
+}
@@ -0,0 +1,286 @@
+//! Highlights from LSP semantic tokens.
+//! Depending on the `semantic_tokens` language setting, tokens are either layered over
+//! the tree-sitter highlights, or replace them entirely.
+
+use std::ops::Range;
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{
+    App, Context, Entity, FontStyle, FontWeight, HighlightStyle, StrikethroughStyle, Task,
+    UnderlineStyle, px,
+};
+use itertools::Itertools as _;
+use language::{
+    Buffer,
+    language_settings::{SemanticTokens, language_settings},
+};
+use lsp::LanguageServerId;
+use multi_buffer::Anchor;
+use theme::SyntaxTheme;
+use ui::ActiveTheme as _;
+
+use crate::{Editor, FETCH_COLORS_DEBOUNCE_TIMEOUT};
+
+struct SemanticTokenHighlight;
+
+impl Editor {
+    pub(super) fn refresh_semantic_tokens(
+        &mut self,
+        buffer_id: Option<text::BufferId>,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_full() || self.ignore_lsp_data() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let visible_buffers = self
+            .visible_excerpts(true, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                let editor_buffer_id = editor_buffer.read(cx).remote_id();
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer_id)
+                    && self.registered_buffers.contains_key(&editor_buffer_id)
+                    && semantic_tokens_mode(editor_buffer, cx) != SemanticTokens::Off
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let all_tokens_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let known_cache_version = self
+                        .semantic_tokens
+                        .get(&buffer_id)
+                        .and_then(|tokens| tokens.cache_version);
+                    let tokens_task = lsp_store.semantic_tokens(known_cache_version, buffer, cx)?;
+                    Some(async move { (buffer_id, tokens_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if all_tokens_task.is_empty() {
+            self.refresh_semantic_tokens_task = Task::ready(());
+            return;
+        }
+
+        self.refresh_semantic_tokens_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_COLORS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_tokens = join_all(all_tokens_task).await;
+            editor
+                .update(cx, |editor, cx| {
+                    let mut updated = false;
+                    for (buffer_id, tokens) in all_tokens {
+                        match tokens {
+                            // The buffer changed while the tokens were fetched, keep the current ones until the next refresh.
+                            Ok(tokens) if tokens.cache_version.is_none() => {}
+                            Ok(tokens) => {
+                                editor.semantic_tokens.insert(buffer_id, tokens);
+                                updated = true;
+                            }
+                            Err(e) => log::error!("Failed to retrieve semantic tokens: {e:#}"),
+                        }
+                    }
+                    if updated {
+                        editor.highlight_semantic_tokens(cx);
+                    }
+                })
+                .ok();
+        });
+    }
+
+    /// Converts the fetched semantic tokens into text highlights, using the current theme and language settings.
+    pub(super) fn highlight_semantic_tokens(&mut self, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer().read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let syntax = cx.theme().syntax().clone();
+        let base_style = HighlightStyle {
+            color: Some(cx.theme().colors().editor_foreground),
+            font_style: Some(FontStyle::Normal),
+            font_weight: Some(FontWeight::NORMAL),
+            ..HighlightStyle::default()
+        };
+
+        // Highlights with greater keys take precedence, so the base style of the full mode uses the lowest one.
+        let mut styles = vec![base_style];
+        let mut ranges_by_style = HashMap::<usize, Vec<Range<Anchor>>>::default();
+        let mut removed_buffers = Vec::new();
+        for (buffer_id, buffer_tokens) in &self.semantic_tokens {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                removed_buffers.push(*buffer_id);
+                continue;
+            };
+            let mode = semantic_tokens_mode(&buffer, cx);
+            if mode == SemanticTokens::Off {
+                removed_buffers.push(*buffer_id);
+                continue;
+            }
+            if buffer_tokens
+                .servers
+                .values()
+                .all(|server_tokens| server_tokens.tokens.is_empty())
+            {
+                continue;
+            }
+
+            let mut token_styles =
+                HashMap::<(LanguageServerId, u32, u32), Option<usize>>::default();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                if mode == SemanticTokens::Full
+                    && let Some(range) = multi_buffer_snapshot
+                        .anchor_range_in_excerpt(excerpt_id, excerpt_range.context)
+                {
+                    ranges_by_style.entry(0).or_default().push(range);
+                }
+                for (server_id, server_tokens) in &buffer_tokens.servers {
+                    for token in server_tokens.tokens.iter() {
+                        let style_ix = *token_styles
+                            .entry((*server_id, token.token_type, token.token_modifiers))
+                            .or_insert_with(|| {
+                                let style = semantic_token_style(
+                                    &syntax,
+                                    server_tokens.token_type(token)?,
+                                    server_tokens.token_modifiers(token),
+                                )?;
+                                Some(
+                                    styles
+                                        .iter()
+                                        .position(|existing| *existing == style)
+                                        .unwrap_or_else(|| {
+                                            styles.push(style);
+                                            styles.len() - 1
+                                        }),
+                                )
+                            });
+                        let Some(style_ix) = style_ix else {
+                            continue;
+                        };
+                        if let Some(range) = multi_buffer_snapshot
+                            .anchor_range_in_excerpt(excerpt_id, token.range.clone())
+                        {
+                            ranges_by_style.entry(style_ix).or_default().push(range);
+                        }
+                    }
+                }
+            }
+        }
+        for buffer_id in removed_buffers {
+            self.semantic_tokens.remove(&buffer_id);
+        }
+
+        self.clear_highlights::<SemanticTokenHighlight>(cx);
+        for (style_ix, mut ranges) in ranges_by_style {
+            ranges.sort_by(|a, b| a.start.cmp(&b.start, &multi_buffer_snapshot));
+            self.highlight_text_key::<SemanticTokenHighlight>(
+                style_ix,
+                ranges,
+                styles[style_ix],
+                false,
+                cx,
+            );
+        }
+    }
+}
+
+fn semantic_tokens_mode(buffer: &Entity<Buffer>, cx: &App) -> SemanticTokens {
+    let buffer = buffer.read(cx);
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .semantic_tokens
+}
+
+/// Returns the style of a token, based on the syntax theme captures closest to its type, and on its modifiers.
+fn semantic_token_style<'a>(
+    syntax: &SyntaxTheme,
+    token_type: &str,
+    token_modifiers: impl Iterator<Item = &'a str>,
+) -> Option<HighlightStyle> {
+    let capture = match token_type {
+        "class" | "struct" | "interface" | "typeParameter" => "type",
+        "enumMember" => "variant",
+        "parameter" => "variable.parameter",
+        "event" => "property",
+        "method" => "function.method",
+        "macro" => "function.special",
+        "modifier" => "keyword",
+        "regexp" => "string.regex",
+        "decorator" => "attribute",
+        other => other,
+    };
+    let mut style = syntax_style(syntax, capture).unwrap_or_default();
+    for modifier in token_modifiers {
+        match modifier {
+            "deprecated" => {
+                style.strikethrough = Some(StrikethroughStyle {
+                    thickness: px(1.),
+                    color: None,
+                })
+            }
+            "mutable" => {
+                style.underline = Some(UnderlineStyle {
+                    thickness: px(1.),
+                    color: None,
+                    wavy: false,
+                })
+            }
+            _ => {}
+        }
+    }
+    (style != HighlightStyle::default()).then_some(style)
+}
+
+/// Looks up the style of a capture name, falling back to its less specific parents, e.g. `function.method` to `function`.
+fn syntax_style(syntax: &SyntaxTheme, mut capture: &str) -> Option<HighlightStyle> {
+    loop {
+        if syntax.highlight_id(capture).is_some() {
+            return Some(syntax.get(capture));
+        }
+        capture = capture.rsplit_once('.')?.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::hsla;
+
+    use super::*;
+
+    #[test]
+    fn test_semantic_token_style() {
+        let function_color = hsla(0.1, 0.5, 0.5, 1.);
+        let type_color = hsla(0.3, 0.5, 0.5, 1.);
+        let syntax = SyntaxTheme::new_test([("function", function_color), ("type", type_color)]);
+
+        assert_eq!(
+            semantic_token_style(&syntax, "method", [].into_iter()).and_then(|style| style.color),
+            Some(function_color),
+            "methods should fall back to the function style"
+        );
+        assert_eq!(
+            semantic_token_style(&syntax, "struct", [].into_iter()).and_then(|style| style.color),
+            Some(type_color),
+        );
+        assert_eq!(
+            semantic_token_style(&syntax, "comment", [].into_iter()),
+            None,
+            "tokens without a theme style should not be highlighted"
+        );
+
+        let deprecated = semantic_token_style(&syntax, "comment", ["deprecated"].into_iter())
+            .expect("deprecated tokens should be highlighted");
+        assert!(deprecated.strikethrough.is_some());
+        assert_eq!(deprecated.color, None);
+    }
+}
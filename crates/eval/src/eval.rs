@@ -21,7 +21,10 @@ use gpui::http_client::read_proxy_from_env;
 use gpui::{App, AppContext, Application, AsyncApp, Entity, UpdateGlobal};
 use gpui_tokio::Tokio;
 use language::LanguageRegistry;
-use language_model::{ConfiguredModel, LanguageModel, LanguageModelRegistry, SelectedModel};
+use language_model::{
+    CachingLanguageModel, ConfiguredModel, LanguageModel, LanguageModelRegistry, ResponseCacheMode,
+    SelectedModel,
+};
 use node_runtime::{NodeBinaryOptions, NodeRuntime};
 use project::project_settings::ProjectSettings;
 use prompt_store::PromptBuilder;
@@ -60,6 +63,13 @@ struct Args {
     /// Maximum number of examples to run concurrently.
     #[arg(long, default_value = "4")]
     concurrency: usize,
+    /// Directory in which to cache model responses, keyed on the normalized request.
+    /// When unset, no responses are cached.
+    #[arg(long, value_name = "DIR")]
+    model_cache: Option<PathBuf>,
+    /// How to use the model response cache: record, replay-only or passthrough
+    #[arg(long, default_value = "record", requires = "model_cache")]
+    model_cache_mode: ResponseCacheMode,
     /// Output current environment variables as JSON to stdout
     #[arg(long, hide = true)]
     printenv: bool,
@@ -144,8 +154,17 @@ fn main() {
         cx.spawn(async move |cx| {
             future::join_all(tasks).await;
             let judge_model = cx.update(|cx| {
-                let agent_model = load_model(&args.model, cx).unwrap();
-                let judge_model = load_model(&args.judge_model, cx).unwrap();
+                let mut agent_model = load_model(&args.model, cx).unwrap();
+                let mut judge_model = load_model(&args.judge_model, cx).unwrap();
+                if let Some(cache_dir) = &args.model_cache {
+                    for configured_model in [&mut agent_model, &mut judge_model] {
+                        configured_model.model = CachingLanguageModel::new(
+                            configured_model.model.clone(),
+                            cache_dir.clone(),
+                            args.model_cache_mode,
+                        );
+                    }
+                }
                 LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
                     registry.set_default_model(Some(agent_model.clone()), cx);
                 });
@@ -331,6 +331,14 @@ impl ExampleContext {
                     ThreadEvent::Retry(status) => {
                         println!("{log_prefix} Got retry: {status:?}");
                     }
+                    ThreadEvent::ModelFallback(fallback) => {
+                        println!("{log_prefix} Fell back to another model: {fallback:?}");
+                    }
+                    ThreadEvent::RateLimited(status) => {
+                        if status.is_limited() {
+                            println!("{log_prefix} Rate limited: {status:?}");
+                        }
+                    }
                     ThreadEvent::Stop(stop_reason) => match stop_reason {
                         acp::StopReason::EndTurn => {}
                         acp::StopReason::MaxTokens => {
@@ -627,6 +627,11 @@ impl agent::TerminalHandle for EvalTerminalHandle {
             .read_with(cx, |term, cx| term.current_output(cx))
     }
 
+    fn command_result(&self, cx: &AsyncApp) -> Result<Option<acp_thread::CommandResult>> {
+        self.terminal
+            .read_with(cx, |term, cx| term.command_result(cx))
+    }
+
     fn kill(&self, cx: &AsyncApp) -> Result<()> {
         cx.update(|cx| {
             self.terminal.update(cx, |terminal, cx| {
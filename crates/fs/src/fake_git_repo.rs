@@ -6,8 +6,11 @@ use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, BisectCommand, BisectStatus, Branch, CommitDetails, CommitOperation,
+        CommitOptions, CommitSignature, CommitSummary, ConflictTexts, FetchOptions, GitRepository,
+        GitRepositoryCheckpoint, LogEntry, LogFilter, PushOptions, RebaseAction, RebaseCommand,
+        RebaseProgress, RebaseTodoEntry, ReleaseCommits, Remote, RepoPath, ResetMode, Tag,
+        Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -43,6 +46,8 @@ pub struct FakeGitRepository {
 pub struct FakeGitRepositoryState {
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
+    /// The base, ours and theirs versions of conflicted files.
+    pub conflict_texts: HashMap<RepoPath, ConflictTexts>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     // everything in commit contents is in oids
@@ -55,6 +60,65 @@ pub struct FakeGitRepositoryState {
     pub remotes: HashMap<String, String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// The todo list returned for any interactive rebase.
+    pub rebase_todo: Vec<RebaseTodoEntry>,
+    /// The interactive rebases that were started, with their base and todo list.
+    pub rebases: Vec<(String, Vec<RebaseTodoEntry>)>,
+    pub rebase_progress: Option<RebaseProgress>,
+    /// The commits returned by `log`, newest first.
+    pub log: Vec<LogEntry>,
+    /// The commits that were checked out, cherry-picked or reverted.
+    pub commit_operations: Vec<(CommitOperation, String)>,
+    /// Tag names and the commits they point at.
+    pub tags: HashMap<String, String>,
+    /// Values read by `config_value`.
+    pub config: HashMap<String, String>,
+    /// The options of every commit made, in order.
+    pub commits: Vec<CommitOptions>,
+    /// The message of every commit made, in order.
+    pub commit_messages: Vec<String>,
+    /// The bisect in progress, run over the commits in `log`.
+    pub bisect: Option<FakeBisect>,
+    /// The submodules returned by `submodules`.
+    pub submodules: Vec<Submodule>,
+    /// The submodule commands that were run, with the paths they were run on.
+    pub submodule_commands: Vec<(SubmoduleCommand, Vec<RepoPath>)>,
+}
+
+/// A bisect over a linear history, narrowing down the commits that could be the first bad one.
+#[derive(Debug, Clone, Default)]
+pub struct FakeBisect {
+    /// The commits that could be the first bad commit, newest first. The first one is known to be bad.
+    pub candidates: Vec<SharedString>,
+    pub skipped: HashSet<SharedString>,
+    pub testing: Option<SharedString>,
+}
+
+impl FakeBisect {
+    fn next_step(&mut self) -> BisectStatus {
+        if self.candidates.len() <= 1 {
+            self.testing = None;
+            return BisectStatus::FirstBad {
+                commit: self.candidates.first().cloned().unwrap_or_default(),
+            };
+        }
+        let untested = self.candidates[1..]
+            .iter()
+            .filter(|commit| !self.skipped.contains(*commit))
+            .cloned()
+            .collect::<Vec<_>>();
+        let Some(commit) = untested.get(untested.len() / 2).cloned() else {
+            self.testing = None;
+            return BisectStatus::Inconclusive {
+                candidates: self.candidates.clone(),
+            };
+        };
+        self.testing = Some(commit.clone());
+        BisectStatus::Testing {
+            commit,
+            remaining: (untested.len() - 1) / 2,
+        }
+    }
 }
 
 impl FakeGitRepositoryState {
@@ -64,6 +128,7 @@ impl FakeGitRepositoryState {
             head_contents: Default::default(),
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
+            conflict_texts: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
@@ -72,6 +137,18 @@ impl FakeGitRepositoryState {
             merge_base_contents: Default::default(),
             oids: Default::default(),
             remotes: HashMap::default(),
+            rebase_todo: Default::default(),
+            rebases: Default::default(),
+            rebase_progress: None,
+            log: Default::default(),
+            commit_operations: Default::default(),
+            tags: Default::default(),
+            config: Default::default(),
+            commits: Default::default(),
+            commit_messages: Default::default(),
+            bisect: None,
+            submodules: Default::default(),
+            submodule_commands: Default::default(),
         }
     }
 }
@@ -109,6 +186,27 @@ impl GitRepository for FakeGitRepository {
             .boxed()
     }
 
+    fn load_index_file_size(&self, path: RepoPath) -> BoxFuture<'_, Option<u64>> {
+        let fut = self.with_state_async(false, move |state| {
+            state
+                .index_contents
+                .get(&path)
+                .context("not present in index")
+                .map(|content| content.len() as u64)
+        });
+        self.executor.spawn(async move { fut.await.ok() }).boxed()
+    }
+
+    fn load_conflict_texts(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictTexts>> {
+        self.with_state_async(false, move |state| {
+            state
+                .conflict_texts
+                .get(&path)
+                .cloned()
+                .with_context(|| format!("{} is not conflicted", path.as_unix_str()))
+        })
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let fut = self.with_state_async(false, move |state| {
             state
@@ -134,7 +232,7 @@ impl GitRepository for FakeGitRepository {
         _commit: String,
         _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<git::repository::CommitDiff>> {
-        unimplemented!()
+        async { Ok(git::repository::CommitDiff { files: Vec::new() }) }.boxed()
     }
 
     fn set_index_text(
@@ -216,6 +314,10 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn commit_signature(&self, _commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>> {
+        async { Ok(None) }.boxed()
+    }
+
     fn reset(
         &self,
         _commit: String,
@@ -495,6 +597,243 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        self.with_state_async(false, move |state| {
+            let author = filter.author.map(|author| author.to_lowercase());
+            let message = filter.message.map(|message| message.to_lowercase());
+            Ok(state
+                .log
+                .iter()
+                .filter(|entry| {
+                    author.as_ref().is_none_or(|author| {
+                        entry.author_name.to_lowercase().contains(author)
+                            || entry.author_email.to_lowercase().contains(author)
+                    }) && message
+                        .as_ref()
+                        .is_none_or(|message| entry.subject.to_lowercase().contains(message))
+                        && filter
+                            .since
+                            .is_none_or(|since| entry.commit_timestamp >= since)
+                        && filter
+                            .until
+                            .is_none_or(|until| entry.commit_timestamp <= until)
+                })
+                .skip(skip)
+                .take(limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn compare_commits(
+        &self,
+        _base: String,
+        _head: String,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::CommitDiff>> {
+        unimplemented!()
+    }
+
+    fn commit_operation(
+        &self,
+        operation: CommitOperation,
+        commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.commit_operations.push((operation, commit));
+            Ok(())
+        })
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        _message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.contains_key(&name) {
+                bail!("tag '{name}' already exists");
+            }
+            state.tags.insert(name, commit);
+            Ok(())
+        })
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| {
+            let mut tags = state
+                .tags
+                .iter()
+                .map(|(name, commit)| {
+                    let entry = state.log.iter().find(|entry| entry.sha == *commit);
+                    Tag {
+                        name: name.clone().into(),
+                        sha: commit.clone().into(),
+                        subject: entry.map(|entry| entry.subject.clone()).unwrap_or_default(),
+                        annotated: false,
+                        timestamp: entry.map_or(0, |entry| entry.commit_timestamp),
+                    }
+                })
+                .collect::<Vec<_>>();
+            tags.sort_by(|a, b| {
+                b.timestamp
+                    .cmp(&a.timestamp)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            Ok(tags)
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state
+                .tags
+                .remove(&name)
+                .with_context(|| format!("tag '{name}' not found"))?;
+            Ok(())
+        })
+    }
+
+    fn release_commits(&self, head: String) -> BoxFuture<'_, Result<ReleaseCommits>> {
+        self.with_state_async(false, move |state| {
+            let start = if head == "HEAD" {
+                0
+            } else {
+                state
+                    .log
+                    .iter()
+                    .position(|entry| entry.sha.starts_with(head.as_str()))
+                    .context("revision not found")?
+            };
+            let mut release = ReleaseCommits::default();
+            for (ix, entry) in state.log.iter().enumerate().skip(start) {
+                if ix > start
+                    && let Some((name, _)) = state
+                        .tags
+                        .iter()
+                        .find(|(_, commit)| **commit == entry.sha.as_ref())
+                {
+                    release.previous_tag = Some(name.clone().into());
+                    break;
+                }
+                if entry.parents.len() <= 1 {
+                    release.commits.push(CommitSummary {
+                        sha: entry.sha.clone(),
+                        subject: entry.subject.clone(),
+                        commit_timestamp: entry.commit_timestamp,
+                        author_name: entry.author_name.clone(),
+                        has_parent: !entry.parents.is_empty(),
+                    });
+                }
+            }
+            Ok(release)
+        })
+    }
+
+    fn bisect(
+        &self,
+        command: BisectCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>> {
+        self.with_state_async(true, move |state| {
+            let position = |log: &[LogEntry], rev: &str| {
+                if rev == "HEAD" {
+                    return (!log.is_empty()).then_some(0);
+                }
+                log.iter().position(|entry| entry.sha.starts_with(rev))
+            };
+            if let BisectCommand::Start { bad, good } = &command {
+                let bad_ix =
+                    position(&state.log, bad.as_str()).context("bad revision not found")?;
+                let good_ix = good
+                    .iter()
+                    .map(|good| {
+                        position(&state.log, good.as_str()).context("good revision not found")
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .min()
+                    .context("no good revision")?;
+                if good_ix <= bad_ix {
+                    bail!("some good revisions are not ancestors of the bad revision");
+                }
+                let mut bisect = FakeBisect {
+                    candidates: state.log[bad_ix..good_ix]
+                        .iter()
+                        .map(|entry| entry.sha.clone())
+                        .collect(),
+                    ..Default::default()
+                };
+                let status = bisect.next_step();
+                state.bisect = Some(bisect);
+                return Ok(status);
+            }
+            if command == BisectCommand::Reset {
+                state.bisect = None;
+                return Ok(BisectStatus::Inactive);
+            }
+
+            let bisect = state.bisect.as_mut().context("not bisecting")?;
+            let testing = bisect.testing.clone().context("nothing left to test")?;
+            let ix = bisect
+                .candidates
+                .iter()
+                .position(|commit| *commit == testing)
+                .context("tested commit is not a candidate")?;
+            match command {
+                BisectCommand::Good => bisect.candidates.truncate(ix),
+                BisectCommand::Bad => {
+                    bisect.candidates.drain(..ix);
+                }
+                BisectCommand::Skip => {
+                    bisect.skipped.insert(testing);
+                }
+                BisectCommand::Start { .. } | BisectCommand::Reset => unreachable!(),
+            }
+            Ok(bisect.next_step())
+        })
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        self.with_state_async(false, |state| Ok(state.submodules.clone()))
+    }
+
+    fn submodule_command(
+        &self,
+        command: SubmoduleCommand,
+        paths: Vec<RepoPath>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        self.with_state_async(true, move |state| {
+            if command == SubmoduleCommand::Update {
+                for submodule in &mut state.submodules {
+                    if paths.is_empty() || paths.contains(&submodule.path) {
+                        submodule.status = SubmoduleStatus::Current;
+                    }
+                }
+            }
+            state.submodule_commands.push((command, paths));
+            Ok(RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -577,13 +916,17 @@ impl GitRepository for FakeGitRepository {
 
     fn commit(
         &self,
-        _message: gpui::SharedString,
+        message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
-        _options: CommitOptions,
+        options: CommitOptions,
         _askpass: AskPassDelegate,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
-        async { Ok(()) }.boxed()
+        self.with_state_async(false, move |state| {
+            state.commits.push(options);
+            state.commit_messages.push(message.to_string());
+            Ok(())
+        })
     }
 
     fn run_hook(
@@ -650,6 +993,10 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn config_value(&self, key: String) -> BoxFuture<'_, Result<Option<String>>> {
+        self.with_state_async(false, move |state| Ok(state.config.get(&key).cloned()))
+    }
+
     fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<gpui::SharedString>>> {
         future::ready(Ok(Vec::new())).boxed()
     }
@@ -737,6 +1084,64 @@ impl GitRepository for FakeGitRepository {
             Ok(())
         })
     }
+
+    fn rebase_todo(&self, _base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        self.with_state_async(false, |state| Ok(state.rebase_todo.clone()))
+    }
+
+    fn interactive_rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            // Stop at the first commit marked for editing, like git does.
+            state.rebase_progress = todo
+                .iter()
+                .position(|entry| entry.action == RebaseAction::Edit)
+                .map(|ix| RebaseProgress {
+                    head_name: state.current_branch_name.clone().map(Into::into),
+                    onto: base.clone().into(),
+                    done: ix + 1,
+                    total: todo.len(),
+                    stopped_sha: Some(todo[ix].sha.clone()),
+                });
+            state.rebases.push((base, todo));
+            Ok(())
+        })
+    }
+
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let progress = state
+                .rebase_progress
+                .take()
+                .context("no rebase in progress")?;
+            let todo = &state.rebases.last().context("no rebase in progress")?.1;
+            if command != RebaseCommand::Abort {
+                state.rebase_progress = todo
+                    .iter()
+                    .enumerate()
+                    .skip(progress.done)
+                    .find(|(_, entry)| entry.action == RebaseAction::Edit)
+                    .map(|(ix, entry)| RebaseProgress {
+                        done: ix + 1,
+                        stopped_sha: Some(entry.sha.clone()),
+                        ..progress
+                    });
+            }
+            Ok(())
+        })
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        self.with_state_async(false, |state| Ok(state.rebase_progress.clone()))
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 pub mod blame;
 pub mod commit;
+pub mod commit_checks;
 mod hosting_provider;
 mod remote;
 pub mod repository;
@@ -46,6 +47,8 @@ actions!(
         Blame,
         /// Shows the git history for the current file.
         FileHistory,
+        /// Opens the commit graph of the active repository.
+        ViewLog,
         /// Stages the current file.
         StageFile,
         /// Unstages the current file.
@@ -87,6 +90,8 @@ actions!(
         Amend,
         /// Enable the --signoff option.
         Signoff,
+        /// Enable the --gpg-sign option.
+        Sign,
         /// Cancels the current git operation.
         Cancel,
         /// Expands the commit message editor.
@@ -101,6 +106,28 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Continues a rebase that stopped for conflicts or to edit a commit.
+        RebaseContinue,
+        /// Skips the commit a rebase stopped at.
+        RebaseSkip,
+        /// Aborts the rebase in progress and restores the original branch.
+        RebaseAbort,
+        /// Opens the bisect assistant of the active repository.
+        Bisect,
+        /// Initializes the submodules of the active repository.
+        InitSubmodules,
+        /// Checks out the commits recorded for the submodules of the active repository.
+        UpdateSubmodules,
+        /// Synchronizes the submodule URLs of the active repository with `.gitmodules`.
+        SyncSubmodules,
+        /// Opens the diff of the pull request for the current branch, with its review comments.
+        ReviewPullRequest,
+        /// Adds a review comment on the current line of the pull request being reviewed.
+        AddReviewComment,
+        /// Drafts release notes from the commits since the most recent tag.
+        DraftReleaseNotes,
+        /// Opens a three-way merge editor for the selected conflicted file.
+        OpenMergeEditor,
     ]
 );
 
@@ -116,6 +143,18 @@ pub struct RenameBranch {
     pub branch: Option<String>,
 }
 
+/// Opens the interactive rebase editor for the commits on the current branch.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = git)]
+#[serde(deny_unknown_fields)]
+pub struct InteractiveRebase {
+    /// The revision whose descendants are rebased.
+    ///
+    /// Default: the upstream of the current branch, or the default branch.
+    #[serde(default)]
+    pub base: Option<String>,
+}
+
 /// Restores a file to its last committed state, discarding local changes.
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
 #[action(namespace = git, deprecated_aliases = ["editor::RevertFile"])]
@@ -17,6 +17,61 @@ pub struct PullRequest {
     pub url: Url,
 }
 
+/// The side of a pull request diff that a review comment is attached to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReviewSide {
+    /// The base of the pull request, for comments on removed lines.
+    Base,
+    /// The head of the pull request, for comments on added or unchanged lines.
+    Head,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReviewComment {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: String,
+}
+
+/// A conversation about a line of a pull request diff.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReviewThread {
+    /// The ID of the thread, which replies are posted to.
+    pub id: String,
+    pub path: RepoPath,
+    /// The 1-based line the thread is attached to, or `None` if the thread is outdated.
+    pub line: Option<u32>,
+    pub side: ReviewSide,
+    pub comments: Vec<ReviewComment>,
+}
+
+/// An open pull request, with its diff and review threads.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PullRequestReview {
+    pub pull_request: PullRequest,
+    pub title: String,
+    /// The name of the branch that the pull request is merged into.
+    pub base_branch: String,
+    /// The SHA of the commit at the head of the pull request.
+    pub head_sha: String,
+    /// The unified diff of the pull request.
+    pub diff: String,
+    pub threads: Vec<ReviewThread>,
+}
+
+/// A new review comment on a line of a pull request diff.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NewReviewComment {
+    pub path: RepoPath,
+    /// The 1-based line to comment on.
+    pub line: u32,
+    pub side: ReviewSide,
+    /// The SHA of the commit the comment is made on.
+    pub commit_sha: String,
+    pub body: String,
+}
+
 #[derive(Clone)]
 pub struct GitRemote {
     pub host: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
@@ -143,6 +198,59 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns whether this provider supports reviewing pull requests. The review methods below are
+    /// only called for providers that return `true`.
+    fn supports_pull_request_reviews(&self) -> bool {
+        false
+    }
+
+    /// Returns the open pull request for the given branch, if any.
+    async fn open_pull_request_for_branch(
+        &self,
+        _remote: &ParsedGitRemote,
+        _branch: &str,
+        _token: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
+
+    /// Fetches the diff and review threads of a pull request.
+    async fn pull_request_review(
+        &self,
+        _remote: &ParsedGitRemote,
+        _number: u32,
+        _token: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
+
+    /// Posts a reply to a review thread of a pull request.
+    async fn reply_to_review_thread(
+        &self,
+        _remote: &ParsedGitRemote,
+        _number: u32,
+        _thread_id: &str,
+        _body: &str,
+        _token: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
+
+    /// Posts a new review comment on a line of a pull request, starting a new thread.
+    async fn create_review_comment(
+        &self,
+        _remote: &ParsedGitRemote,
+        _number: u32,
+        _comment: NewReviewComment,
+        _token: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
@@ -229,7 +337,7 @@ impl GitHostingProviderRegistry {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ParsedGitRemote {
     pub owner: Arc<str>,
     pub repo: Arc<str>,
@@ -30,6 +30,7 @@ use thiserror::Error;
 use util::command::new_smol_command;
 use util::paths::PathStyle;
 use util::rel_path::RelPath;
+use util::shell::ShellKind;
 use util::{ResultExt, paths};
 use uuid::Uuid;
 
@@ -37,6 +38,9 @@ pub use askpass::{AskPassDelegate, AskPassResult, AskPassSession};
 
 pub const REMOTE_CANCELLED_BY_USER: &str = "Operation cancelled by user";
 
+/// The directory within the git directory that holds the todo list and messages of an interactive rebase.
+const INTERACTIVE_REBASE_DIR: &str = "zed-rebase";
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Branch {
     pub is_head: bool,
@@ -152,10 +156,13 @@ impl Upstream {
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct CommitOptions {
     pub amend: bool,
     pub signoff: bool,
+    /// Signs the commit with the format, key and program configured in git (`gpg.format`,
+    /// `user.signingKey`, `gpg.program`, ...). When unset, `commit.gpgSign` still applies.
+    pub sign: bool,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -222,6 +229,55 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature is good and made with a trusted key.
+    Verified,
+    /// The signature is bad, or made with an expired or revoked key.
+    Unverified,
+    /// The signature couldn't be checked against a trusted key, because the key is missing or
+    /// its validity is unknown.
+    UnknownKey,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    pub signer: Option<SharedString>,
+    pub key: Option<SharedString>,
+}
+
+impl CommitSignature {
+    /// Parses the `%G?`, `%GS` and `%GK` placeholders of `git log --format`.
+    pub fn parse(status: &str, signer: &str, key: &str) -> Option<Self> {
+        let status = match status.trim() {
+            "G" => SignatureStatus::Verified,
+            "U" | "E" => SignatureStatus::UnknownKey,
+            "B" | "X" | "Y" | "R" => SignatureStatus::Unverified,
+            _ => return None,
+        };
+        let non_empty = |field: &str| {
+            let field = field.trim();
+            (!field.is_empty()).then(|| SharedString::from(field.to_string()))
+        };
+        Some(Self {
+            status,
+            signer: non_empty(signer),
+            key: non_empty(key),
+        })
+    }
+}
+
+/// Parses a boolean git config value, as `git config --type=bool` would.
+pub fn parse_config_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        // A key without a value, like `[commit] gpgSign`, is true.
+        "" | "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        value => value.parse::<i64>().ok().map(|value| value != 0),
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct FileHistoryEntry {
     pub sha: SharedString,
@@ -238,6 +294,248 @@ pub struct FileHistory {
     pub path: RepoPath,
 }
 
+/// Narrows down the commits listed by [`GitRepository::log`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Only list commits that change this path.
+    pub path: Option<RepoPath>,
+    /// Only list commits whose author matches this pattern.
+    pub author: Option<String>,
+    /// Only list commits whose message matches this pattern, ignoring case.
+    pub message: Option<String>,
+    /// Only list commits committed at or after this Unix timestamp.
+    pub since: Option<i64>,
+    /// Only list commits committed at or before this Unix timestamp.
+    pub until: Option<i64>,
+}
+
+impl LogFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    /// The parents of the commit. When the log is filtered by path, these are the closest
+    /// ancestors that are part of the log.
+    pub parents: Vec<SharedString>,
+    /// The refs pointing at the commit, as decorated by git, e.g. `HEAD -> main` or `tag: v1.0`.
+    pub refs: Vec<SharedString>,
+    pub subject: SharedString,
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+}
+
+/// An operation applying a single commit to the working tree.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum CommitOperation {
+    /// Checks out the commit, detaching HEAD.
+    Checkout,
+    CherryPick,
+    Revert,
+}
+
+/// A step of `git bisect`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BisectCommand {
+    /// Starts bisecting between a bad revision and one or more good ones.
+    Start { bad: String, good: Vec<String> },
+    /// Marks the commit being tested as good.
+    Good,
+    /// Marks the commit being tested as bad.
+    Bad,
+    /// Marks the commit being tested as untestable.
+    Skip,
+    /// Ends the bisect, checking out the revision it started from.
+    Reset,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BisectStatus {
+    /// `commit` is checked out to be tested, and `remaining` revisions are left to test after it.
+    Testing {
+        commit: SharedString,
+        remaining: usize,
+    },
+    /// The bisect found the commit that introduced the change.
+    FirstBad { commit: SharedString },
+    /// Only skipped commits are left, and any of them could be the first bad commit.
+    Inconclusive { candidates: Vec<SharedString> },
+    /// No bisect is in progress.
+    Inactive,
+}
+
+impl BisectStatus {
+    /// Parses the output of `git bisect start`, `good`, `bad` and `skip`, run in the C locale.
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut lines = output.lines();
+        while let Some(line) = lines.next() {
+            if let Some(rest) = line.strip_prefix("Bisecting: ") {
+                let remaining = rest.split_whitespace().next()?.parse().ok()?;
+                let commit = lines
+                    .next()?
+                    .strip_prefix('[')?
+                    .split_once(']')?
+                    .0
+                    .to_string();
+                return Some(Self::Testing {
+                    commit: commit.into(),
+                    remaining,
+                });
+            } else if let Some(commit) = line.strip_suffix(" is the first bad commit") {
+                return Some(Self::FirstBad {
+                    commit: commit.trim().to_string().into(),
+                });
+            } else if line.starts_with("There are only 'skip'ped commits left to test.") {
+                let candidates = lines
+                    .skip_while(|line| !line.starts_with("The first bad commit could be any of"))
+                    .skip(1)
+                    .take_while(|line| {
+                        !line.is_empty() && line.chars().all(|c| c.is_ascii_hexdigit())
+                    })
+                    .map(|line| line.to_string().into())
+                    .collect();
+                return Some(Self::Inconclusive { candidates });
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points at.
+    pub sha: SharedString,
+    /// The subject of the tag's message, or of the commit's message for lightweight tags.
+    pub subject: SharedString,
+    /// Whether the tag is annotated, rather than lightweight.
+    pub annotated: bool,
+    /// The unix timestamp of the tag, or of the commit for lightweight tags.
+    pub timestamp: i64,
+}
+
+impl Tag {
+    /// The `git for-each-ref` format that [`Tag::parse_list`] parses.
+    pub const FORMAT: &str = "%(refname:strip=2)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(creatordate:unix)%00%(contents:subject)";
+
+    /// Parses the output of `git for-each-ref` with [`Tag::FORMAT`].
+    pub fn parse_list(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\0');
+                let name = fields.next()?;
+                let annotated = fields.next()? == "tag";
+                let object = fields.next()?;
+                let peeled = fields.next()?;
+                let timestamp = fields.next()?.parse().ok()?;
+                let subject = fields.next().unwrap_or_default();
+                Some(Self {
+                    name: name.to_string().into(),
+                    sha: if annotated { peeled } else { object }.to_string().into(),
+                    subject: subject.to_string().into(),
+                    annotated,
+                    timestamp,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The versions of a conflicted file that git recorded in the index while merging.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictTexts {
+    /// The common ancestor's version, or `None` when the file was added on both sides.
+    pub base: Option<String>,
+    /// Our version, or `None` when we deleted the file.
+    pub ours: Option<String>,
+    /// Their version, or `None` when they deleted the file.
+    pub theirs: Option<String>,
+}
+
+/// The commits that are part of a release.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReleaseCommits {
+    /// The most recent tag before the release, if any.
+    pub previous_tag: Option<SharedString>,
+    /// The commits since the previous tag, excluding merges, newest first.
+    pub commits: Vec<CommitSummary>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmoduleStatus {
+    /// The submodule is checked out at the commit recorded in the superproject.
+    Current,
+    /// The submodule hasn't been initialized, so it isn't checked out.
+    Uninitialized,
+    /// The submodule is checked out at a different commit than the one recorded in the superproject.
+    OutOfDate,
+    /// The submodule has merge conflicts in the superproject.
+    Conflicted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    /// The commit checked out in the submodule, or the recorded one if it isn't checked out.
+    pub sha: SharedString,
+    pub status: SubmoduleStatus,
+}
+
+impl Submodule {
+    /// Parses the output of `git submodule status`.
+    pub fn parse_status(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut chars = line.chars();
+                let status = match chars.next()? {
+                    ' ' => SubmoduleStatus::Current,
+                    '-' => SubmoduleStatus::Uninitialized,
+                    '+' => SubmoduleStatus::OutOfDate,
+                    'U' => SubmoduleStatus::Conflicted,
+                    _ => return None,
+                };
+                let (sha, rest) = chars.as_str().split_once(' ')?;
+                // Initialized submodules are followed by a description of their commit.
+                let path = match rest.rsplit_once(" (") {
+                    Some((path, _)) if rest.ends_with(')') => path,
+                    _ => rest,
+                };
+                Some(Self {
+                    path: RepoPath::new(path).ok()?,
+                    sha: sha.to_string().into(),
+                    status,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmoduleCommand {
+    /// Registers the submodules in the repository's config.
+    Init,
+    /// Checks out the commits recorded in the superproject, cloning submodules as needed.
+    Update,
+    /// Copies changed submodule URLs from `.gitmodules` to the repository's config.
+    Sync,
+}
+
+impl SubmoduleCommand {
+    pub fn args(&self) -> &'static [&'static str] {
+        match self {
+            SubmoduleCommand::Init => &["submodule", "init"],
+            SubmoduleCommand::Update => &["submodule", "update", "--init", "--recursive"],
+            SubmoduleCommand::Sync => &["submodule", "sync", "--recursive"],
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CommitDiff {
     pub files: Vec<CommitFile>,
@@ -270,6 +568,89 @@ pub enum ResetMode {
     Mixed,
 }
 
+/// What to do with a commit during an interactive rebase.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    #[default]
+    Pick,
+    /// Use the commit, but change its message.
+    Reword,
+    /// Use the commit, but stop so that it can be amended.
+    Edit,
+    /// Meld the commit into the previous one, combining their messages.
+    Squash,
+    /// Meld the commit into the previous one, keeping only the previous message.
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [Self; 6] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Edit,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    /// The command for this action in a rebase todo list.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Edit => "edit",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is combined with the one before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of a reworded commit.
+    pub message: Option<SharedString>,
+}
+
+/// The state of an interactive rebase that has stopped before completing.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseProgress {
+    /// The branch being rebased, or `None` if HEAD was detached when the rebase started.
+    pub head_name: Option<SharedString>,
+    pub onto: SharedString,
+    /// The number of todo entries that have been processed, including the one the rebase stopped at.
+    pub done: usize,
+    pub total: usize,
+    /// The commit the rebase stopped at, because of conflicts or to edit it.
+    pub stopped_sha: Option<SharedString>,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseCommand {
+    Continue,
+    Skip,
+    Abort,
+}
+
+impl RebaseCommand {
+    fn as_arg(&self) -> &'static str {
+        match self {
+            Self::Continue => "--continue",
+            Self::Skip => "--skip",
+            Self::Abort => "--abort",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum FetchOptions {
     All,
@@ -424,6 +805,16 @@ pub trait GitRepository: Send + Sync {
     ///
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
+
+    /// Returns the size in bytes of an entry in the repository's index, or None if the index has
+    /// no entry for the given path.
+    ///
+    /// Also returns `None` for symlinks.
+    fn load_index_file_size(&self, path: RepoPath) -> BoxFuture<'_, Option<u64>>;
+
+    /// Returns the base, ours and theirs versions of a conflicted file, from stages 1 to 3 of the index.
+    fn load_conflict_texts(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictTexts>>;
+
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
     fn set_index_text(
@@ -493,6 +884,9 @@ pub trait GitRepository: Send + Sync {
 
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
+    /// Verifies the signature of the given commit, returning `None` if it isn't signed.
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(
         &self,
@@ -508,6 +902,71 @@ pub trait GitRepository: Send + Sync {
         limit: Option<usize>,
     ) -> BoxFuture<'_, Result<FileHistory>>;
 
+    /// Lists the commits reachable from HEAD, branches, remote branches and tags, with children
+    /// before their parents.
+    fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>>;
+
+    /// Loads the changes between two commits.
+    fn compare_commits(
+        &self,
+        base: String,
+        head: String,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<CommitDiff>>;
+
+    fn commit_operation(
+        &self,
+        operation: CommitOperation,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates a tag pointing at the given commit. The tag is annotated if a message is given.
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the tags of the repository, most recent first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the commits since the most recent tag that's an ancestor of the given revision.
+    fn release_commits(&self, head: String) -> BoxFuture<'_, Result<ReleaseCommits>>;
+
+    fn bisect(
+        &self,
+        command: BisectCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>>;
+
+    /// Lists the submodules of the repository, without recursing into them.
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>>;
+
+    fn submodule_command(
+        &self,
+        command: SubmoduleCommand,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
     fn path(&self) -> PathBuf;
@@ -609,6 +1068,9 @@ pub trait GitRepository: Send + Sync {
 
     fn get_branch_remote(&self, branch: String) -> BoxFuture<'_, Result<Option<Remote>>>;
 
+    /// Reads a value from the repository's git config, including global and system config.
+    fn config_value(&self, key: String) -> BoxFuture<'_, Result<Option<String>>>;
+
     fn get_all_remotes(&self) -> BoxFuture<'_, Result<Vec<Remote>>>;
 
     fn remove_remote(&self, name: String) -> BoxFuture<'_, Result<()>>;
@@ -642,6 +1104,29 @@ pub trait GitRepository: Send + Sync {
     ) -> BoxFuture<'_, Result<String>>;
 
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>>;
+
+    /// Returns the todo list for an interactive rebase of the commits since `base`, oldest first.
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Rebases the commits since `base` following the given todo list.
+    ///
+    /// Stops early if an entry conflicts or is marked for editing, leaving the rebase in progress.
+    fn interactive_rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress.
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the progress of the interactive rebase in progress, if any.
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>>;
 }
 
 pub enum DiffType {
@@ -666,9 +1151,77 @@ pub struct RealGitRepository {
     pub system_git_binary_path: Option<PathBuf>,
     pub any_git_binary_path: PathBuf,
     any_git_binary_help_output: Arc<Mutex<Option<SharedString>>>,
+    submodules_cache: Arc<Mutex<Option<(SubmodulesFingerprint, Vec<Submodule>)>>>,
     executor: BackgroundExecutor,
 }
 
+/// The state that `git submodule status` reads, used to skip running it when nothing changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SubmodulesFingerprint {
+    head_sha: Option<String>,
+    file_metadata: Vec<Option<(std::time::SystemTime, u64)>>,
+    submodule_heads: Vec<Option<String>>,
+}
+
+impl SubmodulesFingerprint {
+    async fn new(
+        head_sha: Option<String>,
+        working_directory: &Path,
+        dot_git_paths: &[PathBuf],
+        submodules: &[Submodule],
+    ) -> Self {
+        let mut paths = vec![working_directory.join(".gitmodules")];
+        paths.extend(dot_git_paths.iter().cloned());
+        let mut submodule_heads = Vec::with_capacity(submodules.len());
+        for submodule in submodules {
+            let Some(git_dir) =
+                submodule_git_dir(&working_directory.join(submodule.path.as_std_path())).await
+            else {
+                submodule_heads.push(None);
+                continue;
+            };
+            let head = smol::fs::read_to_string(git_dir.join("HEAD")).await.ok();
+            // Commits on a checked out branch move the branch rather than `HEAD`.
+            let branch_head = match head.as_deref().and_then(|head| head.strip_prefix("ref:")) {
+                Some(head_ref) => {
+                    paths.push(git_dir.join("packed-refs"));
+                    smol::fs::read_to_string(git_dir.join(head_ref.trim()))
+                        .await
+                        .ok()
+                }
+                None => None,
+            };
+            submodule_heads.push(head.map(|head| head + &branch_head.unwrap_or_default()));
+        }
+        let mut file_metadata = Vec::with_capacity(paths.len());
+        for path in paths {
+            file_metadata.push(
+                smol::fs::metadata(&path)
+                    .await
+                    .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                    .ok(),
+            );
+        }
+        Self {
+            head_sha,
+            file_metadata,
+            submodule_heads,
+        }
+    }
+}
+
+/// Resolves the git directory of a checked out submodule, whose `.git` is usually a file pointing into
+/// the superproject's `.git/modules`.
+async fn submodule_git_dir(submodule_path: &Path) -> Option<PathBuf> {
+    let dot_git = submodule_path.join(".git");
+    if smol::fs::metadata(&dot_git).await.ok()?.is_dir() {
+        return Some(dot_git);
+    }
+    let contents = smol::fs::read_to_string(&dot_git).await.ok()?;
+    let git_dir = Path::new(contents.strip_prefix("gitdir:")?.trim());
+    Some(submodule_path.join(git_dir))
+}
+
 impl RealGitRepository {
     pub fn new(
         dotgit_path: &Path,
@@ -685,6 +1238,7 @@ impl RealGitRepository {
             any_git_binary_path,
             executor,
             any_git_binary_help_output: Arc::new(Mutex::new(None)),
+            submodules_cache: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -716,6 +1270,22 @@ impl RealGitRepository {
         *self.any_git_binary_help_output.lock() = Some(output.clone());
         output
     }
+
+    /// Git exits with an error when a rebase stops on a conflict, which leaves the rebase in progress
+    /// for the conflicts to be resolved. That's a stop rather than a failure.
+    async fn stopped_rebase_result(&self, result: Result<String>) -> Result<()> {
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => {
+                if self.rebase_progress().await?.is_some() {
+                    log::debug!("rebase stopped: {error:#}");
+                    Ok(())
+                } else {
+                    Err(error)
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -822,6 +1392,39 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<Option<CommitSignature>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                // Verifying the signature runs gpg or ssh-keygen, so it's only done on request.
+                let output = new_smol_command(git_binary_path)
+                    .current_dir(&working_directory)
+                    .args([
+                        "--no-optional-locks",
+                        "show",
+                        "--no-patch",
+                        "--format=%G?%x00%GS%x00%GK%x00",
+                        &commit,
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to verify the signature of {commit:?}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let output = std::str::from_utf8(&output.stdout)?;
+                let fields = output.split('\0').collect::<Vec<_>>();
+                if fields.len() != 4 {
+                    bail!("unexpected git-show output for {commit:?}: {output:?}")
+                }
+                Ok(CommitSignature::parse(fields[0], fields[1], fields[2]))
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
@@ -849,98 +1452,66 @@ impl GitRepository for RealGitRepository {
                 .context("starting git show process")?;
 
             let show_stdout = String::from_utf8_lossy(&show_output.stdout);
-            let changes = parse_git_diff_name_status(&show_stdout);
             let parent_sha = format!("{}^", commit);
+            load_changed_files(
+                &git_binary_path,
+                &working_directory,
+                parse_git_diff_name_status(&show_stdout),
+                &parent_sha,
+                &commit,
+            )
+            .await
+        })
+        .boxed()
+    }
 
-            let mut cat_file_process = util::command::new_smol_command(&git_binary_path)
+    fn compare_commits(
+        &self,
+        base: String,
+        head: String,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<CommitDiff>> {
+        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
+        else {
+            return future::ready(Err(anyhow!("no working directory"))).boxed();
+        };
+        let git_binary_path = self.any_git_binary_path.clone();
+        cx.background_spawn(async move {
+            let diff_output = util::command::new_smol_command(&git_binary_path)
                 .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
+                .args([
+                    "--no-optional-locks",
+                    "diff",
+                    "-z",
+                    "--no-renames",
+                    "--name-status",
+                ])
+                .arg(&base)
+                .arg(&head)
+                .stdin(Stdio::null())
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
-                .spawn()
-                .context("starting git cat-file process")?;
-
-            let mut files = Vec::<CommitFile>::new();
-            let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
-            let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
-            let mut info_line = String::new();
-            let mut newline = [b'\0'];
-            for (path, status_code) in changes {
-                // git-show outputs `/`-delimited paths even on Windows.
-                let Some(rel_path) = RelPath::unix(path).log_err() else {
-                    continue;
-                };
+                .output()
+                .await
+                .context("starting git diff process")?;
+            anyhow::ensure!(
+                diff_output.status.success(),
+                "Failed to compare {base} with {head}:\n{}",
+                String::from_utf8_lossy(&diff_output.stderr),
+            );
 
-                match status_code {
-                    StatusCode::Modified => {
-                        stdin.write_all(commit.as_bytes()).await?;
-                        stdin.write_all(b":").await?;
-                        stdin.write_all(path.as_bytes()).await?;
-                        stdin.write_all(b"\n").await?;
-                        stdin.write_all(parent_sha.as_bytes()).await?;
-                        stdin.write_all(b":").await?;
-                        stdin.write_all(path.as_bytes()).await?;
-                        stdin.write_all(b"\n").await?;
-                    }
-                    StatusCode::Added => {
-                        stdin.write_all(commit.as_bytes()).await?;
-                        stdin.write_all(b":").await?;
-                        stdin.write_all(path.as_bytes()).await?;
-                        stdin.write_all(b"\n").await?;
-                    }
-                    StatusCode::Deleted => {
-                        stdin.write_all(parent_sha.as_bytes()).await?;
-                        stdin.write_all(b":").await?;
-                        stdin.write_all(path.as_bytes()).await?;
-                        stdin.write_all(b"\n").await?;
-                    }
-                    _ => continue,
-                }
-                stdin.flush().await?;
-
-                info_line.clear();
-                stdout.read_line(&mut info_line).await?;
-
-                let len = info_line.trim_end().parse().with_context(|| {
-                    format!("invalid object size output from cat-file {info_line}")
-                })?;
-                let mut text = vec![0; len];
-                stdout.read_exact(&mut text).await?;
-                stdout.read_exact(&mut newline).await?;
-                let text = String::from_utf8_lossy(&text).to_string();
-
-                let mut old_text = None;
-                let mut new_text = None;
-                match status_code {
-                    StatusCode::Modified => {
-                        info_line.clear();
-                        stdout.read_line(&mut info_line).await?;
-                        let len = info_line.trim_end().parse().with_context(|| {
-                            format!("invalid object size output from cat-file {}", info_line)
-                        })?;
-                        let mut parent_text = vec![0; len];
-                        stdout.read_exact(&mut parent_text).await?;
-                        stdout.read_exact(&mut newline).await?;
-                        old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
-                        new_text = Some(text);
-                    }
-                    StatusCode::Added => new_text = Some(text),
-                    StatusCode::Deleted => old_text = Some(text),
-                    _ => continue,
-                }
-
-                files.push(CommitFile {
-                    path: RepoPath(Arc::from(rel_path)),
-                    old_text,
-                    new_text,
-                })
-            }
-
-            Ok(CommitDiff { files })
-        })
-        .boxed()
-    }
+            let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
+            load_changed_files(
+                &git_binary_path,
+                &working_directory,
+                parse_git_diff_name_status(&diff_stdout),
+                &base,
+                &head,
+            )
+            .await
+        })
+        .boxed()
+    }
 
     fn reset(
         &self,
@@ -1041,6 +1612,71 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_index_file_size(&self, path: RepoPath) -> BoxFuture<'_, Option<u64>> {
+        const GIT_MODE_SYMLINK: u32 = 0o120000;
+
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                fn logic(repo: &git2::Repository, path: &RepoPath) -> Result<Option<u64>> {
+                    let mut index = repo.index()?;
+                    index.read(false)?;
+
+                    const STAGE_NORMAL: i32 = 0;
+                    let path = path.as_std_path();
+                    if path.components().next().is_none() {
+                        return Ok(None);
+                    }
+                    let oid = match index.get_path(path, STAGE_NORMAL) {
+                        Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
+                        _ => return Ok(None),
+                    };
+
+                    // Only read the object's header, since binaries can be large.
+                    let (size, _) = repo.odb()?.read_header(oid)?;
+                    Ok(Some(size as u64))
+                }
+
+                match logic(&repo.lock(), &path) {
+                    Ok(value) => return value,
+                    Err(err) => log::error!("Error loading index file size: {:?}", err),
+                }
+                None
+            })
+            .boxed()
+    }
+
+    fn load_conflict_texts(&self, path: RepoPath) -> BoxFuture<'_, Result<ConflictTexts>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+                let load_stage = |stage: i32| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(path.as_std_path(), stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(
+                        String::from_utf8(content).context("conflicted file is not valid UTF-8")?,
+                    ))
+                };
+                let texts = ConflictTexts {
+                    base: load_stage(1)?,
+                    ours: load_stage(2)?,
+                    theirs: load_stage(3)?,
+                };
+                anyhow::ensure!(
+                    texts.ours.is_some() || texts.theirs.is_some(),
+                    "{} is not conflicted",
+                    path.as_unix_str()
+                );
+                Ok(texts)
+            })
+            .boxed()
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let repo = self.repository.clone();
         self.executor
@@ -1637,6 +2273,365 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let mut args = vec![
+                    "--no-optional-locks".to_string(),
+                    "log".to_string(),
+                    "--topo-order".to_string(),
+                    "--parents".to_string(),
+                    "--decorate-refs-exclude=refs/stash".to_string(),
+                    "--format=%H%x00%P%x00%D%x00%s%x00%ct%x00%an%x00%ae%x00".to_string(),
+                ];
+                if skip > 0 {
+                    args.push(format!("--skip={skip}"));
+                }
+                if let Some(limit) = limit {
+                    args.push(format!("--max-count={limit}"));
+                }
+                if let Some(author) = filter.author {
+                    args.push(format!("--author={author}"));
+                }
+                if let Some(message) = filter.message {
+                    args.push(format!("--grep={message}"));
+                    args.push("--regexp-ignore-case".to_string());
+                }
+                if let Some(since) = filter.since {
+                    args.push(format!("--since=@{since}"));
+                }
+                if let Some(until) = filter.until {
+                    args.push(format!("--until=@{until}"));
+                }
+                args.extend(["HEAD", "--branches", "--remotes", "--tags", "--"].map(String::from));
+                if let Some(path) = filter.path {
+                    args.push(path.as_unix_str().to_string());
+                }
+
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&args)
+                    .await?;
+                Ok(parse_log(&output))
+            })
+            .boxed()
+    }
+
+    fn commit_operation(
+        &self,
+        operation: CommitOperation,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        // Note: Do not spawn this command on the background thread, cherry-picks and reverts might need to sign commits.
+        async move {
+            let args = match operation {
+                CommitOperation::Checkout => ["checkout", "--detach", commit.as_str()],
+                CommitOperation::CherryPick => ["cherry-pick", "--no-edit", commit.as_str()],
+                CommitOperation::Revert => ["revert", "--no-edit", commit.as_str()],
+            };
+            GitBinary::new(git_binary_path, working_directory?, executor)
+                .envs(HashMap::clone(&env))
+                .run(args)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        // Note: Do not spawn this command on the background thread, annotated tags might need to be signed.
+        async move {
+            let mut args = vec!["tag".to_string()];
+            if let Some(message) = message {
+                args.push("--annotate".into());
+                args.push(format!("--message={message}"));
+            }
+            args.push(name);
+            args.push(commit);
+            GitBinary::new(git_binary_path, working_directory?, executor)
+                .envs(HashMap::clone(&env))
+                .run(&args)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run([
+                        "for-each-ref",
+                        "--sort=-creatordate",
+                        &format!("--format={}", Tag::FORMAT),
+                        "refs/tags",
+                    ])
+                    .await?;
+                Ok(Tag::parse_list(&output))
+            })
+            .boxed()
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(HashMap::clone(&env))
+                    .run(["tag", "--delete", name.as_str()])
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn release_commits(&self, head: String) -> BoxFuture<'_, Result<ReleaseCommits>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                // Run in the C locale so that the errors of `git describe` can be recognized.
+                let git = GitBinary::new(git_binary_path, working_directory?, executor).envs(
+                    HashMap::from_iter([
+                        ("LC_ALL".to_string(), "C".to_string()),
+                        ("LANGUAGE".to_string(), "C".to_string()),
+                    ]),
+                );
+                let head_and_parents = git.run(["rev-list", "--parents", "-n", "1", &head]).await?;
+                // Describing the parent excludes a tag pointing at `head` itself. A root commit
+                // has no parent, and so no earlier tag either.
+                let previous_tag = if head_and_parents.split_whitespace().nth(1).is_some() {
+                    match git
+                        .run(["describe", "--tags", "--abbrev=0", &format!("{head}^")])
+                        .await
+                    {
+                        Ok(tag) => Some(tag).filter(|tag| !tag.is_empty()),
+                        // Without an earlier tag, all commits are part of the release.
+                        Err(error)
+                            if error.downcast_ref::<GitBinaryCommandError>().is_some_and(
+                                |error| {
+                                    error.stderr.contains("No names found")
+                                        || error.stderr.contains("No tags can describe")
+                                },
+                            ) =>
+                        {
+                            None
+                        }
+                        Err(error) => return Err(error.context("failed to find the previous tag")),
+                    }
+                } else {
+                    None
+                };
+                let range = match &previous_tag {
+                    Some(tag) => format!("{tag}..{head}"),
+                    None => head,
+                };
+                let output = git
+                    .run([
+                        "log",
+                        "--no-merges",
+                        "--format=%H%x00%s%x00%at%x00%an%x00%P",
+                        range.as_str(),
+                        "--",
+                    ])
+                    .await?;
+                let commits = output
+                    .lines()
+                    .filter_map(|line| {
+                        let mut fields = line.split('\0');
+                        Some(CommitSummary {
+                            sha: fields.next()?.to_string().into(),
+                            subject: fields.next()?.to_string().into(),
+                            commit_timestamp: fields.next()?.parse().ok()?,
+                            author_name: fields.next()?.to_string().into(),
+                            has_parent: fields.next().is_some_and(|parents| !parents.is_empty()),
+                        })
+                    })
+                    .collect();
+                Ok(ReleaseCommits {
+                    previous_tag: previous_tag.map(Into::into),
+                    commits,
+                })
+            })
+            .boxed()
+    }
+
+    fn bisect(
+        &self,
+        command: BisectCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["bisect".to_string()];
+                match &command {
+                    BisectCommand::Start { bad, good } => {
+                        args.push("start".into());
+                        args.push(bad.clone());
+                        args.extend(good.iter().cloned());
+                    }
+                    BisectCommand::Good => args.push("good".into()),
+                    BisectCommand::Bad => args.push("bad".into()),
+                    BisectCommand::Skip => args.push("skip".into()),
+                    BisectCommand::Reset => args.push("reset".into()),
+                }
+                // The status is parsed from git's messages, which mustn't be translated.
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .envs(env.iter())
+                    .env("LC_ALL", "C")
+                    .env("LANGUAGE", "C")
+                    .args(&args)
+                    .output()
+                    .await?;
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if command == BisectCommand::Reset && output.status.success() {
+                    return Ok(BisectStatus::Inactive);
+                }
+                // Git exits with an error when only skipped commits are left, so parse the
+                // output before looking at the exit status.
+                if let Some(status) = BisectStatus::parse(&stdout) {
+                    return Ok(status);
+                }
+                anyhow::bail!(
+                    "git {} failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            })
+            .boxed()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let head_sha = self
+            .repository
+            .lock()
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+        // Submodule commits are recorded in the index, and initialized submodules live in `.git/modules`.
+        let dot_git_paths = [
+            self.path().join("index"),
+            self.main_repository_path().join("config"),
+            self.main_repository_path().join("modules"),
+        ];
+        let submodules_cache = self.submodules_cache.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                // Avoid spawning git for the common case of a repository without submodules.
+                if smol::fs::metadata(working_directory.join(".gitmodules"))
+                    .await
+                    .is_err()
+                {
+                    submodules_cache.lock().take();
+                    return Ok(Vec::new());
+                }
+                let cached = submodules_cache.lock().clone();
+                if let Some((cached_fingerprint, cached_submodules)) = cached {
+                    let fingerprint = SubmodulesFingerprint::new(
+                        head_sha.clone(),
+                        &working_directory,
+                        &dot_git_paths,
+                        &cached_submodules,
+                    )
+                    .await;
+                    if fingerprint == cached_fingerprint {
+                        return Ok(cached_submodules);
+                    }
+                }
+
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["submodule", "status"])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to list submodules: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let submodules = Submodule::parse_status(&String::from_utf8_lossy(&output.stdout));
+                let fingerprint = SubmodulesFingerprint::new(
+                    head_sha,
+                    &working_directory,
+                    &dot_git_paths,
+                    &submodules,
+                )
+                .await;
+                *submodules_cache.lock() = Some((fingerprint, submodules.clone()));
+                Ok(submodules)
+            })
+            .boxed()
+    }
+
+    fn submodule_command(
+        &self,
+        command: SubmoduleCommand,
+        paths: Vec<RepoPath>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        // Note: Do not spawn this command on the background thread, updating submodules might
+        // pop open the credential helper which we want to block on.
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't update submodules")?;
+            let mut command_builder = new_smol_command(git_binary_path);
+            command_builder
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(command.args())
+                .arg("--")
+                .args(paths.iter().map(|path| path.as_unix_str()))
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command_builder, &executor).await
+        }
+        .boxed()
+    }
+
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -1873,6 +2868,10 @@ impl GitRepository for RealGitRepository {
                 cmd.arg("--signoff");
             }
 
+            if options.sign {
+                cmd.arg("--gpg-sign");
+            }
+
             if let Some((name, email)) = name_and_email {
                 cmd.arg("--author").arg(&format!("{name} <{email}>"));
             }
@@ -2038,6 +3037,35 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn config_value(&self, key: String) -> BoxFuture<'_, Result<Option<String>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["config", "--get", &key])
+                    .output()
+                    .await?;
+                // `git config --get` exits with 1 when the key isn't set.
+                if output.status.code() == Some(1) {
+                    return Ok(None);
+                }
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to read git config {key:?}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(Some(
+                    String::from_utf8_lossy(&output.stdout)
+                        .trim_end_matches('\n')
+                        .to_string(),
+                ))
+            })
+            .boxed()
+    }
+
     fn get_all_remotes(&self) -> BoxFuture<'_, Result<Vec<Remote>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -2375,10 +3403,336 @@ impl GitRepository for RealGitRepository {
         }
         .boxed()
     }
-}
 
-fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
-    let mut args = vec![
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let range = format!("{base}..HEAD");
+                let output = git
+                    .run([
+                        "--no-optional-locks",
+                        "log",
+                        "--reverse",
+                        "--topo-order",
+                        "--no-merges",
+                        "--format=%H%x00%s",
+                        range.as_str(),
+                    ])
+                    .await?;
+                Ok(output
+                    .lines()
+                    .filter_map(|line| {
+                        let (sha, subject) = line.split_once('\0')?;
+                        Some(RebaseTodoEntry {
+                            action: RebaseAction::Pick,
+                            sha: sha.to_owned().into(),
+                            subject: subject.to_owned().into(),
+                            message: None,
+                        })
+                    })
+                    .collect())
+            })
+            .boxed()
+    }
+
+    fn interactive_rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        let rebase_dir = self.path().join(INTERACTIVE_REBASE_DIR);
+        // Note: Do not spawn this command on the background thread, rewriting commits might need to sign them.
+        async move {
+            let working_directory = working_directory?;
+            anyhow::ensure!(
+                self.rebase_progress().await?.is_none(),
+                "a rebase is already in progress"
+            );
+            smol::fs::remove_dir_all(&rebase_dir).await.ok();
+            smol::fs::create_dir_all(&rebase_dir).await?;
+
+            // Reworded commits are picked and then amended with their new message, so that
+            // git doesn't need to open an editor for them.
+            let mut todo_text = String::new();
+            for (ix, entry) in todo.iter().enumerate() {
+                let new_message = entry
+                    .message
+                    .as_ref()
+                    .filter(|_| entry.action == RebaseAction::Reword);
+                let action = if new_message.is_some() {
+                    RebaseAction::Pick
+                } else {
+                    entry.action
+                };
+                todo_text.push_str(&format!(
+                    "{} {} {}\n",
+                    action.as_str(),
+                    entry.sha,
+                    entry.subject
+                ));
+                if let Some(message) = new_message {
+                    let message_path = rebase_dir.join(format!("message-{ix}"));
+                    smol::fs::write(&message_path, message.as_bytes()).await?;
+                    todo_text.push_str(&format!(
+                        "exec git commit --amend --only --allow-empty --no-verify --cleanup=strip -F {}\n",
+                        quote_for_git_shell(&message_path)?
+                    ));
+                }
+            }
+            let todo_path = rebase_dir.join("git-rebase-todo");
+            smol::fs::write(&todo_path, todo_text).await?;
+
+            let mut env = non_interactive_rebase_env(&env);
+            env.insert(
+                "GIT_SEQUENCE_EDITOR".into(),
+                format!("cp {}", quote_for_git_shell(&todo_path)?),
+            );
+            let result = GitBinary::new(git_binary_path, working_directory, executor)
+                .envs(env)
+                .run(["rebase", "--interactive", base.as_str()])
+                .await;
+            self.stopped_rebase_result(result).await
+        }
+        .boxed()
+    }
+
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        // Note: Do not spawn this command on the background thread, rewriting commits might need to sign them.
+        async move {
+            let result = GitBinary::new(git_binary_path, working_directory?, executor)
+                .envs(non_interactive_rebase_env(&env))
+                .run(["rebase", command.as_arg()])
+                .await;
+            match command {
+                RebaseCommand::Continue | RebaseCommand::Skip => {
+                    self.stopped_rebase_result(result).await
+                }
+                RebaseCommand::Abort => result.map(drop),
+            }
+        }
+        .boxed()
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        let rebase_merge_dir = self.path().join("rebase-merge");
+        self.executor
+            .spawn(async move {
+                let Some(onto) = read_rebase_state(&rebase_merge_dir, "onto").await else {
+                    return Ok(None);
+                };
+                let done = read_rebase_state(&rebase_merge_dir, "msgnum")
+                    .await
+                    .and_then(|done| done.parse().ok())
+                    .unwrap_or(0);
+                let total = read_rebase_state(&rebase_merge_dir, "end")
+                    .await
+                    .and_then(|total| total.parse().ok())
+                    .unwrap_or(done);
+                let head_name = read_rebase_state(&rebase_merge_dir, "head-name")
+                    .await
+                    .filter(|head_name| head_name != "detached HEAD")
+                    .map(|head_name| {
+                        head_name
+                            .strip_prefix("refs/heads/")
+                            .unwrap_or(&head_name)
+                            .to_owned()
+                            .into()
+                    });
+                let stopped_sha = read_rebase_state(&rebase_merge_dir, "stopped-sha")
+                    .await
+                    .map(SharedString::from);
+                Ok(Some(RebaseProgress {
+                    head_name,
+                    onto: onto.into(),
+                    done,
+                    total,
+                    stopped_sha,
+                }))
+            })
+            .boxed()
+    }
+}
+
+/// Parses the output of `git log` with the format used by [`GitRepository::log`].
+fn parse_log(output: &str) -> Vec<LogEntry> {
+    let mut fields = output.split('\0');
+    let mut entries = Vec::new();
+    while let (
+        Some(sha),
+        Some(parents),
+        Some(refs),
+        Some(subject),
+        Some(timestamp),
+        Some(author_name),
+        Some(author_email),
+    ) = (
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+        fields.next(),
+    ) {
+        entries.push(LogEntry {
+            sha: sha.trim().to_string().into(),
+            parents: parents
+                .split_whitespace()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            refs: refs
+                .split(", ")
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_string().into())
+                .collect(),
+            subject: subject.to_string().into(),
+            commit_timestamp: timestamp.parse().unwrap_or(0),
+            author_name: author_name.to_string().into(),
+            author_email: author_email.to_string().into(),
+        });
+    }
+    entries
+}
+
+/// Loads the old and new contents of the given changed files, reading them at `old_rev` and
+/// `new_rev` respectively.
+async fn load_changed_files<'a>(
+    git_binary_path: &Path,
+    working_directory: &Path,
+    changes: impl Iterator<Item = (&'a str, StatusCode)>,
+    old_rev: &str,
+    new_rev: &str,
+) -> Result<CommitDiff> {
+    let mut cat_file_process = util::command::new_smol_command(git_binary_path)
+        .current_dir(working_directory)
+        .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("starting git cat-file process")?;
+
+    let mut files = Vec::<CommitFile>::new();
+    let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
+    let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
+    let mut info_line = String::new();
+    let mut newline = [b'\0'];
+    for (path, status_code) in changes {
+        // git outputs `/`-delimited paths even on Windows.
+        let Some(rel_path) = RelPath::unix(path).log_err() else {
+            continue;
+        };
+
+        match status_code {
+            StatusCode::Modified => {
+                stdin.write_all(new_rev.as_bytes()).await?;
+                stdin.write_all(b":").await?;
+                stdin.write_all(path.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+                stdin.write_all(old_rev.as_bytes()).await?;
+                stdin.write_all(b":").await?;
+                stdin.write_all(path.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+            }
+            StatusCode::Added => {
+                stdin.write_all(new_rev.as_bytes()).await?;
+                stdin.write_all(b":").await?;
+                stdin.write_all(path.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+            }
+            StatusCode::Deleted => {
+                stdin.write_all(old_rev.as_bytes()).await?;
+                stdin.write_all(b":").await?;
+                stdin.write_all(path.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+            }
+            _ => continue,
+        }
+        stdin.flush().await?;
+
+        info_line.clear();
+        stdout.read_line(&mut info_line).await?;
+
+        let len = info_line
+            .trim_end()
+            .parse()
+            .with_context(|| format!("invalid object size output from cat-file {info_line}"))?;
+        let mut text = vec![0; len];
+        stdout.read_exact(&mut text).await?;
+        stdout.read_exact(&mut newline).await?;
+        let text = String::from_utf8_lossy(&text).to_string();
+
+        let mut old_text = None;
+        let mut new_text = None;
+        match status_code {
+            StatusCode::Modified => {
+                info_line.clear();
+                stdout.read_line(&mut info_line).await?;
+                let len = info_line.trim_end().parse().with_context(|| {
+                    format!("invalid object size output from cat-file {}", info_line)
+                })?;
+                let mut parent_text = vec![0; len];
+                stdout.read_exact(&mut parent_text).await?;
+                stdout.read_exact(&mut newline).await?;
+                old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
+                new_text = Some(text);
+            }
+            StatusCode::Added => new_text = Some(text),
+            StatusCode::Deleted => old_text = Some(text),
+            _ => continue,
+        }
+
+        files.push(CommitFile {
+            path: RepoPath(Arc::from(rel_path)),
+            old_text,
+            new_text,
+        })
+    }
+
+    Ok(CommitDiff { files })
+}
+
+/// Returns the environment for rebase commands, which accept the default message of
+/// squashed commits instead of opening an editor.
+fn non_interactive_rebase_env(env: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut env = env.clone();
+    env.insert("GIT_EDITOR".into(), "true".into());
+    env
+}
+
+/// Quotes a path for the shell git uses to run editors and `exec` commands, which is a POSIX shell on every platform.
+fn quote_for_git_shell(path: &Path) -> Result<String> {
+    let path = path.to_string_lossy().replace('\\', "/");
+    Ok(ShellKind::Posix
+        .try_quote(&path)
+        .with_context(|| format!("failed to quote {path:?}"))?
+        .into_owned())
+}
+
+async fn read_rebase_state(rebase_merge_dir: &Path, name: &str) -> Option<String> {
+    let contents = smol::fs::read_to_string(rebase_merge_dir.join(name))
+        .await
+        .ok()?;
+    Some(contents.trim().to_owned())
+}
+
+fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
+    let mut args = vec![
         OsString::from("--no-optional-locks"),
         OsString::from("status"),
         OsString::from("--porcelain=v1"),
@@ -2964,6 +4318,791 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (file, message) in [
+            ("base", "Initial commit"),
+            ("a", "Add a"),
+            ("b", "Add b"),
+            ("c", "Add c"),
+        ] {
+            smol::fs::write(repo_dir.path().join(file), file)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut todo = repo.rebase_todo("HEAD~3".into()).await.unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add a", "Add b", "Add c"]
+        );
+        assert!(todo.iter().all(|entry| entry.action == RebaseAction::Pick));
+
+        todo[0].action = RebaseAction::Reword;
+        todo[0].message = Some("Add a and b".into());
+        todo[1].action = RebaseAction::Fixup;
+        todo[2].action = RebaseAction::Drop;
+        repo.interactive_rebase("HEAD~3".into(), todo, env.clone())
+            .await
+            .unwrap();
+
+        let git = GitBinary::new("git".into(), repo_dir.path().into(), cx.executor());
+        assert_eq!(
+            git.run(["log", "--format=%s"]).await.unwrap(),
+            "Add a and b\nInitial commit"
+        );
+        assert!(repo_dir.path().join("b").exists());
+        assert!(!repo_dir.path().join("c").exists());
+        assert_eq!(repo.rebase_progress().await.unwrap(), None);
+
+        let mut todo = repo.rebase_todo("HEAD~1".into()).await.unwrap();
+        todo[0].action = RebaseAction::Edit;
+        repo.interactive_rebase("HEAD~1".into(), todo, env.clone())
+            .await
+            .unwrap();
+        let progress = repo.rebase_progress().await.unwrap().unwrap();
+        assert_eq!((progress.done, progress.total), (1, 1));
+        assert!(progress.head_name.is_some());
+        assert!(progress.stopped_sha.is_some());
+
+        repo.rebase_command(RebaseCommand::Continue, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.rebase_progress().await.unwrap(), None);
+    }
+
+    #[gpui::test]
+    async fn test_interactive_rebase_stopping_on_conflict(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (contents, message) in [
+            ("base\n", "Initial commit"),
+            ("one\n", "Change to one"),
+            ("two\n", "Change to two"),
+        ] {
+            smol::fs::write(repo_dir.path().join("file"), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        // Dropping the first change makes the second one conflict with the initial contents.
+        let mut todo = repo.rebase_todo("HEAD~2".into()).await.unwrap();
+        todo[0].action = RebaseAction::Drop;
+        repo.interactive_rebase("HEAD~2".into(), todo.clone(), env.clone())
+            .await
+            .expect("stopping on a conflict should not fail the rebase");
+        let progress = repo.rebase_progress().await.unwrap().unwrap();
+        assert_eq!((progress.done, progress.total), (2, 2));
+        let contents = smol::fs::read_to_string(repo_dir.path().join("file"))
+            .await
+            .unwrap();
+        assert!(contents.contains("<<<<<<<"), "{contents:?}");
+
+        assert!(
+            repo.interactive_rebase("HEAD~2".into(), todo, env.clone())
+                .await
+                .is_err(),
+            "a rebase can't be started while another one is in progress"
+        );
+
+        // Continuing without resolving the conflict stops again.
+        repo.rebase_command(RebaseCommand::Continue, env.clone())
+            .await
+            .unwrap();
+        assert!(repo.rebase_progress().await.unwrap().is_some());
+
+        repo.rebase_command(RebaseCommand::Abort, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(repo.rebase_progress().await.unwrap(), None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("file"))
+                .await
+                .unwrap(),
+            "two\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_log(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let git = GitBinary::new("git".into(), repo_dir.path().into(), cx.executor())
+            .envs(HashMap::clone(&env));
+
+        for (file, message) in [("a", "Add a"), ("b", "Add b")] {
+            smol::fs::write(repo_dir.path().join(file), file)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        git.run(["branch", "feature", "HEAD~1"]).await.unwrap();
+        repo.create_tag("v1".into(), "HEAD".into(), None, env.clone())
+            .await
+            .unwrap();
+
+        let log = repo.log(LogFilter::default(), 0, None).await.unwrap();
+        assert_eq!(
+            log.iter()
+                .map(|entry| (entry.subject.as_ref(), entry.parents.len()))
+                .collect::<Vec<_>>(),
+            [("Add b", 1), ("Add a", 0)]
+        );
+        assert_eq!(log[0].parents[0], log[1].sha);
+        assert!(log[0].refs.iter().any(|name| name == "tag: v1"));
+        assert_eq!(log[1].refs, ["feature"]);
+
+        let filtered = repo
+            .log(
+                LogFilter {
+                    message: Some("ADD A".into()),
+                    ..Default::default()
+                },
+                0,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].sha, log[1].sha);
+
+        let filtered = repo
+            .log(
+                LogFilter {
+                    path: Some(repo_path("b")),
+                    ..Default::default()
+                },
+                0,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].sha, log[0].sha);
+
+        let page = repo.log(LogFilter::default(), 1, Some(1)).await.unwrap();
+        assert_eq!(page, log[1..]);
+
+        let diff = repo
+            .compare_commits(log[1].sha.to_string(), "v1".into(), cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, repo_path("b"));
+        assert_eq!(diff.files[0].old_text, None);
+        assert_eq!(diff.files[0].new_text.as_deref(), Some("b"));
+
+        repo.commit_operation(CommitOperation::Revert, "HEAD".into(), env.clone())
+            .await
+            .unwrap();
+        assert!(!repo_dir.path().join("b").exists());
+        repo.commit_operation(CommitOperation::Checkout, "v1".into(), env.clone())
+            .await
+            .unwrap();
+        assert!(repo_dir.path().join("b").exists());
+
+        // Commits are dated and filtered by when they were committed, not when they were authored.
+        let mut old_author_env = HashMap::clone(&env);
+        old_author_env.insert("GIT_AUTHOR_DATE".into(), "@1000000000 +0000".into());
+        GitBinary::new("git".into(), repo_dir.path().into(), cx.executor())
+            .envs(old_author_env)
+            .run(["commit", "--allow-empty", "--message=Old change"])
+            .await
+            .unwrap();
+        let recent = repo
+            .log(
+                LogFilter {
+                    since: Some(1_500_000_000),
+                    ..Default::default()
+                },
+                0,
+                Some(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(recent[0].subject, "Old change");
+        assert!(recent[0].commit_timestamp >= 1_500_000_000);
+    }
+
+    #[test]
+    fn test_parse_commit_signature() {
+        let signature = CommitSignature::parse("G", "me@example.com", "SHA256:abc").unwrap();
+        assert_eq!(signature.status, SignatureStatus::Verified);
+        assert_eq!(signature.signer.as_deref(), Some("me@example.com"));
+        assert_eq!(signature.key.as_deref(), Some("SHA256:abc"));
+        assert_eq!(
+            CommitSignature::parse("E", "", "ABCDEF").unwrap().status,
+            SignatureStatus::UnknownKey
+        );
+        assert_eq!(
+            CommitSignature::parse("B", "", "").unwrap().status,
+            SignatureStatus::Unverified
+        );
+        assert_eq!(CommitSignature::parse("N", "", ""), None);
+
+        assert_eq!(parse_config_bool("Yes"), Some(true));
+        assert_eq!(parse_config_bool(""), Some(true));
+        assert_eq!(parse_config_bool("0"), Some(false));
+        assert_eq!(parse_config_bool("maybe"), None);
+    }
+
+    #[cfg(not(windows))]
+    #[gpui::test]
+    async fn test_signed_commit(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let git = GitBinary::new("git".into(), repo_dir.path().into(), cx.executor());
+
+        let key_dir = tempfile::tempdir().unwrap();
+        let key_path = key_dir.path().join("key");
+        let status = std::process::Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let public_key = std::fs::read_to_string(key_path.with_extension("pub")).unwrap();
+        let allowed_signers = key_dir.path().join("allowed_signers");
+        std::fs::write(&allowed_signers, format!("hi@zed.dev {public_key}")).unwrap();
+        git.run(["config", "gpg.format", "ssh"]).await.unwrap();
+        git.run([
+            "config",
+            "user.signingKey",
+            key_path.with_extension("pub").to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(
+            repo.config_value("gpg.format".into()).await.unwrap(),
+            Some("ssh".into())
+        );
+        assert_eq!(
+            repo.config_value("commit.gpgSign".into()).await.unwrap(),
+            None
+        );
+
+        for (file, sign) in [("a", false), ("b", true)] {
+            smol::fs::write(repo_dir.path().join(file), file)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                file.to_string().into(),
+                None,
+                CommitOptions {
+                    sign,
+                    ..Default::default()
+                },
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(repo.commit_signature("HEAD~1".into()).await.unwrap(), None);
+
+        git.run([
+            "config",
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().unwrap(),
+        ])
+        .await
+        .unwrap();
+        let signature = repo.commit_signature("HEAD".into()).await.unwrap().unwrap();
+        assert_eq!(signature.status, SignatureStatus::Verified);
+        assert_eq!(signature.signer.as_deref(), Some("hi@zed.dev"));
+    }
+
+    #[gpui::test]
+    async fn test_load_index_file_size(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        let path = repo_dir.path().join("image.bin");
+        smol::fs::write(&path, [0u8, 159, 146, 150]).await.unwrap();
+        repo.stage_paths(vec![repo_path("image.bin")], env)
+            .await
+            .unwrap();
+        // The size comes from the index, not the working copy.
+        smol::fs::write(&path, vec![0u8; 1024]).await.unwrap();
+
+        assert_eq!(repo.load_index_text(repo_path("image.bin")).await, None);
+        assert_eq!(
+            repo.load_index_file_size(repo_path("image.bin")).await,
+            Some(4)
+        );
+        assert_eq!(repo.load_index_file_size(repo_path("missing")).await, None);
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let git = GitBinary::new("git".into(), repo_dir.path().into(), cx.executor());
+
+        // The file's content becomes "bad" in the 5th commit.
+        for ix in 1..=8 {
+            let content = if ix >= 5 { "bad" } else { "good" };
+            smol::fs::write(repo_dir.path().join("file"), format!("{content} {ix}"))
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Commit {ix}").into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        let first_bad = git.run(["rev-parse", "HEAD~3"]).await.unwrap();
+
+        // The user's locale doesn't change the messages that the status is parsed from.
+        let mut bisect_env = HashMap::clone(&env);
+        bisect_env.insert("LC_ALL".into(), "de_DE.UTF-8".into());
+        bisect_env.insert("LANGUAGE".into(), "de".into());
+        let bisect_env = Arc::new(bisect_env);
+
+        let mut status = repo
+            .bisect(
+                BisectCommand::Start {
+                    bad: "HEAD".into(),
+                    good: vec!["HEAD~7".into()],
+                },
+                bisect_env.clone(),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(status, BisectStatus::Testing { remaining: 3, .. }));
+        while let BisectStatus::Testing { .. } = status {
+            let content = smol::fs::read_to_string(repo_dir.path().join("file"))
+                .await
+                .unwrap();
+            let command = if content.starts_with("bad") {
+                BisectCommand::Bad
+            } else {
+                BisectCommand::Good
+            };
+            status = repo.bisect(command, bisect_env.clone()).await.unwrap();
+        }
+        assert_eq!(
+            status,
+            BisectStatus::FirstBad {
+                commit: first_bad.into()
+            }
+        );
+
+        assert_eq!(
+            repo.bisect(BisectCommand::Reset, bisect_env.clone())
+                .await
+                .unwrap(),
+            BisectStatus::Inactive
+        );
+        let content = smol::fs::read_to_string(repo_dir.path().join("file"))
+            .await
+            .unwrap();
+        assert_eq!(content, "bad 8");
+    }
+
+    #[test]
+    fn test_parse_bisect_status() {
+        assert_eq!(
+            BisectStatus::parse(concat!(
+                "There are only 'skip'ped commits left to test.\n",
+                "The first bad commit could be any of:\n",
+                "8384ee5a8e6e6c990fd63dae852c1fd3a2c2e83f\n",
+                "cb5a3510885060cd2678ac93ff4d88143c4fe5c7\n",
+                "We cannot bisect more!\n",
+            )),
+            Some(BisectStatus::Inconclusive {
+                candidates: vec![
+                    "8384ee5a8e6e6c990fd63dae852c1fd3a2c2e83f".into(),
+                    "cb5a3510885060cd2678ac93ff4d88143c4fe5c7".into(),
+                ]
+            })
+        );
+        assert_eq!(
+            BisectStatus::parse(
+                "Bisecting: 1 revision left to test after this (roughly 1 step)\n[cb5a3510885060cd2678ac93ff4d88143c4fe5c7] c6\n"
+            ),
+            Some(BisectStatus::Testing {
+                commit: "cb5a3510885060cd2678ac93ff4d88143c4fe5c7".into(),
+                remaining: 1,
+            })
+        );
+        assert_eq!(
+            BisectStatus::parse("status: waiting for both good and bad commits"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_submodule_status() {
+        assert_eq!(
+            Submodule::parse_status(concat!(
+                " 60f4028863ca0b03642d4d2250ae5a21e2a95d0e my sub (heads/master)\n",
+                "+f3624ea5e2773631d963cdd93fa58621636ea5cd libs/a (v1.0-2-gf3624ea)\n",
+                "-1b9b2b8fd9b36f0f5d3d1d0e7d4e4cf2b3b0ab11 libs/b\n",
+                "U0000000000000000000000000000000000000000 libs/c\n",
+            )),
+            [
+                Submodule {
+                    path: repo_path("my sub"),
+                    sha: "60f4028863ca0b03642d4d2250ae5a21e2a95d0e".into(),
+                    status: SubmoduleStatus::Current,
+                },
+                Submodule {
+                    path: repo_path("libs/a"),
+                    sha: "f3624ea5e2773631d963cdd93fa58621636ea5cd".into(),
+                    status: SubmoduleStatus::OutOfDate,
+                },
+                Submodule {
+                    path: repo_path("libs/b"),
+                    sha: "1b9b2b8fd9b36f0f5d3d1d0e7d4e4cf2b3b0ab11".into(),
+                    status: SubmoduleStatus::Uninitialized,
+                },
+                Submodule {
+                    path: repo_path("libs/c"),
+                    sha: "0000000000000000000000000000000000000000".into(),
+                    status: SubmoduleStatus::Conflicted,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tag_list() {
+        assert_eq!(
+            Tag::parse_list(concat!(
+                "v1.1\0tag\0aaaa\0bbbb\01700000100\0Release 1.1\n",
+                "v1.0\0commit\0cccc\0\01700000000\0Fix the build\n",
+                "malformed\n",
+            )),
+            [
+                Tag {
+                    name: "v1.1".into(),
+                    sha: "bbbb".into(),
+                    subject: "Release 1.1".into(),
+                    annotated: true,
+                    timestamp: 1700000100,
+                },
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "cccc".into(),
+                    subject: "Fix the build".into(),
+                    annotated: false,
+                    timestamp: 1700000000,
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_submodules(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let dir = tempfile::tempdir().unwrap();
+        let env = checkpoint_author_envs();
+        let origin_dir = dir.path().join("origin");
+        let repo_dir = dir.path().join("repo");
+        for path in [&origin_dir, &repo_dir] {
+            git2::Repository::init(path).unwrap();
+            GitBinary::new("git".into(), path.clone(), cx.executor())
+                .envs(env.clone())
+                .run(["commit", "--allow-empty", "-m", "Initial commit"])
+                .await
+                .unwrap();
+        }
+        let repo = RealGitRepository::new(
+            &repo_dir.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        assert!(repo.submodules().await.unwrap().is_empty());
+
+        let git = GitBinary::new("git".into(), repo_dir.clone(), cx.executor()).envs(env.clone());
+        git.run([
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            origin_dir.to_str().unwrap(),
+            "sub",
+        ])
+        .await
+        .unwrap();
+        git.run(["commit", "-m", "Add submodule"]).await.unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].path, repo_path("sub"));
+        assert_eq!(submodules[0].status, SubmoduleStatus::Current);
+
+        // A new commit in the submodule moves it away from the recorded commit.
+        git.run(["-C", "sub", "commit", "--allow-empty", "-m", "Change"])
+            .await
+            .unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules[0].status, SubmoduleStatus::OutOfDate);
+
+        // The cached status is reused until the submodule's checkout changes.
+        assert_eq!(repo.submodules().await.unwrap(), submodules);
+        git.run(["-C", "sub", "checkout", "--detach", "HEAD~1"])
+            .await
+            .unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules[0].status, SubmoduleStatus::Current);
+
+        git.run(["submodule", "deinit", "--force", "sub"])
+            .await
+            .unwrap();
+        let submodules = repo.submodules().await.unwrap();
+        assert_eq!(submodules[0].status, SubmoduleStatus::Uninitialized);
+    }
+
+    #[gpui::test]
+    async fn test_load_conflict_texts(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let git = GitBinary::new("git".into(), repo_dir.path().to_path_buf(), cx.executor())
+            .envs(checkpoint_author_envs());
+        let file_path = repo_dir.path().join("file.txt");
+
+        smol::fs::write(&file_path, "one\ntwo\nthree\n")
+            .await
+            .unwrap();
+        git.run(["add", "file.txt"]).await.unwrap();
+        git.run(["commit", "-m", "Base"]).await.unwrap();
+        git.run(["branch", "theirs"]).await.unwrap();
+        smol::fs::write(&file_path, "one\nours\nthree\n")
+            .await
+            .unwrap();
+        git.run(["commit", "-am", "Ours"]).await.unwrap();
+        git.run(["checkout", "theirs"]).await.unwrap();
+        smol::fs::write(&file_path, "one\ntheirs\nthree\n")
+            .await
+            .unwrap();
+        git.run(["commit", "-am", "Theirs"]).await.unwrap();
+        git.run(["checkout", "-"]).await.unwrap();
+
+        assert!(
+            repo.load_conflict_texts(repo_path("file.txt"))
+                .await
+                .is_err()
+        );
+        git.run(["merge", "theirs"]).await.unwrap_err();
+        assert_eq!(
+            repo.load_conflict_texts(repo_path("file.txt"))
+                .await
+                .unwrap(),
+            ConflictTexts {
+                base: Some("one\ntwo\nthree\n".into()),
+                ours: Some("one\nours\nthree\n".into()),
+                theirs: Some("one\ntheirs\nthree\n".into()),
+            }
+        );
+    }
+
+    #[gpui::test]
+    async fn test_release_commits(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let git = GitBinary::new("git".into(), repo_dir.path().to_path_buf(), cx.executor())
+            .envs(checkpoint_author_envs());
+        let release = async |head: &str| {
+            let release = repo.release_commits(head.to_string()).await.unwrap();
+            let subjects = release
+                .commits
+                .iter()
+                .map(|commit| commit.subject.to_string())
+                .collect::<Vec<_>>();
+            (release.previous_tag.map(|tag| tag.to_string()), subjects)
+        };
+
+        // A root commit has no previous tag.
+        git.run(["commit", "--allow-empty", "-m", "One"])
+            .await
+            .unwrap();
+        assert_eq!(release("HEAD").await, (None, vec!["One".to_string()]));
+
+        // Without any tags, all commits are part of the release.
+        git.run(["commit", "--allow-empty", "-m", "Two"])
+            .await
+            .unwrap();
+        assert_eq!(
+            release("HEAD").await,
+            (None, vec!["Two".to_string(), "One".to_string()])
+        );
+
+        // A tag on the released commit itself isn't the previous tag.
+        git.run(["tag", "v1"]).await.unwrap();
+        assert_eq!(
+            release("HEAD").await,
+            (None, vec!["Two".to_string(), "One".to_string()])
+        );
+
+        git.run(["commit", "--allow-empty", "-m", "Three"])
+            .await
+            .unwrap();
+        git.run(["tag", "v2"]).await.unwrap();
+        assert_eq!(
+            release("HEAD").await,
+            (Some("v1".to_string()), vec!["Three".to_string()])
+        );
+
+        assert!(
+            repo.release_commits("nonexistent".to_string())
+                .await
+                .is_err()
+        );
+    }
+
     #[gpui::test]
     async fn test_compare_checkpoints(cx: &mut TestAppContext) {
         disable_git_global_config();
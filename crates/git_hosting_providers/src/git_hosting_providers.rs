@@ -1,4 +1,5 @@
 mod providers;
+mod review_api;
 mod settings;
 
 use std::sync::Arc;
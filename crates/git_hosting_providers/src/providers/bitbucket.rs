@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 use std::{str::FromStr, sync::Arc};
 
@@ -9,14 +10,17 @@ use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use itertools::Itertools as _;
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::json;
 use url::Url;
+use urlencoding::encode;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, NewReviewComment,
+    ParsedGitRemote, PullRequest, PullRequestReview, RemoteUrl, ReviewComment, ReviewSide,
+    ReviewThread, repository::RepoPath,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{get_host_from_git_remote_url, review_api};
 
 fn pull_request_regex() -> &'static Regex {
     static PULL_REQUEST_REGEX: LazyLock<Regex> = LazyLock::new(|| {
@@ -62,6 +66,97 @@ struct AuthorSelfHosted {
     avatar_url: Option<String>,
 }
 
+/// A page of the results of a Bitbucket Cloud API endpoint.
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    values: Vec<T>,
+    /// The URL of the next page, if any.
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestDetails {
+    id: u32,
+    title: String,
+    links: PullRequestLinks,
+    source: PullRequestEndpoint,
+    destination: PullRequestEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestLinks {
+    html: Link,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestEndpoint {
+    branch: BranchName,
+    commit: CommitHash,
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchName {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitHash {
+    hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestComment {
+    id: u64,
+    content: CommentContent,
+    user: Option<CommentUser>,
+    created_on: String,
+    /// Where the comment is in the diff, or `None` for comments on the whole pull request.
+    inline: Option<InlinePosition>,
+    parent: Option<CommentParent>,
+    #[serde(default)]
+    deleted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentContent {
+    raw: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentUser {
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InlinePosition {
+    path: String,
+    /// The line of the base of the pull request that the comment is on.
+    from: Option<u32>,
+    /// The line of the head of the pull request that the comment is on.
+    to: Option<u32>,
+    #[serde(default)]
+    outdated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentParent {
+    id: u64,
+}
+
+impl From<&PullRequestComment> for ReviewComment {
+    fn from(comment: &PullRequestComment) -> Self {
+        Self {
+            id: comment.id.to_string(),
+            author: comment.user.as_ref().map_or_else(
+                || "Former user".to_string(),
+                |user| user.display_name.clone(),
+            ),
+            body: comment.content.raw.clone(),
+            created_at: comment.created_on.clone(),
+        }
+    }
+}
+
 pub struct Bitbucket {
     name: String,
     base_url: Url,
@@ -104,6 +199,27 @@ impl Bitbucket {
             .is_some_and(|host| host != "bitbucket.org")
     }
 
+    /// Returns an authenticated request to the Bitbucket Cloud API endpoint of the repository at
+    /// the given path.
+    fn api_request(
+        &self,
+        method: http_client::Method,
+        remote: &ParsedGitRemote,
+        path: &str,
+        token: &str,
+    ) -> Result<http_client::Builder> {
+        let Some(host) = self.base_url.host_str() else {
+            bail!("failed to get host from bitbucket base url");
+        };
+        let ParsedGitRemote { owner, repo } = remote;
+        Ok(Request::builder()
+            .method(method)
+            .uri(format!(
+                "https://api.{host}/2.0/repositories/{owner}/{repo}/{path}"
+            ))
+            .header("Authorization", format!("Bearer {token}")))
+    }
+
     async fn fetch_bitbucket_commit_author(
         &self,
         repo_owner: &str,
@@ -156,6 +272,43 @@ impl Bitbucket {
     }
 }
 
+/// Groups comments on the diff into threads, in the order the threads were started.
+fn review_threads(comments: Vec<PullRequestComment>) -> Result<Vec<ReviewThread>> {
+    let mut threads = Vec::<ReviewThread>::new();
+    let mut thread_ixs = HashMap::default();
+    for comment in comments {
+        // Replies can be made to other replies, which belong to the thread of their parent.
+        if let Some(thread_ix) = comment
+            .parent
+            .as_ref()
+            .and_then(|parent| thread_ixs.get(&parent.id).copied())
+        {
+            thread_ixs.insert(comment.id, thread_ix);
+            if !comment.deleted {
+                threads[thread_ix].comments.push((&comment).into());
+            }
+            continue;
+        }
+
+        let Some(inline) = comment.inline.as_ref().filter(|_| !comment.deleted) else {
+            continue;
+        };
+        let (line, side) = match inline.to {
+            Some(line) => (Some(line), ReviewSide::Head),
+            None => (inline.from, ReviewSide::Base),
+        };
+        thread_ixs.insert(comment.id, threads.len());
+        threads.push(ReviewThread {
+            id: comment.id.to_string(),
+            path: RepoPath::new(&inline.path)?,
+            line: line.filter(|_| !inline.outdated),
+            side,
+            comments: vec![(&comment).into()],
+        });
+    }
+    Ok(threads)
+}
+
 #[async_trait]
 impl GitHostingProvider for Bitbucket {
     fn name(&self) -> String {
@@ -293,11 +446,164 @@ impl GitHostingProvider for Bitbucket {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        // Bitbucket Data Center has a different API, which isn't supported yet.
+        !self.is_self_hosted()
+    }
+
+    async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        let query = format!("source.branch.name=\"{branch}\"");
+        let request = self.api_request(
+            http_client::Method::GET,
+            remote,
+            &format!("pullrequests?state=OPEN&q={}", encode(&query)),
+            token,
+        )?;
+        let pull_requests =
+            review_api::get_json::<Page<PullRequestDetails>>(request, &http_client).await?;
+        pull_requests
+            .values
+            .into_iter()
+            .next()
+            .map(|pull_request| {
+                Ok(PullRequest {
+                    number: pull_request.id,
+                    url: Url::parse(&pull_request.links.html.href)?,
+                })
+            })
+            .transpose()
+    }
+
+    async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        let path = format!("pullrequests/{number}");
+        let details = review_api::get_json::<PullRequestDetails>(
+            self.api_request(http_client::Method::GET, remote, &path, token)?,
+            &http_client,
+        )
+        .await?;
+        let diff = review_api::send_request(
+            self.api_request(
+                http_client::Method::GET,
+                remote,
+                &format!("{path}/diff"),
+                token,
+            )?,
+            AsyncBody::default(),
+            &http_client,
+        )
+        .await?;
+
+        let mut comments = Vec::new();
+        let mut request = self.api_request(
+            http_client::Method::GET,
+            remote,
+            &format!("{path}/comments?pagelen={}", review_api::ITEMS_PER_PAGE),
+            token,
+        )?;
+        loop {
+            let page =
+                review_api::get_json::<Page<PullRequestComment>>(request, &http_client).await?;
+            comments.extend(page.values);
+            let Some(next) = page.next else {
+                break;
+            };
+            request = Request::get(next).header("Authorization", format!("Bearer {token}"));
+        }
+
+        Ok(PullRequestReview {
+            pull_request: PullRequest {
+                number: details.id,
+                url: Url::parse(&details.links.html.href)?,
+            },
+            title: details.title,
+            base_branch: details.destination.branch.name,
+            head_sha: details.source.commit.hash,
+            diff: String::from_utf8(diff).context("pull request diff is not valid UTF-8")?,
+            threads: review_threads(comments)?,
+        })
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        let thread_id = thread_id
+            .parse::<u64>()
+            .with_context(|| format!("invalid review thread {thread_id:?}"))?;
+        let request = self.api_request(
+            http_client::Method::POST,
+            remote,
+            &format!("pullrequests/{number}/comments"),
+            token,
+        )?;
+        let comment = review_api::send_json::<PullRequestComment>(
+            request,
+            json!({
+                "content": { "raw": body },
+                "parent": { "id": thread_id },
+            }),
+            &http_client,
+        )
+        .await?;
+        Ok((&comment).into())
+    }
+
+    async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        let inline = match comment.side {
+            ReviewSide::Base => json!({ "path": comment.path.as_unix_str(), "from": comment.line }),
+            ReviewSide::Head => json!({ "path": comment.path.as_unix_str(), "to": comment.line }),
+        };
+        let request = self.api_request(
+            http_client::Method::POST,
+            remote,
+            &format!("pullrequests/{number}/comments"),
+            token,
+        )?;
+        let comment = review_api::send_json::<PullRequestComment>(
+            request,
+            json!({
+                "content": { "raw": comment.body },
+                "inline": inline,
+            }),
+            &http_client,
+        )
+        .await?;
+        review_threads(vec![comment])?
+            .pop()
+            .context("Bitbucket returned no review comment")
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -588,4 +894,222 @@ mod tests {
             "https://bitbucket.company.com/projects/zed-industries/repos/zed/pull-requests/123"
         );
     }
+
+    fn zed_remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    fn comment_json(
+        id: u64,
+        parent: Option<u64>,
+        inline: Option<serde_json::Value>,
+        body: &str,
+    ) -> serde_json::Value {
+        json!({
+            "id": id,
+            "content": { "raw": body },
+            "user": { "display_name": "Reviewer" },
+            "created_on": "2024-01-01T00:00:00Z",
+            "inline": inline,
+            "parent": parent.map(|id| json!({ "id": id })),
+        })
+    }
+
+    #[test]
+    fn test_pull_request_review() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri().host(), Some("api.bitbucket.org"));
+            assert_eq!(request.headers()["Authorization"], "Bearer secret");
+            let body = match request.uri().path() {
+                "/2.0/repositories/zed-industries/zed/pullrequests" => {
+                    assert_eq!(
+                        request.uri().query(),
+                        Some("state=OPEN&q=source.branch.name%3D%22feature%22")
+                    );
+                    json!({ "values": [{
+                        "id": 42,
+                        "title": "Add a feature",
+                        "links": { "html": { "href": "https://bitbucket.org/zed-industries/zed/pull-requests/42" } },
+                        "source": { "branch": { "name": "feature" }, "commit": { "hash": "2222222" } },
+                        "destination": { "branch": { "name": "main" }, "commit": { "hash": "1111111" } },
+                    }] })
+                    .to_string()
+                }
+                "/2.0/repositories/zed-industries/zed/pullrequests/42" => json!({
+                    "id": 42,
+                    "title": "Add a feature",
+                    "links": { "html": { "href": "https://bitbucket.org/zed-industries/zed/pull-requests/42" } },
+                    "source": { "branch": { "name": "feature" }, "commit": { "hash": "2222222" } },
+                    "destination": { "branch": { "name": "main" }, "commit": { "hash": "1111111" } },
+                })
+                .to_string(),
+                "/2.0/repositories/zed-industries/zed/pullrequests/42/diff" => {
+                    "diff --git a/src/main.rs b/src/main.rs\n".to_string()
+                }
+                "/2.0/repositories/zed-industries/zed/pullrequests/42/comments" => {
+                    match request.uri().query() {
+                        Some("pagelen=100") => json!({
+                            "values": [
+                                comment_json(1, None, Some(json!({ "path": "src/main.rs", "from": null, "to": 3 })), "Can this be simplified?"),
+                                comment_json(2, None, None, "Looks good overall"),
+                            ],
+                            "next": "https://api.bitbucket.org/2.0/repositories/zed-industries/zed/pullrequests/42/comments?pagelen=100&page=2",
+                        }),
+                        Some("pagelen=100&page=2") => json!({
+                            "values": [
+                                comment_json(3, Some(1), Some(json!({ "path": "src/main.rs", "from": null, "to": 3 })), "Done."),
+                                comment_json(4, Some(3), Some(json!({ "path": "src/main.rs", "from": null, "to": 3 })), "Thanks!"),
+                                comment_json(5, None, Some(json!({ "path": "src/lib.rs", "from": 7, "to": null, "outdated": true })), "Why was this removed?"),
+                            ],
+                        }),
+                        query => panic!("unexpected comments query {query:?}"),
+                    }
+                    .to_string()
+                }
+                path => panic!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body))
+                .unwrap())
+        });
+
+        let bitbucket = Bitbucket::public_instance();
+        assert!(bitbucket.supports_pull_request_reviews());
+        let pull_request = block_on(bitbucket.open_pull_request_for_branch(
+            &zed_remote(),
+            "feature",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pull_request.number, 42);
+
+        let review = block_on(bitbucket.pull_request_review(
+            &zed_remote(),
+            pull_request.number,
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        let comment = |id: &str, body: &str| ReviewComment {
+            id: id.into(),
+            author: "Reviewer".into(),
+            body: body.into(),
+            created_at: "2024-01-01T00:00:00Z".into(),
+        };
+        assert_eq!(
+            review,
+            PullRequestReview {
+                pull_request,
+                title: "Add a feature".into(),
+                base_branch: "main".into(),
+                head_sha: "2222222".into(),
+                diff: "diff --git a/src/main.rs b/src/main.rs\n".into(),
+                threads: vec![
+                    ReviewThread {
+                        id: "1".into(),
+                        path: repo_path("src/main.rs"),
+                        line: Some(3),
+                        side: ReviewSide::Head,
+                        comments: vec![
+                            comment("1", "Can this be simplified?"),
+                            comment("3", "Done."),
+                            comment("4", "Thanks!"),
+                        ],
+                    },
+                    ReviewThread {
+                        id: "5".into(),
+                        path: repo_path("src/lib.rs"),
+                        line: None,
+                        side: ReviewSide::Base,
+                        comments: vec![comment("5", "Why was this removed?")],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_post_review_comments() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|mut request| async move {
+            assert_eq!(request.method(), "POST");
+            assert_eq!(
+                request.uri().path(),
+                "/2.0/repositories/zed-industries/zed/pullrequests/42/comments"
+            );
+            let mut body = String::new();
+            request.body_mut().read_to_string(&mut body).await?;
+            let body = serde_json::from_str::<serde_json::Value>(&body)?;
+
+            let response = if body.get("parent").is_some() {
+                assert_eq!(
+                    body,
+                    json!({ "content": { "raw": "Done." }, "parent": { "id": 1 } })
+                );
+                comment_json(
+                    3,
+                    Some(1),
+                    Some(json!({ "path": "src/main.rs", "to": 3 })),
+                    "Done.",
+                )
+            } else {
+                assert_eq!(
+                    body,
+                    json!({
+                        "content": { "raw": "Typo" },
+                        "inline": { "path": "src/lib.rs", "from": 7 },
+                    })
+                );
+                comment_json(4, None, Some(body["inline"].clone()), "Typo")
+            };
+            Ok(Response::builder()
+                .status(201)
+                .body(AsyncBody::from(response.to_string()))
+                .unwrap())
+        });
+
+        let bitbucket = Bitbucket::public_instance();
+        let reply = block_on(bitbucket.reply_to_review_thread(
+            &zed_remote(),
+            42,
+            "1",
+            "Done.",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap();
+        assert_eq!(reply.id, "3");
+        assert_eq!(reply.body, "Done.");
+
+        let thread = block_on(bitbucket.create_review_comment(
+            &zed_remote(),
+            42,
+            NewReviewComment {
+                path: repo_path("src/lib.rs"),
+                line: 7,
+                side: ReviewSide::Base,
+                commit_sha: "2222222".into(),
+                body: "Typo".into(),
+            },
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(thread.id, "4");
+        assert_eq!(thread.line, Some(7));
+        assert_eq!(thread.side, ReviewSide::Base);
+    }
+
+    #[test]
+    fn test_bitbucket_self_hosted_reviews_unsupported() {
+        let bitbucket =
+            Bitbucket::from_remote_url("https://bitbucket.company.com/zed-industries/zed.git")
+                .unwrap();
+        assert!(!bitbucket.supports_pull_request_reviews());
+    }
 }
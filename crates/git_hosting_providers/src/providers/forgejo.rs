@@ -10,10 +10,11 @@ use serde::Deserialize;
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, NewReviewComment,
+    ParsedGitRemote, PullRequest, PullRequestReview, RemoteUrl, ReviewComment, ReviewThread,
 };
 
+use super::gitea::GiteaReviewApi;
 use crate::get_host_from_git_remote_url;
 
 #[derive(Debug, Deserialize)]
@@ -261,11 +262,68 @@ impl GitHostingProvider for Forgejo {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        true
+    }
+
+    async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        GiteaReviewApi::new(&self.base_url)?
+            .open_pull_request_for_branch(remote, branch, token, &http_client)
+            .await
+    }
+
+    async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        GiteaReviewApi::new(&self.base_url)?
+            .pull_request_review(remote, number, token, &http_client)
+            .await
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        GiteaReviewApi::new(&self.base_url)?
+            .reply_to_review_thread(remote, number, thread_id, body, token, &http_client)
+            .await
+    }
+
+    async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        GiteaReviewApi::new(&self.base_url)?
+            .create_review_comment(remote, number, comment, token, &http_client)
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -430,4 +488,51 @@ mod tests {
         let expected_url = "https://forgejo-instance.big-co.com/zed-industries/zed/src/commit/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_open_pull_request_for_branch() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri().host(), Some("codeberg.org"));
+            assert_eq!(request.headers()["Authorization"], "token secret");
+            let body = match request.uri().query() {
+                Some("state=open&limit=50&page=1") => serde_json::json!([{
+                    "number": 42,
+                    "html_url": "https://codeberg.org/zed-industries/zed/pulls/42",
+                    "title": "Add a feature",
+                    "base": { "ref": "main", "sha": "1111111" },
+                    "head": { "ref": "feature", "sha": "2222222" },
+                }])
+                .to_string(),
+                _ => "[]".to_string(),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body))
+                .unwrap())
+        });
+
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let forgejo = Forgejo::public_instance();
+        let pull_request = block_on(forgejo.open_pull_request_for_branch(
+            &remote,
+            "feature",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pull_request.number, 42);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://codeberg.org/zed-industries/zed/pulls/42"
+        );
+
+        let pull_request =
+            block_on(forgejo.open_pull_request_for_branch(&remote, "other", "secret", http_client))
+                .unwrap();
+        assert_eq!(pull_request, None);
+    }
 }
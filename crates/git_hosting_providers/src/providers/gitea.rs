@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -7,14 +8,16 @@ use futures::AsyncReadExt;
 use gpui::SharedString;
 use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use serde::Deserialize;
+use serde_json::json;
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, NewReviewComment,
+    ParsedGitRemote, PullRequest, PullRequestReview, RemoteUrl, ReviewComment, ReviewSide,
+    ReviewThread, repository::RepoPath,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{get_host_from_git_remote_url, review_api};
 
 #[derive(Debug, Deserialize)]
 struct CommitDetails {
@@ -26,6 +29,322 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct PullRequestDetails {
+    number: u32,
+    html_url: String,
+    title: String,
+    base: PullRequestRef,
+    head: PullRequestRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullReview {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullReviewComment {
+    id: u64,
+    path: String,
+    /// The line of the head of the pull request that the comment is on, or 0.
+    position: u32,
+    /// The line of the base of the pull request that the comment is on, or 0.
+    original_position: u32,
+    body: String,
+    user: Option<PullReviewCommentUser>,
+    created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullReviewCommentUser {
+    login: String,
+}
+
+impl From<&PullReviewComment> for ReviewComment {
+    fn from(comment: &PullReviewComment) -> Self {
+        Self {
+            id: comment.id.to_string(),
+            author: comment
+                .user
+                .as_ref()
+                .map_or_else(|| "Ghost".to_string(), |user| user.login.clone()),
+            body: comment.body.clone(),
+            created_at: comment.created_at.clone(),
+        }
+    }
+}
+
+/// The number of items that are requested per page, which is the default maximum of Gitea.
+const ITEMS_PER_PAGE: usize = 50;
+
+/// The pull request review API that Gitea and Forgejo share.
+///
+/// Gitea doesn't thread review comments, and shows the comments on a line as one conversation, so
+/// threads are identified by their line, like `-12:src/main.rs` for line 12 of the base.
+pub(crate) struct GiteaReviewApi {
+    api_url: String,
+}
+
+impl GiteaReviewApi {
+    pub(crate) fn new(base_url: &Url) -> Result<Self> {
+        let Some(host) = base_url.host_str() else {
+            bail!("failed to get host from base url {base_url}");
+        };
+        Ok(Self {
+            api_url: format!("https://{host}/api/v1"),
+        })
+    }
+
+    /// Returns an authenticated request to the API endpoint of the repository at the given path.
+    fn request(
+        &self,
+        method: http_client::Method,
+        remote: &ParsedGitRemote,
+        path: &str,
+        token: &str,
+    ) -> http_client::Builder {
+        let ParsedGitRemote { owner, repo } = remote;
+        Request::builder()
+            .method(method)
+            .uri(format!("{}/repos/{owner}/{repo}/{path}", self.api_url))
+            .header("Authorization", format!("token {token}"))
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        remote: &ParsedGitRemote,
+        path: &str,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
+        let request = self.request(http_client::Method::GET, remote, path, token);
+        review_api::get_json(request, client).await
+    }
+
+    pub(crate) async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        for page in 1.. {
+            let pull_requests = self
+                .get_json::<Vec<PullRequestDetails>>(
+                    remote,
+                    &format!("pulls?state=open&limit={ITEMS_PER_PAGE}&page={page}"),
+                    token,
+                    client,
+                )
+                .await?;
+            if pull_requests.is_empty() {
+                break;
+            }
+            if let Some(pull_request) = pull_requests
+                .into_iter()
+                .find(|pull_request| pull_request.head.ref_name == branch)
+            {
+                return Ok(Some(PullRequest {
+                    number: pull_request.number,
+                    url: Url::parse(&pull_request.html_url)?,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        let details = self
+            .get_json::<PullRequestDetails>(remote, &format!("pulls/{number}"), token, client)
+            .await?;
+        let diff = review_api::send_request(
+            self.request(
+                http_client::Method::GET,
+                remote,
+                &format!("pulls/{number}.diff"),
+                token,
+            ),
+            AsyncBody::default(),
+            client,
+        )
+        .await?;
+
+        let mut comments = Vec::new();
+        for page in 1.. {
+            let reviews = self
+                .get_json::<Vec<PullReview>>(
+                    remote,
+                    &format!("pulls/{number}/reviews?limit={ITEMS_PER_PAGE}&page={page}"),
+                    token,
+                    client,
+                )
+                .await?;
+            if reviews.is_empty() {
+                break;
+            }
+            for review in reviews {
+                comments.extend(
+                    self.get_json::<Vec<PullReviewComment>>(
+                        remote,
+                        &format!("pulls/{number}/reviews/{}/comments", review.id),
+                        token,
+                        client,
+                    )
+                    .await?,
+                );
+            }
+        }
+
+        Ok(PullRequestReview {
+            pull_request: PullRequest {
+                number: details.number,
+                url: Url::parse(&details.html_url)?,
+            },
+            title: details.title,
+            base_branch: details.base.ref_name,
+            head_sha: details.head.sha,
+            diff: String::from_utf8(diff).context("pull request diff is not valid UTF-8")?,
+            threads: review_threads(comments)?,
+        })
+    }
+
+    pub(crate) async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        let (line, path) = thread_id
+            .split_once(':')
+            .with_context(|| format!("invalid review thread {thread_id:?}"))?;
+        let line = line
+            .parse::<i64>()
+            .with_context(|| format!("invalid review thread {thread_id:?}"))?;
+        let details = self
+            .get_json::<PullRequestDetails>(remote, &format!("pulls/{number}"), token, client)
+            .await?;
+        let thread = self
+            .create_review_comment(
+                remote,
+                number,
+                NewReviewComment {
+                    path: RepoPath::new(path)?,
+                    line: line.unsigned_abs().try_into()?,
+                    side: if line < 0 {
+                        ReviewSide::Base
+                    } else {
+                        ReviewSide::Head
+                    },
+                    commit_sha: details.head.sha,
+                    body: body.to_string(),
+                },
+                token,
+                client,
+            )
+            .await?;
+        thread
+            .comments
+            .into_iter()
+            .next()
+            .context("no review comment was created")
+    }
+
+    pub(crate) async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        let (old_position, new_position) = match comment.side {
+            ReviewSide::Base => (comment.line, 0),
+            ReviewSide::Head => (0, comment.line),
+        };
+        // Comments can only be posted as part of a review.
+        let review = review_api::send_json::<PullReview>(
+            self.request(
+                http_client::Method::POST,
+                remote,
+                &format!("pulls/{number}/reviews"),
+                token,
+            ),
+            json!({
+                "commit_id": comment.commit_sha,
+                "event": "COMMENT",
+                "body": "",
+                "comments": [{
+                    "path": comment.path.as_unix_str(),
+                    "body": comment.body,
+                    "old_position": old_position,
+                    "new_position": new_position,
+                }],
+            }),
+            client,
+        )
+        .await?;
+        let comments = self
+            .get_json::<Vec<PullReviewComment>>(
+                remote,
+                &format!("pulls/{number}/reviews/{}/comments", review.id),
+                token,
+                client,
+            )
+            .await?;
+        review_threads(comments)?
+            .pop()
+            .context("no review comment was created")
+    }
+}
+
+/// Groups review comments into threads by the line they're on, in the order the threads were started.
+fn review_threads(comments: Vec<PullReviewComment>) -> Result<Vec<ReviewThread>> {
+    let mut threads = Vec::<ReviewThread>::new();
+    let mut thread_ixs = HashMap::new();
+    for comment in comments {
+        let (line, side) = if comment.position > 0 {
+            (comment.position, ReviewSide::Head)
+        } else {
+            (comment.original_position, ReviewSide::Base)
+        };
+        let id = match side {
+            ReviewSide::Base => format!("-{line}:{}", comment.path),
+            ReviewSide::Head => format!("{line}:{}", comment.path),
+        };
+        if let Some(&thread_ix) = thread_ixs.get(&id) {
+            threads[thread_ix].comments.push((&comment).into());
+            continue;
+        }
+
+        thread_ixs.insert(id.clone(), threads.len());
+        threads.push(ReviewThread {
+            id,
+            path: RepoPath::new(&comment.path)?,
+            line: Some(line),
+            side,
+            comments: vec![(&comment).into()],
+        });
+    }
+    Ok(threads)
+}
+
 pub struct Gitea {
     name: String,
     base_url: Url,
@@ -209,11 +528,68 @@ impl GitHostingProvider for Gitea {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        true
+    }
+
+    async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        GiteaReviewApi::new(&self.base_url)?
+            .open_pull_request_for_branch(remote, branch, token, &http_client)
+            .await
+    }
+
+    async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        GiteaReviewApi::new(&self.base_url)?
+            .pull_request_review(remote, number, token, &http_client)
+            .await
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        GiteaReviewApi::new(&self.base_url)?
+            .reply_to_review_thread(remote, number, thread_id, body, token, &http_client)
+            .await
+    }
+
+    async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        GiteaReviewApi::new(&self.base_url)?
+            .create_review_comment(remote, number, comment, token, &http_client)
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -377,4 +753,208 @@ mod tests {
         let expected_url = "https://gitea-instance.big-co.com/zed-industries/zed/src/commit/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    fn zed_remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    fn pull_request_json(number: u32, branch: &str) -> serde_json::Value {
+        json!({
+            "number": number,
+            "html_url": format!("https://gitea.com/zed-industries/zed/pulls/{number}"),
+            "title": "Add a feature",
+            "base": { "ref": "main", "sha": "1111111" },
+            "head": { "ref": branch, "sha": "2222222" },
+        })
+    }
+
+    fn review_comment_json(
+        id: u64,
+        path: &str,
+        position: u32,
+        original_position: u32,
+        body: &str,
+    ) -> serde_json::Value {
+        json!({
+            "id": id,
+            "path": path,
+            "position": position,
+            "original_position": original_position,
+            "body": body,
+            "user": { "login": "reviewer" },
+            "created_at": "2024-01-01T00:00:00Z",
+        })
+    }
+
+    #[test]
+    fn test_pull_request_review() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.headers()["Authorization"], "token secret");
+            let query = request.uri().query().unwrap_or_default().to_string();
+            let body = match (request.uri().path(), query.as_str()) {
+                ("/api/v1/repos/zed-industries/zed/pulls", "state=open&limit=50&page=1") => {
+                    json!([
+                        pull_request_json(41, "other"),
+                        pull_request_json(42, "feature")
+                    ])
+                    .to_string()
+                }
+                ("/api/v1/repos/zed-industries/zed/pulls/42.diff", _) => {
+                    "diff --git a/src/main.rs b/src/main.rs\n".to_string()
+                }
+                ("/api/v1/repos/zed-industries/zed/pulls/42", _) => {
+                    pull_request_json(42, "feature").to_string()
+                }
+                ("/api/v1/repos/zed-industries/zed/pulls/42/reviews", "limit=50&page=1") => {
+                    json!([{ "id": 7 }, { "id": 8 }]).to_string()
+                }
+                ("/api/v1/repos/zed-industries/zed/pulls/42/reviews", _) => "[]".to_string(),
+                ("/api/v1/repos/zed-industries/zed/pulls/42/reviews/7/comments", _) => json!([
+                    review_comment_json(1, "src/main.rs", 3, 0, "Can this be simplified?"),
+                    review_comment_json(2, "src/lib.rs", 0, 7, "Why was this removed?"),
+                ])
+                .to_string(),
+                ("/api/v1/repos/zed-industries/zed/pulls/42/reviews/8/comments", _) => {
+                    json!([review_comment_json(3, "src/main.rs", 3, 0, "Done."),]).to_string()
+                }
+                (path, _) => panic!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body))
+                .unwrap())
+        });
+
+        let gitea = Gitea::public_instance();
+        let pull_request = block_on(gitea.open_pull_request_for_branch(
+            &zed_remote(),
+            "feature",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pull_request.number, 42);
+
+        let review = block_on(gitea.pull_request_review(
+            &zed_remote(),
+            pull_request.number,
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        let comment = |id: &str, body: &str| ReviewComment {
+            id: id.into(),
+            author: "reviewer".into(),
+            body: body.into(),
+            created_at: "2024-01-01T00:00:00Z".into(),
+        };
+        assert_eq!(
+            review,
+            PullRequestReview {
+                pull_request,
+                title: "Add a feature".into(),
+                base_branch: "main".into(),
+                head_sha: "2222222".into(),
+                diff: "diff --git a/src/main.rs b/src/main.rs\n".into(),
+                threads: vec![
+                    ReviewThread {
+                        id: "3:src/main.rs".into(),
+                        path: repo_path("src/main.rs"),
+                        line: Some(3),
+                        side: ReviewSide::Head,
+                        comments: vec![
+                            comment("1", "Can this be simplified?"),
+                            comment("3", "Done."),
+                        ],
+                    },
+                    ReviewThread {
+                        id: "-7:src/lib.rs".into(),
+                        path: repo_path("src/lib.rs"),
+                        line: Some(7),
+                        side: ReviewSide::Base,
+                        comments: vec![comment("2", "Why was this removed?")],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_post_review_comments() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|mut request| async move {
+            assert_eq!(request.uri().host(), Some("gitea.zed.com"));
+            let response = match (request.method().as_str(), request.uri().path()) {
+                ("GET", "/api/v1/repos/zed-industries/zed/pulls/42") => {
+                    pull_request_json(42, "feature")
+                }
+                ("POST", "/api/v1/repos/zed-industries/zed/pulls/42/reviews") => {
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    let body = serde_json::from_str::<serde_json::Value>(&body)?;
+                    assert_eq!(
+                        body,
+                        json!({
+                            "commit_id": "2222222",
+                            "event": "COMMENT",
+                            "body": "",
+                            "comments": [{
+                                "path": "src/lib.rs",
+                                "body": "Typo",
+                                "old_position": 7,
+                                "new_position": 0,
+                            }],
+                        })
+                    );
+                    json!({ "id": 9 })
+                }
+                ("GET", "/api/v1/repos/zed-industries/zed/pulls/42/reviews/9/comments") => {
+                    json!([review_comment_json(4, "src/lib.rs", 0, 7, "Typo")])
+                }
+                (method, path) => panic!("unexpected {method} request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(response.to_string()))
+                .unwrap())
+        });
+
+        // Replies are posted as new comments on the line of the thread.
+        let gitea = Gitea::new(
+            "Gitea Self-Hosted",
+            Url::parse("https://gitea.zed.com").unwrap(),
+        );
+        let reply = block_on(gitea.reply_to_review_thread(
+            &zed_remote(),
+            42,
+            "-7:src/lib.rs",
+            "Typo",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap();
+        assert_eq!(reply.id, "4");
+        assert_eq!(reply.body, "Typo");
+
+        let thread = block_on(gitea.create_review_comment(
+            &zed_remote(),
+            42,
+            NewReviewComment {
+                path: repo_path("src/lib.rs"),
+                line: 7,
+                side: ReviewSide::Base,
+                commit_sha: "2222222".into(),
+                body: "Typo".into(),
+            },
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(thread.id, "-7:src/lib.rs");
+        assert_eq!(thread.line, Some(7));
+        assert_eq!(thread.side, ReviewSide::Base);
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::{Arc, LazyLock};
 
@@ -8,15 +9,17 @@ use gpui::SharedString;
 use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::json;
 use url::Url;
 use urlencoding::encode;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, NewReviewComment,
+    ParsedGitRemote, PullRequest, PullRequestReview, RemoteUrl, ReviewComment, ReviewSide,
+    ReviewThread, repository::RepoPath,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{get_host_from_git_remote_url, review_api};
 
 fn pull_request_number_regex() -> &'static Regex {
     static PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
@@ -62,6 +65,59 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct PullRequestSummary {
+    number: u32,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestDetails {
+    number: u32,
+    html_url: String,
+    title: String,
+    base: PullRequestRef,
+    head: PullRequestRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestComment {
+    id: u64,
+    in_reply_to_id: Option<u64>,
+    path: String,
+    line: Option<u32>,
+    side: Option<String>,
+    body: String,
+    user: Option<PullRequestCommentUser>,
+    created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestCommentUser {
+    login: String,
+}
+
+impl From<&PullRequestComment> for ReviewComment {
+    fn from(comment: &PullRequestComment) -> Self {
+        Self {
+            id: comment.id.to_string(),
+            author: comment
+                .user
+                .as_ref()
+                .map_or_else(|| "ghost".to_string(), |user| user.login.clone()),
+            body: comment.body.clone(),
+            created_at: comment.created_at.clone(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Github {
     name: String,
@@ -143,6 +199,95 @@ impl Github {
     }
 }
 
+impl Github {
+    /// Returns the URL of the REST API endpoint at the given path.
+    fn api_url(&self, path: &str) -> Result<String> {
+        let Some(host) = self.base_url.host_str() else {
+            bail!("failed to get host from github base url");
+        };
+        if host == "github.com" {
+            Ok(format!("https://api.github.com/{path}"))
+        } else {
+            Ok(format!("https://{host}/api/v3/{path}"))
+        }
+    }
+
+    /// Returns an authenticated request to the REST API endpoint at the given path.
+    fn api_request(
+        &self,
+        method: http_client::Method,
+        path: &str,
+        token: &str,
+        accept: &str,
+    ) -> Result<http_client::Builder> {
+        Ok(Request::builder()
+            .method(method)
+            .uri(self.api_url(path)?)
+            .header("Accept", accept)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("X-GitHub-Api-Version", "2022-11-28"))
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
+        let request = self.api_request(
+            http_client::Method::GET,
+            path,
+            token,
+            "application/vnd.github+json",
+        )?;
+        review_api::get_json(request, client).await
+    }
+
+    async fn post_json<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: serde_json::Value,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
+        let request = self.api_request(
+            http_client::Method::POST,
+            path,
+            token,
+            "application/vnd.github+json",
+        )?;
+        review_api::send_json(request, body, client).await
+    }
+}
+
+/// Groups review comments into threads, in the order the threads were started.
+fn review_threads(comments: Vec<PullRequestComment>) -> Result<Vec<ReviewThread>> {
+    let mut threads = Vec::<ReviewThread>::new();
+    let mut thread_ixs = HashMap::default();
+    for comment in comments {
+        if let Some(thread_ix) = comment
+            .in_reply_to_id
+            .and_then(|id| thread_ixs.get(&id).copied())
+        {
+            threads[thread_ix].comments.push((&comment).into());
+            continue;
+        }
+
+        thread_ixs.insert(comment.id, threads.len());
+        threads.push(ReviewThread {
+            id: comment.id.to_string(),
+            path: RepoPath::new(&comment.path)?,
+            line: comment.line,
+            side: match comment.side.as_deref() {
+                Some("LEFT") => ReviewSide::Base,
+                _ => ReviewSide::Head,
+            },
+            comments: vec![(&comment).into()],
+        });
+    }
+    Ok(threads)
+}
+
 #[async_trait]
 impl GitHostingProvider for Github {
     fn name(&self) -> String {
@@ -269,11 +414,155 @@ impl GitHostingProvider for Github {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        true
+    }
+
+    async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let pull_requests = self
+            .get_json::<Vec<PullRequestSummary>>(
+                &format!(
+                    "repos/{owner}/{repo}/pulls?state=open&head={}",
+                    encode(&format!("{owner}:{branch}"))
+                ),
+                token,
+                &http_client,
+            )
+            .await?;
+        pull_requests
+            .into_iter()
+            .next()
+            .map(|pull_request| {
+                Ok(PullRequest {
+                    number: pull_request.number,
+                    url: Url::parse(&pull_request.html_url)?,
+                })
+            })
+            .transpose()
+    }
+
+    async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let path = format!("repos/{owner}/{repo}/pulls/{number}");
+        let details = self
+            .get_json::<PullRequestDetails>(&path, token, &http_client)
+            .await?;
+        let diff = review_api::send_request(
+            self.api_request(
+                http_client::Method::GET,
+                &path,
+                token,
+                "application/vnd.github.diff",
+            )?,
+            AsyncBody::default(),
+            &http_client,
+        )
+        .await?;
+
+        let mut comments = Vec::new();
+        for page in 1.. {
+            let page_comments = self
+                .get_json::<Vec<PullRequestComment>>(
+                    &format!(
+                        "{path}/comments?per_page={}&page={page}",
+                        review_api::ITEMS_PER_PAGE
+                    ),
+                    token,
+                    &http_client,
+                )
+                .await?;
+            let is_last_page = page_comments.len() < review_api::ITEMS_PER_PAGE;
+            comments.extend(page_comments);
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(PullRequestReview {
+            pull_request: PullRequest {
+                number: details.number,
+                url: Url::parse(&details.html_url)?,
+            },
+            title: details.title,
+            base_branch: details.base.ref_name,
+            head_sha: details.head.sha,
+            diff: String::from_utf8(diff).context("pull request diff is not valid UTF-8")?,
+            threads: review_threads(comments)?,
+        })
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let comment = self
+            .post_json::<PullRequestComment>(
+                &format!("repos/{owner}/{repo}/pulls/{number}/comments/{thread_id}/replies"),
+                json!({ "body": body }),
+                token,
+                &http_client,
+            )
+            .await?;
+        Ok((&comment).into())
+    }
+
+    async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let comment = self
+            .post_json::<PullRequestComment>(
+                &format!("repos/{owner}/{repo}/pulls/{number}/comments"),
+                json!({
+                    "body": comment.body,
+                    "commit_id": comment.commit_sha,
+                    "path": comment.path.as_unix_str(),
+                    "line": comment.line,
+                    "side": match comment.side {
+                        ReviewSide::Base => "LEFT",
+                        ReviewSide::Head => "RIGHT",
+                    },
+                }),
+                token,
+                &http_client,
+            )
+            .await?;
+        review_threads(vec![comment])?
+            .pop()
+            .context("GitHub returned no review comment")
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
@@ -588,4 +877,226 @@ mod tests {
             "https://github.zed.com/zed-industries/zed/pull/new/feature%2Fnew-feature"
         );
     }
+
+    fn zed_remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    #[test]
+    fn test_pull_request_review() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.headers()["Authorization"], "Bearer secret");
+            let accept = request.headers()["Accept"].to_str().unwrap().to_string();
+            let body = match (request.uri().path(), accept.as_str()) {
+                ("/repos/zed-industries/zed/pulls", _) => {
+                    assert_eq!(
+                        request.uri().query(),
+                        Some("state=open&head=zed-industries%3Afeature")
+                    );
+                    json!([{ "number": 42, "html_url": "https://github.com/zed-industries/zed/pull/42" }])
+                        .to_string()
+                }
+                ("/repos/zed-industries/zed/pulls/42", "application/vnd.github.diff") => {
+                    "diff --git a/src/main.rs b/src/main.rs\n".to_string()
+                }
+                ("/repos/zed-industries/zed/pulls/42", _) => json!({
+                    "number": 42,
+                    "html_url": "https://github.com/zed-industries/zed/pull/42",
+                    "title": "Add a feature",
+                    "base": { "ref": "main", "sha": "1111111" },
+                    "head": { "ref": "feature", "sha": "2222222" },
+                })
+                .to_string(),
+                ("/repos/zed-industries/zed/pulls/42/comments", _) => json!([
+                    {
+                        "id": 1,
+                        "path": "src/main.rs",
+                        "line": 3,
+                        "side": "RIGHT",
+                        "body": "Can this be simplified?",
+                        "user": { "login": "reviewer" },
+                        "created_at": "2024-01-01T00:00:00Z",
+                    },
+                    {
+                        "id": 2,
+                        "path": "src/lib.rs",
+                        "line": null,
+                        "side": "LEFT",
+                        "body": "Why was this removed?",
+                        "user": null,
+                        "created_at": "2024-01-01T00:01:00Z",
+                    },
+                    {
+                        "id": 3,
+                        "in_reply_to_id": 1,
+                        "path": "src/main.rs",
+                        "line": 3,
+                        "side": "RIGHT",
+                        "body": "Done.",
+                        "user": { "login": "author" },
+                        "created_at": "2024-01-01T00:02:00Z",
+                    },
+                ])
+                .to_string(),
+                (path, _) => panic!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body))
+                .unwrap())
+        });
+
+        let github = Github::public_instance();
+        let pull_request = block_on(github.open_pull_request_for_branch(
+            &zed_remote(),
+            "feature",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pull_request.number, 42);
+
+        let review = block_on(github.pull_request_review(
+            &zed_remote(),
+            pull_request.number,
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(
+            review,
+            PullRequestReview {
+                pull_request,
+                title: "Add a feature".into(),
+                base_branch: "main".into(),
+                head_sha: "2222222".into(),
+                diff: "diff --git a/src/main.rs b/src/main.rs\n".into(),
+                threads: vec![
+                    ReviewThread {
+                        id: "1".into(),
+                        path: repo_path("src/main.rs"),
+                        line: Some(3),
+                        side: ReviewSide::Head,
+                        comments: vec![
+                            ReviewComment {
+                                id: "1".into(),
+                                author: "reviewer".into(),
+                                body: "Can this be simplified?".into(),
+                                created_at: "2024-01-01T00:00:00Z".into(),
+                            },
+                            ReviewComment {
+                                id: "3".into(),
+                                author: "author".into(),
+                                body: "Done.".into(),
+                                created_at: "2024-01-01T00:02:00Z".into(),
+                            },
+                        ],
+                    },
+                    ReviewThread {
+                        id: "2".into(),
+                        path: repo_path("src/lib.rs"),
+                        line: None,
+                        side: ReviewSide::Base,
+                        comments: vec![ReviewComment {
+                            id: "2".into(),
+                            author: "ghost".into(),
+                            body: "Why was this removed?".into(),
+                            created_at: "2024-01-01T00:01:00Z".into(),
+                        }],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_post_review_comments() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|mut request| async move {
+            assert_eq!(request.method(), "POST");
+            assert_eq!(request.uri().host(), Some("github.zed.com"));
+            let mut body = String::new();
+            request.body_mut().read_to_string(&mut body).await?;
+            let body = serde_json::from_str::<serde_json::Value>(&body)?;
+
+            let response = match request.uri().path() {
+                "/api/v3/repos/zed-industries/zed/pulls/42/comments/1/replies" => {
+                    assert_eq!(body, json!({ "body": "Done." }));
+                    json!({
+                        "id": 3,
+                        "in_reply_to_id": 1,
+                        "path": "src/main.rs",
+                        "line": 3,
+                        "side": "RIGHT",
+                        "body": "Done.",
+                        "user": { "login": "author" },
+                        "created_at": "2024-01-01T00:02:00Z",
+                    })
+                }
+                "/api/v3/repos/zed-industries/zed/pulls/42/comments" => {
+                    assert_eq!(
+                        body,
+                        json!({
+                            "body": "Typo",
+                            "commit_id": "2222222",
+                            "path": "src/lib.rs",
+                            "line": 7,
+                            "side": "LEFT",
+                        })
+                    );
+                    json!({
+                        "id": 4,
+                        "path": "src/lib.rs",
+                        "line": 7,
+                        "side": "LEFT",
+                        "body": "Typo",
+                        "user": { "login": "author" },
+                        "created_at": "2024-01-01T00:03:00Z",
+                    })
+                }
+                path => panic!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(201)
+                .body(AsyncBody::from(response.to_string()))
+                .unwrap())
+        });
+
+        let github = Github::new(
+            "GitHub Self-Hosted",
+            Url::parse("https://github.zed.com").unwrap(),
+        );
+        let reply = block_on(github.reply_to_review_thread(
+            &zed_remote(),
+            42,
+            "1",
+            "Done.",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap();
+        assert_eq!(reply.id, "3");
+        assert_eq!(reply.body, "Done.");
+
+        let thread = block_on(github.create_review_comment(
+            &zed_remote(),
+            42,
+            NewReviewComment {
+                path: repo_path("src/lib.rs"),
+                line: 7,
+                side: ReviewSide::Base,
+                commit_sha: "2222222".into(),
+                body: "Typo".into(),
+            },
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(thread.id, "4");
+        assert_eq!(thread.line, Some(7));
+        assert_eq!(thread.side, ReviewSide::Base);
+    }
 }
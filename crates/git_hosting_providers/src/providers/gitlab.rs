@@ -6,15 +6,17 @@ use futures::AsyncReadExt;
 use gpui::SharedString;
 use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use serde::Deserialize;
+use serde_json::json;
 use url::Url;
 use urlencoding::encode;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, NewReviewComment,
+    ParsedGitRemote, PullRequest, PullRequestReview, RemoteUrl, ReviewComment, ReviewSide,
+    ReviewThread, repository::RepoPath,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{get_host_from_git_remote_url, review_api};
 
 #[derive(Debug, Deserialize)]
 struct CommitDetails {
@@ -26,6 +28,83 @@ struct AvatarInfo {
     avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct MergeRequestSummary {
+    iid: u32,
+    web_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequestDetails {
+    iid: u32,
+    web_url: String,
+    title: String,
+    target_branch: String,
+    /// The commits the diff of the merge request is computed from, which GitLab only sets once
+    /// the diff is ready.
+    diff_refs: Option<DiffRefs>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffRefs {
+    base_sha: String,
+    start_sha: String,
+    head_sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequestDiff {
+    old_path: String,
+    new_path: String,
+    /// The hunks of the file's diff, without the file headers.
+    diff: String,
+    new_file: bool,
+    deleted_file: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Discussion {
+    id: String,
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: u64,
+    body: String,
+    author: NoteAuthor,
+    created_at: String,
+    #[serde(default)]
+    system: bool,
+    position: Option<NotePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteAuthor {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePosition {
+    old_path: String,
+    new_path: String,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+    /// The head of the merge request when the note was made.
+    head_sha: String,
+}
+
+impl From<&Note> for ReviewComment {
+    fn from(note: &Note) -> Self {
+        Self {
+            id: note.id.to_string(),
+            author: note.author.username.clone(),
+            body: note.body.clone(),
+            created_at: note.created_at.clone(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Gitlab {
     name: String,
@@ -134,6 +213,161 @@ impl Gitlab {
     }
 }
 
+impl Gitlab {
+    /// Returns an authenticated request to the REST API endpoint of the project at the given path.
+    fn api_request(
+        &self,
+        method: http_client::Method,
+        remote: &ParsedGitRemote,
+        path: &str,
+        token: &str,
+    ) -> Result<http_client::Builder> {
+        let Some(host) = self.base_url.host_str() else {
+            bail!("failed to get host from gitlab base url");
+        };
+        let project_path = encode(&format!("{}/{}", remote.owner, remote.repo)).into_owned();
+        Ok(Request::builder()
+            .method(method)
+            .uri(format!(
+                "https://{host}/api/v4/projects/{project_path}/{path}"
+            ))
+            .header("Authorization", format!("Bearer {token}")))
+    }
+
+    /// Fetches all pages of a paginated endpoint of the project.
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(
+        &self,
+        remote: &ParsedGitRemote,
+        path: &str,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        for page in 1.. {
+            let request = self.api_request(
+                http_client::Method::GET,
+                remote,
+                &format!("{path}?per_page={}&page={page}", review_api::ITEMS_PER_PAGE),
+                token,
+            )?;
+            let page_items = review_api::get_json::<Vec<T>>(request, client).await?;
+            let is_last_page = page_items.len() < review_api::ITEMS_PER_PAGE;
+            items.extend(page_items);
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn merge_request_details(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<MergeRequestDetails> {
+        let request = self.api_request(
+            http_client::Method::GET,
+            remote,
+            &format!("merge_requests/{number}"),
+            token,
+        )?;
+        review_api::get_json(request, client).await
+    }
+}
+
+/// Joins the per-file diffs of a merge request into a unified diff.
+fn unified_diff(diffs: &[MergeRequestDiff]) -> String {
+    let mut unified_diff = String::new();
+    for diff in diffs {
+        let old_path = if diff.new_file {
+            "/dev/null".to_string()
+        } else {
+            format!("a/{}", diff.old_path)
+        };
+        let new_path = if diff.deleted_file {
+            "/dev/null".to_string()
+        } else {
+            format!("b/{}", diff.new_path)
+        };
+        unified_diff.push_str(&format!(
+            "diff --git a/{} b/{}\n--- {old_path}\n+++ {new_path}\n",
+            diff.old_path, diff.new_path
+        ));
+        unified_diff.push_str(&diff.diff);
+        if !unified_diff.ends_with('\n') {
+            unified_diff.push('\n');
+        }
+    }
+    unified_diff
+}
+
+/// Returns the line of the old file that an unchanged line of the new file was at, or `None` if
+/// the line was added.
+fn unchanged_old_line(diff: &str, new_line: u32) -> Option<u32> {
+    let new_line = i64::from(new_line);
+    let (mut old, mut new) = (1, 1);
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("@@ -") {
+            let (old_range, new_range) = header.split_once(" +")?;
+            let start = |range: &str| {
+                range
+                    .split([',', ' '])
+                    .next()
+                    .and_then(|start| start.parse::<i64>().ok())
+            };
+            let (hunk_old, hunk_new) = (start(old_range)?, start(new_range)?);
+            if new_line < hunk_new {
+                break;
+            }
+            (old, new) = (hunk_old, hunk_new);
+        } else if line.starts_with('+') {
+            if new == new_line {
+                return None;
+            }
+            new += 1;
+        } else if line.starts_with('-') {
+            old += 1;
+        } else if !line.starts_with('\\') {
+            if new == new_line {
+                return u32::try_from(old).ok();
+            }
+            old += 1;
+            new += 1;
+        }
+    }
+    u32::try_from(new_line + old - new).ok()
+}
+
+/// Returns the review thread of a discussion on the diff, or `None` for other discussions.
+fn review_thread(discussion: &Discussion, head_sha: &str) -> Result<Option<ReviewThread>> {
+    let Some(first_note) = discussion.notes.first().filter(|note| !note.system) else {
+        return Ok(None);
+    };
+    let Some(position) = &first_note.position else {
+        return Ok(None);
+    };
+    let (path, line, side) = match position.new_line {
+        Some(line) => (&position.new_path, Some(line), ReviewSide::Head),
+        None => (&position.old_path, position.old_line, ReviewSide::Base),
+    };
+    // Notes made on an earlier version of the merge request may no longer match the diff.
+    let line = line.filter(|_| position.head_sha == head_sha);
+    Ok(Some(ReviewThread {
+        id: discussion.id.clone(),
+        path: RepoPath::new(path)?,
+        line,
+        side,
+        comments: discussion
+            .notes
+            .iter()
+            .filter(|note| !note.system)
+            .map(Into::into)
+            .collect(),
+    }))
+}
+
 #[async_trait]
 impl GitHostingProvider for Gitlab {
     fn name(&self) -> String {
@@ -261,11 +495,178 @@ impl GitHostingProvider for Gitlab {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        true
+    }
+
+    async fn open_pull_request_for_branch(
+        &self,
+        remote: &ParsedGitRemote,
+        branch: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        let request = self.api_request(
+            http_client::Method::GET,
+            remote,
+            &format!(
+                "merge_requests?state=opened&source_branch={}",
+                encode(branch)
+            ),
+            token,
+        )?;
+        let merge_requests =
+            review_api::get_json::<Vec<MergeRequestSummary>>(request, &http_client).await?;
+        merge_requests
+            .into_iter()
+            .next()
+            .map(|merge_request| {
+                Ok(PullRequest {
+                    number: merge_request.iid,
+                    url: Url::parse(&merge_request.web_url)?,
+                })
+            })
+            .transpose()
+    }
+
+    async fn pull_request_review(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<PullRequestReview> {
+        let details = self
+            .merge_request_details(remote, number, token, &http_client)
+            .await?;
+        let diff_refs = details
+            .diff_refs
+            .context("the diff of the merge request isn't ready yet")?;
+        let diffs = self
+            .get_all_pages::<MergeRequestDiff>(
+                remote,
+                &format!("merge_requests/{number}/diffs"),
+                token,
+                &http_client,
+            )
+            .await?;
+        let discussions = self
+            .get_all_pages::<Discussion>(
+                remote,
+                &format!("merge_requests/{number}/discussions"),
+                token,
+                &http_client,
+            )
+            .await?;
+        let mut threads = Vec::new();
+        for discussion in &discussions {
+            threads.extend(review_thread(discussion, &diff_refs.head_sha)?);
+        }
+
+        Ok(PullRequestReview {
+            pull_request: PullRequest {
+                number: details.iid,
+                url: Url::parse(&details.web_url)?,
+            },
+            title: details.title,
+            base_branch: details.target_branch,
+            head_sha: diff_refs.head_sha,
+            diff: unified_diff(&diffs),
+            threads,
+        })
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        thread_id: &str,
+        body: &str,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        let request = self.api_request(
+            http_client::Method::POST,
+            remote,
+            &format!("merge_requests/{number}/discussions/{thread_id}/notes"),
+            token,
+        )?;
+        let note =
+            review_api::send_json::<Note>(request, json!({ "body": body }), &http_client).await?;
+        Ok((&note).into())
+    }
+
+    async fn create_review_comment(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+        comment: NewReviewComment,
+        token: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewThread> {
+        // GitLab positions notes by the commits the diff is computed from, and needs both lines of
+        // an unchanged line.
+        let details = self
+            .merge_request_details(remote, number, token, &http_client)
+            .await?;
+        let diff_refs = details
+            .diff_refs
+            .context("the diff of the merge request isn't ready yet")?;
+        let path = comment.path.as_unix_str();
+        let (old_line, new_line) = match comment.side {
+            ReviewSide::Base => (Some(comment.line), None),
+            ReviewSide::Head => {
+                let diffs = self
+                    .get_all_pages::<MergeRequestDiff>(
+                        remote,
+                        &format!("merge_requests/{number}/diffs"),
+                        token,
+                        &http_client,
+                    )
+                    .await?;
+                let old_line = match diffs.iter().find(|diff| diff.new_path == path) {
+                    Some(diff) => unchanged_old_line(&diff.diff, comment.line),
+                    None => Some(comment.line),
+                };
+                (old_line, Some(comment.line))
+            }
+        };
+
+        let request = self.api_request(
+            http_client::Method::POST,
+            remote,
+            &format!("merge_requests/{number}/discussions"),
+            token,
+        )?;
+        let discussion = review_api::send_json::<Discussion>(
+            request,
+            json!({
+                "body": comment.body,
+                "position": {
+                    "position_type": "text",
+                    "base_sha": diff_refs.base_sha,
+                    "start_sha": diff_refs.start_sha,
+                    "head_sha": diff_refs.head_sha,
+                    "old_path": path,
+                    "new_path": path,
+                    "old_line": old_line,
+                    "new_line": new_line,
+                },
+            }),
+            &http_client,
+        )
+        .await?;
+        review_thread(&discussion, &diff_refs.head_sha)?
+            .context("GitLab returned no review comment")
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::executor::block_on;
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -485,4 +886,303 @@ mod tests {
             "https://gitlab.zed.com/zed-industries/zed/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature%2Fnew-feature"
         );
     }
+
+    fn zed_remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    fn merge_request_json() -> serde_json::Value {
+        json!({
+            "iid": 42,
+            "web_url": "https://gitlab.com/zed-industries/zed/-/merge_requests/42",
+            "title": "Add a feature",
+            "target_branch": "main",
+            "diff_refs": { "base_sha": "1111111", "start_sha": "1111112", "head_sha": "2222222" },
+        })
+    }
+
+    fn diffs_json() -> serde_json::Value {
+        json!([{
+            "old_path": "src/main.rs",
+            "new_path": "src/main.rs",
+            "diff": "@@ -1,3 +1,4 @@\n fn main() {\n+    run();\n }\n \n",
+            "new_file": false,
+            "deleted_file": false,
+        }])
+    }
+
+    #[test]
+    fn test_merge_request_review() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.headers()["Authorization"], "Bearer secret");
+            let body = match request.uri().path() {
+                "/api/v4/projects/zed-industries%2Fzed/merge_requests" => {
+                    assert_eq!(
+                        request.uri().query(),
+                        Some("state=opened&source_branch=feature%2Fx")
+                    );
+                    json!([{
+                        "iid": 42,
+                        "web_url": "https://gitlab.com/zed-industries/zed/-/merge_requests/42",
+                    }])
+                }
+                "/api/v4/projects/zed-industries%2Fzed/merge_requests/42" => merge_request_json(),
+                "/api/v4/projects/zed-industries%2Fzed/merge_requests/42/diffs" => diffs_json(),
+                "/api/v4/projects/zed-industries%2Fzed/merge_requests/42/discussions" => json!([
+                    {
+                        "id": "abc",
+                        "notes": [
+                            {
+                                "id": 1,
+                                "body": "Can this be simplified?",
+                                "author": { "username": "reviewer" },
+                                "created_at": "2024-01-01T00:00:00Z",
+                                "position": {
+                                    "old_path": "src/main.rs",
+                                    "new_path": "src/main.rs",
+                                    "old_line": null,
+                                    "new_line": 2,
+                                    "head_sha": "2222222",
+                                },
+                            },
+                            {
+                                "id": 3,
+                                "body": "Done.",
+                                "author": { "username": "author" },
+                                "created_at": "2024-01-01T00:02:00Z",
+                                "position": {
+                                    "old_path": "src/main.rs",
+                                    "new_path": "src/main.rs",
+                                    "old_line": null,
+                                    "new_line": 2,
+                                    "head_sha": "2222222",
+                                },
+                            },
+                        ],
+                    },
+                    {
+                        "id": "def",
+                        "notes": [{
+                            "id": 2,
+                            "body": "Why was this removed?",
+                            "author": { "username": "reviewer" },
+                            "created_at": "2024-01-01T00:01:00Z",
+                            "position": {
+                                "old_path": "src/lib.rs",
+                                "new_path": "src/lib.rs",
+                                "old_line": 7,
+                                "new_line": null,
+                                "head_sha": "0000000",
+                            },
+                        }],
+                    },
+                    {
+                        "id": "ghi",
+                        "notes": [{
+                            "id": 4,
+                            "body": "Looks good overall",
+                            "author": { "username": "reviewer" },
+                            "created_at": "2024-01-01T00:03:00Z",
+                        }],
+                    },
+                    {
+                        "id": "jkl",
+                        "notes": [{
+                            "id": 5,
+                            "body": "added 1 commit",
+                            "author": { "username": "author" },
+                            "created_at": "2024-01-01T00:04:00Z",
+                            "system": true,
+                        }],
+                    },
+                ]),
+                path => panic!("unexpected request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body.to_string()))
+                .unwrap())
+        });
+
+        let gitlab = Gitlab::public_instance();
+        let pull_request = block_on(gitlab.open_pull_request_for_branch(
+            &zed_remote(),
+            "feature/x",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pull_request.number, 42);
+
+        let review = block_on(gitlab.pull_request_review(
+            &zed_remote(),
+            pull_request.number,
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(
+            review,
+            PullRequestReview {
+                pull_request,
+                title: "Add a feature".into(),
+                base_branch: "main".into(),
+                head_sha: "2222222".into(),
+                diff: "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,3 +1,4 @@\n fn main() {\n+    run();\n }\n \n".into(),
+                threads: vec![
+                    ReviewThread {
+                        id: "abc".into(),
+                        path: repo_path("src/main.rs"),
+                        line: Some(2),
+                        side: ReviewSide::Head,
+                        comments: vec![
+                            ReviewComment {
+                                id: "1".into(),
+                                author: "reviewer".into(),
+                                body: "Can this be simplified?".into(),
+                                created_at: "2024-01-01T00:00:00Z".into(),
+                            },
+                            ReviewComment {
+                                id: "3".into(),
+                                author: "author".into(),
+                                body: "Done.".into(),
+                                created_at: "2024-01-01T00:02:00Z".into(),
+                            },
+                        ],
+                    },
+                    ReviewThread {
+                        id: "def".into(),
+                        path: repo_path("src/lib.rs"),
+                        line: None,
+                        side: ReviewSide::Base,
+                        comments: vec![ReviewComment {
+                            id: "2".into(),
+                            author: "reviewer".into(),
+                            body: "Why was this removed?".into(),
+                            created_at: "2024-01-01T00:01:00Z".into(),
+                        }],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_post_merge_request_comments() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|mut request| async move {
+            assert_eq!(request.uri().host(), Some("gitlab.zed.com"));
+            let response = match (request.method().as_str(), request.uri().path()) {
+                ("GET", "/api/v4/projects/zed-industries%2Fzed/merge_requests/42") => {
+                    merge_request_json()
+                }
+                ("GET", "/api/v4/projects/zed-industries%2Fzed/merge_requests/42/diffs") => {
+                    diffs_json()
+                }
+                (
+                    "POST",
+                    "/api/v4/projects/zed-industries%2Fzed/merge_requests/42/discussions/abc/notes",
+                ) => {
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    assert_eq!(
+                        serde_json::from_str::<serde_json::Value>(&body)?,
+                        json!({ "body": "Done." })
+                    );
+                    json!({
+                        "id": 3,
+                        "body": "Done.",
+                        "author": { "username": "author" },
+                        "created_at": "2024-01-01T00:02:00Z",
+                    })
+                }
+                ("POST", "/api/v4/projects/zed-industries%2Fzed/merge_requests/42/discussions") => {
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    let body = serde_json::from_str::<serde_json::Value>(&body)?;
+                    // The unchanged line is at line 2 of the old file, below the added line.
+                    assert_eq!(
+                        body,
+                        json!({
+                            "body": "Typo",
+                            "position": {
+                                "position_type": "text",
+                                "base_sha": "1111111",
+                                "start_sha": "1111112",
+                                "head_sha": "2222222",
+                                "old_path": "src/main.rs",
+                                "new_path": "src/main.rs",
+                                "old_line": 2,
+                                "new_line": 3,
+                            },
+                        })
+                    );
+                    json!({
+                        "id": "xyz",
+                        "notes": [{
+                            "id": 4,
+                            "body": "Typo",
+                            "author": { "username": "author" },
+                            "created_at": "2024-01-01T00:03:00Z",
+                            "position": body["position"],
+                        }],
+                    })
+                }
+                (method, path) => panic!("unexpected {method} request to {path}"),
+            };
+            Ok(Response::builder()
+                .status(201)
+                .body(AsyncBody::from(response.to_string()))
+                .unwrap())
+        });
+
+        let gitlab = Gitlab::new(
+            "GitLab Self-Hosted",
+            Url::parse("https://gitlab.zed.com").unwrap(),
+        );
+        let reply = block_on(gitlab.reply_to_review_thread(
+            &zed_remote(),
+            42,
+            "abc",
+            "Done.",
+            "secret",
+            http_client.clone(),
+        ))
+        .unwrap();
+        assert_eq!(reply.id, "3");
+        assert_eq!(reply.body, "Done.");
+
+        let thread = block_on(gitlab.create_review_comment(
+            &zed_remote(),
+            42,
+            NewReviewComment {
+                path: repo_path("src/main.rs"),
+                line: 3,
+                side: ReviewSide::Head,
+                commit_sha: "2222222".into(),
+                body: "Typo".into(),
+            },
+            "secret",
+            http_client,
+        ))
+        .unwrap();
+        assert_eq!(thread.id, "xyz");
+        assert_eq!(thread.line, Some(3));
+        assert_eq!(thread.side, ReviewSide::Head);
+    }
+
+    #[test]
+    fn test_unchanged_old_line() {
+        let diff = "@@ -2,3 +2,4 @@\n a\n+b\n c\n-d\n@@ -10,2 +10,1 @@\n-x\n y\n";
+        assert_eq!(unchanged_old_line(diff, 1), Some(1));
+        assert_eq!(unchanged_old_line(diff, 2), Some(2));
+        assert_eq!(unchanged_old_line(diff, 3), None);
+        assert_eq!(unchanged_old_line(diff, 4), Some(3));
+        assert_eq!(unchanged_old_line(diff, 6), Some(6));
+        assert_eq!(unchanged_old_line(diff, 10), Some(11));
+        assert_eq!(unchanged_old_line(diff, 20), Some(21));
+    }
 }
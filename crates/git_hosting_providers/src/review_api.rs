@@ -0,0 +1,67 @@
+//! Requests to the REST APIs of the hosting providers that pull request reviews are fetched from
+//! and posted to.
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use futures::AsyncReadExt;
+use http_client::{AsyncBody, HttpClient, HttpRequestExt};
+use serde::de::DeserializeOwned;
+
+/// The number of items that are requested per page from paginated endpoints.
+pub(crate) const ITEMS_PER_PAGE: usize = 100;
+
+/// Sends an API request, returning the body of a successful response.
+pub(crate) async fn send_request(
+    request: http_client::Builder,
+    body: AsyncBody,
+    client: &Arc<dyn HttpClient>,
+) -> Result<Vec<u8>> {
+    let request = request
+        .follow_redirects(http_client::RedirectPolicy::FollowAll)
+        .body(body)?;
+    let url = request.uri().to_string();
+
+    let mut response = client
+        .send(request)
+        .await
+        .with_context(|| format!("error sending request to {url:?}"))?;
+
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+
+    if !response.status().is_success() {
+        let text = String::from_utf8_lossy(body.as_slice());
+        bail!(
+            "status error {}, response: {text:?}",
+            response.status().as_u16()
+        );
+    }
+
+    Ok(body)
+}
+
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    request: http_client::Builder,
+    client: &Arc<dyn HttpClient>,
+) -> Result<T> {
+    let url = request.uri_ref().map(ToString::to_string).unwrap_or_default();
+    let body = send_request(request, AsyncBody::default(), client).await?;
+    serde_json::from_slice(&body).with_context(|| format!("failed to deserialize {url:?}"))
+}
+
+/// Sends a JSON body with the request, and deserializes the JSON response.
+pub(crate) async fn send_json<T: DeserializeOwned>(
+    request: http_client::Builder,
+    body: serde_json::Value,
+    client: &Arc<dyn HttpClient>,
+) -> Result<T> {
+    let url = request.uri_ref().map(ToString::to_string).unwrap_or_default();
+    let body = send_request(
+        request.header("Content-Type", "application/json"),
+        AsyncBody::from(serde_json::to_string(&body)?),
+        client,
+    )
+    .await?;
+    serde_json::from_slice(&body).with_context(|| format!("failed to deserialize {url:?}"))
+}
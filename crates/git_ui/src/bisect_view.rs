@@ -0,0 +1,987 @@
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use git::repository::{BisectCommand, BisectStatus};
+use gpui::{
+    AnyElement, App, AsyncWindowContext, Context, Corner, Entity, EventEmitter, FocusHandle,
+    Focusable, Task, WeakEntity, Window, actions,
+};
+use project::{Project, git_store::Repository};
+use std::process::ExitStatus;
+use task::{TaskContext, TaskTemplate};
+use ui::{ContextMenu, ListItem, PopoverMenu, Tooltip, prelude::*};
+use workspace::{Item, Workspace, item::ItemEvent};
+
+use crate::commit_view::CommitView;
+
+actions!(
+    git,
+    [
+        /// Starts bisecting between the chosen bad and good revisions.
+        StartBisect,
+        /// Marks the commit being bisected as good.
+        BisectGood,
+        /// Marks the commit being bisected as bad.
+        BisectBad,
+        /// Skips the commit being bisected, because it can't be tested.
+        BisectSkip,
+        /// Ends the bisect and checks out the revision it started from.
+        BisectReset,
+        /// Runs the test task on every commit of the bisect, marking each one by its exit code.
+        RunBisect,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::Bisect, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        BisectView::open(workspace, repository, window, cx);
+    });
+}
+
+/// The exit code with which a test command tells `git bisect run` that a commit can't be tested.
+const SKIP_EXIT_CODE: i32 = 125;
+
+/// A commit that was marked during the bisect.
+struct BisectStep {
+    commit: SharedString,
+    subject: Option<SharedString>,
+    command: BisectCommand,
+}
+
+/// Guides a `git bisect` of a repository: picking the bad and good revisions, marking each commit
+/// it checks out, and optionally running a task to test the commits automatically.
+pub struct BisectView {
+    repository: Entity<Repository>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    bad_editor: Entity<Editor>,
+    good_editor: Entity<Editor>,
+    status: BisectStatus,
+    /// The subject of the commit being tested, once it's loaded.
+    testing_subject: Option<SharedString>,
+    steps: Vec<BisectStep>,
+    test_tasks: Vec<TaskTemplate>,
+    test_task: Option<TaskTemplate>,
+    /// Whether the test task is being run on every commit.
+    running_automatically: bool,
+    pending_operation: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    _load_tasks: Task<()>,
+}
+
+impl BisectView {
+    /// Activates the bisect view of the repository, opening one if there's none.
+    pub(crate) fn open(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        if let Some(existing) = workspace
+            .items_of_type::<BisectView>(cx)
+            .find(|view| view.read(cx).repository == repository)
+        {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return existing;
+        }
+
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new(|cx| BisectView::new(repository, project, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+        view
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let bad_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bad revision (default: HEAD)", window, cx);
+            editor
+        });
+        let good_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Good revisions, separated by spaces", window, cx);
+            editor
+        });
+
+        let mut this = Self {
+            repository,
+            project,
+            workspace,
+            bad_editor,
+            good_editor,
+            status: BisectStatus::Inactive,
+            testing_subject: None,
+            steps: Vec::new(),
+            test_tasks: Vec::new(),
+            test_task: None,
+            running_automatically: false,
+            pending_operation: None,
+            focus_handle: cx.focus_handle(),
+            _load_tasks: Task::ready(()),
+        };
+        this.load_test_tasks(cx);
+        this
+    }
+
+    #[cfg(test)]
+    pub(crate) fn status(&self) -> &BisectStatus {
+        &self.status
+    }
+
+    fn load_test_tasks(&mut self, cx: &mut Context<Self>) {
+        let project = self.project.read(cx);
+        let Some(inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+            return;
+        };
+        let work_directory = self.repository.read(cx).work_directory_abs_path.clone();
+        let worktree_id = project
+            .find_worktree(&work_directory, cx)
+            .map(|(worktree, _)| worktree.read(cx).id());
+        let tasks = inventory.read(cx).list_tasks(None, None, worktree_id, cx);
+        self._load_tasks = cx.spawn(async move |this, cx| {
+            let tasks = tasks.await;
+            this.update(cx, |this, cx| {
+                this.test_tasks = tasks.into_iter().map(|(_, template)| template).collect();
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    pub(crate) fn set_test_task(&mut self, task: Option<TaskTemplate>, cx: &mut Context<Self>) {
+        self.test_task = task;
+        cx.notify();
+    }
+
+    /// Uses the revision as the bad revision of the next bisect, or adds it to its good revisions.
+    pub(crate) fn mark_revision(
+        &mut self,
+        revision: &str,
+        good: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if good {
+            let mut text = self.good_editor.read(cx).text(cx);
+            if text.split_whitespace().any(|good| good == revision) {
+                return;
+            }
+            if !text.trim().is_empty() {
+                text.push(' ');
+            }
+            text.push_str(revision);
+            self.good_editor
+                .update(cx, |editor, cx| editor.set_text(text, window, cx));
+        } else {
+            self.bad_editor
+                .update(cx, |editor, cx| editor.set_text(revision, window, cx));
+        }
+    }
+
+    fn start_bisect(&mut self, _: &StartBisect, window: &mut Window, cx: &mut Context<Self>) {
+        if self.status != BisectStatus::Inactive {
+            return;
+        }
+        let bad = self.bad_editor.read(cx).text(cx).trim().to_string();
+        let bad = if bad.is_empty() { "HEAD".into() } else { bad };
+        let good = self
+            .good_editor
+            .read(cx)
+            .text(cx)
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|revision| !revision.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        if good.is_empty() {
+            window
+                .prompt(
+                    gpui::PromptLevel::Info,
+                    "Pick at least one good revision to start bisecting",
+                    None,
+                    &["Ok"],
+                    cx,
+                )
+                .detach();
+            return;
+        }
+        self.run_command(BisectCommand::Start { bad, good }, window, cx);
+    }
+
+    pub(crate) fn run_command(
+        &mut self,
+        command: BisectCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        let bisect = self
+            .repository
+            .update(cx, |repository, _| repository.bisect(command.clone()));
+        self.run_operation(
+            command_error_message(&command),
+            async move |this, cx| {
+                let status = bisect.await??;
+                this.update_in(cx, |this, window, cx| {
+                    this.apply_status(command, status, window, cx)
+                })?;
+                Ok(())
+            },
+            window,
+            cx,
+        );
+    }
+
+    /// Runs the test task on the commit being tested, marks the commit by the task's exit code,
+    /// and repeats until the first bad commit is found, like `git bisect run`.
+    fn run_bisect(&mut self, _: &RunBisect, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() || !matches!(self.status, BisectStatus::Testing { .. })
+        {
+            return;
+        }
+        let Some(template) = self.test_task.clone() else {
+            return;
+        };
+        let task_context = TaskContext {
+            cwd: Some(
+                self.repository
+                    .read(cx)
+                    .work_directory_abs_path
+                    .to_path_buf(),
+            ),
+            ..TaskContext::default()
+        };
+        let Some(resolved_task) = template.resolve_task("git-bisect", &task_context) else {
+            prompt_error(
+                "Failed to run the bisect",
+                &anyhow::anyhow!("the task \"{}\" can't be resolved", template.label),
+                window,
+                cx,
+            );
+            return;
+        };
+        let spawn_in_terminal = resolved_task.resolved;
+        let repository = self.repository.clone();
+        let workspace = self.workspace.clone();
+        self.running_automatically = true;
+        self.run_operation(
+            "Failed to run the bisect",
+            async move |this, cx| {
+                loop {
+                    let exit_status = workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.spawn_in_terminal(spawn_in_terminal.clone(), window, cx)
+                        })?
+                        .await;
+                    let command = command_for_exit_status(exit_status)?;
+                    let status = repository
+                        .update(cx, |repository, _| repository.bisect(command.clone()))?
+                        .await??;
+                    let is_done = !matches!(status, BisectStatus::Testing { .. });
+                    this.update_in(cx, |this, window, cx| {
+                        this.apply_status(command, status, window, cx)
+                    })?;
+                    if is_done {
+                        return Ok(());
+                    }
+                }
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn stop(&mut self, cx: &mut Context<Self>) {
+        self.pending_operation = None;
+        self.running_automatically = false;
+        cx.notify();
+    }
+
+    fn run_operation(
+        &mut self,
+        error_message: &'static str,
+        operation: impl AsyncFnOnce(WeakEntity<Self>, &mut AsyncWindowContext) -> Result<()> + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.pending_operation = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = operation(this.clone(), cx).await;
+            this.update_in(cx, |this, window, cx| {
+                this.pending_operation = None;
+                this.running_automatically = false;
+                cx.notify();
+                if let Err(error) = result {
+                    prompt_error(error_message, &error, window, cx);
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn apply_status(
+        &mut self,
+        command: BisectCommand,
+        status: BisectStatus,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match command {
+            BisectCommand::Start { .. } | BisectCommand::Reset => self.steps.clear(),
+            BisectCommand::Good | BisectCommand::Bad | BisectCommand::Skip => {
+                if let BisectStatus::Testing { commit, .. } = &self.status {
+                    self.steps.push(BisectStep {
+                        commit: commit.clone(),
+                        subject: self.testing_subject.clone(),
+                        command,
+                    });
+                }
+            }
+        }
+        self.testing_subject = None;
+        match &status {
+            BisectStatus::Testing { commit, .. } => self.load_subject(commit.clone(), cx),
+            BisectStatus::FirstBad { commit } => self.open_commit(commit, window, cx),
+            BisectStatus::Inconclusive { .. } | BisectStatus::Inactive => {}
+        }
+        self.status = status;
+        cx.notify();
+    }
+
+    fn load_subject(&mut self, commit: SharedString, cx: &mut Context<Self>) {
+        let details = self
+            .repository
+            .update(cx, |repository, _| repository.show(commit.to_string()));
+        cx.spawn(async move |this, cx| {
+            let details = details.await??;
+            this.update(cx, |this, cx| {
+                let is_testing = matches!(
+                    &this.status,
+                    BisectStatus::Testing { commit: testing, .. } if *testing == commit
+                );
+                if is_testing {
+                    let subject = details.message.lines().next().unwrap_or_default();
+                    this.testing_subject = Some(subject.to_string().into());
+                    cx.notify();
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_commit(&self, commit: &str, window: &mut Window, cx: &mut Context<Self>) {
+        CommitView::open(
+            commit.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn render_test_task_menu(&self, cx: &Context<Self>) -> impl IntoElement {
+        let view = cx.weak_entity();
+        let tasks = self.test_tasks.clone();
+        let selected_label = self.test_task.as_ref().map(|task| task.label.clone());
+        let trigger_label = selected_label
+            .clone()
+            .unwrap_or_else(|| "Mark Manually".into());
+        PopoverMenu::new("bisect-test-task")
+            .trigger(
+                Button::new("bisect-test-task-trigger", trigger_label)
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_position(IconPosition::End),
+            )
+            .menu(move |window, cx| {
+                let view = view.clone();
+                let tasks = tasks.clone();
+                let selected_label = selected_label.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    let none_view = view.clone();
+                    menu = menu.toggleable_entry(
+                        "Mark Manually",
+                        selected_label.is_none(),
+                        IconPosition::Start,
+                        None,
+                        move |_, cx| {
+                            none_view
+                                .update(cx, |view, cx| view.set_test_task(None, cx))
+                                .ok();
+                        },
+                    );
+                    if !tasks.is_empty() {
+                        menu = menu.separator().header("Tasks");
+                    }
+                    for task in tasks {
+                        let view = view.clone();
+                        let is_selected = selected_label.as_ref() == Some(&task.label);
+                        menu = menu.toggleable_entry(
+                            task.label.clone(),
+                            is_selected,
+                            IconPosition::Start,
+                            None,
+                            move |_, cx| {
+                                view.update(cx, |view, cx| {
+                                    view.set_test_task(Some(task.clone()), cx)
+                                })
+                                .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+            .anchor(Corner::TopLeft)
+    }
+
+    fn render_test_task(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_2()
+            .child(
+                Label::new("Test with")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.render_test_task_menu(cx))
+            .child(
+                Label::new("Exit code 0 marks a commit good, 125 skips it, 1–127 mark it bad.")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    fn render_revisions(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let editor_row = |label: &'static str, editor: &Entity<Editor>| {
+            h_flex()
+                .gap_2()
+                .child(
+                    div()
+                        .w(rems_from_px(120.))
+                        .flex_none()
+                        .child(Label::new(label).size(LabelSize::Small)),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .px_2()
+                        .py_1()
+                        .border_1()
+                        .rounded_sm()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(editor.clone()),
+                )
+        };
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .child(
+                Label::new(
+                    "Pick a bad revision and the good revisions to search between, \
+                     or mark them from the context menu of the commit graph.",
+                )
+                .color(Color::Muted),
+            )
+            .child(editor_row("Bad revision", &self.bad_editor))
+            .child(editor_row("Good revisions", &self.good_editor))
+            .child(self.render_test_task(cx))
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("start-bisect", "Start Bisect")
+                        .style(ButtonStyle::Filled)
+                        .disabled(self.pending_operation.is_some())
+                        .key_binding(ui::KeyBinding::for_action_in(
+                            &StartBisect,
+                            &self.focus_handle,
+                            cx,
+                        ))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.start_bisect(&StartBisect, window, cx)
+                        })),
+                ),
+            )
+    }
+
+    fn render_testing(
+        &self,
+        commit: &SharedString,
+        remaining: usize,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_pending = self.pending_operation.is_some();
+        let remaining = match remaining {
+            0 => "This is the last revision left to test".to_string(),
+            1 => "1 revision left to test after this one".to_string(),
+            remaining => format!("{remaining} revisions left to test after this one"),
+        };
+        let commit_sha = commit.clone();
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new("Testing"))
+                    .child(
+                        Button::new("bisect-testing-commit", short_sha(commit))
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::text("Open Commit"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.open_commit(&commit_sha, window, cx)
+                            })),
+                    )
+                    .children(self.testing_subject.clone().map(|subject| {
+                        Label::new(subject).color(Color::Muted).truncate()
+                    })),
+            )
+            .child(
+                Label::new(remaining)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.render_test_task(cx))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("bisect-good", "Good")
+                            .style(ButtonStyle::Filled)
+                            .disabled(is_pending)
+                            .key_binding(ui::KeyBinding::for_action_in(
+                                &BisectGood,
+                                &self.focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(BisectCommand::Good, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("bisect-bad", "Bad")
+                            .style(ButtonStyle::Filled)
+                            .disabled(is_pending)
+                            .key_binding(ui::KeyBinding::for_action_in(
+                                &BisectBad,
+                                &self.focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(BisectCommand::Bad, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("bisect-skip", "Skip")
+                            .disabled(is_pending)
+                            .tooltip(Tooltip::text("Leave out a commit that can't be tested"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(BisectCommand::Skip, window, cx)
+                            })),
+                    )
+                    .map(|this| {
+                        if self.running_automatically {
+                            this.child(
+                                Button::new("bisect-stop", "Stop")
+                                    .icon(IconName::Stop)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon_position(IconPosition::Start)
+                                    .on_click(cx.listener(|this, _, _, cx| this.stop(cx))),
+                            )
+                        } else {
+                            this.child(
+                                Button::new("bisect-run", "Run Automatically")
+                                    .icon(IconName::PlayFilled)
+                                    .icon_size(IconSize::XSmall)
+                                    .icon_position(IconPosition::Start)
+                                    .disabled(is_pending || self.test_task.is_none())
+                                    .tooltip(Tooltip::text(
+                                        "Test every commit with the task until the first bad one is found",
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_bisect(&RunBisect, window, cx)
+                                    })),
+                            )
+                        }
+                    }),
+            )
+    }
+
+    fn render_result(&self, cx: &mut Context<Self>) -> AnyElement {
+        match &self.status {
+            BisectStatus::FirstBad { commit } => {
+                let commit_sha = commit.clone();
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .child(Label::new(format!(
+                        "{} is the first bad commit",
+                        short_sha(commit)
+                    )))
+                    .child(
+                        Button::new("bisect-open-first-bad", "Open Commit").on_click(cx.listener(
+                            move |this, _, window, cx| this.open_commit(&commit_sha, window, cx),
+                        )),
+                    )
+                    .into_any_element()
+            }
+            BisectStatus::Inconclusive { candidates } => v_flex()
+                .p_2()
+                .gap_1()
+                .child(Label::new(
+                    "Only skipped commits are left. The first bad commit could be any of:",
+                ))
+                .children(candidates.iter().enumerate().map(|(ix, commit)| {
+                    let commit_sha = commit.clone();
+                    ListItem::new(("bisect-candidate", ix))
+                        .child(
+                            Label::new(short_sha(commit))
+                                .size(LabelSize::Small)
+                                .buffer_font(cx),
+                        )
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_commit(&commit_sha, window, cx)
+                        }))
+                }))
+                .into_any_element(),
+            BisectStatus::Testing { .. } | BisectStatus::Inactive => gpui::Empty.into_any_element(),
+        }
+    }
+
+    fn render_steps(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new("Marked Commits")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(self.steps.iter().enumerate().map(|(ix, step)| {
+                let (label, color) = match step.command {
+                    BisectCommand::Good => ("good", Color::Success),
+                    BisectCommand::Bad => ("bad", Color::Error),
+                    _ => ("skip", Color::Muted),
+                };
+                let commit_sha = step.commit.clone();
+                ListItem::new(("bisect-step", ix))
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .w(rems_from_px(40.))
+                                    .flex_none()
+                                    .child(Label::new(label).size(LabelSize::Small).color(color)),
+                            )
+                            .child(
+                                Label::new(short_sha(&step.commit))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .buffer_font(cx),
+                            )
+                            .children(step.subject.clone().map(|subject| {
+                                Label::new(subject).size(LabelSize::Small).truncate()
+                            })),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_commit(&commit_sha, window, cx)
+                    }))
+            }))
+    }
+}
+
+/// Maps the exit code of a test command to a bisect step, the same way `git bisect run` does.
+fn command_for_exit_status(exit_status: Option<Result<ExitStatus>>) -> Result<BisectCommand> {
+    let exit_status = exit_status.context("the test task was cancelled")??;
+    match exit_status.code() {
+        Some(0) => Ok(BisectCommand::Good),
+        Some(SKIP_EXIT_CODE) => Ok(BisectCommand::Skip),
+        Some(1..=127) => Ok(BisectCommand::Bad),
+        code => anyhow::bail!("the test task was aborted with exit code {code:?}"),
+    }
+}
+
+fn command_error_message(command: &BisectCommand) -> &'static str {
+    match command {
+        BisectCommand::Start { .. } => "Failed to start the bisect",
+        BisectCommand::Good | BisectCommand::Bad | BisectCommand::Skip => {
+            "Failed to mark the commit"
+        }
+        BisectCommand::Reset => "Failed to reset the bisect",
+    }
+}
+
+fn short_sha(sha: &str) -> String {
+    sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha).to_string()
+}
+
+fn prompt_error(message: &str, error: &anyhow::Error, window: &mut Window, cx: &mut App) {
+    window
+        .prompt(
+            gpui::PromptLevel::Critical,
+            message,
+            Some(&format!("{error:#}")),
+            &["Ok"],
+            cx,
+        )
+        .detach();
+}
+
+impl EventEmitter<ItemEvent> for BisectView {}
+
+impl Focusable for BisectView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for BisectView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.status {
+            BisectStatus::Inactive => "Not bisecting".to_string(),
+            BisectStatus::Testing { .. } => format!("Bisecting, {} marked", self.steps.len()),
+            BisectStatus::FirstBad { .. } => "Found the first bad commit".to_string(),
+            BisectStatus::Inconclusive { .. } => "The bisect is inconclusive".to_string(),
+        };
+        let is_active = self.status != BisectStatus::Inactive;
+
+        v_flex()
+            .id("bisect-view")
+            .key_context("BisectView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::start_bisect))
+            .on_action(cx.listener(Self::run_bisect))
+            .on_action(cx.listener(|this, _: &BisectGood, window, cx| {
+                this.run_command(BisectCommand::Good, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &BisectBad, window, cx| {
+                this.run_command(BisectCommand::Bad, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &BisectSkip, window, cx| {
+                this.run_command(BisectCommand::Skip, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &BisectReset, window, cx| {
+                this.run_command(BisectCommand::Reset, window, cx)
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).color(Color::Muted))
+                    .when(is_active, |this| {
+                        this.child(
+                            Button::new("bisect-reset", "Reset")
+                                .disabled(self.pending_operation.is_some())
+                                .tooltip(Tooltip::text(
+                                    "End the bisect and check out the revision it started from",
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.run_command(BisectCommand::Reset, window, cx)
+                                })),
+                        )
+                    }),
+            )
+            .map(|this| match self.status.clone() {
+                BisectStatus::Inactive => this.child(self.render_revisions(cx)),
+                BisectStatus::Testing { commit, remaining } => {
+                    this.child(self.render_testing(&commit, remaining, cx))
+                }
+                BisectStatus::FirstBad { .. } | BisectStatus::Inconclusive { .. } => {
+                    this.child(self.render_result(cx))
+                }
+            })
+            .when(!self.steps.is_empty(), |this| {
+                this.child(self.render_steps(cx))
+            })
+    }
+}
+
+impl Item for BisectView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Bisect".into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(format!("Bisect of {}", self.repository.read(cx).display_name()).into())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Debug))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git bisect")
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::LogEntry;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn entry(sha: &str, parent: Option<&str>) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parents: parent
+                .into_iter()
+                .map(|sha| sha.to_string().into())
+                .collect(),
+            refs: Vec::new(),
+            subject: format!("Commit {sha}").into(),
+            commit_timestamp: 0,
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_bisect_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        let shas = [
+            "8888", "7777", "6666", "5555", "4444", "3333", "2222", "1111",
+        ];
+        fs.with_git_state(dot_git, false, |state| {
+            state.log = shas
+                .iter()
+                .zip(shas.iter().skip(1).map(Some).chain([None]))
+                .map(|(sha, parent)| entry(sha, parent.copied()))
+                .collect();
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        cx.dispatch_action(git::Bisect);
+        cx.run_until_parked();
+        let view = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<BisectView>(cx)
+            })
+            .expect("bisect view should be open");
+
+        // 5555 introduced the change, so it and every later commit are bad.
+        view.update_in(cx, |view, window, cx| {
+            view.mark_revision("8888", false, window, cx);
+            view.mark_revision("1111", true, window, cx);
+            view.start_bisect(&StartBisect, window, cx);
+        });
+        cx.run_until_parked();
+
+        let mut marked = Vec::new();
+        loop {
+            let testing = view.read_with(cx, |view, _| match view.status() {
+                BisectStatus::Testing { commit, .. } => Some(commit.clone()),
+                _ => None,
+            });
+            let Some(testing) = testing else {
+                break;
+            };
+            let command = if testing.as_ref() >= "5555" {
+                BisectCommand::Bad
+            } else {
+                BisectCommand::Good
+            };
+            marked.push(testing);
+            view.update_in(cx, |view, window, cx| view.run_command(command, window, cx));
+            cx.run_until_parked();
+        }
+        assert!(marked.len() <= 3, "bisect took too many steps: {marked:?}");
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.status(),
+                &BisectStatus::FirstBad {
+                    commit: "5555".into()
+                }
+            );
+            assert_eq!(view.steps.len(), marked.len());
+        });
+
+        // The first bad commit is opened.
+        let commit_view = workspace.read_with(cx, |workspace, cx| {
+            workspace.active_item_as::<CommitView>(cx)
+        });
+        assert!(commit_view.is_some());
+
+        view.update_in(cx, |view, window, cx| {
+            view.run_command(BisectCommand::Reset, window, cx)
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.status(), &BisectStatus::Inactive);
+            assert!(view.steps.is_empty());
+        });
+        assert!(
+            fs.with_git_state(dot_git, false, |state| state.bisect.is_none())
+                .unwrap()
+        );
+    }
+}
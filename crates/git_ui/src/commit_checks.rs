@@ -0,0 +1,183 @@
+use editor::Editor;
+use git::commit_checks::CommitCheckFinding;
+use gpui::{
+    AnyElement, App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, WeakEntity,
+};
+use ui::{Tooltip, prelude::*};
+use util::paths::PathStyle;
+use workspace::ModalView;
+
+use crate::git_panel::GitPanel;
+
+/// The number of findings that are listed before the rest are summarized.
+const MAX_VISIBLE_FINDINGS: usize = 5;
+
+/// Renders the findings of the commit checks, with a button to commit anyway.
+pub(crate) fn render_findings(
+    findings: &[CommitCheckFinding],
+    path_style: PathStyle,
+    on_override: impl Fn(&mut Window, &mut App) + 'static,
+    cx: &App,
+) -> AnyElement {
+    let title = if findings.len() == 1 {
+        "Commit checks found 1 problem".to_string()
+    } else {
+        format!("Commit checks found {} problems", findings.len())
+    };
+
+    v_flex()
+        .p_1p5()
+        .gap_0p5()
+        .border_t_1()
+        .border_color(cx.theme().colors().border.opacity(0.8))
+        .child(
+            h_flex()
+                .gap_1()
+                .justify_between()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(title).size(LabelSize::Small)),
+                )
+                .child(
+                    Button::new("override-commit-checks", "Commit Anyway…")
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text(
+                            "Commit despite the problems, recording a reason in the commit message",
+                        ))
+                        .on_click(move |_, window, cx| on_override(window, cx)),
+                ),
+        )
+        .children(findings.iter().take(MAX_VISIBLE_FINDINGS).map(|finding| {
+            let mut location = finding.path.display(path_style).into_owned();
+            if let Some(line) = finding.line {
+                location.push_str(&format!(":{line}"));
+            }
+            h_flex()
+                .pl_4()
+                .gap_1()
+                .min_w_0()
+                .child(
+                    Label::new(location)
+                        .size(LabelSize::Small)
+                        .buffer_font(cx)
+                        .truncate(),
+                )
+                .child(
+                    Label::new(finding.kind.description())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+        }))
+        .when(findings.len() > MAX_VISIBLE_FINDINGS, |this| {
+            this.child(
+                Label::new(format!(
+                    "and {} more",
+                    findings.len() - MAX_VISIBLE_FINDINGS
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .ml_4(),
+            )
+        })
+        .into_any_element()
+}
+
+/// Asks for the reason to commit despite the findings of the commit checks.
+pub(crate) struct CommitCheckOverrideModal {
+    git_panel: WeakEntity<GitPanel>,
+    finding_count: usize,
+    editor: Entity<Editor>,
+}
+
+impl EventEmitter<DismissEvent> for CommitCheckOverrideModal {}
+impl ModalView for CommitCheckOverrideModal {}
+impl Focusable for CommitCheckOverrideModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl CommitCheckOverrideModal {
+    pub fn new(
+        git_panel: WeakEntity<GitPanel>,
+        finding_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Reason for committing anyway", window, cx);
+            editor
+        });
+        Self {
+            git_panel,
+            finding_count,
+            editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let reason = self.editor.read(cx).text(cx).trim().to_string();
+        if reason.is_empty() {
+            return;
+        }
+        self.git_panel
+            .update(cx, |git_panel, cx| {
+                git_panel.commit_with_override(reason, window, cx)
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for CommitCheckOverrideModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let problems = if self.finding_count == 1 {
+            "1 problem".to_string()
+        } else {
+            format!("{} problems", self.finding_count)
+        };
+
+        v_flex()
+            .key_context("CommitCheckOverride")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                v_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_0p5()
+                    .child(Headline::new("Commit Anyway").size(HeadlineSize::XSmall))
+                    .child(
+                        Label::new(format!(
+                            "The commit checks found {problems}. The reason you give is recorded in the commit message."
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+    }
+}
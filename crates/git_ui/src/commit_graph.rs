@@ -0,0 +1,288 @@
+//! Assigns commits to lanes so that the log can be drawn as a graph of branches and merges.
+
+use git::repository::LogEntry;
+use gpui::SharedString;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphLineKind {
+    /// Crosses the whole row, staying in its lane.
+    PassThrough,
+    /// Comes from the top of the row into the row's commit.
+    Incoming,
+    /// Leaves the row's commit towards the bottom of the row, to one of its parents.
+    Outgoing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphLine {
+    pub kind: GraphLineKind,
+    pub from_lane: usize,
+    pub to_lane: usize,
+    /// Picks the color of the line, stable along a branch.
+    pub color: usize,
+}
+
+/// The part of the graph drawn next to a single commit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The lane the commit is drawn in.
+    pub lane: usize,
+    pub color: usize,
+    pub lines: Vec<GraphLine>,
+}
+
+impl GraphRow {
+    /// The number of lanes this row needs to be drawn.
+    pub fn lane_count(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.from_lane.max(line.to_lane))
+            .chain([self.lane])
+            .max()
+            .map_or(0, |lane| lane + 1)
+    }
+}
+
+/// Lays out the commits of a log, in the order git lists them, with children before parents.
+///
+/// Commits are added incrementally, so that the graph can grow as more of the log is loaded.
+#[derive(Default)]
+pub struct CommitGraph {
+    /// The commit each lane leads to, and the color of that lane.
+    lanes: Vec<Option<(SharedString, usize)>>,
+    next_color: usize,
+    rows: Vec<GraphRow>,
+    lane_count: usize,
+}
+
+impl CommitGraph {
+    pub fn new(entries: &[LogEntry]) -> Self {
+        let mut graph = Self::default();
+        graph.extend(entries);
+        graph
+    }
+
+    pub fn extend(&mut self, entries: &[LogEntry]) {
+        for entry in entries {
+            self.push(&entry.sha, &entry.parents);
+        }
+    }
+
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    /// The number of lanes needed to draw every row.
+    pub fn lane_count(&self) -> usize {
+        self.lane_count
+    }
+
+    fn push(&mut self, sha: &SharedString, parents: &[SharedString]) {
+        let incoming_lanes = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, lane)| lane.as_ref().is_some_and(|(target, _)| target == sha))
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        let (lane, color) = match incoming_lanes.first() {
+            Some(&lane) => (
+                lane,
+                self.lanes[lane].as_ref().map_or(0, |(_, color)| *color),
+            ),
+            None => (self.free_lane(), self.allocate_color()),
+        };
+
+        let mut lines = Vec::new();
+        for (ix, target) in self.lanes.iter().enumerate() {
+            let Some((_, line_color)) = target else {
+                continue;
+            };
+            let kind = if incoming_lanes.contains(&ix) {
+                GraphLineKind::Incoming
+            } else {
+                GraphLineKind::PassThrough
+            };
+            let to_lane = if kind == GraphLineKind::Incoming {
+                lane
+            } else {
+                ix
+            };
+            lines.push(GraphLine {
+                kind,
+                from_lane: ix,
+                to_lane,
+                color: *line_color,
+            });
+        }
+        for ix in incoming_lanes {
+            self.lanes[ix] = None;
+        }
+        if self.lanes.len() <= lane {
+            self.lanes.resize(lane + 1, None);
+        }
+
+        for (parent_ix, parent) in parents.iter().enumerate() {
+            let existing_lane = self
+                .lanes
+                .iter()
+                .position(|target| target.as_ref().is_some_and(|(target, _)| target == parent));
+            let (to_lane, line_color) = match existing_lane {
+                // Another child already leads to this parent, so join its lane.
+                Some(existing_lane) => (
+                    existing_lane,
+                    self.lanes[existing_lane]
+                        .as_ref()
+                        .map_or(color, |(_, color)| *color),
+                ),
+                None => {
+                    let (to_lane, line_color) = if parent_ix == 0 && self.lanes[lane].is_none() {
+                        (lane, color)
+                    } else {
+                        (self.free_lane(), self.allocate_color())
+                    };
+                    if self.lanes.len() <= to_lane {
+                        self.lanes.resize(to_lane + 1, None);
+                    }
+                    self.lanes[to_lane] = Some((parent.clone(), line_color));
+                    (to_lane, line_color)
+                }
+            };
+            lines.push(GraphLine {
+                kind: GraphLineKind::Outgoing,
+                from_lane: lane,
+                to_lane,
+                color: line_color,
+            });
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        let row = GraphRow { lane, color, lines };
+        self.lane_count = self.lane_count.max(row.lane_count());
+        self.rows.push(row);
+    }
+
+    fn free_lane(&self) -> usize {
+        self.lanes
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(self.lanes.len())
+    }
+
+    fn allocate_color(&mut self) -> usize {
+        let color = self.next_color;
+        self.next_color += 1;
+        color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            refs: Vec::new(),
+            subject: SharedString::default(),
+            commit_timestamp: 0,
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+        }
+    }
+
+    fn lines(row: &GraphRow) -> Vec<(GraphLineKind, usize, usize)> {
+        row.lines
+            .iter()
+            .map(|line| (line.kind, line.from_lane, line.to_lane))
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let graph = CommitGraph::new(&[entry("c", &["b"]), entry("b", &["a"]), entry("a", &[])]);
+        assert_eq!(graph.lane_count(), 1);
+        for row in graph.rows() {
+            assert_eq!(row.lane, 0);
+            assert_eq!(row.color, graph.rows()[0].color);
+        }
+        assert_eq!(lines(&graph.rows()[0]), [(GraphLineKind::Outgoing, 0, 0)]);
+        assert_eq!(
+            lines(&graph.rows()[1]),
+            [
+                (GraphLineKind::Incoming, 0, 0),
+                (GraphLineKind::Outgoing, 0, 0)
+            ]
+        );
+        assert_eq!(lines(&graph.rows()[2]), [(GraphLineKind::Incoming, 0, 0)]);
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        // m merges feature (f) into main (c); both branched off a.
+        let graph = CommitGraph::new(&[
+            entry("m", &["c", "f"]),
+            entry("f", &["a"]),
+            entry("c", &["a"]),
+            entry("a", &[]),
+        ]);
+        assert_eq!(graph.lane_count(), 2);
+        let rows = graph.rows();
+
+        assert_eq!(rows[0].lane, 0);
+        assert_eq!(
+            lines(&rows[0]),
+            [
+                (GraphLineKind::Outgoing, 0, 0),
+                (GraphLineKind::Outgoing, 0, 1)
+            ]
+        );
+
+        assert_eq!(rows[1].lane, 1);
+        assert_eq!(
+            lines(&rows[1]),
+            [
+                (GraphLineKind::PassThrough, 0, 0),
+                (GraphLineKind::Incoming, 1, 1),
+                (GraphLineKind::Outgoing, 1, 1)
+            ]
+        );
+
+        // The main line continues to a, and the feature lane joins it there.
+        assert_eq!(rows[2].lane, 0);
+        assert_eq!(
+            lines(&rows[2]),
+            [
+                (GraphLineKind::Incoming, 0, 0),
+                (GraphLineKind::PassThrough, 1, 1),
+                (GraphLineKind::Outgoing, 0, 1)
+            ]
+        );
+        assert_eq!(rows[3].lane, 1);
+        assert_eq!(lines(&rows[3]), [(GraphLineKind::Incoming, 1, 1)]);
+        assert_ne!(rows[0].color, rows[1].color);
+        assert_eq!(rows[3].color, rows[1].color);
+    }
+
+    #[test]
+    fn test_extend_continues_lanes() {
+        let entries = [
+            entry("d", &["b"]),
+            entry("c", &["a"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ];
+        let mut graph = CommitGraph::new(&entries[..2]);
+        graph.extend(&entries[2..]);
+        assert_eq!(graph.rows(), CommitGraph::new(&entries).rows());
+        assert_eq!(graph.rows()[1].lane, 1);
+        assert_eq!(graph.rows()[2].lane, 0);
+    }
+}
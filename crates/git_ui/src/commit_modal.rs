@@ -1,7 +1,7 @@
 use crate::branch_picker::{self, BranchList};
 use crate::git_panel::{GitPanel, commit_message_editor};
 use git::repository::CommitOptions;
-use git::{Amend, Commit, GenerateCommitMessage, Signoff};
+use git::{Amend, Commit, GenerateCommitMessage, Sign, Signoff};
 use panel::{panel_button, panel_editor_style};
 use project::DisableAiSettings;
 use settings::Settings;
@@ -281,6 +281,7 @@ impl CommitModal {
                     let git_panel = git_panel_entity.read(cx);
                     let amend_enabled = git_panel.amend_pending();
                     let signoff_enabled = git_panel.signoff_enabled();
+                    let sign_enabled = git_panel.sign_enabled();
                     let has_previous_commit = git_panel.head_commit(cx).is_some();
 
                     Some(ContextMenu::build(window, cx, |context_menu, _, _| {
@@ -320,6 +321,20 @@ impl CommitModal {
                                     }
                                 },
                             )
+                            .toggleable_entry(
+                                "Sign",
+                                sign_enabled,
+                                IconPosition::Start,
+                                Some(Box::new(Sign)),
+                                {
+                                    let git_panel = git_panel_entity.clone();
+                                    move |window, cx| {
+                                        git_panel.update(cx, |git_panel, cx| {
+                                            git_panel.toggle_sign_enabled(&Sign, window, cx);
+                                        })
+                                    }
+                                },
+                            )
                     }))
                 }
             })
@@ -337,6 +352,7 @@ impl CommitModal {
             active_repo,
             is_amend_pending,
             is_signoff_enabled,
+            is_sign_enabled,
             workspace,
         ) = self.git_panel.update(cx, |git_panel, cx| {
             let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
@@ -346,6 +362,7 @@ impl CommitModal {
             let active_repo = git_panel.active_repository.clone();
             let is_amend_pending = git_panel.amend_pending();
             let is_signoff_enabled = git_panel.signoff_enabled();
+            let is_sign_enabled = git_panel.sign_enabled();
             (
                 can_commit,
                 tooltip,
@@ -355,6 +372,7 @@ impl CommitModal {
                 active_repo,
                 is_amend_pending,
                 is_signoff_enabled,
+                is_sign_enabled,
                 git_panel.workspace.clone(),
             )
         });
@@ -452,6 +470,7 @@ impl CommitModal {
                                     CommitOptions {
                                         amend: is_amend_pending,
                                         signoff: is_signoff_enabled,
+                                        sign: is_sign_enabled,
                                     },
                                     window,
                                     cx,
@@ -472,9 +491,10 @@ impl CommitModal {
                                             &git::Commit
                                         }),
                                         format!(
-                                            "git commit{}{}",
+                                            "git commit{}{}{}",
                                             if is_amend_pending { " --amend" } else { "" },
-                                            if is_signoff_enabled { " --signoff" } else { "" }
+                                            if is_signoff_enabled { " --signoff" } else { "" },
+                                            if is_sign_enabled { " --gpg-sign" } else { "" }
                                         ),
                                         &focus_handle.clone(),
                                         cx,
@@ -536,6 +556,9 @@ impl Render for CommitModal {
         let container_padding = px(properties.container_padding);
         let border_radius = properties.modal_border_radius;
         let editor_focus_handle = self.commit_editor.focus_handle(cx);
+        let commit_check_findings = self.git_panel.update(cx, |git_panel, cx| {
+            git_panel.render_commit_check_findings(cx)
+        });
 
         v_flex()
             .id("commit-modal")
@@ -597,6 +620,7 @@ impl Render for CommitModal {
                             .size_full()
                             .child(self.render_commit_editor(window, cx)),
                     )
+                    .children(commit_check_findings)
                     .child(self.render_footer(window, cx)),
             )
     }
@@ -2,11 +2,11 @@ use crate::commit_view::CommitView;
 use editor::hover_markdown_style;
 use futures::Future;
 use git::blame::BlameEntry;
-use git::repository::CommitSummary;
+use git::repository::{CommitSignature, CommitSummary, SignatureStatus};
 use git::{GitRemote, commit::ParsedCommitMessage};
 use gpui::{
     App, Asset, Element, Entity, MouseButton, ParentElement, Render, ScrollHandle,
-    StatefulInteractiveElement, WeakEntity, prelude::*,
+    StatefulInteractiveElement, Task, WeakEntity, prelude::*,
 };
 use markdown::{Markdown, MarkdownElement};
 use project::git_store::Repository;
@@ -14,7 +14,7 @@ use settings::Settings;
 use std::hash::Hash;
 use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
-use ui::{Avatar, CopyButton, Divider, prelude::*, tooltip_container};
+use ui::{Avatar, CopyButton, Divider, Tooltip, prelude::*, tooltip_container};
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -82,6 +82,51 @@ impl<'a> CommitAvatar<'a> {
     }
 }
 
+/// Shows whether the signature of a commit could be verified.
+#[derive(IntoElement)]
+pub struct SignatureBadge {
+    signature: CommitSignature,
+}
+
+impl SignatureBadge {
+    pub fn new(signature: CommitSignature) -> Self {
+        Self { signature }
+    }
+}
+
+impl RenderOnce for SignatureBadge {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        let (icon, color, label) = match self.signature.status {
+            SignatureStatus::Verified => (IconName::ShieldCheck, Color::Success, "Verified"),
+            SignatureStatus::Unverified => (IconName::Warning, Color::Error, "Unverified"),
+            SignatureStatus::UnknownKey => (IconName::Info, Color::Warning, "Unknown Key"),
+        };
+        let description = match self.signature.status {
+            SignatureStatus::Verified => "Signed with a trusted key",
+            SignatureStatus::Unverified => {
+                "The signature is invalid, or its key has expired or was revoked"
+            }
+            SignatureStatus::UnknownKey => "Signed with a key that git can't find or doesn't trust",
+        };
+        let meta = match (&self.signature.signer, &self.signature.key) {
+            (Some(signer), Some(key)) => Some(format!("{signer} ({key})")),
+            (Some(signer), None) => Some(signer.to_string()),
+            (None, Some(key)) => Some(key.to_string()),
+            (None, None) => None,
+        };
+
+        h_flex()
+            .id("commit-signature")
+            .gap_0p5()
+            .child(Icon::new(icon).size(IconSize::XSmall).color(color))
+            .child(Label::new(label).size(LabelSize::Small).color(color))
+            .tooltip(move |_, cx| match meta.clone() {
+                Some(meta) => Tooltip::with_meta(description, None, meta, cx),
+                None => Tooltip::simple(description, cx),
+            })
+    }
+}
+
 #[derive(Clone, Debug)]
 struct CommitAvatarAsset {
     sha: SharedString,
@@ -123,10 +168,12 @@ impl Asset for CommitAvatarAsset {
 
 pub struct CommitTooltip {
     commit: CommitDetails,
+    signature: Option<CommitSignature>,
     scroll_handle: ScrollHandle,
     markdown: Entity<Markdown>,
     repository: Entity<Repository>,
     workspace: WeakEntity<Workspace>,
+    _load_signature: Task<()>,
 }
 
 impl CommitTooltip {
@@ -178,12 +225,26 @@ impl CommitTooltip {
                 cx,
             )
         });
+        let signature = repository.update(cx, |repository, _| {
+            repository.commit_signature(commit.sha.to_string())
+        });
+        let load_signature = cx.spawn(async move |this, cx| {
+            if let Ok(Ok(signature)) = signature.await {
+                this.update(cx, |this, cx| {
+                    this.signature = signature;
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
         Self {
             commit,
+            signature: None,
             repository,
             workspace,
             scroll_handle: ScrollHandle::new(),
             markdown,
+            _load_signature: load_signature,
         }
     }
 }
@@ -232,6 +293,7 @@ impl Render for CommitTooltip {
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
         let repo = self.repository.clone();
         let workspace = self.workspace.clone();
+        let signature = self.signature.clone();
         let commit_summary = CommitSummary {
             sha: self.commit.sha.clone(),
             subject: self
@@ -295,7 +357,12 @@ impl Render for CommitTooltip {
                                 .pt_1p5()
                                 .border_t_1()
                                 .border_color(cx.theme().colors().border_variant)
-                                .child(absolute_timestamp)
+                                .child(
+                                    h_flex()
+                                        .gap_2()
+                                        .child(absolute_timestamp)
+                                        .children(signature.map(SignatureBadge::new)),
+                                )
                                 .child(
                                     h_flex()
                                         .gap_1p5()
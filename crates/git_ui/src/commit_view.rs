@@ -2,7 +2,8 @@ use anyhow::{Context as _, Result};
 use buffer_diff::BufferDiff;
 use editor::display_map::{BlockPlacement, BlockProperties, BlockStyle};
 use editor::{Editor, EditorEvent, ExcerptRange, MultiBuffer, multibuffer_context_lines};
-use git::repository::{CommitDetails, CommitDiff, RepoPath};
+use futures::channel::oneshot;
+use git::repository::{CommitDetails, CommitDiff, CommitSignature, RepoPath};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, ParsedGitRemote,
     parse_git_remote_url,
@@ -36,7 +37,7 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
-use crate::commit_tooltip::CommitAvatar;
+use crate::commit_tooltip::{CommitAvatar, SignatureBadge};
 use crate::git_panel::GitPanel;
 
 actions!(git, [ApplyCurrentStash, PopCurrentStash, DropCurrentStash,]);
@@ -58,11 +59,16 @@ pub fn init(cx: &mut App) {
 
 pub struct CommitView {
     commit: CommitDetails,
+    /// The commit the changes are compared against, when comparing two commits rather than
+    /// showing the changes made by a single one.
+    base: Option<SharedString>,
     editor: Entity<Editor>,
     stash: Option<usize>,
     multibuffer: Entity<MultiBuffer>,
     repository: Entity<Repository>,
     remote: Option<GitRemote>,
+    signature: Option<CommitSignature>,
+    _load_signature: Task<()>,
 }
 
 struct GitBlob {
@@ -88,6 +94,57 @@ impl CommitView {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit_sha.clone()))
             .ok();
+        Self::open_diff(
+            commit_sha,
+            None,
+            commit_diff,
+            repo,
+            workspace,
+            stash,
+            file_filter,
+            window,
+            cx,
+        );
+    }
+
+    /// Opens the changes between two commits.
+    pub fn open_comparison(
+        base_sha: String,
+        commit_sha: String,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let commit_diff = repo
+            .update(cx, |repo, _| {
+                repo.compare_commits(base_sha.clone(), commit_sha.clone())
+            })
+            .ok();
+        Self::open_diff(
+            commit_sha,
+            Some(base_sha.into()),
+            commit_diff,
+            repo,
+            workspace,
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn open_diff(
+        commit_sha: String,
+        base: Option<SharedString>,
+        commit_diff: Option<oneshot::Receiver<Result<CommitDiff>>>,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        stash: Option<usize>,
+        file_filter: Option<RepoPath>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
         let commit_details = repo
             .update(cx, |repo, _| repo.show(commit_sha.clone()))
             .ok();
@@ -111,6 +168,7 @@ impl CommitView {
                         let commit_view = cx.new(|cx| {
                             CommitView::new(
                                 commit_details,
+                                base.clone(),
                                 commit_diff,
                                 repo,
                                 project.clone(),
@@ -124,8 +182,10 @@ impl CommitView {
                         pane.update(cx, |pane, cx| {
                             let ix = pane.items().position(|item| {
                                 let commit_view = item.downcast::<CommitView>();
-                                commit_view
-                                    .is_some_and(|view| view.read(cx).commit.sha == commit_sha)
+                                commit_view.is_some_and(|view| {
+                                    let view = view.read(cx);
+                                    view.commit.sha == commit_sha && view.base == base
+                                })
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
@@ -141,6 +201,7 @@ impl CommitView {
 
     fn new(
         commit: CommitDetails,
+        base: Option<SharedString>,
         commit_diff: CommitDiff,
         repository: Entity<Repository>,
         project: Entity<Project>,
@@ -304,13 +365,34 @@ impl CommitView {
             })
         });
 
+        // Verifying a signature runs gpg or ssh-keygen, so only do it once the view is open.
+        let load_signature = if stash.is_none() {
+            let signature = repository.update(cx, |repository, _| {
+                repository.commit_signature(commit.sha.to_string())
+            });
+            cx.spawn(async move |this, cx| {
+                if let Ok(Ok(signature)) = signature.await {
+                    this.update(cx, |this, cx| {
+                        this.signature = signature;
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            })
+        } else {
+            Task::ready(())
+        };
+
         Self {
             commit,
+            base,
             editor,
             multibuffer,
             stash,
             repository,
             remote,
+            signature: None,
+            _load_signature: load_signature,
         }
     }
 
@@ -469,6 +551,15 @@ impl CommitView {
                                 h_flex()
                                     .gap_1()
                                     .child(Label::new(author_name).color(Color::Default))
+                                    .when_some(self.base.as_ref(), |this, base| {
+                                        let short_base = base.get(0..7).unwrap_or(base);
+                                        this.child(
+                                            Label::new(format!("{short_base}.."))
+                                                .color(Color::Muted)
+                                                .size(LabelSize::Small)
+                                                .buffer_font(cx),
+                                        )
+                                    })
                                     .child({
                                         ButtonLike::new("sha")
                                             .child(
@@ -519,6 +610,14 @@ impl CommitView {
                                             .color(Color::Muted)
                                             .size(LabelSize::Small),
                                     )
+                                    .when_some(self.signature.clone(), |this, signature| {
+                                        this.child(
+                                            Label::new("•")
+                                                .color(Color::Ignored)
+                                                .size(LabelSize::Small),
+                                        )
+                                        .child(SignatureBadge::new(signature))
+                                    })
                                     .child(
                                         Label::new("•")
                                             .color(Color::Ignored)
@@ -839,6 +938,10 @@ impl Item for CommitView {
 
     fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
         let short_sha = self.commit.sha.get(0..7).unwrap_or(&*self.commit.sha);
+        if let Some(base) = &self.base {
+            let short_base = base.get(0..7).unwrap_or(base);
+            return format!("{short_base}..{short_sha}").into();
+        }
         let subject = truncate_and_trailoff(self.commit.message.split('\n').next().unwrap(), 20);
         format!("{short_sha} — {subject}").into()
     }
@@ -959,9 +1062,12 @@ impl Item for CommitView {
                 editor,
                 multibuffer,
                 commit: self.commit.clone(),
+                base: self.base.clone(),
                 stash: self.stash,
                 repository: self.repository.clone(),
                 remote: self.remote.clone(),
+                signature: self.signature.clone(),
+                _load_signature: Task::ready(()),
             }
         })))
     }
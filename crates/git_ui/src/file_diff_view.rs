@@ -162,7 +162,7 @@ impl FileDiffView {
     }
 }
 
-async fn build_buffer_diff(
+pub(crate) async fn build_buffer_diff(
     old_buffer: &Entity<Buffer>,
     new_buffer: &Entity<Buffer>,
     language_registry: Arc<LanguageRegistry>,
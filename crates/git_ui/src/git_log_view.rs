@@ -0,0 +1,1133 @@
+use anyhow::{Context as _, Result};
+use editor::{Editor, EditorEvent};
+use git::repository::{CommitOperation, LogEntry, LogFilter, RepoPath};
+use gpui::{
+    AnyElement, App, Bounds, ClickEvent, Context, Corner, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, MouseButton, MouseDownEvent, PathBuilder, Pixels, Point,
+    ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, actions,
+    anchored, canvas, deferred, fill, point, px, size, uniform_list,
+};
+use menu::{Cancel, Confirm};
+use project::git_store::{GitStore, Repository, RepositoryEvent};
+use std::time::Duration;
+use time::OffsetDateTime;
+use ui::{Chip, ContextMenu, Divider, WithScrollbar, prelude::*};
+use workspace::{Item, ModalView, Workspace, item::ItemEvent, notifications::DetachAndPromptErr};
+
+use crate::{
+    bisect_view::BisectView,
+    commit_graph::{CommitGraph, GraphLineKind, GraphRow},
+    commit_view::CommitView,
+};
+
+actions!(
+    git,
+    [
+        /// Checks out the selected commit of the repository log, detaching HEAD.
+        CheckoutCommit,
+        /// Applies the changes made by the selected commit of the repository log on top of HEAD.
+        CherryPickCommit,
+        /// Creates a commit undoing the changes made by the selected commit of the repository log.
+        RevertCommit,
+        /// Creates a branch at the selected commit of the repository log.
+        CreateBranchAtCommit,
+        /// Creates a tag at the selected commit of the repository log.
+        CreateTagAtCommit,
+        /// Marks the selected commit of the repository log as the base of a comparison.
+        SelectCommitForCompare,
+        /// Compares the selected commit of the repository log with the one marked for comparison.
+        CompareWithMarkedCommit,
+        /// Uses the selected commit of the repository log as the bad revision of a bisect.
+        MarkCommitBadForBisect,
+        /// Adds the selected commit of the repository log to the good revisions of a bisect.
+        MarkCommitGoodForBisect,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::ViewLog, window, cx| {
+        GitLogView::deploy(workspace, window, cx);
+    });
+}
+
+const PAGE_SIZE: usize = 200;
+const LANE_WIDTH: Pixels = px(14.);
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A repository-wide log of commits, drawn as a graph of branches and merges.
+pub struct GitLogView {
+    repository: Entity<Repository>,
+    git_store: WeakEntity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    entries: Vec<LogEntry>,
+    graph: CommitGraph,
+    filter: LogFilter,
+    filter_error: Option<SharedString>,
+    path_editor: Entity<Editor>,
+    author_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+    since_editor: Entity<Editor>,
+    until_editor: Entity<Editor>,
+    selected_entry: Option<usize>,
+    /// The commit marked as the base of a comparison.
+    compare_base: Option<LogEntry>,
+    loading: bool,
+    has_more: bool,
+    load_task: Task<()>,
+    filter_task: Task<()>,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitLogView {
+    fn deploy(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let project = workspace.project().clone();
+        let Some(repository) = project.read(cx).active_repository(cx) else {
+            return;
+        };
+        if let Some(existing) = workspace
+            .items_of_type::<GitLogView>(cx)
+            .find(|view| view.read(cx).repository == repository)
+        {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let git_store = project.read(cx).git_store().downgrade();
+        let workspace_handle = workspace.weak_handle();
+        let view =
+            cx.new(|cx| GitLogView::new(repository, git_store, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    pub(crate) fn new(
+        repository: Entity<Repository>,
+        git_store: WeakEntity<GitStore>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let filter_editor = |placeholder: &str, window: &mut Window, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text(placeholder, window, cx);
+                editor
+            })
+        };
+        let path_editor = filter_editor("Path", window, cx);
+        let author_editor = filter_editor("Author", window, cx);
+        let message_editor = filter_editor("Message", window, cx);
+        let since_editor = filter_editor("Since (YYYY-MM-DD)", window, cx);
+        let until_editor = filter_editor("Until (YYYY-MM-DD)", window, cx);
+
+        let mut subscriptions = [
+            &path_editor,
+            &author_editor,
+            &message_editor,
+            &since_editor,
+            &until_editor,
+        ]
+        .into_iter()
+        .map(|editor| {
+            cx.subscribe_in(editor, window, |this, _, event, window, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.filter_changed(window, cx);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+        subscriptions.push(
+            cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event, window, cx| match event {
+                    RepositoryEvent::BranchChanged | RepositoryEvent::MergeHeadsChanged => {
+                        this.reload(window, cx);
+                    }
+                    _ => {}
+                },
+            ),
+        );
+
+        let mut this = Self {
+            repository,
+            git_store,
+            workspace,
+            entries: Vec::new(),
+            graph: CommitGraph::default(),
+            filter: LogFilter::default(),
+            filter_error: None,
+            path_editor,
+            author_editor,
+            message_editor,
+            since_editor,
+            until_editor,
+            selected_entry: None,
+            compare_base: None,
+            loading: false,
+            has_more: true,
+            load_task: Task::ready(()),
+            filter_task: Task::ready(()),
+            context_menu: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _subscriptions: subscriptions,
+        };
+        this.reload(window, cx);
+        this
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    fn filter_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_task = cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(FILTER_DEBOUNCE).await;
+            this.update_in(cx, |this, window, cx| {
+                match this.filter_from_editors(cx) {
+                    Ok(filter) => {
+                        this.filter_error = None;
+                        if filter != this.filter {
+                            this.filter = filter;
+                            this.reload(window, cx);
+                        }
+                    }
+                    Err(error) => this.filter_error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn filter_from_editors(&self, cx: &App) -> Result<LogFilter> {
+        let text = |editor: &Entity<Editor>| {
+            let text = editor.read(cx).text(cx);
+            let text = text.trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let path = text(&self.path_editor)
+            .map(|path| RepoPath::new(&path))
+            .transpose()
+            .context("invalid path")?;
+        let since = text(&self.since_editor)
+            .map(|date| parse_date(&date))
+            .transpose()?;
+        let until = text(&self.until_editor)
+            .map(|date| parse_date(&date))
+            .transpose()?
+            // Include the whole day.
+            .map(|until| until + 24 * 60 * 60 - 1);
+        Ok(LogFilter {
+            path,
+            author: text(&self.author_editor),
+            message: text(&self.message_editor),
+            since,
+            until,
+        })
+    }
+
+    /// Discards the loaded commits and loads the first page of the log again.
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.graph = CommitGraph::default();
+        self.selected_entry = None;
+        self.has_more = true;
+        self.loading = false;
+        self.load_more(window, cx);
+    }
+
+    fn load_more(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.loading || !self.has_more {
+            return;
+        }
+        let Some(git_store) = self.git_store.upgrade() else {
+            return;
+        };
+        self.loading = true;
+        cx.notify();
+
+        let skip = self.entries.len();
+        let filter = self.filter.clone();
+        let log = git_store.update(cx, |git_store, cx| {
+            git_store.log_paginated(&self.repository, filter.clone(), skip, Some(PAGE_SIZE), cx)
+        });
+        self.load_task = cx.spawn_in(window, async move |this, cx| {
+            let entries = log.await;
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                // The filter might have changed while the page was loading.
+                if this.filter != filter || this.entries.len() != skip {
+                    return;
+                }
+                match entries {
+                    Ok(entries) => {
+                        this.has_more = entries.len() >= PAGE_SIZE;
+                        this.graph.extend(&entries);
+                        this.entries.extend(entries);
+                        if this.selected_entry.is_none() && !this.entries.is_empty() {
+                            this.selected_entry = Some(0);
+                        }
+                    }
+                    Err(error) => {
+                        this.has_more = false;
+                        this.filter_error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_entry {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1).min(self.entries.len() - 1)),
+        };
+        self.select_ix(ix, window, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_entry {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some(ix.saturating_sub(1)),
+        };
+        self.select_ix(ix, window, cx);
+    }
+
+    fn select_first(&mut self, _: &menu::SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
+        let ix = (!self.entries.is_empty()).then_some(0);
+        self.select_ix(ix, window, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, window: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.entries.len().checked_sub(1);
+        self.select_ix(ix, window, cx);
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+            if ix + 1 == self.entries.len() {
+                self.load_more(window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn selected_entry(&self) -> Option<&LogEntry> {
+        self.entries.get(self.selected_entry?)
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        CommitView::open(
+            entry.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn checkout_commit(&mut self, _: &CheckoutCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_commit_operation(CommitOperation::Checkout, window, cx);
+    }
+
+    fn cherry_pick_commit(
+        &mut self,
+        _: &CherryPickCommit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_commit_operation(CommitOperation::CherryPick, window, cx);
+    }
+
+    fn revert_commit(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_commit_operation(CommitOperation::Revert, window, cx);
+    }
+
+    fn run_commit_operation(
+        &mut self,
+        operation: CommitOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let error_message = match operation {
+            CommitOperation::Checkout => "Failed to check out commit",
+            CommitOperation::CherryPick => "Failed to cherry-pick commit",
+            CommitOperation::Revert => "Failed to revert commit",
+        };
+        let task = self.repository.update(cx, |repository, _| {
+            repository.commit_operation(operation, entry.sha.to_string())
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            task.await??;
+            this.update_in(cx, |this, window, cx| this.reload(window, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(error_message, window, cx, |_, _, _| None);
+    }
+
+    fn create_branch_at_commit(
+        &mut self,
+        _: &CreateBranchAtCommit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.create_ref_at_commit(RefKind::Branch, window, cx);
+    }
+
+    fn create_tag_at_commit(
+        &mut self,
+        _: &CreateTagAtCommit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.create_ref_at_commit(RefKind::Tag, window, cx);
+    }
+
+    fn create_ref_at_commit(&mut self, kind: RefKind, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.selected_entry().cloned() else {
+            return;
+        };
+        let repository = self.repository.clone();
+        let log_view = cx.weak_entity();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    CreateRefModal::new(kind, entry, repository, log_view, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    fn select_commit_for_compare(
+        &mut self,
+        _: &SelectCommitForCompare,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.compare_base = self.selected_entry().cloned();
+        cx.notify();
+    }
+
+    fn compare_with_marked_commit(
+        &mut self,
+        _: &CompareWithMarkedCommit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(base), Some(entry)) = (self.compare_base.as_ref(), self.selected_entry()) else {
+            return;
+        };
+        CommitView::open_comparison(
+            base.sha.to_string(),
+            entry.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn mark_commit_bad_for_bisect(
+        &mut self,
+        _: &MarkCommitBadForBisect,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.mark_commit_for_bisect(false, window, cx);
+    }
+
+    fn mark_commit_good_for_bisect(
+        &mut self,
+        _: &MarkCommitGoodForBisect,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.mark_commit_for_bisect(true, window, cx);
+    }
+
+    fn mark_commit_for_bisect(&mut self, good: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sha) = self.selected_entry().map(|entry| entry.sha.clone()) else {
+            return;
+        };
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let view = BisectView::open(workspace, repository, window, cx);
+                view.update(cx, |view, cx| view.mark_revision(&sha, good, window, cx));
+            })
+            .ok();
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let compare_label = self.compare_base.as_ref().map(|base| {
+            format!(
+                "Compare with {}",
+                base.sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(&base.sha)
+            )
+        });
+        let is_compare_base = self
+            .compare_base
+            .as_ref()
+            .is_some_and(|base| base.sha == entry.sha);
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("Open Commit", Confirm.boxed_clone())
+                .separator()
+                .action("Checkout", CheckoutCommit.boxed_clone())
+                .action("Cherry-Pick", CherryPickCommit.boxed_clone())
+                .action("Revert", RevertCommit.boxed_clone())
+                .separator()
+                .action("Create Branch…", CreateBranchAtCommit.boxed_clone())
+                .action("Create Tag…", CreateTagAtCommit.boxed_clone())
+                .separator()
+                .action("Select for Compare", SelectCommitForCompare.boxed_clone())
+                .when_some(compare_label, |context_menu, label| {
+                    context_menu.action_disabled_when(
+                        is_compare_base,
+                        label,
+                        CompareWithMarkedCommit.boxed_clone(),
+                    )
+                })
+                .separator()
+                .action(
+                    "Mark as Bad for Bisect",
+                    MarkCommitBadForBisect.boxed_clone(),
+                )
+                .action(
+                    "Mark as Good for Bisect",
+                    MarkCommitGoodForBisect.boxed_clone(),
+                )
+        });
+        self.selected_entry = Some(ix);
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let is_selected = self.selected_entry == Some(ix);
+        let is_compare_base = self
+            .compare_base
+            .as_ref()
+            .is_some_and(|base| base.sha == entry.sha);
+        let commit_time = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+            .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+        let date = time_format::format_localized_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC),
+            time_format::TimestampFormat::MediumAbsolute,
+        );
+        let short_sha = entry
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&entry.sha)
+            .to_string();
+        let colors = cx.theme().colors();
+
+        h_flex()
+            .id(("log-entry", ix))
+            .h_7()
+            .w_full()
+            .pr_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(colors.element_selected))
+            .when(!is_selected, |this| {
+                this.hover(|this| this.bg(colors.element_hover))
+            })
+            .child(self.render_graph(ix))
+            .child(
+                h_flex()
+                    .min_w_0()
+                    .w_full()
+                    .gap_1()
+                    .children(entry.refs.iter().map(|name| render_ref(name, cx)))
+                    .when(is_compare_base, |this| {
+                        this.child(Chip::new("compare base").label_color(Color::Accent))
+                    })
+                    .child(
+                        Label::new(entry.subject.clone())
+                            .size(LabelSize::Small)
+                            .truncate(),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_none()
+                    .gap_3()
+                    .child(
+                        Label::new(entry.author_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                    .child(Label::new(date).size(LabelSize::Small).color(Color::Muted))
+                    .child(
+                        Label::new(short_sha)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    ),
+            )
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.selected_entry = Some(ix);
+                window.focus(&this.focus_handle, cx);
+                if event.click_count() > 1 {
+                    this.confirm(&Confirm, window, cx);
+                }
+                cx.notify();
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn render_graph(&self, ix: usize) -> impl IntoElement {
+        let row = self.graph.rows().get(ix).cloned();
+        let width = LANE_WIDTH * self.graph.lane_count().max(1) as f32;
+        canvas(
+            |_, _, _| {},
+            move |bounds, _, window, cx| {
+                if let Some(row) = &row {
+                    paint_graph_row(row, bounds, window, cx);
+                }
+            },
+        )
+        .flex_none()
+        .w(width + LANE_WIDTH / 2.)
+        .h_full()
+    }
+
+    fn render_filters(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let border = cx.theme().colors().border_variant;
+        let field = |editor: &Entity<Editor>| {
+            div()
+                .flex_1()
+                .min_w_0()
+                .px_1p5()
+                .py_0p5()
+                .border_1()
+                .rounded_sm()
+                .border_color(border)
+                .child(editor.clone())
+        };
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(border)
+            .child(field(&self.path_editor))
+            .child(field(&self.author_editor))
+            .child(field(&self.message_editor))
+            .child(field(&self.since_editor))
+            .child(field(&self.until_editor))
+    }
+}
+
+fn paint_graph_row(row: &GraphRow, bounds: Bounds<Pixels>, window: &mut Window, cx: &mut App) {
+    let accents = cx.theme().accents();
+    let x = |lane: usize| bounds.origin.x + LANE_WIDTH * (lane as f32 + 0.5);
+    let top = bounds.origin.y;
+    let middle = bounds.center().y;
+    let bottom = bounds.bottom();
+
+    for line in &row.lines {
+        let (start, end, control) = match line.kind {
+            GraphLineKind::PassThrough => (
+                point(x(line.from_lane), top),
+                point(x(line.to_lane), bottom),
+                None,
+            ),
+            GraphLineKind::Incoming => (
+                point(x(line.from_lane), top),
+                point(x(line.to_lane), middle),
+                Some(point(x(line.from_lane), middle)),
+            ),
+            GraphLineKind::Outgoing => (
+                point(x(line.from_lane), middle),
+                point(x(line.to_lane), bottom),
+                Some(point(x(line.to_lane), middle)),
+            ),
+        };
+        let mut builder = PathBuilder::stroke(px(1.5));
+        builder.move_to(start);
+        match control {
+            Some(control) if start.x != end.x => builder.curve_to(end, control),
+            _ => builder.line_to(end),
+        }
+        if let Ok(path) = builder.build() {
+            window.paint_path(path, accents.color_for_index(line.color as u32));
+        }
+    }
+
+    let radius = px(4.);
+    window.paint_quad(
+        fill(
+            Bounds::centered_at(point(x(row.lane), middle), size(radius * 2., radius * 2.)),
+            accents.color_for_index(row.color as u32),
+        )
+        .corner_radii(radius),
+    );
+}
+
+fn render_ref(name: &SharedString, cx: &App) -> impl IntoElement {
+    let (label, color) = if let Some(branch) = name.strip_prefix("HEAD -> ") {
+        (branch.to_string(), Color::Accent)
+    } else if let Some(tag) = name.strip_prefix("tag: ") {
+        (tag.to_string(), Color::Warning)
+    } else if name.as_ref() == "HEAD" {
+        ("HEAD".to_string(), Color::Accent)
+    } else {
+        (name.to_string(), Color::Default)
+    };
+    Chip::new(label)
+        .label_color(color)
+        .bg_color(cx.theme().colors().element_background)
+}
+
+/// Parses a `YYYY-MM-DD` date into the Unix timestamp of its start, in UTC.
+fn parse_date(date: &str) -> Result<i64> {
+    let parse = || -> Option<i64> {
+        let mut parts = date.split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month: u8 = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        let date =
+            time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day).ok()?;
+        Some(date.midnight().assume_utc().unix_timestamp())
+    };
+    parse().with_context(|| format!("invalid date {date:?}, expected YYYY-MM-DD"))
+}
+
+#[derive(Clone, Copy)]
+enum RefKind {
+    Branch,
+    Tag,
+}
+
+/// Asks for the name of a branch or tag to create at a commit.
+struct CreateRefModal {
+    kind: RefKind,
+    entry: LogEntry,
+    editor: Entity<Editor>,
+    repository: Entity<Repository>,
+    log_view: WeakEntity<GitLogView>,
+}
+
+impl CreateRefModal {
+    fn new(
+        kind: RefKind,
+        entry: LogEntry,
+        repository: Entity<Repository>,
+        log_view: WeakEntity<GitLogView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                match kind {
+                    RefKind::Branch => "Branch name",
+                    RefKind::Tag => "Tag name",
+                },
+                window,
+                cx,
+            );
+            editor
+        });
+        Self {
+            kind,
+            entry,
+            editor,
+            repository,
+            log_view,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            cx.emit(DismissEvent);
+            return;
+        }
+
+        let sha = self.entry.sha.to_string();
+        let (task, error_message) = match self.kind {
+            RefKind::Branch => (
+                self.repository.update(cx, |repository, _| {
+                    repository.create_branch(name, Some(sha))
+                }),
+                "Failed to create branch",
+            ),
+            RefKind::Tag => (
+                self.repository
+                    .update(cx, |repository, _| repository.create_tag(name, sha, None)),
+                "Failed to create tag",
+            ),
+        };
+        let log_view = self.log_view.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            task.await??;
+            log_view.update_in(cx, |log_view, window, cx| log_view.reload(window, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(error_message, window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateRefModal {}
+impl ModalView for CreateRefModal {}
+impl Focusable for CreateRefModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateRefModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let short_sha = self
+            .entry
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&self.entry.sha);
+        let (icon, title) = match self.kind {
+            RefKind::Branch => (IconName::GitBranch, "Create Branch"),
+            RefKind::Tag => (IconName::Hash, "Create Tag"),
+        };
+        v_flex()
+            .key_context("CreateRefModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(icon).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("{title} at {short_sha}")).size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(div().px_3().pb_3().w_full().child(self.editor.clone()))
+    }
+}
+
+impl EventEmitter<ItemEvent> for GitLogView {}
+
+impl Focusable for GitLogView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for GitLogView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+
+        v_flex()
+            .id("git-log-view")
+            .key_context("GitLogView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::checkout_commit))
+            .on_action(cx.listener(Self::cherry_pick_commit))
+            .on_action(cx.listener(Self::revert_commit))
+            .on_action(cx.listener(Self::create_branch_at_commit))
+            .on_action(cx.listener(Self::create_tag_at_commit))
+            .on_action(cx.listener(Self::select_commit_for_compare))
+            .on_action(cx.listener(Self::compare_with_marked_commit))
+            .on_action(cx.listener(Self::mark_commit_bad_for_bisect))
+            .on_action(cx.listener(Self::mark_commit_good_for_bisect))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.repository.read(cx).display_name()).color(Color::Muted))
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .when_some(self.filter_error.clone(), |this, error| {
+                                this.child(
+                                    Label::new(error).size(LabelSize::Small).color(Color::Error),
+                                )
+                            })
+                            .child(
+                                Label::new(format!("{entry_count} commits"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .when(self.has_more, |this| {
+                                this.child(Divider::vertical()).child(
+                                    Button::new("load-more", "Load More")
+                                        .disabled(self.loading)
+                                        .label_size(LabelSize::Small)
+                                        .icon(IconName::ArrowCircle)
+                                        .icon_size(IconSize::Small)
+                                        .icon_color(Color::Muted)
+                                        .icon_position(IconPosition::Start)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.load_more(window, cx);
+                                        })),
+                                )
+                            }),
+                    ),
+            )
+            .child(self.render_filters(cx))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            "git-log",
+                            entry_count,
+                            cx.processor(|this, range: std::ops::Range<usize>, _, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }),
+                        )
+                        .flex_1()
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+impl Item for GitLogView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        format!("Log: {}", self.repository.read(cx).display_name()).into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(format!("Git log for {}", self.repository.read(cx).display_name()).into())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git log")
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(sha: &str, parents: &[&str], subject: &str, author: &str) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            refs: Vec::new(),
+            subject: subject.to_string().into(),
+            commit_timestamp: 1_700_000_000,
+            author_name: author.to_string().into(),
+            author_email: format!("{}@example.com", author.to_lowercase()).into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2023-11-14").unwrap(), 1_699_920_000);
+        assert!(parse_date("2023-13-01").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_git_log_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.with_git_state(dot_git, false, |state| {
+            state.log = vec![
+                commit(
+                    "cccccccccc",
+                    &["aaaaaaaaaa", "bbbbbbbbbb"],
+                    "Merge feature",
+                    "Alice",
+                ),
+                commit("bbbbbbbbbb", &["aaaaaaaaaa"], "Add feature", "Bob"),
+                commit("aaaaaaaaaa", &[], "Initial commit", "Alice"),
+            ];
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        cx.dispatch_action(git::ViewLog);
+        cx.run_until_parked();
+        let view = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<GitLogView>(cx)
+            })
+            .expect("log view should be open");
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.entries().len(), 3);
+            assert_eq!(view.graph.lane_count(), 2);
+            assert_eq!(view.graph.rows()[1].lane, 1);
+        });
+
+        view.update_in(cx, |view, window, cx| {
+            view.author_editor
+                .update(cx, |editor, cx| editor.set_text("bob", window, cx));
+        });
+        cx.executor().advance_clock(FILTER_DEBOUNCE);
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.entries()
+                    .iter()
+                    .map(|entry| entry.subject.as_ref())
+                    .collect::<Vec<_>>(),
+                ["Add feature"]
+            );
+        });
+
+        view.update_in(cx, |view, window, cx| {
+            view.select_ix(Some(0), window, cx);
+            view.cherry_pick_commit(&CherryPickCommit, window, cx);
+        });
+        cx.run_until_parked();
+        let operations = fs
+            .with_git_state(dot_git, false, |state| state.commit_operations.clone())
+            .unwrap();
+        assert_eq!(
+            operations,
+            [(CommitOperation::CherryPick, "bbbbbbbbbb".to_string())]
+        );
+    }
+}
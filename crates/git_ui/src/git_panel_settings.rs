@@ -25,6 +25,17 @@ pub struct GitPanelSettings {
     pub sort_by_path: bool,
     pub collapse_untracked_diff: bool,
     pub tree_view: bool,
+    pub protected_branches: Vec<String>,
+    pub commit_checks: CommitChecksSettings,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitChecksSettings {
+    pub enabled: bool,
+    pub secret_patterns: Vec<String>,
+    pub forbidden_paths: Vec<String>,
+    pub max_binary_size: u64,
+    pub conflict_markers: bool,
 }
 
 impl ScrollbarVisibility for GitPanelSettings {
@@ -58,6 +69,17 @@ impl Settings for GitPanelSettings {
             sort_by_path: git_panel.sort_by_path.unwrap(),
             collapse_untracked_diff: git_panel.collapse_untracked_diff.unwrap(),
             tree_view: git_panel.tree_view.unwrap(),
+            protected_branches: git_panel.protected_branches.unwrap(),
+            commit_checks: {
+                let commit_checks = git_panel.commit_checks.unwrap();
+                CommitChecksSettings {
+                    enabled: commit_checks.enabled.unwrap(),
+                    secret_patterns: commit_checks.secret_patterns.unwrap(),
+                    forbidden_paths: commit_checks.forbidden_paths.unwrap(),
+                    max_binary_size: commit_checks.max_binary_size.unwrap(),
+                    conflict_markers: commit_checks.conflict_markers.unwrap(),
+                }
+            },
         }
     }
 }
@@ -14,7 +14,7 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
-    repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{Branch, SubmoduleCommand, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
@@ -32,21 +32,30 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect_view;
 pub mod branch_picker;
+mod commit_checks;
+mod commit_graph;
 mod commit_modal;
 pub mod commit_tooltip;
 pub mod commit_view;
 mod conflict_view;
 pub mod file_diff_view;
 pub mod file_history_view;
+pub mod git_log_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod merge_editor;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+mod pull_request_review;
+pub mod rebase_view;
+mod release_notes;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
 pub mod worktree_picker;
 
@@ -76,6 +85,13 @@ pub fn init(cx: &mut App) {
         branch_picker::register(workspace);
         worktree_picker::register(workspace);
         stash_picker::register(workspace);
+        rebase_view::register(workspace);
+        git_log_view::register(workspace);
+        bisect_view::register(workspace);
+        pull_request_review::register(workspace);
+        tag_picker::register(workspace);
+        release_notes::register(workspace);
+        merge_editor::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -107,6 +123,30 @@ pub fn init(cx: &mut App) {
                     panel.fetch(false, window, cx);
                 });
             });
+            workspace.register_action(|workspace, _: &git::InitSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.submodule_command(SubmoduleCommand::Init, Vec::new(), window, cx);
+                });
+            });
+            workspace.register_action(|workspace, _: &git::UpdateSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.submodule_command(SubmoduleCommand::Update, Vec::new(), window, cx);
+                });
+            });
+            workspace.register_action(|workspace, _: &git::SyncSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.submodule_command(SubmoduleCommand::Sync, Vec::new(), window, cx);
+                });
+            });
             workspace.register_action(|workspace, _: &git::Push, window, cx| {
                 let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                     return;
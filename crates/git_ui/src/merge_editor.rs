@@ -0,0 +1,1077 @@
+//! MergeEditor provides a three-way view for resolving the conflicts in a file: the base, ours and
+//! theirs versions side by side, above an editable result.
+
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use collections::HashSet;
+use editor::{
+    Editor, EditorEvent, MultiBuffer, RowHighlightOptions, SelectionEffects,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+};
+use git::{OpenMergeEditor, repository::RepoPath};
+use gpui::{
+    Action, AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    WeakEntity, Window, actions,
+};
+use language::{
+    Buffer, Capability, Language, LanguageRegistry, OffsetRangeExt as _, ToOffset as _,
+};
+use project::{ConflictRegion, ConflictSet, ConflictSetUpdate, Project, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    mem,
+    ops::Range,
+    sync::Arc,
+};
+use ui::{Divider, Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+use crate::file_diff_view::build_buffer_diff;
+
+actions!(
+    merge_editor,
+    [
+        /// Resolves the conflict at the cursor with our version.
+        AcceptOurs,
+        /// Resolves the conflict at the cursor with their version.
+        AcceptTheirs,
+        /// Resolves the conflict at the cursor with both versions, ours first.
+        AcceptBoth,
+        /// Moves the cursor to the next conflict in the result.
+        NextConflict,
+        /// Moves the cursor to the previous conflict in the result.
+        PreviousConflict,
+        /// Saves the result and stages the file, marking it as resolved.
+        MarkResolved,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &OpenMergeEditor, window, cx| {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        if !repository.read(cx).has_conflict(&repo_path) {
+            return;
+        }
+        MergeEditor::open(repository, repo_path, workspace, window, cx).detach_and_prompt_err(
+            "Failed to open merge editor",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Resolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+/// Highlights the rows of a side pane that correspond to a conflict in the result.
+struct MergeConflictHighlight;
+
+/// One of the read-only panes showing a side of the merge, with per-conflict accept buttons.
+struct MergeSide {
+    buffer: Entity<Buffer>,
+    /// The diff from the base to this side.
+    diff: Entity<BufferDiff>,
+    editor: Entity<Editor>,
+    resolution: Resolution,
+    /// For each conflict in the result, where that conflict's text for this side appears in
+    /// this pane, if it could be found.
+    conflict_ranges: Vec<Option<Range<language::Anchor>>>,
+    block_ids: HashSet<CustomBlockId>,
+}
+
+impl MergeSide {
+    fn changes(&self, cx: &App) -> Vec<SideChange> {
+        let snapshot = self.buffer.read(cx).snapshot();
+        self.diff
+            .read(cx)
+            .snapshot(cx)
+            .hunks(&snapshot)
+            .map(|hunk| SideChange {
+                base: hunk.diff_base_byte_range,
+                side: hunk.buffer_range.to_offset(&snapshot),
+            })
+            .collect()
+    }
+}
+
+pub struct MergeEditor {
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    result_buffer: Entity<Buffer>,
+    result_editor: Entity<Editor>,
+    base_editor: Entity<Editor>,
+    ours: MergeSide,
+    theirs: MergeSide,
+    conflict_set: Entity<ConflictSet>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    /// Opens the merge editor for a conflicted path, reusing an existing one if it is already
+    /// open in the workspace.
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Entity<Self>>> {
+        let existing = workspace.items_of_type::<Self>(cx).find(|merge_editor| {
+            let merge_editor = merge_editor.read(cx);
+            merge_editor.repository == repository && merge_editor.repo_path == repo_path
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let project = workspace.project().clone();
+        let project_path = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx);
+        let texts = repository.update(cx, |repository, _| {
+            repository.load_conflict_texts(repo_path.clone())
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let project_path = project_path
+                .with_context(|| format!("{} is not in the project", repo_path.as_unix_str()))?;
+            let texts = texts.await??;
+            let result_buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            let languages = project.read_with(cx, |project, _| project.languages().clone())?;
+            let language = result_buffer.read_with(cx, |buffer, _| buffer.language().cloned())?;
+
+            let [base_buffer, ours_buffer, theirs_buffer] = [texts.base, texts.ours, texts.theirs]
+                .map(|text| {
+                    cx.new(|cx| side_buffer(text, language.clone(), languages.clone(), cx))
+                });
+            let base_buffer = base_buffer?;
+            let ours_buffer = ours_buffer?;
+            let theirs_buffer = theirs_buffer?;
+            let ours_diff =
+                build_buffer_diff(&base_buffer, &ours_buffer, languages.clone(), cx).await?;
+            let theirs_diff =
+                build_buffer_diff(&base_buffer, &theirs_buffer, languages, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    MergeEditor::new(
+                        project,
+                        repository,
+                        repo_path,
+                        result_buffer,
+                        base_buffer,
+                        (ours_buffer, ours_diff),
+                        (theirs_buffer, theirs_diff),
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        result_buffer: Entity<Buffer>,
+        base_buffer: Entity<Buffer>,
+        (ours_buffer, ours_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        (theirs_buffer, theirs_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_editor = side_editor(base_buffer, None, window, cx);
+        let ours = MergeSide {
+            editor: side_editor(ours_buffer.clone(), Some(ours_diff.clone()), window, cx),
+            buffer: ours_buffer,
+            diff: ours_diff,
+            resolution: Resolution::Ours,
+            conflict_ranges: Vec::new(),
+            block_ids: HashSet::default(),
+        };
+        let theirs = MergeSide {
+            editor: side_editor(theirs_buffer.clone(), Some(theirs_diff.clone()), window, cx),
+            buffer: theirs_buffer,
+            diff: theirs_diff,
+            resolution: Resolution::Theirs,
+            conflict_ranges: Vec::new(),
+            block_ids: HashSet::default(),
+        };
+        let result_editor = cx
+            .new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project.clone()), window, cx));
+        let git_store = project.read(cx).git_store().clone();
+        let conflict_set = git_store.update(cx, |git_store, cx| {
+            git_store.open_conflict_set(result_buffer.clone(), cx)
+        });
+
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &conflict_set,
+                window,
+                |this, _, _: &ConflictSetUpdate, window, cx| {
+                    this.update_sides(window, cx);
+                },
+            ),
+            cx.subscribe_in(
+                &result_editor,
+                window,
+                |this, _, event: &EditorEvent, window, cx| {
+                    if let EditorEvent::SelectionsChanged { local: true } = event {
+                        this.reveal_conflict_at_cursor(window, cx);
+                    }
+                    cx.emit(event.clone());
+                },
+            ),
+        ];
+
+        let mut this = Self {
+            project,
+            repository,
+            repo_path,
+            result_buffer,
+            result_editor,
+            base_editor,
+            ours,
+            theirs,
+            conflict_set,
+            _subscriptions: subscriptions,
+        };
+        this.update_sides(window, cx);
+        this
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    /// Re-locates the current conflicts in the ours and theirs panes and rebuilds their
+    /// highlights and accept buttons.
+    fn update_sides(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        let conflicts = self.conflicts(cx);
+        let result = self.result_buffer.read(cx).snapshot();
+        let regions = conflicting_regions(&self.ours.changes(cx), &self.theirs.changes(cx));
+        let this = cx.weak_entity();
+        for side in [&mut self.ours, &mut self.theirs] {
+            let snapshot = side.buffer.read(cx).snapshot();
+            let needles = conflicts
+                .iter()
+                .map(|conflict| {
+                    result
+                        .text_for_range(side_range(conflict, side.resolution))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let side_regions = regions
+                .iter()
+                .map(|(ours, theirs)| match side.resolution {
+                    Resolution::Theirs => theirs.clone(),
+                    _ => ours.clone(),
+                })
+                .collect::<Vec<_>>();
+            side.conflict_ranges = locate_conflicts(&snapshot.text(), &side_regions, &needles)
+                .into_iter()
+                .map(|range| {
+                    range.map(|range| {
+                        snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end)
+                    })
+                })
+                .collect();
+
+            let color = match side.resolution {
+                Resolution::Theirs => cx.theme().colors().version_control_conflict_marker_theirs,
+                _ => cx.theme().colors().version_control_conflict_marker_ours,
+            };
+            let resolution = side.resolution;
+            let old_block_ids = mem::take(&mut side.block_ids);
+            let conflict_ranges = &side.conflict_ranges;
+            side.block_ids = side.editor.update(cx, |editor, cx| {
+                editor.remove_blocks(old_block_ids, None, cx);
+                editor.clear_row_highlights::<MergeConflictHighlight>();
+                let multibuffer = editor.buffer().read(cx).snapshot(cx);
+                let mut blocks = Vec::new();
+                for (conflict, range) in conflicts.iter().zip(conflict_ranges) {
+                    let Some(range) = range else {
+                        continue;
+                    };
+                    let (Some(start), Some(end)) = (
+                        multibuffer.as_singleton_anchor(range.start),
+                        multibuffer.as_singleton_anchor(range.end),
+                    ) else {
+                        continue;
+                    };
+                    editor.highlight_rows::<MergeConflictHighlight>(
+                        start..end,
+                        color,
+                        RowHighlightOptions {
+                            include_gutter: true,
+                            ..Default::default()
+                        },
+                        cx,
+                    );
+                    let conflict = conflict.clone();
+                    let this = this.clone();
+                    blocks.push(BlockProperties {
+                        placement: BlockPlacement::Above(start),
+                        height: Some(1),
+                        style: BlockStyle::Fixed,
+                        render: Arc::new(move |cx: &mut BlockContext| {
+                            render_accept_buttons(&conflict, resolution, this.clone(), cx)
+                        }),
+                        priority: 0,
+                    });
+                }
+                editor.insert_blocks(blocks, None, cx).into_iter().collect()
+            });
+        }
+        cx.notify();
+    }
+
+    fn cursor_offset(&self, cx: &App) -> usize {
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        self.result_editor
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head()
+            .text_anchor
+            .to_offset(&snapshot)
+    }
+
+    fn conflict_index_at_cursor(&self, cx: &App) -> Option<usize> {
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        self.conflicts(cx).iter().position(|conflict| {
+            let range = conflict.range.to_offset(&snapshot);
+            range.start <= cursor && cursor <= range.end
+        })
+    }
+
+    /// Scrolls the ours and theirs panes to the conflict under the cursor in the result.
+    fn reveal_conflict_at_cursor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.conflict_index_at_cursor(cx) else {
+            return;
+        };
+        for side in [&self.ours, &self.theirs] {
+            let Some(Some(range)) = side.conflict_ranges.get(ix) else {
+                continue;
+            };
+            side.editor.update(cx, |editor, cx| {
+                let multibuffer = editor.buffer().read(cx).snapshot(cx);
+                let Some(start) = multibuffer.as_singleton_anchor(range.start) else {
+                    return;
+                };
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_anchor_ranges([start..start]),
+                );
+            });
+        }
+    }
+
+    fn resolve(&mut self, conflict: &ConflictRegion, resolution: Resolution, cx: &mut App) {
+        // The buttons in the side panes may outlive the conflict they were rendered for.
+        let is_current = self
+            .conflicts(cx)
+            .iter()
+            .any(|current| current.range == conflict.range);
+        if !is_current {
+            return;
+        }
+        let ranges = match resolution {
+            Resolution::Ours => vec![conflict.ours.clone()],
+            Resolution::Theirs => vec![conflict.theirs.clone()],
+            Resolution::Both => vec![conflict.ours.clone(), conflict.theirs.clone()],
+        };
+        conflict.resolve(self.result_buffer.clone(), &ranges, cx);
+    }
+
+    fn resolve_at_cursor(&mut self, resolution: Resolution, cx: &mut Context<Self>) {
+        let Some(ix) = self.conflict_index_at_cursor(cx) else {
+            return;
+        };
+        let conflict = self.conflicts(cx)[ix].clone();
+        self.resolve(&conflict, resolution, cx);
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, _: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_at_cursor(Resolution::Ours, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, _: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_at_cursor(Resolution::Theirs, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, _: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_at_cursor(Resolution::Both, cx);
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        let conflicts = self.conflicts(cx);
+        let next = conflicts
+            .iter()
+            .find(|conflict| conflict.range.start.to_offset(&snapshot) > cursor)
+            .or_else(|| conflicts.first());
+        if let Some(conflict) = next {
+            self.move_to_conflict(conflict.range.start, window, cx);
+        }
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.result_buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        let conflicts = self.conflicts(cx);
+        let previous = conflicts
+            .iter()
+            .rev()
+            .find(|conflict| conflict.range.end.to_offset(&snapshot) < cursor)
+            .or_else(|| conflicts.last());
+        if let Some(conflict) = previous {
+            self.move_to_conflict(conflict.range.start, window, cx);
+        }
+    }
+
+    fn move_to_conflict(
+        &mut self,
+        position: language::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            let multibuffer = editor.buffer().read(cx).snapshot(cx);
+            let Some(position) = multibuffer.as_singleton_anchor(position) else {
+                return;
+            };
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_anchor_ranges([position..position]),
+            );
+        });
+        window.focus(&self.result_editor.focus_handle(cx));
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.conflicts(cx).is_empty() {
+            return;
+        }
+        let save = self.project.update(cx, |project, cx| {
+            project.save_buffer(self.result_buffer.clone(), cx)
+        });
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            save.await?;
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err(
+            "Failed to mark file as resolved",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    }
+
+    fn render_pane(
+        &self,
+        title: SharedString,
+        editor: &Entity<Editor>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .border_r_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+
+    fn render_result_header(&self, cx: &Context<Self>) -> impl IntoElement {
+        let remaining = self.conflicts(cx).len();
+        let focus_handle = self.result_editor.focus_handle(cx);
+        let action_button = |id: &'static str, label: &'static str, action: &dyn Action| {
+            let action = action.boxed_clone();
+            Button::new(id, label)
+                .label_size(LabelSize::Small)
+                .disabled(remaining == 0)
+                .tooltip(Tooltip::for_action_title_in(
+                    label,
+                    action.as_ref(),
+                    &focus_handle,
+                ))
+                .on_click({
+                    let focus_handle = focus_handle.clone();
+                    move |_, window, cx| {
+                        focus_handle.dispatch_action(action.as_ref(), window, cx);
+                    }
+                })
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_t_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new("Result").size(LabelSize::Small))
+                    .child(
+                        Label::new(match remaining {
+                            0 => "No conflicts remaining".to_string(),
+                            1 => "1 conflict remaining".to_string(),
+                            n => format!("{n} conflicts remaining"),
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(action_button("previous", "Previous", &PreviousConflict))
+                    .child(action_button("next", "Next", &NextConflict))
+                    .child(Divider::vertical())
+                    .child(action_button("ours", "Accept Ours", &AcceptOurs))
+                    .child(action_button("theirs", "Accept Theirs", &AcceptTheirs))
+                    .child(action_button("both", "Accept Both", &AcceptBoth))
+                    .child(Divider::vertical())
+                    .child(
+                        Button::new("mark-resolved", "Mark as Resolved")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(remaining > 0)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Save and stage the result",
+                                &MarkResolved,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.mark_resolved(&MarkResolved, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+fn side_buffer(
+    text: Option<String>,
+    language: Option<Arc<Language>>,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut Context<Buffer>,
+) -> Buffer {
+    let mut buffer = Buffer::local(text.unwrap_or_default(), cx);
+    buffer.set_language_registry(languages);
+    buffer.set_language(language, cx);
+    buffer.set_capability(Capability::ReadOnly, cx);
+    buffer
+}
+
+fn side_editor(
+    buffer: Entity<Buffer>,
+    diff: Option<Entity<BufferDiff>>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<Editor> {
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+        if let Some(diff) = diff {
+            multibuffer.add_diff(diff, cx);
+        }
+        multibuffer
+    });
+    cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, None, window, cx);
+        editor.start_temporary_diff_override();
+        editor.disable_diagnostics(cx);
+        editor.set_expand_all_diff_hunks(cx);
+        editor.set_render_diff_hunk_controls(
+            Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+            cx,
+        );
+        editor.set_read_only(true);
+        editor
+    })
+}
+
+fn side_range(conflict: &ConflictRegion, resolution: Resolution) -> Range<language::Anchor> {
+    match resolution {
+        Resolution::Theirs => conflict.theirs.clone(),
+        _ => conflict.ours.clone(),
+    }
+}
+
+/// A change that one side of the merge made to the base: the replaced range of the base text and
+/// the range of the side's text that replaced it.
+#[derive(Clone, Debug, PartialEq)]
+struct SideChange {
+    base: Range<usize>,
+    side: Range<usize>,
+}
+
+/// Groups the changes of both sides into the regions where git may have reported a conflict:
+/// runs of overlapping or adjacent changes to the base that include changes from both sides.
+/// Returns the range of each region in the ours and theirs texts.
+fn conflicting_regions(
+    ours: &[SideChange],
+    theirs: &[SideChange],
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut changes = ours
+        .iter()
+        .map(|change| (change, true))
+        .chain(theirs.iter().map(|change| (change, false)))
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|(change, _)| (change.base.start, change.base.end));
+
+    let mut regions = Vec::new();
+    let mut changes = changes.into_iter().peekable();
+    while let Some((first, is_ours)) = changes.next() {
+        let mut base = first.base.clone();
+        let mut has_ours = is_ours;
+        let mut has_theirs = !is_ours;
+        while let Some((change, is_ours)) =
+            changes.next_if(|(change, _)| change.base.start <= base.end)
+        {
+            base.end = base.end.max(change.base.end);
+            has_ours |= is_ours;
+            has_theirs |= !is_ours;
+        }
+        if has_ours && has_theirs {
+            regions.push((side_region(ours, &base), side_region(theirs, &base)));
+        }
+    }
+    regions
+}
+
+/// Maps a range of the base text that starts and ends outside of this side's changes, or on
+/// their boundaries, to the corresponding range of the side's text.
+fn side_region(changes: &[SideChange], base: &Range<usize>) -> Range<usize> {
+    let unchanged = |offset: usize| {
+        changes
+            .iter()
+            .take_while(|change| change.base.end <= offset)
+            .last()
+            .map_or(offset, |change| change.side.end + offset - change.base.end)
+    };
+    let start = changes
+        .iter()
+        .find(|change| change.base.start == base.start)
+        .map_or_else(|| unchanged(base.start), |change| change.side.start);
+    let end = changes
+        .iter()
+        .rev()
+        .find(|change| change.base.end == base.end)
+        .map_or_else(|| unchanged(base.end), |change| change.side.end);
+    start..end
+}
+
+/// Finds each conflict's text for a side of the merge in the regions of that side where both sides
+/// changed the base, in order. Git may leave lines that both sides share out of a conflict, so a
+/// conflict's text only needs to appear within its region. Conflicts whose text is empty or can't be
+/// found map to `None`.
+fn locate_conflicts(
+    text: &str,
+    regions: &[Range<usize>],
+    needles: &[String],
+) -> Vec<Option<Range<usize>>> {
+    let mut regions = regions.iter();
+    needles
+        .iter()
+        .map(|needle| {
+            if needle.is_empty() {
+                return None;
+            }
+            let mut remaining = regions.clone();
+            let start = remaining.find_map(|region| {
+                Some(region.start + text.get(region.clone())?.find(needle.as_str())?)
+            })?;
+            regions = remaining;
+            Some(start..start + needle.len())
+        })
+        .collect()
+}
+
+fn render_accept_buttons(
+    conflict: &ConflictRegion,
+    resolution: Resolution,
+    merge_editor: WeakEntity<MergeEditor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let branch_name = match resolution {
+        Resolution::Theirs => &conflict.theirs_branch_name,
+        _ => &conflict.ours_branch_name,
+    };
+    let button = |id: &'static str, label: String, resolution: Resolution| {
+        let conflict = conflict.clone();
+        let merge_editor = merge_editor.clone();
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .on_click(move |_, _, cx| {
+                merge_editor
+                    .update(cx, |merge_editor, cx| {
+                        merge_editor.resolve(&conflict, resolution, cx)
+                    })
+                    .ok();
+            })
+    };
+
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .ml(cx.margins.gutter.width)
+        .items_end()
+        .gap_1()
+        .bg(cx.theme().colors().editor_background)
+        .child(button(
+            "accept",
+            format!("Accept {branch_name}"),
+            resolution,
+        ))
+        .child(button(
+            "accept-both",
+            "Accept Both".into(),
+            Resolution::Both,
+        ))
+        .into_any_element()
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        let file_name = self
+            .repo_path
+            .file_name()
+            .unwrap_or(self.repo_path.as_unix_str());
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", self.repo_path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflicts = self.conflicts(cx);
+        let (ours_title, theirs_title) = match conflicts.first() {
+            Some(conflict) => (
+                format!("Ours ({})", conflict.ours_branch_name).into(),
+                format!("Theirs ({})", conflict.theirs_branch_name).into(),
+            ),
+            None => ("Ours".into(), "Theirs".into()),
+        };
+
+        v_flex()
+            .key_context("MergeEditor")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::mark_resolved))
+            .child(
+                h_flex()
+                    .h(relative(0.45))
+                    .w_full()
+                    .child(self.render_pane("Base".into(), &self.base_editor, cx))
+                    .child(self.render_pane(ours_title, &self.ours.editor, cx))
+                    .child(self.render_pane(theirs_title, &self.theirs.editor, cx)),
+            )
+            .child(self.render_result_header(cx))
+            .child(div().flex_1().min_h_0().child(self.result_editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_conflicts() {
+        // base:   "a\nb\nc\nd\ne\nf\n"
+        // ours:   "a\nB\nc\nd\nE\nf\n"
+        // theirs: "a\nb2\nc\nd\nX\nY\nf2\n"
+        let ours = [
+            SideChange {
+                base: 2..4,
+                side: 2..4,
+            },
+            SideChange {
+                base: 8..10,
+                side: 8..10,
+            },
+        ];
+        let theirs = [
+            SideChange {
+                base: 2..4,
+                side: 2..5,
+            },
+            SideChange {
+                base: 8..10,
+                side: 9..13,
+            },
+            SideChange {
+                base: 10..12,
+                side: 13..16,
+            },
+        ];
+        let regions = conflicting_regions(&ours, &theirs);
+        assert_eq!(regions, vec![(2..4, 2..5), (8..12, 9..16)]);
+
+        let ours_regions = regions
+            .iter()
+            .map(|(ours, _)| ours.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locate_conflicts(
+                "a\nB\nc\nd\nE\nf\n",
+                &ours_regions,
+                &["B\n".to_string(), String::new(), "E\n".to_string()]
+            ),
+            vec![Some(2..4), None, Some(8..10)]
+        );
+
+        // Text that also appears outside of the regions where both sides changed the base isn't
+        // matched there, and git may leave shared lines out of a conflict.
+        let theirs_regions = regions
+            .iter()
+            .map(|(_, theirs)| theirs.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locate_conflicts(
+                "a\nb2\nc\nd\nX\nY\nf2\n",
+                &theirs_regions,
+                &["d\n".to_string(), "Y\n".to_string(), "b2\n".to_string()]
+            ),
+            vec![None, Some(11..13), None]
+        );
+    }
+
+    #[test]
+    fn test_conflicting_regions() {
+        // A change by only one side isn't a conflict.
+        assert_eq!(
+            conflicting_regions(
+                &[SideChange {
+                    base: 0..2,
+                    side: 0..3,
+                }],
+                &[SideChange {
+                    base: 4..6,
+                    side: 4..6,
+                }],
+            ),
+            vec![]
+        );
+
+        // Adjacent changes are grouped, and a region's boundaries that fall outside of a side's
+        // changes are mapped through the unchanged text before them.
+        let ours = [
+            SideChange {
+                base: 0..2,
+                side: 0..5,
+            },
+            SideChange {
+                base: 6..8,
+                side: 9..10,
+            },
+        ];
+        let theirs = [SideChange {
+            base: 4..8,
+            side: 4..6,
+        }];
+        assert_eq!(conflicting_regions(&ours, &theirs), vec![(7..10, 4..6)]);
+
+        let ours = [SideChange {
+            base: 6..6,
+            side: 6..8,
+        }];
+        let theirs = [SideChange {
+            base: 2..6,
+            side: 2..4,
+        }];
+        assert_eq!(conflicting_regions(&ours, &theirs), vec![(2..8, 2..4)]);
+    }
+}
@@ -2,6 +2,9 @@ use crate::{
     conflict_view::ConflictAddon,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
     git_panel_settings::GitPanelSettings,
+    pull_request_review::{
+        self, PullRequestReviewSession, ReviewCommentModal, ReviewCommentTarget,
+    },
     remote_button::{render_publish_button, render_push_button},
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -10,11 +13,12 @@ use collections::{HashMap, HashSet};
 use editor::{
     Addon, Editor, EditorEvent, SelectionEffects, SplittableEditor,
     actions::{GoToHunk, GoToPreviousHunk},
+    display_map::CustomBlockId,
     multibuffer_context_lines,
     scroll::Autoscroll,
 };
 use git::{
-    Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
+    AddReviewComment, Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
     repository::{Branch, RepoPath, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::FileStatus,
 };
@@ -70,6 +74,8 @@ pub struct ProjectDiff {
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
     pending_scroll: Option<PathKey>,
+    pull_request_review: Option<PullRequestReviewSession>,
+    review_block_ids: HashSet<CustomBlockId>,
     _task: Task<Result<()>>,
     _subscription: Subscription,
 }
@@ -331,6 +337,8 @@ impl ProjectDiff {
             multibuffer,
             buffer_diff_subscriptions: Default::default(),
             pending_scroll: None,
+            pull_request_review: None,
+            review_block_ids: HashSet::default(),
             _task: task,
             _subscription: branch_diff_subscription,
         }
@@ -340,6 +348,131 @@ impl ProjectDiff {
         self.branch_diff.read(cx).diff_base()
     }
 
+    /// Returns a diff of the changes of a pull request, with its review threads.
+    pub(crate) fn new_for_pull_request(
+        session: PullRequestReviewSession,
+        project: Entity<Project>,
+        workspace: Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let branch_diff = cx.new(|cx| {
+            branch_diff::BranchDiff::new(
+                DiffBase::Merge {
+                    base_ref: session.base_ref().into(),
+                },
+                project.clone(),
+                window,
+                cx,
+            )
+        });
+        cx.new(|cx| {
+            let mut this = Self::new_impl(branch_diff, project, workspace, window, cx);
+            this.pull_request_review = Some(session);
+            this
+        })
+    }
+
+    pub(crate) fn pull_request_review(&self) -> Option<&PullRequestReviewSession> {
+        self.pull_request_review.as_ref()
+    }
+
+    pub(crate) fn set_pull_request_review(
+        &mut self,
+        session: PullRequestReviewSession,
+        cx: &mut Context<Self>,
+    ) {
+        self.pull_request_review = Some(session);
+        self.update_review_blocks(cx);
+        cx.notify();
+    }
+
+    /// Replaces the blocks that show the review threads of the pull request.
+    fn update_review_blocks(&mut self, cx: &mut Context<Self>) {
+        let blocks = self
+            .pull_request_review
+            .as_ref()
+            .map(|session| {
+                pull_request_review::review_thread_blocks(
+                    session,
+                    self.multibuffer.read(cx),
+                    cx.weak_entity(),
+                    cx,
+                )
+            })
+            .unwrap_or_default();
+        let old_block_ids = std::mem::take(&mut self.review_block_ids);
+        self.review_block_ids = self.editor.update(cx, |editor, cx| {
+            editor.primary_editor().update(cx, |editor, cx| {
+                editor.remove_blocks(old_block_ids, None, cx);
+                editor.insert_blocks(blocks, None, cx).into_iter().collect()
+            })
+        });
+    }
+
+    fn add_review_comment(
+        &mut self,
+        _: &AddReviewComment,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pull_request_review.is_none() {
+            return;
+        }
+        let position = self
+            .editor
+            .read(cx)
+            .primary_editor()
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head();
+        if let Some(target) =
+            pull_request_review::review_comment_target(&self.multibuffer, position, cx)
+        {
+            self.open_review_comment_modal(target, window, cx);
+        }
+    }
+
+    pub(crate) fn open_review_comment_modal(
+        &mut self,
+        target: ReviewCommentTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let project_diff = cx.weak_entity();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    ReviewCommentModal::new(project_diff, target, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    pub(crate) fn post_review_comment(
+        &mut self,
+        target: ReviewCommentTarget,
+        body: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(session) = self.pull_request_review.as_ref() else {
+            return;
+        };
+        let post = session.post_comment(target, body, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let posted = post.await?;
+            this.update(cx, |this, cx| {
+                if let Some(session) = this.pull_request_review.as_mut() {
+                    session.add_posted_comment(posted);
+                }
+                this.update_review_blocks(cx);
+            })
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
     pub fn move_to_entry(
         &mut self,
         entry: GitStatusEntry,
@@ -697,6 +830,7 @@ impl ProjectDiff {
         }
         this.update(cx, |this, cx| {
             this.pending_scroll.take();
+            this.update_review_blocks(cx);
             cx.notify();
         })?;
 
@@ -787,6 +921,9 @@ impl Item for ProjectDiff {
     }
 
     fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        if let Some(session) = &self.pull_request_review {
+            return format!("Review #{}", session.review.pull_request.number).into();
+        }
         match self.branch_diff.read(cx).diff_base() {
             DiffBase::Head => "Uncommitted Changes".into(),
             DiffBase::Merge { base_ref } => format!("Changes since {}", base_ref).into(),
@@ -931,6 +1068,7 @@ impl Render for ProjectDiff {
         div()
             .track_focus(&self.focus_handle)
             .key_context(if is_empty { "EmptyPane" } else { "GitDiff" })
+            .on_action(cx.listener(Self::add_review_comment))
             .bg(cx.theme().colors().editor_background)
             .flex()
             .items_center()
@@ -1033,6 +1171,10 @@ impl SerializableItem for ProjectDiff {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<Result<()>>> {
+        // Reviews aren't restored, since they need to be fetched from the hosting provider.
+        if self.pull_request_review.is_some() {
+            return None;
+        }
         let workspace_id = workspace.database_id()?;
         let diff_base = self.diff_base(cx).clone();
 
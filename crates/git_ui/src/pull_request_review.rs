@@ -0,0 +1,626 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use credentials_provider::CredentialsProvider;
+use editor::{
+    Editor,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle},
+};
+use git::{
+    GitHostingProvider, GitHostingProviderRegistry, NewReviewComment, ParsedGitRemote,
+    PullRequestReview, ReviewComment, ReviewPullRequest, ReviewSide, ReviewThread,
+    parse_git_remote_url,
+    repository::{RepoPath, UpstreamTracking},
+};
+use gpui::{
+    App, AppContext as _, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task,
+    WeakEntity,
+};
+use language::Point;
+use multi_buffer::{MultiBuffer, PathKey, ToPoint as _};
+use project::git_store::Repository;
+use ui::{Tooltip, prelude::*};
+use util::rel_path::RelPath;
+use workspace::{ModalView, Workspace, notifications::NotifyTaskExt};
+
+use crate::project_diff::ProjectDiff;
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    // Not every hosting provider implements pull request reviews, so the action is only available
+    // for repositories whose remote supports them.
+    workspace.register_action_renderer(|div, workspace, _, cx| {
+        let supports_reviews = workspace
+            .project()
+            .read(cx)
+            .active_repository(cx)
+            .and_then(|repository| pull_request_remote(repository.read(cx), cx).ok())
+            .is_some_and(|remote| remote.provider.supports_pull_request_reviews());
+        div.when(supports_reviews, |div| {
+            div.on_action(cx.listener(review_pull_request))
+        })
+    });
+}
+
+/// The hosting provider remote that pull requests for the current branch are opened on.
+pub(crate) struct PullRequestRemote {
+    pub provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    pub remote: ParsedGitRemote,
+    /// The name of the git remote, like `origin`.
+    pub remote_name: String,
+    /// The name of the current branch on the remote.
+    pub branch: String,
+}
+
+pub(crate) fn pull_request_remote(repository: &Repository, cx: &App) -> Result<PullRequestRemote> {
+    let branch = repository
+        .branch
+        .as_ref()
+        .ok_or_else(|| anyhow!("No active branch"))?;
+    let upstream = branch
+        .upstream
+        .as_ref()
+        .filter(|upstream| matches!(upstream.tracking, UpstreamTracking::Tracked(_)));
+    let source_branch = upstream
+        .and_then(|upstream| upstream.branch_name())
+        .ok_or_else(|| anyhow!("No remote configured for repository"))?;
+
+    let remote_origin = repository.remote_origin_url.as_deref();
+    let remote_upstream = repository.remote_upstream_url.as_deref();
+    let remote_name = upstream.and_then(|upstream| upstream.remote_name());
+    let remote_url = match remote_name {
+        Some("upstream") => remote_upstream,
+        Some(_) => remote_origin,
+        None => None,
+    }
+    .or(remote_origin)
+    .or(remote_upstream)
+    .ok_or_else(|| anyhow!("No remote configured for repository"))?;
+
+    let provider_registry = GitHostingProviderRegistry::global(cx);
+    let Some((provider, remote)) = parse_git_remote_url(provider_registry, remote_url) else {
+        return Err(anyhow!("Unsupported remote URL: {}", remote_url));
+    };
+
+    Ok(PullRequestRemote {
+        provider,
+        remote,
+        remote_name: remote_name.unwrap_or("origin").to_string(),
+        branch: source_branch.to_string(),
+    })
+}
+
+/// A pull request that's being reviewed in a [`ProjectDiff`].
+pub(crate) struct PullRequestReviewSession {
+    pub remote: PullRequestRemote,
+    pub token: String,
+    pub review: PullRequestReview,
+}
+
+impl PullRequestReviewSession {
+    pub fn base_ref(&self) -> String {
+        format!("{}/{}", self.remote.remote_name, self.review.base_branch)
+    }
+
+    /// Posts a review comment, returning the thread it was added to.
+    pub fn post_comment(
+        &self,
+        target: ReviewCommentTarget,
+        body: String,
+        cx: &App,
+    ) -> Task<Result<PostedComment>> {
+        let provider = self.remote.provider.clone();
+        let remote = self.remote.remote.clone();
+        let number = self.review.pull_request.number;
+        let token = self.token.clone();
+        let commit_sha = self.review.head_sha.clone();
+        let http_client = cx.http_client();
+        cx.background_spawn(async move {
+            match target {
+                ReviewCommentTarget::Reply { thread_id } => {
+                    let comment = provider
+                        .reply_to_review_thread(
+                            &remote,
+                            number,
+                            &thread_id,
+                            &body,
+                            &token,
+                            http_client,
+                        )
+                        .await?;
+                    Ok(PostedComment::Reply { thread_id, comment })
+                }
+                ReviewCommentTarget::Line { path, line, side } => {
+                    let thread = provider
+                        .create_review_comment(
+                            &remote,
+                            number,
+                            NewReviewComment {
+                                path,
+                                line,
+                                side,
+                                commit_sha,
+                                body,
+                            },
+                            &token,
+                            http_client,
+                        )
+                        .await?;
+                    Ok(PostedComment::Thread(thread))
+                }
+            }
+        })
+    }
+
+    pub fn add_posted_comment(&mut self, posted: PostedComment) {
+        match posted {
+            PostedComment::Reply { thread_id, comment } => {
+                if let Some(thread) = self
+                    .review
+                    .threads
+                    .iter_mut()
+                    .find(|thread| thread.id == thread_id)
+                {
+                    thread.comments.push(comment);
+                }
+            }
+            PostedComment::Thread(thread) => self.review.threads.push(thread),
+        }
+    }
+}
+
+/// Where a review comment is posted.
+#[derive(Clone, Debug)]
+pub(crate) enum ReviewCommentTarget {
+    Reply {
+        thread_id: String,
+    },
+    Line {
+        path: RepoPath,
+        line: u32,
+        side: ReviewSide,
+    },
+}
+
+pub(crate) enum PostedComment {
+    Reply {
+        thread_id: String,
+        comment: ReviewComment,
+    },
+    Thread(ReviewThread),
+}
+
+fn review_pull_request(
+    workspace: &mut Workspace,
+    _: &ReviewPullRequest,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(repository) = project.read(cx).active_repository(cx) else {
+        return;
+    };
+    let remote = match pull_request_remote(repository.read(cx), cx) {
+        Ok(remote) => remote,
+        Err(error) => {
+            Task::<Result<()>>::ready(Err(error)).detach_and_notify_err(window, cx);
+            return;
+        }
+    };
+    let credentials_provider = <dyn CredentialsProvider>::global(cx);
+    let http_client = cx.http_client();
+    let workspace_handle = cx.entity();
+    window
+        .spawn(cx, async move |cx| {
+            let credentials_url = remote.provider.base_url().to_string();
+            let token = credentials_provider
+                .read_credentials(&credentials_url, cx)
+                .await?
+                .map(|(_, token)| String::from_utf8(token))
+                .transpose()
+                .context("invalid token")?;
+            let Some(token) = token else {
+                workspace_handle.update_in(cx, |workspace, window, cx| {
+                    let provider_name = remote.provider.name();
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        ForgeTokenModal::new(provider_name, credentials_url, window, cx)
+                    });
+                })?;
+                return Ok(());
+            };
+
+            let pull_request = remote
+                .provider
+                .open_pull_request_for_branch(
+                    &remote.remote,
+                    &remote.branch,
+                    &token,
+                    http_client.clone(),
+                )
+                .await?
+                .with_context(|| format!("No open pull request for branch {}", remote.branch))?;
+            let review = remote
+                .provider
+                .pull_request_review(&remote.remote, pull_request.number, &token, http_client)
+                .await?;
+
+            workspace_handle.update_in(cx, |workspace, window, cx| {
+                let existing = workspace.items_of_type::<ProjectDiff>(cx).find(|item| {
+                    item.read(cx).pull_request_review().is_some_and(|session| {
+                        session.review.pull_request.number == review.pull_request.number
+                    })
+                });
+                let session = PullRequestReviewSession {
+                    remote,
+                    token,
+                    review,
+                };
+                if let Some(existing) = existing {
+                    existing.update(cx, |project_diff, cx| {
+                        project_diff.set_pull_request_review(session, cx)
+                    });
+                    workspace.activate_item(&existing, true, true, window, cx);
+                } else {
+                    let project_diff = ProjectDiff::new_for_pull_request(
+                        session,
+                        project,
+                        workspace_handle.clone(),
+                        window,
+                        cx,
+                    );
+                    workspace.add_item_to_active_pane(
+                        Box::new(project_diff),
+                        None,
+                        true,
+                        window,
+                        cx,
+                    );
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+}
+
+/// Returns the blocks that show the review threads below the lines they're about.
+pub(crate) fn review_thread_blocks(
+    session: &PullRequestReviewSession,
+    multibuffer: &MultiBuffer,
+    project_diff: WeakEntity<ProjectDiff>,
+    cx: &App,
+) -> Vec<BlockProperties<multi_buffer::Anchor>> {
+    session
+        .review
+        .threads
+        .iter()
+        .filter_map(|thread| {
+            let (placement, outdated) = thread_placement(thread, multibuffer, cx)?;
+            let height = 2 + thread
+                .comments
+                .iter()
+                .map(|comment| 1 + comment.body.lines().count().max(1) as u32)
+                .sum::<u32>();
+            let thread = thread.clone();
+            let project_diff = project_diff.clone();
+            Some(BlockProperties {
+                placement,
+                height: Some(height),
+                style: BlockStyle::Flex,
+                render: Arc::new(move |cx| {
+                    render_review_thread(&thread, outdated, project_diff.clone(), cx)
+                }),
+                priority: 0,
+            })
+        })
+        .collect()
+}
+
+/// Returns where the block of a thread is placed, and whether the thread is outdated.
+fn thread_placement(
+    thread: &ReviewThread,
+    multibuffer: &MultiBuffer,
+    cx: &App,
+) -> Option<(BlockPlacement<multi_buffer::Anchor>, bool)> {
+    let path: &RelPath = &thread.path;
+    let path_key = multibuffer
+        .paths()
+        .find(|key| key.path.as_ref() == path)?
+        .clone();
+    let buffer = multibuffer.buffer_for_path(&path_key, cx)?;
+    let Some(row) = thread.line.map(|line| line.saturating_sub(1)) else {
+        let anchor = multibuffer.location_for_path(&path_key, cx)?;
+        return Some((BlockPlacement::Above(anchor), true));
+    };
+
+    match thread.side {
+        ReviewSide::Head => {
+            let anchor = multibuffer.buffer_point_to_anchor(&buffer, Point::new(row, 0), cx)?;
+            Some((BlockPlacement::Below(anchor), false))
+        }
+        ReviewSide::Base => {
+            // Removed lines are shown above the start of the hunk that removed them.
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let diff = multibuffer.diff_for(buffer_snapshot.remote_id())?;
+            let diff_snapshot = diff.read(cx).snapshot(cx);
+            let base_text = diff_snapshot.base_text();
+            let offset = base_text.point_to_offset(Point::new(row, 0).min(base_text.max_point()));
+            let hunk = diff_snapshot
+                .hunks_intersecting_base_text_range(offset..offset, &buffer_snapshot)
+                .next()?;
+            let anchor = multibuffer.buffer_point_to_anchor(&buffer, hunk.range.start, cx)?;
+            Some((BlockPlacement::Above(anchor), false))
+        }
+    }
+}
+
+fn render_review_thread(
+    thread: &ReviewThread,
+    outdated: bool,
+    project_diff: WeakEntity<ProjectDiff>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let thread_id = thread.id.clone();
+    let colors = cx.theme().colors();
+
+    v_flex()
+        .id(cx.block_id)
+        .ml(cx.margins.gutter.full_width())
+        .mr_4()
+        .my_1()
+        .px_2()
+        .py_1()
+        .gap_1()
+        .border_1()
+        .rounded_sm()
+        .border_color(colors.border_variant)
+        .bg(colors.editor_subheader_background)
+        .when(outdated, |this| {
+            this.child(
+                Label::new(format!("Outdated comment on {}", thread.path.as_unix_str()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        })
+        .children(thread.comments.iter().map(|comment| {
+            v_flex()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Label::new(comment.author.clone()).size(LabelSize::Small))
+                        .child(
+                            Label::new(comment.created_at.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .child(Label::new(comment.body.clone()))
+        }))
+        .child(
+            h_flex().child(
+                Button::new(SharedString::from(format!("reply-{thread_id}")), "Reply…")
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text("Reply to this thread"))
+                    .on_click(move |_, window, cx| {
+                        project_diff
+                            .update(cx, |project_diff, cx| {
+                                project_diff.open_review_comment_modal(
+                                    ReviewCommentTarget::Reply {
+                                        thread_id: thread_id.clone(),
+                                    },
+                                    window,
+                                    cx,
+                                );
+                            })
+                            .ok();
+                    }),
+            ),
+        )
+        .into_any_element()
+}
+
+/// Returns the path, line and side of the pull request diff at the given position.
+pub(crate) fn review_comment_target(
+    multibuffer: &Entity<MultiBuffer>,
+    position: multi_buffer::Anchor,
+    cx: &App,
+) -> Option<ReviewCommentTarget> {
+    let multibuffer = multibuffer.read(cx);
+    let snapshot = multibuffer.snapshot(cx);
+    let point = position.to_point(&snapshot);
+    let (buffer, buffer_point, excerpt_id) = snapshot.point_to_buffer_point(point)?;
+    let PathKey { path, .. } = multibuffer.path_for_excerpt(excerpt_id)?;
+    // Removed lines are read from the base text of the diff, rather than from the buffer.
+    let side = if snapshot.buffer_id_for_excerpt(excerpt_id)? == buffer.remote_id() {
+        ReviewSide::Head
+    } else {
+        ReviewSide::Base
+    };
+    Some(ReviewCommentTarget::Line {
+        path: RepoPath::from_rel_path(&path),
+        line: buffer_point.row + 1,
+        side,
+    })
+}
+
+/// Asks for the body of a review comment and posts it.
+pub(crate) struct ReviewCommentModal {
+    project_diff: WeakEntity<ProjectDiff>,
+    target: ReviewCommentTarget,
+    editor: Entity<Editor>,
+}
+
+impl EventEmitter<DismissEvent> for ReviewCommentModal {}
+impl ModalView for ReviewCommentModal {}
+impl Focusable for ReviewCommentModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl ReviewCommentModal {
+    pub fn new(
+        project_diff: WeakEntity<ProjectDiff>,
+        target: ReviewCommentTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Leave a comment", window, cx);
+            editor
+        });
+        Self {
+            project_diff,
+            target,
+            editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let body = self.editor.read(cx).text(cx).trim().to_string();
+        if body.is_empty() {
+            return;
+        }
+        self.project_diff
+            .update(cx, |project_diff, cx| {
+                project_diff.post_review_comment(self.target.clone(), body, window, cx)
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for ReviewCommentModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.target {
+            ReviewCommentTarget::Reply { .. } => "Reply".to_string(),
+            ReviewCommentTarget::Line { path, line, .. } => {
+                format!("Comment on {}:{line}", path.as_unix_str())
+            }
+        };
+
+        v_flex()
+            .key_context("ReviewComment")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                div()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+    }
+}
+
+/// Asks for the API token of a hosting provider and stores it with the system credentials.
+pub(crate) struct ForgeTokenModal {
+    provider_name: String,
+    credentials_url: String,
+    editor: Entity<Editor>,
+}
+
+impl EventEmitter<DismissEvent> for ForgeTokenModal {}
+impl ModalView for ForgeTokenModal {}
+impl Focusable for ForgeTokenModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl ForgeTokenModal {
+    pub fn new(
+        provider_name: String,
+        credentials_url: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(true, cx);
+            editor.set_placeholder_text("API token", window, cx);
+            editor
+        });
+        Self {
+            provider_name,
+            credentials_url,
+            editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let token = self.editor.read(cx).text(cx).trim().to_string();
+        if token.is_empty() {
+            return;
+        }
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            credentials_provider
+                .write_credentials(&credentials_url, "token", token.as_bytes(), cx)
+                .await?;
+            cx.update(|window, cx| {
+                window.dispatch_action(Box::new(ReviewPullRequest), cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for ForgeTokenModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ForgeToken")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                v_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_0p5()
+                    .child(
+                        Headline::new(format!("{} API Token", self.provider_name))
+                            .size(HeadlineSize::XSmall),
+                    )
+                    .child(
+                        Label::new(
+                            "The token is used to read and post review comments, and is stored in your system keychain.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .bg(cx.theme().colors().editor_background)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+    }
+}
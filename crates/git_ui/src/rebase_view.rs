@@ -0,0 +1,1095 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::Editor;
+use git::repository::{RebaseAction, RebaseCommand, RebaseTodoEntry, RepoPath};
+use gpui::{
+    AnyElement, App, AsyncWindowContext, Context, Corner, Entity, EventEmitter, FocusHandle,
+    Focusable, ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity, Window,
+    actions, uniform_list,
+};
+use language::Buffer;
+use project::{
+    Project,
+    git_store::{ConflictSet, Repository, RepositoryEvent},
+};
+use ui::{ContextMenu, ListItem, PopoverMenu, Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt as _;
+use workspace::{Item, Workspace, item::ItemEvent, notifications::DetachAndPromptErr};
+
+actions!(
+    git,
+    [
+        /// Moves the selected commit up in the interactive rebase todo list.
+        MoveCommitUp,
+        /// Moves the selected commit down in the interactive rebase todo list.
+        MoveCommitDown,
+        /// Picks the selected commit as it is.
+        PickCommit,
+        /// Picks the selected commit with a new message.
+        RewordCommit,
+        /// Stops the rebase at the selected commit so that it can be amended.
+        EditCommit,
+        /// Melds the selected commit into the previous one, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit.
+        DropCommit,
+        /// Starts the interactive rebase with the current todo list.
+        StartRebase,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, action: &git::InteractiveRebase, window, cx| {
+        RebaseView::deploy(action.base.clone(), workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseContinue, window, cx| {
+        run_rebase_command(RebaseCommand::Continue, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseSkip, window, cx| {
+        run_rebase_command(RebaseCommand::Skip, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseAbort, window, cx| {
+        run_rebase_command(RebaseCommand::Abort, workspace, window, cx);
+    });
+}
+
+fn run_rebase_command(
+    command: RebaseCommand,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(view) = workspace.active_item_as::<RebaseView>(cx) {
+        view.update(cx, |view, cx| view.run_command(command, window, cx));
+        return;
+    }
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    repository
+        .update(cx, |repository, _| repository.rebase_command(command))
+        .detach_and_prompt_err(command_error_message(command), window, cx, |_, _, _| None);
+}
+
+fn command_error_message(command: RebaseCommand) -> &'static str {
+    match command {
+        RebaseCommand::Continue => "Failed to continue the rebase",
+        RebaseCommand::Skip => "Failed to skip the commit",
+        RebaseCommand::Abort => "Failed to abort the rebase",
+    }
+}
+
+/// A file that had conflicts when the rebase stopped.
+struct ConflictedFile {
+    repo_path: RepoPath,
+    conflict_set: Option<Entity<ConflictSet>>,
+    /// Keeps the buffer, and with it the conflict set, alive while the rebase is stopped.
+    _buffer: Option<Entity<Buffer>>,
+}
+
+impl ConflictedFile {
+    fn unresolved_conflicts(&self, cx: &App) -> Option<usize> {
+        let conflict_set = self.conflict_set.as_ref()?.read(cx);
+        Some(conflict_set.snapshot.conflicts.len())
+    }
+}
+
+/// Edits the todo list of an interactive rebase, and shows the progress of a rebase that stopped
+/// because of conflicts or to edit a commit.
+pub struct RebaseView {
+    repository: Entity<Repository>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    /// The revision whose descendants are rebased.
+    base: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    message_editors: HashMap<SharedString, Entity<Editor>>,
+    selected_ix: Option<usize>,
+    conflicted_files: Vec<ConflictedFile>,
+    pending_operation: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    _conflict_subscriptions: Vec<Subscription>,
+    _repository_subscription: Subscription,
+}
+
+impl RebaseView {
+    fn deploy(
+        base: Option<String>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let Some(repository) = project.read(cx).active_repository(cx) else {
+            return;
+        };
+        if let Some(existing) = workspace
+            .items_of_type::<RebaseView>(cx)
+            .find(|view| view.read(cx).repository == repository)
+        {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = workspace.weak_handle();
+        window
+            .spawn(cx, async move |cx| {
+                let base = match base {
+                    Some(base) => base,
+                    None => default_rebase_base(&repository, cx).await?,
+                };
+                let rebase_in_progress = repository
+                    .read_with(cx, |repository, _| repository.rebase_progress.is_some())?;
+                let entries = if rebase_in_progress {
+                    Vec::new()
+                } else {
+                    repository
+                        .update(cx, |repository, _| repository.rebase_todo(base.clone()))?
+                        .await??
+                };
+                workspace_handle.update_in(cx, |workspace, window, cx| {
+                    let view = cx.new(|cx| {
+                        RebaseView::new(
+                            repository,
+                            project,
+                            base.into(),
+                            entries,
+                            workspace.weak_handle(),
+                            window,
+                            cx,
+                        )
+                    });
+                    workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+                })?;
+                anyhow::Ok(())
+            })
+            .detach_and_prompt_err(
+                "Failed to start interactive rebase",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        project: Entity<Project>,
+        base: SharedString,
+        entries: Vec<RebaseTodoEntry>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let repository_subscription =
+            cx.subscribe_in(&repository, window, |this, _, event, window, cx| {
+                match event {
+                    RepositoryEvent::RebaseProgressChanged => {
+                        this.refresh_conflicted_files(window, cx);
+                        if this.repository.read(cx).rebase_progress.is_none() {
+                            this.reload_entries(window, cx);
+                        }
+                    }
+                    RepositoryEvent::MergeHeadsChanged => {
+                        this.refresh_conflicted_files(window, cx);
+                    }
+                    _ => {}
+                }
+                cx.notify();
+            });
+
+        let mut this = Self {
+            selected_ix: (!entries.is_empty()).then_some(0),
+            repository,
+            project,
+            workspace,
+            base,
+            entries,
+            message_editors: HashMap::default(),
+            conflicted_files: Vec::new(),
+            pending_operation: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _conflict_subscriptions: Vec::new(),
+            _repository_subscription: repository_subscription,
+        };
+        this.refresh_conflicted_files(window, cx);
+        this
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> &[RebaseTodoEntry] {
+        &self.entries
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let todo = self.repository.update(cx, |repository, _| {
+            repository.rebase_todo(self.base.to_string())
+        });
+        self.pending_operation = Some(cx.spawn_in(window, async move |this, cx| {
+            let entries = todo.await.map_err(anyhow::Error::from).flatten().log_err();
+            this.update(cx, |this, cx| {
+                this.pending_operation = None;
+                if let Some(entries) = entries {
+                    this.selected_ix = (!entries.is_empty()).then_some(0);
+                    this.entries = entries;
+                    this.message_editors.clear();
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Opens the conflict set of every file that had conflicts when the rebase stopped, so that
+    /// the view can show which of them still need to be resolved.
+    fn refresh_conflicted_files(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = self.repository.read(cx);
+        let conflicted_paths = if repository.rebase_progress.is_some() {
+            repository
+                .merge
+                .conflicted_paths
+                .iter()
+                .map(|repo_path| {
+                    let project_path = repository.repo_path_to_project_path(repo_path, cx);
+                    (repo_path.clone(), project_path)
+                })
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        if conflicted_paths
+            .iter()
+            .map(|(repo_path, _)| repo_path)
+            .eq(self.conflicted_files.iter().map(|file| &file.repo_path))
+        {
+            return;
+        }
+
+        let git_store = self.project.read(cx).git_store().clone();
+        let mut buffers = Vec::with_capacity(conflicted_paths.len());
+        self.conflicted_files.clear();
+        self._conflict_subscriptions.clear();
+        for (repo_path, project_path) in conflicted_paths {
+            buffers.push(project_path.map(|project_path| {
+                self.project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx))
+            }));
+            self.conflicted_files.push(ConflictedFile {
+                repo_path,
+                conflict_set: None,
+                _buffer: None,
+            });
+        }
+
+        cx.spawn_in(window, async move |this, cx| {
+            for (ix, buffer) in buffers.into_iter().enumerate() {
+                let Some(buffer) = buffer else {
+                    continue;
+                };
+                let Some(buffer) = buffer.await.log_err() else {
+                    continue;
+                };
+                let conflict_set = git_store.update(cx, |git_store, cx| {
+                    git_store.open_conflict_set(buffer.clone(), cx)
+                })?;
+                this.update(cx, |this, cx| {
+                    let subscription = cx.subscribe(&conflict_set, |_, _, _, cx| cx.notify());
+                    if let Some(file) = this.conflicted_files.get_mut(ix) {
+                        file.conflict_set = Some(conflict_set);
+                        file._buffer = Some(buffer);
+                        this._conflict_subscriptions.push(subscription);
+                    }
+                    cx.notify();
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix
+            && ix + 1 < self.entries.len()
+        {
+            self.select_ix(ix + 1, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix
+            && ix > 0
+        {
+            self.select_ix(ix - 1, cx);
+        }
+    }
+
+    fn select_ix(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_ix = Some(ix);
+        self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn move_commit_up(&mut self, _: &MoveCommitUp, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix
+            && ix > 0
+        {
+            self.entries.swap(ix, ix - 1);
+            self.select_ix(ix - 1, cx);
+        }
+    }
+
+    fn move_commit_down(&mut self, _: &MoveCommitDown, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix
+            && ix + 1 < self.entries.len()
+        {
+            self.entries.swap(ix, ix + 1);
+            self.select_ix(ix + 1, cx);
+        }
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix {
+            self.set_action(ix, action, window, cx);
+        }
+    }
+
+    pub(crate) fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        if action == RebaseAction::Reword && !self.message_editors.contains_key(&entry.sha) {
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::auto_height(3, 12, window, cx);
+                editor.set_text(entry.subject.to_string(), window, cx);
+                editor
+            });
+            self.message_editors
+                .insert(entry.sha.clone(), editor.clone());
+
+            // Start from the full message of the commit rather than just its subject.
+            let details = self
+                .repository
+                .update(cx, |repository, _| repository.show(entry.sha.to_string()));
+            cx.spawn_in(window, async move |_, cx| {
+                let details = details.await??;
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.set_text(details.message.trim_end(), window, cx);
+                })?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+        self.selected_ix = Some(ix);
+        cx.notify();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_message(
+        &mut self,
+        ix: usize,
+        message: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let sha = self.entries[ix].sha.clone();
+        if let Some(editor) = self.message_editors.get(&sha) {
+            editor.update(cx, |editor, cx| editor.set_text(message, window, cx));
+        }
+    }
+
+    /// Returns why the todo list can't be used for a rebase, if it can't.
+    fn validation_error(&self) -> Option<&'static str> {
+        if self.entries.is_empty() {
+            return Some("There are no commits to rebase");
+        }
+        if self
+            .entries
+            .iter()
+            .find(|entry| entry.action != RebaseAction::Drop)
+            .is_some_and(|entry| entry.action.melds_into_previous())
+        {
+            return Some("The first commit can't be squashed or fixed up into a previous commit");
+        }
+        None
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() || self.validation_error().is_some() {
+            return;
+        }
+        let todo = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let message = (entry.action == RebaseAction::Reword)
+                    .then(|| self.message_editors.get(&entry.sha))
+                    .flatten()
+                    .map(|editor| editor.read(cx).text(cx).into());
+                RebaseTodoEntry {
+                    message,
+                    ..entry.clone()
+                }
+            })
+            .collect();
+        let rebase = self.repository.update(cx, |repository, _| {
+            repository.interactive_rebase(self.base.to_string(), todo)
+        });
+        self.run_operation("Failed to rebase", async move |_| rebase.await?, window, cx);
+    }
+
+    pub(crate) fn run_command(
+        &mut self,
+        command: RebaseCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        if command == RebaseCommand::Continue {
+            let unresolved_conflicts: usize = self
+                .conflicted_files
+                .iter()
+                .filter_map(|file| file.unresolved_conflicts(cx))
+                .sum();
+            if unresolved_conflicts > 0 {
+                let message = format!(
+                    "Resolve the remaining {unresolved_conflicts} conflict{} before continuing",
+                    if unresolved_conflicts == 1 { "" } else { "s" }
+                );
+                window
+                    .prompt(gpui::PromptLevel::Info, &message, None, &["Ok"], cx)
+                    .detach();
+                return;
+            }
+        }
+
+        // Resolved files need to be staged for the rebase to continue.
+        let resolved_paths = self
+            .conflicted_files
+            .iter()
+            .map(|file| file.repo_path.clone())
+            .collect::<Vec<_>>();
+        let repository = self.repository.clone();
+        self.run_operation(
+            command_error_message(command),
+            async move |cx| {
+                if command == RebaseCommand::Continue && !resolved_paths.is_empty() {
+                    repository
+                        .update(cx, |repository, cx| {
+                            repository.stage_entries(resolved_paths, cx)
+                        })?
+                        .await?;
+                }
+                repository
+                    .update(cx, |repository, _| repository.rebase_command(command))?
+                    .await?
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn run_operation(
+        &mut self,
+        error_message: &'static str,
+        operation: impl AsyncFnOnce(&mut AsyncWindowContext) -> Result<()> + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.pending_operation = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = operation(cx).await;
+            this.update_in(cx, |this, window, cx| {
+                this.pending_operation = None;
+                cx.notify();
+                match result {
+                    // The rebase may have stopped on conflicts that need to be resolved.
+                    Ok(()) => this.refresh_conflicted_files(window, cx),
+                    Err(error) => prompt_error(error_message, &error, window, cx),
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn open_conflicted_file(
+        &mut self,
+        repo_path: &RepoPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project_path) = self
+            .repository
+            .read(cx)
+            .repo_path_to_project_path(repo_path, cx)
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_action_menu(
+        &self,
+        ix: usize,
+        action: RebaseAction,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let view = cx.weak_entity();
+        PopoverMenu::new(("rebase-action", ix))
+            .trigger(
+                Button::new(("rebase-action-trigger", ix), action.as_str())
+                    .label_size(LabelSize::Small)
+                    .color(action_color(action))
+                    .icon(IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_position(IconPosition::End),
+            )
+            .menu(move |window, cx| {
+                let view = view.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    for option in RebaseAction::ALL {
+                        let view = view.clone();
+                        menu = menu.toggleable_entry(
+                            option.as_str(),
+                            option == action,
+                            IconPosition::Start,
+                            Some(action_for(option)),
+                            move |window, cx| {
+                                view.update(cx, |view, cx| view.set_action(ix, option, window, cx))
+                                    .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+            .anchor(Corner::TopLeft)
+            .into_any_element()
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let short_sha = entry
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&entry.sha)
+            .to_string();
+        let subject = match (&entry.action, self.message_editors.get(&entry.sha)) {
+            (RebaseAction::Reword, Some(editor)) => editor
+                .read(cx)
+                .text(cx)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+                .into(),
+            _ => entry.subject.clone(),
+        };
+        let is_dropped = entry.action == RebaseAction::Drop;
+
+        ListItem::new(("rebase-entry", ix))
+            .toggle_state(Some(ix) == self.selected_ix)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .when(entry.action.melds_into_previous(), |this| this.pl_4())
+                    .child(
+                        div()
+                            .w(rems_from_px(80.))
+                            .flex_none()
+                            .child(self.render_action_menu(ix, entry.action, cx)),
+                    )
+                    .child(
+                        Label::new(short_sha)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        Label::new(subject)
+                            .size(LabelSize::Small)
+                            .when(is_dropped, |label| {
+                                label.strikethrough().color(Color::Disabled)
+                            })
+                            .truncate(),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.selected_ix = Some(ix);
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
+    fn render_todo(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_message_editor = self
+            .selected_ix
+            .and_then(|ix| self.entries.get(ix))
+            .filter(|entry| entry.action == RebaseAction::Reword)
+            .and_then(|entry| Some((entry, self.message_editors.get(&entry.sha)?.clone())));
+
+        v_flex()
+            .flex_1()
+            .size_full()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            "rebase-todo",
+                            self.entries.len(),
+                            cx.processor(|this, range: std::ops::Range<usize>, _, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }),
+                        )
+                        .flex_1()
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .when_some(selected_message_editor, |this, (entry, editor)| {
+                this.child(
+                    v_flex()
+                        .p_2()
+                        .gap_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new(format!("New message for {}", entry.subject))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(div().p_1().child(editor)),
+                )
+            })
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(self.validation_error().unwrap_or_default())
+                            .size(LabelSize::Small)
+                            .color(Color::Error),
+                    )
+                    .child(
+                        Button::new("start-rebase", "Start Rebase")
+                            .style(ButtonStyle::Filled)
+                            .disabled(
+                                self.pending_operation.is_some()
+                                    || self.validation_error().is_some(),
+                            )
+                            .key_binding(ui::KeyBinding::for_action_in(
+                                &StartRebase,
+                                &self.focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.start_rebase(&StartRebase, window, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_progress(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let progress = self.repository.read(cx).rebase_progress.clone();
+        let stopped_at = progress
+            .as_ref()
+            .and_then(|progress| progress.stopped_sha.as_ref())
+            .map(|sha| sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha).to_string());
+        let is_pending = self.pending_operation.is_some();
+
+        v_flex()
+            .flex_1()
+            .p_2()
+            .gap_2()
+            .child(
+                Label::new(match stopped_at {
+                    Some(sha) if self.conflicted_files.is_empty() => {
+                        format!("Stopped at {sha}. Amend the commit, then continue.")
+                    }
+                    Some(sha) => format!("Stopped at {sha} because of conflicts."),
+                    None => "The rebase is waiting to continue.".to_string(),
+                })
+                .color(Color::Muted),
+            )
+            .children(self.conflicted_files.iter().enumerate().map(|(ix, file)| {
+                let repo_path = file.repo_path.clone();
+                let status = match file.unresolved_conflicts(cx) {
+                    Some(0) => "Resolved".to_string(),
+                    Some(1) => "1 conflict".to_string(),
+                    Some(count) => format!("{count} conflicts"),
+                    None => "Loading…".to_string(),
+                };
+                let is_resolved = file.unresolved_conflicts(cx) == Some(0);
+                ListItem::new(("conflicted-file", ix))
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .child(
+                                Label::new(repo_path.as_unix_str().to_string())
+                                    .size(LabelSize::Small)
+                                    .buffer_font(cx),
+                            )
+                            .child(Label::new(status).size(LabelSize::Small).color(
+                                if is_resolved {
+                                    Color::Success
+                                } else {
+                                    Color::Conflict
+                                },
+                            )),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_conflicted_file(&repo_path, window, cx);
+                    }))
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("rebase-continue", "Continue")
+                            .style(ButtonStyle::Filled)
+                            .disabled(is_pending)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(RebaseCommand::Continue, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("rebase-skip", "Skip Commit")
+                            .disabled(is_pending)
+                            .tooltip(Tooltip::text("Leave out the commit the rebase stopped at"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(RebaseCommand::Skip, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("rebase-abort", "Abort")
+                            .disabled(is_pending)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(RebaseCommand::Abort, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+/// Picks the revision to rebase onto when none was given: the upstream of the current branch,
+/// or otherwise the default branch.
+async fn default_rebase_base(
+    repository: &Entity<Repository>,
+    cx: &mut AsyncWindowContext,
+) -> Result<String> {
+    let has_upstream = repository.read_with(cx, |repository, _| {
+        repository
+            .branch
+            .as_ref()
+            .is_some_and(|branch| branch.upstream.is_some())
+    })?;
+    if has_upstream {
+        return Ok("@{upstream}".into());
+    }
+    let default_branch = repository
+        .update(cx, |repository, _| repository.default_branch())?
+        .await??;
+    default_branch.map(String::from).context(
+        "the current branch has no upstream, and there is no default branch to rebase onto",
+    )
+}
+
+fn prompt_error(message: &str, error: &anyhow::Error, window: &mut Window, cx: &mut App) {
+    window
+        .prompt(
+            gpui::PromptLevel::Critical,
+            message,
+            Some(&format!("{error:#}")),
+            &["Ok"],
+            cx,
+        )
+        .detach();
+}
+
+fn action_for(action: RebaseAction) -> Box<dyn gpui::Action> {
+    match action {
+        RebaseAction::Pick => Box::new(PickCommit),
+        RebaseAction::Reword => Box::new(RewordCommit),
+        RebaseAction::Edit => Box::new(EditCommit),
+        RebaseAction::Squash => Box::new(SquashCommit),
+        RebaseAction::Fixup => Box::new(FixupCommit),
+        RebaseAction::Drop => Box::new(DropCommit),
+    }
+}
+
+fn action_color(action: RebaseAction) -> Color {
+    match action {
+        RebaseAction::Pick => Color::Default,
+        RebaseAction::Reword | RebaseAction::Edit => Color::Accent,
+        RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+        RebaseAction::Drop => Color::Error,
+    }
+}
+
+impl EventEmitter<ItemEvent> for RebaseView {}
+
+impl Focusable for RebaseView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let repository = self.repository.read(cx);
+        let title = match &repository.rebase_progress {
+            Some(progress) => format!(
+                "Rebasing {} onto {} ({}/{})",
+                progress.head_name.as_deref().unwrap_or("detached HEAD"),
+                progress
+                    .onto
+                    .get(..git::SHORT_SHA_LENGTH)
+                    .unwrap_or(&progress.onto),
+                progress.done,
+                progress.total
+            ),
+            None => format!("{} commits since {}", self.entries.len(), self.base),
+        };
+        let rebase_in_progress = repository.rebase_progress.is_some();
+
+        v_flex()
+            .id("rebase-view")
+            .key_context("RebaseView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_commit_up))
+            .on_action(cx.listener(Self::move_commit_down))
+            .on_action(cx.listener(Self::start_rebase))
+            .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Pick, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Reword, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &EditCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Edit, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Squash, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Fixup, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Drop, window, cx)
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).color(Color::Muted)),
+            )
+            .map(|this| {
+                if rebase_in_progress {
+                    this.child(self.render_progress(cx))
+                } else {
+                    this.child(self.render_todo(window, cx))
+                }
+            })
+    }
+}
+
+impl Item for RebaseView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Interactive Rebase".into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(
+            format!(
+                "Interactive rebase of {}",
+                self.repository.read(cx).display_name()
+            )
+            .into(),
+        )
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("interactive rebase")
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(sha: &str, subject: &str) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action: RebaseAction::Pick,
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            message: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_interactive_rebase_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "a.txt": "a",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_branch_name(dot_git, Some("feature"));
+        fs.with_git_state(dot_git, false, |state| {
+            state.rebase_todo = vec![
+                commit("aaaaaaaaaa", "Add a"),
+                commit("bbbbbbbbbb", "Add b"),
+                commit("cccccccccc", "Fix a"),
+            ];
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        cx.dispatch_action(git::InteractiveRebase {
+            base: Some("main".into()),
+        });
+        cx.run_until_parked();
+        let view = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<RebaseView>(cx)
+            })
+            .expect("rebase view should be open");
+
+        view.update_in(cx, |view, window, cx| {
+            assert_eq!(view.entries().len(), 3);
+
+            // Move the fix right after the commit it fixes.
+            view.select_ix(2, cx);
+            view.move_commit_up(&MoveCommitUp, window, cx);
+            view.set_action(0, RebaseAction::Reword, window, cx);
+            view.set_action(1, RebaseAction::Fixup, window, cx);
+            view.set_action(2, RebaseAction::Edit, window, cx);
+        });
+        cx.run_until_parked();
+        view.update_in(cx, |view, window, cx| {
+            view.set_message(0, "Add a properly", window, cx);
+            view.start_rebase(&StartRebase, window, cx);
+        });
+        cx.run_until_parked();
+
+        let rebases = fs
+            .with_git_state(dot_git, false, |state| state.rebases.clone())
+            .unwrap();
+        assert_eq!(rebases.len(), 1);
+        let (base, todo) = &rebases[0];
+        assert_eq!(base, "main");
+        assert_eq!(
+            todo.iter()
+                .map(|entry| (entry.action, entry.sha.as_ref(), entry.message.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                (RebaseAction::Reword, "aaaaaaaaaa", Some("Add a properly")),
+                (RebaseAction::Fixup, "cccccccccc", None),
+                (RebaseAction::Edit, "bbbbbbbbbb", None),
+            ]
+        );
+
+        // The rebase stopped to edit the last commit.
+        view.read_with(cx, |view, cx| {
+            let progress = view.repository.read(cx).rebase_progress.clone().unwrap();
+            assert_eq!((progress.done, progress.total), (3, 3));
+            assert_eq!(progress.stopped_sha.as_deref(), Some("bbbbbbbbbb"));
+        });
+
+        view.update_in(cx, |view, window, cx| {
+            view.run_command(RebaseCommand::Continue, window, cx);
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, cx| {
+            assert!(view.repository.read(cx).rebase_progress.is_none());
+        });
+    }
+}
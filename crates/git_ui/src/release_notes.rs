@@ -0,0 +1,217 @@
+use agent_settings::AgentSettings;
+use anyhow::{Context as _, Result};
+use cloud_llm_client::CompletionIntent;
+use editor::Editor;
+use futures::StreamExt as _;
+use git::{DraftReleaseNotes, repository::ReleaseCommits};
+use gpui::{AsyncWindowContext, Entity, SharedString, Window};
+use language::Buffer;
+use language_model::{
+    ConfiguredModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+use multi_buffer::MultiBuffer;
+use project::git_store::Repository;
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+/// Upper bound on the number of commits sent to the model, to keep the request small.
+const MAX_SUMMARIZED_COMMITS: usize = 500;
+
+const SUMMARY_PROMPT: &str = "You are writing release notes for the users of a software project. \
+Summarize the commits below as a short Markdown bullet list. Group related changes, lead with \
+user-facing features and fixes, and leave out internal refactors and chores. Respond with the \
+bullet list only.";
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &DraftReleaseNotes, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        draft_release_notes(workspace, repository, "HEAD".into(), window, cx);
+    });
+}
+
+/// Opens a buffer listing the commits between the tag preceding `head` and `head`. When a commit
+/// message model is configured, a summary of those commits is generated below the list.
+pub(crate) fn draft_release_notes(
+    workspace: &mut Workspace,
+    repository: Entity<Repository>,
+    head: SharedString,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let release = repository.update(cx, |repository, _| {
+        repository.release_commits(head.to_string())
+    });
+    let markdown = project.read(cx).languages().language_for_name("Markdown");
+    let model = AgentSettings::get_global(cx)
+        .enabled(cx)
+        .then(|| LanguageModelRegistry::read_global(cx).commit_message_model())
+        .flatten();
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let release = release.await??;
+        if release.commits.is_empty() {
+            anyhow::bail!(
+                "There are no commits since {}",
+                previous_release_name(&release)
+            );
+        }
+
+        let buffer = project
+            .update(cx, |project, cx| project.create_buffer(false, cx))?
+            .await?;
+        let markdown = markdown.await.log_err();
+        workspace.update_in(cx, |workspace, window, cx| {
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text(format_release_notes(&head, &release), cx);
+                buffer.set_language(markdown, cx);
+            });
+            let title = if head == "HEAD" {
+                "Release Notes".to_string()
+            } else {
+                format!("Release Notes for {head}")
+            };
+            let multibuffer =
+                cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx).with_title(title));
+            let editor =
+                cx.new(|cx| Editor::for_multibuffer(multibuffer, Some(project), window, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        })?;
+
+        if let Some(model) = model {
+            summarize_release(model, &release, &buffer, cx).await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to draft release notes", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+fn previous_release_name(release: &ReleaseCommits) -> SharedString {
+    release
+        .previous_tag
+        .clone()
+        .unwrap_or_else(|| "the first commit".into())
+}
+
+fn format_release_notes(head: &str, release: &ReleaseCommits) -> String {
+    let mut notes = String::new();
+    if head != "HEAD" {
+        notes.push_str(&format!("# {head}\n\n"));
+    }
+    notes.push_str(&format!(
+        "## Changes since {}\n\n",
+        previous_release_name(release)
+    ));
+    for commit in &release.commits {
+        let short_sha = commit
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&commit.sha);
+        notes.push_str(&format!("- {} ({short_sha})\n", commit.subject));
+    }
+    notes
+}
+
+async fn summarize_release(
+    ConfiguredModel { provider, model }: ConfiguredModel,
+    release: &ReleaseCommits,
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    if let Some(task) = cx.update(|_, cx| {
+        if !provider.is_authenticated(cx) {
+            Some(provider.authenticate(cx))
+        } else {
+            None
+        }
+    })? {
+        task.await.log_err();
+    }
+
+    let commits = release
+        .commits
+        .iter()
+        .take(MAX_SUMMARIZED_COMMITS)
+        .map(|commit| format!("- {} ({})", commit.subject, commit.author_name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let temperature = cx.update(|_, cx| AgentSettings::temperature_for_model(&model, cx))?;
+    let request = LanguageModelRequest {
+        thread_id: None,
+        prompt_id: None,
+        intent: Some(CompletionIntent::GenerateGitCommitMessage),
+        mode: None,
+        messages: vec![LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![format!("{SUMMARY_PROMPT}\n\n<commits>\n{commits}\n</commits>").into()],
+            cache: false,
+            reasoning_details: None,
+        }],
+        tools: Vec::new(),
+        tool_choice: None,
+        stop: Vec::new(),
+        temperature,
+        thinking_allowed: false,
+    };
+
+    let mut messages = model
+        .stream_completion_text(request, cx)
+        .await
+        .context("generating the release summary")?;
+    let append = |text: &str, cx: &mut AsyncWindowContext| {
+        buffer.update(cx, |buffer, cx| {
+            let end = buffer.len();
+            buffer.edit([(end..end, text)], None, cx);
+        })
+    };
+    append("\n## Summary\n\n", cx)?;
+    while let Some(message) = messages.stream.next().await {
+        append(&message.context("generating the release summary")?, cx)?;
+    }
+    append("\n", cx)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::CommitSummary;
+
+    #[test]
+    fn test_format_release_notes() {
+        let commit = |sha: &str, subject: &str| CommitSummary {
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            commit_timestamp: 0,
+            author_name: "Jane".into(),
+            has_parent: true,
+        };
+        let release = ReleaseCommits {
+            previous_tag: Some("v1.0".into()),
+            commits: vec![
+                commit("1111111111", "Add tag picker"),
+                commit("2222222222", "Fix crash on startup"),
+            ],
+        };
+
+        assert_eq!(
+            format_release_notes("v1.1", &release),
+            "# v1.1\n\n## Changes since v1.0\n\n- Add tag picker (1111111)\n- Fix crash on startup (2222222)\n"
+        );
+        assert_eq!(
+            format_release_notes(
+                "HEAD",
+                &ReleaseCommits {
+                    previous_tag: None,
+                    commits: vec![commit("abc", "Initial commit")],
+                }
+            ),
+            "## Changes since the first commit\n\n- Initial commit (abc)\n"
+        );
+    }
+}
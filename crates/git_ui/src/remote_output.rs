@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 
-use git::repository::{Remote, RemoteCommandOutput};
+use git::repository::{Remote, RemoteCommandOutput, SubmoduleCommand};
 use linkify::{LinkFinder, LinkKind};
 use ui::SharedString;
 use util::ResultExt as _;
@@ -10,6 +10,7 @@ pub enum RemoteAction {
     Fetch(Option<Remote>),
     Pull(Remote),
     Push(SharedString, Remote),
+    Submodule(SubmoduleCommand),
 }
 
 impl RemoteAction {
@@ -18,6 +19,9 @@ impl RemoteAction {
             RemoteAction::Fetch(_) => "fetch",
             RemoteAction::Pull(_) => "pull",
             RemoteAction::Push(_, _) => "push",
+            RemoteAction::Submodule(SubmoduleCommand::Init) => "submodule init",
+            RemoteAction::Submodule(SubmoduleCommand::Update) => "submodule update",
+            RemoteAction::Submodule(SubmoduleCommand::Sync) => "submodule sync",
         }
     }
 }
@@ -157,6 +161,22 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 style: style.unwrap_or(SuccessStyle::ToastWithLog { output }),
             }
         }
+        RemoteAction::Submodule(command) => {
+            let message = match command {
+                SubmoduleCommand::Init => "Initialized submodules",
+                SubmoduleCommand::Update => "Updated submodules",
+                SubmoduleCommand::Sync => "Synchronized submodule URLs",
+            };
+            let style = if output.stdout.is_empty() && output.stderr.is_empty() {
+                SuccessStyle::Toast
+            } else {
+                SuccessStyle::ToastWithLog { output }
+            };
+            SuccessMessage {
+                message: message.into(),
+                style,
+            }
+        }
     }
 }
 
@@ -0,0 +1,699 @@
+use editor::Editor;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::Tag;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, actions, rems,
+};
+use menu::{Cancel, Confirm};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use time_format;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::GitPanel;
+use crate::{release_notes, tag_picker};
+
+actions!(
+    tag_picker,
+    [
+        /// Delete the selected tag.
+        DeleteTag,
+        /// Push the selected tag to a remote.
+        PushTag,
+        /// Draft release notes from the commits between the previous tag and the selected tag.
+        DraftTagReleaseNotes,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewTags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(repository, workspace, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, cx| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+            picker.delegate.reload_tags(window, cx);
+        });
+
+        let _subscriptions = vec![cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        })];
+
+        Self {
+            picker,
+            picker_focus_handle,
+            width,
+            _subscriptions,
+        }
+    }
+
+    fn handle_delete_tag(&mut self, _: &DeleteTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+
+    fn handle_push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .push_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+
+    fn handle_draft_release_notes(
+        &mut self,
+        _: &DraftTagReleaseNotes,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .draft_release_notes_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+impl Focusable for TagList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_delete_tag))
+            .on_action(cx.listener(Self::handle_push_tag))
+            .on_action(cx.listener(Self::handle_draft_release_notes))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TagEntry {
+    Tag {
+        tag: Tag,
+        positions: Vec<usize>,
+        formatted_timestamp: String,
+    },
+    NewTag {
+        name: String,
+    },
+}
+
+pub struct TagListDelegate {
+    matches: Vec<TagEntry>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+    timezone: UtcOffset,
+}
+
+impl TagListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<TagList>,
+    ) -> Self {
+        let timezone = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+
+        Self {
+            matches: vec![],
+            repo,
+            workspace,
+            all_tags: None,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+            timezone,
+        }
+    }
+
+    fn format_timestamp(timestamp: i64, timezone: UtcOffset) -> String {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or(OffsetDateTime::now_utc());
+        time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            timezone,
+            time_format::TimestampFormat::EnhancedAbsolute,
+        )
+    }
+
+    fn reload_tags(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let tags = repo.update(cx, |repo, _| repo.tags());
+        cx.spawn_in(window, async move |picker, cx| {
+            let tags = tags.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_tags = Some(tags);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn tag_at(&self, ix: usize) -> Option<&Tag> {
+        match self.matches.get(ix)? {
+            TagEntry::Tag { tag, .. } => Some(tag),
+            TagEntry::NewTag { .. } => None,
+        }
+    }
+
+    fn delete_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = tag.name.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| repo.delete_tag(name.to_string()))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                if let Some(tags) = picker.delegate.all_tags.as_mut() {
+                    tags.retain(|tag| tag.name != name);
+                }
+                picker.refresh(window, cx);
+            })?;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(git_panel) = workspace.read(cx).panel::<GitPanel>(cx) else {
+            return;
+        };
+        let name = tag.name.clone();
+        cx.emit(DismissEvent);
+        git_panel.update(cx, |git_panel, cx| git_panel.push_tag(name, window, cx));
+    }
+
+    fn draft_release_notes_at(
+        &self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = tag.name.clone();
+        cx.emit(DismissEvent);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                release_notes::draft_release_notes(workspace, repo, name, window, cx)
+            })
+            .log_err();
+    }
+
+    fn show_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            tag.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+
+    fn create_tag(&self, name: String, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name, "HEAD".into(), None))?
+                .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn create_annotated_tag(
+        &self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        cx.emit(DismissEvent);
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                TagMessageModal::new(name, repo, window, cx)
+            });
+        });
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select or create a tag…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        let timezone = self.timezone;
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<TagEntry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| {
+                        let formatted_timestamp = Self::format_timestamp(tag.timestamp, timezone);
+                        TagEntry::Tag {
+                            tag,
+                            positions: Vec::new(),
+                            formatted_timestamp,
+                        }
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    let tag = all_tags[candidate.candidate_id].clone();
+                    let formatted_timestamp = Self::format_timestamp(tag.timestamp, timezone);
+                    TagEntry::Tag {
+                        tag,
+                        positions: candidate.positions,
+                        formatted_timestamp,
+                    }
+                })
+                .collect()
+            };
+
+            let name = query.trim().replace(' ', "-");
+            if !name.is_empty()
+                && !matches
+                    .iter()
+                    .any(|entry| matches!(entry, TagEntry::Tag { tag, .. } if tag.name == name))
+            {
+                matches.push(TagEntry::NewTag { name });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        match self.matches.get(self.selected_index()) {
+            Some(TagEntry::NewTag { name }) => {
+                let name = name.clone();
+                if secondary {
+                    self.create_annotated_tag(name, window, cx);
+                } else {
+                    self.create_tag(name, window, cx);
+                }
+            }
+            Some(TagEntry::Tag { .. }) => {
+                self.show_tag_at(self.selected_index(), window, cx);
+            }
+            None => {}
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(format!("tag-{ix}"))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+
+        match &self.matches[ix] {
+            TagEntry::NewTag { name } => Some(
+                item.start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(
+                        Label::new(format!("Create Tag: \"{name}\" at HEAD"))
+                            .single_line()
+                            .truncate(),
+                    ),
+            ),
+            TagEntry::Tag {
+                tag,
+                positions,
+                formatted_timestamp,
+            } => {
+                let tag_label = HighlightedLabel::new(tag.name.clone(), positions.clone())
+                    .truncate()
+                    .into_any_element();
+
+                let short_sha = tag.sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(&tag.sha);
+                let tag_info = h_flex()
+                    .gap_1p5()
+                    .w_full()
+                    .child(
+                        Label::new(tag.subject.clone())
+                            .truncate()
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        Label::new("•")
+                            .alpha(0.5)
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        Label::new(formatted_timestamp.clone())
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    );
+
+                let kind = if tag.annotated {
+                    "Annotated"
+                } else {
+                    "Lightweight"
+                };
+                Some(
+                    item.start_slot(Icon::new(IconName::Hash).color(Color::Muted))
+                        .child(v_flex().w_full().child(tag_label).child(tag_info))
+                        .tooltip(Tooltip::text(format!("{kind} tag at {short_sha}"))),
+                )
+            }
+        }
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No tags found".into())
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let focus_handle = self.focus_handle.clone();
+        let footer = h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant);
+
+        if let Some(TagEntry::NewTag { .. }) = self.matches.get(self.selected_index) {
+            return Some(
+                footer
+                    .child(
+                        Button::new("create-annotated-tag", "Create Annotated")
+                            .key_binding(
+                                KeyBinding::for_action_in(
+                                    &menu::SecondaryConfirm,
+                                    &focus_handle,
+                                    cx,
+                                )
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("create-tag", "Create")
+                            .key_binding(
+                                KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                            }),
+                    )
+                    .into_any(),
+            );
+        }
+
+        Some(
+            footer
+                .child(
+                    Button::new("delete-tag", "Delete")
+                        .key_binding(
+                            KeyBinding::for_action_in(&tag_picker::DeleteTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(tag_picker::DeleteTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("push-tag", "Push")
+                        .key_binding(
+                            KeyBinding::for_action_in(&tag_picker::PushTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(tag_picker::PushTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("draft-release-notes", "Release Notes")
+                        .key_binding(
+                            KeyBinding::for_action_in(
+                                &tag_picker::DraftTagReleaseNotes,
+                                &focus_handle,
+                                cx,
+                            )
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window
+                                .dispatch_action(tag_picker::DraftTagReleaseNotes.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("view-tag", "View")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+}
+
+/// Asks for the message of an annotated tag to create at `HEAD`.
+struct TagMessageModal {
+    name: String,
+    editor: Entity<Editor>,
+    repository: Entity<Repository>,
+}
+
+impl TagMessageModal {
+    fn new(
+        name: String,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag message", window, cx);
+            editor
+        });
+        Self {
+            name,
+            editor,
+            repository,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let message = self.editor.read(cx).text(cx).trim().to_string();
+        if message.is_empty() {
+            return;
+        }
+
+        let name = self.name.clone();
+        let task = self.repository.update(cx, |repository, _| {
+            repository.create_tag(name, "HEAD".into(), Some(message))
+        });
+        cx.spawn(async move |_, _| {
+            task.await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for TagMessageModal {}
+impl ModalView for TagMessageModal {}
+impl Focusable for TagMessageModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for TagMessageModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagMessageModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Hash).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("Create Annotated Tag \"{}\"", self.name))
+                            .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(div().px_3().pb_3().w_full().child(self.editor.clone()))
+    }
+}
@@ -0,0 +1,579 @@
+use std::ops::Range;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, ScrollStrategy, SharedString, Styled,
+    Task, UniformListScrollHandle, WeakEntity, Window, actions, px, uniform_list,
+};
+use language::ToPoint as _;
+use project::{HierarchyCall, HierarchyItem, Location, Project};
+use theme::ActiveTheme;
+use ui::{
+    Color, FluentBuilder as _, Label, LabelCommon, LabelSize, ListItem, Toggleable, WithScrollbar,
+    h_flex, v_flex,
+};
+use workspace::{Pane, SplitDirection, Workspace, item::Item};
+
+actions!(
+    hierarchy_view,
+    [
+        /// Shows the functions calling the symbol under the cursor.
+        ShowIncomingCalls,
+        /// Shows the functions called by the symbol under the cursor.
+        ShowOutgoingCalls,
+        /// Shows the supertypes of the type under the cursor.
+        ShowSupertypes,
+        /// Shows the subtypes of the type under the cursor.
+        ShowSubtypes,
+        /// Expands the selected hierarchy entry, fetching its children if needed.
+        ExpandSelectedEntry,
+        /// Collapses the selected hierarchy entry, or selects its parent.
+        CollapseSelectedEntry,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::IncomingCalls, window, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::OutgoingCalls, window, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Supertypes, window, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Subtypes, window, cx)
+        });
+    })
+    .detach();
+}
+
+/// Queries the hierarchy of the symbol under the cursor of the active editor,
+/// and shows it in the existing hierarchy view or a new one.
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = ({
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let roots = project.update(cx, |project, cx| match kind {
+        HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls => {
+            project.prepare_call_hierarchy(&buffer, position, cx)
+        }
+        HierarchyKind::Supertypes | HierarchyKind::Subtypes => {
+            project.prepare_type_hierarchy(&buffer, position, cx)
+        }
+    });
+    let source_pane = workspace.active_pane().downgrade();
+
+    if let Some(view) = workspace.item_of_type::<HierarchyView>(cx) {
+        view.update(cx, |view, cx| {
+            view.source_pane = source_pane;
+            view.set_roots(kind, roots, cx);
+        });
+        workspace.activate_item(&view, true, true, window, cx);
+    } else {
+        let workspace_handle = workspace.weak_handle();
+        let view = cx
+            .new(|cx| HierarchyView::new(workspace_handle, project, source_pane, kind, roots, cx));
+        workspace.split_item(SplitDirection::Right, Box::new(view), window, cx);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn label(&self) -> &'static str {
+        match self {
+            HierarchyKind::IncomingCalls => "Incoming Calls",
+            HierarchyKind::OutgoingCalls => "Outgoing Calls",
+            HierarchyKind::Supertypes => "Supertypes",
+            HierarchyKind::Subtypes => "Subtypes",
+        }
+    }
+}
+
+enum Children {
+    NotLoaded,
+    Loading { _task: Task<()> },
+    Loaded(Vec<usize>),
+    Failed(SharedString),
+}
+
+struct HierarchyNode {
+    call: HierarchyCall,
+    parent: Option<usize>,
+    depth: usize,
+    expanded: bool,
+    children: Children,
+}
+
+enum RootsState {
+    Loading { _task: Task<()> },
+    Loaded(Vec<usize>),
+    Failed(SharedString),
+}
+
+/// A tree of the callers, callees, supertypes or subtypes of a symbol,
+/// whose entries are fetched from the language server when expanded.
+pub struct HierarchyView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    /// The pane the hierarchy was requested from, where entries are opened.
+    source_pane: WeakEntity<Pane>,
+    kind: HierarchyKind,
+    nodes: Vec<HierarchyNode>,
+    roots: RootsState,
+    visible_nodes: Vec<usize>,
+    selected_node: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+impl HierarchyView {
+    pub fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        source_pane: WeakEntity<Pane>,
+        kind: HierarchyKind,
+        roots: Task<anyhow::Result<Vec<HierarchyItem>>>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            workspace,
+            project,
+            source_pane,
+            kind,
+            nodes: Vec::new(),
+            roots: RootsState::Loaded(Vec::new()),
+            visible_nodes: Vec::new(),
+            selected_node: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        };
+        this.set_roots(kind, roots, cx);
+        this
+    }
+
+    fn set_roots(
+        &mut self,
+        kind: HierarchyKind,
+        roots: Task<anyhow::Result<Vec<HierarchyItem>>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.kind = kind;
+        self.nodes.clear();
+        self.visible_nodes.clear();
+        self.selected_node = None;
+        self.roots = RootsState::Loading {
+            _task: cx.spawn(async move |this, cx| {
+                let roots = roots.await;
+                this.update(cx, |this, cx| {
+                    match roots {
+                        Ok(items) => {
+                            let roots = items
+                                .into_iter()
+                                .map(|item| {
+                                    this.push_node(
+                                        HierarchyCall {
+                                            item,
+                                            call_sites: Vec::new(),
+                                        },
+                                        None,
+                                    )
+                                })
+                                .collect::<Vec<_>>();
+                            this.selected_node = roots.first().copied();
+                            this.roots = RootsState::Loaded(roots.clone());
+                            for root in roots {
+                                this.expand(root, cx);
+                            }
+                        }
+                        Err(error) => {
+                            this.roots = RootsState::Failed(format!("{error:#}").into());
+                        }
+                    }
+                    this.update_visible_nodes();
+                    cx.notify();
+                })
+                .ok();
+            }),
+        };
+        cx.notify();
+    }
+
+    fn push_node(&mut self, call: HierarchyCall, parent: Option<usize>) -> usize {
+        let depth = parent.map_or(0, |parent| self.nodes[parent].depth + 1);
+        self.nodes.push(HierarchyNode {
+            call,
+            parent,
+            depth,
+            expanded: false,
+            children: Children::NotLoaded,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn fetch_children(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Vec<HierarchyCall>>> {
+        let types = match self.kind {
+            HierarchyKind::IncomingCalls => {
+                return self
+                    .project
+                    .update(cx, |project, cx| project.incoming_calls(item, cx));
+            }
+            HierarchyKind::OutgoingCalls => {
+                return self
+                    .project
+                    .update(cx, |project, cx| project.outgoing_calls(item, cx));
+            }
+            HierarchyKind::Supertypes => self
+                .project
+                .update(cx, |project, cx| project.supertypes(item, cx)),
+            HierarchyKind::Subtypes => self
+                .project
+                .update(cx, |project, cx| project.subtypes(item, cx)),
+        };
+        cx.background_spawn(async move {
+            Ok(types
+                .await?
+                .into_iter()
+                .map(|item| HierarchyCall {
+                    item,
+                    call_sites: Vec::new(),
+                })
+                .collect())
+        })
+    }
+
+    fn expand(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        self.nodes[node_ix].expanded = true;
+        if matches!(self.nodes[node_ix].children, Children::NotLoaded) {
+            let children = self.fetch_children(&self.nodes[node_ix].call.item, cx);
+            self.nodes[node_ix].children = Children::Loading {
+                _task: cx.spawn(async move |this, cx| {
+                    let children = children.await;
+                    this.update(cx, |this, cx| {
+                        this.nodes[node_ix].children = match children {
+                            Ok(calls) => Children::Loaded(
+                                calls
+                                    .into_iter()
+                                    .map(|call| this.push_node(call, Some(node_ix)))
+                                    .collect(),
+                            ),
+                            Err(error) => Children::Failed(format!("{error:#}").into()),
+                        };
+                        this.update_visible_nodes();
+                        cx.notify();
+                    })
+                    .ok();
+                }),
+            };
+        }
+        self.update_visible_nodes();
+        cx.notify();
+    }
+
+    fn collapse(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        self.nodes[node_ix].expanded = false;
+        self.update_visible_nodes();
+        cx.notify();
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        if self.nodes[node_ix].expanded {
+            self.collapse(node_ix, cx);
+        } else {
+            self.expand(node_ix, cx);
+        }
+    }
+
+    fn update_visible_nodes(&mut self) {
+        self.visible_nodes.clear();
+        let RootsState::Loaded(roots) = &self.roots else {
+            return;
+        };
+        let mut stack = roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node_ix) = stack.pop() {
+            self.visible_nodes.push(node_ix);
+            let node = &self.nodes[node_ix];
+            if node.expanded
+                && let Children::Loaded(children) = &node.children
+            {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+    }
+
+    fn select_visible_node(&mut self, visible_ix: usize, cx: &mut Context<Self>) {
+        if let Some(node_ix) = self.visible_nodes.get(visible_ix) {
+            self.selected_node = Some(*node_ix);
+            self.scroll_handle
+                .scroll_to_item(visible_ix, ScrollStrategy::Nearest);
+            cx.notify();
+        }
+    }
+
+    fn selected_visible_ix(&self) -> Option<usize> {
+        let selected_node = self.selected_node?;
+        self.visible_nodes
+            .iter()
+            .position(|node_ix| *node_ix == selected_node)
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let next_ix = self.selected_visible_ix().map_or(0, |ix| {
+            (ix + 1).min(self.visible_nodes.len().saturating_sub(1))
+        });
+        self.select_visible_node(next_ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_ix = self
+            .selected_visible_ix()
+            .map_or(0, |ix| ix.saturating_sub(1));
+        self.select_visible_node(previous_ix, cx);
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node_ix) = self.selected_node else {
+            return;
+        };
+        let node = &self.nodes[node_ix];
+        if !node.expanded {
+            self.expand(node_ix, cx);
+        } else if let Children::Loaded(children) = &node.children
+            && let Some(first_child) = children.first().copied()
+        {
+            self.selected_node = Some(first_child);
+            if let Some(visible_ix) = self.selected_visible_ix() {
+                self.select_visible_node(visible_ix, cx);
+            }
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node_ix) = self.selected_node else {
+            return;
+        };
+        let node = &self.nodes[node_ix];
+        if node.expanded {
+            self.collapse(node_ix, cx);
+        } else if let Some(parent) = node.parent {
+            self.selected_node = Some(parent);
+            if let Some(visible_ix) = self.selected_visible_ix() {
+                self.select_visible_node(visible_ix, cx);
+            }
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.open_node(node_ix, window, cx);
+        }
+    }
+
+    fn open_node(&mut self, node_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Location { buffer, range } = self.nodes[node_ix].call.item.location.clone();
+        let source_pane = self.source_pane.upgrade();
+        workspace.update(cx, |workspace, cx| {
+            let pane = source_pane
+                .filter(|pane| workspace.panes().contains(pane))
+                .unwrap_or_else(|| workspace.active_pane().clone());
+            let position = range.start.to_point(buffer.read(cx));
+            let editor = workspace
+                .open_project_item::<Editor>(pane, buffer, true, true, true, true, window, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |s| s.select_ranges([position..position]),
+                );
+            });
+        });
+    }
+
+    fn render_entries(&self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<ListItem> {
+        self.visible_nodes[range]
+            .iter()
+            .map(|&node_ix| {
+                let node = &self.nodes[node_ix];
+                let toggle = match &node.children {
+                    Children::Loaded(children) if children.is_empty() => None,
+                    _ => Some(node.expanded),
+                };
+                let status = match &node.children {
+                    Children::Loading { .. } if node.expanded => {
+                        Some(Label::new("Loading…").color(Color::Muted))
+                    }
+                    Children::Failed(error) => Some(Label::new(error.clone()).color(Color::Error)),
+                    _ => match node.call.call_sites.len() {
+                        0 | 1 => None,
+                        call_count => {
+                            Some(Label::new(format!("{call_count} calls")).color(Color::Muted))
+                        }
+                    },
+                };
+                ListItem::new(node_ix)
+                    .indent_level(node.depth)
+                    .indent_step_size(px(12.))
+                    .toggle(toggle)
+                    .toggle_state(self.selected_node == Some(node_ix))
+                    .on_toggle(cx.listener(move |this, _, _, cx| {
+                        this.toggle_expanded(node_ix, cx);
+                    }))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_node = Some(node_ix);
+                        this.open_node(node_ix, window, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(node.call.item.name.clone()))
+                            .when_some(node.call.item.detail.clone(), |this, detail| {
+                                this.child(
+                                    Label::new(detail)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .end_slot(status.map(|label| label.size(LabelSize::Small)))
+            })
+            .collect()
+    }
+}
+
+impl Render for HierarchyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("hierarchy-view")
+            .key_context("HierarchyView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(self.kind.label())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .map(|this| {
+                let message = match &self.roots {
+                    RootsState::Loading { .. } => Some(Label::new("Loading…")),
+                    RootsState::Failed(error) => {
+                        Some(Label::new(error.clone()).color(Color::Error))
+                    }
+                    RootsState::Loaded(roots) if roots.is_empty() => {
+                        Some(Label::new("No hierarchy found at the cursor"))
+                    }
+                    RootsState::Loaded(_) => None,
+                };
+                match message {
+                    Some(message) => this.child(
+                        h_flex()
+                            .size_full()
+                            .justify_center()
+                            .child(message.color(Color::Muted)),
+                    ),
+                    None => this
+                        .child(
+                            uniform_list(
+                                "HierarchyView",
+                                self.visible_nodes.len(),
+                                cx.processor(|this, range: Range<usize>, _, cx| {
+                                    this.render_entries(range, cx)
+                                }),
+                            )
+                            .size_full()
+                            .track_scroll(&self.scroll_handle),
+                        )
+                        .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                }
+            })
+    }
+}
+
+impl EventEmitter<()> for HierarchyView {}
+
+impl Focusable for HierarchyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HierarchyView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        match &self.roots {
+            RootsState::Loaded(roots) if roots.len() == 1 => format!(
+                "{}: {}",
+                self.kind.label(),
+                self.nodes[roots[0]].call.item.name
+            )
+            .into(),
+            _ => self.kind.label().into(),
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
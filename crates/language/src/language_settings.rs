@@ -11,9 +11,10 @@ use gpui::{App, Modifiers, SharedString};
 use itertools::{Either, Itertools};
 
 pub use settings::{
-    CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FoldingRanges,
+    FormatOnSave, Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
+    RewrapBehavior, SelectionRanges, SemanticTokens, ShowWhitespaceSetting, SoftWrap,
+    WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use shellexpand;
@@ -166,6 +167,12 @@ pub struct LanguageSettings {
     pub word_diff_enabled: bool,
     /// Whether to use tree-sitter bracket queries to detect and colorize the brackets in the editor.
     pub colorize_brackets: bool,
+    /// How to use the semantic tokens provided by language servers to highlight code.
+    pub semantic_tokens: SemanticTokens,
+    /// Where the foldable ranges of the editor come from.
+    pub folding_ranges: FoldingRanges,
+    /// Where the ranges used to expand and shrink selections come from.
+    pub selection_ranges: SelectionRanges,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -604,6 +611,9 @@ impl settings::Settings for AllLanguageSettings {
                 show_completions_on_input: settings.show_completions_on_input.unwrap(),
                 show_completion_documentation: settings.show_completion_documentation.unwrap(),
                 colorize_brackets: settings.colorize_brackets.unwrap(),
+                semantic_tokens: settings.semantic_tokens.unwrap(),
+                folding_ranges: settings.folding_ranges.unwrap(),
+                selection_ranges: settings.selection_ranges.unwrap(),
                 completions: CompletionSettings {
                     words: completions.words.unwrap(),
                     words_min_length: completions.words_min_length.unwrap() as usize,
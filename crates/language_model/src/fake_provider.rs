@@ -48,15 +48,15 @@ impl LanguageModelProvider for FakeLanguageModelProvider {
     }
 
     fn default_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(Arc::new(FakeLanguageModel::default()))
+        Some(Arc::new(self.test_model()))
     }
 
     fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
-        Some(Arc::new(FakeLanguageModel::default()))
+        Some(Arc::new(self.test_model()))
     }
 
     fn provided_models(&self, _: &App) -> Vec<Arc<dyn LanguageModel>> {
-        vec![Arc::new(FakeLanguageModel::default())]
+        vec![Arc::new(self.test_model())]
     }
 
     fn is_authenticated(&self, _: &App) -> bool {
@@ -87,7 +87,11 @@ impl FakeLanguageModelProvider {
     }
 
     pub fn test_model(&self) -> FakeLanguageModel {
-        FakeLanguageModel::default()
+        FakeLanguageModel {
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            ..FakeLanguageModel::default()
+        }
     }
 }
 
@@ -3,6 +3,7 @@ mod model;
 mod rate_limiter;
 mod registry;
 mod request;
+mod response_cache;
 mod role;
 mod telemetry;
 pub mod tool_schema;
@@ -36,6 +37,7 @@ pub use crate::model::*;
 pub use crate::rate_limiter::*;
 pub use crate::registry::*;
 pub use crate::request::*;
+pub use crate::response_cache::*;
 pub use crate::role::*;
 pub use crate::telemetry::*;
 pub use crate::tool_schema::LanguageModelToolSchemaFormat;
@@ -314,6 +316,27 @@ impl LanguageModelCompletionError {
             },
         }
     }
+
+    /// Whether the same request might succeed if it is sent again, possibly to another model.
+    /// This is false for errors that need the user to act, such as missing credentials.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpResponseError {
+                status_code:
+                    StatusCode::PAYLOAD_TOO_LARGE | StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED,
+                ..
+            }
+            | Self::AuthenticationError { .. }
+            | Self::PermissionError { .. }
+            | Self::NoApiKey { .. }
+            | Self::ApiEndpointNotFound { .. }
+            | Self::PromptTooLarge { .. } => false,
+            Self::Other(error) => {
+                !error.is::<PaymentRequiredError>() && !error.is::<ModelRequestLimitReachedError>()
+            }
+            _ => true,
+        }
+    }
 }
 
 impl From<AnthropicError> for LanguageModelCompletionError {
@@ -400,7 +423,8 @@ impl From<open_ai::RequestError> for LanguageModelCompletionError {
                 let retry_after = headers
                     .get(http::header::RETRY_AFTER)
                     .and_then(|val| val.to_str().ok()?.parse::<u64>().ok())
-                    .map(Duration::from_secs);
+                    .map(Duration::from_secs)
+                    .or_else(|| exhausted_rate_limit_reset(&headers));
 
                 Self::from_http_status(provider.into(), status_code, body, retry_after)
             }
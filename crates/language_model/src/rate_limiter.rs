@@ -1,60 +1,492 @@
+use chrono::{DateTime, Utc};
+use collections::HashMap;
 use futures::Stream;
+use gpui::{App, Global};
+use http_client::http::HeaderMap;
+use parking_lot::Mutex;
+use rand::Rng as _;
 use smol::lock::{Semaphore, SemaphoreGuardArc};
 use std::{
+    collections::VecDeque,
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
-use crate::LanguageModelCompletionError;
+use crate::{LanguageModelCompletionError, LanguageModelProviderId};
+
+/// The sliding window over which per-minute limits are enforced.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// The maximum fraction of a backoff delay that is added as random jitter, so
+/// that requests which were rate limited together don't all retry at once.
+const MAX_BACKOFF_JITTER: f64 = 0.2;
+
+/// Client-side limits for the requests sent to a single language model provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimitPolicy {
+    /// The maximum number of requests to start in any one minute window.
+    pub requests_per_minute: Option<u32>,
+    /// The maximum number of (estimated) input tokens to send in any one minute window.
+    pub tokens_per_minute: Option<u64>,
+    /// How long to pause the provider after it first reports that we are rate limited,
+    /// when it doesn't tell us how long to wait.
+    pub initial_backoff: Duration,
+    /// The upper bound for the exponential backoff between consecutive rate limit errors.
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: None,
+            tokens_per_minute: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A snapshot of the requests that are waiting on a provider's [`RateLimitBudget`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// The number of requests waiting to be sent.
+    pub queued_requests: usize,
+    /// When the next queued request is expected to be sent.
+    pub resumes_at: Option<Instant>,
+}
+
+impl RateLimitStatus {
+    pub fn is_limited(&self) -> bool {
+        self.queued_requests > 0
+    }
+}
+
+/// The request budget shared by all the models of a language model provider.
+///
+/// Requests wait here until they fit in the provider's [`RateLimitPolicy`], and
+/// rate limit errors from the provider pause every request to it, not just the
+/// one that received the error.
+pub struct RateLimitBudget {
+    state: Mutex<BudgetState>,
+    /// Admits waiting requests one at a time, in the order they arrived.
+    gate: smol::lock::Mutex<()>,
+}
+
+struct BudgetState {
+    policy: RateLimitPolicy,
+    /// The start time and estimated token count of each request sent during the current window.
+    window: VecDeque<(Instant, u64)>,
+    /// Set when the provider reported that we are rate limited.
+    paused_until: Option<Instant>,
+    consecutive_rate_limit_errors: u32,
+    queued_requests: usize,
+    resumes_at: Option<Instant>,
+    status: watch::Sender<RateLimitStatus>,
+}
+
+impl BudgetState {
+    /// Returns when a request with the given token estimate may be sent, or `None` if it may be
+    /// sent right away.
+    fn ready_at(&mut self, estimated_tokens: u64, now: Instant) -> Option<Instant> {
+        while let Some((started_at, _)) = self.window.front() {
+            if now.saturating_duration_since(*started_at) >= RATE_LIMIT_WINDOW {
+                self.window.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let mut ready_at = self.paused_until.filter(|paused_until| *paused_until > now);
+
+        if let Some(requests_per_minute) = self.policy.requests_per_minute {
+            let requests_per_minute = requests_per_minute.max(1) as usize;
+            if self.window.len() >= requests_per_minute {
+                let (started_at, _) = self.window[self.window.len() - requests_per_minute];
+                ready_at = ready_at.max(Some(started_at + RATE_LIMIT_WINDOW));
+            }
+        }
+
+        if let Some(tokens_per_minute) = self.policy.tokens_per_minute {
+            // A request that is larger than the whole budget is sent once the window is empty,
+            // rather than being held back forever.
+            let mut used_tokens = self.window.iter().map(|(_, tokens)| tokens).sum::<u64>();
+            let mut oldest_requests = self.window.iter();
+            while used_tokens > 0 && used_tokens + estimated_tokens > tokens_per_minute {
+                let Some((started_at, tokens)) = oldest_requests.next() else {
+                    break;
+                };
+                used_tokens -= tokens;
+                ready_at = ready_at.max(Some(*started_at + RATE_LIMIT_WINDOW));
+            }
+        }
+
+        ready_at
+    }
+
+    fn record_request(&mut self, estimated_tokens: u64, now: Instant) {
+        self.window.push_back((now, estimated_tokens));
+    }
+
+    /// Pauses the provider after it reported that we are rate limited. The server's
+    /// `retry_after` is honored when present, otherwise we back off exponentially.
+    fn record_rate_limited(&mut self, retry_after: Option<Duration>, jitter: f64, now: Instant) {
+        let delay = retry_after.unwrap_or_else(|| {
+            self.policy
+                .initial_backoff
+                .saturating_mul(2u32.saturating_pow(self.consecutive_rate_limit_errors))
+                .min(self.policy.max_backoff)
+        });
+        let delay = delay + delay.mul_f64(jitter.clamp(0., MAX_BACKOFF_JITTER));
+        self.consecutive_rate_limit_errors = self.consecutive_rate_limit_errors.saturating_add(1);
+        self.paused_until = self.paused_until.max(Some(now + delay));
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_rate_limit_errors = 0;
+    }
+
+    fn publish_status(&mut self, now: Instant) {
+        let resumes_at = if self.queued_requests > 0 {
+            self.resumes_at.filter(|resumes_at| *resumes_at > now)
+        } else {
+            None
+        };
+        let status = RateLimitStatus {
+            queued_requests: self.queued_requests,
+            resumes_at,
+        };
+        self.status.send(status).ok();
+    }
+}
+
+/// Keeps a request counted as queued until it is either sent or dropped.
+struct QueuedRequest<'a> {
+    budget: &'a RateLimitBudget,
+}
+
+impl Drop for QueuedRequest<'_> {
+    fn drop(&mut self) {
+        let mut state = self.budget.state.lock();
+        state.queued_requests -= 1;
+        state.publish_status(Instant::now());
+    }
+}
+
+#[derive(Default)]
+struct GlobalRateLimitBudgets {
+    budgets: HashMap<LanguageModelProviderId, Arc<RateLimitBudget>>,
+    policies: HashMap<LanguageModelProviderId, RateLimitPolicy>,
+}
+
+impl Global for GlobalRateLimitBudgets {}
+
+impl RateLimitBudget {
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        let (status, _) = watch::channel(RateLimitStatus::default());
+        Self {
+            state: Mutex::new(BudgetState {
+                policy,
+                window: VecDeque::new(),
+                paused_until: None,
+                consecutive_rate_limit_errors: 0,
+                queued_requests: 0,
+                resumes_at: None,
+                status,
+            }),
+            gate: smol::lock::Mutex::new(()),
+        }
+    }
+
+    /// Returns the budget shared by all models of the given provider.
+    pub fn for_provider(provider_id: LanguageModelProviderId, cx: &mut App) -> Arc<Self> {
+        let budgets = cx.default_global::<GlobalRateLimitBudgets>();
+        let policy = budgets
+            .policies
+            .get(&provider_id)
+            .cloned()
+            .unwrap_or_default();
+        budgets
+            .budgets
+            .entry(provider_id)
+            .or_insert_with(|| Arc::new(Self::new(policy)))
+            .clone()
+    }
+
+    /// Replaces the policies of all providers. Providers without a policy are not limited.
+    pub fn set_policies(policies: HashMap<LanguageModelProviderId, RateLimitPolicy>, cx: &mut App) {
+        let budgets = cx.default_global::<GlobalRateLimitBudgets>();
+        for (provider_id, budget) in &budgets.budgets {
+            budget.set_policy(policies.get(provider_id).cloned().unwrap_or_default());
+        }
+        budgets.policies = policies;
+    }
+
+    pub fn set_policy(&self, policy: RateLimitPolicy) {
+        self.state.lock().policy = policy;
+    }
+
+    /// Returns a receiver that is notified whenever requests start or stop waiting on this budget.
+    pub fn status(&self) -> watch::Receiver<RateLimitStatus> {
+        self.state.lock().status.receiver()
+    }
+
+    /// Waits until a request with the given token estimate fits in the budget, and records it.
+    async fn acquire(&self, estimated_tokens: u64) {
+        let mut queued_request = None;
+        let _gate = match self.gate.try_lock() {
+            Some(gate) => gate,
+            None => {
+                queued_request = Some(self.enqueue());
+                self.gate.lock().await
+            }
+        };
+
+        loop {
+            let now = Instant::now();
+            let Some(ready_at) = self.reserve(estimated_tokens, now) else {
+                break;
+            };
+            if queued_request.is_none() {
+                queued_request = Some(self.enqueue());
+            } else {
+                self.state.lock().publish_status(now);
+            }
+            let delay = ready_at.saturating_duration_since(now);
+            log::debug!("Waiting {delay:?} for the language model provider's rate limit");
+            smol::Timer::after(delay).await;
+        }
+    }
+
+    /// Records a request if it fits in the budget, otherwise returns when it will fit.
+    fn reserve(&self, estimated_tokens: u64, now: Instant) -> Option<Instant> {
+        let mut state = self.state.lock();
+        match state.ready_at(estimated_tokens, now) {
+            Some(ready_at) => {
+                state.resumes_at = Some(ready_at);
+                Some(ready_at)
+            }
+            None => {
+                state.record_request(estimated_tokens, now);
+                None
+            }
+        }
+    }
+
+    fn enqueue(&self) -> QueuedRequest<'_> {
+        let mut state = self.state.lock();
+        state.queued_requests += 1;
+        state.publish_status(Instant::now());
+        QueuedRequest { budget: self }
+    }
+
+    fn record_result<T>(&self, result: &Result<T, LanguageModelCompletionError>) {
+        match result {
+            Ok(_) => self.state.lock().record_success(),
+            Err(error) => self.record_error(error),
+        }
+    }
+
+    fn record_error(&self, error: &LanguageModelCompletionError) {
+        if let Some(retry_after) = rate_limit_retry_after(error) {
+            let jitter = rand::rng().random_range(0.0..MAX_BACKOFF_JITTER);
+            self.state
+                .lock()
+                .record_rate_limited(retry_after, jitter, Instant::now());
+        }
+    }
+
+    /// Pauses the provider until one of its limits resets, when its response headers said that
+    /// the limit was used up.
+    pub fn record_rate_limit_headers(&self, headers: &HeaderMap) {
+        if let Some(resets_in) = exhausted_rate_limit_reset(headers) {
+            self.record_exhausted(resets_in);
+        }
+    }
+
+    /// Pauses the provider for `resets_in`, after it reported that one of its limits was used up.
+    pub fn record_exhausted(&self, resets_in: Duration) {
+        let mut state = self.state.lock();
+        state.paused_until = state.paused_until.max(Some(Instant::now() + resets_in));
+    }
+}
+
+/// Returns how long until the provider's exhausted limit resets, if the `x-ratelimit-*` or
+/// `anthropic-ratelimit-*` headers say that one of its limits was used up.
+pub fn exhausted_rate_limit_reset(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let mut resets_in = None;
+
+    for resource in ["requests", "tokens"] {
+        if header(&format!("x-ratelimit-remaining-{resource}")) == Some("0")
+            && let Some(reset) =
+                header(&format!("x-ratelimit-reset-{resource}")).and_then(parse_rate_limit_duration)
+        {
+            resets_in = resets_in.max(Some(reset));
+        }
+    }
+
+    for resource in ["requests", "tokens", "input-tokens", "output-tokens"] {
+        if header(&format!("anthropic-ratelimit-{resource}-remaining")) == Some("0")
+            && let Some(reset) = header(&format!("anthropic-ratelimit-{resource}-reset"))
+                .and_then(|reset| DateTime::parse_from_rfc3339(reset).ok())
+        {
+            let reset = (reset.to_utc() - Utc::now()).to_std().unwrap_or_default();
+            resets_in = resets_in.max(Some(reset));
+        }
+    }
+
+    resets_in
+}
+
+/// Parses the durations that OpenAI uses in its `x-ratelimit-reset-*` headers, such as `1s`,
+/// `6m0s` or `20ms`.
+fn parse_rate_limit_duration(value: &str) -> Option<Duration> {
+    let mut duration = Duration::ZERO;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number = rest[..number_len].parse::<f64>().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds = match &rest[..unit_len] {
+            "ms" => number / 1000.,
+            "s" | "" => number,
+            "m" => number * 60.,
+            "h" => number * 3600.,
+            _ => return None,
+        };
+        duration += Duration::try_from_secs_f64(seconds).ok()?;
+        rest = &rest[unit_len..];
+    }
+    Some(duration)
+}
+
+/// An item of a rate limited stream, which may be an error that the provider reported mid-stream.
+pub trait RateLimitedStreamItem {
+    fn completion_error(&self) -> Option<&LanguageModelCompletionError>;
+}
+
+impl<T> RateLimitedStreamItem for Result<T, LanguageModelCompletionError> {
+    fn completion_error(&self) -> Option<&LanguageModelCompletionError> {
+        self.as_ref().err()
+    }
+}
+
+impl<T> RateLimitedStreamItem for anyhow::Result<T> {
+    fn completion_error(&self) -> Option<&LanguageModelCompletionError> {
+        self.as_ref().err()?.downcast_ref()
+    }
+}
+
+/// Returns `Some` if the error means that the provider asked us to slow down,
+/// along with how long it asked us to wait, if it said.
+fn rate_limit_retry_after(error: &LanguageModelCompletionError) -> Option<Option<Duration>> {
+    use http_client::StatusCode;
+
+    match error {
+        LanguageModelCompletionError::RateLimitExceeded { retry_after, .. }
+        | LanguageModelCompletionError::ServerOverloaded { retry_after, .. } => Some(*retry_after),
+        LanguageModelCompletionError::UpstreamProviderError {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            retry_after,
+            ..
+        } => Some(*retry_after),
+        LanguageModelCompletionError::HttpResponseError {
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            ..
+        } => Some(None),
+        LanguageModelCompletionError::Other(error) => error
+            .downcast_ref::<LanguageModelCompletionError>()
+            .and_then(rate_limit_retry_after),
+        _ => None,
+    }
+}
 
 #[derive(Clone)]
 pub struct RateLimiter {
     semaphore: Arc<Semaphore>,
+    budget: Arc<RateLimitBudget>,
 }
 
 pub struct RateLimitGuard<T> {
     inner: T,
+    budget: Arc<RateLimitBudget>,
     _guard: SemaphoreGuardArc,
 }
 
 impl<T> Stream for RateLimitGuard<T>
 where
     T: Stream,
+    T::Item: RateLimitedStreamItem,
 {
     type Item = T::Item;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        unsafe { Pin::map_unchecked_mut(self, |this| &mut this.inner).poll_next(cx) }
+        let this = unsafe { self.get_unchecked_mut() };
+        let item = unsafe { Pin::new_unchecked(&mut this.inner) }.poll_next(cx);
+        if let Poll::Ready(Some(item)) = &item
+            && let Some(error) = item.completion_error()
+        {
+            this.budget.record_error(error);
+        }
+        item
     }
 }
 
 impl RateLimiter {
+    /// Creates a rate limiter that only limits the number of concurrent requests.
     pub fn new(limit: usize) -> Self {
+        Self::with_budget(
+            limit,
+            Arc::new(RateLimitBudget::new(RateLimitPolicy::default())),
+        )
+    }
+
+    /// Creates a rate limiter that also draws from the given (usually per-provider) budget.
+    pub fn with_budget(limit: usize, budget: Arc<RateLimitBudget>) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(limit)),
+            budget,
         }
     }
 
+    pub fn budget(&self) -> &Arc<RateLimitBudget> {
+        &self.budget
+    }
+
     pub fn run<'a, Fut, T>(
         &self,
+        estimated_tokens: u64,
         future: Fut,
     ) -> impl 'a + Future<Output = Result<T, LanguageModelCompletionError>>
     where
         Fut: 'a + Future<Output = Result<T, LanguageModelCompletionError>>,
     {
         let guard = self.semaphore.acquire_arc();
+        let budget = self.budget.clone();
         async move {
             let guard = guard.await;
-            let result = future.await?;
+            budget.acquire(estimated_tokens).await;
+            let result = future.await;
+            budget.record_result(&result);
             drop(guard);
-            Ok(result)
+            result
         }
     }
 
     pub fn stream<'a, Fut, T>(
         &self,
+        estimated_tokens: u64,
         future: Fut,
     ) -> impl 'a
     + Future<
@@ -63,15 +495,206 @@ impl RateLimiter {
     where
         Fut: 'a + Future<Output = Result<T, LanguageModelCompletionError>>,
         T: Stream,
+        T::Item: RateLimitedStreamItem,
     {
         let guard = self.semaphore.acquire_arc();
+        let budget = self.budget.clone();
         async move {
             let guard = guard.await;
-            let inner = future.await?;
+            budget.acquire(estimated_tokens).await;
+            let result = future.await;
+            budget.record_result(&result);
             Ok(RateLimitGuard {
-                inner,
+                inner: result?,
+                budget,
                 _guard: guard,
             })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+
+    fn budget_state(policy: RateLimitPolicy) -> BudgetState {
+        let (status, _) = watch::channel(RateLimitStatus::default());
+        BudgetState {
+            policy,
+            window: VecDeque::new(),
+            paused_until: None,
+            consecutive_rate_limit_errors: 0,
+            queued_requests: 0,
+            resumes_at: None,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_requests_per_minute() {
+        let mut state = budget_state(RateLimitPolicy {
+            requests_per_minute: Some(2),
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        assert_eq!(state.ready_at(0, start), None);
+        state.record_request(0, start);
+        let second = start + Duration::from_secs(10);
+        assert_eq!(state.ready_at(0, second), None);
+        state.record_request(0, second);
+
+        let third = start + Duration::from_secs(20);
+        assert_eq!(state.ready_at(0, third), Some(start + RATE_LIMIT_WINDOW));
+        assert_eq!(state.ready_at(0, start + RATE_LIMIT_WINDOW), None);
+        assert_eq!(state.window.len(), 1);
+    }
+
+    #[test]
+    fn test_tokens_per_minute() {
+        let mut state = budget_state(RateLimitPolicy {
+            tokens_per_minute: Some(1000),
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        state.record_request(600, start);
+        state.record_request(300, start + Duration::from_secs(5));
+        let now = start + Duration::from_secs(10);
+        assert_eq!(state.ready_at(100, now), None);
+        assert_eq!(state.ready_at(200, now), Some(start + RATE_LIMIT_WINDOW));
+        assert_eq!(
+            state.ready_at(800, now),
+            Some(start + Duration::from_secs(5) + RATE_LIMIT_WINDOW)
+        );
+
+        // Requests larger than the whole budget wait for the window to empty.
+        assert_eq!(
+            state.ready_at(5000, now),
+            Some(start + Duration::from_secs(5) + RATE_LIMIT_WINDOW)
+        );
+        assert_eq!(
+            state.ready_at(5000, start + Duration::from_secs(5) + RATE_LIMIT_WINDOW),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_backoff() {
+        let mut state = budget_state(RateLimitPolicy {
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(5),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        state.record_rate_limited(None, 0., now);
+        assert_eq!(state.ready_at(0, now), Some(now + Duration::from_secs(2)));
+        state.record_rate_limited(None, 0., now);
+        assert_eq!(state.ready_at(0, now), Some(now + Duration::from_secs(4)));
+        state.record_rate_limited(None, 0., now);
+        assert_eq!(state.ready_at(0, now), Some(now + Duration::from_secs(5)));
+
+        // The server's retry-after takes precedence over our own backoff.
+        let later = now + Duration::from_secs(10);
+        state.record_rate_limited(Some(Duration::from_secs(30)), 0., later);
+        assert_eq!(
+            state.ready_at(0, later),
+            Some(later + Duration::from_secs(30))
+        );
+
+        // Jitter only ever extends the delay, and by at most `MAX_BACKOFF_JITTER`.
+        state.record_success();
+        let much_later = now + Duration::from_secs(100);
+        state.record_rate_limited(None, 1., much_later);
+        assert_eq!(
+            state.ready_at(0, much_later),
+            Some(
+                much_later
+                    + Duration::from_secs(2)
+                    + Duration::from_secs(2).mul_f64(MAX_BACKOFF_JITTER)
+            )
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_errors_pause_budget() {
+        let budget = RateLimitBudget::new(RateLimitPolicy::default());
+        budget.record_result::<()>(&Err(LanguageModelCompletionError::RateLimitExceeded {
+            provider: crate::ANTHROPIC_PROVIDER_NAME,
+            retry_after: Some(Duration::from_secs(30)),
+        }));
+        let now = Instant::now();
+        let ready_at = budget.state.lock().ready_at(0, now).unwrap();
+        assert!(ready_at >= now + Duration::from_secs(29));
+
+        let budget = RateLimitBudget::new(RateLimitPolicy::default());
+        budget.record_result::<()>(&Err(LanguageModelCompletionError::NoApiKey {
+            provider: crate::ANTHROPIC_PROVIDER_NAME,
+        }));
+        assert_eq!(budget.state.lock().ready_at(0, Instant::now()), None);
+    }
+
+    #[gpui::test]
+    async fn test_mid_stream_rate_limit_errors_pause_budget() {
+        let limiter = RateLimiter::new(4);
+        let events = futures::stream::iter([
+            Ok(()),
+            Err(LanguageModelCompletionError::ServerOverloaded {
+                provider: crate::ANTHROPIC_PROVIDER_NAME,
+                retry_after: Some(Duration::from_secs(30)),
+            }),
+        ]);
+        let stream = limiter.stream(0, async move { Ok(events) }).await.unwrap();
+        assert_eq!(
+            limiter.budget().state.lock().ready_at(0, Instant::now()),
+            None
+        );
+
+        let results = stream.collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 2);
+        let now = Instant::now();
+        let ready_at = limiter.budget().state.lock().ready_at(0, now).unwrap();
+        assert!(ready_at >= now + Duration::from_secs(29));
+    }
+
+    #[test]
+    fn test_exhausted_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", "12".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "1s".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-tokens", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-tokens", "6m0.5s".parse().unwrap());
+        assert_eq!(
+            exhausted_rate_limit_reset(&headers),
+            Some(Duration::from_millis(360_500))
+        );
+
+        headers.insert("x-ratelimit-remaining-tokens", "100".parse().unwrap());
+        assert_eq!(exhausted_rate_limit_reset(&headers), None);
+
+        let reset = (Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "anthropic-ratelimit-input-tokens-remaining",
+            "0".parse().unwrap(),
+        );
+        headers.insert(
+            "anthropic-ratelimit-input-tokens-reset",
+            reset.parse().unwrap(),
+        );
+        let resets_in = exhausted_rate_limit_reset(&headers).unwrap();
+        assert!(resets_in > Duration::from_secs(28) && resets_in <= Duration::from_secs(30));
+
+        assert_eq!(
+            parse_rate_limit_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            parse_rate_limit_duration("1h2m"),
+            Some(Duration::from_secs(3720))
+        );
+        assert_eq!(parse_rate_limit_duration("soon"), None);
+    }
+}
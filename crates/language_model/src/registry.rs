@@ -1,6 +1,6 @@
 use crate::{
     LanguageModel, LanguageModelId, LanguageModelProvider, LanguageModelProviderId,
-    LanguageModelProviderState,
+    LanguageModelProviderState, LanguageModelRequest,
 };
 use collections::{BTreeMap, HashSet};
 use gpui::{App, Context, Entity, EventEmitter, Global, prelude::*};
@@ -52,6 +52,7 @@ pub struct LanguageModelRegistry {
     thread_summary_model: Option<ConfiguredModel>,
     providers: BTreeMap<LanguageModelProviderId, Arc<dyn LanguageModelProvider>>,
     inline_alternatives: Vec<Arc<dyn LanguageModel>>,
+    fallback_models: Vec<Arc<dyn LanguageModel>>,
     /// Set of installed extension IDs that provide language models.
     /// Used to determine which built-in providers should be hidden.
     installed_llm_extension_ids: HashSet<Arc<str>>,
@@ -334,6 +335,21 @@ impl LanguageModelRegistry {
             .collect::<Vec<_>>();
     }
 
+    /// Selects the models to fall back to, in order, when a model's provider is unavailable.
+    pub fn select_fallback_models(
+        &mut self,
+        fallback_models: impl IntoIterator<Item = SelectedModel>,
+        cx: &mut Context<Self>,
+    ) {
+        self.fallback_models = fallback_models
+            .into_iter()
+            .flat_map(|fallback_model| {
+                self.select_model(&fallback_model, cx)
+                    .map(|configured_model| configured_model.model)
+            })
+            .collect::<Vec<_>>();
+    }
+
     pub fn select_model(
         &mut self,
         selected_model: &SelectedModel,
@@ -454,6 +470,41 @@ impl LanguageModelRegistry {
     pub fn inline_alternative_models(&self) -> &[Arc<dyn LanguageModel>] {
         &self.inline_alternatives
     }
+
+    pub fn fallback_models(&self) -> &[Arc<dyn LanguageModel>] {
+        &self.fallback_models
+    }
+
+    /// Returns the model to use when `model` fails with a retryable error while
+    /// handling `request`: the next model in the fallback chain that supports the
+    /// request's tools, images, and size. Models earlier in the chain than `model`
+    /// are skipped, so walking the chain always terminates.
+    pub fn next_fallback_model(
+        &self,
+        model: &dyn LanguageModel,
+        request: &LanguageModelRequest,
+    ) -> Option<Arc<dyn LanguageModel>> {
+        let is_current_model = |fallback_model: &Arc<dyn LanguageModel>| {
+            fallback_model.id() == model.id() && fallback_model.provider_id() == model.provider_id()
+        };
+        let candidates = match self.fallback_models.iter().position(is_current_model) {
+            Some(ix) => &self.fallback_models[ix + 1..],
+            None => &self.fallback_models[..],
+        };
+
+        let needs_tools = !request.tools.is_empty();
+        let needs_images = request.contains_images();
+        let estimated_tokens = request.estimated_token_count();
+        candidates
+            .iter()
+            .find(|candidate| {
+                !is_current_model(*candidate)
+                    && (!needs_tools || candidate.supports_tools())
+                    && (!needs_images || candidate.supports_images())
+                    && candidate.max_token_count() >= estimated_tokens
+            })
+            .cloned()
+    }
 }
 
 #[cfg(test)]
@@ -609,4 +660,62 @@ mod tests {
 
         assert_eq!(registry.read(cx).visible_providers().len(), 1);
     }
+
+    #[gpui::test]
+    fn test_next_fallback_model(cx: &mut App) {
+        let registry = cx.new(|_| LanguageModelRegistry::default());
+        let primary_provider = Arc::new(FakeLanguageModelProvider::default());
+        let fallback_providers = ["first", "second"].map(|id| {
+            Arc::new(FakeLanguageModelProvider::new(
+                LanguageModelProviderId::from(id.to_string()),
+                crate::LanguageModelProviderName::from(id.to_string()),
+            ))
+        });
+
+        registry.update(cx, |registry, cx| {
+            registry.register_provider(primary_provider.clone(), cx);
+            for provider in &fallback_providers {
+                registry.register_provider(provider.clone(), cx);
+            }
+            registry.select_fallback_models(
+                ["first/fake", "missing/fake", "second/fake"]
+                    .map(|id| SelectedModel::from_str(id).unwrap()),
+                cx,
+            );
+        });
+
+        let registry = registry.read(cx);
+        assert_eq!(registry.fallback_models().len(), 2);
+
+        let primary_model = primary_provider.test_model();
+        let request = LanguageModelRequest::default();
+        let first = registry
+            .next_fallback_model(&primary_model, &request)
+            .unwrap();
+        assert_eq!(first.provider_id().0.as_ref(), "first");
+        let second = registry
+            .next_fallback_model(first.as_ref(), &request)
+            .unwrap();
+        assert_eq!(second.provider_id().0.as_ref(), "second");
+        assert!(
+            registry
+                .next_fallback_model(second.as_ref(), &request)
+                .is_none()
+        );
+
+        // Fake models don't support tools, so none of them can take over a request that uses them.
+        let request_with_tools = LanguageModelRequest {
+            tools: vec![crate::LanguageModelRequestTool {
+                name: "echo".into(),
+                description: "Echoes its input".into(),
+                input_schema: serde_json::json!({}),
+            }],
+            ..Default::default()
+        };
+        assert!(
+            registry
+                .next_fallback_model(&primary_model, &request_with_tools)
+                .is_none()
+        );
+    }
 }
@@ -451,6 +451,56 @@ pub struct LanguageModelRequest {
     pub thinking_allowed: bool,
 }
 
+impl LanguageModelRequest {
+    /// Whether any message or tool result in this request includes an image.
+    pub fn contains_images(&self) -> bool {
+        self.messages.iter().any(|message| {
+            message.content.iter().any(|content| match content {
+                MessageContent::Image(_) => true,
+                MessageContent::ToolResult(tool_result) => {
+                    matches!(
+                        tool_result.content,
+                        LanguageModelToolResultContent::Image(_)
+                    )
+                }
+                _ => false,
+            })
+        })
+    }
+
+    /// A rough, tokenizer-independent estimate of the number of input tokens in this request,
+    /// used for client-side rate limiting.
+    pub fn estimated_token_count(&self) -> u64 {
+        let mut text_len = 0;
+        let mut image_tokens = 0;
+        for message in &self.messages {
+            for content in &message.content {
+                match content {
+                    MessageContent::Text(text) | MessageContent::Thinking { text, .. } => {
+                        text_len += text.len();
+                    }
+                    MessageContent::RedactedThinking(data) => text_len += data.len(),
+                    MessageContent::Image(image) => image_tokens += image.estimate_tokens(),
+                    MessageContent::ToolUse(tool_use) => text_len += tool_use.raw_input.len(),
+                    MessageContent::ToolResult(tool_result) => match &tool_result.content {
+                        LanguageModelToolResultContent::Text(text) => text_len += text.len(),
+                        LanguageModelToolResultContent::Image(image) => {
+                            image_tokens += image.estimate_tokens()
+                        }
+                    },
+                }
+            }
+        }
+        for tool in &self.tools {
+            text_len += tool.name.len() + tool.description.len();
+            text_len += tool.input_schema.to_string().len();
+        }
+
+        // Assume roughly four bytes per token, which holds for most tokenizers on English text and code.
+        (text_len / 4 + image_tokens) as u64
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
@@ -0,0 +1,544 @@
+use crate::{
+    LanguageModel, LanguageModelCacheConfiguration, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelRequest, LanguageModelToolChoice,
+    LanguageModelToolSchemaFormat,
+};
+use anyhow::{Context as _, Result, anyhow};
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{App, AsyncApp};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use util::ResultExt as _;
+
+/// Controls how a [`CachingLanguageModel`] uses its on-disk cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseCacheMode {
+    /// Replay cached responses and record the ones that are missing.
+    #[default]
+    Record,
+    /// Only replay cached responses; requests that are not cached fail.
+    ReplayOnly,
+    /// Bypass the cache entirely.
+    Passthrough,
+}
+
+impl FromStr for ResponseCacheMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "record" => Ok(Self::Record),
+            "replay-only" | "replay_only" => Ok(Self::ReplayOnly),
+            "passthrough" => Ok(Self::Passthrough),
+            _ => Err(anyhow!(
+                "invalid response cache mode {s:?}, expected one of: record, replay-only, passthrough"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ResponseCacheMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Record => write!(f, "record"),
+            Self::ReplayOnly => write!(f, "replay-only"),
+            Self::Passthrough => write!(f, "passthrough"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CachedResponse<T> {
+    response: T,
+}
+
+/// An on-disk cache of model responses, keyed on a hash of the serialized request.
+///
+/// This is the storage behind [`CachingLanguageModel`], and can be used directly by tools that
+/// talk to a provider's API without going through a [`LanguageModel`].
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    mode: ResponseCacheMode,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>, mode: ResponseCacheMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    pub fn mode(&self) -> ResponseCacheMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached response to `request`, if there is one. In replay-only mode, a missing
+    /// response is an error.
+    pub fn lookup<T: DeserializeOwned>(&self, request: &impl Serialize) -> Result<Option<T>> {
+        if self.mode == ResponseCacheMode::Passthrough {
+            return Ok(None);
+        }
+
+        let path = self.path(request)?;
+        match read_cached_response(&path) {
+            Ok(Some(response)) => {
+                log::debug!("replaying cached response from {path:?}");
+                return Ok(Some(response));
+            }
+            Ok(None) => {}
+            Err(error) => {
+                // A corrupt entry is treated like a miss so that recording can overwrite it.
+                log::warn!("ignoring unreadable cached response: {error:#}");
+            }
+        }
+
+        if self.mode == ResponseCacheMode::ReplayOnly {
+            return Err(anyhow!(
+                "no cached response for request in {:?} (replay-only mode)",
+                self.dir
+            ));
+        }
+        Ok(None)
+    }
+
+    /// Records the response to `request`, unless the cache is in passthrough mode.
+    pub fn record(&self, request: &impl Serialize, response: &impl Serialize) -> Result<()> {
+        if self.mode == ResponseCacheMode::Passthrough {
+            return Ok(());
+        }
+        let path = self.path(request)?;
+        let entry = serde_json::json!({
+            "request": request,
+            "response": response,
+        });
+        write_cached_response(&path, &entry)
+            .with_context(|| format!("failed to record response to {path:?}"))
+    }
+
+    fn path(&self, request: &impl Serialize) -> Result<PathBuf> {
+        let digest = Sha256::digest(serde_json::to_vec(request)?);
+        Ok(self.dir.join(format!("{digest:x}.json")))
+    }
+}
+
+fn read_cached_response<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let entry: CachedResponse<T> = serde_json::from_slice(&contents)
+        .with_context(|| format!("failed to parse cached response {path:?}"))?;
+    Ok(Some(entry.response))
+}
+
+fn write_cached_response(path: &Path, entry: &serde_json::Value) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write to a temporary file first so that concurrent runs never observe a partial entry.
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut temp_file = tempfile::NamedTempFile::new_in(dir)?;
+    serde_json::to_writer_pretty(&mut temp_file, entry)?;
+    temp_file.persist(path)?;
+    Ok(())
+}
+
+/// Wraps a [`LanguageModel`] with an on-disk cache of completion responses, keyed on the
+/// normalized request. Intended for eval and CI runs that repeatedly send identical requests.
+///
+/// Requests are normalized for the cache key by dropping the thread and prompt ids, so that
+/// reruns of the same prompt share an entry. The temperature stays part of the key, since
+/// sampling at a different temperature is a different request. The wrapped model still receives
+/// the request as it was sent. Only streams that complete without an error are
+/// recorded.
+pub struct CachingLanguageModel {
+    model: Arc<dyn LanguageModel>,
+    cache: ResponseCache,
+}
+
+impl CachingLanguageModel {
+    pub fn new(
+        model: Arc<dyn LanguageModel>,
+        cache_dir: impl Into<PathBuf>,
+        mode: ResponseCacheMode,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            model,
+            cache: ResponseCache::new(cache_dir, mode),
+        })
+    }
+
+    pub fn mode(&self) -> ResponseCacheMode {
+        self.cache.mode()
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        self.cache.dir()
+    }
+
+    fn cache_key(&self, request: &LanguageModelRequest) -> (String, String, LanguageModelRequest) {
+        let mut request = request.clone();
+        request.thread_id = None;
+        request.prompt_id = None;
+        (
+            self.model.provider_id().0.to_string(),
+            self.model.id().0.to_string(),
+            request,
+        )
+    }
+}
+
+impl LanguageModel for CachingLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.model.id()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        self.model.name()
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.model.provider_id()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.model.provider_name()
+    }
+
+    fn upstream_provider_id(&self) -> LanguageModelProviderId {
+        self.model.upstream_provider_id()
+    }
+
+    fn upstream_provider_name(&self) -> LanguageModelProviderName {
+        self.model.upstream_provider_name()
+    }
+
+    fn telemetry_id(&self) -> String {
+        self.model.telemetry_id()
+    }
+
+    fn api_key(&self, cx: &App) -> Option<String> {
+        self.model.api_key(cx)
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools()
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        self.model.supports_tool_choice(choice)
+    }
+
+    fn supports_burn_mode(&self) -> bool {
+        self.model.supports_burn_mode()
+    }
+
+    fn supports_streaming_tools(&self) -> bool {
+        self.model.supports_streaming_tools()
+    }
+
+    fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
+        self.model.tool_input_format()
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_token_count()
+    }
+
+    fn max_token_count_in_burn_mode(&self) -> Option<u64> {
+        self.model.max_token_count_in_burn_mode()
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens()
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        self.model.count_tokens(request, cx)
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        if self.cache.mode() == ResponseCacheMode::Passthrough {
+            return self.model.stream_completion(request, cx);
+        }
+
+        let cache_key = self.cache_key(&request);
+        match self
+            .cache
+            .lookup::<Vec<LanguageModelCompletionEvent>>(&cache_key)
+        {
+            Ok(Some(events)) => {
+                return futures::future::ready(Ok(futures::stream::iter(
+                    events.into_iter().map(Ok),
+                )
+                .boxed()))
+                .boxed();
+            }
+            Ok(None) => {}
+            Err(error) => return futures::future::ready(Err(error.into())).boxed(),
+        }
+
+        let cache = self.cache.clone();
+        let stream = self.model.stream_completion(request, cx);
+        async move {
+            let stream = stream.await?;
+
+            // `None` once the stream has produced an error, in which case nothing is recorded.
+            let recorded_events = Arc::new(Mutex::new(Some(Vec::new())));
+            let stream = stream
+                .inspect({
+                    let recorded_events = recorded_events.clone();
+                    move |event| {
+                        let mut recorded_events = recorded_events.lock();
+                        match event {
+                            Ok(event) => {
+                                if let Some(events) = recorded_events.as_mut() {
+                                    events.push(event.clone());
+                                }
+                            }
+                            Err(_) => *recorded_events = None,
+                        }
+                    }
+                })
+                .chain(
+                    futures::stream::once(async move {
+                        let Some(events) = recorded_events.lock().take() else {
+                            return;
+                        };
+                        cache.record(&cache_key, &events).log_err();
+                    })
+                    .filter_map(|()| futures::future::ready(None)),
+                );
+            Ok(stream.boxed())
+        }
+        .boxed()
+    }
+
+    fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
+        self.model.cache_configuration()
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn as_fake(&self) -> &crate::fake_provider::FakeLanguageModel {
+        self.model.as_fake()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LanguageModelRequestMessage, Role, StopReason, fake_provider::FakeLanguageModel};
+    use gpui::TestAppContext;
+
+    fn request(text: &str) -> LanguageModelRequest {
+        LanguageModelRequest {
+            thread_id: Some("thread".into()),
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![text.into()],
+                cache: false,
+                reasoning_details: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    async fn collect_events(
+        model: &Arc<CachingLanguageModel>,
+        request: LanguageModelRequest,
+        fake_model: &FakeLanguageModel,
+        fake_events: Vec<LanguageModelCompletionEvent>,
+        cx: &mut TestAppContext,
+    ) -> Result<Vec<LanguageModelCompletionEvent>, LanguageModelCompletionError> {
+        let stream = cx.update(|cx| model.stream_completion(request, &cx.to_async()));
+        if !fake_events.is_empty() {
+            for event in fake_events {
+                fake_model.send_last_completion_stream_event(event);
+            }
+            fake_model.end_last_completion_stream();
+        }
+        let stream = stream.await?;
+        stream.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let events = vec![
+            LanguageModelCompletionEvent::StartMessage {
+                message_id: "message".into(),
+            },
+            LanguageModelCompletionEvent::Text("Hello".into()),
+            LanguageModelCompletionEvent::Text(", world".into()),
+            LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+        ];
+
+        let recording_model = CachingLanguageModel::new(
+            fake_model.clone(),
+            cache_dir.path(),
+            ResponseCacheMode::Record,
+        );
+        let recorded = collect_events(
+            &recording_model,
+            request("Hi"),
+            &fake_model,
+            events.clone(),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(recorded, events);
+
+        // Requests are normalized when looking up their cached responses.
+        let replaying_model = CachingLanguageModel::new(
+            fake_model.clone(),
+            cache_dir.path(),
+            ResponseCacheMode::ReplayOnly,
+        );
+        let mut other_thread_request = request("Hi");
+        other_thread_request.thread_id = Some("other-thread".into());
+        let replayed = collect_events(
+            &replaying_model,
+            other_thread_request,
+            &fake_model,
+            Vec::new(),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(replayed, events);
+        assert_eq!(fake_model.completion_count(), 0);
+
+        // Uncached requests fail in replay-only mode.
+        let result = collect_events(
+            &replaying_model,
+            request("Bye"),
+            &fake_model,
+            Vec::new(),
+            cx,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(fake_model.completion_count(), 0);
+
+        // The same prompt at another temperature is a different request.
+        let mut warmer_request = request("Hi");
+        warmer_request.temperature = Some(0.7);
+        let result = collect_events(
+            &replaying_model,
+            warmer_request,
+            &fake_model,
+            Vec::new(),
+            cx,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(fake_model.completion_count(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_failed_streams_are_not_recorded(cx: &mut TestAppContext) {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let model = CachingLanguageModel::new(
+            fake_model.clone(),
+            cache_dir.path(),
+            ResponseCacheMode::Record,
+        );
+
+        let stream = cx.update(|cx| model.stream_completion(request("Hi"), &cx.to_async()));
+        fake_model.send_last_completion_stream_text_chunk("Hel");
+        fake_model.send_last_completion_stream_error(LanguageModelCompletionError::Other(anyhow!(
+            "connection reset"
+        )));
+        fake_model.end_last_completion_stream();
+        let events = stream.await.unwrap().collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 2);
+        assert!(events[1].is_err());
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_requests_are_forwarded_unchanged(cx: &mut TestAppContext) {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let model = CachingLanguageModel::new(
+            fake_model.clone(),
+            cache_dir.path(),
+            ResponseCacheMode::Record,
+        );
+
+        let mut request = request("Hi");
+        request.temperature = Some(1.0);
+        let _stream = cx.update(|cx| model.stream_completion(request.clone(), &cx.to_async()));
+        assert_eq!(fake_model.pending_completions(), vec![request]);
+    }
+
+    #[test]
+    fn test_response_cache_modes() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let record = ResponseCache::new(cache_dir.path(), ResponseCacheMode::Record);
+        assert_eq!(record.lookup::<String>(&"request").unwrap(), None);
+        record.record(&"request", &"response").unwrap();
+        assert_eq!(
+            record.lookup::<String>(&"request").unwrap().as_deref(),
+            Some("response")
+        );
+
+        let replay_only = ResponseCache::new(cache_dir.path(), ResponseCacheMode::ReplayOnly);
+        assert_eq!(
+            replay_only.lookup::<String>(&"request").unwrap().as_deref(),
+            Some("response")
+        );
+        assert!(replay_only.lookup::<String>(&"other request").is_err());
+
+        let passthrough = ResponseCache::new(cache_dir.path(), ResponseCacheMode::Passthrough);
+        assert_eq!(passthrough.lookup::<String>(&"request").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        for mode in [
+            ResponseCacheMode::Record,
+            ResponseCacheMode::ReplayOnly,
+            ResponseCacheMode::Passthrough,
+        ] {
+            assert_eq!(mode.to_string().parse::<ResponseCacheMode>().unwrap(), mode);
+        }
+        assert!("replay".parse::<ResponseCacheMode>().is_err());
+    }
+}
@@ -4,7 +4,7 @@ use ::settings::{Settings, SettingsStore};
 use client::{Client, UserStore};
 use collections::HashSet;
 use gpui::{App, Context, Entity};
-use language_model::{LanguageModelProviderId, LanguageModelRegistry};
+use language_model::{LanguageModelProviderId, LanguageModelRegistry, RateLimitBudget};
 use provider::deepseek::DeepSeekLanguageModelProvider;
 
 pub mod extension;
@@ -18,11 +18,13 @@ use crate::provider::bedrock::BedrockLanguageModelProvider;
 use crate::provider::cloud::CloudLanguageModelProvider;
 use crate::provider::copilot_chat::CopilotChatLanguageModelProvider;
 use crate::provider::google::GoogleLanguageModelProvider;
+use crate::provider::llama_cpp::LlamaCppLanguageModelProvider;
 use crate::provider::lmstudio::LmStudioLanguageModelProvider;
 pub use crate::provider::mistral::MistralLanguageModelProvider;
 use crate::provider::ollama::OllamaLanguageModelProvider;
 use crate::provider::open_ai::OpenAiLanguageModelProvider;
 use crate::provider::open_ai_compatible::OpenAiCompatibleLanguageModelProvider;
+use crate::provider::open_ai_responses::OpenAiResponsesLanguageModelProvider;
 use crate::provider::open_router::OpenRouterLanguageModelProvider;
 use crate::provider::vercel::VercelLanguageModelProvider;
 use crate::provider::x_ai::XAiLanguageModelProvider;
@@ -84,6 +86,17 @@ pub fn init(user_store: Entity<UserStore>, client: Arc<Client>, cx: &mut App) {
         });
     }
 
+    let mut rate_limits = AllLanguageModelSettings::get_global(cx).rate_limits.clone();
+    RateLimitBudget::set_policies(rate_limits.clone(), cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let rate_limits_new = AllLanguageModelSettings::get_global(cx).rate_limits.clone();
+        if rate_limits_new != rate_limits {
+            RateLimitBudget::set_policies(rate_limits_new.clone(), cx);
+            rate_limits = rate_limits_new;
+        }
+    })
+    .detach();
+
     let mut openai_compatible_providers = AllLanguageModelSettings::get_global(cx)
         .openai_compatible
         .keys()
@@ -181,6 +194,17 @@ fn register_language_model_providers(
         Arc::new(LmStudioLanguageModelProvider::new(client.http_client(), cx)),
         cx,
     );
+    registry.register_provider(
+        Arc::new(LlamaCppLanguageModelProvider::new(client.http_client(), cx)),
+        cx,
+    );
+    registry.register_provider(
+        Arc::new(OpenAiResponsesLanguageModelProvider::new(
+            client.http_client(),
+            cx,
+        )),
+        cx,
+    );
     registry.register_provider(
         Arc::new(DeepSeekLanguageModelProvider::new(client.http_client(), cx)),
         cx,
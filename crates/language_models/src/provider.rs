@@ -4,11 +4,13 @@ pub mod cloud;
 pub mod copilot_chat;
 pub mod deepseek;
 pub mod google;
+pub mod llama_cpp;
 pub mod lmstudio;
 pub mod mistral;
 pub mod ollama;
 pub mod open_ai;
 pub mod open_ai_compatible;
+pub mod open_ai_responses;
 pub mod open_router;
 pub mod vercel;
 pub mod x_ai;
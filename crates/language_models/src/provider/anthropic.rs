@@ -3,6 +3,7 @@ use anthropic::{
     ResponseContent, ToolResultContent, ToolResultPart, Usage,
 };
 use anyhow::{Result, anyhow};
+use chrono::Utc;
 use collections::{BTreeMap, HashMap};
 use futures::{FutureExt, Stream, StreamExt, future, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, App, AsyncApp, Context, Entity, Task};
@@ -13,7 +14,7 @@ use language_model::{
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
     LanguageModelToolChoice, LanguageModelToolResultContent, LanguageModelToolUse, MessageContent,
-    RateLimiter, Role, StopReason, env_var,
+    RateLimitBudget, RateLimiter, Role, StopReason, env_var,
 };
 use settings::{Settings, SettingsStore};
 use std::pin::Pin;
@@ -39,6 +40,7 @@ pub struct AnthropicSettings {
 pub struct AnthropicLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 const API_KEY_ENV_VAR_NAME: &str = "ANTHROPIC_API_KEY";
@@ -81,7 +83,11 @@ impl AnthropicLanguageModelProvider {
             }
         });
 
-        Self { http_client, state }
+        Self {
+            http_client,
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        }
     }
 
     fn create_language_model(&self, model: anthropic::Model) -> Arc<dyn LanguageModel> {
@@ -90,7 +96,7 @@ impl AnthropicLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 
@@ -452,6 +458,7 @@ impl AnthropicModel {
         };
 
         let beta_headers = self.model.beta_headers();
+        let budget = self.request_limiter.budget().clone();
 
         async move {
             let Some(api_key) = api_key else {
@@ -459,14 +466,19 @@ impl AnthropicModel {
                     provider: PROVIDER_NAME,
                 });
             };
-            let request = anthropic::stream_completion(
+            let request = anthropic::stream_completion_with_rate_limit_info(
                 http_client.as_ref(),
                 &api_url,
                 &api_key,
                 request,
                 beta_headers,
             );
-            request.await.map_err(Into::into)
+            let (stream, rate_limits) = request.await?;
+            if let Some(exhausted_until) = rate_limits.and_then(|limits| limits.exhausted_until()) {
+                budget
+                    .record_exhausted((exhausted_until - Utc::now()).to_std().unwrap_or_default());
+            }
+            Ok(stream)
         }
         .boxed()
     }
@@ -580,6 +592,7 @@ impl LanguageModel for AnthropicModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = into_anthropic(
             request,
             self.model.request_id().into(),
@@ -588,7 +601,7 @@ impl LanguageModel for AnthropicModel {
             self.model.mode(),
         );
         let request = self.stream_completion(request, cx);
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let response = request.await?;
             Ok(AnthropicEventMapper::new().map_stream(response))
         });
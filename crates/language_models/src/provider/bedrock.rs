@@ -34,8 +34,8 @@ use language_model::{
     LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
     LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
-    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimiter, Role,
-    TokenUsage, env_var,
+    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimitBudget,
+    RateLimiter, Role, TokenUsage, env_var,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -384,6 +384,7 @@ pub struct BedrockLanguageModelProvider {
     http_client: AwsHttpClient,
     handle: tokio::runtime::Handle,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 impl BedrockLanguageModelProvider {
@@ -401,6 +402,7 @@ impl BedrockLanguageModelProvider {
             http_client: AwsHttpClient::new(http_client),
             handle: Tokio::handle(cx),
             state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
         }
     }
 
@@ -412,7 +414,7 @@ impl BedrockLanguageModelProvider {
             handle: self.handle.clone(),
             state: self.state.clone(),
             client: OnceCell::new(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 }
@@ -684,6 +686,7 @@ impl LanguageModel for BedrockModel {
         };
 
         let deny_tool_calls = request.tool_choice == Some(LanguageModelToolChoice::None);
+        let estimated_tokens = request.estimated_token_count();
 
         let request = match into_bedrock(
             request,
@@ -698,8 +701,17 @@ impl LanguageModel for BedrockModel {
         };
 
         let request = self.stream_completion(request, cx);
-        let future = self.request_limiter.stream(async move {
-            let response = request.await.map_err(|err| anyhow!(err))?;
+        let future = self.request_limiter.stream(estimated_tokens, async move {
+            let response = request.await.map_err(|err| {
+                if let Some(BedrockError::Throttled) = err.downcast_ref::<BedrockError>() {
+                    LanguageModelCompletionError::RateLimitExceeded {
+                        provider: PROVIDER_NAME,
+                        retry_after: None,
+                    }
+                } else {
+                    anyhow!(err).into()
+                }
+            })?;
             let events = map_to_language_model_completion_events(response);
 
             if deny_tool_calls {
@@ -24,7 +24,7 @@ use language_model::{
     LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
     LanguageModelProviderState, LanguageModelRequest, LanguageModelToolChoice,
     LanguageModelToolSchemaFormat, LlmApiToken, ModelRequestLimitReachedError,
-    PaymentRequiredError, RateLimiter, RefreshLlmTokenListener,
+    PaymentRequiredError, RateLimitBudget, RateLimiter, RefreshLlmTokenListener,
 };
 use release_channel::AppVersion;
 use schemars::JsonSchema;
@@ -79,6 +79,7 @@ impl From<ModelMode> for AnthropicModelMode {
 pub struct CloudLanguageModelProvider {
     client: Arc<Client>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
     _maintain_client_status: Task<()>,
 }
 
@@ -268,6 +269,7 @@ impl CloudLanguageModelProvider {
         Self {
             client,
             state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
             _maintain_client_status: maintain_client_status,
         }
     }
@@ -282,7 +284,7 @@ impl CloudLanguageModelProvider {
             model,
             llm_api_token,
             client: self.client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 }
@@ -757,6 +759,7 @@ impl LanguageModel for CloudLanguageModel {
         let mode = request.mode;
         let app_version = cx.update(|cx| AppVersion::global(cx)).ok();
         let thinking_allowed = request.thinking_allowed;
+        let estimated_tokens = request.estimated_token_count();
         let provider_name = provider_name(&self.model.provider);
         match self.model.provider {
             cloud_llm_client::LanguageModelProvider::Anthropic => {
@@ -775,7 +778,7 @@ impl LanguageModel for CloudLanguageModel {
                 );
                 let client = self.client.clone();
                 let llm_api_token = self.llm_api_token.clone();
-                let future = self.request_limiter.stream(async move {
+                let future = self.request_limiter.stream(estimated_tokens, async move {
                     let PerformLlmCompletionResponse {
                         response,
                         usage,
@@ -826,7 +829,7 @@ impl LanguageModel for CloudLanguageModel {
                     None,
                 );
                 let llm_api_token = self.llm_api_token.clone();
-                let future = self.request_limiter.stream(async move {
+                let future = self.request_limiter.stream(estimated_tokens, async move {
                     let PerformLlmCompletionResponse {
                         response,
                         usage,
@@ -873,7 +876,7 @@ impl LanguageModel for CloudLanguageModel {
                     None,
                 );
                 let llm_api_token = self.llm_api_token.clone();
-                let future = self.request_limiter.stream(async move {
+                let future = self.request_limiter.stream(estimated_tokens, async move {
                     let PerformLlmCompletionResponse {
                         response,
                         usage,
@@ -914,7 +917,7 @@ impl LanguageModel for CloudLanguageModel {
                 let request =
                     into_google(request, self.model.id.to_string(), GoogleModelMode::Default);
                 let llm_api_token = self.llm_api_token.clone();
-                let future = self.request_limiter.stream(async move {
+                let future = self.request_limiter.stream(estimated_tokens, async move {
                     let PerformLlmCompletionResponse {
                         response,
                         usage,
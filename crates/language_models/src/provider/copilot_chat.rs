@@ -23,7 +23,7 @@ use language_model::{
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelRequest, LanguageModelRequestMessage, LanguageModelToolChoice,
     LanguageModelToolResultContent, LanguageModelToolSchemaFormat, LanguageModelToolUse,
-    MessageContent, RateLimiter, Role, StopReason, TokenUsage,
+    MessageContent, RateLimitBudget, RateLimiter, Role, StopReason, TokenUsage,
 };
 use settings::SettingsStore;
 use ui::prelude::*;
@@ -35,6 +35,7 @@ const PROVIDER_NAME: LanguageModelProviderName =
 
 pub struct CopilotChatLanguageModelProvider {
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -76,13 +77,16 @@ impl CopilotChatLanguageModelProvider {
             }
         });
 
-        Self { state }
+        Self {
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        }
     }
 
     fn create_language_model(&self, model: CopilotChatModel) -> Arc<dyn LanguageModel> {
         Arc::new(CopilotChatLanguageModel {
             model,
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 }
@@ -318,6 +322,7 @@ impl LanguageModel for CopilotChatLanguageModel {
             | CompletionIntent::CreateFile
             | CompletionIntent::EditFile => false,
         });
+        let estimated_tokens = request.estimated_token_count();
 
         if self.model.supports_response() {
             let responses_request = into_copilot_responses(&self.model, request);
@@ -326,7 +331,7 @@ impl LanguageModel for CopilotChatLanguageModel {
                 let request =
                     CopilotChat::stream_response(responses_request, is_user_initiated, cx.clone());
                 request_limiter
-                    .stream(async move {
+                    .stream(estimated_tokens, async move {
                         let stream = request.await?;
                         let mapper = CopilotResponsesEventMapper::new();
                         Ok(mapper.map_stream(stream).boxed())
@@ -347,7 +352,7 @@ impl LanguageModel for CopilotChatLanguageModel {
             let request =
                 CopilotChat::stream_completion(copilot_request, is_user_initiated, cx.clone());
             request_limiter
-                .stream(async move {
+                .stream(estimated_tokens, async move {
                     let response = request.await?;
                     Ok(map_to_language_model_completion_events(
                         response,
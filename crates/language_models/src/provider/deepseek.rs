@@ -11,7 +11,8 @@ use language_model::{
     LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
-    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage, env_var,
+    LanguageModelToolUse, MessageContent, RateLimitBudget, RateLimiter, Role, StopReason,
+    TokenUsage, env_var,
 };
 pub use settings::DeepseekAvailableModel as AvailableModel;
 use settings::{Settings, SettingsStore};
@@ -44,6 +45,7 @@ pub struct DeepSeekSettings {
 pub struct DeepSeekLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -83,7 +85,11 @@ impl DeepSeekLanguageModelProvider {
             }
         });
 
-        Self { http_client, state }
+        Self {
+            http_client,
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        }
     }
 
     fn create_language_model(&self, model: deepseek::Model) -> Arc<dyn LanguageModel> {
@@ -92,7 +98,7 @@ impl DeepSeekLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 
@@ -199,6 +205,7 @@ impl DeepSeekLanguageModel {
     fn stream_completion(
         &self,
         request: deepseek::Request,
+        estimated_tokens: u64,
         cx: &AsyncApp,
     ) -> BoxFuture<'static, Result<BoxStream<'static, Result<deepseek::StreamResponse>>>> {
         let http_client = self.http_client.clone();
@@ -210,7 +217,7 @@ impl DeepSeekLanguageModel {
             return future::ready(Err(anyhow!("App state dropped"))).boxed();
         };
 
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let Some(api_key) = api_key else {
                 return Err(LanguageModelCompletionError::NoApiKey {
                     provider: PROVIDER_NAME,
@@ -304,8 +311,9 @@ impl LanguageModel for DeepSeekLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = into_deepseek(request, &self.model, self.max_output_tokens());
-        let stream = self.stream_completion(request, cx);
+        let stream = self.stream_completion(request, estimated_tokens, cx);
 
         async move {
             let mapper = DeepSeekEventMapper::new();
@@ -16,7 +16,7 @@ use language_model::{
 use language_model::{
     IconOrSvg, LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    LanguageModelRequest, RateLimitBudget, RateLimiter, Role,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -57,6 +57,7 @@ pub enum ModelMode {
 pub struct GoogleLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -104,7 +105,11 @@ impl GoogleLanguageModelProvider {
             }
         });
 
-        Self { http_client, state }
+        Self {
+            http_client,
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        }
     }
 
     fn create_language_model(&self, model: google_ai::Model) -> Arc<dyn LanguageModel> {
@@ -113,7 +118,7 @@ impl GoogleLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 
@@ -207,7 +212,7 @@ impl LanguageModelProvider for GoogleLanguageModelProvider {
                     model,
                     state: self.state.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
@@ -372,13 +377,14 @@ impl LanguageModel for GoogleLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = into_google(
             request,
             self.model.request_id().to_string(),
             self.model.mode(),
         );
         let request = self.stream_completion(request, cx);
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let response = request.await.map_err(LanguageModelCompletionError::from)?;
             Ok(GoogleEventMapper::new().map_stream(response))
         });
@@ -0,0 +1,827 @@
+use anyhow::{Result, anyhow};
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{AnyView, App, AsyncApp, Context, Entity, Subscription, Task};
+use http_client::HttpClient;
+use language_model::{
+    AuthenticateError, IconOrSvg, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolSchemaFormat, RateLimitBudget,
+    RateLimiter,
+};
+use llama_cpp::{ServerProps, get_models, get_props, is_jinja_required_error};
+use open_ai::{RequestError, ResponseStreamEvent};
+pub use settings::LlamaCppAvailableModel as AvailableModel;
+use settings::{Settings, SettingsStore};
+use std::{collections::BTreeMap, sync::Arc};
+use ui::{ButtonLike, Indicator, List, ListBulletItem, prelude::*};
+use util::ResultExt;
+
+use crate::AllLanguageModelSettings;
+use crate::provider::open_ai::{OpenAiEventMapper, into_open_ai};
+
+const LLAMA_CPP_SERVER_DOCS_URL: &str =
+    "https://github.com/ggml-org/llama.cpp/tree/master/tools/server";
+
+const PROVIDER_ID: LanguageModelProviderId = LanguageModelProviderId::new("llama_cpp");
+const PROVIDER_NAME: LanguageModelProviderName = LanguageModelProviderName::new("llama.cpp");
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LlamaCppSettings {
+    pub api_url: String,
+    pub available_models: Vec<AvailableModel>,
+}
+
+pub struct LlamaCppLanguageModelProvider {
+    http_client: Arc<dyn HttpClient>,
+    state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
+}
+
+pub struct State {
+    http_client: Arc<dyn HttpClient>,
+    available_models: Vec<llama_cpp::Model>,
+    fetch_model_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    fn is_authenticated(&self) -> bool {
+        !self.available_models.is_empty()
+    }
+
+    fn fetch_models(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let settings = &AllLanguageModelSettings::get_global(cx).llama_cpp;
+        let http_client = self.http_client.clone();
+        let api_url = settings.api_url.clone();
+
+        // As a proxy for the server being "authenticated", we'll check if it's up by fetching the models
+        cx.spawn(async move |this, cx| {
+            let models = get_models(http_client.as_ref(), &api_url).await?;
+            // `/props` describes the loaded model's runtime context length and chat template.
+            // It can be disabled on shared servers, in which case we fall back to what
+            // `/v1/models` reports.
+            let props = get_props(http_client.as_ref(), &api_url)
+                .await
+                .log_err()
+                .unwrap_or_else(ServerProps::default);
+
+            let mut models: Vec<llama_cpp::Model> = models
+                .iter()
+                .map(|model| llama_cpp::Model::from_server(model, &props))
+                .collect();
+            models.sort_by(|a, b| a.name.cmp(&b.name));
+
+            this.update(cx, |this, cx| {
+                this.available_models = models;
+                cx.notify();
+            })
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut Context<Self>) {
+        let task = self.fetch_models(cx);
+        self.fetch_model_task.replace(task);
+    }
+
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let fetch_models_task = self.fetch_models(cx);
+        cx.spawn(async move |_this, _cx| {
+            fetch_models_task.await.map_err(|err| {
+                // A refused connection means that `llama-server` isn't running.
+                let connection_refused = err.chain().any(|cause| {
+                    cause
+                        .downcast_ref::<std::io::Error>()
+                        .is_some_and(|err| err.kind() == std::io::ErrorKind::ConnectionRefused)
+                });
+                if connection_refused {
+                    AuthenticateError::ConnectionRefused
+                } else {
+                    AuthenticateError::Other(err)
+                }
+            })
+        })
+    }
+}
+
+impl LlamaCppLanguageModelProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
+        let this = Self {
+            http_client: http_client.clone(),
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+            state: cx.new(|cx| {
+                let subscription = cx.observe_global::<SettingsStore>({
+                    let mut settings = AllLanguageModelSettings::get_global(cx).llama_cpp.clone();
+                    move |this: &mut State, cx| {
+                        let new_settings = &AllLanguageModelSettings::get_global(cx).llama_cpp;
+                        if &settings != new_settings {
+                            settings = new_settings.clone();
+                            this.restart_fetch_models_task(cx);
+                            cx.notify();
+                        }
+                    }
+                });
+
+                State {
+                    http_client,
+                    available_models: Default::default(),
+                    fetch_model_task: None,
+                    _subscription: subscription,
+                }
+            }),
+        };
+        this.state
+            .update(cx, |state, cx| state.restart_fetch_models_task(cx));
+        this
+    }
+
+    fn create_language_model(&self, model: llama_cpp::Model) -> Arc<dyn LanguageModel> {
+        Arc::new(LlamaCppLanguageModel {
+            id: LanguageModelId::from(model.name.clone()),
+            model,
+            http_client: self.http_client.clone(),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
+        })
+    }
+}
+
+impl LanguageModelProviderState for LlamaCppLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for LlamaCppLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn icon(&self) -> IconOrSvg {
+        IconOrSvg::Icon(IconName::AiOpenAiCompat)
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        // `llama-server` serves the model it was started with, so it's always loaded.
+        self.provided_models(cx).into_iter().next()
+    }
+
+    fn default_fast_model(&self, _: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        let mut models: BTreeMap<String, llama_cpp::Model> = BTreeMap::default();
+
+        // Add models from the llama.cpp server
+        for model in self.state.read(cx).available_models.iter() {
+            models.insert(model.name.clone(), model.clone());
+        }
+
+        // Override with available models from settings
+        for model in AllLanguageModelSettings::get_global(cx)
+            .llama_cpp
+            .available_models
+            .iter()
+        {
+            models.insert(
+                model.name.clone(),
+                llama_cpp::Model {
+                    name: model.name.clone(),
+                    display_name: model.display_name.clone(),
+                    max_tokens: model.max_tokens,
+                    supports_tool_calls: model.supports_tool_calls,
+                    supports_parallel_tool_calls: model.supports_parallel_tool_calls,
+                    supports_images: model.supports_images,
+                },
+            );
+        }
+
+        models
+            .into_values()
+            .map(|model| self.create_language_model(model))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(
+        &self,
+        _target_agent: language_model::ConfigurationViewTargetAgent,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> AnyView {
+        let state = self.state.clone();
+        cx.new(|cx| ConfigurationView::new(state, cx)).into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.fetch_models(cx))
+    }
+}
+
+pub struct LlamaCppLanguageModel {
+    id: LanguageModelId,
+    model: llama_cpp::Model,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl LlamaCppLanguageModel {
+    fn to_llama_cpp_request(&self, request: LanguageModelRequest) -> open_ai::Request {
+        let mut request = into_open_ai(
+            request,
+            &self.model.name,
+            self.model.supports_parallel_tool_calls,
+            false,
+            None,
+            None,
+        );
+        // `llama-server` rejects tool definitions unless it was started with `--jinja`, even
+        // when the request doesn't end up calling them.
+        if !self.model.supports_tool_calls {
+            request.tools.clear();
+            request.tool_choice = None;
+        }
+        request
+    }
+
+    fn stream_completion(
+        &self,
+        request: open_ai::Request,
+        estimated_tokens: u64,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<BoxStream<'static, Result<ResponseStreamEvent>>, LanguageModelCompletionError>,
+    > {
+        let http_client = self.http_client.clone();
+        let Ok(api_url) = cx.update(|cx| {
+            let settings = &AllLanguageModelSettings::get_global(cx).llama_cpp;
+            settings.api_url.clone()
+        }) else {
+            return futures::future::ready(Err(anyhow!("App state dropped").into())).boxed();
+        };
+
+        let future = self.request_limiter.stream(estimated_tokens, async move {
+            // The OpenAI-compatible endpoints are served under `/v1`, next to llama.cpp's own ones.
+            let api_url = format!("{api_url}/v1");
+            let response = open_ai::stream_completion(
+                http_client.as_ref(),
+                PROVIDER_NAME.0.as_str(),
+                &api_url,
+                "",
+                request,
+            )
+            .await;
+            match response {
+                Ok(response) => Ok(response),
+                Err(RequestError::HttpResponseError { body, .. })
+                    if is_jinja_required_error(&body) =>
+                {
+                    Err(LanguageModelCompletionError::Other(anyhow!(
+                        "llama.cpp needs to be started with `--jinja` to use tools"
+                    )))
+                }
+                Err(error) => Err(error.into()),
+            }
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+impl LanguageModel for LlamaCppLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.display_name().to_string())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tool_calls
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        self.supports_tools()
+            && match choice {
+                LanguageModelToolChoice::Auto => true,
+                LanguageModelToolChoice::Any => true,
+                LanguageModelToolChoice::None => true,
+            }
+    }
+
+    fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
+        // Tool schemas are compiled into grammars, which only support a subset of JSON Schema.
+        LanguageModelToolSchemaFormat::JsonSchemaSubset
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("llama_cpp/{}", self.model.id())
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_token_count()
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        // The tokenizer depends on the loaded model, so estimate like the other local providers.
+        let token_count = request
+            .messages
+            .iter()
+            .map(|msg| msg.string_contents().split_whitespace().count())
+            .sum::<usize>();
+
+        let estimated_tokens = (token_count as f64 * 0.75) as u64;
+        async move { Ok(estimated_tokens) }.boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let estimated_tokens = request.estimated_token_count();
+        let request = self.to_llama_cpp_request(request);
+        let completions = self.stream_completion(request, estimated_tokens, cx);
+        async move {
+            let mapper = OpenAiEventMapper::new();
+            Ok(mapper.map_stream(completions.await?).boxed())
+        }
+        .boxed()
+    }
+}
+
+struct ConfigurationView {
+    state: Entity<State>,
+    loading_models_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    pub fn new(state: Entity<State>, cx: &mut Context<Self>) -> Self {
+        let loading_models_task = Some(cx.spawn({
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    task.await.log_err();
+                }
+                this.update(cx, |this, cx| {
+                    this.loading_models_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            state,
+            loading_models_task,
+        }
+    }
+
+    fn retry_connection(&self, cx: &mut App) {
+        self.state
+            .update(cx, |state, cx| state.fetch_models(cx))
+            .detach_and_log_err(cx);
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_authenticated = self.state.read(cx).is_authenticated();
+
+        let llama_cpp_intro = "Run GGUF models locally with llama.cpp's `llama-server`.";
+
+        if self.loading_models_task.is_some() {
+            div().child(Label::new("Loading models...")).into_any()
+        } else {
+            v_flex()
+                .gap_2()
+                .child(
+                    v_flex().gap_1().child(Label::new(llama_cpp_intro)).child(
+                        List::new()
+                            .child(
+                                ListBulletItem::new("")
+                                    .child(Label::new("Start a server, for example with"))
+                                    .child(
+                                        Label::new("llama-server --jinja -hf <model>")
+                                            .inline_code(cx),
+                                    ),
+                            )
+                            .child(ListBulletItem::new(
+                                "The --jinja flag is required for tool calling in the agent.",
+                            )),
+                    ),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .gap_2()
+                        .child(
+                            Button::new("llama-cpp-docs", "Server Docs")
+                                .style(ButtonStyle::Subtle)
+                                .icon(IconName::ArrowUpRight)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .on_click(move |_, _window, cx| {
+                                    cx.open_url(LLAMA_CPP_SERVER_DOCS_URL)
+                                }),
+                        )
+                        .map(|this| {
+                            if is_authenticated {
+                                this.child(
+                                    ButtonLike::new("connected")
+                                        .disabled(true)
+                                        .cursor_style(gpui::CursorStyle::Arrow)
+                                        .child(
+                                            h_flex()
+                                                .gap_2()
+                                                .child(Indicator::dot().color(Color::Success))
+                                                .child(Label::new("Connected"))
+                                                .into_any_element(),
+                                        ),
+                                )
+                            } else {
+                                this.child(
+                                    Button::new("retry_llama_cpp_models", "Connect")
+                                        .icon_position(IconPosition::Start)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon(IconName::PlayFilled)
+                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                            this.retry_connection(cx)
+                                        })),
+                                )
+                            }
+                        }),
+                )
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::AsyncReadExt as _;
+    use gpui::TestAppContext;
+    use http_client::{AsyncBody, FakeHttpClient, Response};
+    use language_model::{
+        LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolResult,
+        LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, Role, StopReason,
+    };
+    use serde_json::{Value, json};
+    use std::sync::Mutex;
+
+    fn model(supports_tool_calls: bool) -> LlamaCppLanguageModel {
+        LlamaCppLanguageModel {
+            id: LanguageModelId::from("qwen".to_string()),
+            model: llama_cpp::Model {
+                name: "qwen".into(),
+                display_name: None,
+                max_tokens: 8192,
+                supports_tool_calls,
+                supports_parallel_tool_calls: false,
+                supports_images: false,
+            },
+            http_client: http_client::FakeHttpClient::with_404_response(),
+            request_limiter: RateLimiter::new(4),
+        }
+    }
+
+    #[test]
+    fn test_tools_are_dropped_without_jinja() {
+        let request = LanguageModelRequest {
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["What's in this directory?".into()],
+                cache: false,
+                reasoning_details: None,
+            }],
+            tools: vec![LanguageModelRequestTool {
+                name: "list_directory".into(),
+                description: "Lists a directory".into(),
+                input_schema: serde_json::json!({ "type": "object" }),
+            }],
+            tool_choice: Some(LanguageModelToolChoice::Auto),
+            ..Default::default()
+        };
+
+        let with_tools = model(true).to_llama_cpp_request(request.clone());
+        assert_eq!(with_tools.tools.len(), 1);
+        assert!(with_tools.tool_choice.is_some());
+        assert_eq!(with_tools.max_completion_tokens, None);
+
+        let without_tools = model(false).to_llama_cpp_request(request);
+        assert!(without_tools.tools.is_empty());
+        assert!(without_tools.tool_choice.is_none());
+    }
+
+    /// Serves `/v1/models`, `/props` and `/v1/chat/completions` the way `llama-server` does,
+    /// replying to each completion request with the next of `responses` and recording the
+    /// request bodies.
+    fn fake_llama_server(
+        responses: Vec<Vec<Value>>,
+    ) -> (Arc<dyn HttpClient>, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let responses = Arc::new(Mutex::new(responses.into_iter()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                let responses = responses.clone();
+                async move {
+                    let path = request.uri().path().to_string();
+                    let mut body = String::new();
+                    request.into_body().read_to_string(&mut body).await?;
+                    let body = match path.as_str() {
+                        "/v1/models" => json!({
+                            "object": "list",
+                            "data": [{
+                                "id": "qwen2.5-coder-7b-instruct-q8_0.gguf",
+                                "object": "model",
+                                "meta": { "n_ctx_train": 32768 }
+                            }]
+                        })
+                        .to_string(),
+                        "/props" => json!({
+                            "default_generation_settings": { "n_ctx": 16384 },
+                            "chat_template_caps": {
+                                "supports_tools": true,
+                                "supports_tool_calls": true
+                            }
+                        })
+                        .to_string(),
+                        "/v1/chat/completions" => {
+                            requests.lock().unwrap().push(serde_json::from_str(&body)?);
+                            let chunks = responses
+                                .lock()
+                                .unwrap()
+                                .next()
+                                .expect("unexpected completion request");
+                            chunks
+                                .iter()
+                                .map(|chunk| format!("data: {chunk}\n\n"))
+                                .chain(["data: [DONE]\n\n".to_string()])
+                                .collect()
+                        }
+                        _ => {
+                            return Ok(Response::builder()
+                                .status(404)
+                                .body(AsyncBody::default())?);
+                        }
+                    };
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(AsyncBody::from(body))?)
+                }
+            }
+        });
+        (http_client, requests)
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+        });
+    }
+
+    fn chunk(delta: Value, finish_reason: Option<&str>) -> Value {
+        json!({
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+            "usage": null
+        })
+    }
+
+    async fn complete(
+        model: &Arc<dyn LanguageModel>,
+        request: LanguageModelRequest,
+        cx: &mut TestAppContext,
+    ) -> Vec<LanguageModelCompletionEvent> {
+        let stream = model
+            .stream_completion(request, &cx.to_async())
+            .await
+            .unwrap();
+        stream.map(|event| event.unwrap()).collect().await
+    }
+
+    fn user_message(text: &str) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![text.into()],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_server_model_and_context_length(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, _) = fake_llama_server(Vec::new());
+        let provider = cx.update(|cx| LlamaCppLanguageModelProvider::new(http_client, cx));
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            assert!(provider.is_authenticated(cx));
+            let model = provider.default_model(cx).unwrap();
+            assert_eq!(model.id().0.as_ref(), "qwen2.5-coder-7b-instruct-q8_0.gguf");
+            // The runtime context from `/props` wins over the training context.
+            assert_eq!(model.max_token_count(), 16384);
+            assert!(model.supports_tools());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_streamed_completion(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, requests) = fake_llama_server(vec![vec![
+            chunk(json!({ "role": "assistant", "content": "Hello" }), None),
+            chunk(json!({ "content": ", world" }), None),
+            chunk(json!({}), Some("stop")),
+        ]]);
+        let provider = cx.update(|cx| LlamaCppLanguageModelProvider::new(http_client, cx));
+        cx.run_until_parked();
+        let model = cx.update(|cx| provider.default_model(cx)).unwrap();
+
+        let events = complete(
+            &model,
+            LanguageModelRequest {
+                messages: vec![user_message("Say hello")],
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::Text("Hello".into()),
+                LanguageModelCompletionEvent::Text(", world".into()),
+                LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+            ]
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["model"], "qwen2.5-coder-7b-instruct-q8_0.gguf");
+        assert_eq!(requests[0]["stream"], true);
+    }
+
+    #[gpui::test]
+    async fn test_tool_call_round_trip(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, requests) = fake_llama_server(vec![
+            vec![
+                chunk(
+                    json!({
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "index": 0,
+                            "id": "call_1",
+                            "type": "function",
+                            "function": { "name": "get_weather", "arguments": "{\"city\":" }
+                        }]
+                    }),
+                    None,
+                ),
+                chunk(
+                    json!({
+                        "content": null,
+                        "tool_calls": [{
+                            "index": 0,
+                            "function": { "arguments": "\"Paris\"}" }
+                        }]
+                    }),
+                    None,
+                ),
+                chunk(json!({}), Some("tool_calls")),
+            ],
+            vec![
+                chunk(
+                    json!({ "role": "assistant", "content": "It's sunny." }),
+                    None,
+                ),
+                chunk(json!({}), Some("stop")),
+            ],
+        ]);
+        let provider = cx.update(|cx| LlamaCppLanguageModelProvider::new(http_client, cx));
+        cx.run_until_parked();
+        let model = cx.update(|cx| provider.default_model(cx)).unwrap();
+
+        let mut request = LanguageModelRequest {
+            messages: vec![user_message("What's the weather in Paris?")],
+            tools: vec![LanguageModelRequestTool {
+                name: "get_weather".into(),
+                description: "Gets the weather for a city".into(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } }
+                }),
+            }],
+            ..Default::default()
+        };
+        let events = complete(&model, request.clone(), cx).await;
+        let tool_use = LanguageModelToolUse {
+            id: "call_1".into(),
+            name: "get_weather".into(),
+            raw_input: "{\"city\":\"Paris\"}".into(),
+            input: json!({ "city": "Paris" }),
+            is_input_complete: true,
+            thought_signature: None,
+        };
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::ToolUse(tool_use.clone()),
+                LanguageModelCompletionEvent::Stop(StopReason::ToolUse),
+            ]
+        );
+
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::Assistant,
+            content: vec![MessageContent::ToolUse(tool_use)],
+            cache: false,
+            reasoning_details: None,
+        });
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![MessageContent::ToolResult(LanguageModelToolResult {
+                tool_use_id: "call_1".into(),
+                tool_name: "get_weather".into(),
+                is_error: false,
+                content: LanguageModelToolResultContent::Text("Sunny, 24°C".into()),
+                output: None,
+            })],
+            cache: false,
+            reasoning_details: None,
+        });
+        let events = complete(&model, request, cx).await;
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::Text("It's sunny.".into()),
+                LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+            ]
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0]["tools"][0]["function"]["name"], "get_weather");
+        let messages = requests[1]["messages"].as_array().unwrap();
+        let tool_call = messages
+            .iter()
+            .find_map(|message| message["tool_calls"].get(0))
+            .unwrap();
+        assert_eq!(tool_call["id"], "call_1");
+        assert_eq!(tool_call["function"]["name"], "get_weather");
+        let tool_result = messages
+            .iter()
+            .find(|message| message["role"] == "tool")
+            .unwrap();
+        assert_eq!(tool_result["tool_call_id"], "call_1");
+    }
+}
@@ -12,7 +12,7 @@ use language_model::{
 use language_model::{
     IconOrSvg, LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    LanguageModelRequest, RateLimitBudget, RateLimiter, Role,
 };
 use lmstudio::{ModelType, get_models};
 pub use settings::LmStudioAvailableModel as AvailableModel;
@@ -41,6 +41,7 @@ pub struct LmStudioSettings {
 pub struct LmStudioLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -131,6 +132,7 @@ impl LmStudioLanguageModelProvider {
     pub fn new(http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
         let this = Self {
             http_client: http_client.clone(),
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
             state: cx.new(|cx| {
                 let subscription = cx.observe_global::<SettingsStore>({
                     let mut settings = AllLanguageModelSettings::get_global(cx).lmstudio.clone();
@@ -224,7 +226,7 @@ impl LanguageModelProvider for LmStudioLanguageModelProvider {
                     id: LanguageModelId::from(model.name.clone()),
                     model,
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
@@ -370,6 +372,7 @@ impl LmStudioLanguageModel {
     fn stream_completion(
         &self,
         request: lmstudio::ChatCompletionRequest,
+        estimated_tokens: u64,
         cx: &AsyncApp,
     ) -> BoxFuture<
         'static,
@@ -383,7 +386,7 @@ impl LmStudioLanguageModel {
             return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
         };
 
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let request = lmstudio::stream_chat_completion(http_client.as_ref(), &api_url, request);
             let response = request.await?;
             Ok(response)
@@ -462,8 +465,9 @@ impl LanguageModel for LmStudioLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = self.to_lmstudio_request(request);
-        let completions = self.stream_completion(request, cx);
+        let completions = self.stream_completion(request, estimated_tokens, cx);
         async move {
             let mapper = LmStudioEventMapper::new();
             Ok(mapper.map_stream(completions.await?).boxed())
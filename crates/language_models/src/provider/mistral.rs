@@ -9,7 +9,8 @@ use language_model::{
     LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
-    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage, env_var,
+    LanguageModelToolUse, MessageContent, RateLimitBudget, RateLimiter, Role, StopReason,
+    TokenUsage, env_var,
 };
 pub use mistral::{CODESTRAL_API_URL, MISTRAL_API_URL, StreamResponse};
 pub use settings::MistralAvailableModel as AvailableModel;
@@ -41,6 +42,7 @@ pub struct MistralSettings {
 pub struct MistralLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     pub state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -117,7 +119,11 @@ impl MistralLanguageModelProvider {
             }
         });
 
-        let this = Arc::new(Self { http_client, state });
+        let this = Arc::new(Self {
+            http_client,
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        });
         cx.set_global(GlobalMistralLanguageModelProvider(this));
         cx.global::<GlobalMistralLanguageModelProvider>().0.clone()
     }
@@ -141,7 +147,7 @@ impl MistralLanguageModelProvider {
             model,
             state: self.state.clone(),
             http_client: self.http_client.clone(),
-            request_limiter: RateLimiter::new(4),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
         })
     }
 
@@ -223,7 +229,7 @@ impl LanguageModelProvider for MistralLanguageModelProvider {
                     model,
                     state: self.state.clone(),
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
                 }) as Arc<dyn LanguageModel>
             })
             .collect()
@@ -265,6 +271,7 @@ impl MistralLanguageModel {
     fn stream_completion(
         &self,
         request: mistral::Request,
+        estimated_tokens: u64,
         cx: &AsyncApp,
     ) -> BoxFuture<
         'static,
@@ -279,7 +286,7 @@ impl MistralLanguageModel {
             return future::ready(Err(anyhow!("App state dropped"))).boxed();
         };
 
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let Some(api_key) = api_key else {
                 return Err(LanguageModelCompletionError::NoApiKey {
                     provider: PROVIDER_NAME,
@@ -373,8 +380,9 @@ impl LanguageModel for MistralLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = into_mistral(request, self.model.clone(), self.max_output_tokens());
-        let stream = self.stream_completion(request, cx);
+        let stream = self.stream_completion(request, estimated_tokens, cx);
 
         async move {
             let stream = stream.await?;
@@ -9,7 +9,8 @@ use language_model::{
     LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
     LanguageModelRequest, LanguageModelRequestTool, LanguageModelToolChoice, LanguageModelToolUse,
-    LanguageModelToolUseId, MessageContent, RateLimiter, Role, StopReason, TokenUsage, env_var,
+    LanguageModelToolUseId, MessageContent, RateLimitBudget, RateLimiter, Role, StopReason,
+    TokenUsage, env_var,
 };
 use menu;
 use ollama::{
@@ -50,6 +51,7 @@ pub struct OllamaSettings {
 pub struct OllamaLanguageModelProvider {
     http_client: Arc<dyn HttpClient>,
     state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
 }
 
 pub struct State {
@@ -160,6 +162,7 @@ impl OllamaLanguageModelProvider {
     pub fn new(http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
         let this = Self {
             http_client: http_client.clone(),
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
             state: cx.new(|cx| {
                 cx.observe_global::<SettingsStore>({
                     let mut last_settings = OllamaLanguageModelProvider::settings(cx).clone();
@@ -258,7 +261,7 @@ impl LanguageModelProvider for OllamaLanguageModelProvider {
                     id: LanguageModelId::from(model.name.clone()),
                     model,
                     http_client: self.http_client.clone(),
-                    request_limiter: RateLimiter::new(4),
+                    request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
                     state: self.state.clone(),
                 }) as Arc<dyn LanguageModel>
             })
@@ -476,6 +479,7 @@ impl LanguageModel for OllamaLanguageModel {
             LanguageModelCompletionError,
         >,
     > {
+        let estimated_tokens = request.estimated_token_count();
         let request = self.to_ollama_request(request);
 
         let http_client = self.http_client.clone();
@@ -486,7 +490,7 @@ impl LanguageModel for OllamaLanguageModel {
             return futures::future::ready(Err(anyhow!("App state dropped").into())).boxed();
         };
 
-        let future = self.request_limiter.stream(async move {
+        let future = self.request_limiter.stream(estimated_tokens, async move {
             let stream =
                 stream_chat_completion(http_client.as_ref(), &api_url, api_key.as_deref(), request)
                     .await?;
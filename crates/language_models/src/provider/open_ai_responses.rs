@@ -0,0 +1,899 @@
+use anyhow::{Result, anyhow};
+use futures::{FutureExt, StreamExt, future, future::BoxFuture};
+use gpui::{AnyView, App, AsyncApp, Context, Entity, SharedString, Subscription, Task, Window};
+use http_client::HttpClient;
+use language_model::{
+    ApiKeyState, AuthenticateError, EnvVar, IconOrSvg, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolSchemaFormat, RateLimitBudget,
+    RateLimiter, env_var,
+};
+use menu;
+use open_ai::{
+    ListedModel, list_models,
+    responses::{Request as ResponseRequest, StreamEvent as ResponsesStreamEvent, stream_response},
+};
+pub use settings::OpenAiResponsesAvailableModel as AvailableModel;
+pub use settings::OpenAiResponsesModelCapabilities as ModelCapabilities;
+use settings::{Settings, SettingsStore};
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+use ui::{ElevationIndex, Tooltip, prelude::*};
+use ui_input::InputField;
+use util::ResultExt;
+
+use crate::AllLanguageModelSettings;
+use crate::provider::open_ai::{OpenAiResponseEventMapper, into_open_ai_response};
+
+const PROVIDER_ID: LanguageModelProviderId = LanguageModelProviderId::new("openai_responses");
+const PROVIDER_NAME: LanguageModelProviderName =
+    LanguageModelProviderName::new("OpenAI Responses API");
+
+/// The context length to assume for listed models when the gateway doesn't report one.
+const DEFAULT_MAX_TOKENS: u64 = 32_768;
+
+const API_KEY_ENV_VAR_NAME: &str = "OPENAI_RESPONSES_API_KEY";
+static API_KEY_ENV_VAR: LazyLock<EnvVar> = env_var!(API_KEY_ENV_VAR_NAME);
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct OpenAiResponsesSettings {
+    pub api_url: String,
+    pub available_models: Vec<AvailableModel>,
+}
+
+pub struct OpenAiResponsesLanguageModelProvider {
+    http_client: Arc<dyn HttpClient>,
+    state: Entity<State>,
+    rate_limit_budget: Arc<RateLimitBudget>,
+}
+
+pub struct State {
+    api_key_state: ApiKeyState,
+    http_client: Arc<dyn HttpClient>,
+    listed_models: Vec<ListedModel>,
+    fetch_models_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    /// Gateways that don't require an API key are usable as soon as they list their models.
+    fn is_authenticated(&self) -> bool {
+        self.api_key_state.has_key() || !self.listed_models.is_empty()
+    }
+
+    fn api_url(cx: &App) -> SharedString {
+        SharedString::new(
+            AllLanguageModelSettings::get_global(cx)
+                .openai_responses
+                .api_url
+                .as_str(),
+        )
+    }
+
+    fn set_api_key(&mut self, api_key: Option<String>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let api_url = Self::api_url(cx);
+        let store = self
+            .api_key_state
+            .store(api_url, api_key, |this| &mut this.api_key_state, cx);
+        cx.spawn(async move |this, cx| {
+            store.await?;
+            this.update(cx, |this, cx| this.restart_fetch_models_task(cx))
+        })
+    }
+
+    fn fetch_models(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let http_client = self.http_client.clone();
+        let api_url = Self::api_url(cx);
+        let api_key = self.api_key_state.key(&api_url);
+
+        cx.spawn(async move |this, cx| {
+            let mut models = list_models(
+                http_client.as_ref(),
+                PROVIDER_NAME.0.as_str(),
+                &api_url,
+                api_key.as_deref(),
+            )
+            .await?;
+            models.sort_by(|a, b| a.id.cmp(&b.id));
+
+            this.update(cx, |this, cx| {
+                this.listed_models = models;
+                cx.notify();
+            })
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut Context<Self>) {
+        let task = self.fetch_models(cx);
+        self.fetch_models_task.replace(task);
+    }
+
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let api_url = Self::api_url(cx);
+        let load_api_key =
+            self.api_key_state
+                .load_if_needed(api_url, |this| &mut this.api_key_state, cx);
+        cx.spawn(async move |this, cx| {
+            let api_key_result = load_api_key.await;
+            let fetch_models_result = this.update(cx, |this, cx| this.fetch_models(cx))?.await;
+            match (api_key_result, fetch_models_result) {
+                (_, Ok(())) => Ok(()),
+                (Ok(()), Err(error)) => {
+                    // Not every gateway implements `/models`, in which case only the models
+                    // from the settings are offered.
+                    log::warn!("Failed to list {} models: {error:#}", PROVIDER_NAME.0);
+                    Ok(())
+                }
+                (Err(error), Err(_)) => Err(error),
+            }
+        })
+    }
+}
+
+impl OpenAiResponsesLanguageModelProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, cx: &mut App) -> Self {
+        let state = cx.new(|cx| {
+            let subscription = cx.observe_global::<SettingsStore>({
+                let mut settings = AllLanguageModelSettings::get_global(cx)
+                    .openai_responses
+                    .clone();
+                move |this: &mut State, cx| {
+                    let new_settings = &AllLanguageModelSettings::get_global(cx).openai_responses;
+                    if &settings != new_settings {
+                        if settings.api_url != new_settings.api_url {
+                            let api_url = SharedString::new(new_settings.api_url.as_str());
+                            this.api_key_state.handle_url_change(
+                                api_url,
+                                |this| &mut this.api_key_state,
+                                cx,
+                            );
+                            this.listed_models.clear();
+                            this.restart_fetch_models_task(cx);
+                        }
+                        settings = new_settings.clone();
+                        cx.notify();
+                    }
+                }
+            });
+
+            State {
+                api_key_state: ApiKeyState::new(State::api_url(cx), (*API_KEY_ENV_VAR).clone()),
+                http_client: http_client.clone(),
+                listed_models: Vec::new(),
+                fetch_models_task: None,
+                _subscription: subscription,
+            }
+        });
+
+        Self {
+            http_client,
+            state,
+            rate_limit_budget: RateLimitBudget::for_provider(PROVIDER_ID, cx),
+        }
+    }
+
+    fn create_language_model(&self, model: AvailableModel) -> Arc<dyn LanguageModel> {
+        Arc::new(OpenAiResponsesLanguageModel {
+            id: LanguageModelId::from(model.name.clone()),
+            model,
+            state: self.state.clone(),
+            http_client: self.http_client.clone(),
+            request_limiter: RateLimiter::with_budget(4, self.rate_limit_budget.clone()),
+        })
+    }
+}
+
+impl LanguageModelProviderState for OpenAiResponsesLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for OpenAiResponsesLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn icon(&self) -> IconOrSvg {
+        IconOrSvg::Icon(IconName::AiOpenAiCompat)
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        self.provided_models(cx).into_iter().next()
+    }
+
+    fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        let mut models = BTreeMap::default();
+
+        // Add the models listed by the gateway
+        for model in self.state.read(cx).listed_models.iter() {
+            models.insert(
+                model.id.clone(),
+                AvailableModel {
+                    name: model.id.clone(),
+                    display_name: None,
+                    max_tokens: model.context_length().unwrap_or(DEFAULT_MAX_TOKENS),
+                    max_output_tokens: None,
+                    capabilities: ModelCapabilities::default(),
+                },
+            );
+        }
+
+        // Override with available models from settings
+        for model in AllLanguageModelSettings::get_global(cx)
+            .openai_responses
+            .available_models
+            .iter()
+        {
+            models.insert(model.name.clone(), model.clone());
+        }
+
+        models
+            .into_values()
+            .map(|model| self.create_language_model(model))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(
+        &self,
+        _target_agent: language_model::ConfigurationViewTargetAgent,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state
+            .update(cx, |state, cx| state.set_api_key(None, cx))
+    }
+}
+
+pub struct OpenAiResponsesLanguageModel {
+    id: LanguageModelId,
+    model: AvailableModel,
+    state: Entity<State>,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl OpenAiResponsesLanguageModel {
+    fn stream_response(
+        &self,
+        request: ResponseRequest,
+        estimated_tokens: u64,
+        cx: &AsyncApp,
+    ) -> BoxFuture<'static, Result<futures::stream::BoxStream<'static, Result<ResponsesStreamEvent>>>>
+    {
+        let http_client = self.http_client.clone();
+
+        let Ok((api_key, api_url)) = self.state.read_with(cx, |state, cx| {
+            let api_url = State::api_url(cx);
+            (state.api_key_state.key(&api_url), api_url)
+        }) else {
+            return future::ready(Err(anyhow!("App state dropped"))).boxed();
+        };
+
+        let future = self.request_limiter.stream(estimated_tokens, async move {
+            // Gateways without authentication ignore the empty bearer token.
+            let request = stream_response(
+                http_client.as_ref(),
+                PROVIDER_NAME.0.as_str(),
+                &api_url,
+                api_key.as_deref().unwrap_or_default(),
+                request,
+            );
+            let response = request.await?;
+            Ok(response)
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+impl LanguageModel for OpenAiResponsesLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(
+            self.model
+                .display_name
+                .clone()
+                .unwrap_or_else(|| self.model.name.clone()),
+        )
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.capabilities.tools
+    }
+
+    fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
+        LanguageModelToolSchemaFormat::JsonSchemaSubset
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.capabilities.images
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        match choice {
+            LanguageModelToolChoice::Auto => self.model.capabilities.tools,
+            LanguageModelToolChoice::Any => self.model.capabilities.tools,
+            LanguageModelToolChoice::None => true,
+        }
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("openai_responses/{}", self.model.name)
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_tokens
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        let max_token_count = self.max_token_count();
+        cx.background_spawn(async move {
+            let messages = super::open_ai::collect_tiktoken_messages(request);
+            let model = if max_token_count >= 100_000 {
+                // If the max tokens is 100k or more, it is likely the o200k_base tokenizer from gpt4o
+                "gpt-4o"
+            } else {
+                // Otherwise fallback to gpt-4, since only cl100k_base and o200k_base are
+                // supported with this tiktoken method
+                "gpt-4"
+            };
+            tiktoken_rs::num_tokens_from_messages(model, &messages).map(|tokens| tokens as u64)
+        })
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            futures::stream::BoxStream<
+                'static,
+                Result<LanguageModelCompletionEvent, LanguageModelCompletionError>,
+            >,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let estimated_tokens = request.estimated_token_count();
+        let request = into_open_ai_response(
+            request,
+            &self.model.name,
+            self.model.capabilities.parallel_tool_calls,
+            self.model.capabilities.prompt_cache_key,
+            self.max_output_tokens(),
+            None,
+        );
+        let completions = self.stream_response(request, estimated_tokens, cx);
+        async move {
+            let mapper = OpenAiResponseEventMapper::new();
+            Ok(mapper.map_stream(completions.await?).boxed())
+        }
+        .boxed()
+    }
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<InputField>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            InputField::new(
+                window,
+                cx,
+                "000000000000000000000000000000000000000000000000000",
+            )
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn_in(window, {
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // We don't log an error, because "not signed in" is also an error.
+                    let _ = task.await;
+                }
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx).trim().to_string();
+        if api_key.is_empty() {
+            return;
+        }
+
+        // url changes can cause the editor to be displayed again
+        self.api_key_editor
+            .update(cx, |input, cx| input.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(Some(api_key), cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |input, cx| input.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(None, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn should_render_editor(&self, cx: &Context<Self>) -> bool {
+        !self.state.read(cx).is_authenticated()
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let has_api_key = state.api_key_state.has_key();
+        let env_var_set = state.api_key_state.is_from_env_var();
+        let env_var_name = state.api_key_state.env_var_name();
+        let api_url = State::api_url(cx);
+
+        let api_key_section = if self.should_render_editor(cx) {
+            v_flex()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(format!("To use Zed's agent with the Responses API gateway at {api_url}, add its API key.")))
+                .child(
+                    div()
+                        .pt(DynamicSpacing::Base04.rems(cx))
+                        .child(self.api_key_editor.clone())
+                )
+                .child(
+                    Label::new(
+                        format!("You can also set the {env_var_name} environment variable and restart Zed. Gateways that don't require a key connect automatically."),
+                    )
+                    .size(LabelSize::Small).color(Color::Muted),
+                )
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .flex_1()
+                        .min_w_0()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(
+                            div()
+                                .w_full()
+                                .overflow_x_hidden()
+                                .text_ellipsis()
+                                .child(Label::new(
+                                    if env_var_set {
+                                        format!("API key set in {env_var_name} environment variable")
+                                    } else if has_api_key {
+                                        format!("API key configured for {api_url}")
+                                    } else {
+                                        format!("Connected to {api_url} without an API key")
+                                    }
+                                ))
+                        ),
+                )
+                .when(has_api_key, |this| {
+                    this.child(
+                        h_flex()
+                            .flex_shrink_0()
+                            .child(
+                                Button::new("reset-api-key", "Reset API Key")
+                                    .label_size(LabelSize::Small)
+                                    .icon(IconName::Undo)
+                                    .icon_size(IconSize::Small)
+                                    .icon_position(IconPosition::Start)
+                                    .layer(ElevationIndex::ModalSurface)
+                                    .when(env_var_set, |this| {
+                                        this.tooltip(Tooltip::text(format!("To reset your API key, unset the {env_var_name} environment variable.")))
+                                    })
+                                    .on_click(cx.listener(|this, _, window, cx| this.reset_api_key(window, cx))),
+                            ),
+                    )
+                })
+                .into_any()
+        };
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials…")).into_any()
+        } else {
+            v_flex().size_full().child(api_key_section).into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::AsyncReadExt as _;
+    use gpui::TestAppContext;
+    use http_client::{AsyncBody, FakeHttpClient, Response};
+    use language_model::{
+        LanguageModelRequestMessage, LanguageModelRequestTool, LanguageModelToolResult,
+        LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, Role, StopReason,
+    };
+    use serde_json::{Value, json};
+    use std::sync::Mutex;
+
+    /// Serves `/models` and `/responses` the way a Responses API gateway would, replying to
+    /// each completion request with the next of `responses` and recording the request bodies.
+    fn fake_gateway(responses: Vec<Vec<Value>>) -> (Arc<dyn HttpClient>, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let responses = Arc::new(Mutex::new(responses.into_iter()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                let responses = responses.clone();
+                async move {
+                    let path = request.uri().path().to_string();
+                    let mut body = String::new();
+                    request.into_body().read_to_string(&mut body).await?;
+                    let body = match path.as_str() {
+                        "/v1/models" => json!({
+                            "object": "list",
+                            "data": [
+                                { "id": "qwen3-coder", "context_length": 131072 },
+                                { "id": "gpt-oss", "max_model_len": 65536 },
+                                { "id": "unknown-context" },
+                            ]
+                        })
+                        .to_string(),
+                        "/v1/responses" => {
+                            requests.lock().unwrap().push(serde_json::from_str(&body)?);
+                            let events = responses
+                                .lock()
+                                .unwrap()
+                                .next()
+                                .expect("unexpected completion request");
+                            events
+                                .iter()
+                                .map(|event| format!("data: {event}\n\n"))
+                                .collect()
+                        }
+                        _ => {
+                            return Ok(Response::builder()
+                                .status(404)
+                                .body(AsyncBody::default())?);
+                        }
+                    };
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(AsyncBody::from(body))?)
+                }
+            }
+        });
+        (http_client, requests)
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+        });
+    }
+
+    async fn fetch_models(
+        http_client: Arc<dyn HttpClient>,
+        cx: &mut TestAppContext,
+    ) -> OpenAiResponsesLanguageModelProvider {
+        let provider = cx.update(|cx| OpenAiResponsesLanguageModelProvider::new(http_client, cx));
+        provider
+            .state
+            .update(cx, |state, cx| state.fetch_models(cx))
+            .await
+            .unwrap();
+        provider
+    }
+
+    async fn complete(
+        model: &Arc<dyn LanguageModel>,
+        request: LanguageModelRequest,
+        cx: &mut TestAppContext,
+    ) -> Vec<LanguageModelCompletionEvent> {
+        let stream = model
+            .stream_completion(request, &cx.to_async())
+            .await
+            .unwrap();
+        stream.map(|event| event.unwrap()).collect().await
+    }
+
+    fn user_message(text: &str) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![text.into()],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_listed_models_and_context_lengths(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, _) = fake_gateway(Vec::new());
+        let provider = fetch_models(http_client, cx).await;
+
+        cx.update(|cx| {
+            assert!(provider.is_authenticated(cx));
+            let models = provider
+                .provided_models(cx)
+                .iter()
+                .map(|model| (model.id().0.to_string(), model.max_token_count()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                models,
+                [
+                    ("gpt-oss".to_string(), 65536),
+                    ("qwen3-coder".to_string(), 131072),
+                    ("unknown-context".to_string(), DEFAULT_MAX_TOKENS),
+                ]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_streamed_completion(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, requests) = fake_gateway(vec![vec![
+            json!({
+                "type": "response.output_item.added",
+                "output_index": 0,
+                "item": { "type": "message", "id": "msg_1", "role": "assistant", "content": [] }
+            }),
+            json!({
+                "type": "response.output_text.delta",
+                "item_id": "msg_1",
+                "output_index": 0,
+                "content_index": 0,
+                "delta": "Hello"
+            }),
+            json!({
+                "type": "response.output_text.delta",
+                "item_id": "msg_1",
+                "output_index": 0,
+                "content_index": 0,
+                "delta": ", world"
+            }),
+            json!({ "type": "response.completed", "response": {} }),
+        ]]);
+        let provider = fetch_models(http_client, cx).await;
+        let model = cx.update(|cx| provider.default_model(cx)).unwrap();
+
+        let events = complete(
+            &model,
+            LanguageModelRequest {
+                messages: vec![user_message("Say hello")],
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::StartMessage {
+                    message_id: "msg_1".into()
+                },
+                LanguageModelCompletionEvent::Text("Hello".into()),
+                LanguageModelCompletionEvent::Text(", world".into()),
+                LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+            ]
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["model"], "gpt-oss");
+        assert_eq!(requests[0]["stream"], true);
+    }
+
+    #[gpui::test]
+    async fn test_tool_call_round_trip(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (http_client, requests) = fake_gateway(vec![
+            vec![
+                json!({
+                    "type": "response.output_item.added",
+                    "output_index": 0,
+                    "item": {
+                        "type": "function_call",
+                        "id": "fc_1",
+                        "call_id": "call_1",
+                        "name": "get_weather",
+                        "arguments": "",
+                        "status": "in_progress"
+                    }
+                }),
+                json!({
+                    "type": "response.function_call_arguments.delta",
+                    "item_id": "fc_1",
+                    "output_index": 0,
+                    "delta": "{\"city\":"
+                }),
+                json!({
+                    "type": "response.function_call_arguments.delta",
+                    "item_id": "fc_1",
+                    "output_index": 0,
+                    "delta": "\"Paris\"}"
+                }),
+                json!({
+                    "type": "response.function_call_arguments.done",
+                    "item_id": "fc_1",
+                    "output_index": 0,
+                    "arguments": "{\"city\":\"Paris\"}"
+                }),
+                json!({ "type": "response.completed", "response": {} }),
+            ],
+            vec![
+                json!({
+                    "type": "response.output_text.delta",
+                    "item_id": "msg_2",
+                    "output_index": 0,
+                    "delta": "It's sunny."
+                }),
+                json!({ "type": "response.completed", "response": {} }),
+            ],
+        ]);
+        let provider = fetch_models(http_client, cx).await;
+        let model = cx.update(|cx| provider.default_model(cx)).unwrap();
+
+        let mut request = LanguageModelRequest {
+            messages: vec![user_message("What's the weather in Paris?")],
+            tools: vec![LanguageModelRequestTool {
+                name: "get_weather".into(),
+                description: "Gets the weather for a city".into(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } }
+                }),
+            }],
+            ..Default::default()
+        };
+        let events = complete(&model, request.clone(), cx).await;
+        let tool_use = LanguageModelToolUse {
+            id: "call_1".into(),
+            name: "get_weather".into(),
+            raw_input: "{\"city\":\"Paris\"}".into(),
+            input: json!({ "city": "Paris" }),
+            is_input_complete: true,
+            thought_signature: None,
+        };
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::ToolUse(tool_use.clone()),
+                LanguageModelCompletionEvent::Stop(StopReason::ToolUse),
+            ]
+        );
+
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::Assistant,
+            content: vec![MessageContent::ToolUse(tool_use)],
+            cache: false,
+            reasoning_details: None,
+        });
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![MessageContent::ToolResult(LanguageModelToolResult {
+                tool_use_id: "call_1".into(),
+                tool_name: "get_weather".into(),
+                is_error: false,
+                content: LanguageModelToolResultContent::Text("Sunny, 24°C".into()),
+                output: None,
+            })],
+            cache: false,
+            reasoning_details: None,
+        });
+        let events = complete(&model, request, cx).await;
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::Text("It's sunny.".into()),
+                LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+            ]
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0]["tools"][0]["name"], "get_weather");
+        let input = requests[1]["input"].as_array().unwrap();
+        let function_call = input
+            .iter()
+            .find(|item| item["type"] == "function_call")
+            .unwrap();
+        assert_eq!(function_call["call_id"], "call_1");
+        assert_eq!(function_call["name"], "get_weather");
+        let function_call_output = input
+            .iter()
+            .find(|item| item["type"] == "function_call_output")
+            .unwrap();
+        assert_eq!(function_call_output["call_id"], "call_1");
+        assert_eq!(function_call_output["output"], "Sunny, 24°C");
+    }
+}
@@ -1,13 +1,15 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use collections::HashMap;
+use language_model::{LanguageModelProviderId, RateLimitPolicy};
 use settings::RegisterSetting;
 
 use crate::provider::{
     anthropic::AnthropicSettings, bedrock::AmazonBedrockSettings, cloud::ZedDotDevSettings,
-    deepseek::DeepSeekSettings, google::GoogleSettings, lmstudio::LmStudioSettings,
-    mistral::MistralSettings, ollama::OllamaSettings, open_ai::OpenAiSettings,
-    open_ai_compatible::OpenAiCompatibleSettings, open_router::OpenRouterSettings,
+    deepseek::DeepSeekSettings, google::GoogleSettings, llama_cpp::LlamaCppSettings,
+    lmstudio::LmStudioSettings, mistral::MistralSettings, ollama::OllamaSettings,
+    open_ai::OpenAiSettings, open_ai_compatible::OpenAiCompatibleSettings,
+    open_ai_responses::OpenAiResponsesSettings, open_router::OpenRouterSettings,
     vercel::VercelSettings, x_ai::XAiSettings,
 };
 
@@ -17,15 +19,18 @@ pub struct AllLanguageModelSettings {
     pub bedrock: AmazonBedrockSettings,
     pub deepseek: DeepSeekSettings,
     pub google: GoogleSettings,
+    pub llama_cpp: LlamaCppSettings,
     pub lmstudio: LmStudioSettings,
     pub mistral: MistralSettings,
     pub ollama: OllamaSettings,
     pub open_router: OpenRouterSettings,
     pub openai: OpenAiSettings,
     pub openai_compatible: HashMap<Arc<str>, OpenAiCompatibleSettings>,
+    pub openai_responses: OpenAiResponsesSettings,
     pub vercel: VercelSettings,
     pub x_ai: XAiSettings,
     pub zed_dot_dev: ZedDotDevSettings,
+    pub rate_limits: HashMap<LanguageModelProviderId, RateLimitPolicy>,
 }
 
 impl settings::Settings for AllLanguageModelSettings {
@@ -37,15 +42,18 @@ impl settings::Settings for AllLanguageModelSettings {
         let bedrock = language_models.bedrock.unwrap();
         let deepseek = language_models.deepseek.unwrap();
         let google = language_models.google.unwrap();
+        let llama_cpp = language_models.llama_cpp.unwrap();
         let lmstudio = language_models.lmstudio.unwrap();
         let mistral = language_models.mistral.unwrap();
         let ollama = language_models.ollama.unwrap();
         let open_router = language_models.open_router.unwrap();
         let openai = language_models.openai.unwrap();
         let openai_compatible = language_models.openai_compatible.unwrap();
+        let openai_responses = language_models.openai_responses.unwrap();
         let vercel = language_models.vercel.unwrap();
         let x_ai = language_models.x_ai.unwrap();
         let zed_dot_dev = language_models.zed_dot_dev.unwrap();
+        let rate_limits = language_models.rate_limits.unwrap_or_default();
         Self {
             anthropic: AnthropicSettings {
                 api_url: anthropic.api_url.unwrap(),
@@ -68,6 +76,10 @@ impl settings::Settings for AllLanguageModelSettings {
                 api_url: google.api_url.unwrap(),
                 available_models: google.available_models.unwrap_or_default(),
             },
+            llama_cpp: LlamaCppSettings {
+                api_url: llama_cpp.api_url.unwrap(),
+                available_models: llama_cpp.available_models.unwrap_or_default(),
+            },
             lmstudio: LmStudioSettings {
                 api_url: lmstudio.api_url.unwrap(),
                 available_models: lmstudio.available_models.unwrap_or_default(),
@@ -101,6 +113,10 @@ impl settings::Settings for AllLanguageModelSettings {
                     )
                 })
                 .collect(),
+            openai_responses: OpenAiResponsesSettings {
+                api_url: openai_responses.api_url.unwrap(),
+                available_models: openai_responses.available_models.unwrap_or_default(),
+            },
             vercel: VercelSettings {
                 api_url: vercel.api_url.unwrap(),
                 available_models: vercel.available_models.unwrap_or_default(),
@@ -112,6 +128,25 @@ impl settings::Settings for AllLanguageModelSettings {
             zed_dot_dev: ZedDotDevSettings {
                 available_models: zed_dot_dev.available_models.unwrap_or_default(),
             },
+            rate_limits: rate_limits
+                .into_iter()
+                .map(|(provider_id, rate_limit)| {
+                    let default = RateLimitPolicy::default();
+                    (
+                        LanguageModelProviderId::from(provider_id),
+                        RateLimitPolicy {
+                            requests_per_minute: rate_limit.requests_per_minute,
+                            tokens_per_minute: rate_limit.tokens_per_minute,
+                            initial_backoff: rate_limit
+                                .initial_backoff_ms
+                                .map_or(default.initial_backoff, Duration::from_millis),
+                            max_backoff: rate_limit
+                                .max_backoff_ms
+                                .map_or(default.max_backoff, Duration::from_millis),
+                        },
+                    )
+                })
+                .collect(),
         }
     }
 }
@@ -8,12 +8,12 @@ use std::{
 use client::proto;
 use collections::HashSet;
 use editor::{Editor, EditorEvent};
-use gpui::{Corner, Entity, Subscription, Task, WeakEntity, actions};
+use gpui::{Corner, DismissEvent, Entity, Subscription, Task, WeakEntity, actions};
 use language::{BinaryStatus, BufferId, ServerHealth};
 use lsp::{LanguageServerId, LanguageServerName, LanguageServerSelector};
 use project::{
-    LspStore, LspStoreEvent, Worktree, lsp_store::log_store::GlobalLogStore,
-    project_settings::ProjectSettings,
+    LanguageServerResources, LspStore, LspStoreEvent, Worktree,
+    lsp_store::log_store::GlobalLogStore, project_settings::ProjectSettings,
 };
 use settings::{Settings as _, SettingsStore};
 use ui::{
@@ -38,6 +38,7 @@ pub struct LspButton {
     popover_menu_handle: PopoverMenuHandle<ContextMenu>,
     lsp_menu: Option<Entity<ContextMenu>>,
     lsp_menu_refresh: Task<()>,
+    lsp_menu_dismissed: Subscription,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -141,6 +142,17 @@ impl LanguageServerState {
                     .collect::<HashMap<_, _>>()
             })
             .unwrap_or_default();
+        let server_resources = self
+            .lsp_store
+            .update(cx, |lsp_store, _| {
+                lsp_store
+                    .language_server_statuses()
+                    .filter_map(|(server_id, _)| {
+                        Some((server_id, lsp_store.language_server_resources(server_id)?))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
 
         let mut first_button_encountered = false;
         for item in &self.items {
@@ -281,6 +293,10 @@ impl LanguageServerState {
                 ))),
             };
 
+            let resources_label = server_resources
+                .get(&server_info.id)
+                .and_then(resources_label);
+
             let submenu_server_name = server_info.name.clone();
             let submenu_server_info = server_info.clone();
 
@@ -518,6 +534,15 @@ impl LanguageServerState {
                             }
                         });
 
+                        if let Some(resources_label) = resources_label.clone() {
+                            submenu = submenu.custom_row(move |_, _| {
+                                Label::new(resources_label.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .into_any_element()
+                            });
+                        }
+
                         submenu
                     }
                 },
@@ -527,6 +552,24 @@ impl LanguageServerState {
     }
 }
 
+/// Describes the resource usage of a server, e.g. "312 MB · 4% CPU · 85 ms latency".
+fn resources_label(resources: &LanguageServerResources) -> Option<SharedString> {
+    let mut parts = Vec::new();
+    if let Some(memory) = resources.memory {
+        parts.push(format!("{} MB", memory / 1024 / 1024));
+    }
+    if let Some(cpu_usage) = resources.cpu_usage {
+        parts.push(format!("{cpu_usage:.0}% CPU"));
+    }
+    if let Some(average_latency) = resources.requests.average_latency {
+        parts.push(format!("{} ms latency", average_latency.as_millis()));
+    }
+    if resources.requests.timed_out > 0 {
+        parts.push(format!("{} timed out", resources.requests.timed_out));
+    }
+    (!parts.is_empty()).then(|| parts.join(" · ").into())
+}
+
 impl LanguageServers {
     fn update_binary_status(
         &mut self,
@@ -707,6 +750,13 @@ impl LspButton {
             cx.subscribe_in(&lsp_store, window, |lsp_button, _, e, window, cx| {
                 lsp_button.on_lsp_store_event(e, window, cx)
             });
+        // Keep the resource usage of the servers up to date while the menu is open.
+        let lsp_store_observation =
+            cx.observe_in(&lsp_store, window, |lsp_button, _, window, cx| {
+                if lsp_button.popover_menu_handle.is_deployed() {
+                    lsp_button.refresh_lsp_menu(false, window, cx);
+                }
+            });
 
         let server_state = cx.new(|_| LanguageServerState {
             workspace: workspace.weak_handle(),
@@ -721,7 +771,12 @@ impl LspButton {
             popover_menu_handle,
             lsp_menu: None,
             lsp_menu_refresh: Task::ready(()),
-            _subscriptions: vec![settings_subscription, lsp_store_subscription],
+            lsp_menu_dismissed: Subscription::new(|| {}),
+            _subscriptions: vec![
+                settings_subscription,
+                lsp_store_subscription,
+                lsp_store_observation,
+            ],
         };
         if !lsp_button
             .server_state
@@ -1038,6 +1093,15 @@ impl LspButton {
                         let menu = ContextMenu::build(window, cx, |menu, _, cx| {
                             state.update(cx, |state, cx| state.fill_menu(menu, cx))
                         });
+                        let lsp_store = state.read(cx).lsp_store.clone();
+                        lsp_button.lsp_menu_dismissed =
+                            cx.subscribe(&menu, move |_, _, _: &DismissEvent, cx| {
+                                lsp_store
+                                    .update(cx, |lsp_store, _| {
+                                        lsp_store.set_language_server_resources_visible(false)
+                                    })
+                                    .ok();
+                            });
                         lsp_button.lsp_menu = Some(menu.clone());
                         lsp_button.popover_menu_handle.refresh_menu(
                             window,
@@ -1182,6 +1246,7 @@ impl Render for LspButton {
         };
 
         let lsp_button = cx.weak_entity();
+        let lsp_store = state.lsp_store.clone();
 
         div().child(
             PopoverMenu::new("lsp-tool")
@@ -1191,6 +1256,13 @@ impl Render for LspButton {
                         .ok()
                         .flatten()
                 })
+                .on_open(Rc::new(move |_, cx| {
+                    lsp_store
+                        .update(cx, |lsp_store, _| {
+                            lsp_store.set_language_server_resources_visible(true)
+                        })
+                        .ok();
+                }))
                 .anchor(Corner::BottomLeft)
                 .with_handle(self.popover_menu_handle.clone())
                 .trigger_with_tooltip(
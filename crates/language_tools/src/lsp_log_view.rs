@@ -1,6 +1,7 @@
-use collections::VecDeque;
+use collections::{HashSet, VecDeque};
 use copilot::Copilot;
 use editor::{Editor, EditorEvent, MultiBufferOffset, actions::MoveToEnd, scroll::Autoscroll};
+use fs::Fs as _;
 use gpui::{
     App, Context, Corner, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement,
     Render, Styled, Subscription, Task, WeakEntity, Window, actions, div,
@@ -8,8 +9,8 @@ use gpui::{
 use itertools::Itertools as _;
 use language::{LanguageServerId, language_settings::SoftWrap};
 use lsp::{
-    LanguageServer, LanguageServerName, LanguageServerSelector, MessageType, SetTraceParams,
-    TraceValue, notification::SetTrace,
+    LanguageServer, LanguageServerName, LanguageServerSelector, LspTranscript, MessageType,
+    SetTraceParams, TraceValue, TranscriptFile, notification::SetTrace,
 };
 use project::{
     LanguageServerStatus, Project,
@@ -17,12 +18,19 @@ use project::{
     search::SearchQuery,
 };
 use proto::toggle_lsp_logs::LogType;
-use std::{any::TypeId, borrow::Cow, sync::Arc};
+use std::{
+    any::TypeId,
+    borrow::Cow,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use ui::{Button, Checkbox, ContextMenu, Label, PopoverMenu, ToggleState, prelude::*};
 use util::ResultExt as _;
 use workspace::{
-    SplitDirection, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace, WorkspaceId,
+    OpenOptions, SplitDirection, Toast, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
+    Workspace, WorkspaceId,
     item::{Item, ItemHandle},
+    notifications::{NotificationId, NotifyTaskExt as _},
     searchable::{Direction, SearchEvent, SearchableItem, SearchableItemHandle},
 };
 
@@ -108,7 +116,11 @@ actions!(
     dev,
     [
         /// Opens the language server protocol logs viewer.
-        OpenLanguageServerLogs
+        OpenLanguageServerLogs,
+        /// Restarts the language servers of the active editor and records their JSON-RPC sessions.
+        RecordLanguageServerSessions,
+        /// Stops recording language server sessions and saves the transcripts to the logs directory.
+        StopRecordingLanguageServerSessions
     ]
 );
 
@@ -160,9 +172,9 @@ pub fn init(on_headless_host: bool, cx: &mut App) {
             store.add_project(workspace.project(), cx);
         });
 
-        let log_store = log_store.clone();
+        let open_log_store = log_store.clone();
         workspace.register_action(move |workspace, _: &OpenLanguageServerLogs, window, cx| {
-            let log_store = log_store.clone();
+            let log_store = open_log_store.clone();
             let project = workspace.project().clone();
             get_or_create_tool(
                 workspace,
@@ -172,10 +184,109 @@ pub fn init(on_headless_host: bool, cx: &mut App) {
                 move |window, cx| LspLogView::new(project, log_store, window, cx),
             );
         });
+
+        let record_log_store = log_store.clone();
+        workspace.register_action(move |workspace, _: &RecordLanguageServerSessions, _, cx| {
+            let buffers = workspace
+                .active_item_as::<Editor>(cx)
+                .map(|editor| editor.read(cx).buffer().read(cx).all_buffers())
+                .unwrap_or_default();
+            let lsp_store = workspace.project().read(cx).lsp_store();
+            let server_names = buffers
+                .iter()
+                .flat_map(|buffer| {
+                    buffer.update(cx, |buffer, cx| {
+                        lsp_store.update(cx, |lsp_store, cx| {
+                            lsp_store
+                                .running_language_servers_for_local_buffer(buffer, cx)
+                                .map(|(_, server)| server.name())
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                })
+                .collect::<HashSet<_>>();
+            if server_names.is_empty() {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<RecordLanguageServerSessions>(),
+                        "No language servers are running for the active editor",
+                    ),
+                    cx,
+                );
+                return;
+            }
+
+            record_log_store.update(cx, |log_store, _| {
+                log_store.start_recording_sessions(server_names.clone())
+            });
+            // Restart the servers, so that the transcripts start with their initialization.
+            workspace.project().update(cx, |project, cx| {
+                project.restart_language_servers_for_buffers(
+                    buffers.into_iter().collect(),
+                    server_names
+                        .into_iter()
+                        .map(LanguageServerSelector::Name)
+                        .collect(),
+                    cx,
+                );
+            });
+        });
+
+        let stop_log_store = log_store.clone();
+        workspace.register_action(
+            move |workspace, _: &StopRecordingLanguageServerSessions, window, cx| {
+                let transcripts =
+                    stop_log_store.update(cx, |log_store, _| log_store.stop_recording_sessions());
+                save_session_transcripts(workspace, transcripts, window, cx)
+                    .detach_and_notify_err(window, cx);
+            },
+        );
     })
     .detach();
 }
 
+fn save_session_transcripts(
+    workspace: &mut Workspace,
+    transcripts: Vec<(LanguageServerId, LspTranscript)>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<()>> {
+    let fs = workspace.project().read(cx).fs().clone();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let sessions_dir = paths::logs_dir().join("lsp_sessions");
+        fs.create_dir(&sessions_dir).await?;
+        let mut transcript_paths = Vec::with_capacity(transcripts.len());
+        for (server_id, mut transcript) in transcripts {
+            // Include the files that the server referred to, so that the session can be replayed without the workspace.
+            for path in transcript.unrecorded_file_paths() {
+                if let Some(text) = fs.load(&transcript.root_path.join(&path)).await.log_err() {
+                    transcript.files.push(TranscriptFile { path, text });
+                }
+            }
+            let path = sessions_dir.join(format!(
+                "{}-{server_id}-{timestamp}.json",
+                transcript.server_name
+            ));
+            fs.atomic_write(path.clone(), serde_json::to_string_pretty(&transcript)?)
+                .await?;
+            transcript_paths.push(path);
+        }
+
+        for path in transcript_paths {
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(path, OpenOptions::default(), window, cx)
+                })?
+                .await?;
+        }
+        Ok(())
+    })
+}
+
 impl LspLogView {
     pub fn new(
         project: Entity<Project>,
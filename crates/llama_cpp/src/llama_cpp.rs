@@ -0,0 +1,278 @@
+use anyhow::{Context as _, Result};
+use futures::AsyncReadExt;
+use http_client::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use serde::{Deserialize, Serialize};
+
+pub const LLAMA_CPP_API_URL: &str = "http://localhost:8080";
+
+/// The context length to assume when the server doesn't report one.
+const DEFAULT_CONTEXT_LENGTH: u64 = 4096;
+
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Model {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub max_tokens: u64,
+    pub supports_tool_calls: bool,
+    pub supports_parallel_tool_calls: bool,
+    pub supports_images: bool,
+}
+
+impl Model {
+    /// Describes a model served by `llama-server`, using what the server reports about itself.
+    pub fn from_server(entry: &ModelEntry, props: &ServerProps) -> Self {
+        Self {
+            name: entry.id.clone(),
+            display_name: None,
+            max_tokens: props
+                .context_length()
+                .or_else(|| entry.meta.as_ref().and_then(|meta| meta.n_ctx_train))
+                .unwrap_or(DEFAULT_CONTEXT_LENGTH),
+            supports_tool_calls: props.supports_tool_calls(),
+            supports_parallel_tool_calls: props.supports_parallel_tool_calls(),
+            supports_images: props.supports_images(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_ref().unwrap_or(&self.name)
+    }
+
+    pub fn max_token_count(&self) -> u64 {
+        self.max_tokens
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ListModelsResponse {
+    pub data: Vec<ModelEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ModelEntry {
+    pub id: String,
+    #[serde(default)]
+    pub meta: Option<ModelMeta>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ModelMeta {
+    /// The context length the model was trained with.
+    #[serde(default)]
+    pub n_ctx_train: Option<u64>,
+}
+
+/// The response of `llama-server`'s `/props` endpoint.
+///
+/// Most fields are optional because they were added over time, and older server builds omit them.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ServerProps {
+    #[serde(default)]
+    pub default_generation_settings: Option<GenerationSettings>,
+    #[serde(default)]
+    pub total_slots: Option<u64>,
+    #[serde(default)]
+    pub modalities: Option<Modalities>,
+    #[serde(default)]
+    pub chat_template_caps: Option<ChatTemplateCaps>,
+    #[serde(default)]
+    pub build_info: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct GenerationSettings {
+    /// The context length of a single slot, which bounds each request.
+    #[serde(default)]
+    pub n_ctx: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Modalities {
+    #[serde(default)]
+    pub vision: bool,
+}
+
+/// What the loaded chat template supports. Only reported when the server runs with `--jinja`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ChatTemplateCaps {
+    #[serde(default)]
+    pub supports_tools: bool,
+    #[serde(default)]
+    pub supports_tool_calls: bool,
+    #[serde(default)]
+    pub supports_parallel_tool_calls: bool,
+}
+
+impl ServerProps {
+    pub fn context_length(&self) -> Option<u64> {
+        self.default_generation_settings
+            .as_ref()
+            .and_then(|settings| settings.n_ctx)
+            .filter(|n_ctx| *n_ctx > 0)
+    }
+
+    pub fn supports_images(&self) -> bool {
+        self.modalities
+            .as_ref()
+            .is_some_and(|modalities| modalities.vision)
+    }
+
+    /// Tool calls are only parsed by `llama-server` when it renders prompts with Jinja templates,
+    /// in which case it reports the template's capabilities. Older builds that don't report them
+    /// are assumed to support tool calls, and fail with [`is_jinja_required_error`] otherwise.
+    pub fn supports_tool_calls(&self) -> bool {
+        self.chat_template_caps
+            .as_ref()
+            .is_none_or(|caps| caps.supports_tools && caps.supports_tool_calls)
+    }
+
+    pub fn supports_parallel_tool_calls(&self) -> bool {
+        self.chat_template_caps
+            .as_ref()
+            .is_some_and(|caps| caps.supports_parallel_tool_calls)
+    }
+}
+
+/// Returns whether an error response body means that the request used tools, but the server
+/// wasn't started with `--jinja`.
+pub fn is_jinja_required_error(body: &str) -> bool {
+    body.contains("--jinja")
+}
+
+pub async fn get_models(client: &dyn HttpClient, api_url: &str) -> Result<Vec<ModelEntry>> {
+    let body = get(client, &format!("{api_url}/v1/models")).await?;
+    let response: ListModelsResponse =
+        serde_json::from_str(&body).context("Unable to parse llama.cpp models response")?;
+    Ok(response.data)
+}
+
+pub async fn get_props(client: &dyn HttpClient, api_url: &str) -> Result<ServerProps> {
+    let body = get(client, &format!("{api_url}/props")).await?;
+    serde_json::from_str(&body).context("Unable to parse llama.cpp server properties")
+}
+
+async fn get(client: &dyn HttpClient, uri: &str) -> Result<String> {
+    let request = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Accept", "application/json")
+        .body(AsyncBody::default())?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to llama.cpp server: {} {}",
+        response.status(),
+        body,
+    );
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_client::{FakeHttpClient, Response};
+
+    #[test]
+    fn parse_props() {
+        let props: ServerProps = serde_json::from_value(serde_json::json!({
+            "default_generation_settings": {
+                "n_ctx": 8192,
+                "params": { "temperature": 0.8 }
+            },
+            "total_slots": 4,
+            "model_path": "/models/qwen2.5-coder-7b-instruct-q4_k_m.gguf",
+            "chat_template": "{{ messages }}",
+            "chat_template_caps": {
+                "supports_parallel_tool_calls": true,
+                "supports_system_role": true,
+                "supports_tool_calls": true,
+                "supports_tools": true
+            },
+            "modalities": { "vision": false, "audio": false },
+            "build_info": "b6000-0123456"
+        }))
+        .unwrap();
+        assert_eq!(props.context_length(), Some(8192));
+        assert!(props.supports_tool_calls());
+        assert!(props.supports_parallel_tool_calls());
+        assert!(!props.supports_images());
+
+        // Older builds report neither the template capabilities nor the modalities.
+        let props: ServerProps = serde_json::from_value(serde_json::json!({
+            "default_generation_settings": { "n_ctx": 0 },
+            "total_slots": 1
+        }))
+        .unwrap();
+        assert_eq!(props.context_length(), None);
+        assert!(props.supports_tool_calls());
+        assert!(!props.supports_parallel_tool_calls());
+    }
+
+    #[test]
+    fn discover_model_from_stub_server() {
+        let client = FakeHttpClient::create(|request| async move {
+            let body = match request.uri().path() {
+                "/v1/models" => serde_json::json!({
+                    "object": "list",
+                    "data": [{
+                        "id": "qwen2.5-coder-7b-instruct-q4_k_m.gguf",
+                        "object": "model",
+                        "owned_by": "llamacpp",
+                        "meta": { "n_ctx_train": 32768, "n_vocab": 152064 }
+                    }]
+                }),
+                "/props" => serde_json::json!({
+                    "default_generation_settings": { "n_ctx": 16384 },
+                    "chat_template_caps": {
+                        "supports_tools": true,
+                        "supports_tool_calls": false
+                    },
+                    "modalities": { "vision": true }
+                }),
+                _ => return Ok(Response::builder().status(404).body(AsyncBody::default())?),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(AsyncBody::from(body.to_string()))?)
+        });
+
+        let (models, props) = futures::executor::block_on(async {
+            (
+                get_models(client.as_ref(), LLAMA_CPP_API_URL)
+                    .await
+                    .unwrap(),
+                get_props(client.as_ref(), LLAMA_CPP_API_URL).await.unwrap(),
+            )
+        });
+        assert_eq!(
+            Model::from_server(&models[0], &props),
+            Model {
+                name: "qwen2.5-coder-7b-instruct-q4_k_m.gguf".into(),
+                display_name: None,
+                max_tokens: 16384,
+                supports_tool_calls: false,
+                supports_parallel_tool_calls: false,
+                supports_images: true,
+            }
+        );
+    }
+
+    #[test]
+    fn detect_missing_jinja_flag() {
+        assert!(is_jinja_required_error(
+            r#"{"error":{"code":500,"message":"tools param requires --jinja flag","type":"server_error"}}"#
+        ));
+        assert!(!is_jinja_required_error(
+            r#"{"error":{"code":400,"message":"the request exceeds the available context size"}}"#
+        ));
+    }
+}
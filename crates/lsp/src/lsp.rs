@@ -1,7 +1,9 @@
 mod input_handler;
+mod transcript;
 
 pub use lsp_types::request::*;
 pub use lsp_types::*;
+pub use transcript::*;
 
 use anyhow::{Context as _, Result, anyhow};
 use collections::{BTreeMap, HashMap};
@@ -48,6 +50,50 @@ const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 pub const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// The semantic token types that Zed can highlight. Servers may still send others.
+pub const SUPPORTED_SEMANTIC_TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::MACRO,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::REGEXP,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::DECORATOR,
+];
+
+/// The semantic token modifiers that Zed can highlight, including the non-standard `mutable` and
+/// `unsafe` modifiers that rust-analyzer uses.
+pub const SUPPORTED_SEMANTIC_TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEFINITION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::STATIC,
+    SemanticTokenModifier::DEPRECATED,
+    SemanticTokenModifier::ABSTRACT,
+    SemanticTokenModifier::ASYNC,
+    SemanticTokenModifier::MODIFICATION,
+    SemanticTokenModifier::DOCUMENTATION,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    SemanticTokenModifier::new("mutable"),
+    SemanticTokenModifier::new("unsafe"),
+];
+
 type NotificationHandler = Box<dyn Send + FnMut(Option<RequestId>, Value, &mut AsyncApp)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type IoHandler = Box<dyn Send + FnMut(IoKind, &str)>;
@@ -108,6 +154,38 @@ pub struct LanguageServer {
     server: Arc<Mutex<Option<Child>>>,
     workspace_folders: Option<Arc<Mutex<BTreeSet<Uri>>>>,
     root_uri: Uri,
+    request_stats: Arc<Mutex<RequestStats>>,
+}
+
+/// Statistics about the requests sent to a language server.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RequestStats {
+    /// The number of requests the server responded to.
+    pub responded: u64,
+    /// The number of requests that timed out.
+    pub timed_out: u64,
+    /// The number of requests that timed out since the last response.
+    pub consecutive_timeouts: u32,
+    /// The moving average of the time it took to respond to requests, weighted towards the recent ones.
+    pub average_latency: Option<Duration>,
+    pub max_latency: Option<Duration>,
+}
+
+impl RequestStats {
+    fn record_response(&mut self, latency: Duration) {
+        self.responded += 1;
+        self.consecutive_timeouts = 0;
+        self.average_latency = Some(match self.average_latency {
+            Some(average_latency) => (average_latency * 7 + latency) / 8,
+            None => latency,
+        });
+        self.max_latency = Some(self.max_latency.map_or(latency, |max| max.max(latency)));
+    }
+
+    fn record_timeout(&mut self) {
+        self.timed_out += 1;
+        self.consecutive_timeouts += 1;
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -520,6 +598,7 @@ impl LanguageServer {
             server: Arc::new(Mutex::new(server)),
             workspace_folders,
             root_uri,
+            request_stats: Arc::default(),
         }
     }
 
@@ -876,6 +955,30 @@ impl LanguageServer {
                     color_provider: Some(DocumentColorClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    folding_range: Some(FoldingRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                        line_folding_only: Some(true),
+                        ..FoldingRangeClientCapabilities::default()
+                    }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: None,
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: SUPPORTED_SEMANTIC_TOKEN_TYPES.to_vec(),
+                        token_modifiers: SUPPORTED_SEMANTIC_TOKEN_MODIFIERS.to_vec(),
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        ..SemanticTokensClientCapabilities::default()
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -954,6 +1057,7 @@ impl LanguageServer {
                 &outbound_tx,
                 &notification_serializers,
                 &executor,
+                &self.request_stats,
                 (),
             );
 
@@ -1200,6 +1304,16 @@ impl LanguageServer {
         &self.binary
     }
 
+    /// The id of the language server process, if it's still running.
+    pub fn process_id(&self) -> Option<u32> {
+        self.server.lock().as_ref().map(|server| server.id())
+    }
+
+    /// Latency and timeout statistics of the requests sent to the language server.
+    pub fn request_stats(&self) -> RequestStats {
+        *self.request_stats.lock()
+    }
+
     /// Sends a RPC request to the language server.
     ///
     /// [LSP Specification](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#requestMessage)
@@ -1216,6 +1330,7 @@ impl LanguageServer {
             &self.outbound_tx,
             &self.notification_tx,
             &self.executor,
+            &self.request_stats,
             params,
         )
     }
@@ -1238,6 +1353,7 @@ impl LanguageServer {
             &self.outbound_tx,
             &self.notification_tx,
             &self.executor,
+            &self.request_stats,
             timer,
             params,
         )
@@ -1249,6 +1365,7 @@ impl LanguageServer {
         outbound_tx: &channel::Sender<String>,
         notification_serializers: &channel::Sender<NotificationSerializer>,
         executor: &BackgroundExecutor,
+        request_stats: &Arc<Mutex<RequestStats>>,
         timer: U,
         params: T::Params,
     ) -> impl LspRequestFuture<T::Result> + use<T, U>
@@ -1300,6 +1417,7 @@ impl LanguageServer {
             .context("failed to write to language server's stdin");
 
         let notification_serializers = notification_serializers.downgrade();
+        let request_stats = request_stats.clone();
         let started = Instant::now();
         LspRequest::new(id, async move {
             if let Err(e) = handle_response {
@@ -1328,7 +1446,10 @@ impl LanguageServer {
                     log::trace!("Took {elapsed:?} to receive response to {method:?} id {id}");
                     cancel_on_drop.abort();
                     match response {
-                        Ok(response_result) => ConnectionResult::Result(response_result),
+                        Ok(response_result) => {
+                            request_stats.lock().record_response(elapsed);
+                            ConnectionResult::Result(response_result)
+                        }
                         Err(Canceled) => {
                             log::error!("Server reset connection for a request {method:?} id {id}");
                             ConnectionResult::ConnectionReset
@@ -1338,6 +1459,7 @@ impl LanguageServer {
 
                 message = timer.fuse() => {
                     log::error!("Cancelled LSP request task for {method:?} id {id} {message}");
+                    request_stats.lock().record_timeout();
                     ConnectionResult::Timeout
                 }
             }
@@ -1350,6 +1472,7 @@ impl LanguageServer {
         outbound_tx: &channel::Sender<String>,
         notification_serializers: &channel::Sender<NotificationSerializer>,
         executor: &BackgroundExecutor,
+        request_stats: &Arc<Mutex<RequestStats>>,
         params: T::Params,
     ) -> impl LspRequestFuture<T::Result> + use<T>
     where
@@ -1362,6 +1485,7 @@ impl LanguageServer {
             outbound_tx,
             notification_serializers,
             executor,
+            request_stats,
             Self::default_request_timer(executor.clone()),
             params,
         )
@@ -0,0 +1,280 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    str::FromStr as _,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{InitializeResult, ServerCapabilities, Uri};
+
+/// A recorded JSON-RPC session with a language server, along with the workspace files it started with.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LspTranscript {
+    pub server_name: String,
+    /// The workspace root the paths of [`Self::files`] are relative to.
+    pub root_path: PathBuf,
+    /// The contents of the files open in the workspace when the session started, and of the files the messages refer to.
+    pub files: Vec<TranscriptFile>,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranscriptFile {
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// The direction of a message, from the point of view of the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranscriptMessage {
+    /// Milliseconds elapsed since the start of the session.
+    pub elapsed_ms: u64,
+    pub direction: MessageDirection,
+    pub message: Value,
+}
+
+impl LspTranscript {
+    pub fn new(server_name: String, root_path: PathBuf, files: Vec<TranscriptFile>) -> Self {
+        Self {
+            server_name,
+            root_path,
+            files,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Appends a raw JSON-RPC message, as passed to the handlers of [`crate::LanguageServer::on_io`].
+    pub fn push_message(&mut self, elapsed: Duration, direction: MessageDirection, message: &str) {
+        match serde_json::from_str(message) {
+            Ok(message) => self.messages.push(TranscriptMessage {
+                elapsed_ms: elapsed.as_millis() as u64,
+                direction,
+                message,
+            }),
+            Err(error) => log::warn!("skipping invalid JSON-RPC message in transcript: {error}"),
+        }
+    }
+
+    /// Returns the capabilities the server reported in its recorded `initialize` response.
+    pub fn server_capabilities(&self) -> Option<ServerCapabilities> {
+        let initialize_id = self.messages.iter().find_map(|message| {
+            (message.direction == MessageDirection::Sent
+                && message.message.get("method")?.as_str()? == "initialize")
+                .then(|| message.message.get("id").cloned())
+                .flatten()
+        })?;
+        let result = self.messages.iter().find_map(|message| {
+            (message.direction == MessageDirection::Received
+                && message.message.get("method").is_none()
+                && message.message.get("id") == Some(&initialize_id))
+            .then(|| message.message.get("result").cloned())
+            .flatten()
+        })?;
+        let result = serde_json::from_value::<InitializeResult>(result).ok()?;
+        Some(result.capabilities)
+    }
+
+    /// Returns the paths of the workspace files that the messages refer to, but whose contents aren't part of the
+    /// transcript, such as files that were not open but contain definitions. The paths are relative to [`Self::root_path`].
+    pub fn unrecorded_file_paths(&self) -> Vec<PathBuf> {
+        let mut paths = BTreeSet::new();
+        for message in &self.messages {
+            collect_file_paths(&message.message, &self.root_path, &mut paths);
+        }
+        paths
+            .into_iter()
+            .filter(|path| self.files.iter().all(|file| &file.path != path))
+            .collect()
+    }
+}
+
+fn collect_file_paths(value: &Value, root_path: &Path, paths: &mut BTreeSet<PathBuf>) {
+    match value {
+        Value::String(string) if string.starts_with("file://") => {
+            if let Ok(uri) = Uri::from_str(string)
+                && let Ok(path) = uri.to_file_path()
+                && let Ok(relative_path) = path.strip_prefix(root_path)
+                && relative_path != Path::new("")
+            {
+                paths.insert(relative_path.to_path_buf());
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_file_paths(value, root_path, paths);
+            }
+        }
+        Value::Object(object) => {
+            for value in object.values() {
+                collect_file_paths(value, root_path, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+mod replay {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use anyhow::anyhow;
+    use collections::HashMap;
+    use parking_lot::Mutex;
+    use serde_json::Value;
+
+    use super::{LspTranscript, MessageDirection};
+    use crate::FakeLanguageServer;
+
+    /// The recorded server messages to send back when the client sends messages, keyed by the method of the
+    /// client message and how many times it was sent before.
+    #[derive(Default)]
+    struct ReplayState {
+        client_messages_seen: HashMap<String, usize>,
+        server_messages: HashMap<(String, usize), Vec<String>>,
+        responses: HashMap<String, BTreeMap<usize, Result<Value, String>>>,
+    }
+
+    impl ReplayState {
+        fn new(transcript: &LspTranscript) -> (Self, HashMap<String, bool>) {
+            let mut state = Self::default();
+            let mut client_methods = HashMap::default();
+            let mut requests = HashMap::<String, (String, usize)>::default();
+            let mut seen = HashMap::<String, usize>::default();
+            let mut previous_client_message = (String::new(), 0);
+            for message in &transcript.messages {
+                let method = message.message.get("method").and_then(Value::as_str);
+                let id = message.message.get("id").map(Value::to_string);
+                match (message.direction, method) {
+                    (MessageDirection::Sent, Some(method)) => {
+                        let occurrence = seen.entry(method.to_string()).or_default();
+                        previous_client_message = (method.to_string(), *occurrence);
+                        *occurrence += 1;
+                        client_methods.insert(method.to_string(), id.is_some());
+                        if let Some(id) = id {
+                            requests.insert(id, previous_client_message.clone());
+                        }
+                    }
+                    // Responses of the client to the requests of the server are not replayed.
+                    (MessageDirection::Sent, None) => {}
+                    (MessageDirection::Received, Some(_)) => {
+                        state
+                            .server_messages
+                            .entry(previous_client_message.clone())
+                            .or_default()
+                            .push(message.message.to_string());
+                    }
+                    (MessageDirection::Received, None) => {
+                        let Some((method, occurrence)) = id.and_then(|id| requests.get(&id)) else {
+                            continue;
+                        };
+                        let response = match message.message.get("error") {
+                            Some(error) => Err(error
+                                .get("message")
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string()),
+                            None => Ok(message
+                                .message
+                                .get("result")
+                                .cloned()
+                                .unwrap_or(Value::Null)),
+                        };
+                        state
+                            .responses
+                            .entry(method.clone())
+                            .or_default()
+                            .insert(*occurrence, response);
+                    }
+                }
+            }
+            (state, client_methods)
+        }
+
+        /// Returns the server messages that followed the client message, and the response to it if it's a request.
+        fn on_client_message(
+            &mut self,
+            method: &str,
+        ) -> (Vec<String>, Option<Result<Value, String>>) {
+            let occurrence = self
+                .client_messages_seen
+                .entry(method.to_string())
+                .or_default();
+            let key = (method.to_string(), *occurrence);
+            *occurrence += 1;
+            let server_messages = self.server_messages.remove(&key).unwrap_or_default();
+            let response = self.responses.get(method).and_then(|responses| {
+                responses
+                    .range(..=key.1)
+                    .next_back()
+                    .map(|(_, response)| response.clone())
+            });
+            (server_messages, response)
+        }
+    }
+
+    impl FakeLanguageServer {
+        /// Makes the server answer the client with the messages of a recorded session.
+        ///
+        /// Replaying is driven by the messages of the client rather than the recorded timings, so that it is deterministic:
+        /// every request gets the response recorded for the same occurrence of its method, or the latest one if the client
+        /// sends it more often than it was recorded, and the notifications and requests the server sent after a client
+        /// message are sent again after that message.
+        /// The handlers registered for the recorded methods replace the existing ones.
+        pub fn replay(&self, transcript: &LspTranscript) {
+            let (state, client_methods) = ReplayState::new(transcript);
+            let state = Arc::new(Mutex::new(state));
+            let outbound_tx = self.server.outbound_tx.clone();
+
+            // Messages recorded before the first client message.
+            let (server_messages, _) = state.lock().on_client_message("");
+            for message in server_messages {
+                outbound_tx.try_send(message).ok();
+            }
+
+            for (method, is_request) in client_methods {
+                // Handlers are keyed by static method names, leaking them is fine for tests.
+                let method: &'static str = Box::leak(method.into_boxed_str());
+                self.server.notification_handlers.lock().remove(method);
+                let state = state.clone();
+                let outbound_tx = outbound_tx.clone();
+                if is_request {
+                    self.server
+                        .on_custom_request(method, move |_: Value, _| {
+                            let (server_messages, response) =
+                                state.lock().on_client_message(method);
+                            for message in server_messages {
+                                outbound_tx.try_send(message).ok();
+                            }
+                            async move {
+                                match response {
+                                    Some(Ok(result)) => Ok(result),
+                                    Some(Err(message)) => Err(anyhow!(message)),
+                                    None => Err(anyhow!("no response to {method} was recorded")),
+                                }
+                            }
+                        })
+                        .detach();
+                } else {
+                    self.server
+                        .on_custom_notification(method, move |_: Value, _| {
+                            let (server_messages, _) = state.lock().on_client_message(method);
+                            for message in server_messages {
+                                outbound_tx.try_send(message).ok();
+                            }
+                        })
+                        .detach();
+                }
+            }
+        }
+    }
+}
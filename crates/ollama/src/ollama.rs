@@ -191,6 +191,17 @@ pub struct ModelDetails {
     pub quantization_level: String,
 }
 
+#[derive(Serialize, Debug)]
+pub struct EmbedRequest<'a> {
+    pub model: &'a str,
+    pub input: Vec<&'a str>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmbedResponse {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
 #[derive(Debug)]
 pub struct ModelShow {
     pub capabilities: Vec<String>,
@@ -378,6 +389,38 @@ pub async fn show_model(
     Ok(details)
 }
 
+/// Compute embeddings for each of the inputs, in order.
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    request: EmbedRequest<'_>,
+) -> Result<Vec<Vec<f32>>> {
+    let uri = format!("{api_url}/api/embed");
+    let request = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .when_some(api_key, |builder, api_key| {
+            builder.header("Authorization", format!("Bearer {api_key}"))
+        })
+        .body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to Ollama API: {} {}",
+        response.status(),
+        body,
+    );
+    let response: EmbedResponse =
+        serde_json::from_str(&body).context("Unable to parse Ollama embed response")?;
+    Ok(response.embeddings)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -592,6 +635,21 @@ mod tests {
         assert_eq!(result.context_length, Some(131072));
     }
 
+    #[test]
+    fn parse_embed_response() {
+        let response = serde_json::json!({
+            "model": "nomic-embed-text",
+            "embeddings": [[0.5, -0.25, 0.125], [-0.75, 0.0625, 0.375]],
+            "total_duration": 14143917,
+            "load_duration": 1019500,
+            "prompt_eval_count": 8
+        });
+
+        let response: EmbedResponse = serde_json::from_value(response).unwrap();
+        assert_eq!(response.embeddings.len(), 2);
+        assert_eq!(response.embeddings[1], vec![-0.75, 0.0625, 0.375]);
+    }
+
     #[test]
     fn serialize_chat_request_with_images() {
         let base64_image = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
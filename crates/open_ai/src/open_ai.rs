@@ -587,6 +587,78 @@ pub async fn stream_completion(
     }
 }
 
+#[derive(Deserialize)]
+pub struct ListModelsResponse {
+    pub data: Vec<ListedModel>,
+}
+
+/// A model returned by the `/models` endpoint.
+///
+/// OpenAI itself only reports the model's id, but many self-hosted gateways also report the
+/// context length, each under its own field name.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ListedModel {
+    pub id: String,
+    #[serde(default)]
+    pub context_length: Option<u64>,
+    #[serde(default)]
+    pub context_window: Option<u64>,
+    #[serde(default)]
+    pub max_model_len: Option<u64>,
+    #[serde(default)]
+    pub max_context_length: Option<u64>,
+}
+
+impl ListedModel {
+    pub fn context_length(&self) -> Option<u64> {
+        self.context_length
+            .or(self.context_window)
+            .or(self.max_model_len)
+            .or(self.max_context_length)
+    }
+}
+
+pub async fn list_models(
+    client: &dyn HttpClient,
+    provider_name: &str,
+    api_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<ListedModel>, RequestError> {
+    let uri = format!("{api_url}/models");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Accept", "application/json");
+    if let Some(api_key) = api_key {
+        request_builder =
+            request_builder.header("Authorization", format!("Bearer {}", api_key.trim()));
+    }
+    let request = request_builder
+        .body(AsyncBody::default())
+        .map_err(|e| RequestError::Other(e.into()))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response
+        .body_mut()
+        .read_to_string(&mut body)
+        .await
+        .map_err(|e| RequestError::Other(e.into()))?;
+
+    if response.status().is_success() {
+        let response: ListModelsResponse = serde_json::from_str(&body)
+            .with_context(|| format!("failed to parse {provider_name} models response"))?;
+        Ok(response.data)
+    } else {
+        Err(RequestError::HttpResponseError {
+            provider: provider_name.to_owned(),
+            status_code: response.status(),
+            body,
+            headers: response.headers().clone(),
+        })
+    }
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
     #[serde(rename = "text-embedding-3-small")]
@@ -595,9 +667,18 @@ pub enum OpenAiEmbeddingModel {
     TextEmbedding3Large,
 }
 
+impl OpenAiEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TextEmbedding3Small => "text-embedding-3-small",
+            Self::TextEmbedding3Large => "text-embedding-3-large",
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct OpenAiEmbeddingRequest<'a> {
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     input: Vec<&'a str>,
 }
 
@@ -615,7 +696,7 @@ pub fn embed<'a>(
     client: &dyn HttpClient,
     api_url: &str,
     api_key: &str,
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     texts: impl IntoIterator<Item = &'a str>,
 ) -> impl 'static + Future<Output = Result<OpenAiEmbeddingResponse>> {
     let uri = format!("{api_url}/embeddings");
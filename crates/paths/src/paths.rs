@@ -368,6 +368,14 @@ pub fn languages_dir() -> &'static PathBuf {
     LANGUAGES_DIR.get_or_init(|| data_dir().join("languages"))
 }
 
+/// Returns the path to the shell integration directory.
+///
+/// This is where the scripts that integrate shells with the terminal are written to.
+pub fn shell_integration_dir() -> &'static PathBuf {
+    static SHELL_INTEGRATION_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SHELL_INTEGRATION_DIR.get_or_init(|| data_dir().join("shell_integration"))
+}
+
 /// Returns the path to the debug adapters directory
 ///
 /// This is where debug adapters are downloaded to for DAPs that are built-in to Zed.
@@ -1792,6 +1792,35 @@ impl LocalDapCommand for SetDataBreakpointsCommand {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct SetInstructionBreakpointsCommand {
+    pub breakpoints: Vec<dap::InstructionBreakpoint>,
+}
+
+impl LocalDapCommand for SetInstructionBreakpointsCommand {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetInstructionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetInstructionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub(super) enum SetExceptionBreakpoints {
     Plain {
@@ -1973,3 +2002,40 @@ impl LocalDapCommand for dap::WriteMemoryArguments {
         Ok(message)
     }
 }
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DisassembleCommand {
+    pub memory_reference: String,
+    /// Offset, in instructions, from the memory reference to the first instruction to disassemble. Can be negative.
+    pub instruction_offset: i64,
+    pub instruction_count: u64,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+    const CACHEABLE: bool = true;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: None,
+            instruction_offset: Some(self.instruction_offset),
+            instruction_count: self.instruction_count,
+            resolve_symbols: Some(true),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
@@ -266,6 +266,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            is_background: false,
+            ready_pattern: None,
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
@@ -293,6 +298,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            is_background: false,
+            ready_pattern: None,
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
@@ -431,6 +441,11 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            is_background: false,
+            ready_pattern: None,
+            problem_matchers: Vec::new(),
         };
 
         let scenario = locator
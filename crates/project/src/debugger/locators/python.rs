@@ -119,6 +119,11 @@ mod test {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            is_background: false,
+            ready_pattern: None,
+            problem_matchers: Vec::new(),
         };
 
         let expected_scenario = DebugScenario {
@@ -11,7 +11,9 @@ use super::dap_command::{
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
-use crate::debugger::dap_command::{DataBreakpointContext, ReadMemory};
+use crate::debugger::dap_command::{
+    DataBreakpointContext, DisassembleCommand, ReadMemory, SetInstructionBreakpointsCommand,
+};
 use crate::debugger::memory::{self, Memory, MemoryIterator, MemoryPageBuilder, PageAddress};
 use anyhow::{Context as _, Result, anyhow, bail};
 use base64::Engine;
@@ -152,6 +154,11 @@ pub struct DataBreakpointState {
     pub context: Arc<DataBreakpointContext>,
 }
 
+pub struct InstructionBreakpointState {
+    pub dap: dap::InstructionBreakpoint,
+    pub is_enabled: bool,
+}
+
 pub enum SessionState {
     /// Represents a session that is building/initializing
     /// even if a session doesn't have a pre build task this state
@@ -683,6 +690,7 @@ pub struct SessionSnapshot {
     locations: HashMap<u64, dap::LocationsResponse>,
     modules: Vec<dap::Module>,
     loaded_sources: Vec<dap::Source>,
+    disassembly: HashMap<DisassembleCommand, Arc<[dap::DisassembledInstruction]>>,
 }
 
 type IsEnabled = bool;
@@ -710,6 +718,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, InstructionBreakpointState>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -807,6 +816,7 @@ pub enum SessionEvent {
         sender: mpsc::Sender<Result<u32>>,
     },
     DataBreakpointInfo,
+    Disassembly,
     ConsoleOutput,
     HistoricSnapshotSelected,
 }
@@ -884,6 +894,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1772,6 +1783,7 @@ impl Session {
         self.invalidate_command_type::<ThreadsCommand>();
         self.invalidate_command_type::<DataBreakpointInfoCommand>();
         self.invalidate_command_type::<ReadMemory>();
+        self.invalidate_command_type::<DisassembleCommand>();
         let executor = self.as_running().map(|running| running.executor.clone());
         if let Some(executor) = executor {
             self.memory.clear(&executor);
@@ -1981,6 +1993,40 @@ impl Session {
         );
     }
 
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions away from the memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<Arc<[dap::DisassembledInstruction]>> {
+        let command = DisassembleCommand {
+            memory_reference,
+            instruction_offset,
+            instruction_count,
+        };
+        self.fetch(
+            command.clone(),
+            {
+                let command = command.clone();
+                move |this, result, cx| {
+                    let Some(result) = result.log_err() else {
+                        return;
+                    };
+                    this.active_snapshot
+                        .disassembly
+                        .insert(command, result.into());
+                    cx.emit(SessionEvent::Disassembly);
+                    cx.notify();
+                }
+            },
+            cx,
+        );
+
+        self.session_state().disassembly.get(&command).cloned()
+    }
+
     pub fn ignore_breakpoints(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2087,6 +2133,51 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &InstructionBreakpointState> {
+        self.instruction_breakpoints.values()
+    }
+
+    /// Sets a breakpoint on the instruction at the reference, or removes the one already set there.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: &str,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.to_owned(),
+                InstructionBreakpointState {
+                    dap: dap::InstructionBreakpoint {
+                        instruction_reference: instruction_reference.to_owned(),
+                        offset: None,
+                        condition: None,
+                        hit_condition: None,
+                        mode: None,
+                    },
+                    is_enabled: true,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self
+                .instruction_breakpoints
+                .values()
+                .filter_map(|state| state.is_enabled.then(|| state.dap.clone()))
+                .collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -27,11 +27,15 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
     blame::Blame,
+    commit_checks::{CommitCheckFinding, CommitCheckKind, CommitChecks, StagedFile},
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus, Worktree as GitWorktree,
+        BisectCommand, BisectStatus, Branch, CommitDetails, CommitDiff, CommitFile,
+        CommitOperation, CommitOptions, CommitSignature, CommitSummary, ConflictTexts, DiffType,
+        FetchOptions, GitRepository, GitRepositoryCheckpoint, LogEntry, LogFilter, PushOptions,
+        RebaseAction, RebaseCommand, RebaseProgress, RebaseTodoEntry, ReleaseCommits, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, SignatureStatus, Submodule, SubmoduleCommand,
+        SubmoduleStatus, Tag, UpstreamTrackingStatus, Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -52,7 +56,11 @@ use pending_op::{PendingOp, PendingOpId, PendingOps, PendingOpsSummary};
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, commit_check_finding, git_bisect, git_bisect_response, git_commit_operation,
+        git_commit_signature, git_rebase_command, git_rebase_todo_entry, git_reset, git_submodule,
+        git_submodule_command, split_repository_update,
+    },
 };
 use serde::Deserialize;
 use settings::WorktreeId;
@@ -184,6 +192,22 @@ pub struct GitStoreCheckpoint {
     checkpoints_by_work_dir_abs_path: HashMap<Arc<Path>, GitRepositoryCheckpoint>,
 }
 
+/// A submodule of a repository, along with the repository it's checked out as once it's initialized.
+#[derive(Clone, Debug)]
+pub struct SubmoduleEntry {
+    pub submodule: Submodule,
+    pub repository: Option<Entity<Repository>>,
+}
+
+impl SubmoduleEntry {
+    /// Whether the submodule has changes that aren't committed in it.
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        self.repository
+            .as_ref()
+            .is_some_and(|repository| repository.read(cx).status_summary().count > 0)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StatusEntry {
     pub repo_path: RepoPath,
@@ -265,6 +289,8 @@ pub struct RepositorySnapshot {
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    pub rebase_progress: Option<RebaseProgress>,
+    pub submodules: Vec<Submodule>,
 }
 
 type JobId = u64;
@@ -366,6 +392,8 @@ pub enum RepositoryEvent {
     MergeHeadsChanged,
     BranchChanged,
     StashEntriesChanged,
+    RebaseProgressChanged,
+    SubmodulesChanged,
     PendingOpsChanged { pending_ops: SumTree<PendingOps> },
 }
 
@@ -479,6 +507,8 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
         client.add_entity_request_handler(Self::handle_fetch);
+        client.add_entity_request_handler(Self::handle_submodule_command);
+        client.add_entity_request_handler(Self::handle_check_staged_changes);
         client.add_entity_request_handler(Self::handle_stage);
         client.add_entity_request_handler(Self::handle_unstage);
         client.add_entity_request_handler(Self::handle_stash);
@@ -488,7 +518,20 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_interactive_rebase);
+        client.add_entity_request_handler(Self::handle_rebase_command);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_commit_operation);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_release_commits);
+        client.add_entity_request_handler(Self::handle_load_conflict_texts);
+        client.add_entity_request_handler(Self::handle_get_config);
+        client.add_entity_request_handler(Self::handle_bisect);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_get_commit_signature);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_file_history);
         client.add_entity_request_handler(Self::handle_checkout_files);
@@ -1084,6 +1127,19 @@ impl GitStore {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    pub fn log_paginated(
+        &self,
+        repo: &Entity<Repository>,
+        filter: LogFilter,
+        skip: usize,
+        limit: Option<usize>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<LogEntry>>> {
+        let rx = repo.update(cx, |repo, _| repo.log_paginated(filter, skip, limit));
+
+        cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Entity<Buffer>,
@@ -1607,6 +1663,53 @@ impl GitStore {
         &self.repositories
     }
 
+    /// Returns the submodules of the repository, with the repositories they're checked out as.
+    pub fn submodules(&self, repository: &Entity<Repository>, cx: &App) -> Vec<SubmoduleEntry> {
+        let superproject = repository.read(cx);
+        superproject
+            .submodules
+            .iter()
+            .map(|submodule| {
+                let abs_path = superproject
+                    .work_directory_abs_path
+                    .join(submodule.path.as_std_path());
+                SubmoduleEntry {
+                    submodule: submodule.clone(),
+                    repository: self.repository_for_work_directory(&abs_path, cx),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the submodule whose working directory is at the given path, if there's one.
+    pub fn submodule_for_abs_path(&self, abs_path: &Path, cx: &App) -> Option<SubmoduleEntry> {
+        self.repositories.values().find_map(|superproject| {
+            let superproject = superproject.read(cx);
+            let path = abs_path
+                .strip_prefix(&superproject.work_directory_abs_path)
+                .ok()?;
+            let submodule = superproject
+                .submodules
+                .iter()
+                .find(|submodule| submodule.path.as_std_path() == path)?;
+            Some(SubmoduleEntry {
+                submodule: submodule.clone(),
+                repository: self.repository_for_work_directory(abs_path, cx),
+            })
+        })
+    }
+
+    fn repository_for_work_directory(
+        &self,
+        abs_path: &Path,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        self.repositories
+            .values()
+            .find(|repository| *repository.read(cx).work_directory_abs_path == *abs_path)
+            .cloned()
+    }
+
     pub fn status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
         let (repo, path) = self.repository_and_path_for_buffer_id(buffer_id, cx)?;
         let status = repo.read(cx).snapshot.status_for_path(&path)?;
@@ -1842,6 +1945,69 @@ impl GitStore {
         })
     }
 
+    async fn handle_submodule_command(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSubmoduleCommand>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let command = match envelope.payload.command() {
+            git_submodule_command::Command::Init => SubmoduleCommand::Init,
+            git_submodule_command::Command::Update => SubmoduleCommand::Update,
+            git_submodule_command::Command::Sync => SubmoduleCommand::Sync,
+        };
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_proto(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.submodule_command(command, paths, askpass, cx)
+            })?
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_check_staged_changes(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckStagedChanges>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCheckStagedChangesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let checks = CommitChecks::new(
+            &envelope.payload.secret_patterns,
+            &envelope.payload.forbidden_paths,
+            envelope.payload.max_binary_size,
+            envelope.payload.conflict_markers,
+        )?;
+
+        let findings = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_staged_changes(checks)
+            })?
+            .await??;
+        Ok(proto::GitCheckStagedChangesResponse {
+            findings: findings.iter().map(commit_check_finding_to_proto).collect(),
+        })
+    }
+
     async fn handle_push(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Push>,
@@ -2115,6 +2281,7 @@ impl GitStore {
                     CommitOptions {
                         amend: options.amend,
                         signoff: options.signoff,
+                        sign: options.sign,
                     },
                     askpass,
                     cx,
@@ -2352,12 +2519,24 @@ impl GitStore {
                 repository_handle.show(envelope.payload.commit)
             })?
             .await??;
-        Ok(proto::GitCommitDetails {
-            sha: commit.sha.into(),
-            message: commit.message.into(),
-            commit_timestamp: commit.commit_timestamp,
-            author_email: commit.author_email.into(),
-            author_name: commit.author_name.into(),
+        Ok(commit_details_to_proto(&commit))
+    }
+
+    async fn handle_get_commit_signature(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetCommitSignature>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitGetCommitSignatureResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let signature = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_signature(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::GitGetCommitSignatureResponse {
+            signature: signature.as_ref().map(commit_signature_to_proto),
         })
     }
 
@@ -2371,7 +2550,10 @@ impl GitStore {
 
         let commit_diff = repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.load_commit_diff(envelope.payload.commit)
+                match envelope.payload.base {
+                    Some(base) => repository_handle.compare_commits(base, envelope.payload.commit),
+                    None => repository_handle.load_commit_diff(envelope.payload.commit),
+                }
             })?
             .await??;
         Ok(proto::LoadCommitDiffResponse {
@@ -2442,6 +2624,270 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.base)
+            })?
+            .await??;
+
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_interactive_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitInteractiveRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .entries
+            .into_iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.interactive_rebase(envelope.payload.base, todo)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_command(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseCommand>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let command = match envelope.payload.command() {
+            git_rebase_command::Command::Continue => RebaseCommand::Continue,
+            git_rebase_command::Command::Skip => RebaseCommand::Skip,
+            git_rebase_command::Command::Abort => RebaseCommand::Abort,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_command(command)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let filter = proto_to_log_filter(envelope.payload.filter.unwrap_or_default())?;
+        let skip = envelope.payload.skip as usize;
+        let limit = envelope.payload.limit.map(|limit| limit as usize);
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log_paginated(filter, skip, limit)
+            })?
+            .await??;
+        Ok(proto::GitLogResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::GitLogEntry {
+                    sha: entry.sha.to_string(),
+                    parents: entry.parents.iter().map(ToString::to_string).collect(),
+                    refs: entry.refs.iter().map(ToString::to_string).collect(),
+                    subject: entry.subject.to_string(),
+                    commit_timestamp: entry.commit_timestamp,
+                    author_name: entry.author_name.to_string(),
+                    author_email: entry.author_email.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_commit_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCommitOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let operation = match envelope.payload.operation() {
+            git_commit_operation::Operation::Checkout => CommitOperation::Checkout,
+            git_commit_operation::Operation::CherryPick => CommitOperation::CherryPick,
+            git_commit_operation::Operation::Revert => CommitOperation::Revert,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_operation(operation, envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.commit,
+                    envelope.payload.message,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitGetTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
+        Ok(proto::GitGetTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_release_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitReleaseCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitReleaseCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let release = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.release_commits(envelope.payload.head)
+            })?
+            .await??;
+        Ok(proto::GitReleaseCommitsResponse {
+            previous_tag: release.previous_tag.map(|tag| tag.to_string()),
+            commits: release
+                .commits
+                .iter()
+                .map(|commit| proto::CommitSummary {
+                    sha: commit.sha.to_string(),
+                    subject: commit.subject.to_string(),
+                    commit_timestamp: commit.commit_timestamp,
+                    author_name: commit.author_name.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_load_conflict_texts(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadConflictTexts>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadConflictTextsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let repo_path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let texts = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_texts(repo_path)
+            })?
+            .await??;
+        Ok(proto::GitLoadConflictTextsResponse {
+            base: texts.base,
+            ours: texts.ours,
+            theirs: texts.theirs,
+        })
+    }
+
+    async fn handle_get_config(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetConfig>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitGetConfigResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let value = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.config_value(envelope.payload.key)
+            })?
+            .await??;
+        Ok(proto::GitGetConfigResponse { value })
+    }
+
+    async fn handle_bisect(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisect>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let command = match envelope.payload.command() {
+            git_bisect::Command::Start => BisectCommand::Start {
+                bad: envelope
+                    .payload
+                    .bad
+                    .context("missing bad revision for bisect")?,
+                good: envelope.payload.good,
+            },
+            git_bisect::Command::Good => BisectCommand::Good,
+            git_bisect::Command::Bad => BisectCommand::Bad,
+            git_bisect::Command::Skip => BisectCommand::Skip,
+            git_bisect::Command::Reset => BisectCommand::Reset,
+        };
+
+        let status = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect(command)
+            })?
+            .await??;
+        Ok(bisect_status_to_proto(&status))
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -3272,6 +3718,8 @@ impl RepositorySnapshot {
             remote_origin_url: None,
             remote_upstream_url: None,
             stash_entries: Default::default(),
+            rebase_progress: None,
+            submodules: Vec::new(),
             path_style,
         }
     }
@@ -3307,6 +3755,8 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            rebase_progress: self.rebase_progress.as_ref().map(rebase_progress_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3378,6 +3828,8 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            rebase_progress: self.rebase_progress.as_ref().map(rebase_progress_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3450,11 +3902,114 @@ pub fn stash_to_proto(entry: &StashEntry) -> proto::StashEntry {
     }
 }
 
-pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
-    Ok(StashEntry {
-        oid: Oid::from_bytes(&entry.oid)?,
-        message: entry.message.clone(),
-        index: entry.index as usize,
+fn log_filter_to_proto(filter: LogFilter) -> proto::GitLogFilter {
+    proto::GitLogFilter {
+        path: filter.path.map(|path| path.to_proto()),
+        author: filter.author,
+        message: filter.message,
+        since: filter.since,
+        until: filter.until,
+    }
+}
+
+fn proto_to_log_filter(filter: proto::GitLogFilter) -> Result<LogFilter> {
+    Ok(LogFilter {
+        path: filter
+            .path
+            .map(|path| RepoPath::from_proto(&path))
+            .transpose()?,
+        author: filter.author,
+        message: filter.message,
+        since: filter.since,
+        until: filter.until,
+    })
+}
+
+pub fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::GitRebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => git_rebase_todo_entry::Action::Pick,
+        RebaseAction::Reword => git_rebase_todo_entry::Action::Reword,
+        RebaseAction::Edit => git_rebase_todo_entry::Action::Edit,
+        RebaseAction::Squash => git_rebase_todo_entry::Action::Squash,
+        RebaseAction::Fixup => git_rebase_todo_entry::Action::Fixup,
+        RebaseAction::Drop => git_rebase_todo_entry::Action::Drop,
+    };
+    proto::GitRebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.as_ref().map(|message| message.to_string()),
+    }
+}
+
+pub fn proto_to_rebase_todo_entry(entry: proto::GitRebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match entry.action() {
+        git_rebase_todo_entry::Action::Pick => RebaseAction::Pick,
+        git_rebase_todo_entry::Action::Reword => RebaseAction::Reword,
+        git_rebase_todo_entry::Action::Edit => RebaseAction::Edit,
+        git_rebase_todo_entry::Action::Squash => RebaseAction::Squash,
+        git_rebase_todo_entry::Action::Fixup => RebaseAction::Fixup,
+        git_rebase_todo_entry::Action::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: entry.sha.into(),
+        subject: entry.subject.into(),
+        message: entry.message.map(SharedString::from),
+    }
+}
+
+fn rebase_progress_to_proto(progress: &RebaseProgress) -> proto::GitRebaseProgress {
+    proto::GitRebaseProgress {
+        head_name: progress.head_name.as_ref().map(|name| name.to_string()),
+        onto: progress.onto.to_string(),
+        done: progress.done as u64,
+        total: progress.total as u64,
+        stopped_sha: progress.stopped_sha.as_ref().map(|sha| sha.to_string()),
+    }
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::GitSubmodule {
+    proto::GitSubmodule {
+        path: submodule.path.to_proto(),
+        sha: submodule.sha.to_string(),
+        status: match submodule.status {
+            SubmoduleStatus::Current => git_submodule::Status::Current,
+            SubmoduleStatus::Uninitialized => git_submodule::Status::Uninitialized,
+            SubmoduleStatus::OutOfDate => git_submodule::Status::OutOfDate,
+            SubmoduleStatus::Conflicted => git_submodule::Status::Conflicted,
+        } as i32,
+    }
+}
+
+fn proto_to_submodule(submodule: &proto::GitSubmodule) -> Result<Submodule> {
+    Ok(Submodule {
+        path: RepoPath::from_proto(&submodule.path)?,
+        sha: submodule.sha.clone().into(),
+        status: match submodule.status() {
+            git_submodule::Status::Current => SubmoduleStatus::Current,
+            git_submodule::Status::Uninitialized => SubmoduleStatus::Uninitialized,
+            git_submodule::Status::OutOfDate => SubmoduleStatus::OutOfDate,
+            git_submodule::Status::Conflicted => SubmoduleStatus::Conflicted,
+        },
+    })
+}
+
+fn proto_to_rebase_progress(progress: &proto::GitRebaseProgress) -> RebaseProgress {
+    RebaseProgress {
+        head_name: progress.head_name.clone().map(SharedString::from),
+        onto: progress.onto.clone().into(),
+        done: progress.done as usize,
+        total: progress.total as usize,
+        stopped_sha: progress.stopped_sha.clone().map(SharedString::from),
+    }
+}
+
+pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
+    Ok(StashEntry {
+        oid: Oid::from_bytes(&entry.oid)?,
+        message: entry.message.clone(),
+        index: entry.index as usize,
         branch: entry.branch.clone(),
         timestamp: entry.timestamp,
     })
@@ -4065,6 +4620,99 @@ impl Repository {
         })
     }
 
+    /// Returns the todo list for an interactive rebase of the commits since `base`.
+    pub fn rebase_todo(&mut self, base: String) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_todo(base).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn interactive_rebase(
+        &mut self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git rebase --interactive {base}").into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.interactive_rebase(base, todo, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitInteractiveRebase {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                base,
+                                entries: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn rebase_command(&mut self, command: RebaseCommand) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let job_name = match command {
+            RebaseCommand::Continue => "git rebase --continue",
+            RebaseCommand::Skip => "git rebase --skip",
+            RebaseCommand::Abort => "git rebase --abort",
+        };
+        self.send_job(Some(job_name.into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.rebase_command(command, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let command = match command {
+                        RebaseCommand::Continue => git_rebase_command::Command::Continue,
+                        RebaseCommand::Skip => git_rebase_command::Command::Skip,
+                        RebaseCommand::Abort => git_rebase_command::Command::Abort,
+                    };
+                    client
+                        .request(proto::GitRebaseCommand {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            command: command.into(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
@@ -4081,13 +4729,32 @@ impl Repository {
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
-                    })
+                    Ok(proto_to_commit_details(&resp))
+                }
+            }
+        })
+    }
+
+    pub fn commit_signature(
+        &mut self,
+        commit: String,
+    ) -> oneshot::Receiver<Result<Option<CommitSignature>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.commit_signature(commit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let resp = client
+                        .request(proto::GitGetCommitSignature {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(resp.signature.as_ref().map(proto_to_commit_signature))
                 }
             }
         })
@@ -4108,6 +4775,7 @@ impl Repository {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
                             commit,
+                            base: None,
                         })
                         .await?;
                     Ok(CommitDiff {
@@ -4128,6 +4796,341 @@ impl Repository {
         })
     }
 
+    pub fn compare_commits(
+        &mut self,
+        base: String,
+        head: String,
+    ) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.compare_commits(base, head, cx).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState {
+                    client, project_id, ..
+                }) => {
+                    let response = client
+                        .request(proto::LoadCommitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit: head,
+                            base: Some(base),
+                        })
+                        .await?;
+                    Ok(CommitDiff {
+                        files: response
+                            .files
+                            .into_iter()
+                            .map(|file| {
+                                Ok(CommitFile {
+                                    path: RepoPath::from_proto(&file.path)?,
+                                    old_text: file.old_text,
+                                    new_text: file.new_text,
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()?,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn log_paginated(
+        &mut self,
+        filter: LogFilter,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.log(filter, skip, limit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            filter: Some(log_filter_to_proto(filter)),
+                            skip: skip as u64,
+                            limit: limit.map(|limit| limit as u64),
+                        })
+                        .await?;
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(|entry| LogEntry {
+                            sha: entry.sha.into(),
+                            parents: entry.parents.into_iter().map(Into::into).collect(),
+                            refs: entry.refs.into_iter().map(Into::into).collect(),
+                            subject: entry.subject.into(),
+                            commit_timestamp: entry.commit_timestamp,
+                            author_name: entry.author_name.into(),
+                            author_email: entry.author_email.into(),
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn commit_operation(
+        &mut self,
+        operation: CommitOperation,
+        commit: String,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let job_name = match operation {
+            CommitOperation::Checkout => format!("git checkout {commit}"),
+            CommitOperation::CherryPick => format!("git cherry-pick {commit}"),
+            CommitOperation::Revert => format!("git revert {commit}"),
+        };
+        self.send_job(Some(job_name.into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    backend
+                        .commit_operation(operation, commit, environment)
+                        .await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let operation = match operation {
+                        CommitOperation::Checkout => git_commit_operation::Operation::Checkout,
+                        CommitOperation::CherryPick => git_commit_operation::Operation::CherryPick,
+                        CommitOperation::Revert => git_commit_operation::Operation::Revert,
+                    };
+                    client
+                        .request(proto::GitCommitOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            operation: operation.into(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        commit: String,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.create_tag(name, commit, message, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                commit,
+                                message,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Lists the repository's tags, most recent first.
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.tags().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag --delete {name}").into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.delete_tag(name, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Collects the commits between the previous tag and `head`, for drafting release notes.
+    pub fn release_commits(&mut self, head: String) -> oneshot::Receiver<Result<ReleaseCommits>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.release_commits(head).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitReleaseCommits {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            head,
+                        })
+                        .await?;
+                    Ok(ReleaseCommits {
+                        previous_tag: response.previous_tag.map(Into::into),
+                        commits: response
+                            .commits
+                            .into_iter()
+                            .map(|commit| CommitSummary {
+                                sha: commit.sha.into(),
+                                subject: commit.subject.into(),
+                                commit_timestamp: commit.commit_timestamp,
+                                author_name: commit.author_name.into(),
+                                has_parent: true,
+                            })
+                            .collect(),
+                    })
+                }
+            }
+        })
+    }
+
+    /// Loads the base, ours and theirs versions of a conflicted file.
+    pub fn load_conflict_texts(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictTexts>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_conflict_texts(path).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitLoadConflictTexts {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(ConflictTexts {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Runs a step of `git bisect`, returning where the bisect stands afterwards.
+    pub fn bisect(&mut self, command: BisectCommand) -> oneshot::Receiver<Result<BisectStatus>> {
+        let id = self.id;
+        let job_name = match &command {
+            BisectCommand::Start { bad, good } => {
+                format!("git bisect start {bad} {}", good.join(" "))
+            }
+            BisectCommand::Good => "git bisect good".to_string(),
+            BisectCommand::Bad => "git bisect bad".to_string(),
+            BisectCommand::Skip => "git bisect skip".to_string(),
+            BisectCommand::Reset => "git bisect reset".to_string(),
+        };
+        self.send_job(Some(job_name.into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.bisect(command, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let (command, bad, good) = match command {
+                        BisectCommand::Start { bad, good } => {
+                            (git_bisect::Command::Start, Some(bad), good)
+                        }
+                        BisectCommand::Good => (git_bisect::Command::Good, None, Vec::new()),
+                        BisectCommand::Bad => (git_bisect::Command::Bad, None, Vec::new()),
+                        BisectCommand::Skip => (git_bisect::Command::Skip, None, Vec::new()),
+                        BisectCommand::Reset => (git_bisect::Command::Reset, None, Vec::new()),
+                    };
+                    let response = client
+                        .request(proto::GitBisect {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            command: command.into(),
+                            bad,
+                            good,
+                        })
+                        .await?;
+                    Ok(proto_to_bisect_status(&response))
+                }
+            }
+        })
+    }
+
+    /// Reads a value from the repository's git config.
+    pub fn config_value(&mut self, key: String) -> oneshot::Receiver<Result<Option<String>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.config_value(key).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetConfig {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            key,
+                        })
+                        .await?;
+                    Ok(response.value)
+                }
+            }
+        })
+    }
+
     pub fn file_history(
         &mut self,
         path: RepoPath,
@@ -4644,6 +5647,38 @@ impl Repository {
         )
     }
 
+    /// Runs the commit checks on the staged changes. This doesn't invoke any git hooks.
+    pub fn check_staged_changes(
+        &mut self,
+        checks: CommitChecks,
+    ) -> oneshot::Receiver<Result<Vec<CommitCheckFinding>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    check_staged_changes(&checks, &backend).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitCheckStagedChanges {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            secret_patterns: checks.secret_patterns().map(Into::into).collect(),
+                            forbidden_paths: checks.forbidden_paths().map(Into::into).collect(),
+                            max_binary_size: checks.max_binary_size(),
+                            conflict_markers: checks.conflict_markers(),
+                        })
+                        .await?;
+                    response
+                        .findings
+                        .iter()
+                        .map(proto_to_commit_check_finding)
+                        .collect()
+                }
+            }
+        })
+    }
+
     pub fn commit(
         &mut self,
         message: SharedString,
@@ -4688,6 +5723,7 @@ impl Repository {
                             options: Some(proto::commit::CommitOptions {
                                 amend: options.amend,
                                 signoff: options.signoff,
+                                sign: options.sign,
                             }),
                             askpass_id,
                         })
@@ -4743,6 +5779,61 @@ impl Repository {
         })
     }
 
+    pub fn submodule_command(
+        &mut self,
+        command: SubmoduleCommand,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let job_name = format!("git {}", command.args().join(" "));
+
+        self.send_job(Some(job_name.into()), move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    backend
+                        .submodule_command(command, paths, askpass, environment, cx)
+                        .await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let command = match command {
+                        SubmoduleCommand::Init => git_submodule_command::Command::Init,
+                        SubmoduleCommand::Update => git_submodule_command::Command::Update,
+                        SubmoduleCommand::Sync => git_submodule_command::Command::Sync,
+                    };
+                    let response = client
+                        .request(proto::GitSubmoduleCommand {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            askpass_id,
+                            command: command.into(),
+                            paths: paths.iter().map(|path| path.to_proto()).collect(),
+                        })
+                        .await
+                        .context("sending submodule request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
+            }
+        })
+    }
+
     pub fn push(
         &mut self,
         branch: SharedString,
@@ -5480,6 +6571,23 @@ impl Repository {
             cx.emit(RepositoryEvent::StashEntriesChanged)
         }
         self.snapshot.stash_entries = new_stash_entries;
+        let new_rebase_progress = update
+            .rebase_progress
+            .as_ref()
+            .map(proto_to_rebase_progress);
+        if self.snapshot.rebase_progress != new_rebase_progress {
+            cx.emit(RepositoryEvent::RebaseProgressChanged)
+        }
+        self.snapshot.rebase_progress = new_rebase_progress;
+        let new_submodules = update
+            .submodules
+            .iter()
+            .filter_map(|submodule| proto_to_submodule(submodule).log_err())
+            .collect::<Vec<_>>();
+        if self.snapshot.submodules != new_submodules {
+            cx.emit(RepositoryEvent::SubmodulesChanged)
+        }
+        self.snapshot.submodules = new_submodules;
         self.snapshot.remote_upstream_url = update.remote_upstream_url;
         self.snapshot.remote_origin_url = update.remote_origin_url;
 
@@ -6117,6 +7225,26 @@ fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     }
 }
 
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        subject: tag.subject.to_string(),
+        annotated: tag.annotated,
+        timestamp: tag.timestamp,
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        annotated: proto.annotated,
+        timestamp: proto.timestamp,
+    }
+}
+
 fn worktree_to_proto(worktree: &git::repository::Worktree) -> proto::Worktree {
     proto::Worktree {
         path: worktree.path.to_string_lossy().to_string(),
@@ -6185,6 +7313,147 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn commit_signature_to_proto(signature: &CommitSignature) -> proto::GitCommitSignature {
+    proto::GitCommitSignature {
+        status: match signature.status {
+            SignatureStatus::Verified => git_commit_signature::Status::Verified,
+            SignatureStatus::Unverified => git_commit_signature::Status::Unverified,
+            SignatureStatus::UnknownKey => git_commit_signature::Status::UnknownKey,
+        } as i32,
+        signer: signature.signer.as_ref().map(ToString::to_string),
+        key: signature.key.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_commit_signature(proto: &proto::GitCommitSignature) -> CommitSignature {
+    CommitSignature {
+        status: match proto.status() {
+            git_commit_signature::Status::Verified => SignatureStatus::Verified,
+            git_commit_signature::Status::Unverified => SignatureStatus::Unverified,
+            git_commit_signature::Status::UnknownKey => SignatureStatus::UnknownKey,
+        },
+        signer: proto.signer.clone().map(Into::into),
+        key: proto.key.clone().map(Into::into),
+    }
+}
+
+async fn check_staged_changes(
+    checks: &CommitChecks,
+    backend: &Arc<dyn GitRepository>,
+) -> Result<Vec<CommitCheckFinding>> {
+    let statuses = backend
+        .status(&[RepoPath::from_rel_path(
+            &RelPath::new(".".as_ref(), PathStyle::local()).unwrap(),
+        )])
+        .await?;
+
+    let mut findings = Vec::new();
+    for (repo_path, status) in statuses.entries.iter() {
+        let FileStatus::Tracked(TrackedStatus { index_status, .. }) = status else {
+            continue;
+        };
+        if matches!(index_status, StatusCode::Unmodified | StatusCode::Deleted) {
+            continue;
+        }
+
+        let staged = match backend.load_index_text(repo_path.clone()).await {
+            Some(text) => StagedFile::Text(text),
+            // The working copy may have changed since staging, so use the size of the staged blob.
+            None => match backend.load_index_file_size(repo_path.clone()).await {
+                Some(size) => StagedFile::Binary { size },
+                None => continue,
+            },
+        };
+        let committed_text = backend.load_committed_text(repo_path.clone()).await;
+        findings.extend(checks.check_file(repo_path, &staged, committed_text.as_deref()));
+    }
+    Ok(findings)
+}
+
+fn commit_check_finding_to_proto(finding: &CommitCheckFinding) -> proto::CommitCheckFinding {
+    let (kind, pattern, size) = match &finding.kind {
+        CommitCheckKind::Secret { pattern } => (
+            commit_check_finding::Kind::Secret,
+            Some(pattern.clone()),
+            None,
+        ),
+        CommitCheckKind::LargeBinary { size } => {
+            (commit_check_finding::Kind::LargeBinary, None, Some(*size))
+        }
+        CommitCheckKind::ForbiddenPath => (commit_check_finding::Kind::ForbiddenPath, None, None),
+        CommitCheckKind::ConflictMarker => (commit_check_finding::Kind::ConflictMarker, None, None),
+    };
+    proto::CommitCheckFinding {
+        path: finding.path.to_proto(),
+        line: finding.line,
+        kind: kind.into(),
+        pattern,
+        size,
+    }
+}
+
+fn proto_to_commit_check_finding(proto: &proto::CommitCheckFinding) -> Result<CommitCheckFinding> {
+    let kind = match proto.kind() {
+        commit_check_finding::Kind::Secret => CommitCheckKind::Secret {
+            pattern: proto.pattern.clone().unwrap_or_default(),
+        },
+        commit_check_finding::Kind::LargeBinary => CommitCheckKind::LargeBinary {
+            size: proto.size.unwrap_or_default(),
+        },
+        commit_check_finding::Kind::ForbiddenPath => CommitCheckKind::ForbiddenPath,
+        commit_check_finding::Kind::ConflictMarker => CommitCheckKind::ConflictMarker,
+    };
+    Ok(CommitCheckFinding {
+        path: RepoPath::from_proto(&proto.path)?,
+        line: proto.line,
+        kind,
+    })
+}
+
+fn bisect_status_to_proto(status: &BisectStatus) -> proto::GitBisectResponse {
+    match status {
+        BisectStatus::Testing { commit, remaining } => proto::GitBisectResponse {
+            status: git_bisect_response::Status::Testing as i32,
+            commit: Some(commit.to_string()),
+            remaining: *remaining as u64,
+            candidates: Vec::new(),
+        },
+        BisectStatus::FirstBad { commit } => proto::GitBisectResponse {
+            status: git_bisect_response::Status::FirstBad as i32,
+            commit: Some(commit.to_string()),
+            remaining: 0,
+            candidates: Vec::new(),
+        },
+        BisectStatus::Inconclusive { candidates } => proto::GitBisectResponse {
+            status: git_bisect_response::Status::Inconclusive as i32,
+            commit: None,
+            remaining: 0,
+            candidates: candidates.iter().map(ToString::to_string).collect(),
+        },
+        BisectStatus::Inactive => proto::GitBisectResponse {
+            status: git_bisect_response::Status::Inactive as i32,
+            commit: None,
+            remaining: 0,
+            candidates: Vec::new(),
+        },
+    }
+}
+
+fn proto_to_bisect_status(proto: &proto::GitBisectResponse) -> BisectStatus {
+    let commit = proto.commit.clone().unwrap_or_default().into();
+    match proto.status() {
+        git_bisect_response::Status::Testing => BisectStatus::Testing {
+            commit,
+            remaining: proto.remaining as usize,
+        },
+        git_bisect_response::Status::FirstBad => BisectStatus::FirstBad { commit },
+        git_bisect_response::Status::Inconclusive => BisectStatus::Inconclusive {
+            candidates: proto.candidates.iter().cloned().map(Into::into).collect(),
+        },
+        git_bisect_response::Status::Inactive => BisectStatus::Inactive,
+    }
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
@@ -6232,6 +7501,16 @@ async fn compute_snapshot(
         events.push(RepositoryEvent::BranchChanged);
     }
 
+    let rebase_progress = backend.rebase_progress().await.log_err().flatten();
+    if rebase_progress != prev_snapshot.rebase_progress {
+        events.push(RepositoryEvent::RebaseProgressChanged);
+    }
+
+    let submodules = backend.submodules().await.log_err().unwrap_or_default();
+    if submodules != prev_snapshot.submodules {
+        events.push(RepositoryEvent::SubmodulesChanged);
+    }
+
     let remote_origin_url = backend.remote_url("origin").await;
     let remote_upstream_url = backend.remote_url("upstream").await;
 
@@ -6247,6 +7526,8 @@ async fn compute_snapshot(
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        rebase_progress,
+        submodules,
     };
 
     Ok((snapshot, events))
@@ -1,12 +1,13 @@
+mod hierarchy;
 mod signature_help;
 
 use crate::{
     CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
+    DocumentHighlight, DocumentSymbol, FoldingRange, Hover, HoverBlock, HoverBlockKind, InlayHint,
     InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
     LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
     ProjectTransaction, PulledDiagnostics, ResolveState,
-    lsp_store::{LocalLspStore, LspStore},
+    lsp_store::{LocalLspStore, LspStore, RawSemanticTokens, semantic_tokens_legend},
 };
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
@@ -17,7 +18,7 @@ use futures::future;
 use gpui::{App, AsyncApp, Entity, SharedString, Task};
 use language::{
     Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, CharScopeContext,
-    OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
+    OffsetRangeExt, Point, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{InlayHintKind, LanguageSettings, language_settings},
     point_from_lsp, point_to_lsp,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
@@ -37,6 +38,10 @@ use std::{
 use text::{BufferId, LineEnding};
 use util::{ResultExt as _, debug_panic};
 
+pub(crate) use hierarchy::{
+    GetIncomingCalls, GetOutgoingCalls, GetSubtypes, GetSupertypes, PrepareCallHierarchy,
+    PrepareTypeHierarchy,
+};
 pub use signature_help::SignatureHelp;
 
 fn code_action_kind_matches(requested: &lsp::CodeActionKind, actual: &lsp::CodeActionKind) -> bool {
@@ -257,6 +262,17 @@ pub(crate) struct GetCodeLens;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentColor;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetSemanticTokens;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetFoldingRanges;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<Anchor>,
+}
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -4387,6 +4403,421 @@ impl LspCommand for GetDocumentColor {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    type Response = Vec<FoldingRange>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn display_name(&self) -> &str {
+        "Folding ranges"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .folding_range_provider
+            .as_ref()
+            .is_some_and(|capability| match capability {
+                lsp::FoldingRangeProviderCapability::Simple(supported) => *supported,
+                lsp::FoldingRangeProviderCapability::FoldingProvider(..) => true,
+                lsp::FoldingRangeProviderCapability::Options(..) => true,
+            })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::FoldingRangeParams> {
+        Ok(lsp::FoldingRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::FoldingRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer.read_with(&cx, |buffer, _| {
+            let max_row = buffer.max_point().row;
+            message
+                .unwrap_or_default()
+                .into_iter()
+                // Folds are line-based: the start line stays visible and everything up to the end of the end line is hidden.
+                .filter(|range| range.start_line < range.end_line && range.end_line <= max_row)
+                .map(|range| {
+                    let start = Point::new(range.start_line, buffer.line_len(range.start_line));
+                    let end = Point::new(range.end_line, buffer.line_len(range.end_line));
+                    FoldingRange {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: response
+                .into_iter()
+                .map(|range| proto::FoldingRange {
+                    start: Some(serialize_anchor(&range.range.start)),
+                    end: Some(serialize_anchor(&range.range.end)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(message
+            .ranges
+            .into_iter()
+            .filter_map(|range| {
+                Some(FoldingRange {
+                    range: deserialize_anchor(range.start?)?..deserialize_anchor(range.end?)?,
+                })
+            })
+            .collect())
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    type Response = Vec<Vec<Range<Anchor>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn display_name(&self) -> &str {
+        "Selection ranges"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .selection_range_provider
+            .as_ref()
+            .is_some_and(|capability| match capability {
+                lsp::SelectionRangeProviderCapability::Simple(supported) => *supported,
+                lsp::SelectionRangeProviderCapability::Options(..) => true,
+                lsp::SelectionRangeProviderCapability::RegistrationOptions(..) => true,
+            })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SelectionRangeParams> {
+        Ok(lsp::SelectionRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(position.to_point_utf16(buffer)))
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::SelectionRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer.read_with(&cx, |buffer, _| {
+            message
+                .unwrap_or_default()
+                .into_iter()
+                .map(|selection_range| {
+                    let mut ranges = Vec::new();
+                    let mut next = Some(&selection_range);
+                    while let Some(selection_range) = next {
+                        let range = range_from_lsp(selection_range.range);
+                        let start = buffer.clip_point_utf16(range.start, Bias::Left);
+                        let end = buffer.clip_point_utf16(range.end, Bias::Left);
+                        ranges.push(buffer.anchor_before(start)..buffer.anchor_after(end));
+                        next = selection_range.parent.as_deref();
+                    }
+                    ranges
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            positions: self.positions.iter().map(serialize_anchor).collect(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSelectionRanges,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).context("invalid position"))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(positions.clone())
+            })?
+            .await?;
+        Ok(Self { positions })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            selection_ranges: response
+                .into_iter()
+                .map(|ranges| proto::SelectionRanges {
+                    ranges: ranges
+                        .into_iter()
+                        .map(|range| proto::AnchorRange {
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSelectionRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let selection_ranges = message
+            .selection_ranges
+            .into_iter()
+            .map(|ranges| {
+                ranges
+                    .ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        Some(deserialize_anchor(range.start?)?..deserialize_anchor(range.end?)?)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    selection_ranges
+                        .iter()
+                        .flatten()
+                        .flat_map(|range| [range.start, range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })?
+            .await?;
+        Ok(selection_ranges)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSemanticTokens {
+    type Response = RawSemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
+
+    fn display_name(&self) -> &str {
+        "Semantic tokens"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        semantic_tokens_legend(&server_capabilities.server_capabilities).is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SemanticTokensParams> {
+        Ok(lsp::SemanticTokensParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SemanticTokensResult>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(match message {
+            Some(lsp::SemanticTokensResult::Tokens(tokens)) => RawSemanticTokens {
+                result_id: tokens.result_id,
+                data: tokens.data,
+            },
+            Some(lsp::SemanticTokensResult::Partial(partial)) => RawSemanticTokens {
+                result_id: None,
+                data: partial.data,
+            },
+            None => RawSemanticTokens::default(),
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            result_id: response.result_id,
+            data: response
+                .data
+                .into_iter()
+                .flat_map(|token| {
+                    [
+                        token.delta_line,
+                        token.delta_start,
+                        token.length,
+                        token.token_type,
+                        token.token_modifiers_bitset,
+                    ]
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        anyhow::ensure!(
+            message.data.len() % 5 == 0,
+            "invalid semantic tokens data length {}",
+            message.data.len()
+        );
+        Ok(RawSemanticTokens {
+            result_id: message.result_id,
+            data: message
+                .data
+                .chunks_exact(5)
+                .map(|token| lsp::SemanticToken {
+                    delta_line: token[0],
+                    delta_start: token[1],
+                    length: token[2],
+                    token_type: token[3],
+                    token_modifiers_bitset: token[4],
+                })
+                .collect(),
+        })
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
@@ -0,0 +1,869 @@
+//! Call and type hierarchy requests.
+//!
+//! Hierarchy items are opaque to the client: the language server expects the exact item it
+//! returned when preparing the hierarchy to be sent back when expanding it, so the original
+//! LSP item is kept (and sent over the wire) as JSON.
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{App, AsyncApp, Entity};
+use language::{
+    Bias, Buffer, PointUtf16, ToPointUtf16, point_from_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+};
+use lsp::{AdapterServerCapabilities, LanguageServer, LanguageServerId};
+use serde::Deserialize;
+use serde_json::Value;
+use text::BufferId;
+
+use super::{LspCommand, language_server_for_buffer, make_lsp_text_document_position};
+use crate::{HierarchyCall, HierarchyItem, Location, lsp_store::LspStore};
+
+#[derive(Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub lsp_item: Value,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub lsp_item: Value,
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSupertypes {
+    pub lsp_item: Value,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSubtypes {
+    pub lsp_item: Value,
+}
+
+/// The fields shared by `CallHierarchyItem` and `TypeHierarchyItem` that are displayed to the user.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LspHierarchyItem {
+    name: String,
+    kind: lsp::SymbolKind,
+    detail: Option<String>,
+    uri: lsp::Uri,
+    selection_range: lsp::Range,
+}
+
+async fn location_from_lsp(
+    uri: lsp::Uri,
+    range: lsp::Range,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(uri, server_id, cx)
+        })?
+        .await?;
+    buffer.clone().read_with(cx, |target_buffer, _| {
+        let start = target_buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+        let end = target_buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+        Location {
+            buffer,
+            range: target_buffer.anchor_after(start)..target_buffer.anchor_before(end),
+        }
+    })
+}
+
+async fn hierarchy_item_from_lsp(
+    lsp_item: Value,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let item = LspHierarchyItem::deserialize(&lsp_item).context("invalid hierarchy item")?;
+    let location =
+        location_from_lsp(item.uri, item.selection_range, lsp_store, server_id, cx).await?;
+    Ok(HierarchyItem {
+        server_id,
+        name: item.name,
+        kind: item.kind,
+        detail: item.detail,
+        location,
+        lsp_item,
+    })
+}
+
+async fn hierarchy_items_from_lsp<T: serde::Serialize>(
+    items: Option<Vec<T>>,
+    lsp_store: Entity<LspStore>,
+    buffer: Entity<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let (_, language_server) = language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+    let mut hierarchy_items = Vec::new();
+    for item in items.into_iter().flatten() {
+        hierarchy_items.push(
+            hierarchy_item_from_lsp(
+                serde_json::to_value(item)?,
+                &lsp_store,
+                language_server.server_id(),
+                &mut cx,
+            )
+            .await?,
+        );
+    }
+    Ok(hierarchy_items)
+}
+
+/// Converts the calls of an incoming or outgoing calls response.
+///
+/// The call sites are always relative to the caller: they are located in the `from` item of
+/// incoming calls, and in the expanded item of outgoing calls, whose location is `caller`.
+async fn hierarchy_calls_from_lsp(
+    calls: Vec<(Value, Vec<lsp::Range>)>,
+    caller: Option<&LspHierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    buffer: Entity<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let (_, language_server) = language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+    let server_id = language_server.server_id();
+    let mut hierarchy_calls = Vec::new();
+    for (lsp_item, from_ranges) in calls {
+        let item = hierarchy_item_from_lsp(lsp_item, &lsp_store, server_id, &mut cx).await?;
+        let mut call_sites = Vec::with_capacity(from_ranges.len());
+        for range in from_ranges {
+            let call_site = match caller {
+                Some(caller) => {
+                    location_from_lsp(caller.uri.clone(), range, &lsp_store, server_id, &mut cx)
+                        .await?
+                }
+                None => item.location.buffer.read_with(&cx, |target_buffer, _| {
+                    let start =
+                        target_buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = target_buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    Location {
+                        buffer: item.location.buffer.clone(),
+                        range: target_buffer.anchor_after(start)..target_buffer.anchor_before(end),
+                    }
+                })?,
+            };
+            call_sites.push(call_site);
+        }
+        hierarchy_calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(hierarchy_calls)
+}
+
+fn location_to_proto(
+    location: &Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing location start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing location end")?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn hierarchy_item_to_proto(
+    item: HierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        server_id: item.server_id.to_proto(),
+        name: item.name,
+        kind: unsafe { std::mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail,
+        location: Some(location_to_proto(&item.location, lsp_store, peer_id, cx)),
+        lsp_item: item.lsp_item.to_string(),
+    }
+}
+
+async fn hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let location = location_from_proto(
+        item.location.context("missing hierarchy item location")?,
+        lsp_store,
+        cx,
+    )
+    .await?;
+    Ok(HierarchyItem {
+        server_id: LanguageServerId::from_proto(item.server_id),
+        name: item.name,
+        kind: unsafe { std::mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location,
+        lsp_item: serde_json::from_str(&item.lsp_item).context("invalid hierarchy item")?,
+    })
+}
+
+async fn hierarchy_items_from_proto(
+    items: Vec<proto::HierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut hierarchy_items = Vec::with_capacity(items.len());
+    for item in items {
+        hierarchy_items.push(hierarchy_item_from_proto(item, &lsp_store, &mut cx).await?);
+    }
+    Ok(hierarchy_items)
+}
+
+fn hierarchy_calls_to_proto(
+    calls: Vec<HierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::HierarchyCall> {
+    calls
+        .into_iter()
+        .map(|call| proto::HierarchyCall {
+            call_sites: call
+                .call_sites
+                .iter()
+                .map(|call_site| location_to_proto(call_site, lsp_store, peer_id, cx))
+                .collect(),
+            item: Some(hierarchy_item_to_proto(call.item, lsp_store, peer_id, cx)),
+        })
+        .collect()
+}
+
+async fn hierarchy_calls_from_proto(
+    calls: Vec<proto::HierarchyCall>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let mut hierarchy_calls = Vec::with_capacity(calls.len());
+    for call in calls {
+        let item = hierarchy_item_from_proto(
+            call.item.context("missing hierarchy call item")?,
+            &lsp_store,
+            &mut cx,
+        )
+        .await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            call_sites.push(location_from_proto(call_site, &lsp_store, &mut cx).await?);
+        }
+        hierarchy_calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(hierarchy_calls)
+}
+
+async fn position_from_proto(
+    position: Option<proto::Anchor>,
+    version: &[proto::VectorClockEntry],
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<PointUtf16> {
+    let position = position
+        .and_then(deserialize_anchor)
+        .context("invalid position")?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_version(deserialize_version(version))
+        })?
+        .await?;
+    buffer.read_with(cx, |buffer, _| position.to_point_utf16(buffer))
+}
+
+fn supports_call_hierarchy(capabilities: &AdapterServerCapabilities) -> bool {
+    match &capabilities.server_capabilities.call_hierarchy_provider {
+        Some(lsp::CallHierarchyServerCapability::Simple(enabled)) => *enabled,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: serde_json::from_value(self.lsp_item.clone())?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let calls = calls
+            .into_iter()
+            .flatten()
+            .map(|call| Ok((serde_json::to_value(call.from)?, call.from_ranges)))
+            .collect::<Result<Vec<_>>>()?;
+        hierarchy_calls_from_lsp(calls, None, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item).context("invalid hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: serde_json::from_value(self.lsp_item.clone())?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let caller =
+            LspHierarchyItem::deserialize(&self.lsp_item).context("invalid hierarchy item")?;
+        let calls = calls
+            .into_iter()
+            .flatten()
+            .map(|call| Ok((serde_json::to_value(call.to)?, call.from_ranges)))
+            .collect::<Result<Vec<_>>>()?;
+        hierarchy_calls_from_lsp(calls, Some(&caller), lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item).context("invalid hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    // The server capabilities do not advertise type hierarchy support,
+    // servers without it answer with an error instead.
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: serde_json::from_value(self.lsp_item.clone())?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item).context("invalid hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: serde_json::from_value(self.lsp_item.clone())?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(items, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item).context("invalid hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
@@ -16,9 +16,21 @@ pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 pub mod vue_language_server_ext;
 
+mod folding_ranges;
 mod inlay_hint_cache;
+mod notebooks;
+mod resource_monitor;
+mod semantic_tokens;
+mod server_data_cache;
+mod task_diagnostics;
 
+use self::folding_ranges::FoldingRangesData;
 use self::inlay_hint_cache::BufferInlayHints;
+use self::notebooks::OpenNotebooks;
+use self::resource_monitor::ResourceMonitor;
+use self::semantic_tokens::SemanticTokensData;
+pub(crate) use self::semantic_tokens::semantic_tokens_legend;
+use self::task_diagnostics::TaskDiagnostics;
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
     CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, InlayId, LocationLink,
@@ -134,7 +146,14 @@ use util::{
 
 pub use fs::*;
 pub use language::Location;
+pub use lsp_store::folding_ranges::{BufferFoldingRanges, FoldingRangesTask};
 pub use lsp_store::inlay_hint_cache::{CacheInlayHints, InvalidationStrategy};
+pub use lsp_store::notebooks::NotebookCellBuffer;
+pub use lsp_store::resource_monitor::LanguageServerResources;
+pub use lsp_store::semantic_tokens::{
+    BufferSemanticTokens, RawSemanticTokens, SemanticToken, SemanticTokensTask,
+    ServerSemanticTokens,
+};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use worktree::{
@@ -292,6 +311,8 @@ pub struct LocalLspStore {
     lsp_tree: LanguageServerTree,
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
+    /// The last semantic tokens of each buffer, as returned by each server, to request deltas.
+    semantic_tokens_results: HashMap<BufferId, HashMap<LanguageServerId, RawSemanticTokens>>,
     buffer_pull_diagnostics_result_ids: HashMap<
         LanguageServerId,
         HashMap<Option<SharedString>, HashMap<PathBuf, Option<SharedString>>>,
@@ -301,6 +322,9 @@ pub struct LocalLspStore {
         HashMap<Option<SharedString>, HashMap<PathBuf, Option<SharedString>>>,
     >,
     restricted_worktrees_tasks: HashMap<WorktreeId, (Subscription, Receiver<()>)>,
+    resource_monitor: ResourceMonitor,
+    notebooks: OpenNotebooks,
+    task_diagnostics: TaskDiagnostics,
 }
 
 impl LocalLspStore {
@@ -510,6 +534,15 @@ impl LocalLspStore {
                         adapter.adapter.prepare_initialize_params(params, cx)
                     })??;
 
+                    lsp_store
+                        .update(cx, |lsp_store, cx| {
+                            lsp_store.record_language_server_session(
+                                &language_server,
+                                worktree_id,
+                                cx,
+                            )
+                        })
+                        .ok();
                     Self::setup_lsp_messages(
                         lsp_store.clone(),
                         &language_server,
@@ -746,6 +779,17 @@ impl LocalLspStore {
                     let adapter = adapter.clone();
                     if let Some(this) = this.upgrade() {
                         this.update(cx, |this, cx| {
+                            if let Some(cell_buffer) = this.notebook_cell_buffer(&params.uri) {
+                                this.update_notebook_cell_diagnostics(
+                                    &cell_buffer,
+                                    server_id,
+                                    params,
+                                    &adapter.disk_based_diagnostic_sources,
+                                    cx,
+                                )
+                                .log_err();
+                                return;
+                            }
                             {
                                 let buffer = params
                                     .uri
@@ -2543,50 +2587,23 @@ impl LocalLspStore {
             });
     }
 
-    fn register_buffer_with_language_servers(
+    /// Starts the language servers of the language for a path, unless they are started already, and returns the ones
+    /// that are running.
+    fn language_servers_for_path(
         &mut self,
-        buffer_handle: &Entity<Buffer>,
-        only_register_servers: HashSet<LanguageServerSelector>,
+        worktree: &Entity<Worktree>,
+        path: Arc<RelPath>,
+        language: &Arc<Language>,
+        only_register_servers: &HashSet<LanguageServerSelector>,
         cx: &mut Context<LspStore>,
-    ) {
-        let buffer = buffer_handle.read(cx);
-        let buffer_id = buffer.remote_id();
-
-        let Some(file) = File::from_dyn(buffer.file()) else {
-            return;
-        };
-        if !file.is_local() {
-            return;
-        }
-
-        let abs_path = file.abs_path(cx);
-        let Some(uri) = file_path_to_lsp_url(&abs_path).log_err() else {
-            return;
-        };
-        let initial_snapshot = buffer.text_snapshot();
-        let worktree_id = file.worktree_id(cx);
-
-        let Some(language) = buffer.language().cloned() else {
-            return;
-        };
-        let path: Arc<RelPath> = file
-            .path()
-            .parent()
-            .map(Arc::from)
-            .unwrap_or_else(|| file.path().clone());
-        let Some(worktree) = self
-            .worktree_store
-            .read(cx)
-            .worktree_for_id(worktree_id, cx)
-        else {
-            return;
-        };
+    ) -> Vec<(Arc<LanguageServer>, Arc<CachedLspAdapter>)> {
+        let worktree_id = worktree.read(cx).id();
         let language_name = language.name();
         let (reused, delegate, servers) = self
-            .reuse_existing_language_server(&self.lsp_tree, &worktree, &language_name, cx)
+            .reuse_existing_language_server(&self.lsp_tree, worktree, &language_name, cx)
             .map(|(delegate, apply)| (true, delegate, apply(&mut self.lsp_tree)))
             .unwrap_or_else(|| {
-                let lsp_delegate = LocalLspAdapterDelegate::from_local_lsp(self, &worktree, cx);
+                let lsp_delegate = LocalLspAdapterDelegate::from_local_lsp(self, worktree, cx);
                 let delegate: Arc<dyn ManifestDelegate> =
                     Arc::new(ManifestQueryDelegate::new(worktree.read(cx).snapshot()));
 
@@ -2602,7 +2619,7 @@ impl LocalLspStore {
                     .collect::<Vec<_>>();
                 (false, lsp_delegate, servers)
             });
-        let servers_and_adapters = servers
+        servers
             .into_iter()
             .filter_map(|server_node| {
                 if reused && server_node.server_id().is_none() {
@@ -2628,7 +2645,7 @@ impl LocalLspStore {
                         let uri = Uri::from_file_path(worktree.read(cx).absolutize(&path.path));
 
                         let server_id = self.get_or_insert_language_server(
-                            &worktree,
+                            worktree,
                             delegate.clone(),
                             disposition,
                             &language_name,
@@ -2653,7 +2670,49 @@ impl LocalLspStore {
                     None
                 }
             })
-            .collect::<Vec<_>>();
+            .collect::<Vec<_>>()
+    }
+
+    fn register_buffer_with_language_servers(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        only_register_servers: HashSet<LanguageServerSelector>,
+        cx: &mut Context<LspStore>,
+    ) {
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return;
+        };
+        if !file.is_local() {
+            return;
+        }
+
+        let abs_path = file.abs_path(cx);
+        let Some(uri) = file_path_to_lsp_url(&abs_path).log_err() else {
+            return;
+        };
+        let initial_snapshot = buffer.text_snapshot();
+        let worktree_id = file.worktree_id(cx);
+
+        let Some(language) = buffer.language().cloned() else {
+            return;
+        };
+        let path: Arc<RelPath> = file
+            .path()
+            .parent()
+            .map(Arc::from)
+            .unwrap_or_else(|| file.path().clone());
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+        else {
+            return;
+        };
+        let servers_and_adapters =
+            self.language_servers_for_path(&worktree, path, &language, &only_register_servers, cx);
         for (server, adapter) in servers_and_adapters {
             buffer_handle.update(cx, |buffer, cx| {
                 buffer.set_completion_triggers(
@@ -3371,6 +3430,9 @@ impl LocalLspStore {
             for buffer_servers in self.buffers_opened_in_servers.values_mut() {
                 buffer_servers.remove(server_id_to_remove);
             }
+            for buffer_tokens in self.semantic_tokens_results.values_mut() {
+                buffer_tokens.remove(server_id_to_remove);
+            }
             cx.emit(LspStoreEvent::LanguageServerRemoved(*server_id_to_remove));
         }
         servers_to_remove.into_iter().collect()
@@ -3758,6 +3820,8 @@ pub struct LspStore {
 pub struct BufferLspData {
     buffer_version: Global,
     document_colors: Option<DocumentColorData>,
+    semantic_tokens: Option<SemanticTokensData>,
+    folding_ranges: Option<FoldingRangesData>,
     code_lens: Option<CodeLensData>,
     inlay_hints: BufferInlayHints,
     lsp_requests: HashMap<LspKey, HashMap<LspRequestId, Task<()>>>,
@@ -3775,6 +3839,8 @@ impl BufferLspData {
         Self {
             buffer_version: buffer.read(cx).version(),
             document_colors: None,
+            semantic_tokens: None,
+            folding_ranges: None,
             code_lens: None,
             inlay_hints: BufferInlayHints::new(buffer, cx),
             lsp_requests: HashMap::default(),
@@ -3788,6 +3854,14 @@ impl BufferLspData {
             document_colors.cache_version += 1;
         }
 
+        if let Some(semantic_tokens) = &mut self.semantic_tokens {
+            semantic_tokens.remove_server_data(for_server);
+        }
+
+        if let Some(folding_ranges) = &mut self.folding_ranges {
+            folding_ranges.remove_server_data(for_server);
+        }
+
         if let Some(code_lens) = &mut self.code_lens {
             code_lens.lens.remove(&for_server);
         }
@@ -3909,6 +3983,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_query);
         client.add_entity_message_handler(Self::handle_lsp_query_response);
         client.add_entity_request_handler(Self::handle_restart_language_servers);
+        client.add_entity_request_handler(Self::handle_check_whole_project);
         client.add_entity_request_handler(Self::handle_stop_language_servers);
         client.add_entity_request_handler(Self::handle_cancel_language_server_work);
         client.add_entity_message_handler(Self::handle_start_language_server);
@@ -3933,6 +4008,13 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_get_completions);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -4055,9 +4137,13 @@ impl LspStore {
                 toolchain_store,
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
+                semantic_tokens_results: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
                 workspace_pull_diagnostics_result_ids: HashMap::default(),
                 restricted_worktrees_tasks: HashMap::default(),
+                resource_monitor: ResourceMonitor::new(cx),
+                notebooks: OpenNotebooks::default(),
+                task_diagnostics: TaskDiagnostics::default(),
                 watched_manifest_filenames: ManifestProvidersStore::global(cx)
                     .manifest_file_names(),
             }),
@@ -4333,6 +4419,7 @@ impl LspStore {
                         local.registered_buffers.remove(&buffer_id);
 
                         local.buffers_opened_in_servers.remove(&buffer_id);
+                        local.semantic_tokens_results.remove(&buffer_id);
                         if let Some(file) = File::from_dyn(buffer.0.read(cx).file()).cloned() {
                             local.unregister_old_buffer_from_language_servers(&buffer.0, &file, cx);
 
@@ -8015,58 +8102,10 @@ impl LspStore {
                 .and_then(|m| m.get_mut(&language_server.server_id()))?;
             let previous_snapshot = buffer_snapshots.last()?;
 
-            let build_incremental_change = || {
-                buffer
-                    .edits_since::<Dimensions<PointUtf16, usize>>(
-                        previous_snapshot.snapshot.version(),
-                    )
-                    .map(|edit| {
-                        let edit_start = edit.new.start.0;
-                        let edit_end = edit_start + (edit.old.end.0 - edit.old.start.0);
-                        let new_text = next_snapshot
-                            .text_for_range(edit.new.start.1..edit.new.end.1)
-                            .collect();
-                        lsp::TextDocumentContentChangeEvent {
-                            range: Some(lsp::Range::new(
-                                point_to_lsp(edit_start),
-                                point_to_lsp(edit_end),
-                            )),
-                            range_length: None,
-                            text: new_text,
-                        }
-                    })
-                    .collect()
-            };
-
-            let document_sync_kind = language_server
-                .capabilities()
-                .text_document_sync
-                .as_ref()
-                .and_then(|sync| match sync {
-                    lsp::TextDocumentSyncCapability::Kind(kind) => Some(*kind),
-                    lsp::TextDocumentSyncCapability::Options(options) => options.change,
-                });
-
-            let content_changes: Vec<_> = match document_sync_kind {
-                Some(lsp::TextDocumentSyncKind::FULL) => {
-                    vec![lsp::TextDocumentContentChangeEvent {
-                        range: None,
-                        range_length: None,
-                        text: next_snapshot.text(),
-                    }]
-                }
-                Some(lsp::TextDocumentSyncKind::INCREMENTAL) => build_incremental_change(),
-                _ => {
-                    #[cfg(any(test, feature = "test-support"))]
-                    {
-                        build_incremental_change()
-                    }
-
-                    #[cfg(not(any(test, feature = "test-support")))]
-                    {
-                        continue;
-                    }
-                }
+            let Some(content_changes) =
+                content_changes_since(&language_server, buffer, &previous_snapshot.snapshot)
+            else {
+                continue;
             };
 
             let next_version = previous_snapshot.version + 1;
@@ -8985,6 +9024,30 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::GetSemanticTokens(get_semantic_tokens) => {
+                Self::query_lsp_locally::<GetSemanticTokens>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_semantic_tokens,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
+            Request::GetFoldingRanges(get_folding_ranges) => {
+                Self::query_lsp_locally::<GetFoldingRanges>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_folding_ranges,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
             Request::GetHover(get_hover) => {
                 let position = get_hover.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetHover>(
@@ -10493,6 +10556,16 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    pub async fn handle_check_whole_project(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::CheckWholeProject>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |lsp_store, cx| lsp_store.check_whole_project(cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
     pub async fn handle_stop_language_servers(
         lsp_store: Entity<Self>,
         envelope: TypedEnvelope<proto::StopLanguageServers>,
@@ -11634,6 +11707,17 @@ impl LspStore {
             });
         }
 
+        if self.as_local_mut().is_some_and(|local| {
+            local
+                .resource_monitor
+                .restarted_servers
+                .remove(&(key.worktree_id, key.name.clone()))
+        }) {
+            // The server replaces one restarted by its policy, bring the diagnostics of its documents up to date.
+            self.pull_document_diagnostics_for_server(server_id, cx);
+        }
+        self.open_notebooks_in_language_servers(cx);
+
         cx.notify();
     }
 
@@ -12123,6 +12207,10 @@ impl LspStore {
             for buffer_servers in local.buffers_opened_in_servers.values_mut() {
                 buffer_servers.remove(&for_server);
             }
+            local.notebooks.remove_language_server(for_server);
+            for buffer_tokens in local.semantic_tokens_results.values_mut() {
+                buffer_tokens.remove(&for_server);
+            }
         }
     }
 
@@ -12193,6 +12281,121 @@ impl LspStore {
         }
     }
 
+    /// Pulls the diagnostics of the whole workspace from every server supporting `workspace/diagnostic`,
+    /// including the ones of the files not open in any buffer.
+    /// Unlike the background refresh, the returned task waits for the servers to finish, and the check is reported as
+    /// language server work while it runs.
+    pub fn check_whole_project(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = client.request(proto::CheckWholeProject { project_id });
+            return cx.background_spawn(async move {
+                request.await?;
+                Ok(())
+            });
+        }
+
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(()));
+        };
+        let workspace_pulls = local
+            .language_servers
+            .values()
+            .filter_map(|state| match state {
+                LanguageServerState::Running {
+                    server,
+                    workspace_diagnostics_refresh_tasks,
+                    ..
+                } => Some((server, workspace_diagnostics_refresh_tasks)),
+                LanguageServerState::Starting { .. } => None,
+            })
+            .flat_map(|(server, refresh_tasks)| {
+                refresh_tasks.iter().map(|(registration_id, refresh_task)| {
+                    (
+                        server.clone(),
+                        registration_id.clone(),
+                        refresh_task.identifier.clone(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        let checks = workspace_pulls
+            .into_iter()
+            .map(|(server, registration_id, identifier)| {
+                self.pull_whole_workspace_diagnostics(server, registration_id, identifier, cx)
+            })
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            for result in futures::future::join_all(checks).await {
+                result?;
+            }
+            Ok(())
+        })
+    }
+
+    fn pull_whole_workspace_diagnostics(
+        &mut self,
+        server: Arc<LanguageServer>,
+        registration_id: Option<String>,
+        identifier: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let server_id = server.server_id();
+        let registration_id = registration_id.map(SharedString::from);
+        // Results cached from previous pulls let the server skip the documents that did not change since.
+        let previous_result_ids = self
+            .result_ids_for_workspace_refresh(server_id, &registration_id)
+            .into_iter()
+            .filter_map(|(abs_path, result_id)| {
+                Some(lsp::PreviousResultId {
+                    uri: file_path_to_lsp_url(&abs_path).ok()?,
+                    value: result_id.to_string(),
+                })
+            })
+            .collect();
+        // Partial results are applied as they are streamed, see `on_lsp_progress`.
+        let token = match &registration_id {
+            Some(registration_id) => format!(
+                "workspace/diagnostic/{server_id}/check/{WORKSPACE_DIAGNOSTICS_TOKEN_START}{registration_id}"
+            ),
+            None => format!("workspace/diagnostic/{server_id}/check"),
+        };
+        let progress_token = ProgressToken::String(SharedString::from(token.clone()));
+        self.on_lsp_work_start(
+            server_id,
+            progress_token.clone(),
+            LanguageServerProgress {
+                is_disk_based_diagnostics_progress: false,
+                is_cancellable: false,
+                title: Some("Checking whole project".to_string()),
+                message: None,
+                percentage: None,
+                last_update_at: cx.background_executor().now(),
+            },
+            cx,
+        );
+
+        let request =
+            server.request::<lsp::WorkspaceDiagnosticRequest>(lsp::WorkspaceDiagnosticParams {
+                previous_result_ids,
+                identifier,
+                work_done_progress_params: Default::default(),
+                partial_result_params: lsp::PartialResultParams {
+                    partial_result_token: Some(lsp::ProgressToken::String(token)),
+                },
+            });
+        cx.spawn(async move |lsp_store, cx| {
+            let response = request.await.into_response();
+            lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.on_lsp_work_end(server_id, progress_token, cx);
+                let report = response.with_context(|| {
+                    format!("checking the whole project with {}", server.name())
+                })?;
+                lsp_store.apply_workspace_diagnostic_report(server_id, report, registration_id, cx);
+                anyhow::Ok(())
+            })?
+        })
+    }
+
     /// Refreshes `textDocument/diagnostic` for all open buffers associated with the given server.
     /// This is called in response to `workspace/diagnostic/refresh` to comply with the LSP spec,
     /// which requires refreshing both workspace and document diagnostics.
@@ -13239,6 +13442,7 @@ fn lsp_workspace_diagnostics_refresh(
     cx: &mut Context<'_, LspStore>,
 ) -> Option<WorkspaceRefreshTask> {
     let identifier = workspace_diagnostic_identifier(&options)?;
+    let workspace_identifier = identifier.clone();
     let registration_id_shared = registration_id.as_ref().map(SharedString::from);
 
     let (progress_tx, mut progress_rx) = mpsc::channel(1);
@@ -13355,6 +13559,7 @@ fn lsp_workspace_diagnostics_refresh(
     Some(WorkspaceRefreshTask {
         refresh_tx,
         progress_tx,
+        identifier: workspace_identifier,
         task: workspace_query_language_server,
     })
 }
@@ -13371,6 +13576,64 @@ fn buffer_diagnostic_identifier(options: &DiagnosticServerCapabilities) -> Optio
     }
 }
 
+/// The changes of the buffer since the previous snapshot sent to the server, in the form of the document sync it supports.
+fn content_changes_since(
+    language_server: &LanguageServer,
+    buffer: &Buffer,
+    previous_snapshot: &TextBufferSnapshot,
+) -> Option<Vec<lsp::TextDocumentContentChangeEvent>> {
+    let next_snapshot = buffer.text_snapshot();
+    let build_incremental_change = || {
+        buffer
+            .edits_since::<Dimensions<PointUtf16, usize>>(previous_snapshot.version())
+            .map(|edit| {
+                let edit_start = edit.new.start.0;
+                let edit_end = edit_start + (edit.old.end.0 - edit.old.start.0);
+                let new_text = next_snapshot
+                    .text_for_range(edit.new.start.1..edit.new.end.1)
+                    .collect();
+                lsp::TextDocumentContentChangeEvent {
+                    range: Some(lsp::Range::new(
+                        point_to_lsp(edit_start),
+                        point_to_lsp(edit_end),
+                    )),
+                    range_length: None,
+                    text: new_text,
+                }
+            })
+            .collect()
+    };
+
+    let document_sync_kind = language_server
+        .capabilities()
+        .text_document_sync
+        .as_ref()
+        .and_then(|sync| match sync {
+            lsp::TextDocumentSyncCapability::Kind(kind) => Some(*kind),
+            lsp::TextDocumentSyncCapability::Options(options) => options.change,
+        });
+
+    match document_sync_kind {
+        Some(lsp::TextDocumentSyncKind::FULL) => Some(vec![lsp::TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: next_snapshot.text(),
+        }]),
+        Some(lsp::TextDocumentSyncKind::INCREMENTAL) => Some(build_incremental_change()),
+        _ => {
+            #[cfg(any(test, feature = "test-support"))]
+            {
+                Some(build_incremental_change())
+            }
+
+            #[cfg(not(any(test, feature = "test-support")))]
+            {
+                None
+            }
+        }
+    }
+}
+
 fn workspace_diagnostic_identifier(
     options: &DiagnosticServerCapabilities,
 ) -> Option<Option<String>> {
@@ -13784,6 +14047,7 @@ impl LanguageServerLogType {
 pub struct WorkspaceRefreshTask {
     refresh_tx: mpsc::Sender<()>,
     progress_tx: mpsc::Sender<()>,
+    identifier: Option<String>,
     #[allow(dead_code)]
     task: Task<()>,
 }
@@ -0,0 +1,188 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{Shared, join_all},
+};
+use gpui::{AppContext as _, Context, Entity, Task};
+use language::Buffer;
+use lsp::{LSP_REQUEST_TIMEOUT, LanguageServerId};
+use util::ResultExt as _;
+
+use crate::{
+    FoldingRange,
+    lsp_command::{GetFoldingRanges, LspCommand as _},
+    lsp_store::{
+        LspStore,
+        server_data_cache::{BufferServerData, ServerDataCache},
+    },
+};
+
+pub type FoldingRangesTask = Shared<Task<Result<BufferFoldingRanges, Arc<anyhow::Error>>>>;
+
+/// Folding ranges of a buffer, as reported by all language servers that support them.
+#[derive(Debug, Default, Clone)]
+pub struct BufferFoldingRanges {
+    pub ranges: HashMap<LanguageServerId, Arc<[FoldingRange]>>,
+    pub cache_version: Option<usize>,
+}
+
+impl BufferServerData for BufferFoldingRanges {
+    type ServerData = Arc<[FoldingRange]>;
+
+    fn new(
+        ranges: HashMap<LanguageServerId, Self::ServerData>,
+        cache_version: Option<usize>,
+    ) -> Self {
+        Self {
+            ranges,
+            cache_version,
+        }
+    }
+}
+
+pub(super) type FoldingRangesData = ServerDataCache<BufferFoldingRanges>;
+
+impl LspStore {
+    /// Returns the folding ranges of the buffer, or `None` if the caller already has the ranges of `known_cache_version`.
+    pub fn folding_ranges(
+        &mut self,
+        known_cache_version: Option<usize>,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<FoldingRangesTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+        let current_language_servers = self
+            .buffer_servers_with_capability(buffer_id, |capabilities| {
+                capabilities.folding_range_provider.is_some()
+            });
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id)
+            && let Some(cached_ranges) = &lsp_data.folding_ranges
+            && !version_queried_for.changed_since(&lsp_data.buffer_version)
+            && cached_ranges.is_from_servers(current_language_servers.as_ref())
+        {
+            return cached_ranges.cached(known_cache_version);
+        }
+
+        let ranges_lsp_data = self
+            .latest_lsp_data(&buffer, cx)
+            .folding_ranges
+            .get_or_insert_default();
+        if let Some(running_update) = ranges_lsp_data.running_update(&version_queried_for) {
+            return Some(running_update);
+        }
+        let buffer_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_ranges = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_folding_ranges(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching folding ranges")
+                    .map_err(Arc::new);
+                let fetched_ranges = match fetched_ranges {
+                    Ok(fetched_ranges) => fetched_ranges,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id)
+                                    && let Some(folding_ranges) = &mut lsp_data.folding_ranges
+                                {
+                                    folding_ranges.cancel_update();
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        lsp_data
+                            .folding_ranges
+                            .get_or_insert_default()
+                            .finish_update(
+                                &mut lsp_data.buffer_version,
+                                buffer_version_queried_for,
+                                fetched_ranges,
+                            )
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        ranges_lsp_data.start_update(version_queried_for, new_task.clone());
+        Some(new_task)
+    }
+
+    fn fetch_folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Arc<[FoldingRange]>>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetFoldingRanges;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let ranges = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|ranges_response| {
+                            let server_id = LanguageServerId::from_proto(ranges_response.server_id);
+                            let response = request.response_from_proto(
+                                ranges_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move { Some((server_id, response.await.log_err()?.into())) }
+                        }),
+                )
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+                Ok(Some(ranges))
+            })
+        } else {
+            let ranges_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetFoldingRanges, cx);
+            cx.background_spawn(async move {
+                Ok(Some(
+                    ranges_task
+                        .await
+                        .into_iter()
+                        .map(|(server_id, ranges)| (server_id, ranges.into()))
+                        .collect(),
+                ))
+            })
+        }
+    }
+}
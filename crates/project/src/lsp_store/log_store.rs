@@ -1,12 +1,13 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{collections::VecDeque, path::PathBuf, sync::Arc, time::Instant};
 
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use futures::{StreamExt, channel::mpsc};
 use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Subscription, WeakEntity};
 use lsp::{
     IoKind, LanguageServer, LanguageServerId, LanguageServerName, LanguageServerSelector,
-    MessageType, TraceValue,
+    LspTranscript, MessageDirection, MessageType, TraceValue, TranscriptFile,
 };
+use parking_lot::Mutex;
 use rpc::proto;
 use settings::WorktreeId;
 
@@ -43,12 +44,20 @@ pub struct LogStore {
     pub copilot_log_subscription: Option<lsp::Subscription>,
     pub language_servers: HashMap<LanguageServerId, LanguageServerState>,
     io_tx: mpsc::UnboundedSender<(LanguageServerId, IoKind, String)>,
+    recorded_servers: Option<HashSet<LanguageServerName>>,
+    session_recordings: HashMap<LanguageServerId, SessionRecording>,
 }
 
 struct ProjectState {
     _subscriptions: [Subscription; 2],
 }
 
+/// The transcript of a language server session being recorded, from the `initialize` request on.
+struct SessionRecording {
+    transcript: Arc<Mutex<LspTranscript>>,
+    _io_subscription: lsp::Subscription,
+}
+
 pub trait Message: AsRef<str> {
     type Level: Copy + std::fmt::Debug;
     fn should_include(&self, _: Self::Level) -> bool {
@@ -223,6 +232,8 @@ impl LogStore {
             copilot_log_subscription: None,
             on_headless_host,
             io_tx,
+            recorded_servers: None,
+            session_recordings: HashMap::default(),
         };
         cx.spawn(async move |log_store, cx| {
             while let Some((server_id, io_kind, message)) = io_rx.next().await {
@@ -354,6 +365,63 @@ impl LogStore {
         );
     }
 
+    /// Starts recording the sessions of the given language servers that start from now on, until [`Self::stop_recording_sessions`].
+    pub fn start_recording_sessions(&mut self, servers: HashSet<LanguageServerName>) {
+        self.recorded_servers = Some(servers);
+    }
+
+    pub fn is_recording_sessions(&self) -> bool {
+        self.recorded_servers.is_some()
+    }
+
+    /// Stops recording sessions and returns the transcripts recorded so far, along with the servers they were recorded for.
+    pub fn stop_recording_sessions(&mut self) -> Vec<(LanguageServerId, LspTranscript)> {
+        self.recorded_servers = None;
+        self.session_recordings
+            .drain()
+            .map(|(server_id, recording)| {
+                let transcript = std::mem::take(&mut *recording.transcript.lock());
+                (server_id, transcript)
+            })
+            .filter(|(_, transcript)| !transcript.messages.is_empty())
+            .collect()
+    }
+
+    fn record_session(
+        &mut self,
+        server: &LanguageServer,
+        root_path: PathBuf,
+        files: Vec<TranscriptFile>,
+    ) {
+        let transcript = Arc::new(Mutex::new(LspTranscript::new(
+            server.name().to_string(),
+            root_path,
+            files,
+        )));
+
+        let started_at = Instant::now();
+        let io_subscription = server.on_io({
+            let transcript = transcript.clone();
+            move |io_kind, message| {
+                let direction = match io_kind {
+                    IoKind::StdIn => MessageDirection::Sent,
+                    IoKind::StdOut => MessageDirection::Received,
+                    IoKind::StdErr => return,
+                };
+                transcript
+                    .lock()
+                    .push_message(started_at.elapsed(), direction, message);
+            }
+        });
+        self.session_recordings.insert(
+            server.server_id(),
+            SessionRecording {
+                transcript,
+                _io_subscription: io_subscription,
+            },
+        );
+    }
+
     pub fn get_language_server_state(
         &mut self,
         id: LanguageServerId,
@@ -714,3 +782,50 @@ impl LogStore {
         }
     }
 }
+
+impl LspStore {
+    /// Starts recording the session of a local language server before its initialization, if its sessions are being recorded.
+    pub(super) fn record_language_server_session(
+        &self,
+        server: &LanguageServer,
+        worktree_id: WorktreeId,
+        cx: &mut App,
+    ) {
+        let Some(log_store) = cx
+            .try_global::<GlobalLogStore>()
+            .map(|log_store| log_store.0.clone())
+            .filter(|log_store| {
+                log_store
+                    .read(cx)
+                    .recorded_servers
+                    .as_ref()
+                    .is_some_and(|servers| servers.contains(&server.name()))
+            })
+        else {
+            return;
+        };
+        let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+        else {
+            return;
+        };
+        let root_path = worktree.read(cx).abs_path().to_path_buf();
+        let files = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter_map(|buffer| {
+                let project_path = buffer.read(cx).project_path(cx)?;
+                (project_path.worktree_id == worktree_id).then(|| TranscriptFile {
+                    path: project_path.path.as_std_path().to_path_buf(),
+                    text: buffer.read(cx).text(),
+                })
+            })
+            .collect();
+        log_store.update(cx, |log_store, _| {
+            log_store.record_session(server, root_path, files)
+        });
+    }
+}
@@ -0,0 +1,494 @@
+//! Synchronization of notebook documents with the language servers supporting `notebookDocument/*`.
+//!
+//! The cells of a notebook are edited in buffers without files, so they are not opened in the language servers like
+//! the buffers of the project: each cell is a text document of its own, identified by a URI within its notebook.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr as _,
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::{Context, Entity, Subscription, Task};
+use language::{
+    Buffer, BufferEvent, DiagnosticSourceKind, Language, ToPointUtf16 as _, point_to_lsp,
+};
+use lsp::LanguageServerId;
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    Hover, ProjectPath,
+    lsp_command::{GetHover, LspCommand as _, file_path_to_lsp_url},
+    lsp_store::{LanguageServerState, LspBufferSnapshot, LspStore, content_changes_since},
+};
+
+/// A cell of a notebook, edited in a buffer of its own.
+pub struct NotebookCellBuffer {
+    /// The identifier of the cell, unique within its notebook.
+    pub id: String,
+    pub kind: lsp::NotebookCellKind,
+    pub buffer: Entity<Buffer>,
+}
+
+#[derive(Default)]
+pub(super) struct OpenNotebooks {
+    notebooks: HashMap<lsp::Uri, OpenNotebook>,
+    /// The URI of the notebook of each cell buffer.
+    cells: HashMap<BufferId, lsp::Uri>,
+}
+
+struct OpenNotebook {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    language: Arc<Language>,
+    notebook_type: String,
+    version: i32,
+    cells: Vec<OpenNotebookCell>,
+    /// The servers the notebook is open in.
+    language_servers: HashSet<LanguageServerId>,
+}
+
+struct OpenNotebookCell {
+    uri: lsp::Uri,
+    kind: lsp::NotebookCellKind,
+    buffer: Entity<Buffer>,
+    _subscription: Subscription,
+}
+
+impl OpenNotebooks {
+    pub(super) fn remove_language_server(&mut self, server_id: LanguageServerId) {
+        for notebook in self.notebooks.values_mut() {
+            notebook.language_servers.remove(&server_id);
+        }
+    }
+}
+
+impl LspStore {
+    /// Opens a notebook document in the language servers of its language that support notebooks, and keeps them
+    /// updated with the edits of its cells until it is closed.
+    pub fn open_notebook_document(
+        &mut self,
+        abs_path: &Path,
+        notebook_type: impl Into<String>,
+        language: Arc<Language>,
+        cells: Vec<NotebookCellBuffer>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.mode.is_local(),
+            "notebook documents are only synchronized with local language servers"
+        );
+        let (worktree, path) = self
+            .worktree_store
+            .read(cx)
+            .find_worktree(abs_path, cx)
+            .with_context(|| format!("no worktree found for notebook {abs_path:?}"))?;
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path,
+        };
+        let notebook_uri = file_path_to_lsp_url(abs_path)?;
+        // Cell URIs follow the scheme of the notebook cells of VS Code, which language servers already expect.
+        let cell_uri_prefix = notebook_uri
+            .as_str()
+            .replacen("file:", "vscode-notebook-cell:", 1);
+        let cells = cells
+            .into_iter()
+            .map(|cell| {
+                let uri = lsp::Uri::from_str(&format!("{cell_uri_prefix}#{}", cell.id))
+                    .ok()
+                    .with_context(|| format!("invalid notebook cell id {:?}", cell.id))?;
+                let subscription = cx.subscribe(&cell.buffer, |lsp_store, buffer, event, cx| {
+                    if let BufferEvent::Edited = event {
+                        lsp_store.on_notebook_cell_edited(buffer, cx);
+                    }
+                });
+                anyhow::Ok(OpenNotebookCell {
+                    uri,
+                    kind: cell.kind,
+                    buffer: cell.buffer,
+                    _subscription: subscription,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.close_notebook_document(abs_path, cx);
+        let Some(local) = self.as_local_mut() else {
+            return Ok(());
+        };
+        for cell in &cells {
+            local
+                .notebooks
+                .cells
+                .insert(cell.buffer.read(cx).remote_id(), notebook_uri.clone());
+        }
+        local.notebooks.notebooks.insert(
+            notebook_uri.clone(),
+            OpenNotebook {
+                abs_path: abs_path.to_path_buf(),
+                project_path,
+                language,
+                notebook_type: notebook_type.into(),
+                version: 0,
+                cells,
+                language_servers: HashSet::default(),
+            },
+        );
+        self.open_notebook_in_language_servers(&notebook_uri, cx);
+        Ok(())
+    }
+
+    /// Closes a notebook document in the language servers it was opened in.
+    pub fn close_notebook_document(&mut self, abs_path: &Path, cx: &mut Context<Self>) {
+        let Some(notebook_uri) = file_path_to_lsp_url(abs_path).log_err() else {
+            return;
+        };
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(notebook) = local.notebooks.notebooks.remove(&notebook_uri) else {
+            return;
+        };
+        for cell in &notebook.cells {
+            let buffer_id = cell.buffer.read(cx).remote_id();
+            local.notebooks.cells.remove(&buffer_id);
+            local.buffer_snapshots.remove(&buffer_id);
+        }
+        for server_id in &notebook.language_servers {
+            let Some(server) = local.language_server_for_id(*server_id) else {
+                continue;
+            };
+            server
+                .notify::<lsp::notification::DidCloseNotebookDocument>(
+                    lsp::DidCloseNotebookDocumentParams {
+                        notebook_document: lsp::NotebookDocumentIdentifier {
+                            uri: notebook_uri.clone(),
+                        },
+                        cell_text_documents: notebook
+                            .cells
+                            .iter()
+                            .map(|cell| lsp::TextDocumentIdentifier::new(cell.uri.clone()))
+                            .collect(),
+                    },
+                )
+                .ok();
+        }
+    }
+
+    /// Opens the notebook in the servers of its language that it is not open in yet, starting them if needed.
+    pub(super) fn open_notebook_in_language_servers(
+        &mut self,
+        notebook_uri: &lsp::Uri,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(notebook) = local.notebooks.notebooks.get(notebook_uri) else {
+            return;
+        };
+        let Some(worktree) = local
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(notebook.project_path.worktree_id, cx)
+        else {
+            return;
+        };
+        let path = notebook
+            .project_path
+            .path
+            .parent()
+            .map(Arc::from)
+            .unwrap_or_else(|| notebook.project_path.path.clone());
+        let language = notebook.language.clone();
+        let servers_and_adapters =
+            local.language_servers_for_path(&worktree, path, &language, &HashSet::default(), cx);
+
+        let Some(notebook) = local.notebooks.notebooks.get_mut(notebook_uri) else {
+            return;
+        };
+        for (server, adapter) in servers_and_adapters {
+            if server.capabilities().notebook_document_sync.is_none()
+                || !notebook.language_servers.insert(server.server_id())
+            {
+                continue;
+            }
+            let language_id = adapter.language_id(&language.name());
+            let mut cell_text_documents = Vec::with_capacity(notebook.cells.len());
+            for cell in &notebook.cells {
+                let buffer = cell.buffer.read(cx);
+                let snapshot = buffer.text_snapshot();
+                cell_text_documents.push(lsp::TextDocumentItem::new(
+                    cell.uri.clone(),
+                    language_id.clone(),
+                    0,
+                    snapshot.text(),
+                ));
+                local
+                    .buffer_snapshots
+                    .entry(buffer.remote_id())
+                    .or_default()
+                    .insert(
+                        server.server_id(),
+                        vec![LspBufferSnapshot {
+                            version: 0,
+                            snapshot,
+                        }],
+                    );
+            }
+            server
+                .notify::<lsp::notification::DidOpenNotebookDocument>(
+                    lsp::DidOpenNotebookDocumentParams {
+                        notebook_document: lsp::NotebookDocument {
+                            uri: notebook_uri.clone(),
+                            notebook_type: notebook.notebook_type.clone(),
+                            version: notebook.version,
+                            metadata: None,
+                            cells: notebook
+                                .cells
+                                .iter()
+                                .map(|cell| lsp::NotebookCell {
+                                    kind: cell.kind,
+                                    document: cell.uri.clone(),
+                                    metadata: None,
+                                    execution_summary: None,
+                                })
+                                .collect(),
+                        },
+                        cell_text_documents,
+                    },
+                )
+                .ok();
+        }
+    }
+
+    /// Opens the open notebooks in the servers that started running since they were opened.
+    pub(super) fn open_notebooks_in_language_servers(&mut self, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let notebook_uris = local
+            .notebooks
+            .notebooks
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        for notebook_uri in notebook_uris {
+            self.open_notebook_in_language_servers(&notebook_uri, cx);
+        }
+    }
+
+    fn on_notebook_cell_edited(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(notebook_uri) = local.notebooks.cells.get(&buffer_id) else {
+            return;
+        };
+        let Some(notebook) = local.notebooks.notebooks.get_mut(notebook_uri) else {
+            return;
+        };
+        let Some(cell) = notebook
+            .cells
+            .iter()
+            .find(|cell| cell.buffer.read(cx).remote_id() == buffer_id)
+        else {
+            return;
+        };
+
+        notebook.version += 1;
+        let next_snapshot = buffer.text_snapshot();
+        for server_id in &notebook.language_servers {
+            let Some(LanguageServerState::Running { server, .. }) =
+                local.language_servers.get(server_id)
+            else {
+                continue;
+            };
+            let Some(snapshots) = local
+                .buffer_snapshots
+                .get_mut(&buffer_id)
+                .and_then(|snapshots| snapshots.get_mut(server_id))
+            else {
+                continue;
+            };
+            let Some(previous_snapshot) = snapshots.last() else {
+                continue;
+            };
+            let Some(changes) = content_changes_since(server, buffer, &previous_snapshot.snapshot)
+            else {
+                continue;
+            };
+            let next_version = previous_snapshot.version + 1;
+            snapshots.push(LspBufferSnapshot {
+                version: next_version,
+                snapshot: next_snapshot.clone(),
+            });
+
+            server
+                .notify::<lsp::notification::DidChangeNotebookDocument>(
+                    lsp::DidChangeNotebookDocumentParams {
+                        notebook_document: lsp::VersionedNotebookDocumentIdentifier {
+                            version: notebook.version,
+                            uri: notebook_uri.clone(),
+                        },
+                        change: lsp::NotebookDocumentChangeEvent {
+                            metadata: None,
+                            cells: Some(lsp::NotebookDocumentCellChange {
+                                structure: None,
+                                data: None,
+                                text_content: Some(vec![lsp::NotebookDocumentChangeTextContent {
+                                    document: lsp::VersionedTextDocumentIdentifier::new(
+                                        cell.uri.clone(),
+                                        next_version,
+                                    ),
+                                    changes,
+                                }]),
+                            }),
+                        },
+                    },
+                )
+                .ok();
+        }
+    }
+
+    /// The buffer of the notebook cell with the URI, if it is a cell of an open notebook.
+    pub(super) fn notebook_cell_buffer(&self, uri: &lsp::Uri) -> Option<Entity<Buffer>> {
+        let local = self.as_local()?;
+        local.notebooks.notebooks.values().find_map(|notebook| {
+            notebook
+                .cells
+                .iter()
+                .find(|cell| &cell.uri == uri)
+                .map(|cell| cell.buffer.clone())
+        })
+    }
+
+    /// Applies diagnostics published by a server for a notebook cell to the buffer of the cell.
+    pub(super) fn update_notebook_cell_diagnostics(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        diagnostics: lsp::PublishDiagnosticsParams,
+        disk_based_sources: &[String],
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let notebook_abs_path = self
+            .as_local()
+            .and_then(|local| {
+                let notebook_uri = local.notebooks.cells.get(&buffer_id)?;
+                Some(
+                    local
+                        .notebooks
+                        .notebooks
+                        .get(notebook_uri)?
+                        .abs_path
+                        .clone(),
+                )
+            })
+            .context("the buffer is not a cell of an open notebook")?;
+        let version = diagnostics.version;
+        let diagnostics = self.lsp_to_document_diagnostics(
+            notebook_abs_path,
+            DiagnosticSourceKind::Pushed,
+            server_id,
+            diagnostics,
+            disk_based_sources,
+            None,
+        );
+        self.as_local_mut()
+            .context("notebook documents are only synchronized with local language servers")?
+            .update_buffer_diagnostics(
+                buffer,
+                server_id,
+                None,
+                None,
+                version,
+                diagnostics.diagnostics,
+                Vec::new(),
+                cx,
+            )
+    }
+
+    /// Requests the hovers of a position in a notebook cell from the servers its notebook is open in.
+    pub fn notebook_cell_hover(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Hover>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Vec::new());
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((notebook, cell)) = local
+            .notebooks
+            .cells
+            .get(&buffer_id)
+            .and_then(|notebook_uri| local.notebooks.notebooks.get(notebook_uri))
+            .and_then(|notebook| {
+                let cell = notebook
+                    .cells
+                    .iter()
+                    .find(|cell| cell.buffer.read(cx).remote_id() == buffer_id)?;
+                Some((notebook, cell))
+            })
+        else {
+            return Task::ready(Vec::new());
+        };
+
+        let position = position.to_point_utf16(&buffer.read(cx).snapshot());
+        let params = lsp::HoverParams {
+            text_document_position_params: lsp::TextDocumentPositionParams::new(
+                lsp::TextDocumentIdentifier::new(cell.uri.clone()),
+                point_to_lsp(position),
+            ),
+            work_done_progress_params: Default::default(),
+        };
+        let servers = notebook
+            .language_servers
+            .iter()
+            .filter_map(|server_id| local.language_server_for_id(*server_id))
+            .filter(|server| match server.capabilities().hover_provider {
+                Some(lsp::HoverProviderCapability::Simple(enabled)) => enabled,
+                Some(lsp::HoverProviderCapability::Options(_)) => true,
+                None => false,
+            })
+            .collect::<Vec<_>>();
+        let buffer = buffer.clone();
+        cx.spawn(async move |lsp_store, cx| {
+            let Some(lsp_store) = lsp_store.upgrade() else {
+                return Vec::new();
+            };
+            let mut hovers = Vec::new();
+            for server in servers {
+                let Some(response) = server
+                    .request::<lsp::request::HoverRequest>(params.clone())
+                    .await
+                    .into_response()
+                    .log_err()
+                else {
+                    continue;
+                };
+                let hover = GetHover { position }
+                    .response_from_lsp(
+                        response,
+                        lsp_store.clone(),
+                        buffer.clone(),
+                        server.server_id(),
+                        cx.clone(),
+                    )
+                    .await
+                    .log_err()
+                    .flatten();
+                hovers.extend(hover);
+            }
+            hovers
+        })
+    }
+}
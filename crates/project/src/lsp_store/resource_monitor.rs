@@ -0,0 +1,393 @@
+//! Sampling the resource usage of local language servers, and restarting the ones exceeding their restart policy.
+
+use std::time::{Duration, Instant};
+
+use collections::{HashMap, HashSet};
+use gpui::{App, AppContext as _, Context, Entity, Task};
+use language::Buffer;
+use lsp::{LanguageServerId, LanguageServerName, LanguageServerSelector, RequestStats};
+use settings::{LanguageServerRestartPolicyContent, Settings as _, SettingsLocation, WorktreeId};
+use util::rel_path::RelPath;
+
+use crate::{
+    lsp_store::{LanguageServerState, LspStore, LspStoreEvent},
+    project_settings::ProjectSettings,
+};
+
+const SAMPLING_INTERVAL: Duration = Duration::from_secs(5);
+/// How many times a server is restarted by its policy before it's left running.
+const MAX_POLICY_RESTARTS: u32 = 3;
+/// How long a server restarted by its policy runs before it's restarted again, doubled after each restart.
+const POLICY_RESTART_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// The resource usage of a local language server.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LanguageServerResources {
+    /// The resident memory of the server process, in bytes.
+    pub memory: Option<u64>,
+    /// The CPU usage of the server process since the previous sample, in percent of a single core.
+    pub cpu_usage: Option<f32>,
+    pub requests: RequestStats,
+}
+
+pub(super) struct ResourceMonitor {
+    resources: HashMap<LanguageServerId, LanguageServerResources>,
+    /// The CPU time of each server process at the previous sample.
+    cpu_times: HashMap<LanguageServerId, (Instant, Duration)>,
+    /// The servers restarted by their policy, whose documents need their diagnostics pulled once they run again.
+    pub(super) restarted_servers: HashSet<(WorktreeId, LanguageServerName)>,
+    /// How many times each server was restarted by its policy, and when it was last restarted.
+    policy_restarts: HashMap<(WorktreeId, LanguageServerName), (u32, Instant)>,
+    /// Whether the resources are displayed, in which case they're sampled even without a restart policy.
+    resources_visible: bool,
+    _sampling: Task<()>,
+}
+
+impl ResourceMonitor {
+    pub(super) fn new(cx: &mut Context<LspStore>) -> Self {
+        let sampling = cx.spawn(async move |lsp_store, cx| {
+            loop {
+                cx.background_executor().timer(SAMPLING_INTERVAL).await;
+                let Ok(processes) =
+                    lsp_store.update(cx, |lsp_store, cx| lsp_store.language_servers_to_sample(cx))
+                else {
+                    break;
+                };
+                let Some(processes) = processes else {
+                    continue;
+                };
+                let samples = cx
+                    .background_spawn(async move {
+                        processes
+                            .into_iter()
+                            .map(|(server_id, process_id)| {
+                                (server_id, process_id.and_then(sample_process))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .await;
+                if lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.update_language_server_resources(samples, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self {
+            resources: HashMap::default(),
+            cpu_times: HashMap::default(),
+            restarted_servers: HashSet::default(),
+            policy_restarts: HashMap::default(),
+            resources_visible: false,
+            _sampling: sampling,
+        }
+    }
+}
+
+impl LspStore {
+    /// The resource usage of a local language server, as of the last sample.
+    pub fn language_server_resources(
+        &self,
+        server_id: LanguageServerId,
+    ) -> Option<LanguageServerResources> {
+        self.as_local()?
+            .resource_monitor
+            .resources
+            .get(&server_id)
+            .copied()
+    }
+
+    /// Sets whether the resources of the language servers are displayed, so that they're sampled
+    /// even when no restart policy needs them.
+    pub fn set_language_server_resources_visible(&mut self, visible: bool) {
+        if let Some(local) = self.as_local_mut() {
+            local.resource_monitor.resources_visible = visible;
+        }
+    }
+
+    /// The running servers to sample, along with their process, or `None` when no sample is needed.
+    fn language_servers_to_sample(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Option<Vec<(LanguageServerId, Option<u32>)>> {
+        let local = self.as_local_mut()?;
+        let mut has_policy = false;
+        let mut processes = Vec::new();
+        for (server_id, state) in &local.language_servers {
+            let LanguageServerState::Running { server, .. } = state else {
+                continue;
+            };
+            has_policy |= local
+                .language_server_ids
+                .iter()
+                .find_map(|(seed, state)| (state.id == *server_id).then_some(seed.worktree_id))
+                .is_some_and(|worktree_id| {
+                    restart_policy(&server.name(), worktree_id, cx)
+                        != LanguageServerRestartPolicyContent::default()
+                });
+            processes.push((
+                *server_id,
+                server.process_id().filter(|_| PROCESS_SAMPLING_SUPPORTED),
+            ));
+        }
+
+        if has_policy || local.resource_monitor.resources_visible {
+            Some(processes)
+        } else {
+            // Drop the stale samples, and start measuring the CPU usage afresh when sampling resumes.
+            let monitor = &mut local.resource_monitor;
+            monitor.cpu_times.clear();
+            if !monitor.resources.is_empty() {
+                monitor.resources.clear();
+                cx.notify();
+            }
+            None
+        }
+    }
+
+    fn update_language_server_resources(
+        &mut self,
+        samples: Vec<(LanguageServerId, Option<ProcessSample>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let now = cx.background_executor().now();
+        let monitor = &mut local.resource_monitor;
+        let server_count = monitor.resources.len();
+        monitor
+            .resources
+            .retain(|server_id, _| local.language_servers.contains_key(server_id));
+        let mut changed = monitor.resources.len() != server_count;
+        monitor
+            .cpu_times
+            .retain(|server_id, _| local.language_servers.contains_key(server_id));
+
+        let mut servers_to_restart = Vec::new();
+        for (server_id, sample) in samples {
+            let Some(LanguageServerState::Running { server, .. }) =
+                local.language_servers.get(&server_id)
+            else {
+                continue;
+            };
+            let cpu_usage = sample.and_then(|sample| {
+                let (sampled_at, cpu_time) = monitor
+                    .cpu_times
+                    .insert(server_id, (now, sample.cpu_time))?;
+                let elapsed = now.duration_since(sampled_at).as_secs_f32();
+                (elapsed > 0.).then(|| {
+                    sample.cpu_time.saturating_sub(cpu_time).as_secs_f32() / elapsed * 100.
+                })
+            });
+            let resources = LanguageServerResources {
+                memory: sample.map(|sample| sample.memory),
+                cpu_usage,
+                requests: server.request_stats(),
+            };
+            changed |= monitor.resources.insert(server_id, resources) != Some(resources);
+
+            let Some(worktree_id) = local
+                .language_server_ids
+                .iter()
+                .find_map(|(seed, state)| (state.id == server_id).then_some(seed.worktree_id))
+            else {
+                continue;
+            };
+            let policy = restart_policy(&server.name(), worktree_id, cx);
+            let Some(reason) = restart_reason(&resources, &policy) else {
+                continue;
+            };
+            let can_restart = match monitor.policy_restarts.get(&(worktree_id, server.name())) {
+                Some((restarts, _)) if *restarts >= MAX_POLICY_RESTARTS => false,
+                Some((restarts, last_restart)) => {
+                    now.saturating_duration_since(*last_restart)
+                        >= POLICY_RESTART_COOLDOWN * 2u32.pow(restarts - 1)
+                }
+                None => true,
+            };
+            if can_restart {
+                servers_to_restart.push((server_id, reason));
+            }
+        }
+
+        for (server_id, reason) in servers_to_restart {
+            self.restart_language_server_by_policy(server_id, reason, now, cx);
+        }
+        if changed {
+            cx.notify();
+        }
+    }
+
+    /// Restarts the server in place, reopening its documents in the new server.
+    fn restart_language_server_by_policy(
+        &mut self,
+        server_id: LanguageServerId,
+        reason: String,
+        now: Instant,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(seed) = local
+            .language_server_ids
+            .iter()
+            .find_map(|(seed, state)| (state.id == server_id).then(|| seed.clone()))
+        else {
+            return;
+        };
+        log::warn!("Restarting language server {}: {reason}", seed.name);
+        local
+            .resource_monitor
+            .restarted_servers
+            .insert((seed.worktree_id, seed.name.clone()));
+        let (restarts, last_restart) = local
+            .resource_monitor
+            .policy_restarts
+            .entry((seed.worktree_id, seed.name.clone()))
+            .or_insert((0, now));
+        *restarts += 1;
+        *last_restart = now;
+        let restarts = *restarts;
+        local.resource_monitor.resources.remove(&server_id);
+        local.resource_monitor.cpu_times.remove(&server_id);
+        let buffers_opened_in_server = local
+            .buffers_opened_in_servers
+            .iter()
+            .filter(|(_, servers)| servers.contains(&server_id))
+            .map(|(buffer_id, _)| *buffer_id)
+            .collect::<HashSet<_>>();
+        let buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| buffers_opened_in_server.contains(&buffer.read(cx).remote_id()))
+            .collect::<Vec<Entity<Buffer>>>();
+        let mut notification = format!("Restarted language server {}: {reason}", seed.name);
+        if restarts >= MAX_POLICY_RESTARTS {
+            notification.push_str(&format!(
+                ". It was restarted {restarts} times, and won't be restarted automatically again"
+            ));
+        }
+        cx.emit(LspStoreEvent::Notification(notification));
+
+        let stop = self.stop_local_language_servers_for_buffers(
+            &[],
+            HashSet::from_iter([LanguageServerSelector::Id(server_id)]),
+            cx,
+        );
+        cx.spawn(async move |lsp_store, cx| {
+            stop.await;
+            lsp_store.update(cx, |lsp_store, cx| {
+                for buffer in buffers {
+                    lsp_store.register_buffer_with_language_servers(
+                        &buffer,
+                        HashSet::from_iter([LanguageServerSelector::Name(seed.name.clone())]),
+                        true,
+                        cx,
+                    );
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn restart_policy(
+    server_name: &LanguageServerName,
+    worktree_id: WorktreeId,
+    cx: &App,
+) -> LanguageServerRestartPolicyContent {
+    let settings = ProjectSettings::get(
+        Some(SettingsLocation {
+            worktree_id,
+            path: RelPath::empty(),
+        }),
+        cx,
+    );
+    let global_policy = settings.global_lsp_settings.restart_policy;
+    let server_policy = settings
+        .lsp
+        .get(server_name)
+        .and_then(|settings| settings.restart_policy)
+        .unwrap_or_default();
+    LanguageServerRestartPolicyContent {
+        max_memory_mb: server_policy.max_memory_mb.or(global_policy.max_memory_mb),
+        max_timed_out_requests: server_policy
+            .max_timed_out_requests
+            .or(global_policy.max_timed_out_requests),
+    }
+}
+
+fn restart_reason(
+    resources: &LanguageServerResources,
+    policy: &LanguageServerRestartPolicyContent,
+) -> Option<String> {
+    if let Some((memory, max_memory_mb)) = resources.memory.zip(policy.max_memory_mb) {
+        let memory_mb = memory / 1024 / 1024;
+        if memory_mb > max_memory_mb {
+            return Some(format!(
+                "its memory usage of {memory_mb} MB exceeds the {max_memory_mb} MB limit"
+            ));
+        }
+    }
+    if let Some(max_timed_out_requests) = policy.max_timed_out_requests
+        && max_timed_out_requests > 0
+        && resources.requests.consecutive_timeouts >= max_timed_out_requests
+    {
+        return Some(format!(
+            "{} of its requests timed out in a row",
+            resources.requests.consecutive_timeouts
+        ));
+    }
+    None
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ProcessSample {
+    /// The resident memory of the process, in bytes.
+    memory: u64,
+    /// The CPU time the process spent in user and kernel mode.
+    cpu_time: Duration,
+}
+
+const PROCESS_SAMPLING_SUPPORTED: bool = cfg!(target_os = "linux");
+
+#[cfg(target_os = "linux")]
+fn sample_process(process_id: u32) -> Option<ProcessSample> {
+    /// `USER_HZ`, the unit of the times in `/proc/<pid>/stat`, which is 100 on all the Linux ABIs.
+    const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+    let status = std::fs::read_to_string(format!("/proc/{process_id}/status")).ok()?;
+    let memory_kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    // The process name in the second field is parenthesized and may contain spaces, so the fields are counted from
+    // the last parenthesis, starting with the third field. `utime` and `stime` are the 14th and 15th fields.
+    let stat = std::fs::read_to_string(format!("/proc/{process_id}/stat")).ok()?;
+    let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace();
+    let user_ticks = fields.nth(11)?.parse::<u64>().ok()?;
+    let system_ticks = fields.next()?.parse::<u64>().ok()?;
+
+    Some(ProcessSample {
+        memory: memory_kb * 1024,
+        cpu_time: Duration::from_millis(
+            (user_ticks + system_ticks) * 1000 / CLOCK_TICKS_PER_SECOND,
+        ),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sample_process(_: u32) -> Option<ProcessSample> {
+    None
+}
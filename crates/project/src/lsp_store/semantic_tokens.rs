@@ -0,0 +1,621 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{Shared, join_all},
+};
+use gpui::{AppContext as _, Context, Entity, Task};
+use language::{Bias, Buffer, PointUtf16, Unclipped};
+use lsp::{LSP_REQUEST_TIMEOUT, LanguageServerId};
+use util::ResultExt as _;
+
+use crate::{
+    File,
+    lsp_command::{GetSemanticTokens, LspCommand as _, make_text_document_identifier},
+    lsp_store::{
+        LanguageServerToQuery, LspStore,
+        server_data_cache::{BufferServerData, ServerDataCache},
+    },
+};
+
+pub type SemanticTokensTask = Shared<Task<Result<BufferSemanticTokens, Arc<anyhow::Error>>>>;
+
+/// A single semantic token, with its type and modifiers indexing the legend of the server that produced it.
+#[derive(Debug, Clone)]
+pub struct SemanticToken {
+    pub range: Range<text::Anchor>,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+/// Semantic tokens of a buffer, as reported by a single language server.
+#[derive(Debug, Clone)]
+pub struct ServerSemanticTokens {
+    pub legend: Arc<lsp::SemanticTokensLegend>,
+    pub tokens: Arc<[SemanticToken]>,
+}
+
+impl ServerSemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.legend
+            .token_types
+            .get(token.token_type as usize)
+            .map(|token_type| token_type.as_str())
+    }
+
+    pub fn token_modifiers<'a>(&'a self, token: &SemanticToken) -> impl Iterator<Item = &'a str> {
+        let bitset = token.token_modifiers;
+        self.legend
+            .token_modifiers
+            .iter()
+            .take(u32::BITS as usize)
+            .enumerate()
+            .filter(move |(ix, _)| bitset & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BufferSemanticTokens {
+    pub servers: HashMap<LanguageServerId, ServerSemanticTokens>,
+    pub cache_version: Option<usize>,
+}
+
+/// Semantic tokens in the encoded form of the LSP specification, kept to apply the deltas of subsequent responses to.
+#[derive(Debug, Default, Clone)]
+pub struct RawSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<lsp::SemanticToken>,
+}
+
+impl BufferServerData for BufferSemanticTokens {
+    type ServerData = ServerSemanticTokens;
+
+    fn new(
+        servers: HashMap<LanguageServerId, Self::ServerData>,
+        cache_version: Option<usize>,
+    ) -> Self {
+        Self {
+            servers,
+            cache_version,
+        }
+    }
+}
+
+pub(super) type SemanticTokensData = ServerDataCache<BufferSemanticTokens>;
+
+/// Returns the token legend of the server, if it can report the semantic tokens of a whole document.
+pub(crate) fn semantic_tokens_legend(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<&lsp::SemanticTokensLegend> {
+    let options = semantic_tokens_options(capabilities)?;
+    match options.full.as_ref()? {
+        lsp::SemanticTokensFullOptions::Bool(supported) => supported.then_some(&options.legend),
+        lsp::SemanticTokensFullOptions::Delta { .. } => Some(&options.legend),
+    }
+}
+
+fn supports_semantic_tokens_delta(capabilities: &lsp::ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).is_some_and(|options| {
+        matches!(
+            options.full,
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    })
+}
+
+fn semantic_tokens_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+/// Resolves the relative positions of the encoded tokens into ranges.
+fn semantic_token_ranges(
+    data: &[lsp::SemanticToken],
+) -> impl Iterator<Item = (Range<PointUtf16>, &lsp::SemanticToken)> {
+    let mut row = 0;
+    let mut column = 0;
+    data.iter().map(move |token| {
+        if token.delta_line == 0 {
+            column += token.delta_start;
+        } else {
+            row += token.delta_line;
+            column = token.delta_start;
+        }
+        let start = PointUtf16::new(row, column);
+        let end = PointUtf16::new(row, column.saturating_add(token.length));
+        (start..end, token)
+    })
+}
+
+fn decode_semantic_tokens(
+    data: &[lsp::SemanticToken],
+    snapshot: &text::BufferSnapshot,
+) -> Vec<SemanticToken> {
+    semantic_token_ranges(data)
+        .filter_map(|(range, token)| {
+            let start = snapshot.clip_point_utf16(Unclipped(range.start), Bias::Left);
+            let end = snapshot.clip_point_utf16(Unclipped(range.end), Bias::Left);
+            if start >= end {
+                return None;
+            }
+            Some(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers_bitset,
+            })
+        })
+        .collect()
+}
+
+/// Applies the edits of a delta response to the tokens of the previous response.
+/// Edit offsets index the flat integer array of the specification, where every token takes five integers.
+/// The edits are all validated before any of them is applied, so `data` is left untouched on error.
+fn apply_semantic_tokens_edits(
+    data: &mut Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) -> anyhow::Result<()> {
+    edits.sort_by_key(|edit| edit.start);
+    let mut previous_end = 0;
+    for edit in &edits {
+        anyhow::ensure!(
+            edit.start % 5 == 0 && edit.delete_count % 5 == 0,
+            "semantic tokens edit does not align with token boundaries: {edit:?}"
+        );
+        let start = edit.start as usize / 5;
+        let end = start + edit.delete_count as usize / 5;
+        anyhow::ensure!(
+            end <= data.len(),
+            "semantic tokens edit is out of bounds of {} tokens: {edit:?}",
+            data.len()
+        );
+        anyhow::ensure!(
+            start >= previous_end,
+            "semantic tokens edit overlaps the previous edit: {edit:?}"
+        );
+        previous_end = end;
+    }
+
+    for edit in edits.into_iter().rev() {
+        let start = edit.start as usize / 5;
+        let end = start + edit.delete_count as usize / 5;
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+    Ok(())
+}
+
+fn apply_semantic_tokens_delta(
+    mut previous: RawSemanticTokens,
+    response: Option<lsp::SemanticTokensFullDeltaResult>,
+) -> anyhow::Result<RawSemanticTokens> {
+    Ok(match response {
+        Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => RawSemanticTokens {
+            result_id: tokens.result_id,
+            data: tokens.data,
+        },
+        Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+            apply_semantic_tokens_edits(&mut previous.data, delta.edits)?;
+            RawSemanticTokens {
+                result_id: delta.result_id,
+                data: previous.data,
+            }
+        }
+        Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+            apply_semantic_tokens_edits(&mut previous.data, edits)?;
+            RawSemanticTokens {
+                result_id: None,
+                data: previous.data,
+            }
+        }
+        None => RawSemanticTokens::default(),
+    })
+}
+
+impl LspStore {
+    /// Returns the semantic tokens of the buffer, or `None` if the caller already has the tokens of `known_cache_version`.
+    pub fn semantic_tokens(
+        &mut self,
+        known_cache_version: Option<usize>,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<SemanticTokensTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+        let current_language_servers = self
+            .buffer_servers_with_capability(buffer_id, |capabilities| {
+                semantic_tokens_legend(capabilities).is_some()
+            });
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id)
+            && let Some(cached_tokens) = &lsp_data.semantic_tokens
+            && !version_queried_for.changed_since(&lsp_data.buffer_version)
+            && cached_tokens.is_from_servers(current_language_servers.as_ref())
+        {
+            return cached_tokens.cached(known_cache_version);
+        }
+
+        let tokens_lsp_data = self
+            .latest_lsp_data(&buffer, cx)
+            .semantic_tokens
+            .get_or_insert_default();
+        if let Some(running_update) = tokens_lsp_data.running_update(&version_queried_for) {
+            return Some(running_update);
+        }
+        let buffer_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_tokens = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_semantic_tokens_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching semantic tokens")
+                    .map_err(Arc::new);
+                let fetched_tokens = match fetched_tokens {
+                    Ok(fetched_tokens) => fetched_tokens,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id)
+                                    && let Some(semantic_tokens) = &mut lsp_data.semantic_tokens
+                                {
+                                    semantic_tokens.cancel_update();
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.text_snapshot())?;
+                if snapshot.version() != &buffer_version_queried_for {
+                    return Ok(BufferSemanticTokens::default());
+                }
+                let legends = lsp_store.read_with(cx, |lsp_store, _| {
+                    fetched_tokens
+                        .iter()
+                        .flatten()
+                        .filter_map(|(server_id, _)| {
+                            let capabilities = lsp_store.lsp_server_capabilities.get(server_id)?;
+                            let legend = semantic_tokens_legend(capabilities)?;
+                            Some((*server_id, Arc::new(legend.clone())))
+                        })
+                        .collect::<HashMap<_, _>>()
+                })?;
+                let fetched_tokens = cx
+                    .background_spawn(async move {
+                        fetched_tokens.map(|fetched_tokens| {
+                            fetched_tokens
+                                .into_iter()
+                                .filter_map(|(server_id, raw_tokens)| {
+                                    let tokens = ServerSemanticTokens {
+                                        legend: legends.get(&server_id)?.clone(),
+                                        tokens: decode_semantic_tokens(&raw_tokens.data, &snapshot)
+                                            .into(),
+                                    };
+                                    Some((server_id, tokens))
+                                })
+                                .collect::<HashMap<_, _>>()
+                        })
+                    })
+                    .await;
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        lsp_data
+                            .semantic_tokens
+                            .get_or_insert_default()
+                            .finish_update(
+                                &mut lsp_data.buffer_version,
+                                buffer_version_queried_for,
+                                fetched_tokens,
+                            )
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        tokens_lsp_data.start_update(version_queried_for, new_task.clone());
+        Some(new_task)
+    }
+
+    fn fetch_semantic_tokens_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, RawSemanticTokens>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetSemanticTokens;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            return cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let tokens = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tokens_response| {
+                            let server_id = LanguageServerId::from_proto(tokens_response.server_id);
+                            let response = request.response_from_proto(
+                                tokens_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move { Some((server_id, response.await.log_err()?)) }
+                        }),
+                )
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+                Ok(Some(tokens))
+            });
+        }
+
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(None));
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let abs_path = File::from_dyn(buffer.read(cx).file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx));
+        let servers = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .map(|(_, server)| server.clone())
+                .filter(|server| {
+                    local
+                        .buffers_opened_in_servers
+                        .get(&buffer_id)
+                        .is_some_and(|servers| servers.contains(&server.server_id()))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let servers = servers
+            .into_iter()
+            .filter_map(|server| {
+                let capabilities = server.capabilities();
+                semantic_tokens_legend(&capabilities)?;
+                let previous_tokens = local
+                    .semantic_tokens_results
+                    .get(&buffer_id)
+                    .and_then(|results| results.get(&server.server_id()))
+                    .filter(|previous_tokens| {
+                        previous_tokens.result_id.is_some()
+                            && supports_semantic_tokens_delta(&capabilities)
+                    })
+                    .cloned();
+                Some((server, previous_tokens))
+            })
+            .collect::<Vec<_>>();
+
+        let mut requests = Vec::with_capacity(servers.len());
+        for (server, previous_tokens) in servers {
+            let server_id = server.server_id();
+            let request = match previous_tokens.zip(abs_path.as_deref()) {
+                Some((previous_tokens, abs_path)) => {
+                    let text_document = make_text_document_identifier(abs_path);
+                    cx.spawn(async move |_, _| {
+                        let params = lsp::SemanticTokensDeltaParams {
+                            text_document: text_document?,
+                            previous_result_id: previous_tokens
+                                .result_id
+                                .clone()
+                                .unwrap_or_default(),
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        };
+                        let response = server
+                            .request::<lsp::request::SemanticTokensFullDeltaRequest>(params)
+                            .await
+                            .into_response()?;
+                        apply_semantic_tokens_delta(previous_tokens, response)
+                    })
+                }
+                None => self.request_lsp(
+                    buffer.clone(),
+                    LanguageServerToQuery::Other(server_id),
+                    GetSemanticTokens,
+                    cx,
+                ),
+            };
+            requests.push(async move { (server_id, request.await) });
+        }
+
+        cx.spawn(async move |lsp_store, cx| {
+            let mut tokens = HashMap::default();
+            let mut failed_servers = Vec::new();
+            for (server_id, response) in join_all(requests).await {
+                match response {
+                    Ok(response) => {
+                        tokens.insert(server_id, response);
+                    }
+                    // rust-analyzer likes to error with this when its still loading up
+                    Err(e) if format!("{e:#}").ends_with("content modified") => {
+                        failed_servers.push(server_id);
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "Failed to fetch semantic tokens from server {server_id}: {e:#}"
+                        );
+                        failed_servers.push(server_id);
+                    }
+                }
+            }
+
+            lsp_store.update(cx, |lsp_store, _| {
+                let Some(local) = lsp_store.as_local_mut() else {
+                    return;
+                };
+                if !local.registered_buffers.contains_key(&buffer_id) {
+                    return;
+                }
+                let buffer_results = local.semantic_tokens_results.entry(buffer_id).or_default();
+                for server_id in failed_servers {
+                    buffer_results.remove(&server_id);
+                }
+                buffer_results.extend(
+                    tokens
+                        .iter()
+                        .map(|(server_id, tokens)| (*server_id, tokens.clone())),
+                );
+            })?;
+            Ok(Some(tokens))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        token_type: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_semantic_token_ranges() {
+        let data = vec![token(2, 5, 3, 0), token(0, 5, 4, 1), token(3, 2, 7, 2)];
+        let ranges = semantic_token_ranges(&data)
+            .map(|(range, token)| (range, token.token_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![
+                (PointUtf16::new(2, 5)..PointUtf16::new(2, 8), 0),
+                (PointUtf16::new(2, 10)..PointUtf16::new(2, 14), 1),
+                (PointUtf16::new(5, 2)..PointUtf16::new(5, 9), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_semantic_tokens_edits() {
+        let mut data = vec![token(0, 0, 1, 0), token(1, 0, 1, 1), token(1, 0, 1, 2)];
+        apply_semantic_tokens_edits(
+            &mut data,
+            vec![
+                lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 5,
+                    data: Some(vec![token(1, 0, 1, 3), token(1, 0, 1, 4)]),
+                },
+                lsp::SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 5,
+                    data: None,
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            data,
+            vec![token(1, 0, 1, 1), token(1, 0, 1, 3), token(1, 0, 1, 4)]
+        );
+
+        assert!(
+            apply_semantic_tokens_edits(
+                &mut data,
+                vec![lsp::SemanticTokensEdit {
+                    start: 3,
+                    delete_count: 5,
+                    data: None,
+                }],
+            )
+            .is_err()
+        );
+        assert!(
+            apply_semantic_tokens_edits(
+                &mut data,
+                vec![lsp::SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 10,
+                    data: None,
+                }],
+            )
+            .is_err()
+        );
+
+        // An invalid edit leaves the tokens as they were, even when it comes after valid ones.
+        assert!(
+            apply_semantic_tokens_edits(
+                &mut data,
+                vec![
+                    lsp::SemanticTokensEdit {
+                        start: 0,
+                        delete_count: 5,
+                        data: None,
+                    },
+                    lsp::SemanticTokensEdit {
+                        start: 15,
+                        delete_count: 5,
+                        data: None,
+                    },
+                ],
+            )
+            .is_err()
+        );
+        assert!(
+            apply_semantic_tokens_edits(
+                &mut data,
+                vec![
+                    lsp::SemanticTokensEdit {
+                        start: 0,
+                        delete_count: 10,
+                        data: None,
+                    },
+                    lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: None,
+                    },
+                ],
+            )
+            .is_err()
+        );
+        assert_eq!(
+            data,
+            vec![token(1, 0, 1, 1), token(1, 0, 1, 3), token(1, 0, 1, 4)]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! The cache that semantic tokens and folding ranges share for the data that every language server reports for a whole buffer.
+//! The data is kept for a single buffer version, refetched from all servers at once, and versioned
+//! so that callers holding the latest data can skip redundant updates.
+
+use std::{fmt, sync::Arc};
+
+use clock::Global;
+use collections::{HashMap, HashSet};
+use futures::{FutureExt as _, future::Shared};
+use gpui::Task;
+use lsp::LanguageServerId;
+
+use crate::lsp_store::LspStore;
+
+pub(super) type ServerDataTask<D> = Shared<Task<Result<D, Arc<anyhow::Error>>>>;
+
+/// The data of a buffer that is handed out to callers, along with the cache version it corresponds to.
+pub(super) trait BufferServerData: Clone + fmt::Debug + 'static {
+    type ServerData: Clone + fmt::Debug;
+
+    fn new(
+        servers: HashMap<LanguageServerId, Self::ServerData>,
+        cache_version: Option<usize>,
+    ) -> Self;
+}
+
+#[derive(Debug)]
+pub(super) struct ServerDataCache<D: BufferServerData> {
+    servers: HashMap<LanguageServerId, D::ServerData>,
+    cache_version: usize,
+    update: Option<(Global, ServerDataTask<D>)>,
+}
+
+impl<D: BufferServerData> Default for ServerDataCache<D> {
+    fn default() -> Self {
+        Self {
+            servers: HashMap::default(),
+            cache_version: 0,
+            update: None,
+        }
+    }
+}
+
+impl<D: BufferServerData> ServerDataCache<D> {
+    pub(super) fn remove_server_data(&mut self, for_server: LanguageServerId) {
+        if self.servers.remove(&for_server).is_some() {
+            self.cache_version += 1;
+        }
+    }
+
+    /// Whether the cached data came from exactly the given servers.
+    /// Remote projects don't know the servers of the host, and pass `None` to trust the cache.
+    pub(super) fn is_from_servers(
+        &self,
+        current_servers: Option<&HashSet<LanguageServerId>>,
+    ) -> bool {
+        current_servers.is_none_or(|current_servers| {
+            current_servers.len() == self.servers.len()
+                && current_servers
+                    .iter()
+                    .all(|server_id| self.servers.contains_key(server_id))
+        })
+    }
+
+    /// Returns the cached data, or `None` if the caller already has the data of `known_cache_version`.
+    pub(super) fn cached(&self, known_cache_version: Option<usize>) -> Option<ServerDataTask<D>> {
+        if known_cache_version == Some(self.cache_version) {
+            return None;
+        }
+        Some(Task::ready(Ok(self.data())).shared())
+    }
+
+    /// Returns the update that is already fetching the data of `version`, or a newer one.
+    pub(super) fn running_update(&self, version: &Global) -> Option<ServerDataTask<D>> {
+        let (updating_for, running_update) = self.update.as_ref()?;
+        (!version.changed_since(updating_for)).then(|| running_update.clone())
+    }
+
+    pub(super) fn start_update(&mut self, version: Global, task: ServerDataTask<D>) {
+        self.update = Some((version, task));
+    }
+
+    pub(super) fn cancel_update(&mut self) {
+        self.update = None;
+    }
+
+    /// Stores the data fetched for `version_queried_for` and finishes the running update.
+    ///
+    /// Data fetched for the cached buffer version is merged into the cache, data of a newer buffer version replaces it,
+    /// and data of an outdated buffer version is dropped.
+    pub(super) fn finish_update(
+        &mut self,
+        buffer_version: &mut Global,
+        version_queried_for: Global,
+        fetched: Option<HashMap<LanguageServerId, D::ServerData>>,
+    ) -> D {
+        if let Some(fetched) = fetched {
+            if *buffer_version == version_queried_for {
+                self.servers.extend(fetched);
+                self.cache_version += 1;
+            } else if !buffer_version.changed_since(&version_queried_for) {
+                *buffer_version = version_queried_for;
+                self.servers = fetched;
+                self.cache_version += 1;
+            }
+        }
+        self.update = None;
+        self.data()
+    }
+
+    fn data(&self) -> D {
+        D::new(self.servers.clone(), Some(self.cache_version))
+    }
+}
+
+impl LspStore {
+    /// Returns the servers that the buffer is opened in and have the capability, or `None` in remote projects.
+    pub(super) fn buffer_servers_with_capability(
+        &self,
+        buffer_id: text::BufferId,
+        has_capability: impl Fn(&lsp::ServerCapabilities) -> bool,
+    ) -> Option<HashSet<LanguageServerId>> {
+        let local = self.as_local()?;
+        Some(
+            local
+                .buffers_opened_in_servers
+                .get(&buffer_id)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|server_id| {
+                    self.lsp_server_capabilities
+                        .get(server_id)
+                        .is_some_and(&has_capability)
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+//! Diagnostics found in the output of tasks by their problem matchers.
+//!
+//! They are stored like the diagnostics of a language server, under an id allocated for each task,
+//! so that the diagnostics of a task are replaced as a whole when it reports new ones, e.g. on a rerun.
+
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::{Context, SharedString};
+use language::DiagnosticSourceKind;
+use lsp::LanguageServerId;
+use task::{Problem, ProblemSeverity};
+use util::paths::normalize_lexically;
+
+use crate::lsp_store::{DocumentDiagnosticsUpdate, LspStore};
+
+#[derive(Default)]
+pub(super) struct TaskDiagnostics {
+    /// The diagnostics reported by each task, by the label of the task.
+    tasks: HashMap<SharedString, ReportedTaskDiagnostics>,
+}
+
+struct ReportedTaskDiagnostics {
+    server_id: LanguageServerId,
+    /// The files with diagnostics, to clear when the task reports new ones.
+    paths: HashSet<PathBuf>,
+}
+
+impl LspStore {
+    /// Replaces the diagnostics of the task with the given label with the given problems.
+    /// Relative paths of the problems are resolved against `cwd`, the directory the task runs in.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: SharedString,
+        cwd: Option<&Path>,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<lsp::Diagnostic>>::default();
+        for problem in problems {
+            let path = match cwd {
+                Some(cwd) => cwd.join(&problem.path),
+                None if problem.path.is_absolute() => problem.path.clone(),
+                None => continue,
+            };
+            let Ok(path) = normalize_lexically(&path) else {
+                continue;
+            };
+            diagnostics_by_path
+                .entry(path)
+                .or_default()
+                .push(problem_to_lsp_diagnostic(problem, &task_label));
+        }
+
+        let languages = self.languages.clone();
+        let local = self
+            .as_local_mut()
+            .context("task diagnostics are only supported in local projects")?;
+        let reported = local
+            .task_diagnostics
+            .tasks
+            .entry(task_label)
+            .or_insert_with(|| ReportedTaskDiagnostics {
+                server_id: languages.next_language_server_id(),
+                paths: HashSet::default(),
+            });
+        let server_id = reported.server_id;
+        let stale_paths = std::mem::replace(
+            &mut reported.paths,
+            diagnostics_by_path.keys().cloned().collect(),
+        );
+        for path in stale_paths {
+            diagnostics_by_path.entry(path).or_default();
+        }
+
+        let worktree_store = self.worktree_store.read(cx);
+        let updates = diagnostics_by_path
+            .into_iter()
+            .filter(|(path, _)| worktree_store.find_worktree(path, cx).is_some())
+            .filter_map(|(path, diagnostics)| {
+                Some(DocumentDiagnosticsUpdate {
+                    diagnostics: lsp::PublishDiagnosticsParams {
+                        uri: lsp::Uri::from_file_path(&path).ok()?,
+                        diagnostics,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.merge_lsp_diagnostics(DiagnosticSourceKind::Other, updates, |_, _, _| false, cx)
+    }
+}
+
+fn problem_to_lsp_diagnostic(problem: Problem, task_label: &SharedString) -> lsp::Diagnostic {
+    let line = problem.line.saturating_sub(1);
+    let end_line = problem
+        .end_line
+        .map_or(line, |end_line| end_line.saturating_sub(1));
+    let start = lsp::Position::new(
+        line,
+        problem.column.map_or(0, |column| column.saturating_sub(1)),
+    );
+    let end = match (problem.column, problem.end_column) {
+        (_, Some(end_column)) => lsp::Position::new(end_line, end_column.saturating_sub(1)),
+        // An empty range gets expanded to the character at its start.
+        (Some(_), None) => lsp::Position::new(end_line, start.character),
+        // Without a column, the whole line is highlighted.
+        (None, None) => lsp::Position::new(end_line, u32::MAX),
+    };
+    lsp::Diagnostic {
+        range: lsp::Range::new(start, end),
+        severity: Some(match problem.severity {
+            ProblemSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+            ProblemSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+            ProblemSeverity::Info => lsp::DiagnosticSeverity::INFORMATION,
+            ProblemSeverity::Hint => lsp::DiagnosticSeverity::HINT,
+        }),
+        code: problem.code.map(lsp::NumberOrString::String),
+        source: Some(problem.source.unwrap_or_else(|| task_label.to_string())),
+        message: problem.message,
+        ..lsp::Diagnostic::default()
+    }
+}
@@ -147,14 +147,15 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskDependencyTree, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
-    DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
-    LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
-    LspStoreEvent, ProgressToken, SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    BufferFoldingRanges, BufferSemanticTokens, DiagnosticSummary, InvalidationStrategy,
+    LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
+    LanguageServerResources, LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
+    ProgressToken, SERVER_PROGRESS_THROTTLE_TIMEOUT, SemanticToken, ServerSemanticTokens,
 };
 pub use toolchain_store::{ToolchainStore, Toolchains};
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -800,6 +801,27 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// An entry of a call or type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub server_id: LanguageServerId,
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range of the item's name.
+    pub location: Location,
+    /// The item as returned by the language server, which needs it back to expand the item.
+    pub lsp_item: serde_json::Value,
+}
+
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller of an incoming call, or the callee of an outgoing call.
+    pub item: HierarchyItem,
+    /// The ranges of the calls, in the caller.
+    pub call_sites: Vec<Location>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -843,6 +865,13 @@ pub struct DirectoryItem {
     pub is_dir: bool,
 }
 
+/// A range of lines that a language server reports as foldable.
+#[derive(Clone, Debug)]
+pub struct FoldingRange {
+    /// Spans from the end of the first line to the end of the last folded line.
+    pub range: Range<Anchor>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct DocumentColor {
     pub lsp_range: lsp::Range,
@@ -3557,6 +3586,12 @@ impl Project {
         })
     }
 
+    /// Pulls the diagnostics of the whole project from the language servers supporting workspace diagnostics.
+    pub fn check_whole_project(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.check_whole_project(cx))
+    }
+
     pub fn stop_language_servers_for_buffers(
         &mut self,
         buffers: Vec<Entity<Buffer>>,
@@ -3875,6 +3910,113 @@ impl Project {
         )
     }
 
+    /// Returns the call hierarchy items at the given position, to expand with [`Self::incoming_calls`] and [`Self::outgoing_calls`].
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls {
+                lsp_item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls {
+                lsp_item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Returns the type hierarchy items at the given position, to expand with [`Self::supertypes`] and [`Self::subtypes`].
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes {
+                lsp_item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes {
+                lsp_item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Returns the ranges a language server considers enclosing each of the positions, from the innermost to the outermost.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -122,6 +122,8 @@ pub struct GlobalLspSettings {
     ///
     /// Default: `true`
     pub button: bool,
+    /// When to restart language servers automatically, unless overridden for a specific server.
+    pub restart_policy: settings::LanguageServerRestartPolicyContent,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -583,6 +585,12 @@ impl Settings for ProjectSettings {
                     .unwrap()
                     .button
                     .unwrap(),
+                restart_policy: content
+                    .global_lsp_settings
+                    .as_ref()
+                    .unwrap()
+                    .restart_policy
+                    .unwrap(),
             },
             dap: project
                 .dap
@@ -3,10 +3,12 @@
 use crate::{
     Event,
     git_store::{GitStoreEvent, RepositoryEvent, StatusEntry, pending_op},
+    lsp_store::log_store,
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
     *,
 };
+use askpass::AskPassDelegate;
 use async_trait::async_trait;
 use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
@@ -16,7 +18,7 @@ use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
-    repository::{RepoPath, repo_path},
+    repository::{RepoPath, Submodule, SubmoduleCommand, SubmoduleStatus, repo_path},
     status::{StatusCode, TrackedStatus},
 };
 use git2::RepositoryInitOptions;
@@ -51,7 +53,7 @@ use std::{
     task::Poll,
 };
 use sum_tree::SumTree;
-use task::{ResolvedTask, ShellKind, TaskContext};
+use task::{Problem, ProblemSeverity, ResolvedTask, ShellKind, TaskContext};
 use unindent::Unindent as _;
 use util::{
     TryFutureExt as _, assert_set_eq, maybe, path,
@@ -2878,6 +2880,115 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "a.rs": "let a = 1;", "b.rs": "let b = 2;" }),
+    )
+    .await;
+
+    let project = Project::test(fs, [Path::new(path!("/dir"))], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store.clone());
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let problem = |path: &str, severity, message: &str| Problem {
+        path: PathBuf::from(path),
+        line: 1,
+        column: Some(5),
+        end_line: None,
+        end_column: None,
+        severity,
+        code: None,
+        message: message.to_string(),
+        source: None,
+    };
+    let update_task_diagnostics = |label: &'static str, problems, cx: &mut gpui::TestAppContext| {
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(label.into(), Some(Path::new(path!("/dir"))), problems, cx)
+                .unwrap();
+            lsp_store.diagnostic_summary(false, cx)
+        })
+    };
+
+    assert_eq!(
+        update_task_diagnostics(
+            "build",
+            vec![
+                problem("a.rs", ProblemSeverity::Error, "unknown variable"),
+                problem("b.rs", ProblemSeverity::Warning, "unused variable"),
+                problem("../outside.rs", ProblemSeverity::Error, "outside"),
+            ],
+            cx
+        ),
+        DiagnosticSummary {
+            error_count: 1,
+            warning_count: 1,
+        }
+    );
+    buffer_a.read_with(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| {
+                (
+                    entry.range,
+                    entry.diagnostic.source.clone(),
+                    entry.diagnostic.message.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                Point::new(0, 4)..Point::new(0, 5),
+                Some("build".to_string()),
+                "unknown variable".to_string()
+            )]
+        );
+    });
+
+    assert_eq!(
+        update_task_diagnostics(
+            "lint",
+            vec![problem("a.rs", ProblemSeverity::Warning, "style")],
+            cx
+        ),
+        DiagnosticSummary {
+            error_count: 1,
+            warning_count: 2,
+        }
+    );
+    // A rerun of a task replaces its diagnostics, leaving those of other tasks alone.
+    assert_eq!(
+        update_task_diagnostics(
+            "build",
+            vec![problem("b.rs", ProblemSeverity::Error, "type mismatch")],
+            cx
+        ),
+        DiagnosticSummary {
+            error_count: 1,
+            warning_count: 1,
+        }
+    );
+    assert_eq!(
+        update_task_diagnostics("build", Vec::new(), cx),
+        DiagnosticSummary {
+            error_count: 0,
+            warning_count: 1,
+        }
+    );
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3424,6 +3535,222 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    fn lsp_item(name: &str, path: &str, column: u32) -> lsp::CallHierarchyItem {
+        lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: lsp::Uri::from_file_path(path).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(0, column),
+                lsp::Position::new(0, column + 1),
+            ),
+            data: Some(json!({ "id": name })),
+        }
+    }
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 3)
+            );
+            Ok(Some(vec![lsp_item("b", path!("/dir/b.rs"), 3)]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.item.data,
+                Some(json!({ "id": "b" })),
+                "the prepared item should be sent back unchanged"
+            );
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp_item("a", path!("/dir/a.rs"), 3),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        assert_eq!(call.item.name, "a");
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/a.rs")),
+        );
+        assert_eq!(call.item.location.range.to_offset(caller_buffer), 3..4);
+        assert_eq!(call.call_sites.len(), 1);
+        assert_eq!(call.call_sites[0].buffer, call.item.location.buffer);
+        assert_eq!(call.call_sites[0].range.to_offset(caller_buffer), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {\n    b();\n}\n// region: helpers\nfn b() {}\n// endregion\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    fn lsp_range(
+        start_line: u32,
+        end_line: u32,
+        kind: Option<lsp::FoldingRangeKind>,
+    ) -> lsp::FoldingRange {
+        lsp::FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        }
+    }
+
+    fake_server.set_request_handler::<lsp::request::FoldingRangeRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document.uri,
+                lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+            );
+            Ok(Some(vec![
+                lsp_range(0, 1, None),
+                lsp_range(2, 2, None),
+                lsp_range(3, 5, Some(lsp::FoldingRangeKind::Region)),
+            ]))
+        },
+    );
+
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let folding_ranges = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.folding_ranges(None, buffer.clone(), cx)
+        })
+        .expect("no folding ranges were fetched yet")
+        .await
+        .unwrap();
+    let server_ranges = folding_ranges
+        .ranges
+        .get(&fake_server.server.server_id())
+        .expect("the server should report folding ranges")
+        .clone();
+    cx.update(|cx| {
+        let buffer = buffer.read(cx);
+        let ranges = server_ranges
+            .iter()
+            .map(|folding_range| folding_range.range.to_point(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![
+                Point::new(0, 8)..Point::new(1, 8),
+                Point::new(3, 18)..Point::new(5, 12),
+            ],
+            "single-line ranges should be dropped, and the others should span from the end of their first line to the end of their last one"
+        );
+    });
+
+    let cached_ranges = lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store.folding_ranges(folding_ranges.cache_version, buffer.clone(), cx)
+    });
+    assert!(
+        cached_ranges.is_none(),
+        "unchanged folding ranges should not be returned again"
+    );
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -6737,47 +7064,571 @@ async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
+async fn test_replaying_recorded_lsp_session(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
-            "a.ts": "a",
+            "a.rs": "fn a() {}\n",
+            "b.rs": "fn b() {}\n",
         }),
     )
     .await;
 
-    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let transcript = {
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let log_store = cx.update(|cx| log_store::init(false, cx));
+        log_store.update(cx, |log_store, _| {
+            log_store.start_recording_sessions(HashSet::from_iter([
+                LanguageServerName::new_static("the-fake-language-server"),
+            ]))
+        });
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(rust_lang());
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                    definition_provider: Some(lsp::OneOf::Left(true)),
+                    ..lsp::ServerCapabilities::default()
+                },
+                ..FakeLspAdapter::default()
+            },
+        );
+        let (buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_language_servers.next().await.unwrap();
+        fake_server.set_request_handler::<lsp::request::HoverRequest, _, _>(|_, _| async move {
+            Ok(Some(lsp::Hover {
+                contents: lsp::HoverContents::Scalar(lsp::MarkedString::String(
+                    "recorded hover".to_string(),
+                )),
+                range: None,
+            }))
+        });
+
+        let hovers = project
+            .update(cx, |project, cx| {
+                project.hover(&buffer, Point::new(0, 3), cx)
+            })
+            .await;
+        assert_eq!(hovers.into_iter().flatten().count(), 1);
+        fake_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(|_, _| async move {
+            Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                lsp::Location::new(
+                    lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+                    lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+                ),
+            )))
+        });
+        let definitions = project
+            .update(cx, |project, cx| project.definitions(&buffer, 3, cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(definitions.len(), 1);
+        cx.executor().run_until_parked();
+
+        let mut transcripts = log_store.update(cx, |log_store, _| {
+            assert!(log_store.is_recording_sessions());
+            log_store.stop_recording_sessions()
+        });
+        assert_eq!(
+            transcripts.len(),
+            1,
+            "a single server should have been recorded"
+        );
+        let (server_id, transcript) = transcripts.pop().unwrap();
+        assert_eq!(server_id, fake_server.server.server_id());
+        transcript
+    };
+    assert_eq!(
+        transcript
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(PathBuf::from("a.rs"), "fn a() {}\n")],
+        "the open buffers should be recorded with the session"
+    );
+    assert_eq!(
+        transcript.unrecorded_file_paths(),
+        vec![PathBuf::from("b.rs")],
+        "files that the server refers to should be loaded when the transcript is saved"
+    );
+    let capabilities = transcript
+        .server_capabilities()
+        .expect("the initialize response should be recorded");
+    assert!(capabilities.hover_provider.is_some());
 
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(typescript_lang());
+    language_registry.add(rust_lang());
     let mut fake_language_servers = language_registry.register_fake_lsp(
-        "TypeScript",
+        "Rust",
         FakeLspAdapter {
-            capabilities: lsp::ServerCapabilities {
-                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
-                ..lsp::ServerCapabilities::default()
-            },
+            capabilities,
+            initializer: Some(Box::new(move |fake_server| fake_server.replay(&transcript))),
             ..FakeLspAdapter::default()
         },
     );
-
     let (buffer, _handle) = project
-        .update(cx, |p, cx| {
-            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
         })
         .await
         .unwrap();
+    fake_language_servers.next().await.unwrap();
     cx.executor().run_until_parked();
 
-    let fake_server = fake_language_servers
-        .next()
-        .await
-        .expect("failed to get the language server");
+    let hovers = project
+        .update(cx, |project, cx| {
+            project.hover(&buffer, Point::new(0, 3), cx)
+        })
+        .await;
+    assert_eq!(
+        hovers
+            .into_iter()
+            .flatten()
+            .map(|hover| hover.contents.iter().map(|block| &block.text).join("|"))
+            .collect::<Vec<_>>(),
+        vec!["recorded hover".to_string()],
+        "the replayed server should answer with the recorded hover"
+    );
+}
 
-    let mut request_handled = fake_server
+#[gpui::test]
+async fn test_restarting_language_server_after_timed_out_requests(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.global_lsp_settings = Some(settings::GlobalLspSettingsContent {
+                    button: None,
+                    restart_policy: Some(settings::LanguageServerRestartPolicyContent {
+                        max_memory_mb: None,
+                        max_timed_out_requests: Some(1),
+                    }),
+                });
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_language_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::HoverRequest, _, _>(|_, _| {
+        future::pending::<anyhow::Result<Option<lsp::Hover>>>()
+    });
+
+    let hovers = project.update(cx, |project, cx| {
+        project.hover(&buffer, Point::new(0, 3), cx)
+    });
+    cx.executor().advance_clock(lsp::LSP_REQUEST_TIMEOUT);
+    assert_eq!(hovers.await.into_iter().flatten().count(), 0);
+    cx.executor().advance_clock(Duration::from_secs(5));
+    cx.executor().run_until_parked();
+    let restarted_server = fake_language_servers
+        .next()
+        .await
+        .expect("the server should be restarted after its request timed out");
+    let opened = restarted_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+    assert_eq!(
+        opened.text_document.uri,
+        lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+        "the open document should be reopened in the restarted server"
+    );
+}
+
+#[gpui::test]
+async fn test_checking_whole_project(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}\n",
+            "b.rs": "fn b() { c }\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_language_servers.next().await.unwrap();
+    let workspace_requests = Arc::new(Mutex::new(Vec::new()));
+    fake_server.set_request_handler::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+        let workspace_requests = workspace_requests.clone();
+        move |params, _| {
+            workspace_requests.lock().push(params);
+            async move {
+                Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                    lsp::WorkspaceDiagnosticReport {
+                        items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                            lsp::WorkspaceFullDocumentDiagnosticReport {
+                                uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+                                version: None,
+                                full_document_diagnostic_report:
+                                    lsp::FullDocumentDiagnosticReport {
+                                        result_id: Some("b-1".to_string()),
+                                        items: vec![lsp::Diagnostic {
+                                            range: lsp::Range::new(
+                                                lsp::Position::new(0, 9),
+                                                lsp::Position::new(0, 10),
+                                            ),
+                                            severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                            message: "cannot find value `c`".to_string(),
+                                            ..lsp::Diagnostic::default()
+                                        }],
+                                    },
+                            },
+                        )],
+                    },
+                ))
+            }
+        }
+    });
+    cx.executor().run_until_parked();
+    let background_requests = workspace_requests.lock().len();
+
+    project
+        .update(cx, |project, cx| project.check_whole_project(cx))
+        .await
+        .unwrap();
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summaries(false, cx).collect::<Vec<_>>(),
+            vec![(
+                ProjectPath {
+                    worktree_id,
+                    path: rel_path("b.rs").into(),
+                },
+                fake_server.server.server_id(),
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 0,
+                }
+            )],
+            "the diagnostics of the file not open in any buffer should be reported"
+        );
+        let lsp_store = project.lsp_store().read(cx);
+        assert!(
+            lsp_store
+                .language_server_statuses()
+                .all(|(_, status)| status.pending_work.is_empty()),
+            "the check should not be in progress after it finished"
+        );
+    });
+
+    project
+        .update(cx, |project, cx| project.check_whole_project(cx))
+        .await
+        .unwrap();
+    let workspace_requests = workspace_requests.lock();
+    assert_eq!(workspace_requests.len(), background_requests + 2);
+    let last_request = workspace_requests.last().unwrap();
+    assert_eq!(
+        last_request.previous_result_ids,
+        vec![lsp::PreviousResultId {
+            uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+            value: "b-1".to_string(),
+        }],
+        "repeated checks should send the result ids of the previous ones"
+    );
+    assert!(
+        last_request
+            .partial_result_params
+            .partial_result_token
+            .is_some(),
+        "checks should request partial results"
+    );
+}
+
+#[gpui::test]
+async fn test_notebook_document_synchronization(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "notebook.ipynb": "{}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                notebook_document_sync: Some(lsp::OneOf::Left(lsp::NotebookDocumentSyncOptions {
+                    notebook_selector: Vec::new(),
+                    save: None,
+                })),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let language = language_registry.language_for_name("Rust").await.unwrap();
+    let cell_buffer = cx.new(|cx| language::Buffer::local("fn a() { b }\n", cx));
+    let notebook_path = Path::new(path!("/dir/notebook.ipynb"));
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_notebook_document(
+                notebook_path,
+                "jupyter-notebook",
+                language,
+                vec![lsp_store::NotebookCellBuffer {
+                    id: "cell-1".to_string(),
+                    kind: lsp::NotebookCellKind::Code,
+                    buffer: cell_buffer.clone(),
+                }],
+                cx,
+            )
+        })
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+    let opened = fake_server
+        .receive_notification::<lsp::notification::DidOpenNotebookDocument>()
+        .await;
+    let notebook_uri = Uri::from_file_path(path!("/dir/notebook.ipynb")).unwrap();
+    assert_eq!(opened.notebook_document.uri, notebook_uri);
+    assert_eq!(opened.notebook_document.cells.len(), 1);
+    let cell_uri = opened.notebook_document.cells[0].document.clone();
+    assert!(cell_uri.as_str().starts_with("vscode-notebook-cell:"));
+    assert_eq!(opened.cell_text_documents[0].uri, cell_uri);
+    assert_eq!(opened.cell_text_documents[0].text, "fn a() { b }\n");
+
+    cell_buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    let changed = fake_server
+        .receive_notification::<lsp::notification::DidChangeNotebookDocument>()
+        .await;
+    assert_eq!(changed.notebook_document.version, 1);
+    let text_content = changed.change.cells.unwrap().text_content.unwrap();
+    assert_eq!(text_content.len(), 1);
+    assert_eq!(text_content[0].document.uri, cell_uri);
+    assert_eq!(text_content[0].document.version, 1);
+
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: cell_uri.clone(),
+        version: Some(1),
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(1, 9), lsp::Position::new(1, 10)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "cannot find value `b`".to_string(),
+            ..lsp::Diagnostic::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    cell_buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| (entry.range, entry.diagnostic.message.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                Point::new(1, 9)..Point::new(1, 10),
+                "cannot find value `b`".to_string()
+            )]
+        );
+    });
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store.close_notebook_document(notebook_path, cx)
+    });
+    let closed = fake_server
+        .receive_notification::<lsp::notification::DidCloseNotebookDocument>()
+        .await;
+    assert_eq!(closed.notebook_document.uri, notebook_uri);
+    assert_eq!(closed.cell_text_documents[0].uri, cell_uri);
+}
+
+#[gpui::test]
+async fn test_language_server_restarts_by_policy_are_capped(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.global_lsp_settings = Some(settings::GlobalLspSettingsContent {
+                    button: None,
+                    restart_policy: Some(settings::LanguageServerRestartPolicyContent {
+                        max_memory_mb: None,
+                        max_timed_out_requests: Some(1),
+                    }),
+                });
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_language_servers.next().await.unwrap();
+
+    // Each server times out a request, and is restarted until the restarts are exhausted.
+    for restart in 0..=3 {
+        fake_server.set_request_handler::<lsp::request::HoverRequest, _, _>(|_, _| {
+            future::pending::<anyhow::Result<Option<lsp::Hover>>>()
+        });
+        let hovers = project.update(cx, |project, cx| {
+            project.hover(&buffer, Point::new(0, 3), cx)
+        });
+        cx.executor().advance_clock(lsp::LSP_REQUEST_TIMEOUT);
+        assert_eq!(hovers.await.into_iter().flatten().count(), 0);
+        cx.executor().advance_clock(Duration::from_secs(5));
+        cx.executor().run_until_parked();
+
+        if restart < 3 {
+            fake_server = fake_language_servers
+                .next()
+                .await
+                .expect("the server should be restarted after its request timed out");
+            fake_server
+                .receive_notification::<lsp::notification::DidOpenTextDocument>()
+                .await;
+        } else {
+            assert!(
+                fake_language_servers.try_next().is_err(),
+                "the server shouldn't be restarted more than 3 times"
+            );
+        }
+    }
+}
+
+#[gpui::test]
+async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    let mut request_handled = fake_server
         .set_request_handler::<lsp::request::CodeActionRequest, _, _>(move |_, _| async move {
             Ok(Some(vec![
                 lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
@@ -10248,6 +11099,146 @@ async fn test_git_worktrees_and_submodules(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_git_submodules(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {
+                "modules": {
+                    "initialized": {
+                        // For is_git_dir
+                        "HEAD": "",
+                        "config": "",
+                    }
+                }
+            },
+            ".gitmodules": "",
+            "initialized": {
+                ".git": "gitdir: ../.git/modules/initialized\n",
+                "a.txt": "A",
+            },
+            "uninitialized": {},
+        }),
+    )
+    .await;
+    fs.with_git_state(path!("/project/.git").as_ref(), true, |state| {
+        state.submodules = vec![
+            Submodule {
+                path: repo_path("initialized"),
+                sha: "abc".into(),
+                status: SubmoduleStatus::OutOfDate,
+            },
+            Submodule {
+                path: repo_path("uninitialized"),
+                sha: "def".into(),
+                status: SubmoduleStatus::Uninitialized,
+            },
+        ];
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let scan_complete = project.update(cx, |project, cx| project.git_scans_complete(cx));
+    scan_complete.await;
+    cx.run_until_parked();
+
+    let superproject = project.update(cx, |project, cx| {
+        project
+            .repositories(cx)
+            .values()
+            .find(|repo| *repo.read(cx).work_directory_abs_path == *Path::new(path!("/project")))
+            .unwrap()
+            .clone()
+    });
+    project.update(cx, |project, cx| {
+        let git_store = project.git_store().read(cx);
+        let submodules = git_store.submodules(&superproject, cx);
+        pretty_assertions::assert_eq!(
+            submodules
+                .iter()
+                .map(|entry| (
+                    entry.submodule.path.clone(),
+                    entry.submodule.status,
+                    entry
+                        .repository
+                        .as_ref()
+                        .map(|repo| repo.read(cx).work_directory_abs_path.clone()),
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    repo_path("initialized"),
+                    SubmoduleStatus::OutOfDate,
+                    Some(Path::new(path!("/project/initialized")).into()),
+                ),
+                (
+                    repo_path("uninitialized"),
+                    SubmoduleStatus::Uninitialized,
+                    None
+                ),
+            ]
+        );
+
+        let submodule = git_store
+            .submodule_for_abs_path(Path::new(path!("/project/uninitialized")), cx)
+            .unwrap();
+        assert_eq!(submodule.submodule.sha.as_ref(), "def");
+        assert!(!submodule.is_dirty(cx));
+        assert!(
+            git_store
+                .submodule_for_abs_path(Path::new(path!("/project/initialized/a.txt")), cx)
+                .is_none()
+        );
+    });
+
+    // Modifying a file inside the submodule marks it as dirty.
+    fs.with_git_state(path!("/project/initialized/.git").as_ref(), true, |state| {
+        state
+            .head_contents
+            .insert(repo_path("a.txt"), "a".to_owned());
+        state
+            .index_contents
+            .insert(repo_path("a.txt"), "a".to_owned());
+    })
+    .unwrap();
+    cx.run_until_parked();
+    project.update(cx, |project, cx| {
+        let submodule = project
+            .git_store()
+            .read(cx)
+            .submodule_for_abs_path(Path::new(path!("/project/initialized")), cx)
+            .unwrap();
+        assert!(submodule.is_dirty(cx));
+    });
+
+    // Updating the submodules checks out the recorded commits.
+    let update = superproject.update(cx, |repo, cx| {
+        let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+        repo.submodule_command(SubmoduleCommand::Update, Vec::new(), askpass, cx)
+    });
+    update.await.unwrap().unwrap();
+    cx.run_until_parked();
+    superproject.update(cx, |repo, _| {
+        pretty_assertions::assert_eq!(
+            repo.submodules
+                .iter()
+                .map(|submodule| submodule.status)
+                .collect::<Vec<_>>(),
+            [SubmoduleStatus::Current, SubmoduleStatus::Current]
+        );
+    });
+    let commands = fs
+        .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+            state.submodule_commands.clone()
+        })
+        .unwrap();
+    pretty_assertions::assert_eq!(commands, [(SubmoduleCommand::Update, Vec::new())]);
+}
+
 #[gpui::test]
 async fn test_repository_deduplication(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -155,6 +155,15 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A task, along with the tasks it depends on, directly or transitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskDependencyTree {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    /// Trees of the tasks in the template's `depends_on`, in the same order.
+    pub dependencies: Vec<TaskDependencyTree>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -373,6 +382,22 @@ impl Inventory {
         })
     }
 
+    /// Looks up the tasks that the task given depends on, by their labels, among the worktree and global tasks.
+    /// Fails if a dependency can't be found, or if the dependencies form a cycle.
+    pub fn task_dependency_tree(
+        &self,
+        task_source_kind: TaskSourceKind,
+        task: TaskTemplate,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Task<Result<TaskDependencyTree>> {
+        let tasks = self.list_tasks(None, None, worktree, cx);
+        cx.background_spawn(async move {
+            let tasks = tasks.await;
+            dependency_tree(task_source_kind, task, &tasks, &mut Vec::new())
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -844,6 +869,46 @@ fn task_variables_preference(task: &ResolvedTask) -> Reverse<usize> {
     })
 }
 
+fn dependency_tree(
+    source_kind: TaskSourceKind,
+    template: TaskTemplate,
+    available: &[(TaskSourceKind, TaskTemplate)],
+    dependants: &mut Vec<String>,
+) -> Result<TaskDependencyTree> {
+    if dependants.contains(&template.label) {
+        dependants.push(template.label);
+        anyhow::bail!("Task dependency cycle: {}", dependants.join(" -> "));
+    }
+    dependants.push(template.label.clone());
+    let dependencies = template
+        .depends_on
+        .iter()
+        .map(|label| {
+            let (source_kind, dependency) = available
+                .iter()
+                .find(|(_, task)| &task.label == label)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on an unknown task `{label}`",
+                        template.label
+                    )
+                })?;
+            dependency_tree(
+                source_kind.clone(),
+                dependency.clone(),
+                available,
+                dependants,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    dependants.pop();
+    Ok(TaskDependencyTree {
+        source_kind,
+        template,
+        dependencies,
+    })
+}
+
 #[cfg(test)]
 mod test_inventory {
     use gpui::{AppContext as _, Entity, Task, TestAppContext};
@@ -1570,6 +1635,76 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_task_dependency_tree() {
+        let task = |label: &str, depends_on: &[&str]| TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+        let leaf = |label: &str| TaskDependencyTree {
+            source_kind: TaskSourceKind::UserInput,
+            template: task(label, &[]),
+            dependencies: Vec::new(),
+        };
+        let available = [
+            task("codegen", &[]),
+            task("build", &["codegen"]),
+            task("migrate", &[]),
+            task("serve", &["build", "migrate"]),
+            task("loop a", &["loop b"]),
+            task("loop b", &["loop a"]),
+        ]
+        .into_iter()
+        .map(|task| (TaskSourceKind::UserInput, task))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            dependency_tree(
+                TaskSourceKind::UserInput,
+                task("serve", &["build", "migrate"]),
+                &available,
+                &mut Vec::new(),
+            )
+            .unwrap(),
+            TaskDependencyTree {
+                source_kind: TaskSourceKind::UserInput,
+                template: task("serve", &["build", "migrate"]),
+                dependencies: vec![
+                    TaskDependencyTree {
+                        source_kind: TaskSourceKind::UserInput,
+                        template: task("build", &["codegen"]),
+                        dependencies: vec![leaf("codegen")],
+                    },
+                    leaf("migrate"),
+                ],
+            }
+        );
+        assert_eq!(
+            dependency_tree(
+                TaskSourceKind::UserInput,
+                task("deploy", &["package"]),
+                &available,
+                &mut Vec::new(),
+            )
+            .unwrap_err()
+            .to_string(),
+            "Task `deploy` depends on an unknown task `package`"
+        );
+        assert_eq!(
+            dependency_tree(
+                TaskSourceKind::UserInput,
+                task("loop a", &["loop b"]),
+                &available,
+                &mut Vec::new(),
+            )
+            .unwrap_err()
+            .to_string(),
+            "Task dependency cycle: loop a -> loop b -> loop a"
+        );
+    }
+
     fn mock_tasks_from_names<'a>(task_names: impl IntoIterator<Item = &'a str> + 'a) -> String {
         serde_json::to_string(&serde_json::Value::Array(
             task_names
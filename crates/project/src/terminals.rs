@@ -1,8 +1,8 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::HashMap;
-use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
+use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
 
-use futures::{FutureExt, future::Shared};
+use futures::{FutureExt, StreamExt as _, channel::mpsc, future::Shared};
 use itertools::Itertools as _;
 use language::LanguageName;
 use remote::RemoteClient;
@@ -11,20 +11,68 @@ use smol::channel::bounded;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
+};
+use task::{
+    CompiledProblemMatcher, Problem, ProblemMatcher, Shell, ShellBuilder, ShellKind,
+    SpawnInTerminal,
 };
-use task::{Shell, ShellBuilder, ShellKind, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
+    OutputPosition, TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
     terminal_settings::TerminalSettings,
 };
-use util::{command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath};
+use util::{
+    ResultExt as _, command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath,
+};
 
 use crate::{Project, ProjectPath};
 
+/// How long the output of a task has to settle before its problem matchers run again.
+const PROBLEM_MATCHING_DEBOUNCE: Duration = Duration::from_millis(250);
+
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
 }
 
+/// The problems a matcher found in the output of a task so far.
+struct TaskProblems {
+    matcher: CompiledProblemMatcher,
+    problems: Vec<Problem>,
+    /// Lines of the output that the matcher has to see again along with the ones that follow them, as a
+    /// problem reported over several lines may continue there.
+    pending_lines: Vec<String>,
+}
+
+impl TaskProblems {
+    fn new(matcher: CompiledProblemMatcher) -> Self {
+        Self {
+            matcher,
+            problems: Vec::new(),
+            pending_lines: Vec::new(),
+        }
+    }
+
+    /// Matches the lines printed since the last call, the last of which may still be being printed
+    /// unless the output is complete.
+    fn match_new_lines(&mut self, lines: &[String], is_complete: bool) {
+        let complete_lines = if is_complete {
+            lines
+        } else {
+            &lines[..lines.len().saturating_sub(1)]
+        };
+        self.pending_lines.extend(complete_lines.iter().cloned());
+        let lines = self
+            .pending_lines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let matched = self
+            .matcher
+            .match_lines(&lines, is_complete, &mut self.problems);
+        self.pending_lines.drain(..matched);
+    }
+}
+
 impl Project {
     pub fn active_project_directory(&self, cx: &App) -> Option<Arc<Path>> {
         self.active_entry()
@@ -115,6 +163,9 @@ impl Project {
             .map(|p| self.active_toolchain(p, LanguageName::new_static("Python"), cx))
             .collect::<Vec<_>>();
         let lang_registry = self.languages.clone();
+        let task_label = SharedString::from(spawn_task.full_label.clone());
+        let problem_matchers = spawn_task.problem_matchers.clone();
+        let task_cwd = local_path.clone();
         cx.spawn(async move |project, cx| {
             let mut env = env_task.await.unwrap_or_default();
             env.extend(settings.env);
@@ -243,6 +294,7 @@ impl Project {
                         settings.max_scroll_history_lines,
                         settings.path_hyperlink_regexes,
                         settings.path_hyperlink_timeout_ms,
+                        settings.shell_integration,
                         is_via_remote,
                         cx.entity_id().as_u64(),
                         Some(completion_tx),
@@ -272,11 +324,104 @@ impl Project {
                 })
                 .detach();
 
+                if !problem_matchers.is_empty() && this.is_local() {
+                    this.match_task_problems(
+                        &terminal_handle,
+                        task_label,
+                        task_cwd,
+                        &problem_matchers,
+                        cx,
+                    );
+                }
+
                 terminal_handle
             })
         })
     }
 
+    /// Reports the problems that the matchers of a task find in the output of its terminal as diagnostics,
+    /// replacing the diagnostics of the previous run of the task. Only the output printed since the last
+    /// match is matched.
+    fn match_task_problems(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        task_label: SharedString,
+        cwd: Option<Arc<Path>>,
+        problem_matchers: &[ProblemMatcher],
+        cx: &mut Context<Self>,
+    ) {
+        let matchers = problem_matchers
+            .iter()
+            .filter_map(|matcher| {
+                matcher
+                    .compile()
+                    .with_context(|| format!("compiling a problem matcher of task `{task_label}`"))
+                    .log_err()
+            })
+            .map(TaskProblems::new)
+            .collect::<Vec<_>>();
+        let lsp_store = self.lsp_store.clone();
+        let report_problems = move |problems, cx: &mut App| {
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.update_task_diagnostics(
+                        task_label.clone(),
+                        cwd.as_deref(),
+                        problems,
+                        cx,
+                    )
+                })
+                .log_err();
+        };
+        report_problems(Vec::new(), cx);
+
+        let (output_tx, mut output_rx) = mpsc::unbounded();
+        // The terminal also wakes up when its task finishes.
+        cx.subscribe(terminal, move |_, _, event, _| {
+            if let terminal::Event::Wakeup = event {
+                output_tx.unbounded_send(()).ok();
+            }
+        })
+        .detach();
+        let terminal = terminal.downgrade();
+        cx.spawn(async move |_, cx| {
+            let mut matchers = matchers;
+            let mut position = OutputPosition::default();
+            while output_rx.next().await.is_some() {
+                cx.background_executor()
+                    .timer(PROBLEM_MATCHING_DEBOUNCE)
+                    .await;
+                while let Ok(Some(())) = output_rx.try_next() {}
+
+                let Ok((lines, is_complete)) = terminal.read_with(cx, |terminal, _| {
+                    let is_complete = terminal
+                        .task()
+                        .is_some_and(|task| task.status != TaskStatus::Running);
+                    (terminal.read_new_lines(&mut position), is_complete)
+                }) else {
+                    break;
+                };
+                let (matched, problems) = cx
+                    .background_spawn(async move {
+                        for matcher in &mut matchers {
+                            matcher.match_new_lines(&lines, is_complete);
+                        }
+                        let problems = matchers
+                            .iter()
+                            .flat_map(|matcher| matcher.problems.iter().cloned())
+                            .collect::<Vec<_>>();
+                        (matchers, problems)
+                    })
+                    .await;
+                matchers = matched;
+                if cx.update(|cx| report_problems(problems, cx)).is_err() || is_complete {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
     pub fn create_terminal_shell(
         &mut self,
         cwd: Option<PathBuf>,
@@ -374,6 +519,7 @@ impl Project {
                         settings.max_scroll_history_lines,
                         settings.path_hyperlink_regexes,
                         settings.path_hyperlink_timeout_ms,
+                        settings.shell_integration,
                         is_via_remote,
                         cx.entity_id().as_u64(),
                         None,
@@ -596,3 +742,58 @@ fn create_remote_shell(
         command.env,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use task::ProblemMatcher;
+
+    use super::*;
+
+    fn builtin_matcher(name: &str) -> TaskProblems {
+        TaskProblems::new(ProblemMatcher::Builtin(name.into()).compile().unwrap())
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn messages(task_problems: &TaskProblems) -> Vec<&str> {
+        task_problems
+            .problems
+            .iter()
+            .map(|problem| problem.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_output_that_ends_on_a_problem() {
+        let mut task_problems = builtin_matcher("$gcc");
+        // The cursor moved past the problem, to an empty line.
+        task_problems.match_new_lines(&lines(&["a.c:1:2: error: first", ""]), false);
+        assert_eq!(messages(&task_problems), ["first"]);
+
+        // The line of the cursor may still be being printed, until the task finishes.
+        task_problems.match_new_lines(&lines(&["b.c:3:4: warning: second"]), false);
+        assert_eq!(messages(&task_problems), ["first"]);
+        task_problems.match_new_lines(&lines(&["b.c:3:4: warning: second"]), true);
+        assert_eq!(messages(&task_problems), ["first", "second"]);
+
+        // A problem reported over several lines at the end of the output may go on in the lines that
+        // follow, so it is matched once the task finishes.
+        let mut task_problems = builtin_matcher("$rustc");
+        task_problems.match_new_lines(
+            &lines(&[
+                "error[E0425]: cannot find value `x` in this scope",
+                " --> src/main.rs:2:13",
+                "",
+            ]),
+            false,
+        );
+        assert_eq!(messages(&task_problems), Vec::<&str>::new());
+        task_problems.match_new_lines(&lines(&[""]), true);
+        assert_eq!(
+            messages(&task_problems),
+            ["cannot find value `x` in this scope"]
+        );
+    }
+}
@@ -15,7 +15,7 @@ use editor::{
 };
 use file_icons::FileIcons;
 use git;
-use git::status::GitSummary;
+use git::{repository::SubmoduleStatus, status::GitSummary};
 use git_ui;
 use git_ui::file_diff_view::FileDiffView;
 use gpui::{
@@ -228,6 +228,7 @@ struct EntryDetails {
     filename_text_color: Color,
     diagnostic_severity: Option<DiagnosticSeverity>,
     git_status: GitSummary,
+    submodule_status: Option<&'static str>,
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Arc<Path>>,
@@ -602,6 +603,7 @@ impl ProjectPanel {
                 window,
                 |this, _, event, window, cx| match event {
                     GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::StatusesChanged, _)
+                    | GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::SubmodulesChanged, _)
                     | GitStoreEvent::RepositoryAdded
                     | GitStoreEvent::RepositoryRemoved(_) => {
                         this.update_visible_entries(None, false, false, window, cx);
@@ -4604,6 +4606,17 @@ impl ProjectPanel {
 
         let filename_text_color = details.filename_text_color;
         let diagnostic_severity = details.diagnostic_severity;
+        let submodule_indicator = details.submodule_status.map(|status| {
+            div()
+                .id("submodule-status")
+                .ml_1p5()
+                .child(
+                    Label::new(status)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .tooltip(Tooltip::text(format!("Submodule is {status}")))
+        });
         let item_colors = get_item_color(is_sticky, cx);
 
         let canonical_path = details
@@ -5222,6 +5235,7 @@ impl ProjectPanel {
                                     )
                                 }
                             })
+                            .children(submodule_indicator)
                         },
                     )
                     .on_secondary_mouse_down(cx.listener(
@@ -5350,6 +5364,12 @@ impl ProjectPanel {
             .as_ref()
             .is_some_and(|e| e.is_cut() && e.items().contains(&selection));
 
+        let submodule_status = if entry.is_dir() {
+            self.submodule_status(worktree_id, &entry.path, cx)
+        } else {
+            None
+        };
+
         EntryDetails {
             filename,
             icon,
@@ -5367,12 +5387,37 @@ impl ProjectPanel {
             filename_text_color,
             diagnostic_severity,
             git_status,
+            submodule_status,
             is_private: entry.is_private,
             worktree_id,
             canonical_path: entry.canonical_path.clone(),
         }
     }
 
+    fn submodule_status(
+        &self,
+        worktree_id: WorktreeId,
+        path: &RelPath,
+        cx: &App,
+    ) -> Option<&'static str> {
+        let project = self.project.read(cx);
+        let abs_path = project
+            .worktree_for_id(worktree_id, cx)?
+            .read(cx)
+            .absolutize(path);
+        let submodule = project
+            .git_store()
+            .read(cx)
+            .submodule_for_abs_path(&abs_path, cx)?;
+        match submodule.submodule.status {
+            SubmoduleStatus::Uninitialized => Some("not initialized"),
+            SubmoduleStatus::OutOfDate => Some("out of date"),
+            SubmoduleStatus::Conflicted => Some("conflicted"),
+            SubmoduleStatus::Current if submodule.is_dirty(cx) => Some("modified"),
+            SubmoduleStatus::Current => None,
+        }
+    }
+
     fn dispatch_context(&self, window: &Window, cx: &Context<Self>) -> KeyContext {
         let mut dispatch_context = KeyContext::new_with_defaults();
         dispatch_context.add("ProjectPanel");
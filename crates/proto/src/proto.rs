@@ -220,6 +220,24 @@ messages!(
     (ResolveInlayHintResponse, Background),
     (GetDocumentColor, Background),
     (GetDocumentColorResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
     (GetColorPresentation, Background),
     (GetColorPresentationResponse, Background),
     (RefreshCodeLens, Background),
@@ -229,6 +247,7 @@ messages!(
     (RespondToContactRequest, Foreground),
     (RestartLanguageServers, Foreground),
     (StopLanguageServers, Background),
+    (CheckWholeProject, Background),
     (RoomUpdated, Foreground),
     (SaveBuffer, Foreground),
     (SendChannelMessage, Background),
@@ -293,6 +312,30 @@ messages!(
     (GitCommitDetails, Background),
     (GitFileHistory, Background),
     (GitFileHistoryResponse, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitInteractiveRebase, Background),
+    (GitRebaseCommand, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitCommitOperation, Background),
+    (GitCreateTag, Background),
+    (GitGetConfig, Background),
+    (GitGetConfigResponse, Background),
+    (GitBisect, Background),
+    (GitBisectResponse, Background),
+    (GitSubmoduleCommand, Background),
+    (GitCheckStagedChanges, Background),
+    (GitCheckStagedChangesResponse, Background),
+    (GitGetTags, Background),
+    (GitGetTagsResponse, Background),
+    (GitDeleteTag, Background),
+    (GitReleaseCommits, Background),
+    (GitReleaseCommitsResponse, Background),
+    (GitLoadConflictTexts, Background),
+    (GitLoadConflictTextsResponse, Background),
+    (GitGetCommitSignature, Background),
+    (GitGetCommitSignatureResponse, Background),
     (SetIndexText, Background),
     (Push, Background),
     (Fetch, Background),
@@ -435,6 +478,15 @@ request_messages!(
     ),
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
@@ -471,6 +523,7 @@ request_messages!(
     (LspQueryResponse, Ack),
     (RestartLanguageServers, Ack),
     (StopLanguageServers, Ack),
+    (CheckWholeProject, Ack),
     (OpenContext, OpenContextResponse),
     (CreateContext, CreateContextResponse),
     (SynchronizeContexts, SynchronizeContextsResponse),
@@ -500,6 +553,21 @@ request_messages!(
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
     (GitFileHistory, GitFileHistoryResponse),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitInteractiveRebase, Ack),
+    (GitRebaseCommand, Ack),
+    (GitLog, GitLogResponse),
+    (GitCommitOperation, Ack),
+    (GitCreateTag, Ack),
+    (GitGetConfig, GitGetConfigResponse),
+    (GitBisect, GitBisectResponse),
+    (GitSubmoduleCommand, RemoteMessageResponse),
+    (GitCheckStagedChanges, GitCheckStagedChangesResponse),
+    (GitGetTags, GitGetTagsResponse),
+    (GitDeleteTag, Ack),
+    (GitReleaseCommits, GitReleaseCommitsResponse),
+    (GitLoadConflictTexts, GitLoadConflictTextsResponse),
+    (GitGetCommitSignature, GitGetCommitSignatureResponse),
     (GitReset, Ack),
     (GitDeleteBranch, Ack),
     (GitCheckoutFiles, Ack),
@@ -550,6 +618,8 @@ lsp_messages!(
     (GetTypeDefinition, GetTypeDefinitionResponse, true),
     (GetImplementation, GetImplementationResponse, true),
     (InlayHints, InlayHintsResponse, false),
+    (GetSemanticTokens, GetSemanticTokensResponse, true),
+    (GetFoldingRanges, GetFoldingRangesResponse, true),
 );
 
 entity_messages!(
@@ -570,6 +640,8 @@ entity_messages!(
     CreateImageForPeer,
     CreateProjectEntry,
     GetDocumentColor,
+    GetSemanticTokens,
+    GetFoldingRanges,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -584,6 +656,13 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetDocumentSymbols,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetSelectionRanges,
     GetHover,
     GetProjectSymbols,
     GetReferences,
@@ -600,6 +679,7 @@ entity_messages!(
     LspQueryResponse,
     RestartLanguageServers,
     StopLanguageServers,
+    CheckWholeProject,
     OnTypeFormatting,
     OpenNewBuffer,
     OpenBufferById,
@@ -670,6 +750,21 @@ entity_messages!(
     RegisterBufferWithLanguageServers,
     GitShow,
     GitFileHistory,
+    GitRebaseTodo,
+    GitInteractiveRebase,
+    GitRebaseCommand,
+    GitLog,
+    GitCommitOperation,
+    GitCreateTag,
+    GitGetConfig,
+    GitBisect,
+    GitSubmoduleCommand,
+    GitCheckStagedChanges,
+    GitGetTags,
+    GitDeleteTag,
+    GitReleaseCommits,
+    GitLoadConflictTexts,
+    GitGetCommitSignature,
     GitReset,
     GitDeleteBranch,
     GitCheckoutFiles,
@@ -895,6 +990,8 @@ impl LspQuery {
             Some(lsp_query::Request::GetReferences(_)) => ("GetReferences", false),
             Some(lsp_query::Request::GetDocumentColor(_)) => ("GetDocumentColor", false),
             Some(lsp_query::Request::InlayHints(_)) => ("InlayHints", false),
+            Some(lsp_query::Request::GetSemanticTokens(_)) => ("GetSemanticTokens", false),
+            Some(lsp_query::Request::GetFoldingRanges(_)) => ("GetFoldingRanges", false),
             None => ("<unknown>", true),
         }
     }
@@ -1,4 +1,5 @@
 mod cell;
 mod notebook_ui;
+mod semantics_provider;
 pub use cell::*;
 pub use notebook_ui::*;
@@ -441,6 +441,10 @@ pub struct CodeCell {
 }
 
 impl CodeCell {
+    pub fn editor(&self) -> &Entity<editor::Editor> {
+        &self.editor
+    }
+
     pub fn is_dirty(&self, cx: &App) -> bool {
         self.editor.read(cx).buffer().read(cx).is_dirty(cx)
     }
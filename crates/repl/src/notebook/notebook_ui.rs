@@ -1,26 +1,29 @@
 #![allow(unused, dead_code)]
 use std::future::Future;
+use std::rc::Rc;
 use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use client::proto::ViewId;
 use collections::HashMap;
+use editor::SemanticsProvider;
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    Point, Subscription, Task, actions, list, prelude::*,
 };
 use language::{Language, LanguageRegistry};
-use project::{Project, ProjectEntryId, ProjectPath};
+use project::{Project, ProjectEntryId, ProjectPath, lsp_store::NotebookCellBuffer};
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
 use workspace::searchable::SearchableItemHandle;
 use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{Cell, CellPosition, RenderableCell, semantics_provider::NotebookSemanticsProvider};
 
 use nbformat::v4::CellId;
 use nbformat::v4::Metadata as NotebookMetadata;
@@ -84,6 +87,7 @@ pub struct NotebookEditor {
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     cell_map: HashMap<CellId, Cell>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl NotebookEditor {
@@ -122,6 +126,57 @@ impl NotebookEditor {
             );
         }
 
+        // Code cells are synchronized with the language servers as the cells of a notebook document,
+        // which also lets their diagnostics and hovers be mapped back onto them.
+        let lsp_store = project.read(cx).lsp_store();
+        let semantics_provider: Rc<dyn SemanticsProvider> = Rc::new(NotebookSemanticsProvider {
+            lsp_store: lsp_store.clone(),
+        });
+        let mut cell_buffers = Vec::new();
+        for cell_id in &cell_order {
+            let Some(Cell::Code(code_cell)) = cell_map.get(cell_id) else {
+                continue;
+            };
+            let editor = code_cell.read(cx).editor().clone();
+            editor.update(cx, |editor, _| {
+                editor.set_semantics_provider(Some(semantics_provider.clone()));
+            });
+            if let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
+                let id: &str = cell_id.as_ref();
+                cell_buffers.push(NotebookCellBuffer {
+                    id: id.to_string(),
+                    kind: lsp::NotebookCellKind::Code,
+                    buffer,
+                });
+            }
+        }
+        let abs_path = notebook_item.read(cx).path.clone();
+        cx.spawn({
+            let lsp_store = lsp_store.clone();
+            let abs_path = abs_path.clone();
+            let notebook_language = notebook_language.clone();
+            async move |_, cx| {
+                let Some(language) = notebook_language.await else {
+                    return Ok(());
+                };
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.open_notebook_document(
+                        &abs_path,
+                        "jupyter-notebook",
+                        language,
+                        cell_buffers,
+                        cx,
+                    )
+                })?
+            }
+        })
+        .detach_and_log_err(cx);
+        let release_subscription = cx.on_release(move |_, cx| {
+            lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.close_notebook_document(&abs_path, cx);
+            });
+        });
+
         let notebook_handle = cx.entity().downgrade();
         let cell_count = cell_order.len();
 
@@ -138,6 +193,7 @@ impl NotebookEditor {
             selected_cell_index: 0,
             cell_order: cell_order.clone(),
             cell_map: cell_map.clone(),
+            _subscriptions: vec![release_subscription],
         }
     }
 
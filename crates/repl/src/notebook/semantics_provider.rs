@@ -0,0 +1,105 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::{GotoDefinitionKind, SemanticsProvider};
+use gpui::{App, Entity, Task};
+use language::{Anchor, Buffer, BufferId, BufferRow};
+use project::{
+    DocumentHighlight, Hover, InlayHint, LocationLink, ProjectTransaction,
+    lsp_store::{CacheInlayHints, InvalidationStrategy, LspStore},
+};
+
+/// Provides the cell editors of a notebook with the language features of the servers the notebook is open in.
+///
+/// Cells are not project buffers, so only the features requested through the notebook document are available.
+pub(crate) struct NotebookSemanticsProvider {
+    pub(crate) lsp_store: Entity<LspStore>,
+}
+
+impl SemanticsProvider for NotebookSemanticsProvider {
+    fn hover(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: Anchor,
+        cx: &mut App,
+    ) -> Option<Task<Option<Vec<Hover>>>> {
+        let hovers = self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.notebook_cell_hover(buffer, position, cx)
+        });
+        Some(cx.background_spawn(async move { Some(hovers.await) }))
+    }
+
+    fn inline_values(
+        &self,
+        _: Entity<Buffer>,
+        _: Range<Anchor>,
+        _: &mut App,
+    ) -> Option<Task<Result<Vec<InlayHint>>>> {
+        None
+    }
+
+    fn applicable_inlay_chunks(
+        &self,
+        _: &Entity<Buffer>,
+        _: &[Range<Anchor>],
+        _: &mut App,
+    ) -> Vec<Range<BufferRow>> {
+        Vec::new()
+    }
+
+    fn invalidate_inlay_hints(&self, _: &HashSet<BufferId>, _: &mut App) {}
+
+    fn inlay_hints(
+        &self,
+        _: InvalidationStrategy,
+        _: Entity<Buffer>,
+        _: Vec<Range<Anchor>>,
+        _: Option<(clock::Global, HashSet<Range<BufferRow>>)>,
+        _: &mut App,
+    ) -> Option<HashMap<Range<BufferRow>, Task<Result<CacheInlayHints>>>> {
+        None
+    }
+
+    fn supports_inlay_hints(&self, _: &Entity<Buffer>, _: &mut App) -> bool {
+        false
+    }
+
+    fn document_highlights(
+        &self,
+        _: &Entity<Buffer>,
+        _: Anchor,
+        _: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentHighlight>>>> {
+        None
+    }
+
+    fn definitions(
+        &self,
+        _: &Entity<Buffer>,
+        _: Anchor,
+        _: GotoDefinitionKind,
+        _: &mut App,
+    ) -> Option<Task<Result<Option<Vec<LocationLink>>>>> {
+        None
+    }
+
+    fn range_for_rename(
+        &self,
+        _: &Entity<Buffer>,
+        _: Anchor,
+        _: &mut App,
+    ) -> Option<Task<Result<Option<Range<Anchor>>>>> {
+        None
+    }
+
+    fn perform_rename(
+        &self,
+        _: &Entity<Buffer>,
+        _: Anchor,
+        _: String,
+        _: &mut App,
+    ) -> Option<Task<Result<ProjectTransaction>>> {
+        None
+    }
+}
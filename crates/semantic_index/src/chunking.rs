@@ -0,0 +1,171 @@
+use language::{Language, with_parser, with_query_cursor};
+use sha2::{Digest, Sha256};
+use std::{cmp, ops::Range, sync::Arc};
+use streaming_iterator::StreamingIterator;
+
+/// The largest chunk we embed, in bytes. Most embedding models have a context of a few thousand
+/// tokens, and smaller chunks make for more precise search results.
+const MAX_CHUNK_SIZE: usize = 1500;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    pub range: Range<usize>,
+    pub digest: [u8; 32],
+}
+
+/// Splits a file into chunks to embed.
+///
+/// When the file's language has an outline query, chunks end where an outline item (a function, a
+/// type, etc.) begins, so that an item is only split across chunks when it doesn't fit in one.
+/// Otherwise, chunks end at line boundaries.
+pub fn chunk_text(text: &str, language: Option<&Arc<Language>>, path_prefix: &str) -> Vec<Chunk> {
+    let boundaries = language
+        .map(|language| syntactic_boundaries(text, language))
+        .unwrap_or_default();
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut boundaries = boundaries.into_iter().peekable();
+    while start < text.len() {
+        let max_end = floor_char_boundary(text, cmp::min(start + MAX_CHUNK_SIZE, text.len()));
+
+        let mut end = None;
+        while let Some(&boundary) = boundaries.peek() {
+            if boundary <= start {
+                boundaries.next();
+            } else if boundary <= max_end {
+                end = Some(boundary);
+                boundaries.next();
+            } else {
+                break;
+            }
+        }
+
+        let end = if max_end == text.len() {
+            max_end
+        } else if let Some(end) = end {
+            end
+        } else if let Some(newline_ix) = text[start..max_end].rfind('\n') {
+            start + newline_ix + 1
+        } else {
+            // A single line is longer than a chunk.
+            max_end
+        };
+
+        chunks.push(Chunk {
+            range: start..end,
+            digest: digest(path_prefix, &text[start..end]),
+        });
+        start = end;
+    }
+
+    chunks
+}
+
+/// The text that is embedded for a chunk. Including the path helps to match queries that mention
+/// the area of the codebase.
+pub fn text_to_embed(path_prefix: &str, chunk_text: &str) -> String {
+    format!("{path_prefix}\n{chunk_text}")
+}
+
+fn digest(path_prefix: &str, chunk_text: &str) -> [u8; 32] {
+    Sha256::digest(text_to_embed(path_prefix, chunk_text)).into()
+}
+
+/// Returns the offsets of the starts of the lines on which outline items begin.
+fn syntactic_boundaries(text: &str, language: &Arc<Language>) -> Vec<usize> {
+    let Some(grammar) = language.grammar() else {
+        return Vec::new();
+    };
+    let Some(outline) = grammar.outline_config.as_ref() else {
+        return Vec::new();
+    };
+    let Some(tree) = with_parser(|parser| {
+        parser.set_language(&grammar.ts_language).ok()?;
+        parser.parse(text, None)
+    }) else {
+        return Vec::new();
+    };
+
+    let mut boundaries = with_query_cursor(|cursor| {
+        let mut boundaries = Vec::new();
+        let mut matches = cursor.matches(&outline.query, tree.root_node(), text.as_bytes());
+        while let Some(mat) = matches.next() {
+            for capture in mat.captures {
+                if capture.index == outline.item_capture_ix {
+                    let item_start = capture.node.start_byte();
+                    let line_start = text[..item_start].rfind('\n').map_or(0, |ix| ix + 1);
+                    boundaries.push(line_start);
+                }
+            }
+        }
+        boundaries
+    });
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    boundaries
+}
+
+fn floor_char_boundary(text: &str, mut ix: usize) -> usize {
+    while !text.is_char_boundary(ix) {
+        ix -= 1;
+    }
+    ix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_cover_text() {
+        let text = "line\n".repeat(1000);
+        let chunks = chunk_text(&text, None, "a.txt");
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].range.start, 0);
+        assert_eq!(chunks.last().unwrap().range.end, text.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].range.end, pair[1].range.start);
+        }
+        for chunk in &chunks {
+            assert!(chunk.range.len() <= MAX_CHUNK_SIZE);
+            assert!(text[chunk.range.clone()].ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn test_chunks_split_at_outline_items() {
+        let first_function = format!("fn first() {{\n{}}}\n\n", "    let x = 1;\n".repeat(90));
+        let second_function = format!(
+            "fn second() {{\n{}}}\n",
+            "    println!(\"hello\");\n".repeat(10)
+        );
+        let text = format!("{first_function}{second_function}");
+        assert!(text.len() > MAX_CHUNK_SIZE);
+
+        let chunks = chunk_text(&text, Some(&language::rust_lang()), "lib.rs");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(&text[chunks[0].range.clone()], first_function);
+        assert_eq!(&text[chunks[1].range.clone()], second_function);
+    }
+
+    #[test]
+    fn test_digest_depends_on_content() {
+        let a = chunk_text("fn a() {}\n", None, "lib.rs");
+        let b = chunk_text("fn b() {}\n", None, "lib.rs");
+        let c = chunk_text("fn a() {}\n", None, "lib.rs");
+        assert_ne!(a[0].digest, b[0].digest);
+        assert_eq!(a[0].digest, c[0].digest);
+    }
+
+    #[test]
+    fn test_long_lines_are_split_on_char_boundaries() {
+        let text = "é".repeat(MAX_CHUNK_SIZE);
+        let chunks = chunk_text(&text, None, "a.txt");
+        assert_eq!(chunks.last().unwrap().range.end, text.len());
+        for chunk in &chunks {
+            assert!(text.is_char_boundary(chunk.range.start));
+            assert!(text.is_char_boundary(chunk.range.end));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::embedding::Embedding;
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use db::{
+    sqlez::{connection::Connection, domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+use fs::MTime;
+use std::{ops::Range, sync::Arc};
+
+/// A chunk of a file, along with its embedding.
+#[derive(Clone, Debug)]
+pub struct EmbeddedChunk {
+    pub range: Range<usize>,
+    pub digest: [u8; 32],
+    pub embedding: Embedding,
+}
+
+/// Stores the embeddings of the chunks of indexed files.
+///
+/// Files are keyed by the absolute path of their worktree and the embedding model, so that
+/// switching models or opening the same directory in several windows doesn't mix up embeddings.
+#[derive(Clone)]
+pub struct IndexDatabase(ThreadSafeConnection);
+
+impl Domain for IndexDatabase {
+    const NAME: &str = stringify!(IndexDatabase);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE semantic_index_files (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            worktree TEXT NOT NULL,
+            model TEXT NOT NULL,
+            path TEXT NOT NULL,
+            mtime_seconds INTEGER NOT NULL,
+            mtime_nanos INTEGER NOT NULL,
+            UNIQUE(worktree, model, path)
+        ) STRICT;
+        CREATE TABLE semantic_index_chunks (
+            file_id INTEGER NOT NULL,
+            start_offset INTEGER NOT NULL,
+            end_offset INTEGER NOT NULL,
+            digest BLOB NOT NULL,
+            embedding BLOB NOT NULL,
+            FOREIGN KEY(file_id) REFERENCES semantic_index_files(id)
+            ON DELETE CASCADE
+        ) STRICT;
+        CREATE INDEX semantic_index_chunks_by_file ON semantic_index_chunks (file_id);
+    )];
+}
+
+db::static_connection!(SEMANTIC_INDEX_DB, IndexDatabase, []);
+
+impl IndexDatabase {
+    /// Returns the modification time of each indexed file in the worktree, when it was indexed.
+    pub fn indexed_files(&self, worktree: &str, model: &str) -> Result<HashMap<String, MTime>> {
+        let mut select = self.select_bound::<(&str, &str), (String, u64, u32)>(sql!(
+            SELECT path, mtime_seconds, mtime_nanos FROM semantic_index_files
+            WHERE worktree = ? AND model = ?
+        ))?;
+        Ok(select((worktree, model))?
+            .into_iter()
+            .map(|(path, seconds, nanos)| (path, MTime::from_seconds_and_nanos(seconds, nanos)))
+            .collect())
+    }
+
+    /// Returns the embeddings of the chunks of a file, by the digest of the chunks' contents.
+    pub fn embeddings_by_digest(
+        &self,
+        worktree: &str,
+        model: &str,
+        path: &str,
+    ) -> Result<HashMap<[u8; 32], Embedding>> {
+        let mut select = self.select_bound::<(&str, &str, &str), ([u8; 32], Vec<u8>)>(sql!(
+            SELECT semantic_index_chunks.digest, semantic_index_chunks.embedding
+            FROM semantic_index_chunks
+            JOIN semantic_index_files ON semantic_index_chunks.file_id = semantic_index_files.id
+            WHERE semantic_index_files.worktree = ?
+                AND semantic_index_files.model = ?
+                AND semantic_index_files.path = ?
+        ))?;
+        select((worktree, model, path))?
+            .into_iter()
+            .map(|(digest, embedding)| Ok((digest, Embedding::from_bytes(&embedding)?)))
+            .collect()
+    }
+
+    pub async fn save_file(
+        &self,
+        worktree: Arc<str>,
+        model: String,
+        path: String,
+        mtime: MTime,
+        chunks: Vec<EmbeddedChunk>,
+    ) -> Result<()> {
+        let (mtime_seconds, mtime_nanos) = mtime
+            .to_seconds_and_nanos_for_persistence()
+            .unwrap_or_default();
+        self.write(move |connection| {
+            connection.with_savepoint("save_file", || {
+                Self::delete_file_internal(connection, &worktree, &model, &path)?;
+
+                let mut insert_file = connection
+                    .select_row_bound::<(&str, &str, &str, u64, u32), i64>(sql!(
+                        INSERT INTO semantic_index_files
+                            (worktree, model, path, mtime_seconds, mtime_nanos)
+                        VALUES (?, ?, ?, ?, ?)
+                        RETURNING id
+                    ))?;
+                let file_id = insert_file((
+                    worktree.as_ref(),
+                    model.as_str(),
+                    path.as_str(),
+                    mtime_seconds,
+                    mtime_nanos,
+                ))?
+                .context("failed to insert file")?;
+
+                let mut insert_chunk = connection
+                    .exec_bound::<(i64, usize, usize, &[u8], Vec<u8>)>(sql!(
+                        INSERT INTO semantic_index_chunks
+                            (file_id, start_offset, end_offset, digest, embedding)
+                        VALUES (?, ?, ?, ?, ?)
+                    ))?;
+                for chunk in &chunks {
+                    insert_chunk((
+                        file_id,
+                        chunk.range.start,
+                        chunk.range.end,
+                        chunk.digest.as_slice(),
+                        chunk.embedding.to_bytes(),
+                    ))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    pub async fn delete_file(&self, worktree: Arc<str>, model: String, path: String) -> Result<()> {
+        self.write(move |connection| {
+            Self::delete_file_internal(connection, &worktree, &model, &path)
+        })
+        .await
+    }
+
+    fn delete_file_internal(
+        connection: &Connection,
+        worktree: &str,
+        model: &str,
+        path: &str,
+    ) -> Result<()> {
+        // Deleting the file also deletes its chunks.
+        let mut delete_file = connection.exec_bound::<(&str, &str, &str)>(sql!(
+            DELETE FROM semantic_index_files WHERE worktree = ? AND model = ? AND path = ?
+        ))?;
+        delete_file((worktree, model, path))
+    }
+
+    /// Calls `f` with the path, range, and embedding of every indexed chunk in the worktree.
+    pub fn for_each_chunk(
+        &self,
+        worktree: &str,
+        model: &str,
+        mut f: impl FnMut(&str, Range<usize>, Embedding),
+    ) -> Result<()> {
+        let mut select =
+            self.select_bound::<(&str, &str), (String, usize, usize, Vec<u8>)>(sql!(
+                SELECT
+                    semantic_index_files.path,
+                    semantic_index_chunks.start_offset,
+                    semantic_index_chunks.end_offset,
+                    semantic_index_chunks.embedding
+                FROM semantic_index_chunks
+                JOIN semantic_index_files ON semantic_index_chunks.file_id = semantic_index_files.id
+                WHERE semantic_index_files.worktree = ? AND semantic_index_files.model = ?
+            ))?;
+        for (path, start, end, embedding) in select((worktree, model))? {
+            f(&path, start..end, Embedding::from_bytes(&embedding)?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(range: Range<usize>, digest: u8, embedding: Vec<f32>) -> EmbeddedChunk {
+        EmbeddedChunk {
+            range,
+            digest: [digest; 32],
+            embedding: Embedding::new(embedding),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_save_and_replace_file() {
+        let db = IndexDatabase::open_test_db("test_save_and_replace_file").await;
+        let root: Arc<str> = "/root".into();
+        let mtime = MTime::from_seconds_and_nanos(100, 5);
+
+        db.save_file(
+            root.clone(),
+            "fake".into(),
+            "src/lib.rs".into(),
+            mtime,
+            vec![
+                chunk(0..10, 1, vec![1., 0.]),
+                chunk(10..20, 2, vec![0., 1.]),
+            ],
+        )
+        .await
+        .unwrap();
+        db.save_file(
+            root.clone(),
+            "other".into(),
+            "src/lib.rs".into(),
+            mtime,
+            vec![chunk(0..5, 3, vec![1., 1.])],
+        )
+        .await
+        .unwrap();
+
+        let files = db.indexed_files("/root", "fake").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files["src/lib.rs"], mtime);
+
+        let embeddings = db
+            .embeddings_by_digest("/root", "fake", "src/lib.rs")
+            .unwrap();
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(embeddings[&[2; 32]], Embedding::new(vec![0., 1.]));
+
+        // Saving the file again replaces its chunks.
+        let new_mtime = MTime::from_seconds_and_nanos(200, 0);
+        db.save_file(
+            root.clone(),
+            "fake".into(),
+            "src/lib.rs".into(),
+            new_mtime,
+            vec![chunk(0..20, 4, vec![1., 0.])],
+        )
+        .await
+        .unwrap();
+        let mut chunks = Vec::new();
+        db.for_each_chunk("/root", "fake", |path, range, _| {
+            chunks.push((path.to_string(), range))
+        })
+        .unwrap();
+        assert_eq!(chunks, vec![("src/lib.rs".to_string(), 0..20)]);
+        assert_eq!(
+            db.indexed_files("/root", "fake").unwrap()["src/lib.rs"],
+            new_mtime
+        );
+
+        db.delete_file(root.clone(), "fake".into(), "src/lib.rs".into())
+            .await
+            .unwrap();
+        assert!(db.indexed_files("/root", "fake").unwrap().is_empty());
+        assert_eq!(db.indexed_files("/root", "other").unwrap().len(), 1);
+
+        // Deleting a file deletes its chunks.
+        let mut chunks = Vec::new();
+        db.for_each_chunk("/root", "fake", |path, range, _| {
+            chunks.push((path.to_string(), range))
+        })
+        .unwrap();
+        assert!(chunks.is_empty());
+    }
+}
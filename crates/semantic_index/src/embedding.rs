@@ -0,0 +1,222 @@
+use anyhow::{Context as _, Result};
+use futures::{FutureExt as _, future::BoxFuture};
+use http_client::HttpClient;
+use std::sync::Arc;
+
+/// A normalized embedding vector, so that the similarity of two embeddings is their dot product.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embedding(Vec<f32>);
+
+impl Embedding {
+    pub fn new(mut embedding: Vec<f32>) -> Self {
+        let norm = embedding
+            .iter()
+            .map(|value| value * value)
+            .sum::<f32>()
+            .sqrt();
+        if norm > 0. {
+            for value in &mut embedding {
+                *value /= norm;
+            }
+        }
+        Self(embedding)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The cosine similarity of two embeddings, between -1 and 1.
+    pub fn similarity(&self, other: &Embedding) -> f32 {
+        debug_assert_eq!(self.0.len(), other.0.len());
+        self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        anyhow::ensure!(bytes.len() % 4 == 0, "invalid embedding length");
+        Ok(Self(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        ))
+    }
+}
+
+/// A service that computes embeddings for text.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifies the model, so that embeddings computed by different models are never compared.
+    fn model_id(&self) -> &str;
+    /// Computes one embedding per text, in order.
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>>;
+    /// How many texts to embed per request.
+    fn batch_size(&self) -> usize;
+}
+
+pub struct OllamaEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+    model_id: String,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(client: Arc<dyn HttpClient>, api_url: String, model: String) -> Self {
+        Self {
+            client,
+            model_id: format!("ollama/{model}"),
+            api_url,
+            model,
+        }
+    }
+}
+
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let request = ollama::EmbedRequest {
+                model: &self.model,
+                input: texts.iter().map(String::as_str).collect(),
+            };
+            let embeddings = ollama::embed(self.client.as_ref(), &self.api_url, None, request)
+                .await
+                .context("failed to compute embeddings with Ollama")?;
+            Ok(embeddings.into_iter().map(Embedding::new).collect())
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        32
+    }
+}
+
+pub struct OpenAiEmbeddingProvider {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    api_key: Arc<str>,
+    model: String,
+    model_id: String,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        api_url: String,
+        api_key: Arc<str>,
+        model: String,
+    ) -> Self {
+        Self {
+            client,
+            model_id: format!("openai/{model}"),
+            api_url,
+            api_key,
+            model,
+        }
+    }
+}
+
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        let response = open_ai::embed(
+            self.client.as_ref(),
+            &self.api_url,
+            &self.api_key,
+            &self.model,
+            texts.iter().map(String::as_str),
+        );
+        async move {
+            let response = response.await?;
+            Ok(response
+                .data
+                .into_iter()
+                .map(|embedding| Embedding::new(embedding.embedding))
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        // The API accepts up to 2048 inputs per request, but limits the total number of tokens.
+        256
+    }
+}
+
+/// Embeds text as counts of its lowercase words, hashed into a small vector, so that texts that
+/// share words are similar.
+#[cfg(any(test, feature = "test-support"))]
+pub struct FakeEmbeddingProvider;
+
+#[cfg(any(test, feature = "test-support"))]
+impl EmbeddingProvider for FakeEmbeddingProvider {
+    fn model_id(&self) -> &str {
+        "fake"
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+
+        let embeddings = texts
+            .iter()
+            .map(|text| {
+                let mut embedding = vec![0.; 64];
+                for word in text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                {
+                    let mut hasher = DefaultHasher::new();
+                    word.to_lowercase().hash(&mut hasher);
+                    embedding[hasher.finish() as usize % 64] += 1.;
+                }
+                Embedding::new(embedding)
+            })
+            .collect();
+        futures::future::ready(Ok(embeddings)).boxed()
+    }
+
+    fn batch_size(&self) -> usize {
+        16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embeddings_are_normalized() {
+        let a = Embedding::new(vec![3., 4.]);
+        let b = Embedding::new(vec![6., 8.]);
+        let c = Embedding::new(vec![-4., 3.]);
+        assert!((a.similarity(&b) - 1.).abs() < 1e-6);
+        assert!(a.similarity(&c).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_embedding_bytes_roundtrip() {
+        let embedding = Embedding::new(vec![1., -2., 0.5]);
+        let bytes = embedding.to_bytes();
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(Embedding::from_bytes(&bytes).unwrap(), embedding);
+        assert!(Embedding::from_bytes(&bytes[1..]).is_err());
+    }
+}
@@ -0,0 +1,719 @@
+mod chunking;
+mod db;
+mod embedding;
+mod semantic_index_settings;
+
+use crate::db::{EmbeddedChunk, IndexDatabase, SEMANTIC_INDEX_DB};
+use anyhow::{Context as _, Result, anyhow};
+use chunking::{chunk_text, text_to_embed};
+use collections::HashMap;
+use credentials_provider::CredentialsProvider;
+use fs::Fs;
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::mpsc,
+    future::{Shared, join_all},
+};
+use gpui::{
+    App, AppContext as _, AsyncApp, Context, Entity, EntityId, Global, SharedString, Subscription,
+    Task, WeakEntity,
+};
+use language::LanguageRegistry;
+use project::{Project, WorktreeId};
+use settings::{EmbeddingProviderContent, Settings as _, SettingsStore};
+use std::{
+    cmp,
+    collections::BTreeSet,
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+    sync::Arc,
+};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::{Entry, Worktree};
+
+pub use embedding::*;
+pub use semantic_index_settings::*;
+
+/// Files larger than this are not indexed, as they're usually generated or data files.
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+
+type EmbeddingProviderTask = Shared<Task<Result<Arc<dyn EmbeddingProvider>, Arc<anyhow::Error>>>>;
+
+struct GlobalSemanticDb(Entity<SemanticDb>);
+
+impl Global for GlobalSemanticDb {}
+
+/// Owns the semantic indices of the open projects, which are stored in a single database.
+pub struct SemanticDb {
+    db: Arc<IndexDatabase>,
+    settings: SemanticIndexSettings,
+    project_indices: HashMap<EntityId, Entity<ProjectIndex>>,
+    _settings_subscription: Subscription,
+}
+
+impl SemanticDb {
+    pub fn global(cx: &mut App) -> Entity<Self> {
+        if let Some(semantic_db) = cx.try_global::<GlobalSemanticDb>() {
+            return semantic_db.0.clone();
+        }
+
+        let semantic_db = cx.new(|cx| Self {
+            db: Arc::new(SEMANTIC_INDEX_DB.clone()),
+            settings: SemanticIndexSettings::get_global(cx).clone(),
+            project_indices: HashMap::default(),
+            _settings_subscription: cx.observe_global::<SettingsStore>(|this, cx| {
+                let settings = SemanticIndexSettings::get_global(cx);
+                if *settings != this.settings {
+                    // Drop the indices, so that they are rebuilt with the new embedding model.
+                    this.settings = settings.clone();
+                    this.project_indices.clear();
+                    cx.notify();
+                }
+            }),
+        });
+        cx.set_global(GlobalSemanticDb(semantic_db.clone()));
+        semantic_db
+    }
+
+    /// Returns the index of the project, and starts indexing it in the background if needed.
+    /// Returns `None` when semantic indexing is disabled, or for remote projects.
+    pub fn project_index(project: Entity<Project>, cx: &mut App) -> Option<Entity<ProjectIndex>> {
+        if !SemanticIndexSettings::get_global(cx).enabled || !project.read(cx).is_local() {
+            return None;
+        }
+        Self::global(cx).update(cx, |this, cx| this.project_index_internal(project, cx))
+    }
+
+    fn project_index_internal(
+        &mut self,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<ProjectIndex>> {
+        let project_id = project.entity_id();
+        if let Some(project_index) = self.project_indices.get(&project_id) {
+            return Some(project_index.clone());
+        }
+
+        let embedding_provider = embedding_provider(&self.settings, cx);
+        let db = self.db.clone();
+        let project_index =
+            cx.new(|cx| ProjectIndex::new(project.clone(), db, embedding_provider, cx));
+        self.project_indices
+            .insert(project_id, project_index.clone());
+        cx.observe_release(&project, move |this, _, _| {
+            this.project_indices.remove(&project_id);
+        })
+        .detach();
+        Some(project_index)
+    }
+}
+
+fn embedding_provider(settings: &SemanticIndexSettings, cx: &App) -> EmbeddingProviderTask {
+    let client = cx.http_client();
+    let model = settings.embedding_model.clone();
+    let task = match settings.embedding_provider {
+        EmbeddingProviderContent::Ollama => {
+            let api_url = settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| ollama::OLLAMA_API_URL.to_string());
+            let provider: Arc<dyn EmbeddingProvider> =
+                Arc::new(OllamaEmbeddingProvider::new(client, api_url, model));
+            Task::ready(Ok(provider))
+        }
+        EmbeddingProviderContent::OpenAi => {
+            let api_url = settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| open_ai::OPEN_AI_API_URL.to_string());
+            let credentials_provider = <dyn CredentialsProvider>::global(cx);
+            cx.spawn(async move |cx| {
+                let api_key: Arc<str> = if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+                    api_key.into()
+                } else {
+                    language_model::ApiKey::load_from_system_keychain(
+                        &api_url,
+                        credentials_provider.as_ref(),
+                        cx,
+                    )
+                    .await
+                    .map_err(|_| {
+                        anyhow!(
+                            "No API key for {api_url}. Set OPENAI_API_KEY, or configure the \
+                            OpenAI provider in the agent settings."
+                        )
+                    })?
+                    .key()
+                    .into()
+                };
+                let provider: Arc<dyn EmbeddingProvider> = Arc::new(OpenAiEmbeddingProvider::new(
+                    client, api_url, api_key, model,
+                ));
+                Ok(provider)
+            })
+        }
+    };
+    task.map(|result| result.map_err(Arc::new)).shared()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Idle,
+    Indexing { remaining_files: usize },
+    Error(SharedString),
+}
+
+/// The semantic index of a project's local worktrees.
+pub struct ProjectIndex {
+    project: WeakEntity<Project>,
+    db: Arc<IndexDatabase>,
+    embedding_provider: EmbeddingProviderTask,
+    worktree_indices: HashMap<WorktreeId, WorktreeIndex>,
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+struct WorktreeIndex {
+    worktree: Entity<Worktree>,
+    /// Identifies the worktree in the database.
+    key: Arc<str>,
+    remaining_files: usize,
+    _subscription: Subscription,
+    _index_task: Task<()>,
+}
+
+/// A chunk of a file that is similar to the query.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub worktree: Entity<Worktree>,
+    pub path: Arc<RelPath>,
+    pub range: Range<usize>,
+    pub score: f32,
+}
+
+/// A search result along with the current contents of its lines.
+#[derive(Clone, Debug)]
+pub struct LoadedSearchResult {
+    /// The path of the file, starting with the name of its worktree.
+    pub full_path: Arc<RelPath>,
+    pub abs_path: PathBuf,
+    /// Zero-based, inclusive range of rows.
+    pub row_range: RangeInclusive<u32>,
+    pub excerpt: String,
+    pub score: f32,
+}
+
+impl ProjectIndex {
+    fn new(
+        project: Entity<Project>,
+        db: Arc<IndexDatabase>,
+        embedding_provider: EmbeddingProviderTask,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            project: project.downgrade(),
+            db,
+            embedding_provider,
+            worktree_indices: HashMap::default(),
+            error: None,
+            _subscription: cx.subscribe(&project, |this, project, event, cx| match event {
+                project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                    this.update_worktree_indices(&project, cx);
+                }
+                _ => {}
+            }),
+        };
+        this.update_worktree_indices(&project, cx);
+        this
+    }
+
+    pub fn status(&self) -> Status {
+        if let Some(error) = &self.error {
+            return Status::Error(error.clone());
+        }
+        let remaining_files = self
+            .worktree_indices
+            .values()
+            .map(|index| index.remaining_files)
+            .sum();
+        if remaining_files > 0 {
+            Status::Indexing { remaining_files }
+        } else {
+            Status::Idle
+        }
+    }
+
+    fn update_worktree_indices(&mut self, project: &Entity<Project>, cx: &mut Context<Self>) {
+        let worktrees = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .map(|worktree| (worktree.read(cx).id(), worktree))
+            .collect::<HashMap<_, _>>();
+        self.worktree_indices
+            .retain(|worktree_id, _| worktrees.contains_key(worktree_id));
+
+        let fs = project.read(cx).fs().clone();
+        let languages = project.read(cx).languages().clone();
+        for (worktree_id, worktree) in worktrees {
+            if self.worktree_indices.contains_key(&worktree_id) {
+                continue;
+            }
+
+            let (changed_paths_tx, changed_paths_rx) = mpsc::unbounded();
+            let subscription = cx.subscribe(&worktree, move |_, _, event, _| {
+                if let worktree::Event::UpdatedEntries(changes) = event {
+                    for (path, _, _) in changes.iter() {
+                        changed_paths_tx.unbounded_send(path.clone()).ok();
+                    }
+                }
+            });
+            let key: Arc<str> = worktree.read(cx).abs_path().to_string_lossy().into();
+            let index_task = cx.spawn({
+                let worktree = worktree.clone();
+                let key = key.clone();
+                let fs = fs.clone();
+                let languages = languages.clone();
+                async move |this, cx| {
+                    let result = Self::index_worktree(
+                        this.clone(),
+                        worktree_id,
+                        worktree,
+                        key,
+                        changed_paths_rx,
+                        fs,
+                        languages,
+                        cx,
+                    )
+                    .await;
+                    if let Err(error) = result {
+                        log::error!("failed to index worktree: {error:#}");
+                        this.update(cx, |this, cx| {
+                            this.error = Some(format!("{error:#}").into());
+                            cx.notify();
+                        })
+                        .ok();
+                    }
+                }
+            });
+            self.worktree_indices.insert(
+                worktree_id,
+                WorktreeIndex {
+                    worktree,
+                    key,
+                    remaining_files: 0,
+                    _subscription: subscription,
+                    _index_task: index_task,
+                },
+            );
+        }
+        cx.notify();
+    }
+
+    async fn index_worktree(
+        this: WeakEntity<Self>,
+        worktree_id: WorktreeId,
+        worktree: Entity<Worktree>,
+        key: Arc<str>,
+        mut changed_paths: mpsc::UnboundedReceiver<Arc<RelPath>>,
+        fs: Arc<dyn Fs>,
+        languages: Arc<LanguageRegistry>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let (db, embedding_provider) = this.read_with(cx, |this, _| {
+            (this.db.clone(), this.embedding_provider.clone())
+        })?;
+        let embedding_provider = embedding_provider
+            .await
+            .map_err(|error| anyhow!("{error:#}"))?;
+
+        if let Some(scan_complete) = worktree.read_with(cx, |worktree, _| {
+            worktree.as_local().map(|worktree| worktree.scan_complete())
+        })? {
+            scan_complete.await;
+        }
+
+        // Find the files that changed since the worktree was last indexed.
+        let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot())?;
+        let files = snapshot
+            .files(false, 0)
+            .filter(|entry| should_index(entry))
+            .map(|entry| (entry.path.clone(), entry.mtime))
+            .collect::<Vec<_>>();
+        let model_id = embedding_provider.model_id().to_string();
+        let mut pending_paths = cx
+            .background_spawn({
+                let db = db.clone();
+                let key = key.clone();
+                let model_id = model_id.clone();
+                async move {
+                    let mut indexed_files = db.indexed_files(&key, &model_id)?;
+                    let mut pending_paths = BTreeSet::new();
+                    for (path, mtime) in files {
+                        let indexed_mtime = indexed_files.remove(path.as_unix_str());
+                        if mtime.is_none() || indexed_mtime != mtime {
+                            pending_paths.insert(path);
+                        }
+                    }
+                    for path in indexed_files.into_keys() {
+                        db.delete_file(key.clone(), model_id.clone(), path).await?;
+                    }
+                    anyhow::Ok(pending_paths)
+                }
+            })
+            .await?;
+
+        loop {
+            // Coalesce the paths that changed while the previous ones were being indexed.
+            while let Ok(Some(path)) = changed_paths.try_next() {
+                pending_paths.insert(path);
+            }
+
+            let remaining_files = pending_paths.len();
+            this.update(cx, |this, cx| {
+                this.set_remaining_files(worktree_id, remaining_files, cx)
+            })?;
+            let Some(path) = pending_paths.pop_first() else {
+                match changed_paths.next().await {
+                    Some(path) => pending_paths.insert(path),
+                    None => return Ok(()),
+                };
+                continue;
+            };
+            let entry = worktree.read_with(cx, |worktree, _| {
+                worktree
+                    .entry_for_path(&path)
+                    .filter(|entry| should_index(entry))
+                    .cloned()
+            })?;
+            let full_path =
+                worktree.read_with(cx, |worktree, _| worktree.root_name().join(&path))?;
+            let result = match entry {
+                Some(entry) => {
+                    let abs_path =
+                        worktree.read_with(cx, |worktree, _| worktree.absolutize(&path))?;
+                    index_file(
+                        &db,
+                        &key,
+                        entry,
+                        abs_path,
+                        &full_path,
+                        embedding_provider.as_ref(),
+                        fs.as_ref(),
+                        &languages,
+                        cx,
+                    )
+                    .await
+                }
+                None => {
+                    db.delete_file(key.clone(), model_id.clone(), path.as_unix_str().into())
+                        .await
+                }
+            };
+            // A file that fails to index shouldn't stop the rest of the worktree from being indexed.
+            result
+                .with_context(|| format!("failed to index {}", full_path.as_unix_str()))
+                .log_err();
+        }
+    }
+
+    fn set_remaining_files(
+        &mut self,
+        worktree_id: WorktreeId,
+        remaining_files: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(index) = self.worktree_indices.get_mut(&worktree_id)
+            && index.remaining_files != remaining_files
+        {
+            index.remaining_files = remaining_files;
+            cx.notify();
+        }
+    }
+
+    /// Returns the chunks that are the most similar to the query, across all worktrees.
+    pub fn search(&self, query: String, limit: usize, cx: &App) -> Task<Result<Vec<SearchResult>>> {
+        let db = self.db.clone();
+        let embedding_provider = self.embedding_provider.clone();
+        let worktrees = self
+            .worktree_indices
+            .values()
+            .map(|index| (index.worktree.clone(), index.key.clone()))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let embedding_provider = embedding_provider
+                .await
+                .map_err(|error| anyhow!("{error:#}"))?;
+            let query_embedding = embedding_provider
+                .embed(&[query])
+                .await?
+                .pop()
+                .context("no embedding returned for the query")?;
+
+            let mut results = Vec::new();
+            for (worktree, key) in worktrees {
+                db.for_each_chunk(
+                    &key,
+                    embedding_provider.model_id(),
+                    |path, range, embedding| {
+                        if embedding.len() != query_embedding.len() {
+                            return;
+                        }
+                        let Ok(path) = RelPath::unix(path) else {
+                            return;
+                        };
+                        results.push(SearchResult {
+                            worktree: worktree.clone(),
+                            path: path.into_arc(),
+                            range,
+                            score: embedding.similarity(&query_embedding),
+                        });
+                    },
+                )?;
+            }
+
+            results.sort_unstable_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(limit);
+            Ok(results)
+        })
+    }
+
+    /// Reads the lines of each search result from disk. Results whose files can no longer be read
+    /// are omitted.
+    pub fn load_results(
+        &self,
+        results: Vec<SearchResult>,
+        cx: &App,
+    ) -> Task<Result<Vec<LoadedSearchResult>>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("project was dropped")));
+        };
+        let fs = project.read(cx).fs().clone();
+        let results = results
+            .into_iter()
+            .map(|result| {
+                let worktree = result.worktree.read(cx);
+                (
+                    worktree.root_name().join(&result.path),
+                    worktree.absolutize(&result.path),
+                    result,
+                )
+            })
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let loaded = join_all(results.into_iter().map(|(full_path, abs_path, result)| {
+                let fs = fs.clone();
+                async move {
+                    let text = fs.load(&abs_path).await.ok()?;
+                    let (row_range, excerpt) = lines_for_range(&text, result.range);
+                    Some(LoadedSearchResult {
+                        full_path,
+                        abs_path,
+                        row_range,
+                        excerpt,
+                        score: result.score,
+                    })
+                }
+            }))
+            .await;
+            Ok(loaded.into_iter().flatten().collect())
+        })
+    }
+}
+
+fn should_index(entry: &Entry) -> bool {
+    entry.is_file()
+        && !entry.is_ignored
+        && !entry.is_private
+        && !entry.is_external
+        && entry.size <= MAX_FILE_SIZE
+}
+
+async fn index_file(
+    db: &Arc<IndexDatabase>,
+    key: &Arc<str>,
+    entry: Entry,
+    abs_path: PathBuf,
+    full_path: &RelPath,
+    embedding_provider: &dyn EmbeddingProvider,
+    fs: &dyn Fs,
+    languages: &Arc<LanguageRegistry>,
+    cx: &AsyncApp,
+) -> Result<()> {
+    let model_id = embedding_provider.model_id().to_string();
+    let path = entry.path.as_unix_str().to_string();
+    let Ok(text) = fs.load(&abs_path).await else {
+        // Binary files can't be loaded as text, and aren't worth indexing.
+        return db.delete_file(key.clone(), model_id, path).await;
+    };
+    let language = languages.load_language_for_file_path(&abs_path).await.ok();
+
+    let path_prefix = full_path.as_unix_str().to_string();
+    let (chunks, mut embeddings_by_digest) = cx
+        .background_spawn({
+            let (db, key, model_id, path) =
+                (db.clone(), key.clone(), model_id.clone(), path.clone());
+            let text = text.clone();
+            let path_prefix = path_prefix.clone();
+            async move {
+                let chunks = chunk_text(&text, language.as_ref(), &path_prefix);
+                let embeddings_by_digest = db.embeddings_by_digest(&key, &model_id, &path)?;
+                anyhow::Ok((chunks, embeddings_by_digest))
+            }
+        })
+        .await?;
+
+    // Only embed the chunks whose contents changed since the file was last indexed.
+    let mut chunks_to_embed = chunks
+        .iter()
+        .filter(|chunk| !embeddings_by_digest.contains_key(&chunk.digest))
+        .collect::<Vec<_>>();
+    chunks_to_embed.dedup_by_key(|chunk| chunk.digest);
+    for batch in chunks_to_embed.chunks(cmp::max(embedding_provider.batch_size(), 1)) {
+        let texts = batch
+            .iter()
+            .map(|chunk| text_to_embed(&path_prefix, &text[chunk.range.clone()]))
+            .collect::<Vec<_>>();
+        let embeddings = embedding_provider.embed(&texts).await?;
+        anyhow::ensure!(
+            embeddings.len() == batch.len(),
+            "expected {} embeddings, got {}",
+            batch.len(),
+            embeddings.len()
+        );
+        for (chunk, embedding) in batch.iter().zip(embeddings) {
+            embeddings_by_digest.insert(chunk.digest, embedding);
+        }
+    }
+
+    let embedded_chunks = chunks
+        .into_iter()
+        .filter_map(|chunk| {
+            Some(EmbeddedChunk {
+                embedding: embeddings_by_digest.get(&chunk.digest)?.clone(),
+                range: chunk.range,
+                digest: chunk.digest,
+            })
+        })
+        .collect::<Vec<_>>();
+    let mtime = entry
+        .mtime
+        .unwrap_or_else(|| fs::MTime::from_seconds_and_nanos(0, 0));
+    db.save_file(key.clone(), model_id, path, mtime, embedded_chunks)
+        .await
+}
+
+/// Expands a byte range to whole lines, clamping it to the text, which may have changed since the
+/// range was indexed.
+fn lines_for_range(text: &str, range: Range<usize>) -> (RangeInclusive<u32>, String) {
+    let mut start = cmp::min(range.start, text.len());
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = cmp::min(range.end, text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let start = text[..start].rfind('\n').map_or(0, |ix| ix + 1);
+    let end = cmp::max(start, end);
+    let excerpt = text[start..end].trim_end_matches('\n');
+    let start_row = text[..start].matches('\n').count() as u32;
+    let end_row = start_row + excerpt.matches('\n').count() as u32;
+    (start_row..=end_row, excerpt.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    fn fake_provider() -> EmbeddingProviderTask {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(FakeEmbeddingProvider);
+        Task::ready(Ok(provider)).shared()
+    }
+
+    #[gpui::test]
+    async fn test_index_and_search(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "src": {
+                    "auth.rs": "fn verify_password(hash: &str, password: &str) -> bool { todo!() }\n",
+                    "render.rs": "fn paint_pixels(canvas: &mut Canvas) { todo!() }\n",
+                },
+                ".env": "SECRET_PASSWORD=hunter2\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let db = Arc::new(IndexDatabase::open_test_db("test_index_and_search").await);
+        let project_index =
+            cx.new(|cx| ProjectIndex::new(project.clone(), db, fake_provider(), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Idle
+        );
+
+        let results = project_index
+            .update(cx, |index, cx| index.search("password hash".into(), 1, cx))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path.as_unix_str(), "src/auth.rs");
+
+        // Changes to files are indexed incrementally.
+        fs.save(
+            path!("/project/src/render.rs").as_ref(),
+            &"fn check_password_strength(password: &str) -> u8 { todo!() }\n".into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        fs.remove_file(path!("/project/src/auth.rs").as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let results = project_index
+            .update(cx, |index, cx| index.search("password".into(), 1, cx))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path.as_unix_str(), "src/render.rs");
+
+        let loaded = project_index
+            .update(cx, |index, cx| index.load_results(results, cx))
+            .await
+            .unwrap();
+        assert_eq!(loaded[0].full_path.as_unix_str(), "project/src/render.rs");
+        assert_eq!(loaded[0].row_range, 0..=0);
+        assert_eq!(
+            loaded[0].excerpt,
+            "fn check_password_strength(password: &str) -> u8 { todo!() }"
+        );
+    }
+
+    #[test]
+    fn test_lines_for_range() {
+        let text = "one\ntwo\nthree\nfour\n";
+        assert_eq!(
+            lines_for_range(text, 5..9),
+            (1..=2, "two\nthree".to_string())
+        );
+        assert_eq!(lines_for_range(text, 14..100), (3..=3, "four".to_string()));
+    }
+}
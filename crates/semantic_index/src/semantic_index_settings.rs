@@ -0,0 +1,21 @@
+use settings::{EmbeddingProviderContent, RegisterSetting, Settings};
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct SemanticIndexSettings {
+    pub enabled: bool,
+    pub embedding_provider: EmbeddingProviderContent,
+    pub embedding_model: String,
+    pub api_url: Option<String>,
+}
+
+impl Settings for SemanticIndexSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let semantic_index = content.semantic_index.clone().unwrap();
+        Self {
+            enabled: semantic_index.enabled.unwrap(),
+            embedding_provider: semantic_index.embedding_provider.unwrap(),
+            embedding_model: semantic_index.embedding_model.unwrap(),
+            api_url: semantic_index.api_url,
+        }
+    }
+}
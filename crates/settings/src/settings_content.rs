@@ -100,6 +100,9 @@ pub struct SettingsContent {
 
     pub repl: Option<ReplSettingsContent>,
 
+    /// Settings for the semantic index of the project, which the agent searches by meaning.
+    pub semantic_index: Option<SemanticIndexSettingsContent>,
+
     /// Whether or not to enable Helix mode.
     ///
     /// Default: false
@@ -523,6 +526,40 @@ pub struct GitPanelSettingsContent {
     ///
     /// Default: false
     pub tree_view: Option<bool>,
+
+    /// Branches that should only receive signed commits. Committing to one of
+    /// them without signing the commit asks for confirmation first.
+    ///
+    /// Default: []
+    pub protected_branches: Option<Vec<String>>,
+
+    /// Checks that are run on the staged changes before committing.
+    pub commit_checks: Option<CommitChecksSettingsContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
+pub struct CommitChecksSettingsContent {
+    /// Whether to check the staged changes before committing.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Regular expressions for secrets that shouldn't be committed.
+    ///
+    /// Default: patterns for common API keys, tokens and private keys
+    pub secret_patterns: Option<Vec<String>>,
+    /// Globs for paths that shouldn't be committed.
+    ///
+    /// Default: []
+    pub forbidden_paths: Option<Vec<String>>,
+    /// The maximum size of binary files in bytes, or 0 to allow any size.
+    ///
+    /// Default: 5242880
+    pub max_binary_size: Option<u64>,
+    /// Whether to check for leftover merge conflict markers.
+    ///
+    /// Default: true
+    pub conflict_markers: Option<bool>,
 }
 
 #[derive(
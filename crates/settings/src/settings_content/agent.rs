@@ -55,6 +55,13 @@ pub struct AgentSettingsContent {
     pub thread_summary_model: Option<LanguageModelSelection>,
     /// Additional models with which to generate alternatives when performing inline assists.
     pub inline_alternatives: Option<Vec<LanguageModelSelection>>,
+    /// Models to switch to, in order, for the rest of a turn when the current model's provider
+    /// keeps failing with an error that retrying might fix (e.g. it is overloaded or rate limited)
+    /// after its retries are used up. Models that lack a capability the request needs (tools,
+    /// images or a large enough context window) are skipped.
+    ///
+    /// Default: []
+    pub fallback_models: Option<Vec<LanguageModelSelection>>,
     /// The default profile to use in the Agent.
     ///
     /// Default: write
@@ -285,10 +292,12 @@ impl JsonSchema for LanguageModelProviderSetting {
                         "copilot_chat",
                         "deepseek",
                         "google",
+                        "llama_cpp",
                         "lmstudio",
                         "mistral",
                         "ollama",
                         "openai",
+                        "openai_responses",
                         "openrouter",
                         "vercel",
                         "x_ai",
@@ -466,3 +475,36 @@ pub enum CustomAgentServerSettings {
         favorite_config_option_values: HashMap<String, Vec<String>>,
     },
 }
+
+/// Settings for the semantic index, which lets the agent search the project by meaning.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct SemanticIndexSettingsContent {
+    /// Whether to index the project's worktrees in the background, so that the agent can search
+    /// them with the `semantic_search` tool.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Which service computes the embeddings.
+    ///
+    /// Default: ollama
+    pub embedding_provider: Option<EmbeddingProviderContent>,
+    /// The embedding model to use. Changing it re-indexes the project.
+    ///
+    /// Default: "nomic-embed-text"
+    pub embedding_model: Option<String>,
+    /// The URL of the embedding service. Defaults to the provider's usual URL.
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderContent {
+    /// Compute embeddings locally with Ollama.
+    #[default]
+    Ollama,
+    /// Compute embeddings with the OpenAI API, or with a server that implements its
+    /// `/embeddings` endpoint.
+    #[serde(rename = "openai")]
+    OpenAi,
+}
@@ -449,6 +449,95 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: false
     pub colorize_brackets: Option<bool>,
+    /// How to use the semantic tokens provided by language servers to highlight code.
+    ///
+    /// Default: off
+    pub semantic_tokens: Option<SemanticTokens>,
+    /// Where the foldable ranges of the editor come from.
+    ///
+    /// Default: indentation
+    pub folding_ranges: Option<FoldingRanges>,
+    /// Where the ranges used to expand and shrink selections come from.
+    ///
+    /// Default: tree_sitter
+    pub selection_ranges: Option<SelectionRanges>,
+}
+
+/// How semantic tokens from language servers are used to highlight code.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SemanticTokens {
+    /// Only highlight code with tree-sitter queries.
+    #[default]
+    Off,
+    /// Layer semantic tokens over the tree-sitter highlights.
+    Combined,
+    /// Highlight code with semantic tokens instead of tree-sitter queries, where the language
+    /// server provides them.
+    Full,
+}
+
+/// Where the foldable ranges of the editor come from.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingRanges {
+    /// Fold by indentation and tree-sitter queries.
+    #[default]
+    Indentation,
+    /// Fold the ranges reported by language servers, including regions and imports, falling
+    /// back to indentation when no server provides them.
+    LanguageServer,
+}
+
+/// Where the ranges used to expand and shrink selections come from.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionRanges {
+    /// Expand selections to the enclosing tree-sitter syntax nodes.
+    #[default]
+    TreeSitter,
+    /// Expand selections to the enclosing ranges reported by a language server, falling back
+    /// to tree-sitter when no server provides them.
+    LanguageServer,
 }
 
 /// Controls how whitespace should be displayedin the editor.
@@ -12,16 +12,47 @@ pub struct AllLanguageModelSettingsContent {
     pub bedrock: Option<AmazonBedrockSettingsContent>,
     pub deepseek: Option<DeepseekSettingsContent>,
     pub google: Option<GoogleSettingsContent>,
+    pub llama_cpp: Option<LlamaCppSettingsContent>,
     pub lmstudio: Option<LmStudioSettingsContent>,
     pub mistral: Option<MistralSettingsContent>,
     pub ollama: Option<OllamaSettingsContent>,
     pub open_router: Option<OpenRouterSettingsContent>,
     pub openai: Option<OpenAiSettingsContent>,
     pub openai_compatible: Option<HashMap<Arc<str>, OpenAiCompatibleSettingsContent>>,
+    pub openai_responses: Option<OpenAiResponsesSettingsContent>,
     pub vercel: Option<VercelSettingsContent>,
     pub x_ai: Option<XAiSettingsContent>,
     #[serde(rename = "zed.dev")]
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
+    /// Client-side rate limits, keyed by provider ID (e.g. "anthropic", "openai", "amazon-bedrock").
+    pub rate_limits: Option<HashMap<Arc<str>, LanguageModelRateLimitContent>>,
+}
+
+/// Client-side limits for the requests Zed sends to a language model provider.
+///
+/// The limits are shared by all models of the provider, so that concurrent agent
+/// threads, inline assists and commit message generation back off together.
+#[with_fallible_options]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct LanguageModelRateLimitContent {
+    /// The maximum number of requests to send per minute.
+    ///
+    /// Default: unlimited
+    pub requests_per_minute: Option<u32>,
+    /// The maximum number of input tokens to send per minute, as estimated from the request size.
+    ///
+    /// Default: unlimited
+    pub tokens_per_minute: Option<u64>,
+    /// How many milliseconds to pause requests after the provider first reports that
+    /// the rate limit was exceeded without saying when to retry. Consecutive rate limit
+    /// errors double the pause.
+    ///
+    /// Default: 1000
+    pub initial_backoff_ms: Option<u64>,
+    /// The maximum number of milliseconds to pause requests after a rate limit error.
+    ///
+    /// Default: 60000
+    pub max_backoff_ms: Option<u64>,
 }
 
 #[with_fallible_options]
@@ -139,6 +170,29 @@ impl Default for KeepAlive {
     }
 }
 
+#[with_fallible_options]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct LlamaCppSettingsContent {
+    /// The URL of the `llama-server` instance, without the `/v1` suffix.
+    ///
+    /// Default: "http://localhost:8080"
+    pub api_url: Option<String>,
+    /// Models to offer in addition to, or instead of, the one the server reports.
+    pub available_models: Option<Vec<LlamaCppAvailableModel>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct LlamaCppAvailableModel {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub max_tokens: u64,
+    pub supports_tool_calls: bool,
+    #[serde(default)]
+    pub supports_parallel_tool_calls: bool,
+    pub supports_images: bool,
+}
+
 #[with_fallible_options]
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
 pub struct LmStudioSettingsContent {
@@ -278,6 +332,53 @@ impl Default for OpenAiCompatibleModelCapabilities {
     }
 }
 
+#[with_fallible_options]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct OpenAiResponsesSettingsContent {
+    /// The base URL of a gateway that serves the OpenAI Responses API, including the `/v1` suffix.
+    ///
+    /// Default: "http://localhost:8000/v1"
+    pub api_url: Option<String>,
+    /// Models to offer in addition to the ones the gateway lists, or to override what Zed
+    /// discovered about them.
+    pub available_models: Option<Vec<OpenAiResponsesAvailableModel>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct OpenAiResponsesAvailableModel {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub max_tokens: u64,
+    pub max_output_tokens: Option<u64>,
+    #[serde(default)]
+    pub capabilities: OpenAiResponsesModelCapabilities,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct OpenAiResponsesModelCapabilities {
+    #[serde(default = "default_true")]
+    pub tools: bool,
+    #[serde(default)]
+    pub images: bool,
+    #[serde(default)]
+    pub parallel_tool_calls: bool,
+    #[serde(default)]
+    pub prompt_cache_key: bool,
+}
+
+impl Default for OpenAiResponsesModelCapabilities {
+    fn default() -> Self {
+        Self {
+            tools: default_true(),
+            images: false,
+            parallel_tool_calls: false,
+            prompt_cache_key: false,
+        }
+    }
+}
+
 #[with_fallible_options]
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
 pub struct VercelSettingsContent {
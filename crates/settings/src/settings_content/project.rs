@@ -147,6 +147,9 @@ pub struct LspSettings {
     #[serde(default = "default_true")]
     pub enable_lsp_tasks: bool,
     pub fetch: Option<FetchSettings>,
+    /// When to restart the language server automatically.
+    /// The fields that are set override the ones of `global_lsp_settings.restart_policy`.
+    pub restart_policy: Option<LanguageServerRestartPolicyContent>,
 }
 
 impl Default for LspSettings {
@@ -157,6 +160,7 @@ impl Default for LspSettings {
             settings: None,
             enable_lsp_tasks: true,
             fetch: None,
+            restart_policy: None,
         }
     }
 }
@@ -189,6 +193,26 @@ pub struct GlobalLspSettingsContent {
     ///
     /// Default: `true`
     pub button: Option<bool>,
+    /// When to restart language servers automatically.
+    pub restart_policy: Option<LanguageServerRestartPolicyContent>,
+}
+
+/// When to restart a language server automatically.
+/// Its open documents are reopened, and their diagnostics pulled again, in the restarted server.
+#[with_fallible_options]
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom, Hash,
+)]
+pub struct LanguageServerRestartPolicyContent {
+    /// Restart the language server when its resident memory exceeds this many megabytes.
+    /// Only supported on Linux.
+    ///
+    /// Default: `null`
+    pub max_memory_mb: Option<u64>,
+    /// Restart the language server when this many of its requests time out in a row.
+    ///
+    /// Default: `null`
+    pub max_timed_out_requests: Option<u32>,
 }
 
 #[with_fallible_options]
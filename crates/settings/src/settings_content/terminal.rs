@@ -125,6 +125,12 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: true
     pub keep_selection_on_copy: Option<bool>,
+    /// Whether to make bash, zsh and fish report their prompts, commands and exit
+    /// statuses to the terminal, which enables jumping between prompts and copying
+    /// the output of commands.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
@@ -203,6 +203,7 @@ impl VsCodeSettings {
             proxy: self.read_string("http.proxy"),
             remote: RemoteSettingsContent::default(),
             repl: None,
+            semantic_index: None,
             server_url: None,
             session: None,
             status_bar: self.status_bar_settings_content(),
@@ -456,6 +457,21 @@ impl VsCodeSettings {
             remove_trailing_whitespace_on_save: self.read_bool("editor.trimAutoWhitespace"),
             show_completion_documentation: None,
             colorize_brackets: self.read_bool("editor.bracketPairColorization.enabled"),
+            semantic_tokens: self
+                .read_bool("editor.semanticHighlighting.enabled")
+                .map(|enabled| {
+                    if enabled {
+                        SemanticTokens::Combined
+                    } else {
+                        SemanticTokens::Off
+                    }
+                }),
+            folding_ranges: self.read_enum("editor.foldingStrategy", |s| match s {
+                "indentation" => Some(FoldingRanges::Indentation),
+                "auto" => Some(FoldingRanges::LanguageServer),
+                _ => None,
+            }),
+            selection_ranges: None,
             show_completions_on_input: self.read_bool("editor.suggestOnTriggerCharacters"),
             show_edit_predictions: self.read_bool("editor.inlineSuggest.enabled"),
             show_whitespaces: self.read_enum("editor.renderWhitespace", |s| {
@@ -753,6 +769,7 @@ impl VsCodeSettings {
             project: self.project_terminal_settings_content(),
             scrollbar: None,
             scroll_multiplier: None,
+            shell_integration: self.read_bool("terminal.integrated.shellIntegration.enabled"),
             toolbar: None,
         })
     }
@@ -5416,6 +5416,28 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Shell Integration",
+                    description: "Whether to make bash, zsh and fish report their commands to the terminal.",
+                    field: Box::new(SettingField {
+                        json_path: Some("terminal.shell_integration"),
+                        pick: |settings_content| {
+                            settings_content
+                                .terminal
+                                .as_ref()?
+                                .shell_integration
+                                .as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .shell_integration = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SectionHeader("Layout Settings"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Default Width",
@@ -7406,6 +7428,63 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
             metadata: None,
             files: USER | PROJECT,
         }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Semantic Tokens",
+            description: "How to use the semantic tokens provided by language servers to highlight code.",
+            field: Box::new(SettingField {
+                json_path: Some("languages.$(language).semantic_tokens"),
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        language.semantic_tokens.as_ref()
+                    })
+                },
+                write: |settings_content, value| {
+                    language_settings_field_mut(settings_content, value, |language, value| {
+                        language.semantic_tokens = value;
+                    })
+                },
+            }),
+            metadata: None,
+            files: USER | PROJECT,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Folding Ranges",
+            description: "Whether to fold by indentation or by the ranges reported by language servers.",
+            field: Box::new(SettingField {
+                json_path: Some("languages.$(language).folding_ranges"),
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        language.folding_ranges.as_ref()
+                    })
+                },
+                write: |settings_content, value| {
+                    language_settings_field_mut(settings_content, value, |language, value| {
+                        language.folding_ranges = value;
+                    })
+                },
+            }),
+            metadata: None,
+            files: USER | PROJECT,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Selection Ranges",
+            description: "Whether to expand selections to tree-sitter syntax nodes or to the ranges reported by language servers.",
+            field: Box::new(SettingField {
+                json_path: Some("languages.$(language).selection_ranges"),
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        language.selection_ranges.as_ref()
+                    })
+                },
+                write: |settings_content, value| {
+                    language_settings_field_mut(settings_content, value, |language, value| {
+                        language.selection_ranges = value;
+                    })
+                },
+            }),
+            metadata: None,
+            files: USER | PROJECT,
+        }),
     ]);
 
     if current_language().is_none() {
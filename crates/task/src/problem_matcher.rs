@@ -0,0 +1,831 @@
+//! Problem matchers turn the output of a task into problems, which are shown as diagnostics.
+//!
+//! A matcher is either one of the built-in ones, referred to by name, or a list of regexes with the capture groups
+//! holding the file, location, severity and message of each problem. The regexes of a multi-line matcher are
+//! matched against consecutive lines of the output, for tools that report a problem over several lines.
+//!
+//! Background tasks like watchers print the problems of each build after the previous ones. When a line of their
+//! output matches the `begins_pattern` of a matcher, the problems it found so far are cleared.
+
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result, bail};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Names of the built-in problem matchers.
+pub const BUILTIN_PROBLEM_MATCHERS: &[&str] = &[
+    "$rustc",
+    "$tsc",
+    "$tsc-watch",
+    "$eslint-compact",
+    "$eslint-stylish",
+    "$gcc",
+    "$semgrep",
+];
+
+/// Turns the lines of a task's output into problems, shown as diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// Name of a built-in matcher, e.g. `$rustc`.
+    Builtin(String),
+    /// A matcher defined by regexes.
+    Custom(CustomProblemMatcher),
+}
+
+/// A problem matcher defined by regexes, or built on top of a built-in one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Name of the built-in matcher to use the patterns of, when `pattern` is not set.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// A pattern matching a line of the output or, for problems reported over several lines,
+    /// a list of patterns matching consecutive lines.
+    #[serde(default)]
+    pub pattern: Option<ProblemPatterns>,
+    /// Source of the diagnostics, defaults to the label of the task.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems whose pattern does not capture one.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// Directory to resolve relative file paths against, defaults to the task's `cwd`.
+    #[serde(default)]
+    pub base_dir: Option<String>,
+    /// A regex matching the line a background task prints when it starts a new build, clearing
+    /// the problems of the previous one.
+    #[serde(default)]
+    pub begins_pattern: Option<String>,
+}
+
+/// One or several patterns of a problem matcher.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemPatterns {
+    Single(ProblemPattern),
+    Multiline(Vec<ProblemPattern>),
+}
+
+impl ProblemPatterns {
+    pub fn as_slice(&self) -> &[ProblemPattern] {
+        match self {
+            Self::Single(pattern) => std::slice::from_ref(pattern),
+            Self::Multiline(patterns) => patterns,
+        }
+    }
+}
+
+/// A regex matching a line of output, with the indices of the capture groups holding each part of a problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regex to match a line against.
+    pub regexp: String,
+    /// Capture group of the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Capture group of the location, as `line`, `line,column` or `line,column,end_line,end_column`.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// Capture group of the 1-based line.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Capture group of the 1-based column.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Capture group of the 1-based end line.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Capture group of the 1-based end column.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Capture group of the severity, such as `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Capture group of the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Capture group of the message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the last pattern of a multi-line matcher matches any number of consecutive lines,
+    /// reporting a problem for each of them.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// Severity of a problem.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    /// Parses a severity as printed by tools, e.g. `error`, `W` or `note`.
+    pub(crate) fn parse(severity: &str) -> Option<Self> {
+        match severity.chars().next()?.to_ascii_lowercase() {
+            'e' | 'f' => Some(Self::Error),
+            'w' => Some(Self::Warning),
+            'i' | 'n' => Some(Self::Info),
+            'h' => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Path of the file, as printed by the task or joined to the matcher's `base_dir`.
+    pub path: PathBuf,
+    /// 1-based line of the problem.
+    pub line: u32,
+    /// 1-based column of the problem.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    /// Source of the problem, if the matcher sets one.
+    pub source: Option<String>,
+}
+
+impl ProblemMatcher {
+    /// Compiles the regexes of the matcher, failing if they are invalid or it refers to an unknown built-in matcher.
+    pub fn compile(&self) -> Result<CompiledProblemMatcher> {
+        match self {
+            Self::Builtin(name) => {
+                let (source, patterns) = builtin_patterns(name)?;
+                CompiledProblemMatcher::new(
+                    &patterns,
+                    Some(source.to_string()),
+                    None,
+                    None,
+                    self.begins_pattern(),
+                )
+            }
+            Self::Custom(matcher) => {
+                let patterns = match (&matcher.pattern, &matcher.base) {
+                    (Some(patterns), _) => patterns.as_slice().to_vec(),
+                    (None, Some(base)) => builtin_patterns(base)?.1,
+                    (None, None) => bail!("problem matcher has neither a `pattern` nor a `base`"),
+                };
+                let source = matcher.source.clone().or_else(|| {
+                    let base = matcher.base.as_deref()?;
+                    Some(builtin_patterns(base).ok()?.0.to_string())
+                });
+                CompiledProblemMatcher::new(
+                    &patterns,
+                    source,
+                    matcher.severity,
+                    matcher.base_dir.as_ref().map(PathBuf::from),
+                    self.begins_pattern(),
+                )
+            }
+        }
+    }
+
+    /// Pattern of the output of a background task, marking the start of a new build.
+    pub fn begins_pattern(&self) -> Option<&str> {
+        let name = match self {
+            Self::Builtin(name) => name,
+            Self::Custom(matcher) => {
+                if let Some(pattern) = matcher.begins_pattern.as_deref() {
+                    return Some(pattern);
+                }
+                matcher.base.as_ref()?
+            }
+        };
+        match name.as_str() {
+            "$tsc-watch" => Some(
+                r"(?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Pattern of the output of a built-in watch matcher, marking its task as ready once a build finished.
+    pub fn ready_pattern(&self) -> Option<&'static str> {
+        let name = match self {
+            Self::Builtin(name) => name,
+            Self::Custom(matcher) => matcher.base.as_ref()?,
+        };
+        match name.as_str() {
+            "$tsc-watch" => {
+                Some(r"(?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.")
+            }
+            _ => None,
+        }
+    }
+}
+
+fn builtin_patterns(name: &str) -> Result<(&'static str, Vec<ProblemPattern>)> {
+    let pattern = |regexp: &str| ProblemPattern {
+        regexp: regexp.to_string(),
+        ..ProblemPattern::default()
+    };
+    Ok(match name {
+        "$rustc" => (
+            "rustc",
+            vec![
+                ProblemPattern {
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..pattern(r"^(warning|error)(?:\[(\w+)\])?: (.+)$")
+                },
+                ProblemPattern {
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..pattern(r"^\s*--> (.+?):(\d+):(\d+)$")
+                },
+            ],
+        ),
+        "$tsc" | "$tsc-watch" => (
+            "ts",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..pattern(
+                    r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$",
+                )
+            }],
+        ),
+        "$eslint-compact" => (
+            "eslint",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..pattern(
+                    r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$",
+                )
+            }],
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            vec![
+                ProblemPattern {
+                    file: Some(1),
+                    ..pattern(r"^([^\s].*)$")
+                },
+                ProblemPattern {
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..pattern(r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$")
+                },
+            ],
+        ),
+        "$gcc" => (
+            "gcc",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..pattern(r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$")
+            }],
+        ),
+        // The output of `semgrep --emacs`.
+        "$semgrep" => (
+            "semgrep",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..pattern(r"^(.+?):(\d+):(\d+):(\w+)\(([^)]+)\):(.*)$")
+            }],
+        ),
+        _ => bail!(
+            "unknown problem matcher `{name}`, expected one of {}",
+            BUILTIN_PROBLEM_MATCHERS.join(", ")
+        ),
+    })
+}
+
+/// A [`ProblemMatcher`] with its regexes compiled, ready to match output.
+#[derive(Clone, Debug)]
+pub struct CompiledProblemMatcher {
+    patterns: Vec<(Regex, ProblemPattern)>,
+    begins: Option<Regex>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    base_dir: Option<PathBuf>,
+}
+
+impl CompiledProblemMatcher {
+    fn new(
+        patterns: &[ProblemPattern],
+        source: Option<String>,
+        severity: Option<ProblemSeverity>,
+        base_dir: Option<PathBuf>,
+        begins_pattern: Option<&str>,
+    ) -> Result<Self> {
+        if patterns.is_empty() {
+            bail!("problem matcher has no patterns");
+        }
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp)
+                    .with_context(|| format!("invalid problem pattern `{}`", pattern.regexp))?;
+                anyhow::Ok((regex, pattern.clone()))
+            })
+            .collect::<Result<_>>()?;
+        let begins = begins_pattern
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("invalid begins pattern `{pattern}`"))
+            })
+            .transpose()?;
+        Ok(Self {
+            patterns,
+            begins,
+            source,
+            severity,
+            base_dir,
+        })
+    }
+
+    /// Finds all problems in the given output.
+    pub fn match_output(&self, output: &str) -> Vec<Problem> {
+        let lines = output.lines().collect::<Vec<_>>();
+        let mut problems = Vec::new();
+        self.match_lines(&lines, true, &mut problems);
+        problems
+    }
+
+    /// Adds the problems found in the given lines to `problems`, to match the output of a task as it's printed.
+    ///
+    /// Returns how many of the lines were matched. Unless `is_complete` is set, matching stops at a problem
+    /// that's reported over several lines and reaches the end of `lines`, which might continue in the output
+    /// that follows, so the lines from there on have to be passed again along with it.
+    pub fn match_lines(
+        &self,
+        lines: &[&str],
+        is_complete: bool,
+        problems: &mut Vec<Problem>,
+    ) -> usize {
+        let lines = lines.iter().map(|line| line.trim_end()).collect::<Vec<_>>();
+        let Some(((last_regex, last_pattern), leading_patterns)) = self.patterns.split_last()
+        else {
+            return lines.len();
+        };
+
+        let mut ix = 0;
+        'lines: while ix < lines.len() {
+            if self
+                .begins
+                .as_ref()
+                .is_some_and(|begins| begins.is_match(lines[ix]))
+            {
+                problems.clear();
+                ix += 1;
+                continue;
+            }
+
+            let mut leading_data = ProblemData::default();
+            for (offset, (regex, pattern)) in leading_patterns.iter().enumerate() {
+                match regex.captures(lines[ix + offset]) {
+                    Some(captures) if ix + offset + 1 < lines.len() => {
+                        leading_data.fill(pattern, &captures)
+                    }
+                    Some(_) if !is_complete => return ix,
+                    _ => {
+                        ix += 1;
+                        continue 'lines;
+                    }
+                }
+            }
+
+            let problem_count = problems.len();
+            let mut end = ix + leading_patterns.len();
+            while let Some(captures) = lines.get(end).and_then(|line| last_regex.captures(line)) {
+                let mut data = leading_data.clone();
+                data.fill(last_pattern, &captures);
+                problems.extend(self.problem(data));
+                end += 1;
+                if !last_pattern.repeat {
+                    break;
+                }
+            }
+            let is_multiline = !leading_patterns.is_empty() || last_pattern.repeat;
+            if is_multiline && end == lines.len() && !is_complete {
+                problems.truncate(problem_count);
+                return ix;
+            }
+            ix = if end > ix + leading_patterns.len() {
+                end
+            } else {
+                ix + 1
+            };
+        }
+        lines.len()
+    }
+
+    fn problem(&self, data: ProblemData) -> Option<Problem> {
+        let file = data.file?;
+        let path = match &self.base_dir {
+            Some(base_dir) => base_dir.join(file),
+            None => PathBuf::from(file),
+        };
+        Some(Problem {
+            path,
+            line: data.line?,
+            column: data.column,
+            end_line: data.end_line,
+            end_column: data.end_column,
+            severity: data
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .or(self.severity)
+                .unwrap_or_default(),
+            code: data.code,
+            message: data.message?,
+            source: self.source.clone(),
+        })
+    }
+}
+
+/// Parts of a problem captured so far by the patterns of a matcher.
+#[derive(Clone, Default)]
+struct ProblemData {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemData {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let capture = |group: Option<usize>| {
+            let capture = captures.get(group?)?.as_str().trim();
+            (!capture.is_empty()).then(|| capture.to_string())
+        };
+        let number = |group: Option<usize>| capture(group)?.parse::<u32>().ok();
+
+        if let Some(file) = capture(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(location) = capture(pattern.location) {
+            let mut numbers = location.split(',').map(|n| n.trim().parse::<u32>().ok());
+            self.line = numbers.next().flatten();
+            self.column = numbers.next().flatten();
+            self.end_line = numbers.next().flatten();
+            self.end_column = numbers.next().flatten();
+        }
+        for (group, value) in [
+            (pattern.line, &mut self.line),
+            (pattern.column, &mut self.column),
+            (pattern.end_line, &mut self.end_line),
+            (pattern.end_column, &mut self.end_column),
+        ] {
+            if let Some(number) = number(group) {
+                *value = Some(number);
+            }
+        }
+        for (group, value) in [
+            (pattern.severity, &mut self.severity),
+            (pattern.code, &mut self.code),
+            (pattern.message, &mut self.message),
+        ] {
+            if let Some(capture) = capture(group) {
+                *value = Some(capture);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn problems(matcher: ProblemMatcher, output: &str) -> Vec<Problem> {
+        matcher.compile().unwrap().match_output(output)
+    }
+
+    #[test]
+    fn test_builtin_matchers() {
+        let rustc_output = "   Compiling foo v0.1.0 (/work/foo)
+error[E0425]: cannot find value `x` in this scope
+ --> src/main.rs:2:13
+  |
+2 |     let y = x;
+  |             ^ not found in this scope
+
+warning: unused variable: `y`
+ --> src/main.rs:2:9
+error: could not compile `foo` (bin \"foo\") due to 1 previous error";
+        assert_eq!(
+            problems(ProblemMatcher::Builtin("$rustc".into()), rustc_output),
+            vec![
+                Problem {
+                    path: PathBuf::from("src/main.rs"),
+                    line: 2,
+                    column: Some(13),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0425".into()),
+                    message: "cannot find value `x` in this scope".into(),
+                    source: Some("rustc".into()),
+                },
+                Problem {
+                    path: PathBuf::from("src/main.rs"),
+                    line: 2,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".into(),
+                    source: Some("rustc".into()),
+                },
+            ]
+        );
+
+        let tsc_output =
+            "src/index.ts:3:7 - error TS2322: Type 'string' is not assignable to type 'number'.
+src/util.ts(10,1): warning TS6133: 'a' is declared but its value is never read.
+
+Found 2 errors in 2 files.";
+        let tsc_problems = problems(ProblemMatcher::Builtin("$tsc".into()), tsc_output);
+        assert_eq!(
+            tsc_problems
+                .iter()
+                .map(|p| (p.path.clone(), p.line, p.column, p.severity, p.code.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("src/index.ts"),
+                    3,
+                    Some(7),
+                    ProblemSeverity::Error,
+                    Some("TS2322".into())
+                ),
+                (
+                    PathBuf::from("src/util.ts"),
+                    10,
+                    Some(1),
+                    ProblemSeverity::Warning,
+                    Some("TS6133".into())
+                ),
+            ]
+        );
+
+        let eslint_output = "/work/app/src/index.js
+   1:7   error    'unused' is assigned a value but never used  no-unused-vars
+  12:3   warning  Unexpected console statement                 no-console
+
+/work/app/src/other.js
+  4:1  error  Parsing error: Unexpected token
+
+✖ 3 problems (2 errors, 1 warning)";
+        assert_eq!(
+            problems(
+                ProblemMatcher::Builtin("$eslint-stylish".into()),
+                eslint_output
+            )
+            .iter()
+            .map(|p| (
+                p.path.clone(),
+                p.line,
+                p.severity,
+                p.message.clone(),
+                p.code.clone()
+            ))
+            .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("/work/app/src/index.js"),
+                    1,
+                    ProblemSeverity::Error,
+                    "'unused' is assigned a value but never used".into(),
+                    Some("no-unused-vars".into())
+                ),
+                (
+                    PathBuf::from("/work/app/src/index.js"),
+                    12,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement".into(),
+                    Some("no-console".into())
+                ),
+                (
+                    PathBuf::from("/work/app/src/other.js"),
+                    4,
+                    ProblemSeverity::Error,
+                    "Parsing error: Unexpected token".into(),
+                    None
+                ),
+            ]
+        );
+
+        let semgrep_output =
+            "app/views.py:42:5:ERROR(python.django.security.injection.sql):Found raw SQL";
+        assert_eq!(
+            problems(ProblemMatcher::Builtin("$semgrep".into()), semgrep_output),
+            vec![Problem {
+                path: PathBuf::from("app/views.py"),
+                line: 42,
+                column: Some(5),
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Error,
+                code: Some("python.django.security.injection.sql".into()),
+                message: "Found raw SQL".into(),
+                source: Some("semgrep".into()),
+            }]
+        );
+
+        assert!(
+            ProblemMatcher::Builtin("$unknown".into())
+                .compile()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_custom_matchers() {
+        let matcher: ProblemMatcher = serde_json::from_str(
+            r#"{
+                "source": "lint",
+                "severity": "warning",
+                "base_dir": "/work",
+                "pattern": {
+                    "regexp": "^(\\S+) (\\d+(?:,\\d+)*): (.*)$",
+                    "file": 1,
+                    "location": 2,
+                    "message": 3
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            problems(matcher, "a.txt 3,4,3,9: trailing whitespace\nnot a problem"),
+            vec![Problem {
+                path: PathBuf::from("/work/a.txt"),
+                line: 3,
+                column: Some(4),
+                end_line: Some(3),
+                end_column: Some(9),
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "trailing whitespace".into(),
+                source: Some("lint".into()),
+            }]
+        );
+
+        let matcher: ProblemMatcher = serde_json::from_str(
+            r#"{
+                "pattern": [
+                    { "regexp": "^FAIL (.*)$", "message": 1 },
+                    { "regexp": "^  at (.*):(\\d+)$", "file": 1, "line": 2 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            problems(
+                matcher,
+                "FAIL adds numbers\n  at tests/math.rs:10\nFAIL no location\nok"
+            ),
+            vec![Problem {
+                path: PathBuf::from("tests/math.rs"),
+                line: 10,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Error,
+                code: None,
+                message: "adds numbers".into(),
+                source: None,
+            }]
+        );
+
+        let based_on_builtin = ProblemMatcher::Custom(CustomProblemMatcher {
+            base: Some("$tsc-watch".into()),
+            ..CustomProblemMatcher::default()
+        });
+        assert_eq!(
+            problems(
+                based_on_builtin.clone(),
+                "a.ts(1,2): error TS1005: ';' expected."
+            )[0]
+            .source
+            .as_deref(),
+            Some("ts")
+        );
+        assert!(based_on_builtin.ready_pattern().is_some());
+        assert!(based_on_builtin.begins_pattern().is_some());
+    }
+
+    #[test]
+    fn test_matching_output_as_it_is_printed() {
+        let matcher = ProblemMatcher::Builtin("$rustc".into()).compile().unwrap();
+        let mut found = Vec::new();
+        // The location of the error hasn't been printed yet.
+        let lines = [
+            "   Compiling foo v0.1.0 (/work/foo)",
+            "error[E0425]: cannot find value `x` in this scope",
+        ];
+        assert_eq!(matcher.match_lines(&lines, false, &mut found), 1);
+        assert_eq!(found, []);
+        let lines = [
+            "error[E0425]: cannot find value `x` in this scope",
+            " --> src/main.rs:2:13",
+            "  |",
+        ];
+        assert_eq!(matcher.match_lines(&lines, false, &mut found), 3);
+        assert_eq!(
+            found
+                .iter()
+                .map(|p| (p.path.clone(), p.line, p.column))
+                .collect::<Vec<_>>(),
+            [(PathBuf::from("src/main.rs"), 2, Some(13))]
+        );
+
+        // A looping pattern might match the lines that follow.
+        let matcher = ProblemMatcher::Builtin("$eslint-stylish".into())
+            .compile()
+            .unwrap();
+        let mut found = Vec::new();
+        let lines = [
+            "/work/app/src/index.js",
+            "   1:7   error    'unused' is assigned a value but never used  no-unused-vars",
+        ];
+        assert_eq!(matcher.match_lines(&lines, false, &mut found), 0);
+        assert_eq!(found, []);
+        assert_eq!(matcher.match_lines(&lines, true, &mut found), 2);
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn test_begins_pattern() {
+        let matcher = ProblemMatcher::Builtin("$tsc-watch".into())
+            .compile()
+            .unwrap();
+        let mut found = Vec::new();
+        let lines = [
+            "[10:00:00 AM] Starting compilation in watch mode...",
+            "src/a.ts(1,2): error TS1005: ';' expected.",
+            "[10:00:01 AM] Found 1 error. Watching for file changes.",
+        ];
+        assert_eq!(matcher.match_lines(&lines, false, &mut found), 3);
+        assert_eq!(found.len(), 1);
+
+        // The problems of the previous build are cleared when a new one starts.
+        let lines = [
+            "[10:00:05 AM] File change detected. Starting incremental compilation...",
+            "src/b.ts(3,4): error TS2304: Cannot find name 'b'.",
+            "[10:00:06 AM] Found 1 error. Watching for file changes.",
+        ];
+        assert_eq!(matcher.match_lines(&lines, false, &mut found), 3);
+        assert_eq!(
+            found.iter().map(|p| p.path.clone()).collect::<Vec<_>>(),
+            [PathBuf::from("src/b.ts")]
+        );
+
+        let matcher: ProblemMatcher = serde_json::from_str(
+            r#"{
+                "begins_pattern": "^Rebuilding",
+                "pattern": { "regexp": "^(\\S+):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }
+            }"#,
+        )
+        .unwrap();
+        let paths = problems(matcher, "a.txt:1: first\nRebuilding\nb.txt:2: second")
+            .into_iter()
+            .map(|problem| problem.path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, [PathBuf::from("b.txt")]);
+    }
+}
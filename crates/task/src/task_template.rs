@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, CustomProblemMatcher, ProblemMatcher, ResolvedTask, RevealTarget, Shell,
+    SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +73,27 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one.
+    /// A task with dependencies may leave `command` empty, to only run its dependencies.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks in `depends_on`:
+    /// * `sequence` — one after another, in the order given, stopping at the first failure (default)
+    /// * `parallel` — all at once
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Whether the task keeps running in the background, like a file watcher or a development server.
+    /// Tasks that depend on a background task start once it is ready, rather than once it exits.
+    #[serde(default)]
+    pub is_background: bool,
+    /// A regex that marks a background task as ready once a line of its output matches it.
+    /// Without it, a background task is considered ready as soon as it starts.
+    #[serde(default)]
+    pub ready_pattern: Option<String>,
+    /// Problem matchers turning the output of the task into diagnostics: names of built-in matchers,
+    /// such as `$rustc` or `$tsc`, or custom matchers with regex patterns.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +131,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, stopping at the first failure.
+    #[default]
+    Sequence,
+    /// Run all dependencies at once.
+    Parallel,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -134,7 +167,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -213,6 +248,28 @@ impl TaskTemplate {
             &variable_names,
             &mut substituted_variables,
         )?;
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .map(|matcher| match matcher {
+                ProblemMatcher::Custom(custom) => {
+                    let base_dir = match custom.base_dir.as_deref() {
+                        Some(base_dir) => Some(substitute_all_template_variables_in_str(
+                            base_dir,
+                            &task_variables,
+                            &variable_names,
+                            &mut substituted_variables,
+                        )?),
+                        None => None,
+                    };
+                    Some(ProblemMatcher::Custom(CustomProblemMatcher {
+                        base_dir,
+                        ..custom.clone()
+                    }))
+                }
+                ProblemMatcher::Builtin(_) => Some(matcher.clone()),
+            })
+            .collect::<Option<Vec<_>>>()?;
 
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
@@ -245,6 +302,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
@@ -272,6 +330,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
             },
         })
     }
@@ -470,6 +529,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_composite_templates() {
+        let composite_task = TaskTemplate {
+            label: "build and serve".to_string(),
+            depends_on: vec!["build".to_string(), "serve".to_string()],
+            ..TaskTemplate::default()
+        };
+        let resolved_task = composite_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task with dependencies and no command");
+        assert_eq!(resolved_task.resolved_label, "build and serve");
+        assert_eq!(resolved_task.original_task().depends_on, ["build", "serve"]);
+
+        let deserialized: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "serve",
+            "command": "npm run serve",
+            "depends_on": ["codegen", "migrate"],
+            "depends_order": "parallel",
+            "is_background": true,
+            "ready_pattern": "listening on",
+        }))
+        .unwrap();
+        assert_eq!(deserialized.depends_order, DependsOrder::Parallel);
+        assert!(deserialized.is_background);
+        assert_eq!(deserialized.ready_pattern.as_deref(), Some("listening on"));
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -3,7 +3,11 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BUILTIN_PROBLEM_MATCHERS, CustomProblemMatcher, DependsOrder, EnvVariableReplacer,
+    ProblemMatcher, ProblemPattern, ProblemPatterns, ProblemSeverity, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,30 +45,182 @@ enum Command {
     },
 }
 
-impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Multiple(Vec<VsCodeProblemMatcher>),
+    Single(VsCodeProblemMatcher),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Defined(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    source: Option<String>,
+    severity: Option<String>,
+    file_location: Option<serde_json_lenient::Value>,
+    pattern: Option<VsCodeProblemPatterns>,
+    background: Option<serde_json_lenient::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Multiline(Vec<VsCodeProblemPattern>),
+    Single(VsCodeProblemPattern),
+    /// A pattern contributed by an extension, which cannot be imported.
+    Named(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+impl VsCodeProblemPattern {
+    fn into_zed_format(self) -> ProblemPattern {
+        ProblemPattern {
+            regexp: self.regexp,
+            file: self.file,
+            location: self.location,
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity,
+            code: self.code,
+            message: self.message,
+            repeat: self.repeat,
         }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+    }
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<ProblemMatcher> {
+        let is_builtin = |name: &str| BUILTIN_PROBLEM_MATCHERS.contains(&name);
+        let definition = match self {
+            Self::Named(name) if is_builtin(&name) => return Ok(ProblemMatcher::Builtin(name)),
+            Self::Named(name) => bail!("unsupported problem matcher `{name}`"),
+            Self::Defined(definition) => definition,
+        };
+        let pattern = match definition.pattern {
+            Some(VsCodeProblemPatterns::Multiline(patterns)) => Some(ProblemPatterns::Multiline(
+                patterns
+                    .into_iter()
+                    .map(VsCodeProblemPattern::into_zed_format)
+                    .collect(),
+            )),
+            Some(VsCodeProblemPatterns::Single(pattern)) => {
+                Some(ProblemPatterns::Single(pattern.into_zed_format()))
+            }
+            Some(VsCodeProblemPatterns::Named(name)) => {
+                bail!("unsupported problem pattern `{name}`")
+            }
+            None => None,
+        };
+        match (&pattern, &definition.base) {
+            (None, None) => bail!("problem matcher has neither a `pattern` nor a `base`"),
+            (None, Some(base)) if !is_builtin(base) => {
+                bail!("unsupported problem matcher `{base}`")
+            }
+            _ => {}
+        }
+        // `fileLocation` is either a kind of location, or the kind followed by the directory to resolve paths against.
+        let base_dir = definition
+            .file_location
+            .as_ref()
+            .and_then(|location| location.get(1))
+            .and_then(|base_dir| base_dir.as_str())
+            .map(|base_dir| replacer.replace(base_dir));
+        // `beginsPattern` is either a regex, or an object holding it in `regexp`.
+        let begins_pattern = definition
+            .background
+            .as_ref()
+            .and_then(|background| background.get("beginsPattern"))
+            .and_then(|pattern| pattern.as_str().or_else(|| pattern.get("regexp")?.as_str()))
+            .map(ToOwned::to_owned);
+        let matcher = CustomProblemMatcher {
+            base: definition.base,
+            pattern,
+            source: definition.source,
+            severity: definition
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse),
+            base_dir,
+            begins_pattern,
+        };
+        Ok(match matcher {
+            CustomProblemMatcher {
+                base: Some(base),
+                pattern: None,
+                source: None,
+                severity: None,
+                base_dir: None,
+                begins_pattern: None,
+            } => ProblemMatcher::Builtin(base),
+            matcher => ProblemMatcher::Custom(matcher),
+        })
+    }
+}
+
+impl VsCodeTaskDefinition {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        let depends_on = self.depends_on();
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            // Code runs dependencies in parallel unless told otherwise.
+            _ if !depends_on.is_empty() => DependsOrder::Parallel,
+            _ => DependsOrder::default(),
+        };
+        let is_background = self
+            .other_attributes
+            .get("isBackground")
+            .and_then(|is_background| is_background.as_bool())
+            .unwrap_or(false);
+        let ready_pattern = self
+            .other_attributes
+            .get("problemMatcher")
+            .and_then(|matcher| matcher.pointer("/background/endsPattern"))
+            .and_then(|pattern| pattern.as_str())
+            .map(ToOwned::to_owned);
+        let problem_matchers = self.problem_matchers(replacer);
+
+        // `type` is not set in tasks that only group other tasks with `dependsOn`, hence command is an Option.
+        let command = match self.command {
+            Some(command) => Some(command),
+            None if !depends_on.is_empty() => None,
+            None => bail!("Missing `type` field in task"),
         };
 
         let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None => (String::new(), Vec::new()),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -73,13 +229,68 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            is_background,
+            ready_pattern,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
+    }
+
+    /// Labels of the tasks listed in `dependsOn`, which is either a single label or a list of them.
+    fn depends_on(&self) -> Vec<String> {
+        match self.other_attributes.get("dependsOn") {
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .filter_map(|label| match label.as_str() {
+                    Some(label) => Some(label.to_owned()),
+                    None => {
+                        log::warn!(
+                            "Skipping unsupported dependency {label} of task `{}`",
+                            self.label
+                        );
+                        None
+                    }
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The matchers of `problemMatcher`, which is either a single matcher or a list of them.
+    /// Matchers contributed by extensions, rather than built into Code, are skipped.
+    fn problem_matchers(&self, replacer: &EnvVariableReplacer) -> Vec<ProblemMatcher> {
+        let Some(matchers) = self.other_attributes.get("problemMatcher") else {
+            return Vec::new();
+        };
+        let matchers = match serde_json_lenient::from_value(matchers.clone()) {
+            Ok(VsCodeProblemMatchers::Multiple(matchers)) => matchers,
+            Ok(VsCodeProblemMatchers::Single(matcher)) => vec![matcher],
+            Err(e) => {
+                log::warn!(
+                    "Skipping invalid problem matchers of task `{}`: {e}",
+                    self.label
+                );
+                return Vec::new();
+            }
+        };
+        matchers
+            .into_iter()
+            .filter_map(|matcher| match matcher.into_zed_format(replacer) {
+                Ok(matcher) => Some(matcher),
+                Err(e) => {
+                    log::warn!("Skipping problem matcher of task `{}`: {e}", self.label);
+                    None
+                }
+            })
+            .collect()
     }
 }
 
@@ -108,12 +319,7 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
-            })
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
             .collect();
         Ok(Self(templates))
     }
@@ -124,6 +330,7 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
+        CustomProblemMatcher, DependsOrder, ProblemMatcher, ProblemPattern, ProblemPatterns,
         TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
@@ -168,6 +375,71 @@ mod tests {
         assert_eq!(replacer.replace("${PATH:food}"), "${ZED_PATH:food}");
     }
 
+    #[test]
+    fn can_deserialize_dependent_tasks() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "serve",
+                        "type": "shell",
+                        "command": "npm run serve",
+                        "dependsOn": "build",
+                        "isBackground": true,
+                        "problemMatcher": {
+                            "pattern": { "regexp": "^error: (.*)$", "message": 1 },
+                            "background": {
+                                "beginsPattern": "Starting",
+                                "endsPattern": "Listening on port \\d+"
+                            }
+                        }
+                    },
+                    {
+                        "label": "all",
+                        "dependsOn": ["codegen", "build", "migrate", "serve"],
+                        "dependsOrder": "sequence"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "serve".to_string(),
+                    command: "npm run serve".to_string(),
+                    depends_on: vec!["build".to_string()],
+                    depends_order: DependsOrder::Parallel,
+                    is_background: true,
+                    ready_pattern: Some("Listening on port \\d+".to_string()),
+                    problem_matchers: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                        pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                            regexp: "^error: (.*)$".to_string(),
+                            message: Some(1),
+                            ..Default::default()
+                        })),
+                        begins_pattern: Some("Starting".to_string()),
+                        ..Default::default()
+                    })],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "all".to_string(),
+                    depends_on: vec![
+                        "codegen".to_string(),
+                        "build".to_string(),
+                        "migrate".to_string(),
+                        "serve".to_string(),
+                    ],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn can_deserialize_ts_tasks() {
         const TYPESCRIPT_TASKS: &str = include_str!("../test_data/typescript.json");
@@ -227,6 +499,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +511,22 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                is_background: true,
+                problem_matchers: vec![ProblemMatcher::Builtin("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -330,28 +607,63 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                is_background: true,
+                problem_matchers: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                    base: Some("$tsc-watch".to_string()),
+                    base_dir: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    base_dir: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::Builtin("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::Builtin("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                    base: Some("$tsc".to_string()),
+                    base_dir: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                    ..Default::default()
+                })],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::Builtin("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::Builtin("$rustc".to_string())],
                 ..Default::default()
             },
         ];
@@ -0,0 +1,971 @@
+//! Tracking of the commands run in a terminal, from the `OSC 133` sequences that shells print
+//! around their prompts, command lines and command output (see the `shell_integration` module).
+//!
+//! Alacritty ignores these sequences, and its event loop gives no way to know where the cursor was
+//! when one of them got parsed. So the output of the pty is parsed here instead: it is split at
+//! these sequences, and the position of the cursor is recorded between the parts.
+
+use std::{collections::VecDeque, io, path::PathBuf, sync::Arc, time::Instant};
+
+use alacritty_terminal::{
+    Term,
+    event::{Event as AlacTermEvent, EventListener, OnResize, WindowSize},
+    grid::{Dimensions, Grid, Scroll},
+    index::{Column, Line, Point as AlacPoint},
+    sync::FairMutex,
+    term::{TermMode, cell::Cell},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+    vte::ansi::{Processor, StdSyncHandler},
+};
+use gpui::{BackgroundExecutor, Task};
+use polling::{Event as PollingEvent, PollMode, Poller};
+
+use crate::ZedListener;
+
+const READ_BUFFER_SIZE: usize = 0x1_0000;
+
+/// Upper bound on the bytes parsed while holding the terminal lock, so that the event loop gets to
+/// handle writes and resizes.
+const MAX_LOCKED_READ: usize = u16::MAX as usize;
+
+/// Upper bound on the length of the OSC sequences looked at. Ours are much shorter.
+const MAX_OSC_LENGTH: usize = 4096;
+
+/// Upper bound on the number of commands remembered per terminal.
+const MAX_COMMAND_BLOCKS: usize = 1000;
+
+/// A command run in the terminal, as reported by the shell.
+///
+/// Its points are relative to the grid of the terminal, and are `None` when they left the
+/// scrollback or were never reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandBlock {
+    pub id: usize,
+    pub prompt_start: Option<AlacPoint>,
+    pub command_start: Option<AlacPoint>,
+    pub output_start: Option<AlacPoint>,
+    pub output_end: Option<AlacPoint>,
+    /// The command line, as reported by the shell, or empty if it wasn't.
+    pub command: String,
+    /// The working directory of the shell when the command was entered.
+    pub cwd: Option<PathBuf>,
+    pub status: CommandStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The shell is showing the prompt for this command.
+    Pending,
+    Running,
+    Finished {
+        exit_code: Option<i32>,
+    },
+}
+
+impl CommandBlock {
+    /// The first point of the block that is still in the scrollback.
+    pub fn start(&self) -> Option<AlacPoint> {
+        self.prompt_start
+            .or(self.command_start)
+            .or(self.output_start)
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(
+            self.status,
+            CommandStatus::Finished {
+                exit_code: Some(exit_code)
+            } if exit_code != 0
+        )
+    }
+}
+
+/// What a command run in the terminal did, as reported by the shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandResult {
+    /// The command line, as reported by the shell, or empty if it wasn't.
+    pub command: String,
+    /// The working directory of the shell when the command was entered.
+    pub cwd: Option<PathBuf>,
+    /// `None` while the command runs, or if the shell didn't report its exit code.
+    pub exit_code: Option<i32>,
+    /// The output of the command so far, as far as it is still in the scrollback.
+    pub output: String,
+}
+
+/// What a shell reported with an `OSC 133` or an `OSC 7` sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Mark {
+    PromptStart,
+    CommandStart,
+    OutputStart { command: Option<String> },
+    CommandFinished { exit_code: Option<i32> },
+    WorkingDirectory(PathBuf),
+}
+
+impl Mark {
+    fn parse(osc: &[u8]) -> Option<Self> {
+        let osc = std::str::from_utf8(osc).ok()?;
+        if let Some(mark) = osc.strip_prefix("133;") {
+            let (kind, params) = mark.split_once(';').unwrap_or((mark, ""));
+            return match kind {
+                "A" => Some(Self::PromptStart),
+                "B" => Some(Self::CommandStart),
+                // `cmdline_url=` has the command line percent-encoded, and `cmdline=` has it as is,
+                // so it is last.
+                "C" => Some(Self::OutputStart {
+                    command: match params.strip_prefix("cmdline_url=") {
+                        Some(command) => {
+                            urlencoding::decode(command.split(';').next().unwrap_or_default())
+                                .ok()
+                                .map(|command| command.into_owned())
+                        }
+                        None => params.strip_prefix("cmdline=").map(ToString::to_string),
+                    },
+                }),
+                "D" => Some(Self::CommandFinished {
+                    exit_code: params.split(';').next().and_then(|code| code.parse().ok()),
+                }),
+                _ => None,
+            };
+        }
+
+        // `file://host/path`, with the path percent-encoded.
+        let location = osc.strip_prefix("7;")?.strip_prefix("file://")?;
+        let path = &location[location.find('/')?..];
+        let path = urlencoding::decode(path).ok()?;
+        Some(Self::WorkingDirectory(PathBuf::from(path.into_owned())))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds the marks in the output of a shell, whose sequences may be split across reads.
+#[derive(Default)]
+struct MarkScanner {
+    state: ScanState,
+    osc: Vec<u8>,
+}
+
+impl MarkScanner {
+    /// Returns the next mark in `bytes`, along with the offset right after its sequence.
+    fn next_mark(&mut self, bytes: &[u8]) -> Option<(usize, Mark)> {
+        let mut ix = 0;
+        while ix < bytes.len() {
+            if self.state == ScanState::Ground {
+                ix += bytes[ix..].iter().position(|&byte| byte == 0x1b)?;
+            }
+            let byte = bytes[ix];
+            ix += 1;
+            match self.state {
+                ScanState::Ground => self.state = ScanState::Escape,
+                ScanState::Escape => self.escape(byte),
+                ScanState::Osc => match byte {
+                    // BEL
+                    0x07 => {
+                        if let Some(mark) = self.finish_osc() {
+                            return Some((ix, mark));
+                        }
+                    }
+                    0x1b => self.state = ScanState::OscEscape,
+                    // CAN and SUB abort the sequence.
+                    0x18 | 0x1a => self.state = ScanState::Ground,
+                    _ => {
+                        if self.osc.len() <= MAX_OSC_LENGTH {
+                            self.osc.push(byte);
+                        }
+                    }
+                },
+                // ESC \
+                ScanState::OscEscape if byte == b'\\' => {
+                    if let Some(mark) = self.finish_osc() {
+                        return Some((ix, mark));
+                    }
+                }
+                ScanState::OscEscape => self.escape(byte),
+            }
+        }
+        None
+    }
+
+    fn escape(&mut self, byte: u8) {
+        self.state = match byte {
+            b']' => {
+                self.osc.clear();
+                ScanState::Osc
+            }
+            0x1b => ScanState::Escape,
+            _ => ScanState::Ground,
+        };
+    }
+
+    fn finish_osc(&mut self) -> Option<Mark> {
+        self.state = ScanState::Ground;
+        if self.osc.len() > MAX_OSC_LENGTH {
+            return None;
+        }
+        Mark::parse(&self.osc)
+    }
+}
+
+/// A position in the terminal, counting lines from the first line the terminal displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Position {
+    line: i64,
+    column: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrackedCommand {
+    id: usize,
+    prompt_start: Option<Position>,
+    command_start: Option<Position>,
+    output_start: Option<Position>,
+    output_end: Option<Position>,
+    command: String,
+    cwd: Option<PathBuf>,
+    exit_code: Option<i32>,
+}
+
+impl TrackedCommand {
+    fn start(&self) -> Option<Position> {
+        self.prompt_start
+            .or(self.command_start)
+            .or(self.output_start)
+    }
+
+    fn is_running(&self) -> bool {
+        self.output_start.is_some() && self.output_end.is_none()
+    }
+}
+
+/// The state of the primary grid when the lines that scroll into its scrollback started to be
+/// counted.
+#[derive(Clone, Copy, Debug)]
+struct ScrollCount {
+    /// The display offset that the viewport was scrolled to.
+    display_offset: usize,
+    history_size: usize,
+}
+
+/// The commands run in a terminal. It describes the primary grid of the terminal, so it is only
+/// locked while holding the lock of the terminal.
+pub(crate) struct CommandTracker {
+    /// The number of lines the scrollback of the terminal keeps.
+    scrolling_history: usize,
+    commands: VecDeque<TrackedCommand>,
+    next_id: usize,
+    cwd: Option<PathBuf>,
+    /// The number of lines that scrolled into the scrollback so far.
+    scrolled_lines: i64,
+    /// Set from [`Self::start_counting`] until [`Self::sync`] ends the count.
+    scroll_count: Option<ScrollCount>,
+}
+
+impl CommandTracker {
+    pub(crate) fn new(scrolling_history: usize) -> Self {
+        Self {
+            scrolling_history,
+            commands: VecDeque::new(),
+            next_id: 0,
+            cwd: None,
+            scrolled_lines: 0,
+            scroll_count: None,
+        }
+    }
+
+    /// The number of bytes to parse between calls to [`Self::sync`], for fewer lines than the
+    /// scrollback keeps to scroll in between, as each line takes at least one byte.
+    fn sync_interval(&self) -> usize {
+        match self.scrolling_history {
+            // No line is kept in the scrollback anyway.
+            0 => usize::MAX,
+            lines => (lines / 2).max(1),
+        }
+    }
+
+    fn record(&mut self, mark: Mark, term: &Term<ZedListener>) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let position = self.cursor_position(term);
+
+        match mark {
+            Mark::PromptStart => {
+                // Commands below a new prompt were overwritten, e.g. by clearing the screen, and a
+                // command that never ran was abandoned, e.g. with ctrl-c.
+                while self.commands.back().is_some_and(|command| {
+                    command.output_start.is_none()
+                        || command.start().is_none_or(|start| start >= position)
+                }) {
+                    self.commands.pop_back();
+                }
+                self.push_command().prompt_start = Some(position);
+            }
+            Mark::CommandStart => {
+                self.pending_command().command_start.get_or_insert(position);
+            }
+            Mark::OutputStart { command } => {
+                // Some shells report it more than once, e.g. when their prompt hooks get chained.
+                if self.commands.back().is_some_and(TrackedCommand::is_running) {
+                    return;
+                }
+                let pending = self.pending_command();
+                pending.output_start = Some(position);
+                pending.command = command.unwrap_or_default();
+            }
+            Mark::CommandFinished { exit_code } => {
+                if let Some(command) = self.commands.back_mut()
+                    && command.is_running()
+                {
+                    command.output_end = Some(position);
+                    command.exit_code = exit_code;
+                }
+            }
+            Mark::WorkingDirectory(cwd) => {
+                if let Some(command) = self.commands.back_mut()
+                    && command.output_start.is_none()
+                {
+                    command.cwd = Some(cwd.clone());
+                }
+                self.cwd = Some(cwd);
+            }
+        }
+    }
+
+    fn push_command(&mut self) -> &mut TrackedCommand {
+        if self.commands.len() == MAX_COMMAND_BLOCKS {
+            self.commands.pop_front();
+        }
+        self.commands.push_back(TrackedCommand {
+            id: self.next_id,
+            cwd: self.cwd.clone(),
+            ..TrackedCommand::default()
+        });
+        self.next_id += 1;
+        let last = self.commands.len() - 1;
+        &mut self.commands[last]
+    }
+
+    /// The command that didn't run yet, which shells that only report some of the marks may not
+    /// have started with a prompt.
+    fn pending_command(&mut self) -> &mut TrackedCommand {
+        if self
+            .commands
+            .back()
+            .is_some_and(|command| command.output_start.is_none())
+        {
+            let last = self.commands.len() - 1;
+            return &mut self.commands[last];
+        }
+        self.push_command()
+    }
+
+    /// Starts to count the lines that scroll into the scrollback, by scrolling the viewport one line
+    /// up. Alacritty then keeps the viewport on the same lines as they scroll, as it does for users
+    /// that scrolled up, so it moves up by as many lines. [`Self::sync`] scrolls it back.
+    fn start_counting(&mut self, term: &mut Term<ZedListener>) {
+        if self.scroll_count.is_some() || term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let grid = term.grid_mut();
+        let display_offset = grid.display_offset();
+        self.scroll_count = Some(ScrollCount {
+            display_offset,
+            history_size: grid.history_size(),
+        });
+        grid.scroll_display(Scroll::Delta(1 - display_offset as i32));
+    }
+
+    /// Counts the lines that scrolled into the scrollback since [`Self::start_counting`], and
+    /// forgets the commands that left it. The count goes on while the alternate screen is shown, as
+    /// the primary grid can't be accessed then.
+    pub(crate) fn sync(&mut self, term: &mut Term<ZedListener>) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let Some(count) = self.scroll_count.take() else {
+            return;
+        };
+        let grid = term.grid_mut();
+        let history_size = grid.history_size();
+        let display_offset = grid.display_offset();
+        let scrolled = if count.history_size == 0 {
+            // The viewport couldn't be scrolled up, but every line that scrolled is in the
+            // scrollback.
+            history_size
+        } else if display_offset == 0 {
+            // The scrollback was cleared, and the lines above the screen with it.
+            count.history_size + grid.screen_lines()
+        } else {
+            display_offset - 1
+        };
+        let target_offset = match count.display_offset {
+            0 => 0,
+            offset => offset + scrolled,
+        };
+        grid.scroll_display(Scroll::Delta(target_offset as i32 - display_offset as i32));
+
+        self.scrolled_lines += scrolled as i64;
+        self.forget_scrolled_out(history_size);
+    }
+
+    /// Resizes the terminal, keeping the line of the cursor in place, as reflowing the lines above
+    /// it would move them.
+    pub(crate) fn resize(&mut self, term: &mut Term<ZedListener>, size: impl Dimensions) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            term.resize(size);
+            return;
+        }
+        let cursor_line = self.cursor_position(term).line;
+        term.resize(size);
+        self.scrolled_lines = cursor_line - i64::from(term.grid().cursor.point.line.0);
+        self.forget_scrolled_out(term.grid().history_size());
+    }
+
+    /// Forgets the commands that were cleared, when the line of the cursor was moved from
+    /// `cursor_line` to the top of the screen and the scrollback was cleared.
+    pub(crate) fn clear(&mut self, term: &Term<ZedListener>, cursor_line: Line) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        self.scrolled_lines += i64::from(cursor_line.0);
+        self.commands
+            .retain(|command| command.output_start.is_none());
+    }
+
+    fn forget_scrolled_out(&mut self, history_size: usize) {
+        let top = self.scrolled_lines - history_size as i64;
+        while self.commands.front().is_some_and(|command| {
+            command
+                .output_end
+                .is_some_and(|output_end| output_end.line < top)
+        }) {
+            self.commands.pop_front();
+        }
+    }
+
+    fn cursor_position(&self, term: &Term<ZedListener>) -> Position {
+        let cursor = term.grid().cursor.point;
+        Position {
+            line: self.scrolled_lines + i64::from(cursor.line.0),
+            column: cursor.column.0,
+        }
+    }
+
+    fn point(&self, grid: &Grid<Cell>, position: Option<Position>) -> Option<AlacPoint> {
+        let position = position?;
+        let line = position.line - self.scrolled_lines;
+        let visible = -(grid.history_size() as i64)..grid.screen_lines() as i64;
+        visible.contains(&line).then(|| {
+            AlacPoint::new(
+                Line(line as i32),
+                Column(position.column.min(grid.columns() - 1)),
+            )
+        })
+    }
+
+    /// The text from `start` up to `end`, excluding it, as far as it is still in the scrollback.
+    fn text_between(&self, term: &Term<ZedListener>, start: Position, end: Position) -> String {
+        let grid = term.grid();
+        let last_column = grid.columns() - 1;
+        let top = Position {
+            line: self.scrolled_lines - grid.history_size() as i64,
+            column: 0,
+        };
+        let bottom = Position {
+            line: self.scrolled_lines + grid.screen_lines() as i64 - 1,
+            column: last_column,
+        };
+        let start = start.max(top);
+        let end = match end.column.checked_sub(1) {
+            Some(column) => Position {
+                line: end.line,
+                column: column.min(last_column),
+            },
+            None => Position {
+                line: end.line - 1,
+                column: last_column,
+            },
+        }
+        .min(bottom);
+        if end < start {
+            return String::new();
+        }
+        let point = |position: Position| {
+            AlacPoint::new(
+                Line((position.line - self.scrolled_lines) as i32),
+                Column(position.column),
+            )
+        };
+        term.bounds_to_string(point(start), point(end))
+    }
+
+    pub(crate) fn command_blocks(&self, term: &Term<ZedListener>) -> Vec<CommandBlock> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Vec::new();
+        }
+        let grid = term.grid();
+        self.commands
+            .iter()
+            .map(|command| CommandBlock {
+                id: command.id,
+                prompt_start: self.point(grid, command.prompt_start),
+                command_start: self.point(grid, command.command_start),
+                output_start: self.point(grid, command.output_start),
+                output_end: self.point(grid, command.output_end),
+                command: command.command.clone(),
+                cwd: command.cwd.clone(),
+                status: if command.output_start.is_none() {
+                    CommandStatus::Pending
+                } else if command.output_end.is_none() {
+                    CommandStatus::Running
+                } else {
+                    CommandStatus::Finished {
+                        exit_code: command.exit_code,
+                    }
+                },
+            })
+            .collect()
+    }
+
+    /// The output of the command with the given id so far, as far as it is still in the scrollback.
+    pub(crate) fn command_output(&self, id: usize, term: &Term<ZedListener>) -> Option<String> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        let command = self.commands.iter().find(|command| command.id == id)?;
+        let output_end = command
+            .output_end
+            .unwrap_or_else(|| self.cursor_position(term));
+        let output = self.text_between(term, command.output_start?, output_end);
+        Some(output.trim_end().to_string())
+    }
+
+    pub(crate) fn command_result(
+        &self,
+        id: usize,
+        term: &Term<ZedListener>,
+    ) -> Option<CommandResult> {
+        let command = self.commands.iter().find(|command| command.id == id)?;
+        Some(CommandResult {
+            command: command.command.clone(),
+            cwd: command.cwd.clone(),
+            exit_code: command.exit_code,
+            output: self.command_output(id, term)?,
+        })
+    }
+}
+
+/// Parses the output of a shell into its terminal, recording the commands that the shell reports.
+#[derive(Default)]
+pub(crate) struct OutputParser {
+    processor: Processor<StdSyncHandler>,
+    scanner: MarkScanner,
+}
+
+impl OutputParser {
+    pub(crate) fn advance(
+        &mut self,
+        term: &mut Term<ZedListener>,
+        commands: &mut CommandTracker,
+        mut bytes: &[u8],
+    ) {
+        if self
+            .sync_deadline()
+            .is_some_and(|deadline| deadline <= Instant::now())
+        {
+            self.stop_sync(term, commands);
+        }
+        while let Some((end, mark)) = self.scanner.next_mark(bytes) {
+            self.advance_and_sync(term, commands, &bytes[..end]);
+            commands.record(mark, term);
+            bytes = &bytes[end..];
+        }
+        self.advance_and_sync(term, commands, bytes);
+    }
+
+    /// When the synchronized update in progress times out, if any.
+    fn sync_deadline(&self) -> Option<Instant> {
+        self.processor.sync_timeout().sync_timeout()
+    }
+
+    /// Ends the synchronized update in progress, parsing the output that it held back.
+    fn stop_sync(&mut self, term: &mut Term<ZedListener>, commands: &mut CommandTracker) {
+        commands.start_counting(term);
+        self.processor.stop_sync(term);
+        commands.sync(term);
+    }
+
+    fn advance_and_sync(
+        &mut self,
+        term: &mut Term<ZedListener>,
+        commands: &mut CommandTracker,
+        bytes: &[u8],
+    ) {
+        for chunk in bytes.chunks(commands.sync_interval()) {
+            commands.start_counting(term);
+            self.processor.advance(term, chunk);
+            commands.sync(term);
+        }
+    }
+}
+
+/// The pty of a terminal, which parses what it reads into the terminal itself, so that alacritty's
+/// event loop never sees any output.
+pub(crate) struct CommandTrackingPty {
+    pty: tty::Pty,
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    commands: Arc<FairMutex<CommandTracker>>,
+    /// Only locked while holding the locks of the terminal and of its commands.
+    parser: Arc<FairMutex<OutputParser>>,
+    buffer: Box<[u8]>,
+    listener: ZedListener,
+    executor: BackgroundExecutor,
+    /// Ends the synchronized update in progress once it times out, along with its deadline. The
+    /// event loop only times out the updates in the output that it parses itself.
+    sync_timeout: Option<(Instant, Task<()>)>,
+}
+
+impl CommandTrackingPty {
+    pub(crate) fn new(
+        pty: tty::Pty,
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        commands: Arc<FairMutex<CommandTracker>>,
+        listener: ZedListener,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        Self {
+            pty,
+            term,
+            commands,
+            parser: Arc::new(FairMutex::new(OutputParser::default())),
+            buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            listener,
+            executor,
+            sync_timeout: None,
+        }
+    }
+
+    fn schedule_sync_timeout(&mut self, deadline: Option<Instant>) {
+        let Some(deadline) = deadline else {
+            self.sync_timeout = None;
+            return;
+        };
+        if self
+            .sync_timeout
+            .as_ref()
+            .is_some_and(|(scheduled, _)| *scheduled == deadline)
+        {
+            return;
+        }
+
+        let term = self.term.clone();
+        let commands = self.commands.clone();
+        let parser = self.parser.clone();
+        let listener = self.listener.clone();
+        let timer = self
+            .executor
+            .timer(deadline.saturating_duration_since(Instant::now()));
+        let task = self.executor.spawn(async move {
+            timer.await;
+            let timed_out = {
+                let mut term = term.lock();
+                let mut commands = commands.lock();
+                let mut parser = parser.lock();
+                // Another update may have started since.
+                let timed_out = parser.sync_deadline() == Some(deadline);
+                if timed_out {
+                    parser.stop_sync(&mut term, &mut commands);
+                }
+                timed_out
+            };
+            if timed_out {
+                listener.send_event(AlacTermEvent::Wakeup);
+            }
+        });
+        self.sync_timeout = Some((deadline, task));
+    }
+}
+
+impl io::Read for CommandTrackingPty {
+    /// Parses the available output, and reports that nothing was read.
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        let mut processed = 0;
+        let mut locks = None;
+        let result = loop {
+            match self.pty.reader().read(&mut self.buffer) {
+                Ok(0) => break Ok(0),
+                Ok(read) => {
+                    let (term, commands, parser) = locks.get_or_insert_with(|| {
+                        (
+                            self.term.lock_unfair(),
+                            self.commands.lock_unfair(),
+                            self.parser.lock_unfair(),
+                        )
+                    });
+                    parser.advance(term, commands, &self.buffer[..read]);
+                    processed += read;
+                    if processed >= MAX_LOCKED_READ {
+                        break Ok(0);
+                    }
+                }
+                Err(error) => break Err(error),
+            }
+        };
+        let Some((term, commands, parser)) = locks else {
+            return result;
+        };
+        let held_back = parser.processor.sync_bytes_count();
+        let sync_deadline = parser.sync_deadline();
+        drop((term, commands, parser));
+
+        if held_back < processed {
+            self.listener.send_event(AlacTermEvent::Wakeup);
+        }
+        self.schedule_sync_timeout(sync_deadline);
+        Ok(0)
+    }
+}
+
+impl EventedReadWrite for CommandTrackingPty {
+    type Reader = Self;
+    type Writer = <tty::Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poller: &Arc<Poller>,
+        event: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poller, event, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poller: &Arc<Poller>,
+        event: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poller, event, mode)
+    }
+
+    fn deregister(&mut self, poller: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poller)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for CommandTrackingPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for CommandTrackingPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::term::{Config, test::TermSize};
+    use futures::channel::mpsc::unbounded;
+
+    use super::*;
+
+    fn scan(chunks: &[&[u8]]) -> Vec<Mark> {
+        let mut scanner = MarkScanner::default();
+        let mut marks = Vec::new();
+        for mut chunk in chunks.iter().copied() {
+            while let Some((end, mark)) = scanner.next_mark(chunk) {
+                marks.push(mark);
+                chunk = &chunk[end..];
+            }
+        }
+        marks
+    }
+
+    #[test]
+    fn test_scanning_marks() {
+        assert_eq!(
+            scan(&[b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07out\r\n\x1b]133;D;2\x07"]),
+            vec![
+                Mark::PromptStart,
+                Mark::CommandStart,
+                Mark::OutputStart { command: None },
+                Mark::CommandFinished { exit_code: Some(2) },
+            ]
+        );
+        assert_eq!(
+            scan(&[
+                b"\x1b]133;C;cmdline_url=echo%20%221%3B2%22%0Als\x07",
+                b"\x1b]133;C;cmdline=echo a\\;b\x07"
+            ]),
+            vec![
+                Mark::OutputStart {
+                    command: Some("echo \"1;2\"\nls".to_string())
+                },
+                Mark::OutputStart {
+                    command: Some("echo a\\;b".to_string())
+                },
+            ]
+        );
+        assert_eq!(
+            scan(&[
+                b"\x1b]13",
+                b"3;D\x1b",
+                b"\\\x1b]7;file://host/home/me/a%20b\x07"
+            ]),
+            vec![
+                Mark::CommandFinished { exit_code: None },
+                Mark::WorkingDirectory(PathBuf::from("/home/me/a b")),
+            ]
+        );
+        assert_eq!(
+            scan(&[b"\x1b]0;title\x07\x1b[1m\x1b]133;A\x18\x1b]133;Z\x07\x1b]133;B\x07"]),
+            vec![Mark::CommandStart],
+            "other sequences, aborted sequences and unknown marks are skipped"
+        );
+    }
+
+    fn tracked_term(scrolling_history: usize) -> (Term<ZedListener>, CommandTracker, OutputParser) {
+        let config = Config {
+            scrolling_history,
+            ..Config::default()
+        };
+        let term = Term::new(config, &TermSize::new(10, 3), ZedListener(unbounded().0));
+        (
+            term,
+            CommandTracker::new(scrolling_history),
+            OutputParser::default(),
+        )
+    }
+
+    fn run(command: &str, output: &str) -> String {
+        format!(
+            "\x1b]133;A\x07$ \x1b]133;B\x07{command}\r\n\x1b]133;C;cmdline_url={}\x07{output}\x1b]133;D;0\x07",
+            urlencoding::encode(command)
+        )
+    }
+
+    fn lines(range: std::ops::RangeInclusive<usize>) -> String {
+        range.map(|i| format!("{i}\r\n")).collect()
+    }
+
+    #[test]
+    fn test_tracking_commands_leaving_the_scrollback() {
+        let (mut term, mut commands, mut parser) = tracked_term(4);
+        let output = format!(
+            "{}{}\x1b]133;A\x07$ ",
+            run("a", "1\r\n"),
+            run("b", &lines(1..=8))
+        );
+        parser.advance(&mut term, &mut commands, output.as_bytes());
+
+        // 12 lines were written, and the last 7 are left: `a` left the scrollback along with the
+        // start of `b`.
+        let blocks = commands.command_blocks(&term);
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| block.command.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", ""]
+        );
+        assert_eq!(blocks[0].prompt_start, None);
+        assert_eq!(blocks[0].output_start, None);
+        assert_eq!(
+            blocks[0].output_end,
+            Some(AlacPoint::new(Line(2), Column(0)))
+        );
+        assert_eq!(
+            commands.command_result(blocks[0].id, &term),
+            Some(CommandResult {
+                command: "b".to_string(),
+                cwd: None,
+                exit_code: Some(0),
+                output: "3\n4\n5\n6\n7\n8".to_string(),
+            })
+        );
+
+        // The viewport stays where the user scrolled it to, and follows the lines it shows.
+        term.scroll_display(Scroll::Delta(2));
+        parser.advance(&mut term, &mut commands, b"x\r\n");
+        assert_eq!(term.grid().display_offset(), 3);
+        let blocks = commands.command_blocks(&term);
+        assert_eq!(
+            blocks[0].output_end,
+            Some(AlacPoint::new(Line(1), Column(0)))
+        );
+
+        term.scroll_display(Scroll::Bottom);
+        parser.advance(&mut term, &mut commands, b"\r\n");
+        assert_eq!(term.grid().display_offset(), 0);
+        assert_eq!(
+            commands.command_blocks(&term)[0].output_end,
+            Some(AlacPoint::new(Line(0), Column(0)))
+        );
+    }
+
+    #[test]
+    fn test_tracking_commands_around_the_alternate_screen() {
+        let (mut term, mut commands, mut parser) = tracked_term(4);
+        let output = format!("{}\x1b]133;A\x07$ vim\r\n", run("a", "1\r\n"));
+        parser.advance(&mut term, &mut commands, output.as_bytes());
+        let blocks = commands.command_blocks(&term);
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| block.prompt_start)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(AlacPoint::new(Line(-1), Column(0))),
+                Some(AlacPoint::new(Line(1), Column(0))),
+            ]
+        );
+
+        // The alternate screen is left in a later read than the one it is entered in, and the lines
+        // that scroll in it aren't counted.
+        parser.advance(
+            &mut term,
+            &mut commands,
+            format!("\x1b[?1049h{}", lines(1..=5)).as_bytes(),
+        );
+        assert_eq!(commands.command_blocks(&term), Vec::new());
+        parser.advance(
+            &mut term,
+            &mut commands,
+            format!("{}\x1b[?1049l", lines(6..=10)).as_bytes(),
+        );
+        assert_eq!(commands.command_blocks(&term), blocks);
+
+        parser.advance(&mut term, &mut commands, lines(1..=2).as_bytes());
+        assert_eq!(
+            commands
+                .command_blocks(&term)
+                .iter()
+                .map(|block| block.prompt_start)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(AlacPoint::new(Line(-3), Column(0))),
+                Some(AlacPoint::new(Line(-1), Column(0))),
+            ]
+        );
+    }
+}
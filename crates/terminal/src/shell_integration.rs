@@ -0,0 +1,144 @@
+//! Scripts that make shells report their prompts and commands with `OSC 133` sequences, and the
+//! environment that makes shells load them on startup. See the `command_blocks` module for how these
+//! sequences are tracked.
+
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSH_SCRIPT: &str = include_str!("shell_integration/zed.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// Sets up the shell integration for `program`, if it is a supported shell started as an interactive
+/// shell by `args`. Returns the arguments to start it with, and updates `env` for it.
+///
+/// `login` is whether the shell would have been started as a login shell without arguments.
+pub(crate) fn inject(
+    program: &str,
+    args: &[String],
+    login: bool,
+    env: &mut HashMap<String, String>,
+) -> Result<Option<Vec<String>>> {
+    let dir = paths::shell_integration_dir();
+    match Path::new(program).file_name().and_then(OsStr::to_str) {
+        Some("bash") => {
+            let login = match args {
+                [] => login,
+                [arg] if arg == "-l" || arg == "--login" => true,
+                _ => return Ok(None),
+            };
+            let script = write_script(&dir.join("bash").join("zed.bash"), BASH_SCRIPT)?;
+            if login {
+                env.insert("ZED_BASH_LOGIN_SHELL".to_string(), "1".to_string());
+            }
+            Ok(Some(vec![
+                "--init-file".to_string(),
+                script.to_string_lossy().into_owned(),
+            ]))
+        }
+        Some("zsh") => {
+            let zdotdir = dir.join("zsh");
+            write_script(&zdotdir.join(".zshenv"), ZSH_SCRIPT)?;
+            if let Some(user_zdotdir) = env_var(env, "ZDOTDIR") {
+                env.insert("ZED_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                zdotdir.to_string_lossy().into_owned(),
+            );
+            Ok(Some(args.to_vec()))
+        }
+        Some("fish") => {
+            let data_dir = dir.join("fish");
+            write_script(&data_dir.join("fish/vendor_conf.d/zed.fish"), FISH_SCRIPT)?;
+            let user_data_dirs = env_var(env, "XDG_DATA_DIRS");
+            let data_dirs = user_data_dirs
+                .clone()
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            if let Some(user_data_dirs) = user_data_dirs {
+                env.insert("ZED_FISH_XDG_DATA_DIRS".to_string(), user_data_dirs);
+            }
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{}:{data_dirs}", data_dir.to_string_lossy()),
+            );
+            Ok(Some(args.to_vec()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// A script for a POSIX shell that runs the command passed as its first positional argument, with
+/// the arguments from [`posix_command_wrapper_args`], so that it reports the command, its output and
+/// its exit status like an integrated shell would. Passing the command separately keeps heredocs
+/// and line continuations in it intact.
+pub const POSIX_COMMAND_WRAPPER: &str = "printf '\\033]133;C;cmdline_url=%s\\007' \"$2\"; eval \"$1\"; __zed_status=$?; printf '\\033]133;D;%d\\007' \"$__zed_status\"; exit \"$__zed_status\"";
+
+/// The arguments that [`POSIX_COMMAND_WRAPPER`] runs `command` with, e.g.
+/// `sh -c "$POSIX_COMMAND_WRAPPER" sh "$command" "$encoded_command"`.
+pub fn posix_command_wrapper_args(command: &str) -> [String; 3] {
+    [
+        "sh".to_string(),
+        command.to_string(),
+        urlencoding::encode(command).into_owned(),
+    ]
+}
+
+fn env_var(env: &HashMap<String, String>, name: &str) -> Option<String> {
+    env.get(name)
+        .cloned()
+        .or_else(|| std::env::var(name).ok())
+        .filter(|value| !value.is_empty())
+}
+
+fn write_script(path: &Path, content: &str) -> Result<PathBuf> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(path.to_path_buf());
+    }
+    let dir = path
+        .parent()
+        .context("shell integration script has no parent")?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("creating shell integration directory {dir:?}"))?;
+    fs::write(path, content)
+        .with_context(|| format!("writing shell integration script {path:?}"))?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_posix_command_wrapper() {
+        let run = |command: &str| {
+            std::process::Command::new("sh")
+                .arg("-c")
+                .arg(POSIX_COMMAND_WRAPPER)
+                .args(posix_command_wrapper_args(command))
+                .output()
+                .unwrap()
+        };
+
+        let output = run("echo hi; false");
+        assert_eq!(
+            output.stdout,
+            b"\x1b]133;C;cmdline_url=echo%20hi%3B%20false\x07hi\n\x1b]133;D;1\x07"
+        );
+        assert_eq!(output.status.code(), Some(1));
+
+        let output = run("cat <<EOF\nhi\nEOF\necho a \\\n  b");
+        assert_eq!(
+            output.stdout,
+            b"\x1b]133;C;cmdline_url=cat%20%3C%3CEOF%0Ahi%0AEOF%0Aecho%20a%20%5C%0A%20%20b\x07hi\na b\n\x1b]133;D;0\x07"
+        );
+        assert_eq!(output.status.code(), Some(0));
+    }
+}
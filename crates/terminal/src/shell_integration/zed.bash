@@ -0,0 +1,79 @@
+# Reports the prompts and commands of bash to the Zed terminal, with OSC 133 sequences.
+# Zed starts bash with `--init-file` pointing at this file, so it runs the startup files itself.
+
+if [ -n "${ZED_BASH_LOGIN_SHELL-}" ]; then
+    unset ZED_BASH_LOGIN_SHELL
+    [ -r /etc/profile ] && . /etc/profile
+    for __zed_profile in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [ -r "$__zed_profile" ]; then
+            . "$__zed_profile"
+            break
+        fi
+    done
+    unset __zed_profile
+elif [ -r ~/.bashrc ]; then
+    . ~/.bashrc
+fi
+
+if [[ $- == *i* && -z "${__zed_shell_integration-}" ]]; then
+    __zed_shell_integration=1
+
+    # Zed ignores the end of a command that it didn't see start, e.g. before the first prompt.
+    __zed_precmd() {
+        local status=$?
+        printf '\e]133;D;%s\a' "$status"
+        printf '\e]7;file://%s%s\a' "$HOSTNAME" "$PWD"
+        return $status
+    }
+
+    __zed_prompt() {
+        local status=$?
+        if [[ $PS1 != *'133;A'* ]]; then
+            PS1='\[\e]133;A\a\]'"$PS1"'\[\e]133;B\a\]'
+        fi
+        __zed_at_prompt=1
+        __zed_last_history=$(__zed_history)
+        return $status
+    }
+
+    __zed_history() {
+        HISTTIMEFORMAT= builtin history 1 2>/dev/null
+    }
+
+    # Reports the start of the output of the command line that history got, or of `$1` if history
+    # didn't get a new one, e.g. as it ignores commands that start with a space.
+    __zed_output_start() {
+        local cmdline=${1-} entry
+        entry=$(__zed_history)
+        if [[ $entry != "$__zed_last_history" && $entry =~ ^\ *[0-9]+[\ *]\ (.*)$ ]]; then
+            cmdline=${BASH_REMATCH[1]}
+        fi
+        # Percent-encode what would end or split the sequence.
+        cmdline=${cmdline//\%/%25}
+        cmdline=${cmdline//;/%3B}
+        cmdline=${cmdline//$'\a'/%07}
+        cmdline=${cmdline//$'\e'/%1B}
+        cmdline=${cmdline//$'\n'/%0A}
+        cmdline=${cmdline//$'\r'/%0D}
+        printf '\e]133;C;cmdline_url=%s\a' "$cmdline"
+    }
+
+    if [[ "$(declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(__zed_precmd "${PROMPT_COMMAND[@]}" __zed_prompt)
+    else
+        PROMPT_COMMAND="__zed_precmd${PROMPT_COMMAND:+; $PROMPT_COMMAND}; __zed_prompt"
+    fi
+
+    if (( BASH_VERSINFO[0] > 4 || (BASH_VERSINFO[0] == 4 && BASH_VERSINFO[1] >= 4) )); then
+        # PS0 is printed after a command line is read, before it runs.
+        PS0='$(__zed_output_start)'"${PS0-}"
+    else
+        __zed_preexec() {
+            if [ -n "${__zed_at_prompt-}" ] && [ "$BASH_COMMAND" != __zed_precmd ]; then
+                __zed_at_prompt=
+                __zed_output_start "$BASH_COMMAND"
+            fi
+        }
+        trap '__zed_preexec' DEBUG
+    fi
+fi
@@ -0,0 +1,45 @@
+# Reports the prompts and commands of fish to the Zed terminal, with OSC 133 sequences.
+# Zed adds the directory of this file to XDG_DATA_DIRS so that fish loads it as a vendor
+# configuration snippet, and restores XDG_DATA_DIRS for the rest of the session.
+
+if set -q ZED_FISH_XDG_DATA_DIRS
+    set -gx XDG_DATA_DIRS $ZED_FISH_XDG_DATA_DIRS
+    set -e ZED_FISH_XDG_DATA_DIRS
+else
+    set -e XDG_DATA_DIRS
+end
+
+if status is-interactive; and not set -q __zed_shell_integration
+    set -g __zed_shell_integration 1
+
+    function __zed_preexec --on-event fish_preexec
+        printf '\e]133;C;cmdline_url=%s\a' (string escape --style=url -- $argv)
+    end
+
+    function __zed_postexec --on-event fish_postexec
+        printf '\e]133;D;%s\a' $status
+    end
+
+    function __zed_cwd --on-event fish_prompt
+        printf '\e]7;file://%s%s\a' $hostname $PWD
+    end
+
+    function __zed_return
+        return $argv[1]
+    end
+
+    # Wraps the prompt once the startup files of the user defined it.
+    function __zed_wrap_prompt --on-event fish_prompt
+        functions -e __zed_wrap_prompt
+        functions -q fish_prompt; or return
+        functions -c fish_prompt __zed_fish_prompt
+        function fish_prompt
+            set -l last_status $status
+            printf '\e]133;A\a'
+            # Let the prompt of the user see the status of the command.
+            __zed_return $last_status
+            __zed_fish_prompt
+            printf '\e]133;B\a'
+        end
+    end
+end
@@ -1,15 +1,17 @@
+mod command_blocks;
 pub mod mappings;
 
 pub use alacritty_terminal;
 
 mod pty_info;
+pub mod shell_integration;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
     Term,
-    event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
-    event_loop::{EventLoop, Msg, Notifier},
+    event::{Event as AlacTermEvent, EventListener, Notify, OnResize, WindowSize},
+    event_loop::{EventLoop, EventLoopSender, Msg, Notifier},
     grid::{Dimensions, Grid, Row, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
@@ -19,7 +21,7 @@ use alacritty_terminal::{
         cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
     },
-    tty::{self},
+    tty::{self, EventedPty},
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
@@ -40,6 +42,8 @@ use mappings::mouse::{
 };
 
 use collections::{HashMap, VecDeque};
+pub use command_blocks::{CommandBlock, CommandResult, CommandStatus};
+use command_blocks::{CommandTracker, CommandTrackingPty, OutputParser};
 use futures::StreamExt;
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
@@ -56,6 +60,7 @@ use std::{
     borrow::Cow,
     cmp::{self, min},
     fmt::Display,
+    mem,
     ops::{Deref, RangeInclusive},
     path::PathBuf,
     process::ExitStatus,
@@ -105,6 +110,14 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls to the prompt of the previous command.
+        ScrollToPreviousPrompt,
+        /// Scrolls to the prompt of the next command.
+        ScrollToNextPrompt,
+        /// Copies the output of the last command to the clipboard.
+        CopyCommandOutput,
+        /// Runs the last command again.
+        RerunCommand,
     ]
 );
 
@@ -376,6 +389,8 @@ impl TerminalBuilder {
             terminal_type: TerminalType::DisplayOnly,
             completion_tx: None,
             term,
+            commands: Arc::new(FairMutex::new(CommandTracker::new(scrolling_history))),
+            output_parser: OutputParser::default(),
             term_config: config,
             title_override: None,
             events: VecDeque::with_capacity(10),
@@ -405,6 +420,7 @@ impl TerminalBuilder {
                 max_scroll_history_lines,
                 path_hyperlink_regexes: Vec::default(),
                 path_hyperlink_timeout_ms: 0,
+                shell_integration: false,
                 window_id,
             },
             child_exited: None,
@@ -427,6 +443,7 @@ impl TerminalBuilder {
         max_scroll_history_lines: Option<usize>,
         path_hyperlink_regexes: Vec<String>,
         path_hyperlink_timeout_ms: u64,
+        shell_integration: bool,
         is_remote_terminal: bool,
         window_id: u64,
         completion_tx: Option<Sender<Option<ExitStatus>>>,
@@ -434,6 +451,7 @@ impl TerminalBuilder {
         activation_script: Vec<String>,
     ) -> Task<Result<TerminalBuilder>> {
         let version = release_channel::AppVersion::global(cx);
+        let executor = cx.background_executor().clone();
         let fut = async move {
             // Remove SHLVL so the spawned shell initializes it to 1, matching
             // the behavior of standalone terminal emulators like iTerm2/Kitty/Alacritty.
@@ -472,7 +490,7 @@ impl TerminalBuilder {
                 }
             }
 
-            let shell_params = match shell.clone() {
+            let mut shell_params = match shell.clone() {
                 Shell::System => {
                     if cfg!(windows) {
                         Some(ShellParams::new(
@@ -510,6 +528,32 @@ impl TerminalBuilder {
             // supported remoting into windows.
             let shell_kind = shell.shell_kind(cfg!(windows));
 
+            let mut shell_env = env.clone();
+            if shell_integration && task.is_none() && !is_remote_terminal && !cfg!(windows) {
+                // Alacritty starts the system shell as a login shell on macOS.
+                let (program, args, login) = match &shell_params {
+                    Some(params) => (
+                        params.program.clone(),
+                        params.args.clone().unwrap_or_default(),
+                        false,
+                    ),
+                    None => (
+                        util::shell::get_system_shell(),
+                        Vec::new(),
+                        cfg!(target_os = "macos"),
+                    ),
+                };
+                match shell_integration::inject(&program, &args, login, &mut shell_env) {
+                    Ok(Some(new_args)) if new_args != args => {
+                        let title_override = shell_params.and_then(|params| params.title_override);
+                        shell_params =
+                            Some(ShellParams::new(program, Some(new_args), title_override));
+                    }
+                    Ok(_) => {}
+                    Err(error) => log::error!("failed to set up shell integration: {error:#}"),
+                }
+            }
+
             let pty_options = {
                 let alac_shell = shell_params.as_ref().map(|params| {
                     alacritty_terminal::tty::Shell::new(
@@ -522,7 +566,7 @@ impl TerminalBuilder {
                     shell: alac_shell,
                     working_directory: working_directory.clone(),
                     drain_on_exit: true,
-                    env: env.clone().into_iter().collect(),
+                    env: shell_env.into_iter().collect(),
                     #[cfg(windows)]
                     escape_args: shell_kind.tty_escape_args(),
                 }
@@ -575,21 +619,35 @@ impl TerminalBuilder {
             }
 
             let term = Arc::new(FairMutex::new(term));
+            let commands = Arc::new(FairMutex::new(CommandTracker::new(scrolling_history)));
 
             let pty_info = PtyProcessInfo::new(&pty);
 
             //And connect them together
-            let event_loop = EventLoop::new(
-                term.clone(),
-                ZedListener(events_tx),
-                pty,
-                pty_options.drain_on_exit,
-                false,
-            )
-            .context("failed to create event loop")?;
-
-            let pty_tx = event_loop.channel();
-            let _io_thread = event_loop.spawn(); // DANGER
+            let pty_tx = if shell_integration {
+                // Commands are only reported by shells with the integration, or by the commands
+                // that it wraps.
+                let pty = CommandTrackingPty::new(
+                    pty,
+                    term.clone(),
+                    commands.clone(),
+                    ZedListener(events_tx.clone()),
+                    executor,
+                );
+                spawn_event_loop(
+                    &term,
+                    ZedListener(events_tx),
+                    pty,
+                    pty_options.drain_on_exit,
+                )?
+            } else {
+                spawn_event_loop(
+                    &term,
+                    ZedListener(events_tx),
+                    pty,
+                    pty_options.drain_on_exit,
+                )?
+            };
 
             let no_task = task.is_none();
             let terminal = Terminal {
@@ -600,6 +658,8 @@ impl TerminalBuilder {
                 },
                 completion_tx,
                 term,
+                commands,
+                output_parser: OutputParser::default(),
                 term_config: config,
                 title_override: terminal_title_override,
                 events: VecDeque::with_capacity(10), //Should never get this high.
@@ -632,6 +692,7 @@ impl TerminalBuilder {
                     max_scroll_history_lines,
                     path_hyperlink_regexes,
                     path_hyperlink_timeout_ms,
+                    shell_integration,
                     window_id,
                 },
                 child_exited: None,
@@ -755,6 +816,23 @@ impl TerminalBuilder {
     }
 }
 
+/// Spawns the event loop that connects the pty to the terminal, on its own thread.
+fn spawn_event_loop<T>(
+    term: &Arc<FairMutex<Term<ZedListener>>>,
+    listener: ZedListener,
+    pty: T,
+    drain_on_exit: bool,
+) -> Result<EventLoopSender>
+where
+    T: EventedPty + OnResize + Send + 'static,
+{
+    let event_loop = EventLoop::new(term.clone(), listener, pty, drain_on_exit, false)
+        .context("failed to create event loop")?;
+    let pty_tx = event_loop.channel();
+    let _io_thread = event_loop.spawn(); // DANGER
+    Ok(pty_tx)
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IndexedCell {
     pub point: AlacPoint,
@@ -784,6 +862,8 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The commands reported by the shell integration.
+    pub command_blocks: Vec<CommandBlock>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -810,6 +890,7 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_blocks: Vec::new(),
         }
     }
 }
@@ -832,6 +913,9 @@ pub struct Terminal {
     terminal_type: TerminalType,
     completion_tx: Option<Sender<Option<ExitStatus>>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
+    commands: Arc<FairMutex<CommandTracker>>,
+    /// Parses the output written with [`Terminal::write_output`].
+    output_parser: OutputParser,
     term_config: Config,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
@@ -868,9 +952,18 @@ struct CopyTemplate {
     max_scroll_history_lines: Option<usize>,
     path_hyperlink_regexes: Vec<String>,
     path_hyperlink_timeout_ms: u64,
+    shell_integration: bool,
     window_id: u64,
 }
 
+/// The last line of a terminal's output read by [`Terminal::read_new_lines`], used to find the
+/// output printed after it.
+#[derive(Clone, Debug, Default)]
+pub struct OutputPosition {
+    /// The text of the line, and its index counting from the top of the scrollback.
+    last_read: Option<(String, usize)>,
+}
+
 #[derive(Debug)]
 pub struct TaskState {
     pub status: TaskStatus,
@@ -1012,7 +1105,7 @@ impl Terminal {
                     pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
                 }
 
-                term.resize(new_bounds);
+                self.commands.lock().resize(term, new_bounds);
                 // If there are matches we need to emit a wake up event to
                 // invalidate the matches and recalculate their locations
                 // in the new terminal layout
@@ -1045,6 +1138,7 @@ impl Terminal {
                 term.grid_mut().cursor.point =
                     AlacPoint::new(Line(0), term.grid_mut().cursor.point.column);
                 let new_cursor = term.grid().cursor.point;
+                self.commands.lock().clear(term, cursor.line);
 
                 // Clear the lines below the new cursor
                 if (new_cursor.line.0 as usize) < term.screen_lines() - 1 {
@@ -1292,12 +1386,10 @@ impl Terminal {
             prev_byte = byte;
         }
 
-        let mut processor = alacritty_terminal::vte::ansi::Processor::<
-            alacritty_terminal::vte::ansi::StdSyncHandler,
-        >::new();
         {
             let mut term = self.term.lock();
-            processor.advance(&mut *term, &converted);
+            self.output_parser
+                .advance(&mut term, &mut self.commands.lock(), &converted);
         }
         cx.emit(Event::Wakeup);
     }
@@ -1403,6 +1495,108 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let viewport_top = -(self.last_content.display_offset as i32);
+        let previous_prompt = self
+            .last_content
+            .command_blocks
+            .iter()
+            .rev()
+            .filter_map(CommandBlock::start)
+            .find(|start| start.line.0 < viewport_top);
+        if let Some(prompt) = previous_prompt {
+            self.scroll_to_line(prompt.line);
+        }
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        let viewport_top = -(self.last_content.display_offset as i32);
+        let next_prompt = self
+            .last_content
+            .command_blocks
+            .iter()
+            .filter_map(CommandBlock::start)
+            .find(|start| start.line.0 > viewport_top);
+        match next_prompt {
+            Some(prompt) => self.scroll_to_line(prompt.line),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    /// Scrolls so that the given line is at the top of the viewport, as far as possible.
+    fn scroll_to_line(&mut self, line: Line) {
+        let display_offset = self.last_content.display_offset as i32;
+        self.events
+            .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                -line.0 - display_offset,
+            )));
+    }
+
+    /// The commands reported by the shell integration, in the order they were run.
+    pub fn command_blocks(&self) -> Vec<CommandBlock> {
+        let term = self.term.lock_unfair();
+        self.commands.lock().command_blocks(&term)
+    }
+
+    /// The output of the command with the given id so far, as far as it is still in the scrollback.
+    pub fn command_output(&self, id: usize) -> Option<String> {
+        let term = self.term.lock_unfair();
+        self.commands.lock().command_output(id, &term)
+    }
+
+    /// What the command with the given id did so far, as far as its output is still in the
+    /// scrollback.
+    pub fn command_result(&self, id: usize) -> Option<CommandResult> {
+        let term = self.term.lock_unfair();
+        self.commands.lock().command_result(id, &term)
+    }
+
+    /// Copies the output of the last finished command that starts above the bottom of the viewport.
+    pub fn copy_command_output(&mut self, cx: &mut App) {
+        let viewport_bottom = self.last_content.terminal_bounds.num_lines() as i32
+            - self.last_content.display_offset as i32
+            - 1;
+        let Some(id) = self
+            .last_content
+            .command_blocks
+            .iter()
+            .rev()
+            .filter(|block| matches!(block.status, CommandStatus::Finished { .. }))
+            .find(|block| {
+                block
+                    .start()
+                    .is_some_and(|start| start.line.0 <= viewport_bottom)
+            })
+            .map(|block| block.id)
+        else {
+            return;
+        };
+        if let Some(output) = self.command_output(id) {
+            cx.write_to_clipboard(ClipboardItem::new_string(output));
+        }
+    }
+
+    /// Runs the last command again, if the shell is showing a prompt.
+    pub fn rerun_command(&mut self) {
+        let blocks = self.command_blocks();
+        let Some((pending, finished)) = blocks.split_last() else {
+            return;
+        };
+        if pending.status != CommandStatus::Pending {
+            return;
+        }
+        let Some(command) = finished
+            .iter()
+            .rev()
+            .find(|block| !block.command.is_empty())
+            .map(|block| block.command.clone())
+        else {
+            return;
+        };
+        // Clear whatever was typed at the prompt first.
+        self.input(format!("\x15{command}\r").into_bytes());
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1591,6 +1785,7 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.command_blocks = self.commands.lock().command_blocks(&terminal);
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1623,6 +1818,7 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_blocks: Vec::new(),
         }
     }
 
@@ -1658,6 +1854,51 @@ impl Terminal {
         lines
     }
 
+    /// Returns the lines of the output printed after `position`, with wrapped lines joined, and
+    /// moves `position` to the last of them that is complete. The line of the cursor may still be
+    /// being printed, so it is returned again by the next call.
+    pub fn read_new_lines(&self, position: &mut OutputPosition) -> Vec<String> {
+        let term = self.term.lock_unfair();
+        let grid = term.grid();
+        let history_size = grid.history_size() as i32;
+        let cursor_line = grid.cursor.point.line;
+        let row_text = |line: Line| row_to_string(&grid[line]).trim_end().to_string();
+        let is_wrapped = |line: Line| {
+            grid[line][grid.last_column()]
+                .flags
+                .contains(Flags::WRAPLINE)
+        };
+
+        // Lines only move up, as the scrollback drops its oldest lines, so the last read line is
+        // looked for from where it was, upwards. If it is gone, so is all of the output read.
+        let mut start = grid.topmost_line();
+        if let Some((text, index)) = &position.last_read {
+            let last_read = Line(*index as i32 - history_size).min(cursor_line);
+            if let Some(line) = (start.0..=last_read.0)
+                .rev()
+                .map(Line)
+                .find(|&line| row_text(line) == *text)
+            {
+                start = line + 1;
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        let mut line = start;
+        while line <= cursor_line {
+            current.push_str(&row_to_string(&grid[line]));
+            if !is_wrapped(line) || line == cursor_line {
+                lines.push(mem::take(&mut current).trim_end().to_string());
+                if line < cursor_line {
+                    position.last_read = Some((row_text(line), (line.0 + history_size) as usize));
+                }
+            }
+            line += 1;
+        }
+        lines
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -2236,6 +2477,8 @@ impl Terminal {
             // After the task summary is output once, no more text is appended to the terminal.
             unsafe { append_text_to_term(&mut self.term.lock(), &lines_to_show) };
         }
+        // Let subscribers see the summary and that the task finished.
+        cx.emit(Event::Wakeup);
 
         match task.spawned_task.hide {
             HideStrategy::Never => {}
@@ -2266,6 +2509,7 @@ impl Terminal {
             self.template.max_scroll_history_lines,
             self.template.path_hyperlink_regexes.clone(),
             self.template.path_hyperlink_timeout_ms,
+            self.template.shell_integration,
             self.is_remote_terminal,
             self.template.window_id,
             None,
@@ -2591,6 +2835,7 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -2641,6 +2886,7 @@ mod tests {
                     vec![],
                     0,
                     false,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -2716,6 +2962,7 @@ mod tests {
                     Vec::new(),
                     0,
                     false,
+                    false,
                     0,
                     Some(completion_tx),
                     cx,
@@ -2905,6 +3152,224 @@ mod tests {
         }
     }
 
+    fn write_shell_session(terminal: &mut Terminal, cx: &mut Context<Terminal>) {
+        let prompt = |command: &str| {
+            format!("\x1b]7;file://host/tmp/a%20b\x07\x1b]133;A\x07$ \x1b]133;B\x07{command}")
+        };
+        let seq = (1..=20).map(|i| format!("{i}\n")).collect::<String>();
+        for (command, output, exit_code) in [
+            ("echo one", "one\n", 0),
+            ("false", "", 1),
+            ("seq 20", seq.as_str(), 0),
+        ] {
+            terminal.write_output(
+                format!(
+                    "{}\n\x1b]133;C;cmdline_url={}\x07{output}\x1b]133;D;{exit_code}\x07",
+                    prompt(command),
+                    urlencoding::encode(command)
+                )
+                .as_bytes(),
+                cx,
+            );
+        }
+        terminal.write_output(prompt("").as_bytes(), cx);
+    }
+
+    #[gpui::test]
+    async fn test_command_blocks(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            write_shell_session(terminal, cx);
+
+            // 25 lines were written to a terminal with 6 lines.
+            let blocks = terminal.command_blocks();
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| (block.command.as_str(), block.status))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("echo one", CommandStatus::Finished { exit_code: Some(0) }),
+                    ("false", CommandStatus::Finished { exit_code: Some(1) }),
+                    ("seq 20", CommandStatus::Finished { exit_code: Some(0) }),
+                    ("", CommandStatus::Pending),
+                ]
+            );
+            assert_eq!(
+                blocks.iter().map(CommandBlock::failed).collect::<Vec<_>>(),
+                vec![false, true, false, false]
+            );
+            assert_eq!(blocks[0].cwd, Some(PathBuf::from("/tmp/a b")));
+            assert_eq!(
+                blocks[0].prompt_start,
+                Some(AlacPoint::new(Line(-19), Column(0)))
+            );
+            assert_eq!(
+                blocks[0].command_start,
+                Some(AlacPoint::new(Line(-19), Column(2)))
+            );
+            assert_eq!(
+                blocks[3].prompt_start,
+                Some(AlacPoint::new(Line(5), Column(0)))
+            );
+
+            assert_eq!(
+                terminal.command_output(blocks[0].id).as_deref(),
+                Some("one")
+            );
+            assert_eq!(terminal.command_output(blocks[1].id).as_deref(), Some(""));
+            assert_eq!(
+                terminal.command_output(blocks[2].id),
+                Some(
+                    (1..=20)
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            );
+            assert_eq!(terminal.command_output(blocks[3].id), None);
+            assert_eq!(
+                terminal.command_result(blocks[1].id),
+                Some(CommandResult {
+                    command: "false".to_string(),
+                    cwd: Some(PathBuf::from("/tmp/a b")),
+                    exit_code: Some(1),
+                    output: String::new(),
+                })
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_clearing_command_blocks(cx: &mut TestAppContext) {
+        let window = cx.add_empty_window();
+        let terminal = window.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+        terminal.update_in(window, |terminal, window, cx| {
+            write_shell_session(terminal, cx);
+            terminal.clear();
+            terminal.sync(window, cx);
+
+            // Only the prompt is left, at the top of the screen.
+            let blocks = terminal.command_blocks();
+            assert_eq!(blocks.len(), 1);
+            assert_eq!(blocks[0].status, CommandStatus::Pending);
+            assert_eq!(
+                blocks[0].prompt_start,
+                Some(AlacPoint::new(Line(0), Column(0)))
+            );
+
+            terminal.write_output(
+                b"ls\n\x1b]133;C;cmdline_url=ls\x07a\nb\n\x1b]133;D;0\x07\x1b]133;A\x07$ ",
+                cx,
+            );
+            let blocks = terminal.command_blocks();
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| (block.command.as_str(), block.prompt_start))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("ls", Some(AlacPoint::new(Line(0), Column(0)))),
+                    ("", Some(AlacPoint::new(Line(3), Column(0)))),
+                ]
+            );
+            assert_eq!(
+                terminal.command_output(blocks[0].id).as_deref(),
+                Some("a\nb")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_command_blocks_leaving_scrollback(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(5),
+                0,
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            write_shell_session(terminal, cx);
+
+            // Only the last 11 lines are left, from the 11th line of the output of `seq`.
+            let blocks = terminal.command_blocks();
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| block.command.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["seq 20", ""]
+            );
+            assert_eq!(blocks[0].prompt_start, None);
+            assert_eq!(
+                blocks[0].output_end,
+                Some(AlacPoint::new(Line(5), Column(0)))
+            );
+            assert_eq!(
+                terminal.command_output(blocks[0].id),
+                Some(
+                    (11..=20)
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_read_new_lines(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(5),
+                0,
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            let mut position = OutputPosition::default();
+            terminal.write_output(b"one\ntwo\nthr", cx);
+            assert_eq!(
+                terminal.read_new_lines(&mut position),
+                vec!["one", "two", "thr"]
+            );
+
+            // The line of the cursor is read again.
+            terminal.write_output(b"ee\n", cx);
+            assert_eq!(terminal.read_new_lines(&mut position), vec!["three", ""]);
+            assert_eq!(terminal.read_new_lines(&mut position), vec![""]);
+
+            // Once the last read line leaves the scrollback, all of the output is new.
+            for i in 0..20 {
+                terminal.write_output(format!("{i}\n").as_bytes(), cx);
+            }
+            assert_eq!(
+                terminal.read_new_lines(&mut position),
+                (10..20)
+                    .map(|i| i.to_string())
+                    .chain([String::new()])
+                    .collect::<Vec<_>>()
+            );
+            terminal.write_output(b"20\n", cx);
+            assert_eq!(terminal.read_new_lines(&mut position), vec!["20", ""]);
+        });
+    }
+
     #[gpui::test]
     async fn test_write_output_converts_lf_to_crlf(cx: &mut TestAppContext) {
         let terminal = cx.new(|cx| {
@@ -3118,6 +3583,7 @@ mod tests {
                         settings.path_hyperlink_regexes.clone(),
                         test_path_hyperlink_timeout_ms,
                         false,
+                        false,
                         window.window_handle().window_id().as_u64(),
                         None,
                         cx,
@@ -1,4 +1,6 @@
-use std::{cmp, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc, time::Duration};
+use std::{
+    cmp, ops::ControlFlow, path::PathBuf, pin::pin, process::ExitStatus, sync::Arc, time::Duration,
+};
 
 use crate::{
     TerminalView, default_working_directory,
@@ -9,7 +11,12 @@ use crate::{
 use breadcrumbs::Breadcrumbs;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use futures::{channel::oneshot, future::join_all};
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::{mpsc, oneshot},
+    future::join_all,
+    select_biased,
+};
 use gpui::{
     Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EventEmitter,
     ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels, Render, Styled,
@@ -17,10 +24,11 @@ use gpui::{
 };
 use itertools::Itertools;
 use project::{Fs, Project, ProjectEntryId};
+use regex::Regex;
 use search::{BufferSearchBar, buffer_search::DivRegistrar};
 use settings::{Settings, TerminalDockPosition};
 use task::{RevealStrategy, RevealTarget, Shell, ShellBuilder, SpawnInTerminal, TaskId};
-use terminal::{Terminal, terminal_settings::TerminalSettings};
+use terminal::{OutputPosition, Terminal, terminal_settings::TerminalSettings};
 use ui::{
     ButtonLike, Clickable, ContextMenu, FluentBuilder, PopoverMenu, SplitButton, Toggleable,
     Tooltip, prelude::*,
@@ -42,6 +50,9 @@ use zed_actions::assistant::InlineAssist;
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
+/// How long to wait for more output of a background task before matching its ready pattern again.
+const READY_PATTERN_DEBOUNCE: Duration = Duration::from_millis(100);
+
 actions!(
     terminal_panel,
     [
@@ -1753,6 +1764,81 @@ impl workspace::TerminalProvider for TerminalProvider {
             }
         })
     }
+
+    fn spawn_background(
+        &self,
+        task: SpawnInTerminal,
+        ready_pattern: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<()>>> {
+        let terminal_panel = self.0.clone();
+        window.spawn(cx, async move |cx| {
+            let ready_pattern = match ready_pattern
+                .map(|pattern| Regex::new(&pattern))
+                .transpose()
+            {
+                Ok(ready_pattern) => ready_pattern,
+                Err(e) => return Some(Err(anyhow!("invalid ready pattern: {e}"))),
+            };
+            let terminal = terminal_panel
+                .update_in(cx, |terminal_panel, window, cx| {
+                    terminal_panel.spawn_task(&task, window, cx)
+                })
+                .ok()?
+                .await;
+            let terminal = match terminal {
+                Ok(terminal) => terminal.upgrade()?,
+                Err(e) => return Some(Err(e)),
+            };
+            let Some(ready_pattern) = ready_pattern else {
+                return Some(Ok(()));
+            };
+
+            let (wakeup_tx, mut wakeup_rx) = mpsc::unbounded();
+            let _subscription = cx
+                .update(|_, cx| {
+                    cx.subscribe(&terminal, move |_, event, _| {
+                        if let terminal::Event::Wakeup = event {
+                            wakeup_tx.unbounded_send(()).ok();
+                        }
+                    })
+                })
+                .ok()?;
+            let mut exited = pin!(
+                terminal
+                    .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+                    .ok()?
+                    .fuse()
+            );
+            let mut position = OutputPosition::default();
+            let mut is_ready = |cx: &mut AsyncWindowContext| {
+                terminal.read_with(cx, |terminal, _| {
+                    terminal
+                        .read_new_lines(&mut position)
+                        .iter()
+                        .any(|line| ready_pattern.is_match(line))
+                })
+            };
+            loop {
+                if is_ready(cx).ok()? {
+                    return Some(Ok(()));
+                }
+                select_biased! {
+                    _ = exited => {
+                        return Some(if is_ready(cx).ok()? {
+                            Ok(())
+                        } else {
+                            Err(anyhow!("`{}` exited before it was ready", task.label))
+                        });
+                    }
+                    wakeup = wakeup_rx.next() => wakeup?,
+                }
+                cx.background_executor().timer(READY_PATTERN_DEBOUNCE).await;
+                while let Ok(Some(())) = wakeup_rx.try_next() {}
+            }
+        })
+    }
 }
 
 struct InlineAssistTabBarButton {
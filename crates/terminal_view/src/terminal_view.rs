@@ -17,9 +17,10 @@ use project::{Project, search::SearchQuery};
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
-    TaskStatus, Terminal, TerminalBounds, ToggleViMode,
+    Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste, RerunCommand,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, ShowCharacterPalette, TaskState, TaskStatus, Terminal,
+    TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
         term::{TermMode, point_to_viewport, search::RegexSearch},
@@ -387,6 +388,12 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let has_commands = !self
+            .terminal
+            .read(cx)
+            .last_content
+            .command_blocks
+            .is_empty();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -395,6 +402,11 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .when(has_commands, |menu| {
+                    menu.separator()
+                        .action("Copy Command Output", Box::new(CopyCommandOutput))
+                        .action("Rerun Command", Box::new(RerunCommand))
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -618,6 +630,69 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_command_output(cx));
+    }
+
+    fn rerun_command(&mut self, _: &RerunCommand, _: &mut Window, cx: &mut Context<Self>) {
+        self.terminal.update(cx, |term, _| term.rerun_command());
+    }
+
+    /// Marks the commands that failed along the scrollbar.
+    fn failed_command_marks(&self, cx: &App) -> Vec<Div> {
+        let terminal = self.terminal.read(cx);
+        let total_lines = terminal.total_lines();
+        let history_size = total_lines.saturating_sub(terminal.viewport_lines());
+        if history_size == 0 {
+            return Vec::new();
+        }
+        terminal
+            .last_content
+            .command_blocks
+            .iter()
+            .filter(|block| block.failed())
+            .filter_map(|block| block.start())
+            .map(|start| {
+                let line = history_size as i32 + start.line.0;
+                div()
+                    .absolute()
+                    .right_0()
+                    .top(relative(line as f32 / total_lines as f32))
+                    .w(px(4.))
+                    .h(px(2.))
+                    .bg(cx.theme().status().error)
+            })
+            .collect()
+    }
+
     fn scroll_to_bottom(&mut self, _: &ScrollToBottom, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.scroll_to_bottom());
         if self.block_below_cursor.is_some() {
@@ -1075,6 +1150,7 @@ impl Render for TerminalView {
         let terminal_view_handle = cx.entity();
 
         let focused = self.focus_handle.is_focused(window);
+        let failed_command_marks = self.failed_command_marks(cx);
 
         div()
             .id("terminal-view")
@@ -1093,6 +1169,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_command_output))
+            .on_action(cx.listener(TerminalView::rerun_command))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1129,7 +1209,7 @@ impl Render for TerminalView {
                         self.mode.clone(),
                     ))
                     .when(self.content_mode(window, cx).is_scrollable(), |div| {
-                        div.custom_scrollbars(
+                        div.children(failed_command_marks).custom_scrollbars(
                             Scrollbars::for_settings::<TerminalScrollbarSettingsWrapper>()
                                 .show_along(ScrollAxes::Vertical)
                                 .with_track_along(
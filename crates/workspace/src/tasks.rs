@@ -1,11 +1,16 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared, join_all},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskDependencyTree, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::Window;
 
 use crate::{Toast, Workspace, notifications::NotificationId};
@@ -55,6 +60,12 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let dependant = (!resolved_task.original_task().depends_on.is_empty()).then(|| {
+            (
+                resolved_task.original_task().clone(),
+                resolved_task.task_context().clone(),
+            )
+        });
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -65,12 +76,32 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task);
                     })
                 }
             });
         }
 
+        if let Some((template, task_context)) = dependant {
+            self.schedule_task_dependencies(
+                task_source_kind,
+                template,
+                task_context,
+                spawn_in_terminal,
+                window,
+                cx,
+            );
+        } else {
+            self.spawn_scheduled_task(spawn_in_terminal, window, cx);
+        }
+    }
+
+    fn spawn_scheduled_task(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
@@ -98,6 +129,100 @@ impl Workspace {
         }
     }
 
+    /// Runs the tasks the template depends on, and then the task itself, unless it only groups
+    /// its dependencies.
+    fn schedule_task_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        task_context: TaskContext,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            // Without an inventory (e.g. in a remote project) the dependencies can't be looked up,
+            // and the task can't run without them.
+            let id = NotificationId::unique::<TaskDependencyTree>();
+            self.show_toast(
+                Toast::new(
+                    id,
+                    format!(
+                        "Task `{}` was not started: its dependencies can't be resolved",
+                        template.label
+                    ),
+                ),
+                cx,
+            );
+            return;
+        };
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => self
+                .project
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).id()),
+        };
+        let label = template.label.clone();
+        let has_command = !template.command.trim().is_empty();
+        let dependency_tree =
+            task_inventory
+                .read(cx)
+                .task_dependency_tree(task_source_kind, template, worktree, cx);
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let dependencies_run = async {
+                let dependency_tree = dependency_tree.await?;
+                let runner = Rc::new(DependencyRunner {
+                    workspace: workspace.clone(),
+                    task_context,
+                    runs: RefCell::default(),
+                });
+                runner
+                    .run_dependencies(
+                        dependency_tree.template.depends_order,
+                        dependency_tree.dependencies,
+                        cx,
+                    )
+                    .await
+            }
+            .await;
+
+            match dependencies_run {
+                Ok(()) if has_command => {
+                    workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.spawn_scheduled_task(spawn_in_terminal, window, cx)
+                        })
+                        .ok();
+                }
+                Ok(()) => log::debug!("Dependencies of task `{label}` succeeded"),
+                Err(e) => {
+                    log::error!("Dependencies of task `{label}` failed: {e:#}");
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            let id = NotificationId::unique::<TaskDependencyTree>();
+                            workspace.show_toast(
+                                Toast::new(id, format!("Task `{label}` was not started: {e}")),
+                                cx,
+                            );
+                        })
+                        .ok();
+                }
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -131,4 +256,312 @@ impl Workspace {
             Task::ready(None)
         }
     }
+
+    pub fn spawn_background_in_terminal(
+        self: &mut Workspace,
+        spawn_in_terminal: SpawnInTerminal,
+        ready_pattern: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Result<()>>> {
+        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+            terminal_provider.spawn_background(spawn_in_terminal, ready_pattern, window, cx)
+        } else {
+            Task::ready(None)
+        }
+    }
+}
+
+type DependencyRun = Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>;
+
+/// Runs the tasks of a dependency tree, each of them once, even if several tasks depend on it.
+struct DependencyRunner {
+    workspace: WeakEntity<Workspace>,
+    task_context: TaskContext,
+    runs: RefCell<HashMap<String, DependencyRun>>,
+}
+
+impl DependencyRunner {
+    async fn run_dependencies(
+        self: &Rc<Self>,
+        order: DependsOrder,
+        dependencies: Vec<TaskDependencyTree>,
+        cx: &AsyncWindowContext,
+    ) -> Result<()> {
+        let runs = dependencies
+            .into_iter()
+            .map(|dependency| self.run(dependency, cx.clone()))
+            .collect::<Vec<_>>();
+        match order {
+            DependsOrder::Sequence => {
+                for run in runs {
+                    run.await.map_err(|e| anyhow!("{e:#}"))?;
+                }
+            }
+            DependsOrder::Parallel => {
+                for result in join_all(runs).await {
+                    result.map_err(|e| anyhow!("{e:#}"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn run(self: &Rc<Self>, tree: TaskDependencyTree, cx: AsyncWindowContext) -> DependencyRun {
+        let label = tree.template.label.clone();
+        if let Some(run) = self.runs.borrow().get(&label) {
+            return run.clone();
+        }
+        let this = self.clone();
+        let run = async move { this.run_task(tree, cx).await.map_err(Arc::new) }
+            .boxed_local()
+            .shared();
+        self.runs.borrow_mut().insert(label, run.clone());
+        run
+    }
+
+    async fn run_task(
+        self: Rc<Self>,
+        tree: TaskDependencyTree,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let TaskDependencyTree {
+            source_kind,
+            template,
+            dependencies,
+        } = tree;
+        self.run_dependencies(template.depends_order, dependencies, &cx)
+            .await?;
+        if template.command.trim().is_empty() {
+            return Ok(());
+        }
+
+        let label = &template.label;
+        let resolved_task = template
+            .resolve_task(&source_kind.to_id_base(), &self.task_context)
+            .with_context(|| format!("Failed to resolve task `{label}`"))?;
+        if template.is_background {
+            // Watch matchers like `$tsc-watch` know when their task is done building.
+            let ready_pattern = template.ready_pattern.clone().or_else(|| {
+                template
+                    .problem_matchers
+                    .iter()
+                    .find_map(|matcher| matcher.ready_pattern())
+                    .map(ToOwned::to_owned)
+            });
+            self.workspace
+                .update_in(&mut cx, |workspace, window, cx| {
+                    workspace.spawn_background_in_terminal(
+                        resolved_task.resolved,
+                        ready_pattern,
+                        window,
+                        cx,
+                    )
+                })?
+                .await
+                .with_context(|| format!("Task `{label}` got cancelled"))??;
+        } else {
+            let status = self
+                .workspace
+                .update_in(&mut cx, |workspace, window, cx| {
+                    workspace.spawn_in_terminal(resolved_task.resolved, window, cx)
+                })?
+                .await
+                .with_context(|| format!("Task `{label}` got cancelled"))??;
+            anyhow::ensure!(status.success(), "Task `{label}` failed with {status}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use futures::channel::oneshot;
+    use gpui::{App, TestAppContext};
+    use project::{FakeFs, Project};
+
+    use super::*;
+    use crate::{TerminalProvider, tests::init_test};
+
+    /// Records the labels of the tasks spawned in it, which run until the test finishes them, or
+    /// are ready once it does for background tasks.
+    #[derive(Clone, Default)]
+    struct FakeTerminalProvider {
+        spawned: Rc<RefCell<Vec<String>>>,
+        running: Rc<RefCell<HashMap<String, oneshot::Sender<()>>>>,
+    }
+
+    impl FakeTerminalProvider {
+        fn start(&self, task: &SpawnInTerminal) -> oneshot::Receiver<()> {
+            let (finish_tx, finish_rx) = oneshot::channel();
+            self.spawned.borrow_mut().push(task.label.clone());
+            self.running
+                .borrow_mut()
+                .insert(task.label.clone(), finish_tx);
+            finish_rx
+        }
+
+        fn finish(&self, label: &str) {
+            self.running
+                .borrow_mut()
+                .remove(label)
+                .unwrap()
+                .send(())
+                .unwrap();
+        }
+
+        fn take_spawned(&self) -> Vec<String> {
+            mem::take(&mut *self.spawned.borrow_mut())
+        }
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let finished = self.start(&task);
+            cx.foreground_executor().spawn(async move {
+                finished.await.ok()?;
+                Some(Ok(ExitStatus::default()))
+            })
+        }
+
+        fn spawn_background(
+            &self,
+            task: SpawnInTerminal,
+            _: Option<String>,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<()>>> {
+            let ready = self.start(&task);
+            cx.foreground_executor().spawn(async move {
+                ready.await.ok()?;
+                Some(Ok(()))
+            })
+        }
+    }
+
+    fn dependency(
+        template: TaskTemplate,
+        dependencies: Vec<TaskDependencyTree>,
+    ) -> TaskDependencyTree {
+        TaskDependencyTree {
+            source_kind: TaskSourceKind::UserInput,
+            template,
+            dependencies,
+        }
+    }
+
+    fn task(label: &str) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: "true".to_string(),
+            ..TaskTemplate::default()
+        }
+    }
+
+    async fn run_dependencies(
+        order: DependsOrder,
+        dependencies: Vec<TaskDependencyTree>,
+        cx: &mut TestAppContext,
+    ) -> (
+        FakeTerminalProvider,
+        Task<Result<()>>,
+        &mut gpui::VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let provider = FakeTerminalProvider::default();
+        let run = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.set_terminal_provider(provider.clone());
+            cx.spawn_in(window, async move |workspace, cx| {
+                let runner = Rc::new(DependencyRunner {
+                    workspace,
+                    task_context: TaskContext::default(),
+                    runs: RefCell::default(),
+                });
+                runner.run_dependencies(order, dependencies, cx).await
+            })
+        });
+        cx.run_until_parked();
+        (provider, run, cx)
+    }
+
+    #[gpui::test]
+    async fn test_dependencies_in_sequence(cx: &mut TestAppContext) {
+        let shared = dependency(task("codegen"), Vec::new());
+        let (provider, run, cx) = run_dependencies(
+            DependsOrder::Sequence,
+            vec![
+                dependency(task("build"), vec![shared.clone()]),
+                dependency(task("lint"), vec![shared]),
+            ],
+            cx,
+        )
+        .await;
+
+        assert_eq!(provider.take_spawned(), ["codegen"]);
+        provider.finish("codegen");
+        cx.run_until_parked();
+        assert_eq!(provider.take_spawned(), ["build"]);
+        provider.finish("build");
+        cx.run_until_parked();
+        // The task that both depend on only runs once.
+        assert_eq!(provider.take_spawned(), ["lint"]);
+        provider.finish("lint");
+        run.await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_dependencies_in_parallel(cx: &mut TestAppContext) {
+        let (provider, run, cx) = run_dependencies(
+            DependsOrder::Parallel,
+            vec![
+                dependency(task("frontend"), Vec::new()),
+                dependency(task("backend"), Vec::new()),
+            ],
+            cx,
+        )
+        .await;
+
+        assert_eq!(provider.take_spawned(), ["frontend", "backend"]);
+        provider.finish("backend");
+        cx.run_until_parked();
+        provider.finish("frontend");
+        run.await.unwrap();
+        assert_eq!(provider.take_spawned(), Vec::<String>::new());
+    }
+
+    #[gpui::test]
+    async fn test_background_dependency(cx: &mut TestAppContext) {
+        let server = TaskTemplate {
+            is_background: true,
+            ..task("server")
+        };
+        let (provider, run, cx) = run_dependencies(
+            DependsOrder::Sequence,
+            vec![
+                dependency(server, Vec::new()),
+                dependency(task("e2e"), Vec::new()),
+            ],
+            cx,
+        )
+        .await;
+
+        assert_eq!(provider.take_spawned(), ["server"]);
+        // The server keeps running once it is ready, while the tasks after it start.
+        provider.finish("server");
+        cx.run_until_parked();
+        assert_eq!(provider.take_spawned(), ["e2e"]);
+        provider.finish("e2e");
+        run.await.unwrap();
+    }
 }
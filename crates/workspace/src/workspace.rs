@@ -171,6 +171,16 @@ pub trait TerminalProvider {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Option<Result<ExitStatus>>>;
+
+    /// Spawns a task that keeps running in the background, resolving once its output matches
+    /// `ready_pattern` (or right after it starts, without one) rather than once it exits.
+    fn spawn_background(
+        &self,
+        task: SpawnInTerminal,
+        ready_pattern: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<()>>>;
 }
 
 pub trait DebuggerProvider {
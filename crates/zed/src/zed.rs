@@ -4821,6 +4821,7 @@ mod tests {
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hierarchy_view",
                 "icon_theme_selector",
                 "inline_assistant",
                 "journal",
@@ -226,6 +226,8 @@ pub mod git {
             Branch,
             /// Opens the git stash selector.
             ViewStash,
+            /// Opens the git tag selector.
+            ViewTags,
             /// Opens the git worktree selector.
             Worktree,
             /// Creates a pull request for the current branch.